@@ -8,17 +8,25 @@ use web3::{
     types::{TransactionReceipt, U256, U64},
 };
 use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig};
+use zksync_crypto::ff;
 use zksync_eth_client::EthereumGateway;
+use zksync_state::state::ZkSyncState;
 use zksync_storage::StorageProcessor;
 use zksync_types::{aggregated_operations::stored_block_info, block::Block, BlockNumber, H256};
 
+use crate::report::RevertReport;
+
+mod report;
+
+const REVERT_BLOCKS_FUNCTION: &str = "revertBlocks";
+
+/// Reverts blocks in storage within the provided database transaction,
+/// the caller is responsible for committing it.
 // TODO: don't use anyhow (ZKS-588)
 async fn revert_blocks_in_storage(
-    storage: &mut StorageProcessor<'_>,
+    transaction: &mut StorageProcessor<'_>,
     last_block: BlockNumber,
 ) -> anyhow::Result<()> {
-    let mut transaction = storage.start_transaction().await?;
-
     transaction
         .chain()
         .mempool_schema()
@@ -110,9 +118,45 @@ async fn revert_blocks_in_storage(
         .await?;
     println!("`eth_parameters` table is updated");
 
-    transaction.commit().await?;
+    Ok(())
+}
 
-    println!("Blocks were reverted in storage");
+/// Checks that the state restored from the stored account updates matches the root hash
+/// of the last correct block. If `up_to_block` is not set, the latest committed state is checked,
+/// i.e. the one the server will start with.
+async fn check_state_root(
+    storage: &mut StorageProcessor<'_>,
+    last_correct_block: &Block,
+    up_to_block: Option<BlockNumber>,
+    stage: &str,
+) -> anyhow::Result<()> {
+    let (state_block, accounts) = storage
+        .chain()
+        .state_schema()
+        .load_committed_state(up_to_block)
+        .await?;
+    ensure!(
+        state_block <= last_correct_block.block_number,
+        "{} check: state is restored up to block {}, which is after the last correct block {}",
+        stage,
+        state_block,
+        last_correct_block.block_number
+    );
+
+    let root_hash = ZkSyncState::from_acc_map(accounts).root_hash();
+    ensure!(
+        root_hash == last_correct_block.new_root_hash,
+        "{} check: root hash of the restored state 0x{} doesn't match the root hash 0x{} of block {}",
+        stage,
+        ff::to_hex(&root_hash),
+        ff::to_hex(&last_correct_block.new_root_hash),
+        last_correct_block.block_number
+    );
+
+    println!(
+        "{} check: state root hash matches block {}",
+        stage, last_correct_block.block_number
+    );
     Ok(())
 }
 
@@ -146,17 +190,23 @@ async fn send_raw_tx_and_wait_confirmation(
     }
 }
 
+/// Returns the encoded `revertBlocks` call and the gas limit to send it with.
+fn revert_blocks_call(client: &EthereumGateway, blocks: &[Block]) -> (Vec<u8>, U256) {
+    let tx_arg = Token::Array(blocks.iter().map(stored_block_info).collect());
+    let data = client.encode_tx_data(REVERT_BLOCKS_FUNCTION, tx_arg);
+    let gas_limit = U256::from(200000 + 15000 * blocks.len());
+    (data, gas_limit)
+}
+
 // TODO: don't use anyhow (ZKS-588)
 async fn revert_blocks_on_contract(
     storage: &mut StorageProcessor<'_>,
     client: &EthereumGateway,
     blocks: &[Block],
 ) -> anyhow::Result<()> {
-    let tx_arg = Token::Array(blocks.iter().map(stored_block_info).collect());
-    let data = client.encode_tx_data("revertBlocks", tx_arg);
-    let gas_limit = 200000 + 15000 * blocks.len();
+    let (data, gas_limit) = revert_blocks_call(client, blocks);
     let signed_tx = client
-        .sign_prepared_tx(data, Options::with(|f| f.gas = Some(U256::from(gas_limit))))
+        .sign_prepared_tx(data, Options::with(|f| f.gas = Some(gas_limit)))
         .await
         .map_err(|e| format_err!("Revert blocks send err: {}", e))?;
     let receipt = send_raw_tx_and_wait_confirmation(client, signed_tx.raw_tx).await?;
@@ -212,8 +262,13 @@ struct Opt {
     #[structopt(subcommand)]
     command: Command,
     /// Private key of operator which will call the contract function.
+    /// If not set, the operator key source from the `eth_sender` config is used.
     #[structopt(long = "key", env = "ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY")]
-    operator_private_key: String,
+    operator_private_key: Option<String>,
+    /// Only print the report of the changes without applying them. Storage changes are
+    /// applied and checked within a database transaction which is rolled back afterwards.
+    #[structopt(long)]
+    dry_run: bool,
 }
 
 // TODO: don't use anyhow (ZKS-588)
@@ -221,24 +276,28 @@ struct Opt {
 async fn main() -> anyhow::Result<()> {
     let opt = Opt::from_args();
 
-    let key_without_prefix = opt
-        .operator_private_key
-        .strip_prefix("0x")
-        .unwrap_or_else(|| opt.operator_private_key.as_str());
-
     let contracts = ContractsConfig::from_env();
     let eth_client_config = ETHClientConfig::from_env();
     let mut eth_sender_config = ETHSenderConfig::from_env();
 
-    eth_sender_config.sender.operator_private_key =
-        H256::from_str(key_without_prefix).expect("Cannot deserialize private key");
+    if let Some(operator_private_key) = &opt.operator_private_key {
+        let key_without_prefix = operator_private_key
+            .strip_prefix("0x")
+            .unwrap_or_else(|| operator_private_key.as_str());
+        eth_sender_config.sender.operator_private_key =
+            Some(H256::from_str(key_without_prefix).expect("Cannot deserialize private key"));
+        eth_sender_config.sender.operator_keystore_path = None;
+        eth_sender_config.sender.operator_mnemonic_path = None;
+        eth_sender_config.sender.operator_remote_signer_url = None;
+    }
 
     let mut storage = StorageProcessor::establish_connection().await?;
     let client = EthereumGateway::from_config(
         &eth_client_config,
         &eth_sender_config,
         contracts.contract_addr,
-    );
+    )
+    .await;
 
     let last_commited_block = storage
         .chain()
@@ -262,25 +321,82 @@ async fn main() -> anyhow::Result<()> {
 
     let blocks_to_revert = *last_commited_block - opt.last_correct_block;
     let last_block = BlockNumber(opt.last_correct_block);
+    let (revert_on_contract, revert_in_storage) = match opt.command {
+        Command::All => (true, true),
+        Command::Contract => (true, false),
+        Command::Storage => (false, true),
+    };
 
-    match opt.command {
-        Command::All => {
-            println!("Start reverting blocks in database and in contract");
-            let blocks = get_blocks(last_commited_block, blocks_to_revert, &mut storage).await?;
-            println!("Last block for revert {}", &last_block);
-            revert_blocks_on_contract(&mut storage, &client, &blocks).await?;
-            revert_blocks_in_storage(&mut storage, last_block).await?;
-        }
-        Command::Contract => {
+    let mut report = RevertReport::collect(&mut storage, last_block, last_commited_block).await?;
+    let blocks = if revert_on_contract {
+        let blocks = get_blocks(last_commited_block, blocks_to_revert, &mut storage).await?;
+        let (data, gas_limit) = revert_blocks_call(&client, &blocks);
+        report
+            .add_l1_call(
+                &client,
+                REVERT_BLOCKS_FUNCTION,
+                blocks.iter().map(|block| block.block_number).collect(),
+                data,
+                gas_limit,
+            )
+            .await;
+        blocks
+    } else {
+        Vec::new()
+    };
+    println!("{}", report);
+
+    if !revert_in_storage {
+        if opt.dry_run {
+            println!("Dry run is finished, no changes were made");
+        } else {
             println!("Start reverting blocks in contract");
-            let blocks = get_blocks(last_commited_block, blocks_to_revert, &mut storage).await?;
             revert_blocks_on_contract(&mut storage, &client, &blocks).await?;
         }
-        Command::Storage => {
-            println!("Start reverting blocks in database");
-            revert_blocks_in_storage(&mut storage, last_block).await?;
-        }
+        return Ok(());
+    }
+
+    let last_correct_block = storage
+        .chain()
+        .block_schema()
+        .get_block(last_block)
+        .await?
+        .ok_or_else(|| format_err!("No block {} in storage", last_block))?;
+    check_state_root(
+        &mut storage,
+        &last_correct_block,
+        Some(last_block),
+        "Pre-revert",
+    )
+    .await?;
+
+    // The storage revert is checked within a transaction which is rolled back, so the
+    // contract is reverted only if the storage can be reverted as well.
+    println!("Checking the revert of blocks in database");
+    let mut transaction = storage.start_transaction().await?;
+    revert_blocks_in_storage(&mut transaction, last_block).await?;
+    check_state_root(&mut transaction, &last_correct_block, None, "Post-revert").await?;
+    // Dropping the transaction without committing rolls all the storage changes back.
+    drop(transaction);
+
+    if opt.dry_run {
+        println!("Dry run is finished, storage changes are rolled back");
+        return Ok(());
     }
 
+    if revert_on_contract {
+        // The contract call is confirmed before the storage transaction is started,
+        // so the transaction isn't held open while waiting for Ethereum.
+        println!("Start reverting blocks in contract");
+        revert_blocks_on_contract(&mut storage, &client, &blocks).await?;
+    }
+
+    println!("Start reverting blocks in database");
+    let mut transaction = storage.start_transaction().await?;
+    revert_blocks_in_storage(&mut transaction, last_block).await?;
+    check_state_root(&mut transaction, &last_correct_block, None, "Post-revert").await?;
+    transaction.commit().await?;
+    println!("Blocks were reverted in storage");
+
     Ok(())
 }
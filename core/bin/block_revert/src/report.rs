@@ -0,0 +1,247 @@
+//! Report of the changes made by the block revert.
+
+use std::{collections::BTreeSet, fmt};
+
+use web3::types::U256;
+use zksync_crypto::ff;
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::StorageProcessor;
+use zksync_types::{block::ExecutedOperations, AccountId, BlockNumber, SerialId, TxHash};
+
+/// Block removed from the storage by the revert.
+#[derive(Debug)]
+pub struct RevertedBlock {
+    pub number: BlockNumber,
+    /// Root hash of the block, hex-encoded. Not set for the incomplete and pending blocks.
+    pub root_hash: Option<String>,
+    /// Whether the block is committed on the contract and has to be reverted there as well.
+    pub committed_on_contract: bool,
+    pub successful_txs: usize,
+    pub failed_txs: usize,
+    pub priority_ops: usize,
+}
+
+/// Contract call made by the revert.
+#[derive(Debug)]
+pub struct L1Call {
+    pub function: &'static str,
+    pub blocks: Vec<BlockNumber>,
+    /// Gas limit the transaction is sent with.
+    pub gas_limit: U256,
+    /// Result of the `eth_estimateGas` call, the error is reported as is.
+    pub gas_estimate: Result<U256, String>,
+    pub gas_price: Result<U256, String>,
+}
+
+/// Everything that is changed by the revert of the blocks after the last correct one.
+#[derive(Debug)]
+pub struct RevertReport {
+    pub last_correct_block: BlockNumber,
+    pub blocks: Vec<RevertedBlock>,
+    /// Successful transactions that are returned to the mempool.
+    pub returned_txs: Vec<TxHash>,
+    /// Failed transactions, they are removed without being returned to the mempool.
+    pub removed_failed_txs: Vec<TxHash>,
+    /// Priority operations that are returned to the mempool and will be executed again.
+    pub priority_ops: Vec<SerialId>,
+    pub affected_accounts: BTreeSet<AccountId>,
+    pub l1_calls: Vec<L1Call>,
+}
+
+/// Returns the number of the last block stored as a complete, incomplete or pending one.
+async fn last_stored_block(storage: &mut StorageProcessor<'_>) -> anyhow::Result<BlockNumber> {
+    let mut block_schema = storage.chain().block_schema();
+    let last_saved_block = block_schema.get_last_saved_block().await?;
+    let last_incomplete_block = block_schema
+        .get_last_incomplete_block_number()
+        .await?
+        .unwrap_or(last_saved_block);
+    let pending_block = block_schema
+        .load_pending_block()
+        .await?
+        .map(|block| block.number)
+        .unwrap_or(last_saved_block);
+
+    Ok(last_saved_block
+        .max(last_incomplete_block)
+        .max(pending_block))
+}
+
+impl RevertReport {
+    /// Collects the blocks, operations and accounts affected by the revert of the blocks
+    /// after `last_block`. Operations are loaded block by block, including the incomplete
+    /// and pending ones, up to the last block stored in any form. Blocks without operations
+    /// are reported as well.
+    pub async fn collect(
+        storage: &mut StorageProcessor<'_>,
+        last_block: BlockNumber,
+        last_committed_block: BlockNumber,
+    ) -> anyhow::Result<Self> {
+        let mut report = Self {
+            last_correct_block: last_block,
+            blocks: Vec::new(),
+            returned_txs: Vec::new(),
+            removed_failed_txs: Vec::new(),
+            priority_ops: Vec::new(),
+            affected_accounts: BTreeSet::new(),
+            l1_calls: Vec::new(),
+        };
+
+        let last_stored_block = last_stored_block(storage).await?;
+        let mut block_number = last_block + 1;
+        while block_number <= last_stored_block {
+            let executed_ops = storage
+                .chain()
+                .block_schema()
+                .get_block_executed_ops(block_number)
+                .await?;
+
+            let root_hash = storage
+                .chain()
+                .block_schema()
+                .get_block(block_number)
+                .await?
+                .map(|block| format!("0x{}", ff::to_hex(&block.new_root_hash)));
+            let mut reverted_block = RevertedBlock {
+                number: block_number,
+                root_hash,
+                committed_on_contract: block_number <= last_committed_block,
+                successful_txs: 0,
+                failed_txs: 0,
+                priority_ops: 0,
+            };
+
+            for executed_op in executed_ops {
+                report
+                    .affected_accounts
+                    .extend(executed_op.get_updated_account_ids());
+                match executed_op {
+                    ExecutedOperations::Tx(tx) if tx.success => {
+                        reverted_block.successful_txs += 1;
+                        report.returned_txs.push(tx.signed_tx.hash());
+                    }
+                    ExecutedOperations::Tx(tx) => {
+                        reverted_block.failed_txs += 1;
+                        report.removed_failed_txs.push(tx.signed_tx.hash());
+                    }
+                    ExecutedOperations::PriorityOp(op) => {
+                        reverted_block.priority_ops += 1;
+                        report.priority_ops.push(op.priority_op.serial_id);
+                    }
+                }
+            }
+
+            report.blocks.push(reverted_block);
+            block_number = block_number + 1;
+        }
+
+        Ok(report)
+    }
+
+    /// Adds the contract call to the report, estimating its gas usage.
+    pub async fn add_l1_call(
+        &mut self,
+        client: &EthereumGateway,
+        function: &'static str,
+        blocks: Vec<BlockNumber>,
+        data: Vec<u8>,
+        gas_limit: U256,
+    ) {
+        let gas_estimate = client
+            .estimate_gas(data)
+            .await
+            .map_err(|err| err.to_string());
+        let gas_price = client.get_gas_price().await.map_err(|err| err.to_string());
+        self.l1_calls.push(L1Call {
+            function,
+            blocks,
+            gas_limit,
+            gas_estimate,
+            gas_price,
+        });
+    }
+}
+
+fn format_list<'a, T: ToString + 'a>(items: impl IntoIterator<Item = &'a T>) -> String {
+    let items: Vec<_> = items.into_iter().map(|item| item.to_string()).collect();
+    if items.is_empty() {
+        "-".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+fn format_gas(value: &Result<U256, String>) -> String {
+    match value {
+        Ok(value) => value.to_string(),
+        Err(err) => format!("unavailable ({})", err),
+    }
+}
+
+impl fmt::Display for RevertReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Revert report (last correct block {})",
+            self.last_correct_block
+        )?;
+
+        writeln!(f, "Blocks to revert: {}", self.blocks.len())?;
+        for block in &self.blocks {
+            writeln!(
+                f,
+                "  #{}: root hash {}, {}, {} successful txs, {} failed txs, {} priority ops",
+                block.number,
+                block.root_hash.as_deref().unwrap_or("- (not sealed)"),
+                if block.committed_on_contract {
+                    "committed on contract"
+                } else {
+                    "not committed on contract"
+                },
+                block.successful_txs,
+                block.failed_txs,
+                block.priority_ops
+            )?;
+        }
+
+        writeln!(
+            f,
+            "Transactions returned to the mempool ({}): {}",
+            self.returned_txs.len(),
+            format_list(&self.returned_txs)
+        )?;
+        writeln!(
+            f,
+            "Failed transactions removed ({}): {}",
+            self.removed_failed_txs.len(),
+            format_list(&self.removed_failed_txs)
+        )?;
+        writeln!(
+            f,
+            "Priority operations returned to the mempool ({}): {}",
+            self.priority_ops.len(),
+            format_list(&self.priority_ops)
+        )?;
+        writeln!(
+            f,
+            "Affected accounts ({}): {}",
+            self.affected_accounts.len(),
+            format_list(&self.affected_accounts)
+        )?;
+
+        writeln!(f, "L1 calls: {}", self.l1_calls.len())?;
+        for call in &self.l1_calls {
+            writeln!(
+                f,
+                "  {}({}): gas limit {}, gas estimate {}, gas price {}",
+                call.function,
+                format_list(&call.blocks),
+                call.gas_limit,
+                format_gas(&call.gas_estimate),
+                format_gas(&call.gas_price)
+            )?;
+        }
+
+        Ok(())
+    }
+}
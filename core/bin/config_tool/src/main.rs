@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use structopt::StructOpt;
+use zksync_config::ConfigLoader;
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Loads the config from the provided sources, prints the effective values (with secrets redacted)
+    /// and checks that the config is valid.
+    Check {
+        /// Do not override the values from files with the environment variables.
+        #[structopt(long)]
+        no_env: bool,
+        /// Config files (`*.toml` or `*.json`) or directories with them. Applied in the provided order,
+        /// so the latter sources override the former ones.
+        #[structopt(parse(from_os_str))]
+        paths: Vec<PathBuf>,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zkSync config tool", author = "Matter Labs")]
+#[structopt(about = "Tool for checking the zkSync configuration")]
+struct Opt {
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+
+    match opt.command {
+        Command::Check { no_env, paths } => {
+            let mut loader = paths
+                .into_iter()
+                .fold(ConfigLoader::new(), |loader, path| loader.with_path(path));
+            if no_env {
+                loader = loader.without_env_overrides();
+            }
+
+            let vars = loader.vars()?;
+            for (name, value) in vars.redacted() {
+                println!("{}={}", name, value);
+            }
+
+            zksync_config::ZkSyncConfig::from_vars(&vars)?;
+            println!("Config is valid");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,580 @@
+//! Archive of the Ethereum data required to restore the zkSync state.
+//!
+//! Normally the data restore driver fetches the contract events and the block commit
+//! transactions from the Ethereum node. The archive contains all of them in a single
+//! compressed file, so the state can be restored without a node: [`ArchiveTransport`]
+//! serves the requests of the driver from the archive.
+//!
+//! The archive is a gzip-compressed stream of JSON values: the [`ArchiveHeader`] followed
+//! by the logs and transactions, so it's written while the data is being fetched.
+
+// Built-in deps
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+// External deps
+use anyhow::{ensure, format_err};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use jsonrpc_core::{Call, Params};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use web3::{
+    helpers::build_request,
+    types::{Address, BlockNumber, Bytes, FilterBuilder, Log, Transaction, H256, U64},
+    RequestId, Transport, Web3,
+};
+// Workspace deps
+use zksync_contracts::{
+    governance_contract, upgrade_gatekeeper, zksync_contract, zksync_contract_v3,
+};
+// Local deps
+use crate::{
+    contract::{ZkSyncContractVersion, ZkSyncDeployedContract},
+    eth_tx_helpers::{get_block_number_from_ethereum_transaction, get_ethereum_transaction},
+    events_state::EventsState,
+};
+
+/// Version of the archive layout. Archives of the other versions are rejected.
+pub const ARCHIVE_FORMAT_VERSION: u32 = 2;
+
+/// Contracts to export the data of.
+#[derive(Debug, Clone)]
+pub struct ArchivedContracts {
+    /// Address of the zkSync contract.
+    pub zksync_contract: Address,
+    /// Address of the Governance contract.
+    pub governance_contract: Address,
+    /// Address of the UpgradeGatekeeper contract.
+    pub upgrade_gatekeeper_contract: Address,
+    /// Hash of the zkSync contract creation transaction.
+    pub genesis_tx_hash: H256,
+    /// The initial version of the deployed zkSync contract.
+    pub init_contract_version: ZkSyncContractVersion,
+    /// Ethereum blocks that include correct UpgradeComplete events.
+    pub contract_upgrade_eth_blocks: Vec<u64>,
+}
+
+/// Description of the archived data, which precedes the logs and transactions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveHeader {
+    pub format_version: u32,
+    /// Address of the zkSync contract.
+    pub zksync_contract: Address,
+    /// Address of the Governance contract.
+    pub governance_contract: Address,
+    /// Address of the UpgradeGatekeeper contract.
+    pub upgrade_gatekeeper_contract: Address,
+    /// Hash of the zkSync contract creation transaction.
+    pub genesis_tx_hash: H256,
+    /// Number of the latest Ethereum block covered by the archive.
+    pub last_eth_block: u64,
+    /// Total number of the verified zkSync blocks at the moment of export.
+    pub total_verified_blocks: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum ArchiveEntry {
+    Log(Log),
+    Transaction(Transaction),
+}
+
+/// Writes the gzip-compressed archive entry by entry.
+struct ArchiveWriter<W: Write> {
+    encoder: GzEncoder<W>,
+    logs_count: usize,
+    transactions_count: usize,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    fn new(writer: W, header: &ArchiveHeader) -> anyhow::Result<Self> {
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        serde_json::to_writer(&mut encoder, header)?;
+        Ok(Self {
+            encoder,
+            logs_count: 0,
+            transactions_count: 0,
+        })
+    }
+
+    fn write_entry(&mut self, entry: &ArchiveEntry) -> anyhow::Result<()> {
+        self.encoder.write_all(b"\n")?;
+        serde_json::to_writer(&mut self.encoder, entry)?;
+        match entry {
+            ArchiveEntry::Log(_) => self.logs_count += 1,
+            ArchiveEntry::Transaction(_) => self.transactions_count += 1,
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> anyhow::Result<()> {
+        self.encoder.finish()?.flush()?;
+        Ok(())
+    }
+}
+
+/// Contract events and transactions required to restore the zkSync state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct L1Archive {
+    pub header: ArchiveHeader,
+    /// Block events and priority operations of the zkSync contract, the added tokens
+    /// events of the Governance contract and the UpgradeGatekeeper upgrade events.
+    pub logs: Vec<Log>,
+    /// Genesis transaction and the transactions that committed zkSync blocks.
+    pub transactions: Vec<Transaction>,
+}
+
+impl L1Archive {
+    /// Fetches all the data required to restore the state from the Ethereum node
+    /// and writes it to the gzip-compressed archive as soon as it's fetched.
+    /// Events of every Ethereum blocks range are fetched with the ABI of the zkSync
+    /// contract version that was deployed at these blocks.
+    ///
+    /// # Arguments
+    ///
+    /// * `web3` - Web3 provider
+    /// * `contracts` - Contracts to export the data of
+    /// * `eth_blocks_step` - The step distance of fetching logs in the ethereum blocks
+    /// * `writer` - Destination of the archive
+    ///
+    pub async fn export<T: Transport, W: Write>(
+        web3: &Web3<T>,
+        contracts: &ArchivedContracts,
+        eth_blocks_step: u64,
+        writer: W,
+    ) -> anyhow::Result<ArchiveHeader> {
+        let genesis_transaction =
+            get_ethereum_transaction(web3, &contracts.genesis_tx_hash).await?;
+        let genesis_eth_block = get_block_number_from_ethereum_transaction(&genesis_transaction)?;
+
+        // Verified blocks are counted before the last block is fetched, so all of them
+        // are guaranteed to have their events within the archived range.
+        let current_contract = ZkSyncDeployedContract::new(
+            web3.eth(),
+            contracts.zksync_contract,
+            contracts
+                .init_contract_version
+                .upgrade(contracts.contract_upgrade_eth_blocks.len() as u32),
+        );
+        let total_verified_blocks = current_contract.get_total_verified_blocks().await;
+        let last_eth_block = EventsState::get_last_block_number(web3).await?;
+
+        let header = ArchiveHeader {
+            format_version: ARCHIVE_FORMAT_VERSION,
+            zksync_contract: contracts.zksync_contract,
+            governance_contract: contracts.governance_contract,
+            upgrade_gatekeeper_contract: contracts.upgrade_gatekeeper_contract,
+            genesis_tx_hash: contracts.genesis_tx_hash,
+            last_eth_block,
+            total_verified_blocks,
+        };
+        let mut writer = ArchiveWriter::new(writer, &header)?;
+        writer.write_entry(&ArchiveEntry::Transaction(genesis_transaction))?;
+
+        let governance_topics = vec![governance_contract()
+            .event("NewToken")
+            .expect("Governance contract abi error")
+            .signature()];
+        let gatekeeper_topics = vec![upgrade_gatekeeper()
+            .event("UpgradeComplete")
+            .expect("Upgrade Gatekeeper contract abi error")
+            .signature()];
+
+        // Several blocks may be committed by a single transaction.
+        let mut commit_tx_hashes = HashSet::new();
+        let mut from_block = genesis_eth_block;
+        while from_block <= last_eth_block {
+            let contract_version = contracts
+                .init_contract_version
+                .at_eth_block(&contracts.contract_upgrade_eth_blocks, from_block);
+            // Ranges don't cross the contract upgrades, so all of their events
+            // are fetched with the same contract ABI.
+            let next_upgrade_block = contracts
+                .contract_upgrade_eth_blocks
+                .iter()
+                .copied()
+                .filter(|&block| block > from_block)
+                .min();
+            let mut to_block = std::cmp::min(from_block + eth_blocks_step, last_eth_block);
+            if let Some(next_upgrade_block) = next_upgrade_block {
+                to_block = std::cmp::min(to_block, next_upgrade_block - 1);
+            }
+            vlog::info!(
+                "Exporting logs of the Ethereum blocks {}..={} (contract version {:?})",
+                from_block,
+                to_block,
+                contract_version
+            );
+
+            let zksync_contract = ZkSyncDeployedContract::new(
+                web3.eth(),
+                contracts.zksync_contract,
+                contract_version,
+            );
+            let zksync_topic = |name: &str| {
+                zksync_contract
+                    .abi
+                    .event(name)
+                    .expect("Main contract abi error")
+                    .signature()
+            };
+            let block_commit_topic = zksync_topic("BlockCommit");
+            let zksync_topics = vec![
+                block_commit_topic,
+                zksync_topic("BlockVerification"),
+                zksync_topic("BlocksRevert"),
+                zksync_topic("NewPriorityRequest"),
+            ];
+
+            let mut logs = Vec::new();
+            for (address, topics) in [
+                (contracts.zksync_contract, zksync_topics),
+                (contracts.governance_contract, governance_topics.clone()),
+                (
+                    contracts.upgrade_gatekeeper_contract,
+                    gatekeeper_topics.clone(),
+                ),
+            ] {
+                logs.extend(get_logs(web3, address, topics, from_block, to_block).await?);
+            }
+            logs.sort_by_key(|log| (log.block_number, log.log_index));
+
+            for log in logs {
+                let commit_tx_hash = match log.topics.first() {
+                    Some(topic) if *topic == block_commit_topic => {
+                        Some(log.transaction_hash.ok_or_else(|| {
+                            format_err!("No transaction hash in the BlockCommit log")
+                        })?)
+                    }
+                    _ => None,
+                };
+                writer.write_entry(&ArchiveEntry::Log(log))?;
+
+                if let Some(tx_hash) = commit_tx_hash {
+                    if commit_tx_hashes.insert(tx_hash) {
+                        let transaction = get_ethereum_transaction(web3, &tx_hash).await?;
+                        writer.write_entry(&ArchiveEntry::Transaction(transaction))?;
+                    }
+                }
+            }
+
+            from_block = to_block + 1;
+        }
+        vlog::info!(
+            "Exported {} logs and {} transactions",
+            writer.logs_count,
+            writer.transactions_count
+        );
+        writer.finish()?;
+
+        Ok(header)
+    }
+
+    /// Writes the gzip-compressed archive.
+    pub fn write<W: Write>(&self, writer: W) -> anyhow::Result<()> {
+        let mut writer = ArchiveWriter::new(writer, &self.header)?;
+        for transaction in &self.transactions {
+            writer.write_entry(&ArchiveEntry::Transaction(transaction.clone()))?;
+        }
+        for log in &self.logs {
+            writer.write_entry(&ArchiveEntry::Log(log.clone()))?;
+        }
+        writer.finish()
+    }
+
+    /// Reads the gzip-compressed archive.
+    pub fn read<R: Read>(reader: R) -> anyhow::Result<Self> {
+        let mut deserializer = serde_json::Deserializer::from_reader(GzDecoder::new(reader));
+        let header = ArchiveHeader::deserialize(&mut deserializer)?;
+        ensure!(
+            header.format_version == ARCHIVE_FORMAT_VERSION,
+            "Unsupported archive format version: {}, expected {}",
+            header.format_version,
+            ARCHIVE_FORMAT_VERSION
+        );
+
+        let mut logs = Vec::new();
+        let mut transactions = Vec::new();
+        for entry in deserializer.into_iter::<ArchiveEntry>() {
+            match entry? {
+                ArchiveEntry::Log(log) => logs.push(log),
+                ArchiveEntry::Transaction(transaction) => transactions.push(transaction),
+            }
+        }
+
+        Ok(Self {
+            header,
+            logs,
+            transactions,
+        })
+    }
+
+    pub async fn export_to_file<T: Transport>(
+        web3: &Web3<T>,
+        contracts: &ArchivedContracts,
+        eth_blocks_step: u64,
+        path: impl AsRef<Path>,
+    ) -> anyhow::Result<ArchiveHeader> {
+        let writer = BufWriter::new(File::create(path)?);
+        Self::export(web3, contracts, eth_blocks_step, writer).await
+    }
+
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        self.write(BufWriter::new(File::create(path)?))
+    }
+
+    pub fn read_from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Ethereum blocks of the archived UpgradeComplete events.
+    pub fn upgrade_eth_blocks(&self) -> Vec<u64> {
+        let upgrade_topic = upgrade_gatekeeper()
+            .event("UpgradeComplete")
+            .expect("Upgrade Gatekeeper contract abi error")
+            .signature();
+        self.logs
+            .iter()
+            .filter(|log| {
+                log.address == self.header.upgrade_gatekeeper_contract
+                    && log.topics.first() == Some(&upgrade_topic)
+            })
+            .filter_map(|log| log.block_number.map(|number| number.as_u64()))
+            .collect()
+    }
+}
+
+/// Returns the logs of the contract in the specified blocks, splitting
+/// the range if the Ethereum node refuses to return that many logs at once.
+async fn get_logs<T: Transport>(
+    web3: &Web3<T>,
+    address: Address,
+    topics: Vec<H256>,
+    from_block: u64,
+    to_block: u64,
+) -> anyhow::Result<Vec<Log>> {
+    const LIMIT_ERR: &str = "query returned more than";
+
+    let mut logs = Vec::new();
+    let mut ranges = vec![(from_block, to_block)];
+    while let Some((from, to)) = ranges.pop() {
+        let filter = FilterBuilder::default()
+            .address(vec![address])
+            .from_block(BlockNumber::Number(from.into()))
+            .to_block(BlockNumber::Number(to.into()))
+            .topics(Some(topics.clone()), None, None, None)
+            .build();
+
+        match web3.eth().logs(filter).await {
+            Ok(mut range_logs) => logs.append(&mut range_logs),
+            Err(err) if err.to_string().contains(LIMIT_ERR) && from < to => {
+                // The first half goes on top of the stack, so logs remain ordered.
+                let middle = from + (to - from) / 2;
+                ranges.push((middle + 1, to));
+                ranges.push((from, middle));
+            }
+            Err(err) => {
+                return Err(format_err!(
+                    "Failed to get logs of the Ethereum blocks {}..={}: {}",
+                    from,
+                    to,
+                    err
+                ))
+            }
+        }
+    }
+    Ok(logs)
+}
+
+/// Web3 transport that serves the data restore driver requests from the archive.
+///
+/// Only the methods used by the driver are supported, and logs are filtered
+/// by the contract address, the block range and the first topic only.
+#[derive(Debug, Clone)]
+pub struct ArchiveTransport {
+    data: Arc<ArchiveData>,
+    id: Arc<AtomicUsize>,
+}
+
+#[derive(Debug)]
+struct ArchiveData {
+    zksync_contract: Address,
+    last_eth_block: u64,
+    total_verified_blocks: u32,
+    total_verified_blocks_selectors: Vec<[u8; 4]>,
+    logs: Vec<Log>,
+    transactions: HashMap<H256, Transaction>,
+    end_eth_blocks_offset: u64,
+}
+
+impl ArchiveTransport {
+    /// Creates the transport for the archive.
+    ///
+    /// # Arguments
+    ///
+    /// * `archive` - Archive to serve the requests from
+    /// * `end_eth_blocks_offset` - The distance to the last ethereum block used by the driver.
+    ///   The reported latest block is shifted by it, so the driver processes all the archived blocks.
+    ///
+    pub fn new(archive: L1Archive, end_eth_blocks_offset: u64) -> Self {
+        let transactions = archive
+            .transactions
+            .into_iter()
+            .map(|tx| (tx.hash, tx))
+            .collect();
+        // The driver may query the verified blocks with any version of the contract ABI.
+        let total_verified_blocks_selectors = vec![
+            zksync_contract()
+                .function("totalBlocksExecuted")
+                .expect("Main contract abi error")
+                .short_signature(),
+            zksync_contract_v3()
+                .function("totalBlocksVerified")
+                .expect("Main contract abi error")
+                .short_signature(),
+        ];
+        let data = ArchiveData {
+            zksync_contract: archive.header.zksync_contract,
+            last_eth_block: archive.header.last_eth_block,
+            total_verified_blocks: archive.header.total_verified_blocks,
+            total_verified_blocks_selectors,
+            logs: archive.logs,
+            transactions,
+            end_eth_blocks_offset,
+        };
+
+        Self {
+            data: Arc::new(data),
+            id: Arc::new(AtomicUsize::new(1)),
+        }
+    }
+}
+
+impl Transport for ArchiveTransport {
+    type Out = std::future::Ready<web3::Result<Value>>;
+
+    fn prepare(&self, method: &str, params: Vec<Value>) -> (RequestId, Call) {
+        let id = self.id.fetch_add(1, Ordering::Relaxed);
+        (id, build_request(id, method, params))
+    }
+
+    fn send(&self, _id: RequestId, request: Call) -> Self::Out {
+        std::future::ready(self.data.handle(request))
+    }
+}
+
+impl ArchiveData {
+    fn handle(&self, request: Call) -> web3::Result<Value> {
+        let call = match request {
+            Call::MethodCall(call) => call,
+            _ => return Err(web3::Error::Unreachable),
+        };
+        let params = match call.params {
+            Params::Array(params) => params,
+            _ => Vec::new(),
+        };
+
+        match call.method.as_str() {
+            "eth_blockNumber" => Ok(json!(U64::from(
+                self.last_eth_block + self.end_eth_blocks_offset
+            ))),
+            "eth_getLogs" => {
+                let filter = params.first().ok_or_else(|| invalid_params("No filter"))?;
+                Ok(json!(self.logs(filter)?))
+            }
+            "eth_getTransactionByHash" => {
+                let hash: H256 = parse_param(params.first())?;
+                // Unknown transactions are reported as missing, the same way the node does.
+                Ok(json!(self.transactions.get(&hash)))
+            }
+            "eth_call" => {
+                let request = params.first();
+                let to: Address = parse_param(request.and_then(|request| request.get("to")))?;
+                let data: Bytes = parse_param(request.and_then(|request| request.get("data")))?;
+                if to != self.zksync_contract {
+                    return Err(invalid_params("Only zkSync contract calls are archived"));
+                }
+                // The only contract call of the driver is the total verified blocks request.
+                let is_total_verified_blocks_call = data.0.len() == 4
+                    && self
+                        .total_verified_blocks_selectors
+                        .iter()
+                        .any(|selector| selector[..] == data.0[..]);
+                if !is_total_verified_blocks_call {
+                    return Err(invalid_params(&format!(
+                        "Contract call 0x{} is not archived",
+                        hex::encode(&data.0)
+                    )));
+                }
+                Ok(json!(format!("{:#066x}", self.total_verified_blocks)))
+            }
+            _ => Err(web3::Error::Rpc(jsonrpc_core::Error::method_not_found())),
+        }
+    }
+
+    fn logs(&self, filter: &Value) -> web3::Result<Vec<Log>> {
+        let addresses: Vec<Address> = parse_value_or_array(filter.get("address"))?;
+        let topics: Vec<H256> = match filter.get("topics") {
+            Some(Value::Array(topics)) => parse_value_or_array(topics.first())?,
+            _ => Vec::new(),
+        };
+        let from_block = self.block_number(filter.get("fromBlock"), 0)?;
+        let to_block = self.block_number(filter.get("toBlock"), self.last_eth_block)?;
+
+        let logs = self
+            .logs
+            .iter()
+            .filter(|log| addresses.is_empty() || addresses.contains(&log.address))
+            .filter(|log| {
+                topics.is_empty()
+                    || log
+                        .topics
+                        .first()
+                        .map_or(false, |topic| topics.contains(topic))
+            })
+            .filter(|log| {
+                log.block_number.map_or(true, |number| {
+                    (from_block..=to_block).contains(&number.as_u64())
+                })
+            })
+            .cloned()
+            .collect();
+        Ok(logs)
+    }
+
+    fn block_number(&self, value: Option<&Value>, default: u64) -> web3::Result<u64> {
+        match value {
+            None | Some(Value::Null) => Ok(default),
+            Some(Value::String(tag)) if tag == "earliest" => Ok(0),
+            Some(Value::String(tag)) if tag == "latest" || tag == "pending" => {
+                Ok(self.last_eth_block)
+            }
+            value => parse_param::<U64>(value).map(|number| number.as_u64()),
+        }
+    }
+}
+
+fn parse_param<T: DeserializeOwned>(value: Option<&Value>) -> web3::Result<T> {
+    let value = value.ok_or_else(|| invalid_params("Missing parameter"))?;
+    serde_json::from_value(value.clone()).map_err(|err| invalid_params(&err.to_string()))
+}
+
+/// Parses the filter field, which is either a single value or an array of them.
+fn parse_value_or_array<T: DeserializeOwned>(value: Option<&Value>) -> web3::Result<Vec<T>> {
+    match value {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Array(values)) => values.iter().map(|v| parse_param(Some(v))).collect(),
+        value => Ok(vec![parse_param(value)?]),
+    }
+}
+
+fn invalid_params(message: &str) -> web3::Error {
+    web3::Error::Rpc(jsonrpc_core::Error::invalid_params(message))
+}
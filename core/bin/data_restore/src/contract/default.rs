@@ -7,6 +7,7 @@ use crate::rollup_ops::RollupOpsBlock;
 pub fn rollup_ops_blocks_from_bytes(input_data: Vec<u8>) -> Result<RollupOpsBlock, anyhow::Error> {
     let block_number_argument_id = 0;
     let fee_account_argument_id = 1;
+    let new_roots_argument_id = 2;
     let public_data_argument_id = 3;
     let decoded_commitment_parameters = ethabi::decode(
         vec![
@@ -38,6 +39,14 @@ pub fn rollup_ops_blocks_from_bytes(input_data: Vec<u8>) -> Result<RollupOpsBloc
     ) {
         let ops = get_rollup_ops_from_data(public_data.as_slice())?;
         let fee_account = AccountId(fee_acc.as_u32());
+        // The new state root is the first of the committed roots.
+        let new_root_hash = match &decoded_commitment_parameters[new_roots_argument_id] {
+            ethabi::Token::Array(roots) => roots.first().and_then(|root| match root {
+                ethabi::Token::FixedBytes(root) => Some(H256::from_slice(root)),
+                _ => None,
+            }),
+            _ => None,
+        };
 
         let block = RollupOpsBlock {
             block_num: BlockNumber(block_num.as_u32()),
@@ -45,6 +54,7 @@ pub fn rollup_ops_blocks_from_bytes(input_data: Vec<u8>) -> Result<RollupOpsBloc
             fee_account,
             timestamp: None,
             previous_block_root_hash: H256::default(),
+            new_root_hash,
             contract_version: None,
         };
         Ok(block)
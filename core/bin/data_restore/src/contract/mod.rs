@@ -44,6 +44,26 @@ impl<T: Transport> ZkSyncDeployedContract<T> {
             .as_u32()
     }
 
+    /// Creates the contract with the ABI of the given version.
+    pub fn new(
+        eth: Eth<T>,
+        address: Address,
+        version: ZkSyncContractVersion,
+    ) -> ZkSyncDeployedContract<T> {
+        use ZkSyncContractVersion::*;
+        match version {
+            V0 => Self::version0(eth, address),
+            V1 => Self::version1(eth, address),
+            V2 => Self::version2(eth, address),
+            V3 => Self::version3(eth, address),
+            // Later versions didn't change the events and functions used by the data restore.
+            V4 | V5 | V6 => ZkSyncDeployedContract {
+                version,
+                ..Self::version4(eth, address)
+            },
+        }
+    }
+
     pub fn version0(eth: Eth<T>, address: Address) -> ZkSyncDeployedContract<T> {
         let abi = zksync_contract_v0();
         ZkSyncDeployedContract {
@@ -104,6 +104,7 @@ pub(super) fn rollup_ops_blocks_from_bytes_inner(
                         fee_account: AccountId(fee_acc.as_u32()),
                         timestamp: Some(timestamp.as_u64()),
                         previous_block_root_hash,
+                        new_root_hash: Some(H256::from_slice(root_hash)),
                         contract_version: None,
                     });
 
@@ -97,6 +97,21 @@ impl ZkSyncContractVersion {
             .expect("cannot upgrade past the latest contract version")
     }
 
+    /// Returns the version of the contract at the given Ethereum block.
+    ///
+    /// # Arguments
+    ///
+    /// * `contract_upgrade_eth_blocks` - Ethereum blocks that include correct UpgradeComplete events
+    /// * `eth_block` - Ethereum block to get the version at
+    ///
+    pub fn at_eth_block(&self, contract_upgrade_eth_blocks: &[u64], eth_block: u64) -> Self {
+        let num = contract_upgrade_eth_blocks
+            .iter()
+            .filter(|block| eth_block >= **block)
+            .count();
+        self.upgrade(num as u32)
+    }
+
     /// Returns supported block chunks sizes by the verifier contract
     /// with the given version.
     pub fn available_block_chunk_sizes(&self) -> &'static [usize] {
@@ -1,3 +1,5 @@
+// Built-in deps
+use std::path::PathBuf;
 // External deps
 use web3::{
     contract::Contract,
@@ -22,6 +24,7 @@ use crate::{
     rollup_ops::RollupOpsBlock,
     storage_interactor::StorageInteractor,
     tree_state::TreeState,
+    verification::VerificationReport,
 };
 
 /// Storage state update:
@@ -79,6 +82,10 @@ pub struct DataRestoreDriver<T: Transport> {
     /// Serial id of the last priority operation processed by the driver. It's necessary to manually
     /// keep track of it since it's impossible to restore it from the contract.
     pub last_priority_op_serial_id: SerialId,
+    /// Results of the restored blocks verification against the root hashes committed to the contract.
+    pub verification_report: VerificationReport,
+    /// Path to write the verification report to, when the restoring process is finished or stopped.
+    pub verification_report_path: Option<PathBuf>,
 }
 
 impl<T: Transport> DataRestoreDriver<T> {
@@ -132,6 +139,8 @@ impl<T: Transport> DataRestoreDriver<T> {
             finite_mode,
             final_hash,
             last_priority_op_serial_id: 0,
+            verification_report: VerificationReport::default(),
+            verification_report_path: None,
         }
     }
 
@@ -254,10 +263,7 @@ impl<T: Transport> DataRestoreDriver<T> {
         self.tree_state.state.root_hash();
         let tree_cache = self.tree_state.state.get_balance_tree().get_internals();
         interactor
-            .store_tree_cache(
-                self.tree_state.block_number,
-                serde_json::to_value(tree_cache).expect("failed to serialize tree cache"),
-            )
+            .store_tree_cache(self.tree_state.block_number, tree_cache)
             .await;
     }
 
@@ -391,6 +397,7 @@ impl<T: Transport> DataRestoreDriver<T> {
                         // We've restored all the blocks, our job is done. Store the tree cache for
                         // consequent usage.
                         self.store_tree_cache(interactor).await;
+                        self.write_verification_report();
 
                         break;
                     }
@@ -406,6 +413,16 @@ impl<T: Transport> DataRestoreDriver<T> {
         }
     }
 
+    /// Writes the verification report, if the path for it is set.
+    fn write_verification_report(&self) {
+        if let Some(path) = &self.verification_report_path {
+            match self.verification_report.write_to_file(path) {
+                Ok(()) => vlog::info!("Verification report is written to {}", path.display()),
+                Err(err) => vlog::error!("Failed to write the verification report: {}", err),
+            }
+        }
+    }
+
     /// Updates events state, saves new blocks, tokens events and the last watched eth block number in storage
     /// Returns bool flag, true if there are new block events
     async fn update_events_state(&mut self, interactor: &mut StorageInteractor<'_>) -> bool {
@@ -463,6 +480,15 @@ impl<T: Transport> DataRestoreDriver<T> {
                     &mut self.last_priority_op_serial_id,
                 )
                 .expect("Updating tree state: cant update tree from operations");
+
+            if let Err(mismatch) =
+                self.verification_report
+                    .verify_block(&op_block, &block, &acc_updates)
+            {
+                self.write_verification_report();
+                panic!("Restored state diverged from the contract. {}", mismatch);
+            }
+
             blocks.push(block);
             updates.push(acc_updates);
             count += 1;
@@ -1,6 +1,7 @@
 // Built-in deps
 use std::str::FromStr;
 // Workspace deps
+use zksync_crypto::merkle_tree::parallel_smt::SparseMerkleTreeSerializableCacheBN256;
 use zksync_storage::{
     data_restore::records::{NewBlockEvent, NewRollupOpsBlock},
     StorageProcessor,
@@ -78,6 +79,7 @@ impl<'a> DatabaseStorageInteractor<'a> {
                 fee_account: block.fee_account,
                 timestamp: block.timestamp,
                 previous_block_root_hash: block.previous_block_root_hash,
+                new_root_hash: block.new_root_hash,
             });
         }
 
@@ -384,12 +386,12 @@ impl<'a> DatabaseStorageInteractor<'a> {
     pub async fn store_tree_cache(
         &mut self,
         block_number: BlockNumber,
-        tree_cache: serde_json::Value,
+        tree_cache: SparseMerkleTreeSerializableCacheBN256,
     ) {
         self.storage
             .chain()
             .block_schema()
-            .store_account_tree_cache(block_number, tree_cache)
+            .store_account_tree_cache(block_number, &tree_cache)
             .await
             .expect("Failed to store the tree cache");
     }
@@ -511,11 +511,8 @@ impl EventsState {
             let eth_block = log
                 .block_number
                 .expect("no Ethereum block number for block log");
-            let num = contract_upgrade_eth_blocks
-                .iter()
-                .filter(|block| eth_block.as_u64() >= **block)
-                .count();
-            let contract_version = init_contract_version.upgrade(num as u32);
+            let contract_version =
+                init_contract_version.at_eth_block(contract_upgrade_eth_blocks, eth_block.as_u64());
 
             let block_num = log.topics[1];
 
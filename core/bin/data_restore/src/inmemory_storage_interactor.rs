@@ -5,6 +5,7 @@ use std::sync::Arc;
 
 use web3::types::Address;
 
+use zksync_crypto::merkle_tree::parallel_smt::SparseMerkleTreeSerializableCacheBN256;
 use zksync_types::block::Block;
 use zksync_types::{
     Account, AccountId, AccountMap, AccountUpdate, AccountUpdates, Action, BlockNumber,
@@ -319,7 +320,7 @@ impl InMemoryStorageInteractor {
     pub async fn store_tree_cache(
         &mut self,
         _block_number: BlockNumber,
-        _tree_cache: serde_json::Value,
+        _tree_cache: SparseMerkleTreeSerializableCacheBN256,
     ) {
         // Inmemory storage doesn't support caching.
     }
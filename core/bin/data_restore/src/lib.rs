@@ -1,3 +1,4 @@
+pub mod archive;
 pub mod contract;
 pub mod data_restore_driver;
 pub mod database_storage_interactor;
@@ -8,6 +9,7 @@ pub mod inmemory_storage_interactor;
 pub mod rollup_ops;
 pub mod storage_interactor;
 pub mod tree_state;
+pub mod verification;
 
 #[cfg(test)]
 mod tests;
@@ -1,16 +1,22 @@
+use std::convert::TryFrom;
+use std::path::PathBuf;
+
 use serde::Deserialize;
 use structopt::StructOpt;
-use web3::transports::Http;
+use web3::{transports::Http, Transport};
 use zksync_config::configs::{ChainConfig, ContractsConfig as EnvContractsConfig, ETHClientConfig};
 use zksync_crypto::convert::FeConvert;
 use zksync_storage::ConnectionPool;
 use zksync_types::{Address, H256};
 
 use web3::Web3;
-use zksync_data_restore::contract::ZkSyncDeployedContract;
+use zksync_data_restore::contract::{ZkSyncContractVersion, ZkSyncDeployedContract};
 use zksync_data_restore::{
-    add_tokens_to_storage, data_restore_driver::DataRestoreDriver,
-    database_storage_interactor::DatabaseStorageInteractor, storage_interactor::StorageInteractor,
+    add_tokens_to_storage,
+    archive::{ArchiveTransport, ArchivedContracts, L1Archive},
+    data_restore_driver::DataRestoreDriver,
+    database_storage_interactor::DatabaseStorageInteractor,
+    storage_interactor::StorageInteractor,
     END_ETH_BLOCKS_OFFSET, ETH_BLOCKS_STEP,
 };
 use zksync_types::network::Network;
@@ -45,6 +51,18 @@ struct Opt {
     /// Provides a path to the configuration file for data restore
     #[structopt(long = "config", name = "config")]
     config_path: Option<String>,
+
+    /// Exports the Ethereum data required to restore the state into the archive file and exits
+    #[structopt(long)]
+    export_archive: Option<String>,
+
+    /// Restores data from the archive file instead of the Ethereum node
+    #[structopt(long, conflicts_with = "export_archive")]
+    archive: Option<String>,
+
+    /// Writes the report of the restored blocks verification against the committed root hashes
+    #[structopt(long)]
+    verification_report: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -55,6 +73,9 @@ pub struct ContractsConfig {
     contract_addr: Address,
     init_contract_version: u32,
     upgrade_eth_blocks: Vec<u64>,
+    /// Required only to export the archive.
+    #[serde(default)]
+    upgrade_gatekeeper_addr: Option<Address>,
 }
 
 impl ContractsConfig {
@@ -75,30 +96,82 @@ impl ContractsConfig {
             contract_addr: contracts_opts.contract_addr,
             init_contract_version: contracts_opts.init_contract_version,
             upgrade_eth_blocks: contracts_opts.upgrade_eth_blocks,
+            upgrade_gatekeeper_addr: Some(contracts_opts.upgrade_gatekeeper_addr),
         }
     }
 }
 
 #[tokio::main]
 async fn main() {
-    vlog::info!("Restoring zkSync state from the contract");
     let _sentry_guard = vlog::init();
-    let connection_pool = ConnectionPool::new(Some(1));
     let config_opts = ETHClientConfig::from_env();
 
     let opt = Opt::from_args();
 
-    let web3_url = opt.web3_url.unwrap_or_else(|| config_opts.web3_url());
-
-    let transport = Http::new(&web3_url).expect("failed to start web3 transport");
-
     let config = opt
         .config_path
+        .clone()
         .map(|path| ContractsConfig::from_file(&path))
         .unwrap_or_else(ContractsConfig::from_env);
 
     vlog::info!("Using the following config: {:#?}", config);
 
+    if let Some(path) = &opt.archive {
+        vlog::info!("Restoring zkSync state from the archive {}", path);
+        let archive = L1Archive::read_from_file(path).expect("Unable to read the archive");
+        assert_eq!(
+            archive.header.zksync_contract, config.contract_addr,
+            "The archive was exported for another zkSync contract"
+        );
+        let archived_upgrade_blocks = archive.upgrade_eth_blocks();
+        for block in &config.upgrade_eth_blocks {
+            assert!(
+                archived_upgrade_blocks.contains(block),
+                "There is no UpgradeComplete event in the Ethereum block {} in the archive",
+                block
+            );
+        }
+        let transport = ArchiveTransport::new(archive, END_ETH_BLOCKS_OFFSET);
+        restore(Web3::new(transport), opt, config).await;
+        return;
+    }
+
+    let web3_url = opt
+        .web3_url
+        .clone()
+        .unwrap_or_else(|| config_opts.web3_url());
+    let transport = Http::new(&web3_url).expect("failed to start web3 transport");
+    let web3 = Web3::new(transport);
+
+    if let Some(path) = &opt.export_archive {
+        vlog::info!(
+            "Exporting data required to restore zkSync state to {}",
+            path
+        );
+        let contracts = ArchivedContracts {
+            zksync_contract: config.contract_addr,
+            governance_contract: config.governance_addr,
+            upgrade_gatekeeper_contract: config
+                .upgrade_gatekeeper_addr
+                .expect("upgrade_gatekeeper_addr is required to export the archive"),
+            genesis_tx_hash: config.genesis_tx_hash,
+            init_contract_version: ZkSyncContractVersion::try_from(config.init_contract_version)
+                .expect("invalid initial contract version provided"),
+            contract_upgrade_eth_blocks: config.upgrade_eth_blocks.clone(),
+        };
+        L1Archive::export_to_file(&web3, &contracts, ETH_BLOCKS_STEP, path)
+            .await
+            .expect("Unable to export the archive");
+        return;
+    }
+
+    vlog::info!("Restoring zkSync state from the contract");
+    restore(web3, opt, config).await;
+}
+
+async fn restore<T: Transport>(web3: Web3<T>, opt: Opt, config: ContractsConfig) {
+    let connection_pool = ConnectionPool::new(Some(1));
+
     let finite_mode = opt.finite;
     let final_hash = if finite_mode {
         opt.final_hash
@@ -107,7 +180,6 @@ async fn main() {
         None
     };
     let storage = connection_pool.access_storage().await.unwrap();
-    let web3 = Web3::new(transport);
     let contract = ZkSyncDeployedContract::version4(web3.eth(), config.contract_addr);
     let mut driver = DataRestoreDriver::new(
         web3,
@@ -120,6 +192,7 @@ async fn main() {
         final_hash,
         contract,
     );
+    driver.verification_report_path = opt.verification_report.map(PathBuf::from);
 
     let mut interactor = StorageInteractor::Database(DatabaseStorageInteractor::new(storage));
     // If genesis is argument is present - there will be fetching contracts creation transactions to get first eth block and genesis acc address
@@ -20,6 +20,10 @@ pub struct RollupOpsBlock {
     pub timestamp: Option<u64>,
     /// Previous block root hash.
     pub previous_block_root_hash: H256,
+    /// Root hash of the state after the block, committed to the contract.
+    /// Used to verify the restored state. Absent for the blocks restored from
+    /// the storage which were saved before it has been tracked.
+    pub new_root_hash: Option<H256>,
     /// zkSync contract version for the given block.
     /// Used to obtain block chunk sizes. Stored in the database
     /// in the corresponding block event.
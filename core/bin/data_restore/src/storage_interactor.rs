@@ -2,6 +2,8 @@ use std::{collections::HashMap, convert::TryFrom};
 
 use web3::types::H256;
 
+use zksync_crypto::merkle_tree::parallel_smt::SparseMerkleTreeSerializableCacheBN256;
+
 use zksync_storage::data_restore::records::{
     NewBlockEvent, StoredBlockEvent, StoredRollupOpsBlock,
 };
@@ -28,7 +30,7 @@ pub struct StoredTreeState {
 }
 
 pub struct CachedTreeState {
-    pub tree_cache: serde_json::Value,
+    pub tree_cache: SparseMerkleTreeSerializableCacheBN256,
     pub account_map: AccountMap,
     pub current_block: Block,
     pub nfts: HashMap<TokenId, NFT>,
@@ -206,7 +208,7 @@ impl StorageInteractor<'_> {
     pub async fn store_tree_cache(
         &mut self,
         block_number: BlockNumber,
-        tree_cache: serde_json::Value,
+        tree_cache: SparseMerkleTreeSerializableCacheBN256,
     ) {
         storage_interact!(self.store_tree_cache(block_number, tree_cache))
     }
@@ -281,6 +283,7 @@ pub fn stored_ops_block_into_ops_block(op_block: StoredRollupOpsBlock) -> Rollup
             .previous_block_root_hash
             .map(|h| H256::from_slice(&h))
             .unwrap_or_default(),
+        new_root_hash: op_block.new_root_hash.map(|h| H256::from_slice(&h)),
         contract_version: Some(
             ZkSyncContractVersion::try_from(op_block.contract_version as u32)
                 .expect("invalid contract version in the database"),
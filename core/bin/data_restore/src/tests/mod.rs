@@ -10,12 +10,12 @@ use num::BigUint;
 use serde_json::{json, Value};
 use web3::{
     contract::tokens::Tokenize,
-    types::{Bytes, Transaction, H160},
+    types::{Bytes, CallRequest, Transaction, H160},
     RequestId, Transport, Web3,
 };
 
 use db_test_macro::test as db_test;
-use zksync_contracts::{governance_contract, zksync_contract};
+use zksync_contracts::{governance_contract, upgrade_gatekeeper, zksync_contract};
 use zksync_crypto::Fr;
 use zksync_storage::{
     chain::account::AccountSchema, data_restore::DataRestoreSchema, StorageProcessor,
@@ -26,13 +26,18 @@ use zksync_types::{
     ZkSyncOp, H256,
 };
 
-use crate::contract::ZkSyncDeployedContract;
+use crate::contract::{ZkSyncContractVersion, ZkSyncDeployedContract};
 use crate::{
+    archive::{
+        ArchiveHeader, ArchiveTransport, ArchivedContracts, L1Archive, ARCHIVE_FORMAT_VERSION,
+    },
     data_restore_driver::DataRestoreDriver,
     database_storage_interactor::DatabaseStorageInteractor,
     inmemory_storage_interactor::InMemoryStorageInteractor,
+    rollup_ops::RollupOpsBlock,
     storage_interactor::StorageInteractor,
     tests::utils::{create_log, u32_to_32bytes},
+    tree_state::TreeState,
     END_ETH_BLOCKS_OFFSET, ETH_BLOCKS_STEP,
 };
 use web3::api::{Eth, Namespace};
@@ -112,6 +117,31 @@ fn create_block(block_number: BlockNumber, transactions: Vec<ExecutedOperations>
     )
 }
 
+/// Sets the root hashes of the blocks to the ones obtained by applying their operations
+/// to the empty state, so the blocks pass the verification of the restored state.
+fn set_restored_root_hashes(blocks: &mut [Block]) {
+    let mut tree_state = TreeState::new();
+    for block in blocks {
+        let ops_block = RollupOpsBlock {
+            block_num: block.block_number,
+            ops: block
+                .block_transactions
+                .iter()
+                .filter_map(|op| op.get_executed_op().cloned())
+                .collect(),
+            fee_account: block.fee_account,
+            timestamp: None,
+            previous_block_root_hash: H256::default(),
+            new_root_hash: None,
+            contract_version: None,
+        };
+        tree_state
+            .update_tree_states_from_ops_block(&ops_block, &[block.block_chunks_size], &mut 0)
+            .expect("Cant update state from block");
+        block.new_root_hash = tree_state.root_hash();
+    }
+}
+
 fn create_transaction_v4(number: u32, stored_block: Block, blocks: Vec<Block>) -> Transaction {
     let hash: H256 = u32_to_32bytes(number).into();
     let block_number = blocks
@@ -381,27 +411,28 @@ async fn test_run_state_update(mut storage: StorageProcessor<'_>) {
         )],
     );
 
-    transport.push_transactions(vec![
-        create_transaction(
-            1,
-            create_block(
-                BlockNumber(1),
-                vec![create_deposit(Default::default(), Default::default(), 50)],
-            ),
+    let mut blocks = vec![
+        create_block(
+            BlockNumber(1),
+            vec![create_deposit(Default::default(), Default::default(), 50)],
         ),
-        create_transaction(
-            2,
-            create_block(
-                BlockNumber(2),
-                vec![create_withdraw_operations(
-                    AccountId(0),
-                    Default::default(),
-                    Default::default(),
-                    10,
-                )],
-            ),
+        create_block(
+            BlockNumber(2),
+            vec![create_withdraw_operations(
+                AccountId(0),
+                Default::default(),
+                Default::default(),
+                10,
+            )],
         ),
-    ]);
+    ];
+    set_restored_root_hashes(&mut blocks);
+    transport.push_transactions(
+        (1..)
+            .zip(blocks)
+            .map(|(number, block)| create_transaction(number, block))
+            .collect(),
+    );
 
     let eth = Eth::new(transport.clone());
     let mut driver = DataRestoreDriver::new(
@@ -434,6 +465,8 @@ async fn test_run_state_update(mut storage: StorageProcessor<'_>) {
 
     assert_eq!(BigUint::from(40u32), balance);
     assert_eq!(driver.events_state.committed_events.len(), 2);
+    assert_eq!(driver.verification_report.verified_blocks, 2);
+    assert!(driver.verification_report.mismatch.is_none());
     let events = DataRestoreSchema(db.storage())
         .load_committed_events_state()
         .await
@@ -462,6 +495,164 @@ async fn test_run_state_update(mut storage: StorageProcessor<'_>) {
     assert_eq!(*driver.tree_state.block_number, 2)
 }
 
+#[db_test]
+async fn test_restore_from_archive(mut storage: StorageProcessor<'_>) {
+    let contract_addr = H160::from([1u8; 20]);
+    let contract = zksync_contract();
+    // Use old contract version.
+    let init_contract_version: u32 = 3;
+
+    let mut transport = Web3Transport::new();
+    for event in &["BlockCommit", "BlockVerification"] {
+        let topic = contract
+            .event(event)
+            .expect("Main contract abi error")
+            .signature();
+        transport.insert_logs(
+            format!("{:?}", topic),
+            vec![create_log(
+                contract_addr,
+                topic,
+                vec![u32_to_32bytes(1).into()],
+                Bytes(vec![]),
+                1,
+                u32_to_32bytes(1).into(),
+            )],
+        );
+    }
+    let mut blocks = vec![create_block(
+        BlockNumber(1),
+        vec![create_deposit(Default::default(), Default::default(), 50)],
+    )];
+    set_restored_root_hashes(&mut blocks);
+    // The first transaction plays the role of the genesis one.
+    transport.push_transactions(vec![
+        create_transaction(0, create_block(BlockNumber(0), vec![])),
+        create_transaction(1, blocks.remove(0)),
+    ]);
+
+    // Upgrade events are archived as well.
+    let gatekeeper_addr = H160::from([2u8; 20]);
+    let upgrade_topic = upgrade_gatekeeper()
+        .event("UpgradeComplete")
+        .expect("Upgrade Gatekeeper contract abi error")
+        .signature();
+    transport.insert_logs(
+        format!("{:?}", upgrade_topic),
+        vec![create_log(
+            gatekeeper_addr,
+            upgrade_topic,
+            vec![],
+            Bytes(vec![]),
+            1,
+            u32_to_32bytes(2).into(),
+        )],
+    );
+
+    let contracts = ArchivedContracts {
+        zksync_contract: contract_addr,
+        governance_contract: contract_addr,
+        upgrade_gatekeeper_contract: gatekeeper_addr,
+        genesis_tx_hash: u32_to_32bytes(0).into(),
+        init_contract_version: ZkSyncContractVersion::V3,
+        contract_upgrade_eth_blocks: Vec::new(),
+    };
+    let mut bytes = Vec::new();
+    let header = L1Archive::export(
+        &Web3::new(transport.clone()),
+        &contracts,
+        ETH_BLOCKS_STEP,
+        &mut bytes,
+    )
+    .await
+    .unwrap();
+    assert_eq!(header.total_verified_blocks, 1);
+
+    let archive_from_bytes = L1Archive::read(bytes.as_slice()).unwrap();
+    assert_eq!(archive_from_bytes.header, header);
+    assert_eq!(archive_from_bytes.logs.len(), 3);
+    assert_eq!(archive_from_bytes.transactions.len(), 2);
+    assert_eq!(archive_from_bytes.upgrade_eth_blocks(), vec![1]);
+
+    let mut rewritten_bytes = Vec::new();
+    archive_from_bytes.write(&mut rewritten_bytes).unwrap();
+    assert_eq!(
+        L1Archive::read(rewritten_bytes.as_slice()).unwrap(),
+        archive_from_bytes
+    );
+
+    // Restore the state without the Ethereum node.
+    let transport = ArchiveTransport::new(archive_from_bytes, END_ETH_BLOCKS_OFFSET);
+    let mut interactor = StorageInteractor::Database(DatabaseStorageInteractor::new(storage));
+    let mut driver = DataRestoreDriver::new(
+        Web3::new(transport.clone()),
+        contract_addr,
+        Vec::new(),
+        init_contract_version,
+        ETH_BLOCKS_STEP,
+        END_ETH_BLOCKS_OFFSET,
+        true,
+        None,
+        ZkSyncDeployedContract::version4(Eth::new(transport), contract_addr),
+    );
+    driver.run_state_update(&mut interactor).await;
+
+    let db = match &mut interactor {
+        StorageInteractor::Database(db) => db,
+        _ => unreachable!(),
+    };
+    let (_, account) = AccountSchema(db.storage())
+        .account_state_by_address(Address::default())
+        .await
+        .unwrap()
+        .verified
+        .unwrap();
+
+    assert_eq!(BigUint::from(50u32), account.get_balance(TokenId(0)));
+    assert_eq!(*driver.tree_state.block_number, 1);
+}
+
+#[tokio::test]
+async fn test_archive_transport_calls() {
+    let contract_addr = H160::from([1u8; 20]);
+    let archive = L1Archive {
+        header: ArchiveHeader {
+            format_version: ARCHIVE_FORMAT_VERSION,
+            zksync_contract: contract_addr,
+            governance_contract: contract_addr,
+            upgrade_gatekeeper_contract: contract_addr,
+            genesis_tx_hash: H256::zero(),
+            last_eth_block: 10,
+            total_verified_blocks: 5,
+        },
+        logs: Vec::new(),
+        transactions: Vec::new(),
+    };
+    let transport = ArchiveTransport::new(archive, END_ETH_BLOCKS_OFFSET);
+
+    for version in &[ZkSyncContractVersion::V3, ZkSyncContractVersion::V4] {
+        let contract =
+            ZkSyncDeployedContract::new(Eth::new(transport.clone()), contract_addr, *version);
+        assert_eq!(contract.get_total_verified_blocks().await, 5);
+    }
+
+    // Calls which are not archived must not be answered with the verified blocks count.
+    let selector = zksync_contract()
+        .function("totalBlocksCommitted")
+        .unwrap()
+        .short_signature();
+    let request = CallRequest {
+        to: Some(contract_addr),
+        data: Some(Bytes(selector.to_vec())),
+        ..Default::default()
+    };
+    assert!(Web3::new(transport)
+        .eth()
+        .call(request, None)
+        .await
+        .is_err());
+}
+
 // TODO: Find a way to restore this test (ZKS-694)
 #[tokio::test]
 #[ignore]
@@ -1,7 +1,10 @@
 use crate::rollup_ops::RollupOpsBlock;
 use anyhow::format_err;
 use std::collections::HashMap;
-use zksync_crypto::{params::account_tree_depth, Fr};
+use zksync_crypto::{
+    merkle_tree::parallel_smt::SparseMerkleTreeSerializableCacheBN256, params::account_tree_depth,
+    Fr,
+};
 use zksync_state::{
     handler::TxHandler,
     state::{CollectedFee, OpSuccess, TransferOutcome, ZkSyncState},
@@ -83,7 +86,7 @@ impl TreeState {
     /// * `nfts` - Finalized NFTs
     ///
     pub fn restore_from_cache(
-        tree_cache: serde_json::Value,
+        tree_cache: SparseMerkleTreeSerializableCacheBN256,
         account_map: AccountMap,
         current_block: Block,
         nfts: HashMap<TokenId, NFT>,
@@ -91,9 +94,7 @@ impl TreeState {
         let mut account_id_by_address = HashMap::with_capacity(account_map.len());
         let mut balance_tree = AccountTree::new(account_tree_depth());
 
-        balance_tree.set_internals(
-            serde_json::from_value(tree_cache).expect("failed to deserialize tree cache"),
-        );
+        balance_tree.set_internals(tree_cache);
 
         account_map.into_iter().for_each(|(account_id, account)| {
             account_id_by_address.insert(account.address, account_id);
@@ -627,6 +628,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            new_root_hash: None,
             contract_version: None,
         };
 
@@ -654,6 +656,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            new_root_hash: None,
             contract_version: None,
         };
 
@@ -682,6 +685,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            new_root_hash: None,
             contract_version: None,
         };
 
@@ -710,6 +714,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            new_root_hash: None,
             contract_version: None,
         };
 
@@ -738,6 +743,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            new_root_hash: None,
             contract_version: None,
         };
 
@@ -764,6 +770,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            new_root_hash: None,
             contract_version: None,
         };
 
@@ -790,6 +797,7 @@ mod test {
             fee_account: AccountId(1),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            new_root_hash: None,
             contract_version: None,
         };
         // This transaction have to be deleted, do not uncomment. Delete it after removing the corresponding code        // let tx6 = Close {
@@ -997,6 +1005,7 @@ mod test {
             fee_account: AccountId(0),
             timestamp: None,
             previous_block_root_hash: Default::default(),
+            new_root_hash: None,
             contract_version: None,
         };
 
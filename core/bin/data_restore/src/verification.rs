@@ -0,0 +1,233 @@
+//! Verification of the restored blocks against the state root hashes committed to the contract.
+//!
+//! Every restored block is checked as soon as it's applied to the tree, so a diverged
+//! restore is stopped on the first wrong block instead of the final hash check.
+
+// Built-in deps
+use std::{fmt, fs::File, io::BufWriter, path::Path};
+// External deps
+use serde::Serialize;
+// Workspace deps
+use zksync_types::{block::Block, AccountUpdates, BlockNumber, ZkSyncOp, H256};
+// Local deps
+use crate::rollup_ops::RollupOpsBlock;
+
+/// Operation whose restored execution result differs from its public data committed on chain.
+#[derive(Debug, Clone, Serialize)]
+pub struct DivergentOperation {
+    /// Index of the operation in the block.
+    pub block_index: usize,
+    /// Type of the operation.
+    pub op_type: String,
+    /// Public data of the operation committed to the contract.
+    pub committed_public_data: String,
+    /// Public data of the restored operation.
+    pub restored_public_data: String,
+    /// Updates of the accounts affected by the operation within the block.
+    pub account_updates: AccountUpdates,
+}
+
+/// Restored block whose root hash doesn't match the committed one.
+#[derive(Debug, Clone, Serialize)]
+pub struct RootHashMismatch {
+    pub block_number: BlockNumber,
+    pub committed_root_hash: H256,
+    pub restored_root_hash: H256,
+    /// The first operation of the block which diverged from the committed data.
+    /// Absent if all the operations match their public data, since the intermediate
+    /// state root hashes are not available on chain.
+    pub divergent_op: Option<DivergentOperation>,
+}
+
+impl fmt::Display for RootHashMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Root hash mismatch in block {}: committed {:?}, restored {:?}",
+            *self.block_number, self.committed_root_hash, self.restored_root_hash
+        )?;
+        match &self.divergent_op {
+            Some(op) => write!(
+                f,
+                ". Operation #{} ({}) diverged: committed public data {}, restored {}, account updates: {:?}",
+                op.block_index,
+                op.op_type,
+                op.committed_public_data,
+                op.restored_public_data,
+                op.account_updates
+            ),
+            None => write!(f, ". All the operations match their public data"),
+        }
+    }
+}
+
+/// Results of the restored blocks verification.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VerificationReport {
+    /// Number of the blocks that matched their committed root hashes.
+    pub verified_blocks: u32,
+    /// The last block that matched its committed root hash.
+    pub last_verified_block: Option<BlockNumber>,
+    /// Blocks applied without verification, since their committed root hash is unknown.
+    pub unverified_blocks: Vec<BlockNumber>,
+    /// The first mismatched block. The restoring process is stopped on it.
+    pub mismatch: Option<RootHashMismatch>,
+}
+
+impl VerificationReport {
+    /// Checks the restored block against the root hash committed for it and records the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `ops_block` - Rollup operations block obtained from the contract
+    /// * `block` - Block restored from the operations
+    /// * `account_updates` - Account updates caused by the block
+    ///
+    pub fn verify_block(
+        &mut self,
+        ops_block: &RollupOpsBlock,
+        block: &Block,
+        account_updates: &AccountUpdates,
+    ) -> Result<(), RootHashMismatch> {
+        let committed_root_hash = match ops_block.new_root_hash {
+            Some(root_hash) => root_hash,
+            None => {
+                self.unverified_blocks.push(block.block_number);
+                return Ok(());
+            }
+        };
+
+        let restored_root_hash = block.get_eth_encoded_root();
+        if restored_root_hash == committed_root_hash {
+            self.verified_blocks += 1;
+            self.last_verified_block = Some(block.block_number);
+            return Ok(());
+        }
+
+        let mismatch = RootHashMismatch {
+            block_number: block.block_number,
+            committed_root_hash,
+            restored_root_hash,
+            divergent_op: find_divergent_op(ops_block, block, account_updates),
+        };
+        self.mismatch = Some(mismatch.clone());
+        Err(mismatch)
+    }
+
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        serde_json::to_writer_pretty(BufWriter::new(File::create(path)?), self)?;
+        Ok(())
+    }
+}
+
+/// Returns the first operation whose public data doesn't match the committed one.
+/// Operations which don't depend on the state (e.g. transfers) always match, but
+/// priority operations may be executed differently, e.g. deposit to another account.
+fn find_divergent_op(
+    ops_block: &RollupOpsBlock,
+    block: &Block,
+    account_updates: &AccountUpdates,
+) -> Option<DivergentOperation> {
+    // Noop operations are not included into the restored block.
+    let committed_ops = ops_block
+        .ops
+        .iter()
+        .filter(|op| !matches!(op, ZkSyncOp::Noop(_)));
+
+    committed_ops
+        .zip(&block.block_transactions)
+        .enumerate()
+        .find_map(|(block_index, (committed_op, restored_op))| {
+            let committed_public_data = committed_op.public_data();
+            let restored_public_data = restored_op.get_eth_public_data();
+            if committed_public_data == restored_public_data {
+                return None;
+            }
+
+            let updated_accounts = restored_op.get_updated_account_ids();
+            Some(DivergentOperation {
+                block_index,
+                op_type: restored_op.variance_name(),
+                committed_public_data: format!("0x{}", hex::encode(committed_public_data)),
+                restored_public_data: format!("0x{}", hex::encode(restored_public_data)),
+                account_updates: account_updates
+                    .iter()
+                    .filter(|(account_id, _)| updated_accounts.contains(account_id))
+                    .cloned()
+                    .collect(),
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+    use zksync_types::{AccountId, Deposit, DepositOp, TokenId};
+
+    use super::*;
+    use crate::tree_state::TreeState;
+
+    fn deposit_block(account_id: AccountId, new_root_hash: Option<H256>) -> RollupOpsBlock {
+        let op = ZkSyncOp::Deposit(Box::new(DepositOp {
+            priority_op: Deposit {
+                from: [1u8; 20].into(),
+                token: TokenId(0),
+                amount: BigUint::from(100u32),
+                to: [7u8; 20].into(),
+            },
+            account_id,
+        }));
+        RollupOpsBlock {
+            block_num: BlockNumber(1),
+            ops: vec![op],
+            fee_account: AccountId(0),
+            timestamp: None,
+            previous_block_root_hash: Default::default(),
+            new_root_hash,
+            contract_version: None,
+        }
+    }
+
+    fn restore(ops_block: &RollupOpsBlock) -> (Block, AccountUpdates) {
+        TreeState::new()
+            .update_tree_states_from_ops_block(ops_block, &[10], &mut 0)
+            .expect("Cant update state from block")
+    }
+
+    #[test]
+    fn test_verify_block() {
+        let mut report = VerificationReport::default();
+
+        // The committed root hash is unknown.
+        let ops_block = deposit_block(AccountId(0), None);
+        let (block, updates) = restore(&ops_block);
+        report.verify_block(&ops_block, &block, &updates).unwrap();
+        assert_eq!(report.unverified_blocks, vec![BlockNumber(1)]);
+
+        // The committed root hash matches the restored one.
+        let ops_block = deposit_block(AccountId(0), Some(block.get_eth_encoded_root()));
+        let (block, updates) = restore(&ops_block);
+        report.verify_block(&ops_block, &block, &updates).unwrap();
+        assert_eq!(report.verified_blocks, 1);
+        assert_eq!(report.last_verified_block, Some(BlockNumber(1)));
+
+        // On chain the deposit went to another account.
+        let ops_block = deposit_block(AccountId(5), Some(H256::repeat_byte(1)));
+        let (block, updates) = restore(&ops_block);
+        let mismatch = report
+            .verify_block(&ops_block, &block, &updates)
+            .unwrap_err();
+        assert_eq!(mismatch.committed_root_hash, H256::repeat_byte(1));
+        assert_eq!(mismatch.restored_root_hash, block.get_eth_encoded_root());
+
+        let divergent_op = mismatch.divergent_op.expect("Deposit should diverge");
+        assert_eq!(divergent_op.block_index, 0);
+        assert_eq!(divergent_op.op_type, "Deposit");
+        assert!(!divergent_op.account_updates.is_empty());
+        assert!(divergent_op
+            .account_updates
+            .iter()
+            .all(|(account_id, _)| *account_id == AccountId(0)));
+        assert!(report.mismatch.is_some());
+    }
+}
@@ -0,0 +1,95 @@
+//! Control API of the devnet.
+//!
+//! Allows to initiate the operations which normally require an Ethereum transaction:
+//! deposits, full exits and token listings. The operations are added to the mock L1
+//! and are processed by the node in the same way as the real ones.
+
+use std::{net::SocketAddr, sync::Arc, thread};
+
+use actix_web::{error::InternalError, web, App, HttpResponse, HttpServer};
+use futures::{channel::mpsc, StreamExt};
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+use zksync_types::{Address, Deposit, FullExit};
+use zksync_utils::panic_notify::ThreadPanicNotify;
+
+use crate::l1::MockL1;
+
+#[derive(Debug, Deserialize)]
+struct AddTokenRequest {
+    address: Address,
+}
+
+fn internal_error(err: anyhow::Error) -> actix_web::Error {
+    InternalError::from_response(
+        err.to_string(),
+        HttpResponse::InternalServerError().body(err.to_string()),
+    )
+    .into()
+}
+
+/// Adds a deposit to the mock L1.
+/// Returns a JSON representation of `PriorityOpReceipt`.
+#[actix_web::post("/deposit")]
+async fn deposit(
+    l1: web::Data<Arc<MockL1>>,
+    web::Json(deposit): web::Json<Deposit>,
+) -> actix_web::Result<HttpResponse> {
+    let receipt = l1.deposit(deposit).await.map_err(internal_error)?;
+    Ok(HttpResponse::Ok().json(receipt))
+}
+
+/// Adds a full exit request to the mock L1.
+/// Returns a JSON representation of `PriorityOpReceipt`.
+#[actix_web::post("/full_exit")]
+async fn full_exit(
+    l1: web::Data<Arc<MockL1>>,
+    web::Json(full_exit): web::Json<FullExit>,
+) -> actix_web::Result<HttpResponse> {
+    let receipt = l1.full_exit(full_exit).await.map_err(internal_error)?;
+    Ok(HttpResponse::Ok().json(receipt))
+}
+
+/// Lists a new ERC20 token in the mock governance contract.
+/// Returns a JSON representation of `TokenReceipt`.
+#[actix_web::post("/token")]
+async fn add_token(
+    l1: web::Data<Arc<MockL1>>,
+    web::Json(request): web::Json<AddTokenRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let receipt = l1
+        .add_token(request.address)
+        .await
+        .map_err(internal_error)?;
+    Ok(HttpResponse::Ok().json(receipt))
+}
+
+pub fn start_control_api(l1: Arc<MockL1>, bind_addr: SocketAddr) -> JoinHandle<()> {
+    let (panic_sender, mut panic_receiver) = mpsc::channel(1);
+
+    thread::Builder::new()
+        .name("devnet-control-api".to_string())
+        .spawn(move || {
+            let _panic_sentinel = ThreadPanicNotify(panic_sender.clone());
+            let actix_runtime = actix_rt::System::new();
+
+            actix_runtime.block_on(async move {
+                HttpServer::new(move || {
+                    App::new()
+                        .wrap(actix_web::middleware::Logger::default())
+                        .app_data(web::Data::new(l1.clone()))
+                        .service(deposit)
+                        .service(full_exit)
+                        .service(add_token)
+                })
+                .bind(&bind_addr)
+                .expect("failed to bind")
+                .run()
+                .await
+            })
+        })
+        .expect("failed to start devnet control API");
+    tokio::spawn(async move {
+        panic_receiver.next().await.unwrap();
+    })
+}
@@ -0,0 +1,294 @@
+//! Mock layer 1 of the devnet.
+//!
+//! Operations which are normally initiated on Ethereum (deposits, full exits and token listings)
+//! are turned into the events of the zkSync and governance contracts, the same way the contracts
+//! emit them, and are added to the `MockEthereum`, where they're picked up by the Ethereum watcher.
+
+// Built-in deps
+use std::convert::TryFrom;
+// External deps
+use ethabi::{encode, Hash, Token};
+use num::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use web3::types::{Bytes, Log};
+// Workspace deps
+use zksync_contracts::{governance_contract, zksync_contract};
+use zksync_eth_client::clients::mock::MockEthereum;
+use zksync_types::{
+    AccountId, Address, Deposit, DepositOp, FullExit, FullExitOp, SerialId, TokenId, H256, U256,
+};
+
+/// Number of blocks after which the priority operation is expired.
+const PRIORITY_EXPIRATION: u64 = 40_320;
+
+/// Priority operation added to the mock L1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriorityOpReceipt {
+    pub serial_id: SerialId,
+    pub eth_hash: H256,
+    pub eth_block: u64,
+}
+
+/// Token listed on the mock L1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenReceipt {
+    pub id: TokenId,
+    pub address: Address,
+    pub eth_block: u64,
+}
+
+#[derive(Debug)]
+struct L1State {
+    next_serial_id: SerialId,
+    next_token_id: TokenId,
+    /// Used to generate unique transaction hashes.
+    tx_count: u64,
+}
+
+#[derive(Debug)]
+pub struct MockL1 {
+    ethereum: MockEthereum,
+    contract_addr: Address,
+    governance_addr: Address,
+    /// Blocks mined after each operation, so it's confirmed for the Ethereum watcher.
+    confirmations: u64,
+    new_priority_request_topic: Hash,
+    new_token_topic: Hash,
+    state: Mutex<L1State>,
+}
+
+impl MockL1 {
+    pub fn new(
+        ethereum: MockEthereum,
+        contract_addr: Address,
+        governance_addr: Address,
+        confirmations: u64,
+        next_token_id: TokenId,
+    ) -> Self {
+        Self {
+            ethereum,
+            contract_addr,
+            governance_addr,
+            confirmations,
+            new_priority_request_topic: zksync_contract()
+                .event("NewPriorityRequest")
+                .expect("main contract abi error")
+                .signature(),
+            new_token_topic: governance_contract()
+                .event("NewToken")
+                .expect("governance contract abi error")
+                .signature(),
+            state: Mutex::new(L1State {
+                next_serial_id: 0,
+                next_token_id,
+                tx_count: 0,
+            }),
+        }
+    }
+
+    pub async fn deposit(&self, deposit: Deposit) -> anyhow::Result<PriorityOpReceipt> {
+        let amount = deposit
+            .amount
+            .to_u128()
+            .ok_or_else(|| anyhow::format_err!("Deposit amount doesn't fit into 128 bits"))?;
+
+        let mut pubdata = vec![DepositOp::OP_CODE];
+        pubdata.extend_from_slice(&AccountId(0).to_be_bytes());
+        pubdata.extend_from_slice(&deposit.token.to_be_bytes());
+        pubdata.extend_from_slice(&amount.to_be_bytes());
+        pubdata.extend_from_slice(deposit.to.as_bytes());
+
+        self.add_priority_op(deposit.from, DepositOp::OP_CODE, pubdata)
+            .await
+    }
+
+    pub async fn full_exit(&self, full_exit: FullExit) -> anyhow::Result<PriorityOpReceipt> {
+        let mut pubdata = vec![FullExitOp::OP_CODE];
+        pubdata.extend_from_slice(&full_exit.account_id.to_be_bytes());
+        pubdata.extend_from_slice(full_exit.eth_address.as_bytes());
+        pubdata.extend_from_slice(&full_exit.token.to_be_bytes());
+        // The amount and the NFT fields are filled by the operator.
+        pubdata.resize(pubdata.len() + 16 + 4 + 20 + 4 + 32, 0);
+
+        self.add_priority_op(full_exit.eth_address, FullExitOp::OP_CODE, pubdata)
+            .await
+    }
+
+    /// Lists the ERC20 token in the governance contract.
+    pub async fn add_token(&self, address: Address) -> anyhow::Result<TokenReceipt> {
+        let mut state = self.state.lock().await;
+        let id = state.next_token_id;
+        let log = Log {
+            address: self.governance_addr,
+            topics: vec![
+                self.new_token_topic,
+                H256::from(address),
+                H256::from_low_u64_be(*id as u64),
+            ],
+            transaction_hash: Some(Self::next_tx_hash(&mut state)),
+            ..Self::empty_log()
+        };
+
+        self.ethereum.add_erc20_token(address).await;
+        let eth_block = self.add_log(log).await;
+        state.next_token_id = TokenId(*id + 1);
+
+        Ok(TokenReceipt {
+            id,
+            address,
+            eth_block,
+        })
+    }
+
+    async fn add_priority_op(
+        &self,
+        sender: Address,
+        op_type: u8,
+        pubdata: Vec<u8>,
+    ) -> anyhow::Result<PriorityOpReceipt> {
+        let mut state = self.state.lock().await;
+        let serial_id = state.next_serial_id;
+        let eth_hash = Self::next_tx_hash(&mut state);
+        let expiration_block = self.ethereum.block_number().await?.as_u64() + PRIORITY_EXPIRATION;
+
+        let data = encode(&[
+            Token::Address(sender),
+            Token::Uint(U256::from(serial_id)),
+            Token::Uint(U256::from(op_type)),
+            Token::Bytes(pubdata),
+            Token::Uint(U256::from(expiration_block)),
+        ]);
+        let log = Log {
+            address: self.contract_addr,
+            topics: vec![self.new_priority_request_topic],
+            data: Bytes(data),
+            transaction_hash: Some(eth_hash),
+            ..Self::empty_log()
+        };
+        // Check that the operation is parsed the same way the Ethereum watcher does it.
+        zksync_types::PriorityOp::try_from(Log {
+            block_number: Some(0.into()),
+            ..log.clone()
+        })?;
+
+        let eth_block = self.add_log(log).await;
+        state.next_serial_id += 1;
+
+        Ok(PriorityOpReceipt {
+            serial_id,
+            eth_hash,
+            eth_block,
+        })
+    }
+
+    /// Adds the log in a new block and mines the blocks required to confirm it.
+    async fn add_log(&self, log: Log) -> u64 {
+        let eth_block = self.ethereum.add_logs(vec![log]).await;
+        self.ethereum.mine_blocks(self.confirmations);
+        eth_block
+    }
+
+    fn next_tx_hash(state: &mut L1State) -> H256 {
+        state.tx_count += 1;
+        H256::from_low_u64_be(state.tx_count)
+    }
+
+    fn empty_log() -> Log {
+        Log {
+            address: Address::zero(),
+            topics: Vec::new(),
+            data: Bytes(Vec::new()),
+            block_hash: None,
+            block_number: None,
+            transaction_hash: None,
+            transaction_index: Some(0.into()),
+            log_index: None,
+            transaction_log_index: None,
+            log_type: None,
+            removed: Some(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+    use zksync_types::{PriorityOp, ZkSyncPriorityOp};
+
+    use super::*;
+
+    fn mock_l1() -> (MockEthereum, MockL1) {
+        let ethereum = MockEthereum::default();
+        let l1 = MockL1::new(
+            ethereum.clone(),
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            3,
+            TokenId(5),
+        );
+        (ethereum, l1)
+    }
+
+    #[tokio::test]
+    async fn priority_ops_are_added_as_events() {
+        let (ethereum, l1) = mock_l1();
+        let deposit = Deposit {
+            from: Address::repeat_byte(3),
+            token: TokenId(1),
+            amount: BigUint::from(1000u32),
+            to: Address::repeat_byte(4),
+        };
+        let receipt = l1.deposit(deposit.clone()).await.unwrap();
+        assert_eq!(receipt.serial_id, 0);
+        // The block with the event is followed by the confirmation blocks.
+        assert_eq!(
+            ethereum.block_number().await.unwrap().as_u64(),
+            receipt.eth_block + 3
+        );
+
+        let full_exit = FullExit {
+            account_id: AccountId(2),
+            eth_address: Address::repeat_byte(4),
+            token: TokenId(1),
+            is_legacy: false,
+        };
+        assert_eq!(l1.full_exit(full_exit.clone()).await.unwrap().serial_id, 1);
+
+        let filter = web3::types::FilterBuilder::default()
+            .address(vec![Address::repeat_byte(1)])
+            .from_block(web3::types::BlockNumber::Earliest)
+            .to_block(web3::types::BlockNumber::Latest)
+            .build();
+        let ops: Vec<_> = ethereum
+            .logs(filter)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|log| PriorityOp::try_from(log).unwrap())
+            .collect();
+        assert_eq!(ops.len(), 2);
+        assert_eq!(ops[0].eth_hash, receipt.eth_hash);
+        match (&ops[0].data, &ops[1].data) {
+            (
+                ZkSyncPriorityOp::Deposit(parsed_deposit),
+                ZkSyncPriorityOp::FullExit(parsed_exit),
+            ) => {
+                assert_eq!(parsed_deposit.to, deposit.to);
+                assert_eq!(parsed_deposit.amount, deposit.amount);
+                assert_eq!(parsed_exit.account_id, full_exit.account_id);
+                assert_eq!(parsed_exit.token, full_exit.token);
+            }
+            ops => panic!("Unexpected operations: {:?}", ops),
+        }
+    }
+
+    #[tokio::test]
+    async fn tokens_are_listed() {
+        let (_, l1) = mock_l1();
+        let first = l1.add_token(Address::repeat_byte(7)).await.unwrap();
+        let second = l1.add_token(Address::repeat_byte(8)).await.unwrap();
+        assert_eq!(first.id, TokenId(5));
+        assert_eq!(second.id, TokenId(6));
+    }
+}
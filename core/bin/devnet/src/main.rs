@@ -0,0 +1,250 @@
+//! zkSync devnet: the whole zkSync stack running in a single process against the mock L1.
+//!
+//! The devnet runs the core, the API, the Ethereum sender, the witness generator and the dummy
+//! prover. Ethereum is replaced with `MockEthereum`, which executes every sent transaction right
+//! away, and the L1 operations are injected through the control API.
+//! The mock L1 state is kept in memory, so the devnet always starts from the empty database.
+//! The devnet uses its own database (`DATABASE_URL` with the `_devnet` suffix by default), so
+//! the database of the regular server is never touched.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use anyhow::ensure;
+use futures::{channel::mpsc, StreamExt};
+use structopt::StructOpt;
+use tokio::task::JoinHandle;
+
+use zksync_api::{
+    fee_ticker::{run_updaters, FeeTicker, TickerInfo},
+    runtime_parameters::{run_runtime_parameters_updater, RuntimeParametersStore},
+};
+use zksync_config::ZkSyncConfig;
+use zksync_core::{
+    genesis_init, rejected_tx_cleaner::run_rejected_tx_cleaner, run_core, wait_for_tasks,
+};
+use zksync_eth_client::{clients::mock::MockEthereum, EthereumGateway};
+use zksync_prover::{
+    client::ApiClient,
+    dummy_prover::{DummyProver, DummyProverConfig},
+    prover_work_cycle, ProverConfig, ProverImpl, ShutdownRequest,
+};
+use zksync_storage::{get_database_url, ConnectionPool};
+use zksync_types::{tx::Eip712Domain, Address, TokenId};
+use zksync_witness_generator::{database::Database, run_prover_server};
+
+use crate::{control_api::start_control_api, l1::MockL1};
+
+mod control_api;
+mod l1;
+
+const DUMMY_PROVER_NAME: &str = "devnet-dummy-prover";
+/// Suffix of the default devnet database name.
+const DEVNET_DATABASE_SUFFIX: &str = "_devnet";
+
+#[derive(StructOpt)]
+#[structopt(
+    name = "zkSync devnet",
+    author = "Matter Labs",
+    rename_all = "snake_case"
+)]
+struct Opt {
+    /// Port of the control API used to inject the L1 operations
+    #[structopt(long, default_value = "3040")]
+    control_port: u16,
+    /// URL of the devnet database. `DATABASE_URL` with the `_devnet` suffix is used by default
+    #[structopt(long)]
+    database_url: Option<String>,
+}
+
+/// Points every component of the devnet to the dedicated database.
+/// All the storage users read `DATABASE_URL`, so it's overridden for the whole process.
+fn use_devnet_database(database_url: Option<String>) -> anyhow::Result<()> {
+    let server_database_url = get_database_url();
+    let database_url = database_url
+        .unwrap_or_else(|| format!("{}{}", server_database_url, DEVNET_DATABASE_SUFFIX));
+    ensure!(
+        database_url != server_database_url,
+        "The devnet can't use the database of the server"
+    );
+
+    std::env::set_var("DATABASE_URL", database_url);
+    Ok(())
+}
+
+/// Stores the server configuration and creates the genesis block. The database is required
+/// to be empty, since the operations processed before can't be restored from the mock L1.
+async fn init_storage(pool: &ConnectionPool, config: &ZkSyncConfig) -> anyhow::Result<TokenId> {
+    let mut storage = pool.access_storage().await?;
+    let (last_committed, accounts) = storage
+        .chain()
+        .state_schema()
+        .load_committed_state(None)
+        .await?;
+    ensure!(
+        *last_committed == 0 && accounts.is_empty(),
+        "The devnet requires an empty database, since the mock L1 state is not persisted"
+    );
+
+    // There is no Ethereum node to fetch the operator nonce from and no NFT factory
+    // deployed, so the mock L1 starts with the default Ethereum parameters.
+    storage
+        .config_schema()
+        .store_config(
+            config.contracts.contract_addr,
+            config.contracts.governance_addr,
+            Address::zero(),
+        )
+        .await?;
+    storage.ethereum_schema().initialize_eth_data().await?;
+    genesis_init(&config.chain).await;
+
+    let max_token_id = storage.tokens_schema().get_max_token_id().await?;
+    Ok(TokenId(max_token_id + 1))
+}
+
+fn run_api(
+    connection_pool: ConnectionPool,
+    config: &ZkSyncConfig,
+    eth_gateway: EthereumGateway,
+) -> Vec<JoinHandle<()>> {
+    let mut tasks = run_updaters(connection_pool.clone(), &config.ticker);
+
+    let (sign_check_sender, sign_check_receiver) = mpsc::channel(32768);
+    let eip712_domain =
+        Eip712Domain::new(config.eth_client.chain_id, config.contracts.contract_addr);
+    tasks.push(zksync_api::signature_checker::start_sign_checker(
+        eth_gateway,
+        eip712_domain,
+        sign_check_receiver,
+    ));
+
+    let runtime_parameters = RuntimeParametersStore::new();
+    tasks.push(run_runtime_parameters_updater(
+        connection_pool.clone(),
+        runtime_parameters.clone(),
+    ));
+    let ticker = FeeTicker::new_with_default_validator(
+        Box::new(TickerInfo::new(connection_pool.clone())),
+        config.ticker.clone(),
+        config.chain.max_blocks_to_aggregate(),
+        connection_pool.clone(),
+        runtime_parameters,
+    );
+
+    tasks.push(zksync_api::api_server::rpc_server::start_rpc_server(
+        connection_pool.clone(),
+        sign_check_sender.clone(),
+        ticker.clone(),
+        &config.api.json_rpc,
+        &config.api.common,
+        config.api.private.url.clone(),
+        config.eth_watch.confirmations_for_eth_event,
+    ));
+    tasks.push(zksync_api::api_server::rest::start_server_thread_detached(
+        connection_pool.clone(),
+        config.api.rest.bind_addr(),
+        config.contracts.contract_addr,
+        ticker,
+        sign_check_sender,
+    ));
+    tasks.push(zksync_api::api_server::web3::start_rpc_server(
+        connection_pool,
+        &config.api.web3,
+    ));
+
+    tasks
+}
+
+fn run_dummy_prover(config: &ZkSyncConfig) -> JoinHandle<()> {
+    let prover = DummyProver::create_from_config(DummyProverConfig::from_env());
+    let client = ApiClient::new(
+        &config
+            .api
+            .prover
+            .url
+            .parse()
+            .expect("Prover API URL is invalid"),
+        config.prover.prover.request_timeout(),
+        &config.api.prover.secret_auth,
+    );
+    let prover_options = config.prover.clone();
+
+    tokio::spawn(async move {
+        prover_work_cycle(
+            prover,
+            client,
+            ShutdownRequest::new(),
+            prover_options,
+            DUMMY_PROVER_NAME,
+        )
+        .await;
+    })
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+    let _sentry_guard = vlog::init();
+    let config = ZkSyncConfig::from_env();
+
+    use_devnet_database(opt.database_url)?;
+    let connection_pool = ConnectionPool::new(None);
+    let next_token_id = init_storage(&connection_pool, &config).await?;
+
+    let ethereum = MockEthereum::with_auto_execution(config.eth_sender.sender.wait_confirmations);
+    let eth_gateway = EthereumGateway::Mock(ethereum.clone());
+    let l1 = Arc::new(MockL1::new(
+        ethereum,
+        config.contracts.contract_addr,
+        config.contracts.governance_addr,
+        config.eth_watch.confirmations_for_eth_event,
+        next_token_id,
+    ));
+
+    let mut tasks = run_core(connection_pool.clone(), &config, eth_gateway.clone()).await?;
+    tasks.append(&mut run_api(
+        connection_pool.clone(),
+        &config,
+        eth_gateway.clone(),
+    ));
+    tasks.push(zksync_eth_sender::run_eth_sender(
+        connection_pool.clone(),
+        eth_gateway,
+        config.eth_sender.clone(),
+    ));
+    tasks.push(run_prover_server(
+        Database::new(connection_pool.clone()),
+        config.api.prover.clone(),
+        config.prover.clone(),
+        config.chain.state_keeper.block_prove_deadline(),
+    ));
+    tasks.push(run_dummy_prover(&config));
+    tasks.push(run_rejected_tx_cleaner(&config.db, connection_pool));
+
+    let control_api_addr = SocketAddr::new("0.0.0.0".parse().unwrap(), opt.control_port);
+    tasks.push(start_control_api(l1, control_api_addr));
+    vlog::info!(
+        "zkSync devnet is started, control API is listening on {}",
+        control_api_addr
+    );
+
+    let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(1);
+    ctrlc::set_handler(move || {
+        stop_signal_sender
+            .clone()
+            .try_send(true)
+            .expect("Ctrl+C signal send");
+    })
+    .expect("Error setting Ctrl+C handler");
+
+    tokio::select! {
+        _ = wait_for_tasks(tasks) => {
+            panic!("One of the actors is not supposed to finish its execution")
+        },
+        _ = stop_signal_receiver.next() => {
+            vlog::warn!("Stop signal received, shutting down");
+        }
+    };
+
+    Ok(())
+}
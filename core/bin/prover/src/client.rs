@@ -10,7 +10,8 @@ use vlog::*;
 // Workspace deps
 use crate::auth_utils::AuthTokenGenerator;
 use zksync_prover_utils::api::{
-    ProverInputRequest, ProverInputResponse, ProverOutputRequest, ProverStopped, WorkingOn,
+    ProverInputRequest, ProverInputResponse, ProverJobFailed, ProverOutputRequest, ProverStopped,
+    WorkingOn,
 };
 
 #[derive(Debug, Clone)]
@@ -18,6 +19,7 @@ pub struct ApiClient {
     get_job_url: Url,
     working_on_url: Url,
     publish_url: Url,
+    job_failed_url: Url,
     stopped_url: Url,
     // Client keeps connection pool inside, so it is recommended to reuse it (see docstring for reqwest::Client).
     http_client: reqwest::Client,
@@ -40,6 +42,7 @@ impl ApiClient {
             get_job_url: base_url.join("/get_job").unwrap(),
             working_on_url: base_url.join("/working_on").unwrap(),
             publish_url: base_url.join("/publish").unwrap(),
+            job_failed_url: base_url.join("/job_failed").unwrap(),
             stopped_url: base_url.join("/stopped").unwrap(),
             http_client,
             auth_token_generator,
@@ -142,6 +145,9 @@ impl crate::ApiClient for ApiClient {
             if response.status() == reqwest::StatusCode::UNAUTHORIZED {
                 return Err(Permanent(format_err!("authorization error")));
             }
+            if response.status() == reqwest::StatusCode::CONFLICT {
+                return Err(Permanent(format_err!("job lease is lost")));
+            }
 
             Ok(())
         };
@@ -172,6 +178,39 @@ impl crate::ApiClient for ApiClient {
         self.with_retries(operation).await
     }
 
+    async fn job_failed(
+        &self,
+        job_id: i32,
+        prover_name: &str,
+        reason: String,
+    ) -> anyhow::Result<()> {
+        let operation = || async {
+            let response = self
+                .http_client
+                .post(self.job_failed_url.clone())
+                .bearer_auth(&self.get_encoded_token()?)
+                .json(&ProverJobFailed {
+                    prover_name: prover_name.to_string(),
+                    job_id,
+                    reason: reason.clone(),
+                })
+                .send()
+                .await
+                .map_err(|e| Transient(format_err!("failed to send job_failed request: {}", e)))?;
+
+            if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+                return Err(Permanent(format_err!("authorization error")));
+            }
+            if response.status() == reqwest::StatusCode::CONFLICT {
+                return Err(Permanent(format_err!("job lease is lost")));
+            }
+
+            Ok(())
+        };
+
+        self.with_retries(operation).await
+    }
+
     async fn prover_stopped(&self, prover_name: String) -> anyhow::Result<()> {
         let operation = || async {
             let response = self
@@ -91,22 +91,31 @@ pub trait ApiClient: Debug {
     async fn get_job(&self, req: ProverInputRequest) -> anyhow::Result<ProverInputResponse>;
     async fn working_on(&self, job_id: i32, prover_name: &str) -> anyhow::Result<()>;
     async fn publish(&self, data: ProverOutputRequest) -> anyhow::Result<()>;
+    /// Reports that the job can't be proven, so it can be retried (possibly by another prover).
+    async fn job_failed(
+        &self,
+        job_id: i32,
+        prover_name: &str,
+        reason: String,
+    ) -> anyhow::Result<()>;
     async fn prover_stopped(&self, prover_name: String) -> anyhow::Result<()>;
 }
 
+/// Returns the prover back along with the result of the computation, so it can be reused
+/// even if the proof can't be created. Fails only if the prover thread has panicked.
 async fn compute_proof_no_blocking<PROVER>(
     prover: PROVER,
     data: JobRequestData,
-) -> anyhow::Result<(PROVER, JobResultData)>
+) -> anyhow::Result<(PROVER, anyhow::Result<JobResultData>)>
 where
     PROVER: ProverImpl + Send + Sync + 'static,
 {
     let (result_sender, result_receiver) = oneshot::channel();
     std::thread::spawn(move || {
-        let prover_with_proof = prover.create_proof(data).map(|proof| (prover, proof));
-        result_sender.send(prover_with_proof).unwrap_or_default();
+        let proof = prover.create_proof(data);
+        result_sender.send((prover, proof)).unwrap_or_default();
     });
-    result_receiver.await?
+    Ok(result_receiver.await?)
 }
 
 /// Endlessly sends requests to the server, in case of not receiving a response
@@ -138,6 +147,17 @@ async fn heartbeat_future_handle<CLIENT>(
     }
 }
 
+async fn report_job_failure<CLIENT>(client: &CLIENT, job_id: i32, prover_name: &str, reason: String)
+where
+    CLIENT: ApiClient,
+{
+    client
+        .job_failed(job_id, prover_name, reason)
+        .await
+        .map_err(|e| vlog::warn!("Failed to report job failure: {}", e))
+        .unwrap_or_default();
+}
+
 pub async fn prover_work_cycle<PROVER, CLIENT>(
     mut prover: PROVER,
     client: CLIENT,
@@ -208,13 +228,27 @@ pub async fn prover_work_cycle<PROVER, CLIENT>(
             last_block
         );
 
-        let (ret_prover, proof) = futures::select! {
-            comp_proof = compute_proof_future => {
-                comp_proof.expect("Failed to compute proof")
-            },
+        let comp_proof = futures::select! {
+            comp_proof = compute_proof_future => comp_proof,
             _ = heartbeat_future_handle => unreachable!(),
         };
-        prover = ret_prover;
+        let proof = match comp_proof {
+            Ok((ret_prover, Ok(proof))) => {
+                prover = ret_prover;
+                proof
+            }
+            Ok((ret_prover, Err(e))) => {
+                prover = ret_prover;
+                vlog::error!("Failed to compute proof for job {}: {}", job_id, e);
+                report_job_failure(&client, job_id, prover_name, e.to_string()).await;
+                continue;
+            }
+            Err(e) => {
+                report_job_failure(&client, job_id, prover_name, "prover panicked".to_string())
+                    .await;
+                panic!("Failed to compute proof: {}", e);
+            }
+        };
 
         client
             .publish(ProverOutputRequest {
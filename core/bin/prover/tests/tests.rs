@@ -61,6 +61,7 @@ impl Default for MockProverConfigs {
             core: zksync_config::configs::prover::Core {
                 gone_timeout: 2,
                 idle_provers: 1,
+                max_job_retries: 3,
             },
             witness_generator: zksync_config::configs::prover::WitnessGenerator {
                 prepare_data_interval: 5000,
@@ -296,6 +297,10 @@ impl zksync_prover::ApiClient for MockApiClient {
         Ok(())
     }
 
+    async fn job_failed(&self, _: i32, _: &str, _: String) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     async fn prover_stopped(&self, _: String) -> anyhow::Result<()> {
         Ok(())
     }
@@ -0,0 +1,178 @@
+//! Append-only audit log of the signing requests.
+//!
+//! Every request is written as a single JSON line, whether it was signed or rejected.
+//! The log also serves as the persistent state of the daemon: the greatest signed nonce
+//! is restored from it on start.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    sync::Mutex,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_eth_signer::RawTransaction;
+use zksync_types::{Address, H256, U256};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    Signed,
+    Rejected,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub timestamp: DateTime<Utc>,
+    pub decision: Decision,
+    /// Called method of the zkSync contract, if it's one of the allowed.
+    pub method: Option<String>,
+    /// Reason of the rejection.
+    pub reason: Option<String>,
+    /// Hash of the signed transaction.
+    pub tx_hash: Option<H256>,
+    pub chain_id: u64,
+    pub to: Option<Address>,
+    pub nonce: U256,
+    pub gas_price: U256,
+    pub gas: U256,
+    pub value: U256,
+}
+
+impl AuditRecord {
+    pub fn signed(tx: &RawTransaction, method: &str, tx_hash: H256) -> Self {
+        Self {
+            decision: Decision::Signed,
+            method: Some(method.to_owned()),
+            tx_hash: Some(tx_hash),
+            ..Self::new(tx)
+        }
+    }
+
+    pub fn rejected(tx: &RawTransaction, reason: String) -> Self {
+        Self {
+            decision: Decision::Rejected,
+            reason: Some(reason),
+            ..Self::new(tx)
+        }
+    }
+
+    fn new(tx: &RawTransaction) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            decision: Decision::Rejected,
+            method: None,
+            reason: None,
+            tx_hash: None,
+            chain_id: tx.chain_id,
+            to: tx.to,
+            nonce: tx.nonce,
+            gas_price: tx.gas_price,
+            gas: tx.gas,
+            value: tx.value,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AuditLog {
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    /// Returns the greatest nonce of the transactions signed according to the log at the given path.
+    /// `None` is returned if the log doesn't exist or has no signed transactions.
+    pub fn highest_signed_nonce(path: &Path) -> anyhow::Result<Option<U256>> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => anyhow::bail!("Unable to open {}: {}", path.display(), err),
+        };
+
+        let mut highest_signed_nonce = None;
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let record: AuditRecord = serde_json::from_str(&line?).map_err(|err| {
+                anyhow::format_err!(
+                    "Malformed record at line {} of {}: {}",
+                    index + 1,
+                    path.display(),
+                    err
+                )
+            })?;
+            if record.decision == Decision::Signed {
+                highest_signed_nonce = Some(
+                    highest_signed_nonce
+                        .map_or(record.nonce, |nonce: U256| nonce.max(record.nonce)),
+                );
+            }
+        }
+        Ok(highest_signed_nonce)
+    }
+
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|err| anyhow::format_err!("Unable to open {}: {}", path.display(), err))?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Appends the record to the log. The transaction must not be signed if the record can't be written.
+    pub fn write(&self, record: &AuditRecord) -> anyhow::Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        let mut file = self.file.lock().unwrap();
+        file.write_all(line.as_bytes())?;
+        file.sync_data()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(nonce: u64) -> RawTransaction {
+        RawTransaction {
+            chain_id: 9,
+            nonce: nonce.into(),
+            to: Some(Address::repeat_byte(1)),
+            value: U256::zero(),
+            gas_price: 100.into(),
+            gas: 1000.into(),
+            data: vec![],
+        }
+    }
+
+    #[test]
+    fn highest_signed_nonce() {
+        let path =
+            std::env::temp_dir().join(format!("remote_signer_audit_{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(AuditLog::highest_signed_nonce(&path).unwrap(), None);
+
+        let log = AuditLog::open(&path).unwrap();
+        log.write(&AuditRecord::rejected(&tx(9), "nonce is too high".into()))
+            .unwrap();
+        assert_eq!(AuditLog::highest_signed_nonce(&path).unwrap(), None);
+
+        log.write(&AuditRecord::signed(&tx(5), "commitBlocks", H256::zero()))
+            .unwrap();
+        log.write(&AuditRecord::signed(&tx(3), "proveBlocks", H256::zero()))
+            .unwrap();
+        log.write(&AuditRecord::rejected(&tx(20), "nonce is too high".into()))
+            .unwrap();
+        assert_eq!(
+            AuditLog::highest_signed_nonce(&path).unwrap(),
+            Some(5.into())
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,82 @@
+//! Signing daemon holding the operator key.
+//!
+//! The server is configured to use it via `ETH_SENDER_SENDER_OPERATOR_REMOTE_SIGNER_URL`, so the key
+//! itself is only available to the daemon. Each transaction is checked against the `SigningPolicy`
+//! and written to the audit log before the signature is returned.
+//!
+//! Requests are authenticated with the bearer token read from `REMOTE_SIGNER_AUTH_TOKEN_PATH`,
+//! the server reads the same token from `ETH_SENDER_SENDER_OPERATOR_REMOTE_SIGNER_TOKEN_PATH`.
+
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig, RemoteSignerConfig};
+use zksync_contracts::zksync_contract;
+use zksync_eth_client::ethereum_gateway::{local_operator_signer, read_secret};
+use zksync_eth_signer::EthereumSigner;
+
+use crate::{
+    audit::AuditLog,
+    policy::SigningPolicy,
+    server::{AuthToken, SignerState},
+};
+
+mod audit;
+mod policy;
+mod server;
+
+#[actix_web::main]
+async fn main() -> anyhow::Result<()> {
+    let _sentry_guard = vlog::init();
+
+    let config = RemoteSignerConfig::from_env();
+    let contracts = ContractsConfig::from_env();
+    let eth_client_config = ETHClientConfig::from_env();
+    let eth_sender_config = ETHSenderConfig::from_env();
+
+    let signer = local_operator_signer(&eth_sender_config.sender)?;
+    let address = signer.get_address().await?;
+    anyhow::ensure!(
+        address == eth_sender_config.sender.operator_commit_eth_addr,
+        "Operator key belongs to {:?}, while the operator address is {:?}",
+        address,
+        eth_sender_config.sender.operator_commit_eth_addr
+    );
+
+    let auth_token = read_secret(&config.auth_token_path)?;
+    anyhow::ensure!(
+        !auth_token.is_empty(),
+        "Auth token in {} is empty",
+        config.auth_token_path.display()
+    );
+    let highest_signed_nonce = AuditLog::highest_signed_nonce(&config.audit_log_path)?;
+    if let Some(nonce) = highest_signed_nonce {
+        vlog::info!(
+            "Greatest nonce signed according to the audit log: {}",
+            nonce
+        );
+    }
+
+    let policy = SigningPolicy::new(
+        &config,
+        eth_client_config.chain_id,
+        contracts.contract_addr,
+        &zksync_contract(),
+    );
+    let state = Arc::new(SignerState {
+        signer,
+        address,
+        policy,
+        audit_log: AuditLog::open(&config.audit_log_path)?,
+        auth_token: AuthToken(auth_token),
+        highest_signed_nonce: Mutex::new(highest_signed_nonce),
+    });
+
+    vlog::info!(
+        "Starting remote signer for {:?} on {}",
+        address,
+        config.bind_addr()
+    );
+    server::run_server(state, config.bind_addr()).await?;
+    Ok(())
+}
@@ -0,0 +1,263 @@
+//! Policy the transactions must satisfy in order to be signed.
+//!
+//! The operator only needs to commit, prove and execute blocks, so anything else (transfers of ETH,
+//! calls to other contracts or other methods of the zkSync contract) is a sign of a compromised server.
+
+use thiserror::Error;
+use zksync_config::RemoteSignerConfig;
+use zksync_eth_signer::RawTransaction;
+use zksync_types::{Address, U256};
+
+/// Methods of the zkSync contract the operator is allowed to call.
+pub const ALLOWED_METHODS: &[&str] = &["commitBlocks", "proveBlocks", "executeBlocks"];
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PolicyViolation {
+    #[error("wrong chain id: {0}")]
+    WrongChainId(u64),
+    #[error("recipient is not the zkSync contract: {0:?}")]
+    WrongRecipient(Option<Address>),
+    #[error("transaction transfers ETH: {0}")]
+    NonZeroValue(U256),
+    #[error("method is not allowed: 0x{0}")]
+    MethodNotAllowed(String),
+    #[error("gas price {0} exceeds the limit {1}")]
+    GasPriceTooHigh(U256, U256),
+    #[error("gas limit {0} exceeds the limit {1}")]
+    GasLimitTooHigh(U256, U256),
+    #[error("nonce {0} exceeds the limit {1}")]
+    NonceTooHigh(U256, U256),
+}
+
+#[derive(Debug, Clone)]
+pub struct SigningPolicy {
+    chain_id: u64,
+    contract_addr: Address,
+    /// Selectors of the allowed methods along with their names.
+    allowed_methods: Vec<([u8; 4], &'static str)>,
+    max_gas_price: U256,
+    max_gas_limit: U256,
+    max_nonce: Option<U256>,
+    max_nonce_gap: U256,
+}
+
+impl SigningPolicy {
+    pub fn new(
+        config: &RemoteSignerConfig,
+        chain_id: u64,
+        contract_addr: Address,
+        contract: &ethabi::Contract,
+    ) -> Self {
+        let allowed_methods = ALLOWED_METHODS
+            .iter()
+            .map(|name| {
+                let function = contract
+                    .function(name)
+                    .unwrap_or_else(|_| panic!("zkSync contract has no `{}` method", name));
+                (function.short_signature(), *name)
+            })
+            .collect();
+
+        Self {
+            chain_id,
+            contract_addr,
+            allowed_methods,
+            max_gas_price: config.max_gas_price.into(),
+            max_gas_limit: config.max_gas_limit.into(),
+            max_nonce: config.max_nonce.map(U256::from),
+            max_nonce_gap: config.max_nonce_gap.into(),
+        }
+    }
+
+    /// Checks the transaction against the policy.
+    /// `highest_signed_nonce` is the greatest nonce signed so far according to the audit log, if any.
+    /// Returns the name of the called method.
+    pub fn check(
+        &self,
+        tx: &RawTransaction,
+        highest_signed_nonce: Option<U256>,
+    ) -> Result<&'static str, PolicyViolation> {
+        if tx.chain_id != self.chain_id {
+            return Err(PolicyViolation::WrongChainId(tx.chain_id));
+        }
+        if tx.to != Some(self.contract_addr) {
+            return Err(PolicyViolation::WrongRecipient(tx.to));
+        }
+        if !tx.value.is_zero() {
+            return Err(PolicyViolation::NonZeroValue(tx.value));
+        }
+
+        let method = self
+            .allowed_methods
+            .iter()
+            .find(|(selector, _)| tx.data.get(..4) == Some(&selector[..]))
+            .map(|(_, name)| *name)
+            .ok_or_else(|| {
+                let selector = tx.data.get(..4).unwrap_or(&tx.data);
+                PolicyViolation::MethodNotAllowed(hex::encode(selector))
+            })?;
+
+        if tx.gas_price > self.max_gas_price {
+            return Err(PolicyViolation::GasPriceTooHigh(
+                tx.gas_price,
+                self.max_gas_price,
+            ));
+        }
+        if tx.gas > self.max_gas_limit {
+            return Err(PolicyViolation::GasLimitTooHigh(tx.gas, self.max_gas_limit));
+        }
+        if let Some(max_nonce) = self.max_nonce {
+            if tx.nonce > max_nonce {
+                return Err(PolicyViolation::NonceTooHigh(tx.nonce, max_nonce));
+            }
+        }
+        if let Some(highest_signed_nonce) = highest_signed_nonce {
+            let max_nonce = highest_signed_nonce.saturating_add(self.max_nonce_gap);
+            if tx.nonce > max_nonce {
+                return Err(PolicyViolation::NonceTooHigh(tx.nonce, max_nonce));
+            }
+        }
+
+        Ok(method)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHAIN_ID: u64 = 9;
+
+    /// Contract with the allowed methods and the one which must not be called by the operator.
+    fn contract() -> ethabi::Contract {
+        let functions: Vec<_> = ALLOWED_METHODS
+            .iter()
+            .chain(&["withdrawPendingBalance"])
+            .map(|name| {
+                serde_json::json!({
+                    "type": "function",
+                    "name": name,
+                    "inputs": [{ "name": "data", "type": "bytes" }],
+                    "outputs": [],
+                    "stateMutability": "nonpayable",
+                })
+            })
+            .collect();
+        ethabi::Contract::load(serde_json::to_vec(&functions).unwrap().as_slice()).unwrap()
+    }
+
+    fn policy() -> SigningPolicy {
+        let config = RemoteSignerConfig {
+            host: "127.0.0.1".parse().unwrap(),
+            port: 3031,
+            auth_token_path: "remote_signer.token".into(),
+            max_gas_price: 100,
+            max_gas_limit: 1000,
+            max_nonce: Some(50),
+            max_nonce_gap: 5,
+            audit_log_path: "audit.log".into(),
+        };
+        SigningPolicy::new(&config, CHAIN_ID, Address::repeat_byte(1), &contract())
+    }
+
+    fn tx(method: &str) -> RawTransaction {
+        RawTransaction {
+            chain_id: CHAIN_ID,
+            nonce: 10.into(),
+            to: Some(Address::repeat_byte(1)),
+            value: U256::zero(),
+            gas_price: 100.into(),
+            gas: 1000.into(),
+            data: contract()
+                .function(method)
+                .unwrap()
+                .encode_input(&[ethabi::Token::Bytes(vec![1, 2, 3])])
+                .unwrap(),
+        }
+    }
+
+    #[test]
+    fn allowed_methods() {
+        let policy = policy();
+        for method in ALLOWED_METHODS {
+            assert_eq!(policy.check(&tx(method), None), Ok(*method));
+        }
+    }
+
+    #[test]
+    fn violations() {
+        let policy = policy();
+
+        let mut wrong_chain = tx("commitBlocks");
+        wrong_chain.chain_id = 1;
+        assert_eq!(
+            policy.check(&wrong_chain, None),
+            Err(PolicyViolation::WrongChainId(1))
+        );
+
+        let mut wrong_recipient = tx("commitBlocks");
+        wrong_recipient.to = Some(Address::repeat_byte(2));
+        assert_eq!(
+            policy.check(&wrong_recipient, None),
+            Err(PolicyViolation::WrongRecipient(Some(Address::repeat_byte(
+                2
+            ))))
+        );
+
+        let mut transfer = tx("commitBlocks");
+        transfer.value = 1.into();
+        assert_eq!(
+            policy.check(&transfer, None),
+            Err(PolicyViolation::NonZeroValue(1.into()))
+        );
+
+        assert!(matches!(
+            policy.check(&tx("withdrawPendingBalance"), None),
+            Err(PolicyViolation::MethodNotAllowed(_))
+        ));
+
+        let mut no_data = tx("commitBlocks");
+        no_data.data = vec![];
+        assert_eq!(
+            policy.check(&no_data, None),
+            Err(PolicyViolation::MethodNotAllowed(String::new()))
+        );
+
+        let mut expensive = tx("proveBlocks");
+        expensive.gas_price = 101.into();
+        assert_eq!(
+            policy.check(&expensive, None),
+            Err(PolicyViolation::GasPriceTooHigh(101.into(), 100.into()))
+        );
+
+        let mut heavy = tx("proveBlocks");
+        heavy.gas = 1001.into();
+        assert_eq!(
+            policy.check(&heavy, None),
+            Err(PolicyViolation::GasLimitTooHigh(1001.into(), 1000.into()))
+        );
+    }
+
+    #[test]
+    fn nonce_ceilings() {
+        let policy = policy();
+
+        let mut far_nonce = tx("executeBlocks");
+        far_nonce.nonce = 51.into();
+        assert_eq!(
+            policy.check(&far_nonce, None),
+            Err(PolicyViolation::NonceTooHigh(51.into(), 50.into()))
+        );
+
+        // Nonce gap is only checked once some transaction was signed.
+        let next = tx("executeBlocks");
+        assert!(policy.check(&next, None).is_ok());
+        assert!(policy.check(&next, Some(5.into())).is_ok());
+        assert_eq!(
+            policy.check(&next, Some(4.into())),
+            Err(PolicyViolation::NonceTooHigh(10.into(), 9.into()))
+        );
+        // Resending the transaction with the same nonce is allowed.
+        assert!(policy.check(&next, Some(20.into())).is_ok());
+    }
+}
@@ -0,0 +1,156 @@
+//! HTTP API of the signing daemon. The messages are defined in `zksync_eth_signer::remote_signer`.
+
+use std::{net::SocketAddr, sync::Arc};
+
+use actix_web::{http::header, web, App, HttpRequest, HttpResponse, HttpServer};
+use parity_crypto::Keccak256;
+use tokio::sync::Mutex;
+use zksync_eth_signer::{
+    remote_signer::messages::{
+        AddressResponse, RejectionResponse, SignTransactionRequest, SignTransactionResponse,
+    },
+    EthereumSigner, PrivateKeySigner,
+};
+use zksync_types::{Address, H256, U256};
+
+use crate::{
+    audit::{AuditLog, AuditRecord},
+    policy::SigningPolicy,
+};
+
+/// Token every request must carry in the `Authorization: Bearer` header.
+pub struct AuthToken(pub String);
+
+impl std::fmt::Debug for AuthToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AuthToken")
+    }
+}
+
+impl AuthToken {
+    /// Checks the `Authorization` header of the request.
+    /// The comparison time doesn't depend on the position of the first mismatching byte.
+    fn is_authorized(&self, req: &HttpRequest) -> bool {
+        let provided = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        match provided {
+            Some(provided) if provided.len() == self.0.len() => {
+                provided
+                    .bytes()
+                    .zip(self.0.bytes())
+                    .fold(0, |diff, (a, b)| diff | (a ^ b))
+                    == 0
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SignerState {
+    pub signer: PrivateKeySigner,
+    pub address: Address,
+    pub policy: SigningPolicy,
+    pub audit_log: AuditLog,
+    pub auth_token: AuthToken,
+    /// Greatest signed nonce, restored from the audit log on start.
+    /// The lock is held during the whole request, so the requests are processed one by one.
+    pub highest_signed_nonce: Mutex<Option<U256>>,
+}
+
+fn internal_error(err: impl ToString) -> HttpResponse {
+    vlog::error!("Unable to process the signing request: {}", err.to_string());
+    HttpResponse::InternalServerError().body(err.to_string())
+}
+
+fn unauthorized(req: &HttpRequest) -> HttpResponse {
+    vlog::warn!(
+        "Rejected unauthenticated request to {} from {:?}",
+        req.path(),
+        req.peer_addr()
+    );
+    HttpResponse::Unauthorized().finish()
+}
+
+#[actix_web::get("/address")]
+async fn address(req: HttpRequest, state: web::Data<Arc<SignerState>>) -> HttpResponse {
+    if !state.auth_token.is_authorized(&req) {
+        return unauthorized(&req);
+    }
+    HttpResponse::Ok().json(AddressResponse {
+        address: state.address,
+    })
+}
+
+/// Checks the transaction against the policy and signs it.
+/// Responds with `403 Forbidden` if the transaction violates the policy.
+#[actix_web::post("/sign_transaction")]
+async fn sign_transaction(
+    req: HttpRequest,
+    state: web::Data<Arc<SignerState>>,
+    web::Json(request): web::Json<SignTransactionRequest>,
+) -> HttpResponse {
+    if !state.auth_token.is_authorized(&req) {
+        return unauthorized(&req);
+    }
+    let tx = request.tx;
+    let mut highest_signed_nonce = state.highest_signed_nonce.lock().await;
+
+    let method = match state.policy.check(&tx, *highest_signed_nonce) {
+        Ok(method) => method,
+        Err(violation) => {
+            vlog::warn!(
+                "Rejected transaction with nonce {}: {}",
+                tx.nonce,
+                violation
+            );
+            if let Err(err) = state
+                .audit_log
+                .write(&AuditRecord::rejected(&tx, violation.to_string()))
+            {
+                return internal_error(err);
+            }
+            return HttpResponse::Forbidden().json(RejectionResponse {
+                reason: violation.to_string(),
+            });
+        }
+    };
+
+    let signed_tx = match state.signer.sign_transaction(tx.clone()).await {
+        Ok(signed_tx) => signed_tx,
+        Err(err) => return internal_error(err),
+    };
+    let tx_hash = H256::from(signed_tx.keccak256());
+    // The signature is only returned once the record is persisted.
+    if let Err(err) = state
+        .audit_log
+        .write(&AuditRecord::signed(&tx, method, tx_hash))
+    {
+        return internal_error(err);
+    }
+
+    vlog::info!(
+        "Signed `{}` transaction {:?} with nonce {}",
+        method,
+        tx_hash,
+        tx.nonce
+    );
+    *highest_signed_nonce =
+        Some(highest_signed_nonce.map_or(tx.nonce, |nonce| nonce.max(tx.nonce)));
+    HttpResponse::Ok().json(SignTransactionResponse { signed_tx })
+}
+
+pub async fn run_server(state: Arc<SignerState>, bind_addr: SocketAddr) -> std::io::Result<()> {
+    HttpServer::new(move || {
+        App::new()
+            .app_data(web::Data::new(state.clone()))
+            .service(address)
+            .service(sign_transaction)
+    })
+    .bind(&bind_addr)?
+    .run()
+    .await
+}
@@ -7,6 +7,7 @@ use structopt::StructOpt;
 use serde::{Deserialize, Serialize};
 
 use zksync_api::fee_ticker::{run_updaters, FeeTicker, TickerInfo};
+use zksync_api::runtime_parameters::{run_runtime_parameters_updater, RuntimeParametersStore};
 use zksync_core::{genesis_init, run_core, wait_for_tasks};
 use zksync_eth_client::EthereumGateway;
 use zksync_forced_exit_requests::run_forced_exit_requests_actors;
@@ -26,6 +27,7 @@ use zksync_config::{
 use zksync_core::rejected_tx_cleaner::run_rejected_tx_cleaner;
 use zksync_prometheus_exporter::{run_operation_counter, run_prometheus_exporter};
 use zksync_storage::ConnectionPool;
+use zksync_types::tx::Eip712Domain;
 
 #[derive(Debug, Clone, Copy)]
 pub enum ServerCommand {
@@ -142,6 +144,10 @@ async fn main() -> anyhow::Result<()> {
     // It's a `ServerCommand::Launch`, perform the usual routine.
     vlog::info!("Running the zkSync server");
 
+    // Validate the whole config before launching the components, so all the problems
+    // are reported at once instead of failing the components one by one.
+    ZkSyncConfig::from_env();
+
     run_server(& .components).await;
 
     Ok(())
@@ -175,7 +181,7 @@ async fn run_server(components: &ComponentsToRun) {
         )
     }) {
         // Create gateway
-        let eth_gateway = create_eth_gateway();
+        let eth_gateway = create_eth_gateway().await;
 
         let eth_watch_config = ETHWatchConfig::from_env();
         let gateway_watcher_config = GatewayWatcherConfig::from_env();
@@ -189,8 +195,13 @@ async fn run_server(components: &ComponentsToRun) {
 
         // Run signer
         let (sign_check_sender, sign_check_receiver) = mpsc::channel(channel_size);
+        let eip712_domain = Eip712Domain::new(
+            ETHClientConfig::from_env().chain_id,
+            ContractsConfig::from_env().contract_addr,
+        );
         tasks.push(zksync_api::signature_checker::start_sign_checker(
             eth_gateway,
+            eip712_domain,
             sign_check_receiver,
         ));
 
@@ -201,11 +212,18 @@ async fn run_server(components: &ComponentsToRun) {
         let ticker_info = Box::new(TickerInfo::new(connection_pool.clone()));
         let fee_ticker_config = TickerConfig::from_env();
 
+        let runtime_parameters = RuntimeParametersStore::new();
+        tasks.push(run_runtime_parameters_updater(
+            connection_pool.clone(),
+            runtime_parameters.clone(),
+        ));
+
         let ticker = FeeTicker::new_with_default_validator(
             ticker_info,
             fee_ticker_config,
             chain_config.max_blocks_to_aggregate(),
             connection_pool.clone(),
+            runtime_parameters,
         );
 
         if components.0.contains(&Component::RpcWebSocketApi) {
@@ -245,11 +263,11 @@ async fn run_server(components: &ComponentsToRun) {
     }
 
     if components.0.contains(&Component::EthSender) {
-        tasks.push(run_eth_sender(connection_pool.clone()))
+        tasks.push(run_eth_sender(connection_pool.clone()).await)
     }
 
     if components.0.contains(&Component::Core) {
-        let eth_gateway = create_eth_gateway();
+        let eth_gateway = create_eth_gateway().await;
 
         tasks.append(
             &mut run_core(
@@ -325,11 +343,17 @@ pub fn run_witness_generator(connection_pool: ConnectionPool) -> JoinHandle<()>
     vlog::info!("Starting the Prover server actors");
     let prover_api_config = ProverApiConfig::from_env();
     let prover_config = ProverConfig::from_env();
+    let chain_config = ChainConfig::from_env();
     let database = zksync_witness_generator::database::Database::new(connection_pool);
-    run_prover_server(database, prover_api_config, prover_config)
+    run_prover_server(
+        database,
+        prover_api_config,
+        prover_config,
+        chain_config.state_keeper.block_prove_deadline(),
+    )
 }
 
-pub fn run_eth_sender(connection_pool: ConnectionPool) -> JoinHandle<()> {
+pub async fn run_eth_sender(connection_pool: ConnectionPool) -> JoinHandle<()> {
     vlog::info!("Starting the Ethereum sender actors");
     let eth_client_config = ETHClientConfig::from_env();
     let eth_sender_config = ETHSenderConfig::from_env();
@@ -338,7 +362,8 @@ pub fn run_eth_sender(connection_pool: ConnectionPool) -> JoinHandle<()> {
         &eth_client_config,
         &eth_sender_config,
         contracts.contract_addr,
-    );
+    )
+    .await;
 
     zksync_eth_sender::run_eth_sender(connection_pool, eth_gateway, eth_sender_config)
 }
@@ -348,7 +373,7 @@ pub fn run_price_updaters(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>
     run_updaters(connection_pool, &ticker_config)
 }
 
-pub fn create_eth_gateway() -> EthereumGateway {
+pub async fn create_eth_gateway() -> EthereumGateway {
     let eth_client_config = ETHClientConfig::from_env();
     let eth_sender_config = ETHSenderConfig::from_env();
     let contracts = ContractsConfig::from_env();
@@ -357,4 +382,5 @@ pub fn create_eth_gateway() -> EthereumGateway {
         &eth_sender_config,
         contracts.contract_addr,
     )
+    .await
 }
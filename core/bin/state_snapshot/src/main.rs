@@ -0,0 +1,249 @@
+use std::path::PathBuf;
+
+use anyhow::{ensure, format_err};
+use structopt::StructOpt;
+use zksync_crypto::{
+    ff,
+    params::{NFT_STORAGE_ACCOUNT_ADDRESS, NFT_TOKEN_ID},
+};
+use zksync_state::state::ZkSyncState;
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    aggregated_operations::{
+        AggregatedActionType, AggregatedOperation, BlocksCommitOperation, BlocksExecuteOperation,
+    },
+    block::Block,
+    BlockNumber, Token, TokenKind, NFT,
+};
+
+use crate::snapshot::StateSnapshot;
+
+mod snapshot;
+
+async fn export_snapshot(
+    storage: &mut StorageProcessor<'_>,
+    block: Option<BlockNumber>,
+) -> anyhow::Result<StateSnapshot> {
+    let mut transaction = storage.start_transaction().await?;
+
+    let (block_number, accounts) = transaction
+        .chain()
+        .state_schema()
+        .load_committed_state(block)
+        .await?;
+    if let Some(block) = block {
+        ensure!(
+            block == block_number,
+            "Block {} is not committed, the last committed block is {}",
+            block,
+            block_number
+        );
+    }
+    let block = transaction
+        .chain()
+        .block_schema()
+        .get_block(block_number)
+        .await?
+        .ok_or_else(|| format_err!("Block {} is not found in the storage", block_number))?;
+    println!(
+        "Loaded {} accounts of the block {}",
+        accounts.len(),
+        block_number
+    );
+
+    let nfts = transaction
+        .chain()
+        .state_schema()
+        .load_committed_nft_tokens(Some(block_number))
+        .await?
+        .into_iter()
+        .map(|nft| {
+            let token: NFT = nft.into();
+            (token.id, token)
+        })
+        .collect();
+    let tokens = transaction
+        .tokens_schema()
+        .load_tokens()
+        .await?
+        .into_values()
+        .collect();
+    transaction.commit().await?;
+
+    let mut state = ZkSyncState::from_acc_map(accounts);
+    state.nfts = nfts;
+    let snapshot = StateSnapshot::new(block, &state, tokens);
+    // Don't export the state which can't be imported.
+    snapshot.restore_state()?;
+
+    Ok(snapshot)
+}
+
+async fn import_snapshot(
+    storage: &mut StorageProcessor<'_>,
+    snapshot: StateSnapshot,
+) -> anyhow::Result<()> {
+    let state = snapshot.restore_state()?;
+    println!(
+        "Snapshot of the block {} is verified, root hash: 0x{}",
+        snapshot.block.block_number,
+        ff::to_hex(&snapshot.root_hash)
+    );
+
+    let mut transaction = storage.start_transaction().await?;
+
+    let (last_committed, accounts) = transaction
+        .chain()
+        .state_schema()
+        .load_committed_state(None)
+        .await?;
+    ensure!(
+        *last_committed == 0 && accounts.is_empty(),
+        "The storage must be empty to import the snapshot"
+    );
+
+    transaction
+        .tokens_schema()
+        .store_or_update_token(Token::new(
+            NFT_TOKEN_ID,
+            *NFT_STORAGE_ACCOUNT_ADDRESS,
+            "SPECIAL",
+            18,
+            TokenKind::NFT,
+        ))
+        .await?;
+    for token in &snapshot.tokens {
+        transaction
+            .tokens_schema()
+            .store_or_update_token(token.clone())
+            .await?;
+    }
+    println!("`tokens` table is filled");
+
+    let block = snapshot.block.clone();
+    let block_number = block.block_number;
+    transaction
+        .chain()
+        .state_schema()
+        .commit_state_update(block_number, &snapshot.account_updates(), 0)
+        .await?;
+    store_executed_block(&mut transaction, &block).await?;
+    println!("Account tree is stored");
+
+    transaction
+        .chain()
+        .block_schema()
+        .save_full_block(block)
+        .await?;
+    transaction
+        .chain()
+        .block_schema()
+        .store_account_tree_cache(block_number, &state.get_balance_tree().get_internals())
+        .await?;
+    println!("Block {} is stored", block_number);
+
+    transaction.commit().await?;
+    Ok(())
+}
+
+/// Stores the block as committed and executed on Ethereum and applies its state update.
+/// The block doesn't have operations, so nothing besides the state is restored from it.
+async fn store_executed_block(
+    storage: &mut StorageProcessor<'_>,
+    block: &Block,
+) -> anyhow::Result<()> {
+    let block_number = block.block_number;
+    let mut transaction = storage.start_transaction().await?;
+
+    transaction
+        .chain()
+        .operations_schema()
+        .store_aggregated_action(AggregatedOperation::CommitBlocks(BlocksCommitOperation {
+            last_committed_block: block.clone(),
+            blocks: vec![block.clone()],
+        }))
+        .await?;
+    transaction
+        .chain()
+        .operations_schema()
+        .store_aggregated_action(AggregatedOperation::ExecuteBlocks(BlocksExecuteOperation {
+            blocks: vec![block.clone()],
+        }))
+        .await?;
+    for action_type in &[
+        AggregatedActionType::CommitBlocks,
+        AggregatedActionType::ExecuteBlocks,
+    ] {
+        transaction
+            .chain()
+            .operations_schema()
+            .confirm_aggregated_operations(block_number, block_number, *action_type)
+            .await?;
+    }
+    transaction
+        .chain()
+        .state_schema()
+        .apply_state_update(block_number)
+        .await?;
+
+    transaction.commit().await?;
+    Ok(())
+}
+
+#[derive(StructOpt)]
+#[structopt(
+    name = "zkSync state snapshot",
+    author = "Matter Labs",
+    rename_all = "snake_case"
+)]
+enum Opt {
+    /// Exports the committed state into the snapshot file
+    Export {
+        /// Path to the snapshot file
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+        /// Block to export the state at. The last committed block is used by default
+        #[structopt(long)]
+        block: Option<u32>,
+        /// Maximum number of items in a single chunk of the file
+        #[structopt(long, default_value = "10000")]
+        chunk_size: usize,
+    },
+    /// Verifies the snapshot file and imports it into the empty storage
+    Import {
+        /// Path to the snapshot file
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+
+    let mut storage = StorageProcessor::establish_connection().await?;
+
+    match opt {
+        Opt::Export {
+            path,
+            block,
+            chunk_size,
+        } => {
+            let snapshot = export_snapshot(&mut storage, block.map(BlockNumber)).await?;
+            snapshot.write_to_file(&path, chunk_size)?;
+            println!(
+                "State of the block {} is exported to {}, root hash: 0x{}",
+                snapshot.block.block_number,
+                path.display(),
+                ff::to_hex(&snapshot.root_hash)
+            );
+        }
+        Opt::Import { path } => {
+            let snapshot = StateSnapshot::read_from_file(&path)?;
+            import_snapshot(&mut storage, snapshot).await?;
+            println!("Snapshot {} is imported", path.display());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,464 @@
+//! Portable snapshot of the zkSync state.
+//!
+//! The snapshot file starts with the magic bytes followed by a sequence of frames.
+//! Each frame is `[payload length: u32 BE][SHA-256 of the payload][JSON payload]`.
+//! The first frame is the header with the block and the state root hash, the rest are
+//! chunks of the accounts, account addresses, NFTs and tokens. Chunks keep the memory
+//! usage of a single frame bounded and let the corruption be found before parsing it.
+
+// Built-in deps
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fs::File,
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    path::Path,
+};
+// External deps
+use anyhow::{bail, ensure, format_err};
+use num::BigUint;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+// Workspace deps
+use zksync_crypto::{ff, serialization::FrSerde, Fr};
+use zksync_state::state::ZkSyncState;
+use zksync_types::{
+    block::Block, Account, AccountId, AccountMap, AccountUpdate, AccountUpdates, Address,
+    PubKeyHash, Token, TokenId, NFT,
+};
+
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+const SNAPSHOT_MAGIC: &[u8; 8] = b"ZKSNAPSH";
+const CHECKSUM_SIZE: usize = 32;
+/// Maximum size of the frame payload. Frames of the corrupted or malicious files may state
+/// any length, so it's checked before the memory for the payload is allocated.
+const MAX_FRAME_SIZE: usize = 256 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SnapshotHeader {
+    format_version: u32,
+    /// The block the state corresponds to.
+    block: Block,
+    /// Root hash of the account tree, calculated on export.
+    #[serde(with = "FrSerde")]
+    root_hash: Fr,
+    accounts_count: usize,
+    addresses_count: usize,
+    nfts_count: usize,
+    tokens_count: usize,
+    chunks_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "items")]
+enum SnapshotChunk {
+    Accounts(Vec<(AccountId, Account)>),
+    AccountAddresses(Vec<(Address, AccountId)>),
+    Nfts(Vec<NFT>),
+    Tokens(Vec<Token>),
+}
+
+/// State of the zkSync network at the specific block.
+#[derive(Debug, Clone)]
+pub struct StateSnapshot {
+    pub block: Block,
+    pub root_hash: Fr,
+    pub accounts: AccountMap,
+    pub account_id_by_address: HashMap<Address, AccountId>,
+    pub nfts: HashMap<TokenId, NFT>,
+    /// ERC20 tokens known to the server. NFTs are restored from the `nfts` instead.
+    pub tokens: Vec<Token>,
+}
+
+impl StateSnapshot {
+    pub fn new(block: Block, state: &ZkSyncState, tokens: Vec<Token>) -> Self {
+        Self {
+            block,
+            root_hash: state.root_hash(),
+            accounts: state
+                .get_accounts()
+                .into_iter()
+                .map(|(id, account)| (AccountId(id), account))
+                .collect(),
+            account_id_by_address: state.get_account_addresses(),
+            nfts: state.nfts.clone(),
+            tokens,
+        }
+    }
+
+    /// Rebuilds the account tree and checks that it matches the root hash of the snapshot
+    /// and the root hash of its block.
+    pub fn restore_state(&self) -> anyhow::Result<ZkSyncState> {
+        let mut state = ZkSyncState::from_acc_map(self.accounts.clone());
+        state.nfts = self.nfts.clone();
+
+        let root_hash = state.root_hash();
+        ensure!(
+            root_hash == self.root_hash,
+            "Root hash of the restored state 0x{} doesn't match the snapshot root hash 0x{}",
+            ff::to_hex(&root_hash),
+            ff::to_hex(&self.root_hash)
+        );
+        ensure!(
+            root_hash == self.block.new_root_hash,
+            "Root hash of the restored state 0x{} doesn't match the root hash 0x{} of block {}",
+            ff::to_hex(&root_hash),
+            ff::to_hex(&self.block.new_root_hash),
+            self.block.block_number
+        );
+        ensure!(
+            state.get_account_addresses() == self.account_id_by_address,
+            "Account addresses of the snapshot don't match its accounts"
+        );
+        for nft in self.nfts.values() {
+            ensure!(
+                self.accounts.contains_key(&nft.creator_id),
+                "Creator {} of the NFT {} is not in the snapshot",
+                nft.creator_id,
+                nft.id
+            );
+        }
+
+        Ok(state)
+    }
+
+    /// Returns the updates creating the snapshot state from scratch, in the form
+    /// expected by the `StateSchema`.
+    pub fn account_updates(&self) -> AccountUpdates {
+        let mut accounts: Vec<_> = self.accounts.iter().collect();
+        accounts.sort_by_key(|(id, _)| **id);
+
+        let mut updates = AccountUpdates::new();
+        for (&id, account) in accounts {
+            let nonce = account.nonce;
+            updates.push((
+                id,
+                AccountUpdate::Create {
+                    address: account.address,
+                    nonce,
+                },
+            ));
+            if account.pub_key_hash != PubKeyHash::default() {
+                updates.push((
+                    id,
+                    AccountUpdate::ChangePubKeyHash {
+                        old_pub_key_hash: PubKeyHash::default(),
+                        new_pub_key_hash: account.pub_key_hash,
+                        old_nonce: nonce,
+                        new_nonce: nonce,
+                    },
+                ));
+            }
+
+            let mut balances: Vec<_> = account.get_nonzero_balances().into_iter().collect();
+            balances.sort_by_key(|(token, _)| *token);
+            for (token, balance) in balances {
+                updates.push((
+                    id,
+                    AccountUpdate::UpdateBalance {
+                        old_nonce: nonce,
+                        new_nonce: nonce,
+                        balance_update: (token, BigUint::from(0u32), balance.0),
+                    },
+                ));
+            }
+        }
+
+        let mut nfts: Vec<_> = self.nfts.values().collect();
+        nfts.sort_by_key(|nft| nft.id);
+        for nft in nfts {
+            updates.push((
+                nft.creator_id,
+                AccountUpdate::MintNFT {
+                    token: nft.clone(),
+                    nonce: self.accounts[&nft.creator_id].nonce,
+                },
+            ));
+        }
+
+        updates
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W, chunk_size: usize) -> anyhow::Result<()> {
+        ensure!(chunk_size > 0, "Chunk size must be positive");
+
+        let mut accounts: Vec<_> = self
+            .accounts
+            .iter()
+            .map(|(id, account)| (*id, account.clone()))
+            .collect();
+        accounts.sort_by_key(|(id, _)| *id);
+        let mut addresses: Vec<_> = self
+            .account_id_by_address
+            .iter()
+            .map(|(address, id)| (*address, *id))
+            .collect();
+        addresses.sort_by_key(|(_, id)| *id);
+        let mut nfts: Vec<_> = self.nfts.values().cloned().collect();
+        nfts.sort_by_key(|nft| nft.id);
+        let mut tokens = self.tokens.clone();
+        tokens.sort_by_key(|token| token.id);
+
+        let chunks: Vec<_> = accounts
+            .chunks(chunk_size)
+            .map(|items| SnapshotChunk::Accounts(items.to_vec()))
+            .chain(
+                addresses
+                    .chunks(chunk_size)
+                    .map(|items| SnapshotChunk::AccountAddresses(items.to_vec())),
+            )
+            .chain(
+                nfts.chunks(chunk_size)
+                    .map(|items| SnapshotChunk::Nfts(items.to_vec())),
+            )
+            .chain(
+                tokens
+                    .chunks(chunk_size)
+                    .map(|items| SnapshotChunk::Tokens(items.to_vec())),
+            )
+            .collect();
+
+        let header = SnapshotHeader {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            block: self.block.clone(),
+            root_hash: self.root_hash,
+            accounts_count: accounts.len(),
+            addresses_count: addresses.len(),
+            nfts_count: nfts.len(),
+            tokens_count: tokens.len(),
+            chunks_count: chunks.len(),
+        };
+
+        writer.write_all(SNAPSHOT_MAGIC)?;
+        write_frame(&mut writer, &header)?;
+        for chunk in &chunks {
+            write_frame(&mut writer, chunk)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads the snapshot and checks the checksums of its frames.
+    /// The state itself is checked by the `restore_state`.
+    pub fn read<R: Read>(mut reader: R) -> anyhow::Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        ensure!(&magic == SNAPSHOT_MAGIC, "The file is not a state snapshot");
+
+        let header: SnapshotHeader = read_frame(&mut reader)?
+            .ok_or_else(|| format_err!("The snapshot header is missing"))?;
+        ensure!(
+            header.format_version == SNAPSHOT_FORMAT_VERSION,
+            "Unsupported snapshot format version {}, expected {}",
+            header.format_version,
+            SNAPSHOT_FORMAT_VERSION
+        );
+
+        let mut snapshot = Self {
+            block: header.block,
+            root_hash: header.root_hash,
+            accounts: AccountMap::default(),
+            account_id_by_address: HashMap::new(),
+            nfts: HashMap::new(),
+            tokens: Vec::new(),
+        };
+        for chunk_index in 0..header.chunks_count {
+            let chunk = read_frame(&mut reader)?.ok_or_else(|| {
+                format_err!(
+                    "The snapshot is truncated: read {} of {} chunks",
+                    chunk_index,
+                    header.chunks_count
+                )
+            })?;
+            match chunk {
+                SnapshotChunk::Accounts(items) => snapshot.accounts.extend(items),
+                SnapshotChunk::AccountAddresses(items) => {
+                    snapshot.account_id_by_address.extend(items)
+                }
+                SnapshotChunk::Nfts(items) => snapshot
+                    .nfts
+                    .extend(items.into_iter().map(|nft| (nft.id, nft))),
+                SnapshotChunk::Tokens(items) => snapshot.tokens.extend(items),
+            }
+        }
+        if read_frame::<_, SnapshotChunk>(&mut reader)?.is_some() {
+            bail!("The snapshot contains more chunks than stated in its header");
+        }
+
+        ensure!(
+            snapshot.accounts.len() == header.accounts_count
+                && snapshot.account_id_by_address.len() == header.addresses_count
+                && snapshot.nfts.len() == header.nfts_count
+                && snapshot.tokens.len() == header.tokens_count,
+            "The snapshot content doesn't match its header"
+        );
+
+        Ok(snapshot)
+    }
+
+    pub fn write_to_file(&self, path: impl AsRef<Path>, chunk_size: usize) -> anyhow::Result<()> {
+        self.write(BufWriter::new(File::create(path)?), chunk_size)
+    }
+
+    pub fn read_from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+}
+
+fn write_frame<W: Write, T: Serialize>(writer: &mut W, value: &T) -> anyhow::Result<()> {
+    let payload = serde_json::to_vec(value)?;
+    ensure!(
+        payload.len() <= MAX_FRAME_SIZE,
+        "Snapshot frame is too big: {} bytes, the limit is {} bytes, use the smaller chunks",
+        payload.len(),
+        MAX_FRAME_SIZE
+    );
+    let len = u32::try_from(payload.len()).expect("frame size is limited");
+
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(&Sha256::digest(&payload))?;
+    writer.write_all(&payload)?;
+    Ok(())
+}
+
+/// Reads the next frame. Returns `None` if the end of the snapshot is reached.
+fn read_frame<R: Read, T: DeserializeOwned>(reader: &mut R) -> anyhow::Result<Option<T>> {
+    let mut len = [0u8; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.into()),
+    }
+
+    let mut checksum = [0u8; CHECKSUM_SIZE];
+    reader.read_exact(&mut checksum)?;
+    let len = u32::from_be_bytes(len) as usize;
+    ensure!(
+        len <= MAX_FRAME_SIZE,
+        "Snapshot frame is too big: {} bytes, the limit is {} bytes",
+        len,
+        MAX_FRAME_SIZE
+    );
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload)?;
+    ensure!(
+        Sha256::digest(&payload).as_slice() == &checksum[..],
+        "Snapshot frame checksum mismatch, the file is corrupted"
+    );
+
+    Ok(Some(serde_json::from_slice(&payload)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{BlockNumber, Nonce, H256};
+
+    use super::*;
+
+    fn test_snapshot() -> StateSnapshot {
+        let mut accounts = AccountMap::default();
+        let mut creator = Account::default_with_address(&Address::repeat_byte(1));
+        creator.set_balance(TokenId(0), BigUint::from(100u32));
+        creator.set_balance(TokenId(2), BigUint::from(5u32));
+        creator.pub_key_hash = PubKeyHash { data: [3u8; 20] };
+        creator.nonce = Nonce(4);
+        accounts.insert(AccountId(0), creator);
+        accounts.insert(
+            AccountId(1),
+            Account::default_with_address(&Address::repeat_byte(2)),
+        );
+
+        let mut state = ZkSyncState::from_acc_map(accounts);
+        let nft = NFT::new(
+            TokenId(70000),
+            0,
+            AccountId(0),
+            Address::repeat_byte(1),
+            Address::repeat_byte(9),
+            None,
+            H256::repeat_byte(7),
+        );
+        state.nfts.insert(nft.id, nft);
+
+        let block = Block::new(
+            BlockNumber(12),
+            state.root_hash(),
+            AccountId(0),
+            Vec::new(),
+            (0, 3),
+            10,
+            1_000_000.into(),
+            1_500_000.into(),
+            H256::default(),
+            0,
+        );
+        let tokens = vec![Token::new(
+            TokenId(2),
+            Address::repeat_byte(5),
+            "TKN",
+            18,
+            Default::default(),
+        )];
+        StateSnapshot::new(block, &state, tokens)
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        let snapshot = test_snapshot();
+        let mut bytes = Vec::new();
+        snapshot.write(&mut bytes, 1).unwrap();
+
+        let restored = StateSnapshot::read(bytes.as_slice()).unwrap();
+        assert_eq!(restored.block.block_number, BlockNumber(12));
+        assert_eq!(restored.accounts, snapshot.accounts);
+        assert_eq!(
+            restored.account_id_by_address,
+            snapshot.account_id_by_address
+        );
+        assert_eq!(restored.nfts, snapshot.nfts);
+        assert_eq!(restored.tokens, snapshot.tokens);
+
+        let state = restored.restore_state().unwrap();
+        assert_eq!(state.root_hash(), snapshot.root_hash);
+
+        // Applying the updates to the empty state results in the same state.
+        let mut applied = ZkSyncState::empty();
+        applied.apply_account_updates(restored.account_updates());
+        assert_eq!(applied.root_hash(), snapshot.root_hash);
+    }
+
+    #[test]
+    fn snapshot_corruption_is_detected() {
+        let snapshot = test_snapshot();
+        let mut bytes = Vec::new();
+        snapshot.write(&mut bytes, 1).unwrap();
+
+        // Corrupted payload.
+        let mut corrupted = bytes.clone();
+        let last = corrupted.len() - 2;
+        corrupted[last] ^= 1;
+        let err = StateSnapshot::read(corrupted.as_slice()).unwrap_err();
+        assert!(err.to_string().contains("checksum"), "{}", err);
+
+        // Frame length exceeding the limit is rejected before the payload is read.
+        let mut oversized = bytes.clone();
+        let len_offset = SNAPSHOT_MAGIC.len();
+        oversized[len_offset..len_offset + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        let err = StateSnapshot::read(oversized.as_slice()).unwrap_err();
+        assert!(err.to_string().contains("too big"), "{}", err);
+
+        // Truncated file.
+        let truncated = &bytes[..bytes.len() / 2];
+        assert!(StateSnapshot::read(truncated).is_err());
+
+        // The state doesn't match the root hash of the block.
+        let mut snapshot = StateSnapshot::read(bytes.as_slice()).unwrap();
+        snapshot
+            .accounts
+            .get_mut(&AccountId(1))
+            .unwrap()
+            .set_balance(TokenId(0), BigUint::from(1u32));
+        assert!(snapshot.restore_state().is_err());
+    }
+}
@@ -0,0 +1,297 @@
+//! Cross-checks of the tables that reference blocks.
+
+use std::{collections::HashMap, str::FromStr};
+
+use zksync_storage::chain::operations::records::StoredAggregatedOperationRange;
+use zksync_types::{aggregated_operations::AggregatedActionType, BlockNumber};
+
+/// Blocks known to the storage.
+#[derive(Debug, Clone, Copy)]
+pub struct StoredBlocks {
+    /// The last block in the `blocks` table.
+    pub last_block: BlockNumber,
+    /// Inclusive range of the incomplete blocks, if any.
+    pub incomplete_blocks: Option<(BlockNumber, BlockNumber)>,
+    pub pending_block: Option<BlockNumber>,
+}
+
+impl StoredBlocks {
+    fn is_sealed(&self, block: BlockNumber) -> bool {
+        *block > 0 && block <= self.last_block
+    }
+
+    fn contains(&self, block: BlockNumber) -> bool {
+        self.is_sealed(block)
+            || self
+                .incomplete_blocks
+                .map_or(false, |(first, last)| first <= block && block <= last)
+            || self.pending_block == Some(block)
+    }
+}
+
+/// Checks that every executed transaction and priority operation belongs to a sealed,
+/// incomplete or pending block.
+pub fn check_executed_operations(
+    operations_count: &[(BlockNumber, u64)],
+    blocks: &StoredBlocks,
+) -> Vec<String> {
+    operations_count
+        .iter()
+        .filter(|(block, _)| !blocks.contains(*block))
+        .map(|(block, count)| {
+            format!(
+                "{} executed operations belong to block {}, which doesn't exist",
+                count, block
+            )
+        })
+        .collect()
+}
+
+/// Checks that all the blocks referenced by the table are sealed.
+pub fn check_sealed_blocks(
+    table: &str,
+    blocks: &[BlockNumber],
+    stored: &StoredBlocks,
+) -> Vec<String> {
+    blocks
+        .iter()
+        .filter(|block| !stored.is_sealed(**block))
+        .map(|block| {
+            format!(
+                "`{}` has an entry for block {}, which is not sealed",
+                table, block
+            )
+        })
+        .collect()
+}
+
+/// Checks that the aggregated proofs cover the sealed blocks which all have proofs.
+pub fn check_aggregated_proofs(
+    ranges: &[(BlockNumber, BlockNumber)],
+    proofs: &[BlockNumber],
+    stored: &StoredBlocks,
+) -> Vec<String> {
+    let mut issues = Vec::new();
+    for &(first_block, last_block) in ranges {
+        if first_block > last_block {
+            issues.push(format!(
+                "Aggregated proof for blocks {}-{} has an empty range",
+                first_block, last_block
+            ));
+            continue;
+        }
+        if !stored.is_sealed(first_block) || !stored.is_sealed(last_block) {
+            issues.push(format!(
+                "Aggregated proof for blocks {}-{} references blocks which are not sealed",
+                first_block, last_block
+            ));
+            continue;
+        }
+        let missing_proofs: Vec<_> = (*first_block..=*last_block)
+            .map(BlockNumber)
+            .filter(|block| proofs.binary_search(block).is_err())
+            .map(|block| block.to_string())
+            .collect();
+        if !missing_proofs.is_empty() {
+            issues.push(format!(
+                "Aggregated proof for blocks {}-{} exists, but there are no proofs for blocks {}",
+                first_block,
+                last_block,
+                missing_proofs.join(", ")
+            ));
+        }
+    }
+    issues
+}
+
+/// Checks that the aggregated operations of each type cover consecutive ranges of sealed blocks,
+/// only the latest operations are not confirmed, and the blocks are never executed before they
+/// are proven or proven before they are committed.
+pub fn check_aggregated_operations(
+    operations: &[StoredAggregatedOperationRange],
+    stored: &StoredBlocks,
+) -> Vec<String> {
+    let mut issues = Vec::new();
+    // Last covered block and whether all the operations so far are confirmed, per action type.
+    let mut last_operations: HashMap<String, (BlockNumber, bool)> = HashMap::new();
+
+    for operation in operations {
+        let action_type = match AggregatedActionType::from_str(&operation.action_type) {
+            Ok(action_type) => action_type.to_string(),
+            Err(_) => {
+                issues.push(format!(
+                    "Aggregated operation {} has unknown type {}",
+                    operation.id, operation.action_type
+                ));
+                continue;
+            }
+        };
+        let from_block = BlockNumber(operation.from_block as u32);
+        let to_block = BlockNumber(operation.to_block as u32);
+        let description = format!(
+            "Aggregated operation {} ({} for blocks {}-{})",
+            operation.id, operation.action_type, from_block, to_block
+        );
+
+        if from_block > to_block {
+            issues.push(format!("{} has an empty range", description));
+        }
+        if !stored.is_sealed(from_block) || !stored.is_sealed(to_block) {
+            issues.push(format!(
+                "{} references blocks which are not sealed",
+                description
+            ));
+        }
+
+        if let Some(&(last_block, all_confirmed)) = last_operations.get(&action_type) {
+            if from_block != last_block + 1 {
+                issues.push(format!(
+                    "{} doesn't follow the previous operation of the same type, which ends at block {}",
+                    description, last_block
+                ));
+            }
+            if operation.confirmed && !all_confirmed {
+                issues.push(format!(
+                    "{} is confirmed, while some of the previous operations of the same type are not",
+                    description
+                ));
+            }
+        }
+        let all_confirmed = last_operations
+            .get(&action_type)
+            .map_or(true, |&(_, all_confirmed)| all_confirmed)
+            && operation.confirmed;
+        last_operations.insert(action_type, (to_block, all_confirmed));
+    }
+
+    // Each stage can only process the blocks processed by the previous one. The stages without
+    // operations are skipped: e.g. restored databases only contain commit and execute operations.
+    let stages: Vec<_> = [
+        AggregatedActionType::CommitBlocks,
+        AggregatedActionType::CreateProofBlocks,
+        AggregatedActionType::PublishProofBlocksOnchain,
+        AggregatedActionType::ExecuteBlocks,
+    ]
+    .iter()
+    .filter_map(|action_type| {
+        let action_type = action_type.to_string();
+        last_operations
+            .get(&action_type)
+            .map(|&(last_block, _)| (action_type, last_block))
+    })
+    .collect();
+    for stages in stages.windows(2) {
+        let ((previous, previous_block), (next, next_block)) = (&stages[0], &stages[1]);
+        if next_block > previous_block {
+            issues.push(format!(
+                "{} operations reach block {}, while {} operations only reach block {}",
+                next, next_block, previous, previous_block
+            ));
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored_blocks() -> StoredBlocks {
+        StoredBlocks {
+            last_block: BlockNumber(10),
+            incomplete_blocks: Some((BlockNumber(11), BlockNumber(12))),
+            pending_block: Some(BlockNumber(13)),
+        }
+    }
+
+    fn operation(
+        id: i64,
+        action_type: AggregatedActionType,
+        blocks: (u32, u32),
+        confirmed: bool,
+    ) -> StoredAggregatedOperationRange {
+        StoredAggregatedOperationRange {
+            id,
+            action_type: action_type.to_string(),
+            from_block: blocks.0 as i64,
+            to_block: blocks.1 as i64,
+            confirmed,
+        }
+    }
+
+    #[test]
+    fn executed_operations_belong_to_blocks() {
+        let counts = [
+            (BlockNumber(1), 3),
+            (BlockNumber(12), 1),
+            (BlockNumber(13), 2),
+            (BlockNumber(14), 5),
+        ];
+        let issues = check_executed_operations(&counts, &stored_blocks());
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("block 14"), "{}", issues[0]);
+
+        let issues = check_sealed_blocks(
+            "proofs",
+            &[BlockNumber(10), BlockNumber(11)],
+            &stored_blocks(),
+        );
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("block 11"), "{}", issues[0]);
+    }
+
+    #[test]
+    fn aggregated_proofs_require_proofs() {
+        let proofs: Vec<_> = (1..=4).map(BlockNumber).collect();
+        let ranges = [
+            (BlockNumber(1), BlockNumber(4)),
+            (BlockNumber(5), BlockNumber(6)),
+            (BlockNumber(10), BlockNumber(11)),
+        ];
+        let issues = check_aggregated_proofs(&ranges, &proofs, &stored_blocks());
+        assert_eq!(issues.len(), 2, "{:?}", issues);
+        assert!(issues[0].contains("blocks 5, 6"), "{}", issues[0]);
+        assert!(issues[1].contains("not sealed"), "{}", issues[1]);
+    }
+
+    #[test]
+    fn aggregated_operations_ranges() {
+        use AggregatedActionType::*;
+
+        let correct = vec![
+            operation(1, CommitBlocks, (1, 2), true),
+            operation(2, CreateProofBlocks, (1, 2), true),
+            operation(3, CommitBlocks, (3, 5), true),
+            operation(4, PublishProofBlocksOnchain, (1, 2), true),
+            operation(5, ExecuteBlocks, (1, 2), false),
+            operation(6, CommitBlocks, (6, 6), false),
+        ];
+        assert!(check_aggregated_operations(&correct, &stored_blocks()).is_empty());
+
+        let mut gap = correct.clone();
+        gap[2].from_block = 4;
+        let issues = check_aggregated_operations(&gap, &stored_blocks());
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert!(issues[0].contains("doesn't follow"), "{}", issues[0]);
+
+        let mut confirmed_after_pending = correct.clone();
+        confirmed_after_pending[2].confirmed = false;
+        let issues = check_aggregated_operations(&confirmed_after_pending, &stored_blocks());
+        assert_eq!(issues.len(), 0, "{:?}", issues);
+        confirmed_after_pending[5].confirmed = true;
+        let issues = check_aggregated_operations(&confirmed_after_pending, &stored_blocks());
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert!(issues[0].contains("is confirmed"), "{}", issues[0]);
+
+        let mut executed_before_proven = correct.clone();
+        executed_before_proven[4].to_block = 5;
+        let issues = check_aggregated_operations(&executed_before_proven, &stored_blocks());
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert!(
+            issues[0].contains("ExecuteBlocks operations reach block 5"),
+            "{}",
+            issues[0]
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use anyhow::ensure;
+use structopt::StructOpt;
+use zksync_storage::StorageProcessor;
+use zksync_types::BlockNumber;
+
+use crate::consistency::{
+    check_aggregated_operations, check_aggregated_proofs, check_executed_operations,
+    check_sealed_blocks, StoredBlocks,
+};
+
+mod consistency;
+mod state;
+
+#[derive(StructOpt)]
+#[structopt(
+    name = "zkSync storage check",
+    author = "Matter Labs",
+    rename_all = "snake_case"
+)]
+#[structopt(about = "Tool to cross-check the consistency of the zkSync storage tables")]
+struct Opt {
+    /// First block to replay the account updates from. The replay starts either from the
+    /// genesis state or, for the databases that don't contain the whole chain (e.g. the ones
+    /// imported from a state snapshot), from the verified state right after the last verified block
+    #[structopt(long, default_value = "1")]
+    from_block: u32,
+    /// Last block to replay the account updates to. The last sealed block is used by default
+    #[structopt(long)]
+    to_block: Option<u32>,
+    /// Skip the replay of the account updates, which is the slowest check
+    #[structopt(long)]
+    skip_state: bool,
+}
+
+/// Prints the issues found by the check and returns their amount.
+fn report(check: &str, issues: Vec<String>) -> usize {
+    if issues.is_empty() {
+        println!("[{}] OK", check);
+    } else {
+        println!("[{}] {} issues found:", check, issues.len());
+        for issue in &issues {
+            println!("  - {}", issue);
+        }
+    }
+    issues.len()
+}
+
+async fn check_storage(storage: &mut StorageProcessor<'_>, opt: &Opt) -> anyhow::Result<usize> {
+    let last_block = storage
+        .chain()
+        .block_schema()
+        .get_last_saved_block()
+        .await?;
+    let pending_block = storage
+        .chain()
+        .block_schema()
+        .load_pending_block()
+        .await?
+        .map(|block| block.number);
+    let stored_blocks = StoredBlocks {
+        last_block,
+        incomplete_blocks: storage
+            .chain()
+            .block_schema()
+            .incomplete_blocks_range()
+            .await?,
+        pending_block,
+    };
+    println!(
+        "Last sealed block {}, incomplete blocks {:?}, pending block {:?}",
+        last_block, stored_blocks.incomplete_blocks, pending_block
+    );
+
+    let mut issues = 0;
+
+    let operations_count = storage
+        .chain()
+        .operations_schema()
+        .get_executed_operations_count_by_block()
+        .await?;
+    issues += report(
+        "executed operations",
+        check_executed_operations(&operations_count, &stored_blocks),
+    );
+
+    let proofs = storage.prover_schema().get_proof_blocks().await?;
+    issues += report(
+        "proofs",
+        check_sealed_blocks("proofs", &proofs, &stored_blocks),
+    );
+    let witnesses = storage.prover_schema().get_witness_blocks().await?;
+    issues += report(
+        "witnesses",
+        check_sealed_blocks("block_witness", &witnesses, &stored_blocks),
+    );
+    let cached_blocks = storage
+        .chain()
+        .block_schema()
+        .get_account_tree_cache_blocks()
+        .await?;
+    issues += report(
+        "account tree cache",
+        check_sealed_blocks("account_tree_cache", &cached_blocks, &stored_blocks),
+    );
+    let aggregated_proofs = storage
+        .prover_schema()
+        .get_aggregated_proof_ranges()
+        .await?;
+    issues += report(
+        "aggregated proofs",
+        check_aggregated_proofs(&aggregated_proofs, &proofs, &stored_blocks),
+    );
+    let aggregated_operations = storage
+        .chain()
+        .operations_schema()
+        .get_aggregated_operation_ranges()
+        .await?;
+    issues += report(
+        "aggregated operations",
+        check_aggregated_operations(&aggregated_operations, &stored_blocks),
+    );
+
+    if opt.skip_state {
+        println!("[state] Skipped");
+    } else {
+        let from_block = BlockNumber(opt.from_block);
+        let to_block = opt.to_block.map(BlockNumber).unwrap_or(last_block);
+        println!(
+            "Replaying the account updates of blocks {}-{}",
+            from_block, to_block
+        );
+        issues += report(
+            "state",
+            state::check_state(storage, from_block, to_block).await?,
+        );
+    }
+
+    Ok(issues)
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+    ensure!(opt.from_block > 0, "The genesis block can't be replayed");
+
+    let mut storage = StorageProcessor::establish_connection().await?;
+    let issues = check_storage(&mut storage, &opt).await?;
+
+    if issues > 0 {
+        println!("Storage check failed, {} issues found", issues);
+        std::process::exit(1);
+    }
+    println!("Storage is consistent");
+    Ok(())
+}
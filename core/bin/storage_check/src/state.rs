@@ -0,0 +1,383 @@
+//! Replay of the stored account updates.
+//!
+//! The replay starts from a state which is not derived from the replayed updates: either the
+//! genesis state built from the config, or the verified state from the `accounts` table. Then
+//! the updates of every block are applied one by one. After each block the root hash of the
+//! account tree is compared with the one stored for the block and, if the account tree cache
+//! exists for the block, with the root hash calculated from the cache.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, ensure, format_err};
+use num::BigUint;
+use zksync_config::ChainConfig;
+use zksync_crypto::{
+    convert::FeConvert,
+    ff,
+    merkle_tree::parallel_smt::SparseMerkleTreeSerializableCacheBN256,
+    params::{MIN_NFT_TOKEN_ID, NFT_STORAGE_ACCOUNT_ADDRESS, NFT_STORAGE_ACCOUNT_ID, NFT_TOKEN_ID},
+    Fr,
+};
+use zksync_state::state::ZkSyncState;
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    Account, AccountId, AccountMap, AccountUpdate, AccountUpdates, Address, BlockNumber,
+};
+
+/// How often the progress of the replay is reported.
+const PROGRESS_INTERVAL: u32 = 1000;
+
+/// Replays the account updates of the blocks in range `from_block..=to_block`.
+/// The replay stops at the first inconsistency, since the rest of the blocks can't be checked
+/// against the diverged state.
+///
+/// The replay can start either from the genesis (`from_block` is 1) or right after the last
+/// verified block, since these are the only states that can be loaded without the account updates.
+pub async fn check_state(
+    storage: &mut StorageProcessor<'_>,
+    from_block: BlockNumber,
+    to_block: BlockNumber,
+) -> anyhow::Result<Vec<String>> {
+    let mut issues = Vec::new();
+
+    let (last_verified_block, verified_accounts) =
+        storage.chain().state_schema().load_verified_state().await?;
+
+    let base_block = from_block - 1;
+    let mut state = if *base_block == 0 {
+        genesis_state(ChainConfig::from_env().state_keeper.fee_account_addr)
+    } else {
+        ensure!(
+            base_block == last_verified_block,
+            "The state can be replayed either from the genesis (--from_block 1) \
+             or right after the last verified block (--from_block {})",
+            *last_verified_block + 1
+        );
+        ZkSyncState::from_acc_map(verified_accounts.clone())
+    };
+    match load_root_hash(storage, base_block).await? {
+        Some(root_hash) => {
+            if let Err(err) = check_root_hash(&state, root_hash) {
+                issues.push(format!(
+                    "Initial state doesn't match block {}: {}",
+                    base_block, err
+                ));
+                return Ok(issues);
+            }
+        }
+        // The genesis block is not required, the genesis state is checked by the first replayed block.
+        None if *base_block == 0 => {}
+        None => bail!("Block {} to start the replay from is missing", base_block),
+    }
+
+    let cached_blocks: HashSet<_> = storage
+        .chain()
+        .block_schema()
+        .get_account_tree_cache_blocks()
+        .await?
+        .into_iter()
+        .collect();
+
+    for block in *from_block..=*to_block {
+        let block = BlockNumber(block);
+        let root_hash = match load_root_hash(storage, block).await? {
+            Some(root_hash) => root_hash,
+            None => {
+                issues.push(format!("Block {} is missing", block));
+                return Ok(issues);
+            }
+        };
+
+        let updates = storage
+            .chain()
+            .state_schema()
+            .load_state_diff_for_block(block)
+            .await?;
+        if let Err(err) = replay_block(&mut state, updates, root_hash) {
+            issues.push(format!("Replay of block {} failed: {}", block, err));
+            return Ok(issues);
+        }
+
+        if cached_blocks.contains(&block) {
+            let cache = load_tree_cache(storage, block).await?;
+            let mut tree = state.get_balance_tree();
+            tree.set_internals(cache);
+            if tree.root_hash() != root_hash {
+                issues.push(format!(
+                    "Root hash calculated from the account tree cache of block {} is 0x{}, while the block has root hash 0x{}",
+                    block,
+                    ff::to_hex(&tree.root_hash()),
+                    ff::to_hex(&root_hash)
+                ));
+            }
+        }
+
+        if block == last_verified_block {
+            issues.extend(compare_verified_state(&state, &verified_accounts, block));
+        }
+
+        if *block % PROGRESS_INTERVAL == 0 {
+            println!("State is checked up to block {}", block);
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Builds the genesis state the same way the server does, so it doesn't depend on the storage.
+fn genesis_state(fee_account_address: Address) -> ZkSyncState {
+    let mut accounts = AccountMap::default();
+    accounts.insert(
+        AccountId(0),
+        Account::default_with_address(&fee_account_address),
+    );
+    let mut nft_storage = Account::default_with_address(&NFT_STORAGE_ACCOUNT_ADDRESS);
+    nft_storage.set_balance(NFT_TOKEN_ID, BigUint::from(MIN_NFT_TOKEN_ID));
+    accounts.insert(NFT_STORAGE_ACCOUNT_ID, nft_storage);
+    ZkSyncState::from_acc_map(accounts)
+}
+
+/// Applies the account updates of the block to the state and checks the resulting root hash.
+fn replay_block(
+    state: &mut ZkSyncState,
+    updates: AccountUpdates,
+    root_hash: Fr,
+) -> Result<(), String> {
+    for (account_id, update) in updates {
+        check_update(state, account_id, &update).map_err(|err| {
+            format!(
+                "update of account {} doesn't match the state: {}",
+                account_id, err
+            )
+        })?;
+        state.apply_account_updates(vec![(account_id, update)]);
+    }
+    check_root_hash(state, root_hash)
+}
+
+fn check_root_hash(state: &ZkSyncState, root_hash: Fr) -> Result<(), String> {
+    if state.root_hash() != root_hash {
+        return Err(format!(
+            "root hash of the state is 0x{}, while the block has root hash 0x{}",
+            ff::to_hex(&state.root_hash()),
+            ff::to_hex(&root_hash)
+        ));
+    }
+    Ok(())
+}
+
+async fn load_root_hash(
+    storage: &mut StorageProcessor<'_>,
+    block: BlockNumber,
+) -> anyhow::Result<Option<Fr>> {
+    let block = storage
+        .chain()
+        .block_schema()
+        .get_storage_block(block)
+        .await?;
+    block
+        .map(|block| {
+            Fr::from_bytes(&block.root_hash).map_err(|err| {
+                format_err!("Unparsable root hash of block {}: {}", block.number, err)
+            })
+        })
+        .transpose()
+}
+
+async fn load_tree_cache(
+    storage: &mut StorageProcessor<'_>,
+    block: BlockNumber,
+) -> anyhow::Result<SparseMerkleTreeSerializableCacheBN256> {
+    storage
+        .chain()
+        .block_schema()
+        .get_account_tree_cache_block(block)
+        .await
+        .map_err(|err| {
+            format_err!(
+                "Unable to load account tree cache of block {}: {}",
+                block,
+                err
+            )
+        })?
+        .ok_or_else(|| format_err!("Account tree cache of block {} is removed", block))
+}
+
+/// Checks that the update can be applied to the current state of the account,
+/// i.e. that all the old values recorded in the update match the state.
+fn check_update(
+    state: &ZkSyncState,
+    account_id: AccountId,
+    update: &AccountUpdate,
+) -> Result<(), String> {
+    let account = state.get_account(account_id);
+    match (update, account) {
+        (AccountUpdate::Create { address, .. }, None) => {
+            if let Some((existing_id, _)) = state.get_account_by_address(address) {
+                return Err(format!(
+                    "address {:?} already belongs to account {}",
+                    address, existing_id
+                ));
+            }
+        }
+        (AccountUpdate::Create { .. }, Some(_)) => {
+            return Err("account already exists".to_string());
+        }
+        (_, None) => return Err("account doesn't exist".to_string()),
+        (AccountUpdate::Delete { address, nonce }, Some(account)) => {
+            if account.address != *address || account.nonce != *nonce {
+                return Err(format!(
+                    "account to delete is {:?} with nonce {}, expected {:?} with nonce {}",
+                    account.address, account.nonce, address, nonce
+                ));
+            }
+        }
+        (
+            AccountUpdate::UpdateBalance {
+                old_nonce,
+                balance_update: (token, old_balance, _),
+                ..
+            },
+            Some(account),
+        ) => {
+            if account.nonce != *old_nonce {
+                return Err(format!(
+                    "nonce is {}, expected {}",
+                    account.nonce, old_nonce
+                ));
+            }
+            let balance = account.get_balance(*token);
+            if balance != *old_balance {
+                return Err(format!(
+                    "balance of token {} is {}, expected {}",
+                    token, balance, old_balance
+                ));
+            }
+        }
+        (
+            AccountUpdate::ChangePubKeyHash {
+                old_pub_key_hash,
+                old_nonce,
+                ..
+            },
+            Some(account),
+        ) => {
+            if account.nonce != *old_nonce {
+                return Err(format!(
+                    "nonce is {}, expected {}",
+                    account.nonce, old_nonce
+                ));
+            }
+            if account.pub_key_hash != *old_pub_key_hash {
+                return Err(format!(
+                    "public key hash is {:?}, expected {:?}",
+                    account.pub_key_hash, old_pub_key_hash
+                ));
+            }
+        }
+        (AccountUpdate::MintNFT { .. }, Some(_)) | (AccountUpdate::RemoveNFT { .. }, Some(_)) => {}
+    }
+
+    Ok(())
+}
+
+/// Compares the replayed state with the state stored in the `accounts` table.
+fn compare_verified_state(
+    state: &ZkSyncState,
+    verified_accounts: &AccountMap,
+    block: BlockNumber,
+) -> Vec<String> {
+    let mut issues = Vec::new();
+    let accounts = state.get_accounts();
+    if accounts.len() != verified_accounts.len() {
+        issues.push(format!(
+            "Verified state of block {} has {} accounts, while the replayed state has {}",
+            block,
+            verified_accounts.len(),
+            accounts.len()
+        ));
+    }
+    for (account_id, account) in accounts {
+        let account_id = AccountId(account_id);
+        if verified_accounts.get(&account_id) != Some(&account) {
+            issues.push(format!(
+                "Account {} in the verified state of block {} doesn't match the replayed state",
+                account_id, block
+            ));
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::{Nonce, TokenId};
+
+    fn deposit_to_new_account(address: Address) -> AccountUpdates {
+        vec![
+            (
+                AccountId(1),
+                AccountUpdate::Create {
+                    address,
+                    nonce: Nonce(0),
+                },
+            ),
+            (
+                AccountId(1),
+                AccountUpdate::UpdateBalance {
+                    old_nonce: Nonce(0),
+                    new_nonce: Nonce(0),
+                    balance_update: (TokenId(0), 0u32.into(), 100u32.into()),
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn replay_checks_root_hash() {
+        let fee_account_address = Address::random();
+        let updates = deposit_to_new_account(Address::random());
+
+        let mut expected = genesis_state(fee_account_address);
+        expected.apply_account_updates(updates.clone());
+        let root_hash = expected.root_hash();
+
+        let mut state = genesis_state(fee_account_address);
+        replay_block(&mut state, updates.clone(), root_hash).unwrap();
+        assert_eq!(state.root_hash(), root_hash);
+
+        // The root hash of the block doesn't match the replayed state.
+        let mut state = genesis_state(fee_account_address);
+        let genesis_root_hash = state.root_hash();
+        let err = replay_block(&mut state, updates, genesis_root_hash).unwrap_err();
+        assert!(err.contains("root hash"), "{}", err);
+
+        // Genesis state doesn't match the one built for another fee account.
+        let state = genesis_state(Address::random());
+        assert!(check_root_hash(&state, genesis_root_hash).is_err());
+    }
+
+    #[test]
+    fn replay_checks_updates() {
+        let mut state = genesis_state(Address::random());
+        let root_hash = state.root_hash();
+        let updates = vec![(
+            AccountId(0),
+            AccountUpdate::UpdateBalance {
+                old_nonce: Nonce(0),
+                new_nonce: Nonce(0),
+                balance_update: (TokenId(0), 5u32.into(), 0u32.into()),
+            },
+        )];
+
+        let err = replay_block(&mut state, updates, root_hash).unwrap_err();
+        assert!(
+            err.contains("balance of token 0 is 0, expected 5"),
+            "{}",
+            err
+        );
+        // The state is left at the first mismatching update.
+        assert_eq!(state.root_hash(), root_hash);
+    }
+}
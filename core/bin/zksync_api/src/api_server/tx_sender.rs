@@ -43,6 +43,7 @@ use crate::{
     api_server::forced_exit_checker::{ForcedExitAccountAgeChecker, ForcedExitChecker},
     core_api_client::CoreApiClient,
     fee_ticker::{ResponseBatchFee, ResponseFee, TokenPriceRequestType},
+    runtime_parameters::RuntimeParametersStore,
     signature_checker::{
         BatchRequest, OrderRequest, RequestData, Toggle2FARequest, TxRequest, VerifiedTx,
         VerifySignatureRequest,
@@ -78,6 +79,9 @@ pub struct TxSender {
     pub current_subsidy_type: String,
     pub max_subsidy_usd: Ratio<BigUint>,
     pub subsidized_ips: HashSet<String>,
+
+    /// Overrides of the config values above which can be changed at runtime.
+    pub runtime_parameters: RuntimeParametersStore,
 }
 
 #[derive(Debug, Error)]
@@ -168,6 +172,7 @@ impl TxSender {
         let max_number_of_transactions_per_batch =
             config.max_number_of_transactions_per_batch as usize;
         let max_number_of_authors_per_batch = config.max_number_of_authors_per_batch as usize;
+        let runtime_parameters = ticker.runtime_parameters().clone();
 
         Self {
             core_api_client,
@@ -187,9 +192,45 @@ impl TxSender {
             current_subsidy_type: config.subsidy_name.clone(),
             max_subsidy_usd: config.max_subsidy_usd(),
             subsidized_ips: config.subsidized_ips.clone().into_iter().collect(),
+            runtime_parameters,
+        }
+    }
+
+    async fn is_fee_free_account(&self, account_id: AccountId) -> bool {
+        match &self.runtime_parameters.read().await.fee_free_accounts {
+            Some(accounts) => accounts.contains(&account_id),
+            None => self.fee_free_accounts.contains(&account_id),
+        }
+    }
+
+    async fn is_subsidized_ip(&self, ip: &str) -> bool {
+        match &self.runtime_parameters.read().await.subsidized_ips {
+            Some(ips) => ips.contains(ip),
+            None => self.subsidized_ips.contains(ip),
         }
     }
 
+    async fn max_subsidy_usd(&self) -> Ratio<BigUint> {
+        self.runtime_parameters
+            .read()
+            .await
+            .max_subsidy_usd()
+            .unwrap_or_else(|| self.max_subsidy_usd.clone())
+    }
+
+    /// Returns the maximum number of transactions and Ethereum signatures per batch.
+    async fn batch_limits(&self) -> (usize, usize) {
+        let parameters = self.runtime_parameters.read().await;
+        (
+            parameters
+                .max_number_of_transactions_per_batch
+                .unwrap_or(self.max_number_of_transactions_per_batch),
+            parameters
+                .max_number_of_authors_per_batch
+                .unwrap_or(self.max_number_of_authors_per_batch),
+        )
+    }
+
     /// If `ForcedExit` has Ethereum siganture (e.g. it's a part of a batch), an actual signer
     /// is initiator, not the target, thus, this function will perform a database query to acquire
     /// the corresponding address.
@@ -418,8 +459,9 @@ impl TxSender {
             .await?;
         let subsidized_already_usd = scaled_big_decimal_to_ratio(subsidized_already)?;
 
-        let result = if self.max_subsidy_usd > subsidized_already_usd {
-            &self.max_subsidy_usd - &subsidized_already_usd >= new_subsidy_usd
+        let max_subsidy_usd = self.max_subsidy_usd().await;
+        let result = if max_subsidy_usd > subsidized_already_usd {
+            &max_subsidy_usd - &subsidized_already_usd >= new_subsidy_usd
         } else {
             false
         };
@@ -435,7 +477,7 @@ impl TxSender {
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<bool, SubmitError> {
         let should_subsidize_ip = if let Some(meta) = extracted_request_metadata {
-            self.subsidized_ips.contains(&meta.ip)
+            self.is_subsidized_ip(&meta.ip).await
         } else {
             false
         };
@@ -490,6 +532,7 @@ impl TxSender {
         Ok(())
     }
 
+    #[tracing::instrument(name = "tx_sender.submit_tx", skip_all, fields(tx_hash = %tx.hash()))]
     pub async fn submit_tx(
         &self,
         tx: ZkSyncTx,
@@ -518,10 +561,10 @@ impl TxSender {
             .get_ethereum_sign_message(token.clone())
             .map(String::into_bytes);
 
-        let is_whitelisted_initiator = tx
-            .account_id()
-            .map(|account_id| self.fee_free_accounts.contains(&account_id))
-            .unwrap_or(false);
+        let is_whitelisted_initiator = match tx.account_id() {
+            Ok(account_id) => self.is_fee_free_account(account_id).await,
+            Err(_) => false,
+        };
 
         let tx_fee_info = if !is_whitelisted_initiator {
             tx.get_fee_info()
@@ -636,6 +679,7 @@ impl TxSender {
         Ok(tx.hash())
     }
 
+    #[tracing::instrument(name = "tx_sender.submit_txs_batch", skip_all, fields(batch_size = txs.len()))]
     pub async fn submit_txs_batch(
         &self,
         txs: Vec<TxWithSignature>,
@@ -648,10 +692,12 @@ impl TxSender {
         if txs.is_empty() {
             return Err(SubmitError::TxAdd(TxAddError::EmptyBatch));
         }
+        let (max_number_of_transactions_per_batch, max_number_of_authors_per_batch) =
+            self.batch_limits().await;
         // Even though this is going to be checked on the Mempool part,
         // we don't want to verify huge batches as long as this operation
         // is expensive.
-        if txs.len() > self.max_number_of_transactions_per_batch {
+        if txs.len() > max_number_of_transactions_per_batch {
             return Err(SubmitError::TxAdd(TxAddError::BatchTooBig));
         }
 
@@ -665,7 +711,7 @@ impl TxSender {
         }
 
         // Same check but in terms of signatures.
-        if eth_signatures.len() > self.max_number_of_authors_per_batch {
+        if eth_signatures.len() > max_number_of_authors_per_batch {
             return Err(SubmitError::TxAdd(TxAddError::EthSignaturesLimitExceeded));
         }
 
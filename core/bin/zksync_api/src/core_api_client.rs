@@ -41,14 +41,13 @@ impl CoreApiClient {
         url: &str,
         request: impl serde::Serialize,
     ) -> anyhow::Result<T> {
-        let response = self
-            .client
-            .post(url)
-            .json(&request)
-            .send()
-            .await?
-            .json()
-            .await?;
+        // Pass the trace context so that the Core continues the trace of the request.
+        let mut request_builder = self.client.post(url).json(&request);
+        for (name, value) in vlog::TraceContext::current().headers() {
+            request_builder = request_builder.header(name, value);
+        }
+
+        let response = request_builder.send().await?.json().await?;
 
         Ok(response)
     }
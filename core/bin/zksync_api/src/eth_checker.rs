@@ -6,8 +6,8 @@ use web3::{contract::Options, types::Address};
 use zksync_contracts::eip1271_contract;
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
 use zksync_types::{
-    tx::EIP1271Signature,
-    {Nonce, PubKeyHash},
+    tx::{EIP1271Signature, Eip712Domain},
+    {Nonce, PubKeyHash, H256},
 };
 
 /// isValidSignature return value according to EIP1271 standard
@@ -17,11 +17,20 @@ pub const EIP1271_SUCCESS_RETURN_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
 #[derive(Clone)]
 pub struct EthereumChecker {
     client: EthereumGateway,
+    /// Domain of the EIP-712 typed data signed by users.
+    eip712_domain: Eip712Domain,
 }
 
 impl EthereumChecker {
-    pub fn new(client: EthereumGateway) -> Self {
-        Self { client }
+    pub fn new(client: EthereumGateway, eip712_domain: Eip712Domain) -> Self {
+        Self {
+            client,
+            eip712_domain,
+        }
+    }
+
+    pub fn eip712_domain(&self) -> &Eip712Domain {
+        &self.eip712_domain
     }
 
     /// Transforms the message into an array expected by EIP-1271 standard.
@@ -41,7 +50,27 @@ impl EthereumChecker {
         signature: EIP1271Signature,
     ) -> Result<bool, anyhow::Error> {
         let sign_message = Self::get_sign_message(message);
+        self.is_eip1271_hash_signature_correct(address, sign_message, signature)
+            .await
+    }
 
+    /// Checks the EIP1271 signature of the EIP-712 typed data, given its signing hash.
+    pub async fn is_eip1271_typed_signature_correct(
+        &self,
+        address: Address,
+        signing_hash: H256,
+        signature: EIP1271Signature,
+    ) -> Result<bool, anyhow::Error> {
+        self.is_eip1271_hash_signature_correct(address, signing_hash.to_fixed_bytes(), signature)
+            .await
+    }
+
+    async fn is_eip1271_hash_signature_correct(
+        &self,
+        address: Address,
+        sign_message: [u8; 32],
+        signature: EIP1271Signature,
+    ) -> Result<bool, anyhow::Error> {
         let call_result = self
             .client
             .call_contract_function(
@@ -121,16 +150,16 @@ mod tests {
             web3::transports::Http::new(web3_urls.first().expect("At least one should exist"))
                 .unwrap();
         let client = EthereumGateway::Direct(ETHDirectClient::new(
-            transport,
+            transport.into(),
             zksync_contract(),
             Default::default(),
-            PrivateKeySigner::new(Default::default()),
+            PrivateKeySigner::new(Default::default()).into(),
             Default::default(),
             0,
             1.0,
         ));
 
-        let eth_checker = EthereumChecker::new(client);
+        let eth_checker = EthereumChecker::new(client, Default::default());
 
         let result = eth_checker
             .is_eip1271_signature_correct(
@@ -42,6 +42,7 @@ use crate::fee_ticker::{
     },
     validator::{watcher::UniswapTokenWatcher, MarketUpdater},
 };
+use crate::runtime_parameters::RuntimeParametersStore;
 use crate::utils::token_db_cache::TokenDBCache;
 use std::convert::TryFrom;
 use std::iter::FromIterator;
@@ -196,6 +197,8 @@ pub struct FeeTicker {
     info: Box<dyn FeeTickerInfo>,
     config: TickerConfig,
     validator: FeeTokenValidator,
+    /// Overrides of the `config` values which can be changed at runtime.
+    runtime_parameters: RuntimeParametersStore,
 }
 
 const CPK_CREATE2_FEE_TYPE: OutputFeeType = OutputFeeType::ChangePubKey(
@@ -252,6 +255,7 @@ impl FeeTicker {
             info,
             config,
             validator,
+            runtime_parameters: RuntimeParametersStore::new(),
         }
     }
 
@@ -260,6 +264,7 @@ impl FeeTicker {
         config: zksync_config::TickerConfig,
         max_blocks_to_aggregate: u32,
         connection_pool: ConnectionPool,
+        runtime_parameters: RuntimeParametersStore,
     ) -> Self {
         let cache = (connection_pool, TokenDBCache::new());
         let ticker_config = TickerConfig {
@@ -279,7 +284,15 @@ impl FeeTicker {
             BigDecimal::try_from(config.liquidity_volume).expect("Valid f64 for decimal"),
             HashSet::from_iter(config.unconditionally_valid_tokens),
         );
-        Self::new(info, ticker_config, validator)
+        Self {
+            runtime_parameters,
+            ..Self::new(info, ticker_config, validator)
+        }
+    }
+
+    /// Returns the runtime parameters used by the ticker, so they can be shared with other components.
+    pub fn runtime_parameters(&self) -> &RuntimeParametersStore {
+        &self.runtime_parameters
     }
 }
 
@@ -291,6 +304,14 @@ impl FeeTicker {
         gas_price * BigUint::from(130u32) / BigUint::from(100u32)
     }
 
+    async fn scale_fee_coefficient(&self) -> Ratio<BigUint> {
+        self.runtime_parameters
+            .read()
+            .await
+            .scale_fee_coefficient()
+            .unwrap_or_else(|| self.config.scale_fee_coefficient.clone())
+    }
+
     pub async fn get_token_price(
         &self,
         token: TokenLike,
@@ -348,7 +369,7 @@ impl FeeTicker {
                 | OutputFeeType::MintNFT
                 | OutputFeeType::Swap
         ) {
-            normal_gas_fee *= self.config.scale_fee_coefficient.clone();
+            normal_gas_fee *= self.scale_fee_coefficient().await;
         }
 
         let normal_fee = Fee::new(
@@ -457,6 +478,7 @@ impl FeeTicker {
             &self.config.subsidy_cpk_price_usd / denom_part
         };
 
+        let scale_fee_coefficient = self.scale_fee_coefficient().await;
         for (tx_type, recipient) in txs {
             let (output_fee_type, gas_tx_amount, op_chunks) =
                 self.gas_tx_amount(tx_type, recipient).await?;
@@ -468,7 +490,7 @@ impl FeeTicker {
                     | OutputFeeType::Swap
                     | OutputFeeType::MintNFT
             ) {
-                self.config.scale_fee_coefficient.clone() * gas_tx_amount
+                scale_fee_coefficient.clone() * gas_tx_amount
             } else {
                 gas_tx_amount.into()
             };
@@ -530,10 +552,11 @@ impl FeeTicker {
     pub async fn token_usd_risk(&self, token: &Token) -> anyhow::Result<Ratio<BigUint>> {
         let start = Instant::now();
         let token_risk_factor = self
-            .config
-            .tokens_risk_factors
-            .get(&token.id)
-            .cloned()
+            .runtime_parameters
+            .read()
+            .await
+            .token_risk_factor(token.id)
+            .or_else(|| self.config.tokens_risk_factors.get(&token.id).cloned())
             .unwrap_or_else(|| Ratio::from_integer(1u32.into()));
 
         let token_price_usd = self
@@ -4,6 +4,7 @@ pub mod api_server;
 pub mod core_api_client;
 pub mod eth_checker;
 pub mod fee_ticker;
+pub mod runtime_parameters;
 pub mod signature_checker;
 pub mod tx_error;
 pub mod utils;
@@ -0,0 +1,229 @@
+//! Parameters of the API server which can be changed without restarting it.
+//!
+//! Values are stored in the `runtime_parameters` table, and every change of this table is announced
+//! through the `RUNTIME_PARAMETERS_CHANNEL`. All the API nodes listen to this channel and reload the
+//! parameters once notified. Parameters which are not set in the database fall back to the values
+//! from the `CommonApiConfig` / `TickerConfig`.
+//!
+//! Parameters can be changed with a plain SQL query, e.g.:
+//!
+//! ```sql
+//! INSERT INTO runtime_parameters (name, value) VALUES ('scale_fee_percent', '120')
+//! ON CONFLICT (name) DO UPDATE SET value = EXCLUDED.value, updated_at = now();
+//! ```
+
+// Built-in uses
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+// External uses
+use num::{rational::Ratio, BigUint};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::{RwLock, RwLockReadGuard},
+    task::JoinHandle,
+};
+// Workspace uses
+use zksync_storage::{
+    config::{records::RuntimeParameter, RUNTIME_PARAMETERS_CHANNEL},
+    listener::StorageListener,
+    ConnectionPool,
+};
+use zksync_types::{AccountId, TokenId};
+use zksync_utils::{scaled_u64_to_ratio, UnsignedRatioSerializeAsDecimal};
+
+/// Interval between reconnection attempts if the listener connection can't be established.
+const LISTENER_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Overrides of the config values set in the database.
+/// `None` means that the value from the config should be used.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuntimeParameters {
+    /// List of account IDs that do not have to pay fees for operations.
+    pub fee_free_accounts: Option<HashSet<AccountId>>,
+    /// The IPs which have their CPK (CREATE2) subsidized.
+    pub subsidized_ips: Option<HashSet<String>>,
+    /// Maximum subsidized amount for current subsidy type scaled by `SUBSIDY_USD_AMOUNTS_SCALE`.
+    pub max_subsidy_usd_scaled: Option<u64>,
+    /// Coefficient for scaling all fees in percent.
+    pub scale_fee_percent: Option<u32>,
+    pub max_number_of_transactions_per_batch: Option<usize>,
+    pub max_number_of_authors_per_batch: Option<usize>,
+    /// Risk factors of tokens, e.g. `{ "1": 1.5 }`.
+    pub tokens_risk_factors: Option<HashMap<TokenId, f64>>,
+}
+
+impl RuntimeParameters {
+    /// Builds the parameters from the database rows. Fails if any of parameters is unknown
+    /// or has an unexpected type.
+    pub fn from_records(records: Vec<RuntimeParameter>) -> anyhow::Result<Self> {
+        let object = records
+            .into_iter()
+            .map(|record| (record.name, record.value))
+            .collect::<serde_json::Map<_, _>>();
+
+        Ok(serde_json::from_value(serde_json::Value::Object(object))?)
+    }
+
+    pub fn max_subsidy_usd(&self) -> Option<Ratio<BigUint>> {
+        self.max_subsidy_usd_scaled.map(scaled_u64_to_ratio)
+    }
+
+    pub fn scale_fee_coefficient(&self) -> Option<Ratio<BigUint>> {
+        self.scale_fee_percent
+            .map(|percent| Ratio::new(BigUint::from(percent), BigUint::from(100u32)))
+    }
+
+    pub fn token_risk_factor(&self, token_id: TokenId) -> Option<Ratio<BigUint>> {
+        let risk_factor = self.tokens_risk_factors.as_ref()?.get(&token_id)?;
+        UnsignedRatioSerializeAsDecimal::deserialize_from_str_with_dot(&risk_factor.to_string())
+            .ok()
+    }
+}
+
+/// Shared storage of the current runtime parameters.
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeParametersStore {
+    parameters: Arc<RwLock<RuntimeParameters>>,
+}
+
+impl RuntimeParametersStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn read(&self) -> RwLockReadGuard<'_, RuntimeParameters> {
+        self.parameters.read().await
+    }
+
+    pub async fn set(&self, parameters: RuntimeParameters) {
+        *self.parameters.write().await = parameters;
+    }
+
+    /// Loads the actual parameters from the database.
+    /// If the stored parameters are malformed, the current ones are kept.
+    pub async fn reload(&self, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let records = pool
+            .access_storage()
+            .await?
+            .config_schema()
+            .load_runtime_parameters()
+            .await?;
+        let parameters = RuntimeParameters::from_records(records)?;
+
+        if *self.read().await != parameters {
+            vlog::info!("Runtime parameters updated: {:?}", parameters);
+            self.set(parameters).await;
+        }
+        Ok(())
+    }
+}
+
+async fn reload_parameters(store: &RuntimeParametersStore, pool: &ConnectionPool) {
+    if let Err(err) = store.reload(pool).await {
+        vlog::error!("Failed to reload runtime parameters: {}", err);
+    }
+}
+
+/// Keeps the runtime parameters in the store up to date with the database.
+#[must_use]
+pub fn run_runtime_parameters_updater(
+    pool: ConnectionPool,
+    store: RuntimeParametersStore,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut listener = loop {
+            match StorageListener::connect().await {
+                Ok(mut listener) => match listener.listen(RUNTIME_PARAMETERS_CHANNEL).await {
+                    Ok(()) => break listener,
+                    Err(err) => vlog::warn!("Failed to listen for runtime parameters: {}", err),
+                },
+                Err(err) => vlog::warn!("Failed to connect the storage listener: {}", err),
+            }
+            tokio::time::sleep(LISTENER_RETRY_INTERVAL).await;
+        };
+
+        // Parameters could've been changed before the listener was set up.
+        reload_parameters(&store, &pool).await;
+
+        loop {
+            match listener.try_recv().await {
+                Ok(Some(_)) => reload_parameters(&store, &pool).await,
+                Ok(None) => {
+                    // Connection was lost and will be restored on the next call,
+                    // but notifications sent meanwhile are lost.
+                    vlog::warn!("Runtime parameters listener connection was lost");
+                    reload_parameters(&store, &pool).await;
+                }
+                Err(err) => {
+                    vlog::warn!("Runtime parameters listener error: {}", err);
+                    tokio::time::sleep(LISTENER_RETRY_INTERVAL).await;
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn record(name: &str, value: serde_json::Value) -> RuntimeParameter {
+        RuntimeParameter {
+            name: name.into(),
+            value,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn parameters_from_records() {
+        let parameters = RuntimeParameters::from_records(vec![
+            record("fee_free_accounts", serde_json::json!([4078, 387])),
+            record("scale_fee_percent", serde_json::json!(150)),
+            record("tokens_risk_factors", serde_json::json!({ "1": 1.5 })),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            parameters.fee_free_accounts,
+            Some(vec![AccountId(4078), AccountId(387)].into_iter().collect())
+        );
+        assert_eq!(
+            parameters.scale_fee_coefficient(),
+            Some(Ratio::new(BigUint::from(3u32), BigUint::from(2u32)))
+        );
+        assert_eq!(
+            parameters.token_risk_factor(TokenId(1)),
+            Some(Ratio::new(BigUint::from(3u32), BigUint::from(2u32)))
+        );
+        assert_eq!(parameters.token_risk_factor(TokenId(0)), None);
+        assert_eq!(parameters.subsidized_ips, None);
+        assert_eq!(parameters.max_subsidy_usd(), None);
+
+        assert_eq!(
+            RuntimeParameters::from_records(vec![]).unwrap(),
+            RuntimeParameters::default()
+        );
+    }
+
+    #[test]
+    fn malformed_records() {
+        // Unknown parameter.
+        assert!(RuntimeParameters::from_records(vec![record(
+            "unknown_parameter",
+            serde_json::json!(1)
+        )])
+        .is_err());
+        // Incorrect type.
+        assert!(RuntimeParameters::from_records(vec![record(
+            "scale_fee_percent",
+            serde_json::json!("fast")
+        )])
+        .is_err());
+    }
+}
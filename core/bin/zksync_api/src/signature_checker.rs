@@ -18,8 +18,11 @@ use tokio::task::JoinHandle;
 // Workspace uses
 use zksync_eth_client::EthereumGateway;
 use zksync_types::{
-    tx::{EthBatchSignData, EthSignData, TxEthSignature},
-    Address, Order, SignedZkSyncTx, Token, ZkSyncTx,
+    tx::{
+        Eip712Batch, Eip712Domain, Eip712TypedStructure, EthBatchSignData, EthSignData,
+        TxEthSignature,
+    },
+    Address, Order, SignedZkSyncTx, Token, ZkSyncTx, H256,
 };
 // Local uses
 use crate::{eth_checker::EthereumChecker, tx_error::TxAddError};
@@ -47,13 +50,16 @@ pub struct VerifiedTx(TxVariant);
 impl VerifiedTx {
     /// Checks the (batch of) transaction(s) correctness by verifying its
     /// Ethereum signature (if required) and `ZKSync` signature.
+    ///
+    /// Transactions are checked for correctness first, so the Ethereum signature
+    /// is only checked for the data that can be represented as the signed message.
     pub async fn verify(
         request_data: RequestData,
         eth_checker: &EthereumChecker,
     ) -> Result<Self, TxAddError> {
-        verify_eth_signature(&request_data, eth_checker).await?;
         let mut tx_variant = request_data.get_tx_variant();
         verify_tx_correctness(&mut tx_variant)?;
+        verify_eth_signature(&request_data, eth_checker).await?;
 
         Ok(Self(tx_variant))
     }
@@ -120,9 +126,19 @@ async fn verify_eth_signature(
             }
         }
         RequestData::Order(request) => {
+            let signing_hash = if request.sign_data.signature.is_eip712() {
+                let signing_hash = request
+                    .order
+                    .signing_hash(eth_checker.eip712_domain())
+                    .map_err(|_| TxAddError::IncorrectEthSignature)?;
+                Some(signing_hash)
+            } else {
+                None
+            };
             let signature_correct = verify_ethereum_signature(
                 &request.sign_data.signature,
                 &request.sign_data.message,
+                signing_hash,
                 request.sender,
                 eth_checker,
             )
@@ -135,6 +151,7 @@ async fn verify_eth_signature(
             let signature_correct = verify_ethereum_signature(
                 &request.sign_data.signature,
                 &request.sign_data.message,
+                None,
                 request.sender,
                 eth_checker,
             )
@@ -150,9 +167,12 @@ async fn verify_eth_signature(
 
 /// Given a single Ethereum signature and a message, checks that it
 /// was signed by an expected address.
+/// EIP-712 signatures are checked against the `signing_hash` of the typed data instead
+/// of the message, and are considered incorrect if the data can't be signed as typed.
 async fn verify_ethereum_signature(
     eth_signature: &TxEthSignature,
     message: &[u8],
+    signing_hash: Option<H256>,
     sender_address: Address,
     eth_checker: &EthereumChecker,
 ) -> bool {
@@ -166,6 +186,20 @@ async fn verify_ethereum_signature(
                 .await
                 .expect("Unable to check EIP1271 signature")
         }
+        TxEthSignature::EIP712Signature(packed_signature) => match signing_hash {
+            Some(signing_hash) => packed_signature.signature_recover_signer_from_hash(signing_hash),
+            None => return false,
+        },
+        TxEthSignature::EIP712EIP1271Signature(signature) => {
+            let signing_hash = match signing_hash {
+                Some(signing_hash) => signing_hash,
+                None => return false,
+            };
+            return eth_checker
+                .is_eip1271_typed_signature_correct(sender_address, signing_hash, signature.clone())
+                .await
+                .expect("Unable to check EIP1271 signature");
+        }
     };
     match signer_account {
         Ok(address) => address == sender_address,
@@ -202,15 +236,29 @@ async fn verify_eth_signature_single_tx(
     // Check the signature.
     if let Some(sign_data) = &tx.eth_sign_data {
         let signature = &sign_data.signature;
-        let mut signature_correct =
-            verify_ethereum_signature(signature, &sign_data.message, sender_address, eth_checker)
-                .await;
-        if !signature_correct {
+        let signing_hash = if signature.is_eip712() {
+            let signing_hash = tx
+                .eip712_signing_hash(eth_checker.eip712_domain())
+                .map_err(|_| TxAddError::IncorrectEthSignature)?;
+            Some(signing_hash)
+        } else {
+            None
+        };
+        let mut signature_correct = verify_ethereum_signature(
+            signature,
+            &sign_data.message,
+            signing_hash,
+            sender_address,
+            eth_checker,
+        )
+        .await;
+        if !signature_correct && !signature.is_eip712() {
             let old_message = tx.get_old_ethereum_sign_message(token);
             if let Some(message) = old_message {
                 signature_correct = verify_ethereum_signature(
                     signature,
                     message.as_bytes(),
+                    None,
                     sender_address,
                     eth_checker,
                 )
@@ -245,6 +293,16 @@ async fn verify_eth_signature_txs_batch(
         )),
         false => None,
     };
+    let signing_hash = if batch_sign_data.signatures.iter().any(|s| s.is_eip712()) {
+        // Batches that can't be signed as typed data are checked against the message only.
+        Eip712Batch::new(txs.iter().map(|tx| &tx.tx))
+            .ok()
+            .map(|batch| batch.signing_hash(eth_checker.eip712_domain()))
+            .transpose()
+            .map_err(|_| TxAddError::IncorrectEthSignature)?
+    } else {
+        None
+    };
 
     for sender in senders {
         if signers.contains(sender) {
@@ -260,15 +318,17 @@ async fn verify_eth_signature_txs_batch(
             let mut signature_correct = verify_ethereum_signature(
                 signature,
                 &batch_sign_data.message,
+                signing_hash,
                 *sender,
                 eth_checker,
             )
             .await;
-            if !signature_correct {
+            if !signature_correct && !signature.is_eip712() {
                 if let Some(old_message) = &old_message {
                     signature_correct = verify_ethereum_signature(
                         signature,
                         old_message.as_slice(),
+                        None,
                         *sender,
                         eth_checker,
                     )
@@ -379,9 +439,10 @@ impl RequestData {
 /// See the module documentation for details.
 pub fn start_sign_checker(
     client: EthereumGateway,
+    eip712_domain: Eip712Domain,
     input: mpsc::Receiver<VerifySignatureRequest>,
 ) -> JoinHandle<()> {
-    let eth_checker = EthereumChecker::new(client);
+    let eth_checker = EthereumChecker::new(client, eip712_domain);
 
     /// Basically it receives the requests through the channel and verifies signatures,
     /// notifying the request sender about the check result.
@@ -400,3 +461,52 @@ pub fn start_sign_checker(
     }
     tokio::spawn(checker_routine(input, eth_checker))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use num::BigUint;
+    use zksync_eth_client::clients::mock::MockEthereum;
+    use zksync_types::{tx::PackedEthSignature, AccountId, Nonce, TokenId, TokenKind, Transfer};
+
+    /// Checks that the transaction with an amount which doesn't fit into `uint256`
+    /// is rejected before its EIP-712 signing hash is computed.
+    #[tokio::test]
+    async fn out_of_range_amount_is_rejected() {
+        let eth_checker = EthereumChecker::new(
+            EthereumGateway::Mock(MockEthereum::default()),
+            Eip712Domain::new(9, Address::random()),
+        );
+
+        let sender = Address::random();
+        let transfer = Transfer::new(
+            AccountId(1),
+            sender,
+            Address::random(),
+            TokenId(0),
+            BigUint::from(1u32) << 300,
+            0u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        );
+        let signature = PackedEthSignature::sign_raw(&H256::random(), &H256::random()).unwrap();
+        let tx = SignedZkSyncTx {
+            tx: ZkSyncTx::from(transfer),
+            eth_sign_data: Some(EthSignData {
+                signature: TxEthSignature::EIP712Signature(signature),
+                message: Vec::new(),
+            }),
+            created_at: Utc::now(),
+        };
+        let request = RequestData::Tx(TxRequest {
+            tx,
+            sender,
+            token: Token::new(TokenId(0), Address::zero(), "ETH", 18, TokenKind::ERC20),
+        });
+
+        let result = VerifiedTx::verify(request, &eth_checker).await;
+        assert!(matches!(result, Err(TxAddError::IncorrectTx(_))));
+    }
+}
@@ -15,7 +15,8 @@ async fn main() {
             &eth_client_config,
             &eth_sender_config,
             contracts.contract_addr,
-        ),
+        )
+        .await,
         eth_watcher_config.check_interval(),
         eth_watcher_config.retry_delay(),
         eth_watcher_config.request_timeout(),
@@ -5,6 +5,8 @@ use futures::channel::mpsc::{Receiver, Sender};
 use futures::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use tokio::{task::JoinHandle, time};
+use tracing::Instrument;
+use vlog::TraceContext;
 use zksync_crypto::Fr;
 use zksync_types::block::IncompleteBlock;
 // Workspace uses
@@ -31,6 +33,12 @@ pub struct BlockCommitRequest {
     pub block: IncompleteBlock,
     pub block_metadata: BlockMetadata,
     pub accounts_updated: AccountUpdates,
+    /// Context of the trace in which the block was sealed.
+    #[serde(skip)]
+    pub trace_context: TraceContext,
+    /// Contexts of the traces of the transactions included into the block.
+    #[serde(skip)]
+    pub tx_trace_contexts: Vec<TraceContext>,
 }
 
 #[derive(Clone, Debug)]
@@ -61,12 +69,21 @@ async fn handle_new_commit_task(
     while let Some(request) = rx_for_ops.next().await {
         match request {
             CommitRequest::SealIncompleteBlock((block_commit_request, applied_updates_req)) => {
+                let span = tracing::info_span!(
+                    "committer.seal_incomplete_block",
+                    block_number = *block_commit_request.block.block_number
+                );
+                block_commit_request.trace_context.attach_to(&span);
+                for trace_context in &block_commit_request.tx_trace_contexts {
+                    trace_context.link_to(&span);
+                }
                 seal_incomplete_block(
                     block_commit_request,
                     applied_updates_req,
                     &pool,
                     &mut mempool_req_sender,
                 )
+                .instrument(span)
                 .await;
             }
             CommitRequest::PendingBlock((pending_block, applied_updates_req)) => {
@@ -154,6 +171,8 @@ async fn seal_incomplete_block(
         block,
         block_metadata,
         accounts_updated,
+        tx_trace_contexts,
+        ..
     } = block_commit_request;
 
     let mut storage = pool
@@ -218,6 +237,9 @@ async fn seal_incomplete_block(
         .await
         .expect("Unable to commit DB transaction");
 
+    // Operations of the block are sent to Ethereum later, their spans are linked to the transactions as well.
+    vlog::remember_block_traces(*block_number, tx_trace_contexts);
+
     metrics::histogram!("committer.seal_incomplete_block", start.elapsed());
 }
 
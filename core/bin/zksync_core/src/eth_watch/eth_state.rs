@@ -1,8 +1,8 @@
 // Built-in deps
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 // External uses
 // Workspace deps
-use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId};
+use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId, H256};
 // Local deps
 use super::received_ops::ReceivedPriorityOp;
 
@@ -39,6 +39,10 @@ pub struct ETHState {
     new_tokens: Vec<NewTokenEvent>,
     /// List of events denoting registered factories for NFT withdrawing
     register_nft_factory_events: Vec<RegisterNFTFactoryEvent>,
+    /// Hashes of the recent Ethereum blocks processed by the watcher, by block number.
+    /// Used to detect the reorgs: if the hash of the block has changed, the events from it
+    /// (and all the subsequent blocks) may no longer exist.
+    block_hashes: BTreeMap<u64, H256>,
 }
 
 impl ETHState {
@@ -49,6 +53,7 @@ impl ETHState {
         priority_queue: HashMap<SerialId, ReceivedPriorityOp>,
         new_tokens: Vec<NewTokenEvent>,
         register_nft_factory_events: Vec<RegisterNFTFactoryEvent>,
+        block_hashes: BTreeMap<u64, H256>,
     ) -> Self {
         assert!(
             last_ethereum_block_backup <= last_ethereum_block,
@@ -67,6 +72,7 @@ impl ETHState {
             priority_queue,
             new_tokens,
             register_nft_factory_events,
+            block_hashes,
         }
     }
 
@@ -90,6 +96,10 @@ impl ETHState {
         &self.new_tokens
     }
 
+    pub fn block_hashes(&self) -> &BTreeMap<u64, H256> {
+        &self.block_hashes
+    }
+
     pub fn next_priority_op_id(&self) -> SerialId {
         self.next_priority_op_id
     }
@@ -98,6 +108,14 @@ impl ETHState {
         self.last_ethereum_block = self.last_ethereum_block_backup;
     }
 
+    /// Moves the watcher back to the given block after the reorg, so the blocks after it are processed again.
+    /// Hashes of the reorganized blocks are forgotten.
+    pub fn rewind_to(&mut self, block_number: u64) {
+        self.last_ethereum_block = self.last_ethereum_block.min(block_number);
+        self.last_ethereum_block_backup = self.last_ethereum_block_backup.min(block_number);
+        self.block_hashes.split_off(&(block_number + 1));
+    }
+
     #[cfg(test)]
     pub(crate) fn last_ethereum_block_backup(&self) -> u64 {
         self.last_ethereum_block_backup
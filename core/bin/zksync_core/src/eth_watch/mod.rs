@@ -2,29 +2,37 @@
 //! such as PriorityQueue events or NewToken events.
 //! New events are accepted to the zkSync network once they have the sufficient amount of confirmations.
 //!
-//! Poll interval is configured using the `ETH_POLL_INTERVAL` constant.
+//! If the Ethereum node is accessed via WebSocket or IPC, the node is polled once a new block or a new
+//! priority operation log is received via subscription. Otherwise (or if the subscription is lost),
+//! the node is polled within the interval configured using the `ETH_WATCH_ETH_NODE_POLL_INTERVAL` variable.
 //! Number of confirmations is configured using the `CONFIRMATIONS_FOR_ETH_EVENT` environment variable.
+//!
+//! The watcher keeps the hashes of the recent Ethereum blocks to detect reorgs. Unconfirmed priority
+//! operations from the reorganized blocks are retracted from the mempool. Reorgs affecting the blocks
+//! with already confirmed events can't be reverted automatically, so the watcher stops processing
+//! the priority operations until the operator resolves the situation and restarts the node.
 
 // Built-in deps
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::{Duration, Instant};
 
 // External uses
 use futures::{
     channel::{mpsc, oneshot},
-    SinkExt, StreamExt,
+    stream, SinkExt, StreamExt, TryStreamExt,
 };
 use thiserror::Error;
 
 pub use client::{get_web3_block_number, EthHttpClient};
 use itertools::Itertools;
 use tokio::{task::JoinHandle, time};
-use web3::types::BlockNumber;
+use web3::types::{BlockNumber, FilterBuilder};
 
 use zksync_config::{ContractsConfig, ETHWatchConfig};
+use zksync_contracts::zksync_contract;
 use zksync_crypto::params::PRIORITY_EXPIRATION;
-use zksync_eth_client::ethereum_gateway::EthereumGateway;
-use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId};
+use zksync_eth_client::{ethereum_gateway::EthereumGateway, new_heads::NewHeads};
+use zksync_types::{Address, NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId, H256};
 
 // Local deps
 use self::{client::EthClient, eth_state::ETHState, received_ops::sift_outdated_ops};
@@ -41,6 +49,12 @@ mod tests;
 /// before repeating the request.
 const RATE_LIMIT_DELAY: Duration = Duration::from_secs(30);
 
+/// Amount of blocks beyond the confirmation depth for which the hashes are kept to detect reorgs.
+const REORG_DETECTION_DEPTH: u64 = 64;
+
+/// Maximum amount of the block hashes requested from the Ethereum node concurrently.
+const MAX_CONCURRENT_BLOCK_HASH_REQUESTS: usize = 16;
+
 /// Ethereum Watcher operating mode.
 ///
 /// Normally Ethereum watcher will always poll the Ethereum node upon request,
@@ -48,12 +62,16 @@ const RATE_LIMIT_DELAY: Duration = Duration::from_secs(30);
 /// often. Thus, upon receiving the order to limit amount of request, Ethereum
 /// watcher goes into "backoff" mode in which polling is disabled for a
 /// certain amount of time.
+/// After a reorg of the blocks with already confirmed events the watcher is halted.
 #[derive(Debug)]
 pub enum WatcherMode {
     /// ETHWatcher operates normally.
     Working,
     /// Polling is currently disabled.
     Backoff(Instant),
+    /// Polling is disabled until the node is restarted, since the confirmed priority operations
+    /// may no longer exist on L1.
+    Halted,
 }
 
 #[derive(Debug)]
@@ -77,6 +95,15 @@ fn is_missing_priority_op_error(error: &anyhow::Error) -> bool {
     error.is::<MissingPriorityOpError>()
 }
 
+/// Reorg of the Ethereum chain detected by the watcher.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct L1Reorg {
+    /// The latest processed block which was not reorganized.
+    fork_block: u64,
+    /// Whether the reorg affected the blocks with the events that were already accepted as confirmed.
+    deep: bool,
+}
+
 pub struct EthWatch<W: EthClient> {
     client: W,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
@@ -198,11 +225,144 @@ impl<W: EthClient> EthWatch<W> {
             priority_queue,
             new_tokens,
             register_nft_factory_events,
+            updated_state.block_hashes().clone(),
         );
         self.set_new_state(new_state);
         Ok(())
     }
 
+    /// Checks whether the processed blocks are still a part of the chain.
+    ///
+    /// Only the latest known block is requested if there is no reorg, since its hash depends on all
+    /// the previous blocks. Otherwise, the known blocks are checked one by one to find the fork point.
+    async fn detect_reorg(&self) -> anyhow::Result<Option<L1Reorg>> {
+        let block_hashes = self.eth_state.block_hashes();
+        let mut fork_block = None;
+        for (&block_number, &hash) in block_hashes.iter().rev() {
+            if self.client.block_hash(block_number).await? == Some(hash) {
+                fork_block = Some(block_number);
+                break;
+            }
+        }
+
+        let last_known_block = block_hashes.keys().next_back().copied();
+        if fork_block == last_known_block {
+            return Ok(None);
+        }
+        // If all the known blocks were reorganized, the fork point is unknown and the reorg
+        // is considered to start right before the oldest known block.
+        let fork_block = fork_block.unwrap_or_else(|| {
+            block_hashes
+                .keys()
+                .next()
+                .map_or(0, |block_number| block_number.saturating_sub(1))
+        });
+        let last_confirmed_block = self
+            .eth_state
+            .last_ethereum_block()
+            .saturating_sub(self.number_of_confirmations_for_event);
+
+        Ok(Some(L1Reorg {
+            fork_block,
+            deep: fork_block < last_confirmed_block,
+        }))
+    }
+
+    /// Retracts the unconfirmed priority operations from the reorganized blocks and moves the watcher
+    /// back to the fork point, so the events from the new chain are processed.
+    /// The watcher is halted if the reorg is deep, and no operations are retracted in this case.
+    async fn handle_reorg(&mut self, reorg: L1Reorg) -> anyhow::Result<()> {
+        if reorg.deep {
+            let affected_ops: Vec<_> = self
+                .eth_state
+                .priority_queue()
+                .iter()
+                .filter(|(_, op)| op.as_ref().eth_block > reorg.fork_block)
+                .map(|(serial_id, _)| *serial_id)
+                .sorted()
+                .collect();
+            vlog::error!(
+                "Ethereum reorg deeper than {} confirmations: blocks after {} were reorganized, \
+                while events up to block {} were accepted. Confirmed priority operations {:?} may no longer exist",
+                self.number_of_confirmations_for_event,
+                reorg.fork_block,
+                self.eth_state
+                    .last_ethereum_block()
+                    .saturating_sub(self.number_of_confirmations_for_event),
+                affected_ops
+            );
+            vlog::error!(
+                "Priority operations processing is halted, restart the node once the situation is resolved"
+            );
+            metrics::increment_counter!("eth_watcher.deep_reorg");
+            self.mode = WatcherMode::Halted;
+            return Ok(());
+        }
+
+        vlog::warn!(
+            "Ethereum reorg: blocks after {} were reorganized",
+            reorg.fork_block
+        );
+        metrics::increment_counter!("eth_watcher.reorg");
+
+        // Operations which are still present in the new chain will be added again once it's processed.
+        let retracted_ops: Vec<_> = self
+            .eth_state
+            .unconfirmed_queue()
+            .iter()
+            .filter(|op| op.eth_block > reorg.fork_block)
+            .map(|op| op.serial_id)
+            .collect();
+        if !retracted_ops.is_empty() {
+            vlog::info!(
+                "Retracting unconfirmed priority operations {:?}",
+                retracted_ops
+            );
+            let (sender, receiver) = oneshot::channel();
+            self.mempool_tx_sender
+                .send(MempoolTransactionRequest::RetractPriorityOps(
+                    retracted_ops,
+                    sender,
+                ))
+                .await?;
+            receiver.await.expect("Mempool actor was dropped")?;
+        }
+
+        self.eth_state.rewind_to(reorg.fork_block);
+        Ok(())
+    }
+
+    /// Returns the hashes of the recent blocks up to the given one, fetching the ones which are not known yet.
+    async fn update_block_hashes(
+        &self,
+        current_ethereum_block: u64,
+    ) -> anyhow::Result<BTreeMap<u64, H256>> {
+        let first_block = current_ethereum_block
+            .saturating_sub(self.number_of_confirmations_for_event + REORG_DETECTION_DEPTH);
+        let mut block_hashes = self
+            .eth_state
+            .block_hashes()
+            .clone()
+            .split_off(&first_block);
+
+        let next_block = block_hashes
+            .keys()
+            .next_back()
+            .map_or(first_block, |block_number| block_number + 1);
+        let block_numbers = next_block..=current_ethereum_block;
+        let hashes: Vec<_> = stream::iter(block_numbers.clone())
+            .map(|block_number| self.client.block_hash(block_number))
+            .buffered(MAX_CONCURRENT_BLOCK_HASH_REQUESTS)
+            .try_collect()
+            .await?;
+        for (block_number, hash) in block_numbers.zip(hashes) {
+            if let Some(hash) = hash {
+                block_hashes.insert(block_number, hash);
+            }
+        }
+        Ok(block_hashes)
+    }
+
     async fn restore_state_from_eth(&mut self, last_ethereum_block: u64) -> anyhow::Result<()> {
         let new_state = self
             .update_eth_state(last_ethereum_block, PRIORITY_EXPIRATION)
@@ -254,6 +414,8 @@ impl<W: EthClient> EthWatch<W> {
             )
             .await?;
 
+        let block_hashes = self.update_block_hashes(current_ethereum_block).await?;
+
         let mut new_priority_op_ids: Vec<_> = priority_queue_map.keys().cloned().collect();
         new_priority_op_ids.sort_unstable();
         vlog::debug!(
@@ -295,6 +457,7 @@ impl<W: EthClient> EthWatch<W> {
             priority_queue_map,
             new_tokens,
             new_register_nft_factory_events,
+            block_hashes,
         );
         Ok(state)
     }
@@ -332,7 +495,20 @@ impl<W: EthClient> EthWatch<W> {
     async fn poll_eth_node(&mut self) -> anyhow::Result<()> {
         let start = Instant::now();
         let last_block_number = self.client.block_number().await?;
+        if last_block_number < self.eth_state.last_ethereum_block() {
+            // The node is behind the one that was polled before, its blocks can't be compared
+            // with the known ones.
+            return Ok(());
+        }
 
+        // A reorg may replace the latest blocks without increasing the chain height,
+        // so the known blocks are checked on every poll.
+        if let Some(reorg) = self.detect_reorg().await? {
+            self.handle_reorg(reorg).await?;
+            if matches!(self.mode, WatcherMode::Halted) {
+                return Ok(());
+            }
+        }
         if last_block_number > self.eth_state.last_ethereum_block() {
             self.process_new_blocks(last_block_number).await?;
         }
@@ -367,6 +543,7 @@ impl<W: EthClient> EthWatch<W> {
                     false
                 }
             }
+            WatcherMode::Halted => false,
         }
     }
 
@@ -456,6 +633,15 @@ pub fn start_eth_watch(
     eth_watcher_config: &ETHWatchConfig,
     mempool_req_sender: mpsc::Sender<MempoolTransactionRequest>,
 ) -> JoinHandle<()> {
+    let mut new_heads = NewHeads::subscribe(&eth_gateway, RATE_LIMIT_DELAY);
+    if eth_gateway.supports_subscriptions() {
+        tokio::spawn(poll_on_priority_op_logs(
+            eth_gateway.clone(),
+            contract_config.contract_addr,
+            eth_req_sender.clone(),
+        ));
+    }
+
     let eth_client = EthHttpClient::new(
         eth_gateway,
         contract_config.contract_addr,
@@ -475,7 +661,14 @@ pub fn start_eth_watch(
         let mut timer = time::interval(poll_interval);
 
         loop {
-            timer.tick().await;
+            let new_head = tokio::select! {
+                _ = timer.tick() => false,
+                Some(_) = new_heads.changed() => true,
+            };
+            // While the subscription is active, the node is only polled on the new blocks.
+            if !new_head && new_heads.latest().is_some() {
+                continue;
+            }
             eth_req_sender
                 .clone()
                 .send(EthWatchRequest::PollETHNode)
@@ -484,3 +677,44 @@ pub fn start_eth_watch(
         }
     })
 }
+
+/// Keeps the subscription to the new priority operation logs alive, requesting the poll on each of them.
+async fn poll_on_priority_op_logs(
+    eth_gateway: EthereumGateway,
+    contract_addr: Address,
+    mut eth_req_sender: mpsc::Sender<EthWatchRequest>,
+) {
+    let topic = zksync_contract()
+        .event("NewPriorityRequest")
+        .expect("main contract abi error")
+        .signature();
+    let filter = FilterBuilder::default()
+        .address(vec![contract_addr])
+        .topics(Some(vec![topic]), None, None, None)
+        .build();
+
+    loop {
+        match eth_gateway.subscribe_logs(filter.clone()).await {
+            Ok(mut logs) => {
+                while let Some(Ok(_)) = logs.next().await {
+                    if eth_req_sender
+                        .send(EthWatchRequest::PollETHNode)
+                        .await
+                        .is_err()
+                    {
+                        // ETH watch receiver dropped.
+                        return;
+                    }
+                }
+                vlog::warn!("Priority operation logs subscription has ended");
+            }
+            Err(err) => {
+                vlog::warn!(
+                    "Unable to subscribe to the priority operation logs: {}",
+                    err
+                );
+            }
+        }
+        time::sleep(RATE_LIMIT_DELAY).await;
+    }
+}
@@ -14,11 +14,12 @@ use futures::StreamExt;
 use tokio::sync::RwLock;
 
 use super::is_missing_priority_op_error;
-use crate::eth_watch::{client::EthClient, EthWatch};
+use crate::eth_watch::{client::EthClient, EthWatch, L1Reorg, WatcherMode};
 use crate::mempool::MempoolTransactionRequest;
 
 struct FakeEthClientData {
     priority_ops: HashMap<u64, Vec<PriorityOp>>,
+    block_hashes: HashMap<u64, H256>,
     last_block_number: u64,
 }
 
@@ -26,10 +27,22 @@ impl FakeEthClientData {
     fn new() -> Self {
         Self {
             priority_ops: Default::default(),
+            block_hashes: Default::default(),
             last_block_number: 0,
         }
     }
 
+    /// Replaces all the blocks after the fork one with the new empty blocks.
+    fn reorg(&mut self, fork_block: u64, last_block_number: u64) {
+        self.priority_ops
+            .retain(|&block_number, _| block_number <= fork_block);
+        for block_number in fork_block + 1..=max(last_block_number, self.last_block_number) {
+            self.block_hashes
+                .insert(block_number, H256::from_low_u64_be(block_number | 1 << 63));
+        }
+        self.last_block_number = last_block_number;
+    }
+
     fn add_operations(&mut self, ops: &[PriorityOp]) {
         for op in ops {
             self.last_block_number = max(op.eth_block, self.last_block_number);
@@ -70,6 +83,13 @@ impl FakeEthClient {
         let mut inner = self.inner.write().await;
         inner.last_block_number = block_number;
     }
+
+    async fn reorg(&mut self, fork_block: u64, last_block_number: u64) {
+        self.inner
+            .write()
+            .await
+            .reorg(fork_block, last_block_number);
+    }
 }
 
 #[async_trait::async_trait]
@@ -111,6 +131,19 @@ impl EthClient for FakeEthClient {
         Ok(self.inner.read().await.last_block_number)
     }
 
+    async fn block_hash(&self, block_number: u64) -> anyhow::Result<Option<H256>> {
+        let inner = self.inner.read().await;
+        if block_number > inner.last_block_number {
+            return Ok(None);
+        }
+        let hash = inner
+            .block_hashes
+            .get(&block_number)
+            .copied()
+            .unwrap_or_else(|| H256::from_low_u64_be(block_number));
+        Ok(Some(hash))
+    }
+
     async fn get_auth_fact(
         &self,
         _address: Address,
@@ -141,7 +174,7 @@ async fn fake_mempool(
 ) {
     while let Some(a) = receiver.next().await {
         match a {
-            MempoolTransactionRequest::NewTx(_, _) => {
+            MempoolTransactionRequest::NewTx(_, _, _) => {
                 unreachable!()
             }
             MempoolTransactionRequest::NewPriorityOps(ops, conf, channel) => {
@@ -151,7 +184,14 @@ async fn fake_mempool(
                 }
                 channel.send(Ok(())).unwrap_or_default()
             }
-            MempoolTransactionRequest::NewTxsBatch(_, _, _) => unreachable!(),
+            MempoolTransactionRequest::RetractPriorityOps(serial_ids, channel) => {
+                let mut lock = data.write().await;
+                lock.retain(|serial_id, (_, confirmed)| {
+                    *confirmed || !serial_ids.contains(serial_id)
+                });
+                channel.send(Ok(())).unwrap_or_default()
+            }
+            MempoolTransactionRequest::NewTxsBatch(_, _, _, _) => unreachable!(),
         }
     }
 }
@@ -546,3 +586,202 @@ async fn test_serial_id_gaps() {
     assert_eq!(watcher.eth_state.last_ethereum_block_backup(), 0);
     assert_eq!(watcher.eth_state.last_ethereum_block(), 3);
 }
+
+fn deposit_op(serial_id: SerialId, eth_block: u64, eth_hash: H256) -> PriorityOp {
+    PriorityOp {
+        serial_id,
+        data: ZkSyncPriorityOp::Deposit(Deposit {
+            from: Default::default(),
+            token: TokenId(0),
+            amount: Default::default(),
+            to: Default::default(),
+        }),
+        deadline_block: 0,
+        eth_hash,
+        eth_block,
+        eth_block_index: Some(1),
+    }
+}
+
+/// Checks that the unconfirmed operations from the reorganized blocks are retracted
+/// and the ones from the new chain are processed.
+#[tokio::test]
+async fn test_shallow_reorg() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+    client
+        .add_operations(&[
+            deposit_op(0, 2, H256::repeat_byte(1)),
+            deposit_op(1, 5, H256::repeat_byte(2)),
+        ])
+        .await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    watcher.restore_state_from_eth(5).await.unwrap();
+    assert_eq!(watcher.eth_state.unconfirmed_queue().len(), 1);
+    assert!(!data.read().await.get(&1).unwrap().1);
+
+    // The operation is included into the different block of the new chain.
+    client.reorg(4, 6).await;
+    client
+        .add_operations(&[deposit_op(1, 6, H256::repeat_byte(3))])
+        .await;
+    assert_eq!(
+        watcher.detect_reorg().await.unwrap(),
+        Some(L1Reorg {
+            fork_block: 4,
+            deep: false
+        })
+    );
+
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(watcher.eth_state.last_ethereum_block(), 6);
+    let unconfirmed_queue = watcher.eth_state.unconfirmed_queue();
+    assert_eq!(unconfirmed_queue.len(), 1);
+    assert_eq!(unconfirmed_queue[0].eth_block, 6);
+    assert!(watcher.eth_state.priority_queue().get(&0).is_some());
+
+    let reader = data.read().await;
+    let (op, confirmed) = reader.get(&1).unwrap();
+    assert_eq!(op.eth_hash, H256::repeat_byte(3));
+    assert!(!confirmed);
+    assert!(reader.get(&0).unwrap().1);
+}
+
+/// Checks that the reorg affecting the confirmed events is detected as a deep one
+/// and the watcher stops processing the priority operations.
+#[tokio::test]
+async fn test_deep_reorg() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+    client
+        .add_operations(&[
+            deposit_op(0, 2, H256::repeat_byte(1)),
+            deposit_op(1, 5, H256::repeat_byte(2)),
+        ])
+        .await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    watcher.restore_state_from_eth(5).await.unwrap();
+
+    client.reorg(2, 6).await;
+    assert_eq!(
+        watcher.detect_reorg().await.unwrap(),
+        Some(L1Reorg {
+            fork_block: 2,
+            deep: true
+        })
+    );
+
+    watcher.poll_eth_node().await.unwrap();
+    assert!(matches!(watcher.mode, WatcherMode::Halted));
+    assert!(!watcher.polling_allowed());
+    // Neither the operations from the new chain are processed, nor the old ones are retracted.
+    client
+        .add_operations(&[deposit_op(2, 6, H256::repeat_byte(3))])
+        .await;
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(watcher.eth_state.last_ethereum_block(), 5);
+    assert_eq!(watcher.eth_state.unconfirmed_queue().len(), 1);
+
+    let reader = data.read().await;
+    assert!(reader.get(&0).unwrap().1);
+    assert!(!reader.get(&1).unwrap().1);
+    assert!(reader.get(&2).is_none());
+}
+
+/// Checks that the replacement of the latest block is detected even if the chain height
+/// hasn't changed, and the unconfirmed operations which are missing in the new chain are retracted.
+#[tokio::test]
+async fn test_shallow_reorg_same_height() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+    client
+        .add_operations(&[
+            deposit_op(0, 2, H256::repeat_byte(1)),
+            deposit_op(1, 5, H256::repeat_byte(2)),
+        ])
+        .await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    watcher.restore_state_from_eth(5).await.unwrap();
+    assert!(!data.read().await.get(&1).unwrap().1);
+
+    // The last block is replaced with the one without the operation.
+    client.reorg(4, 5).await;
+    watcher.poll_eth_node().await.unwrap();
+    assert!(matches!(watcher.mode, WatcherMode::Working));
+    assert_eq!(watcher.eth_state.last_ethereum_block(), 5);
+    assert!(watcher.eth_state.unconfirmed_queue().is_empty());
+    assert_eq!(
+        watcher.eth_state.block_hashes().get(&5),
+        client.block_hash(5).await.unwrap().as_ref()
+    );
+
+    // The operation is retracted from the mempool, while the confirmed one is kept.
+    let reader = data.read().await;
+    assert!(reader.get(&1).is_none());
+    assert!(reader.get(&0).unwrap().1);
+}
+
+/// Checks that the watcher is halted by the deep reorg which doesn't change the chain height.
+#[tokio::test]
+async fn test_deep_reorg_same_height() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+    client
+        .add_operations(&[
+            deposit_op(0, 2, H256::repeat_byte(1)),
+            deposit_op(1, 5, H256::repeat_byte(2)),
+        ])
+        .await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    watcher.restore_state_from_eth(5).await.unwrap();
+
+    client.reorg(1, 5).await;
+    watcher.poll_eth_node().await.unwrap();
+    assert!(matches!(watcher.mode, WatcherMode::Halted));
+    assert_eq!(watcher.eth_state.last_ethereum_block(), 5);
+
+    // Nothing is retracted, since the confirmed operations may already be executed.
+    let reader = data.read().await;
+    assert!(reader.get(&0).unwrap().1);
+    assert!(!reader.get(&1).unwrap().1);
+}
+
+/// Checks that nothing is reported if the known blocks are still a part of the chain.
+#[tokio::test]
+async fn test_no_reorg() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    tokio::spawn(fake_mempool(receiver, Default::default()));
+    client
+        .add_operations(&[deposit_op(0, 2, H256::repeat_byte(1))])
+        .await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    watcher.restore_state_from_eth(2).await.unwrap();
+    client.set_last_block_number(4).await;
+    assert_eq!(watcher.detect_reorg().await.unwrap(), None);
+
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(watcher.eth_state.last_ethereum_block(), 4);
+    assert_eq!(
+        watcher
+            .eth_state
+            .block_hashes()
+            .keys()
+            .copied()
+            .collect::<Vec<_>>(),
+        (0..=4).collect::<Vec<_>>()
+    );
+}
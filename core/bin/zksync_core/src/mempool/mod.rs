@@ -29,6 +29,8 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
+use tracing::Instrument;
+use vlog::TraceContext;
 
 // Workspace uses
 use zksync_balancer::{Balancer, BuildBalancedItem};
@@ -36,8 +38,8 @@ use zksync_balancer::{Balancer, BuildBalancedItem};
 use zksync_storage::ConnectionPool;
 use zksync_types::{
     mempool::{SignedTxVariant, SignedTxsBatch},
-    tx::TxEthSignature,
-    AccountId, AccountUpdate, AccountUpdates, Address, Nonce, PriorityOp, SignedZkSyncTx,
+    tx::{TxEthSignature, TxHash},
+    AccountId, AccountUpdate, AccountUpdates, Address, Nonce, PriorityOp, SerialId, SignedZkSyncTx,
     TransferOp, TransferToNewOp, ZkSyncTx,
 };
 
@@ -93,6 +95,8 @@ pub enum TxAddError {
 pub struct ProposedBlock {
     pub priority_ops: Vec<PriorityOp>,
     pub txs: Vec<SignedTxVariant>,
+    /// Trace contexts of the requests which added the proposed transactions to the mempool.
+    pub trace_contexts: HashMap<TxHash, TraceContext>,
 }
 
 impl ProposedBlock {
@@ -117,7 +121,11 @@ pub enum MempoolTransactionRequest {
     /// Add new transaction to mempool, transaction should be previously checked
     /// for correctness (including its Ethereum and ZKSync signatures).
     /// oneshot is used to receive tx add result.
-    NewTx(Box<SignedZkSyncTx>, oneshot::Sender<Result<(), TxAddError>>),
+    NewTx(
+        Box<SignedZkSyncTx>,
+        TraceContext,
+        oneshot::Sender<Result<(), TxAddError>>,
+    ),
 
     /// Add new priority ops, confirmed or not
     NewPriorityOps(
@@ -125,6 +133,9 @@ pub enum MempoolTransactionRequest {
         bool,
         oneshot::Sender<Result<(), TxAddError>>,
     ),
+    /// Remove unconfirmed priority ops with the given serial ids,
+    /// since the L1 blocks containing them were reorganized.
+    RetractPriorityOps(Vec<SerialId>, oneshot::Sender<Result<(), TxAddError>>),
     /// Add a new batch of transactions to the mempool. All transactions in batch must
     /// be either executed successfully, or otherwise fail all together.
     /// Invariants for each individual transaction in the batch are the same as in
@@ -132,6 +143,7 @@ pub enum MempoolTransactionRequest {
     NewTxsBatch(
         Vec<SignedZkSyncTx>,
         Vec<TxEthSignature>,
+        TraceContext,
         oneshot::Sender<Result<(), TxAddError>>,
     ),
 }
@@ -149,6 +161,8 @@ struct MempoolState {
     account_nonces: HashMap<Address, Nonce>,
     account_ids: HashMap<AccountId, Address>,
     transactions_queue: MempoolTransactionsQueue,
+    // trace contexts of the queued transactions, taken when the transactions are proposed
+    trace_contexts: HashMap<TxHash, TraceContext>,
 }
 
 impl MempoolState {
@@ -253,6 +267,7 @@ impl MempoolState {
             account_nonces,
             account_ids,
             transactions_queue,
+            trace_contexts: HashMap::new(),
         }
     }
 
@@ -261,6 +276,7 @@ impl MempoolState {
     }
 
     fn add_tx(&mut self, tx: SignedZkSyncTx) {
+        self.remember_trace_context(&tx);
         self.transactions_queue.add_tx_variant(tx.into());
     }
 
@@ -271,9 +287,32 @@ impl MempoolState {
     fn add_batch(&mut self, batch: SignedTxsBatch) {
         assert_ne!(batch.batch_id, 0, "Batch ID was not set");
 
+        for tx in &batch.txs {
+            self.remember_trace_context(tx);
+        }
         self.transactions_queue
             .add_tx_variant(SignedTxVariant::Batch(batch));
     }
+
+    /// Remembers the trace context of the request which added the transaction,
+    /// so the spans of the block including the transaction can be linked to it.
+    fn remember_trace_context(&mut self, tx: &SignedZkSyncTx) {
+        let trace_context = TraceContext::current();
+        if !trace_context.is_empty() {
+            self.trace_contexts.insert(tx.hash(), trace_context);
+        }
+    }
+
+    fn take_trace_contexts(&mut self, tx: &SignedTxVariant) -> HashMap<TxHash, TraceContext> {
+        tx.hashes()
+            .into_iter()
+            .filter_map(|hash| {
+                self.trace_contexts
+                    .remove(&hash)
+                    .map(|trace_context| (hash, trace_context))
+            })
+            .collect()
+    }
 }
 
 struct MempoolBlocksHandler {
@@ -369,7 +408,7 @@ impl MempoolBlocksHandler {
         let (chunks_left, priority_ops) = self
             .select_priority_ops(current_unprocessed_priority_op)
             .await;
-        let (_chunks_left, txs) = self
+        let (_chunks_left, txs, trace_contexts) = self
             .prepare_tx_for_block(chunks_left, block_timestamp)
             .await;
 
@@ -401,7 +440,11 @@ impl MempoolBlocksHandler {
                 metrics::histogram!("process_tx", tx.elapsed(), &labels);
             }
         }
-        ProposedBlock { priority_ops, txs }
+        ProposedBlock {
+            priority_ops,
+            txs,
+            trace_contexts,
+        }
     }
 
     /// Returns: chunks left from max amount of chunks, ops selected
@@ -442,7 +485,7 @@ impl MempoolBlocksHandler {
         &mut self,
         mut chunks_left: usize,
         block_timestamp: u64,
-    ) -> (usize, Vec<SignedTxVariant>) {
+    ) -> (usize, Vec<SignedTxVariant>, HashMap<TxHash, TraceContext>) {
         let mut mempool_state = self.mempool_state.write().await;
 
         mempool_state
@@ -450,10 +493,12 @@ impl MempoolBlocksHandler {
             .prepare_new_ready_transactions(block_timestamp);
 
         let mut txs_for_commit = Vec::new();
+        let mut trace_contexts = HashMap::new();
 
         while let Some(tx) = mempool_state.transactions_queue.pop_front() {
             let chunks_for_tx = mempool_state.required_chunks(&tx);
             if chunks_left >= chunks_for_tx {
+                trace_contexts.extend(mempool_state.take_trace_contexts(&tx));
                 txs_for_commit.push(tx);
                 chunks_left -= chunks_for_tx;
             } else {
@@ -463,7 +508,7 @@ impl MempoolBlocksHandler {
             }
         }
 
-        (chunks_left, txs_for_commit)
+        (chunks_left, txs_for_commit, trace_contexts)
     }
 
     async fn run(mut self) {
@@ -663,6 +708,23 @@ impl MempoolTransactionsHandler {
         Ok(())
     }
 
+    async fn retract_priority_ops(&mut self, serial_ids: Vec<SerialId>) -> Result<(), TxAddError> {
+        // Unconfirmed operations are only stored in the database, they never get to the mempool queue.
+        let mut storage = self.db_pool.access_storage().await.map_err(|err| {
+            vlog::error!("Mempool storage access error: {}", err);
+            TxAddError::DbError
+        })?;
+        storage
+            .chain()
+            .mempool_schema()
+            .remove_unconfirmed_priority_ops(&serial_ids)
+            .await
+            .map_err(|err| {
+                vlog::error!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })
+    }
+
     async fn add_batch(
         &mut self,
         txs: Vec<SignedZkSyncTx>,
@@ -721,18 +783,31 @@ impl MempoolTransactionsHandler {
         vlog::info!("Transaction mempool handler is running");
         while let Some(request) = self.requests.next().await {
             match request {
-                MempoolTransactionRequest::NewTx(tx, resp) => {
-                    let tx_add_result = self.add_tx(*tx).await;
+                MempoolTransactionRequest::NewTx(tx, trace_context, resp) => {
+                    let span = tracing::info_span!("mempool.add_tx", tx_hash = %tx.hash());
+                    trace_context.attach_to(&span);
+                    let tx_add_result = self.add_tx(*tx).instrument(span).await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
-                MempoolTransactionRequest::NewTxsBatch(txs, eth_signatures, resp) => {
-                    let tx_add_result = self.add_batch(txs, eth_signatures).await;
+                MempoolTransactionRequest::NewTxsBatch(
+                    txs,
+                    eth_signatures,
+                    trace_context,
+                    resp,
+                ) => {
+                    let span = tracing::info_span!("mempool.add_batch", batch_size = txs.len());
+                    trace_context.attach_to(&span);
+                    let tx_add_result = self.add_batch(txs, eth_signatures).instrument(span).await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
                 MempoolTransactionRequest::NewPriorityOps(ops, confirmed, resp) => {
                     let tx_add_result = self.add_priority_ops(ops, confirmed).await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
+                MempoolTransactionRequest::RetractPriorityOps(serial_ids, resp) => {
+                    let result = self.retract_priority_ops(serial_ids).await;
+                    resp.send(result).unwrap_or_default();
+                }
             }
         }
     }
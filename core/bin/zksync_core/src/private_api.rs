@@ -9,7 +9,7 @@
 
 use crate::mempool::MempoolTransactionRequest;
 use actix_web::error::InternalError;
-use actix_web::{web, App, HttpResponse, HttpServer};
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
 use futures::{
     channel::{mpsc, oneshot},
     sink::SinkExt,
@@ -18,6 +18,8 @@ use futures::{
 
 use std::thread;
 use tokio::task::JoinHandle;
+use tracing::Instrument;
+use vlog::TraceContext;
 use zksync_config::configs::api::PrivateApiConfig;
 use zksync_types::{tx::TxEthSignature, SignedZkSyncTx};
 use zksync_utils::panic_notify::ThreadPanicNotify;
@@ -27,26 +29,44 @@ struct AppState {
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
 }
 
+/// Restores the context of the trace started on the API side.
+fn trace_context(request: &HttpRequest) -> TraceContext {
+    TraceContext::from_headers(
+        request
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?))),
+    )
+}
+
 /// Adds a new transaction into the mempool.
 /// Returns a JSON representation of `Result<(), TxAddError>`.
 /// Expects transaction to be checked on the API side.
 #[actix_web::post("/new_tx")]
 async fn new_tx(
+    request: HttpRequest,
     data: web::Data<AppState>,
     web::Json(tx): web::Json<SignedZkSyncTx>,
 ) -> actix_web::Result<HttpResponse> {
-    let (sender, receiver) = oneshot::channel();
-    let item = MempoolTransactionRequest::NewTx(Box::new(tx), sender);
-    let mut mempool_sender = data.mempool_tx_sender.clone();
-    mempool_sender.send(item).await.map_err(|err| {
-        InternalError::from_response(err, HttpResponse::InternalServerError().finish())
-    })?;
-
-    let response = receiver.await.map_err(|err| {
-        InternalError::from_response(err, HttpResponse::InternalServerError().finish())
-    })?;
-
-    Ok(HttpResponse::Ok().json(response))
+    let span = tracing::info_span!("private_api.new_tx", tx_hash = %tx.hash());
+    trace_context(&request).attach_to(&span);
+
+    async move {
+        let (sender, receiver) = oneshot::channel();
+        let item = MempoolTransactionRequest::NewTx(Box::new(tx), TraceContext::current(), sender);
+        let mut mempool_sender = data.mempool_tx_sender.clone();
+        mempool_sender.send(item).await.map_err(|err| {
+            InternalError::from_response(err, HttpResponse::InternalServerError().finish())
+        })?;
+
+        let response = receiver.await.map_err(|err| {
+            InternalError::from_response(err, HttpResponse::InternalServerError().finish())
+        })?;
+
+        Ok(HttpResponse::Ok().json(response))
+    }
+    .instrument(span)
+    .await
 }
 
 /// Adds a new transactions batch into the mempool.
@@ -54,21 +74,34 @@ async fn new_tx(
 /// Expects transaction to be checked on the API side.
 #[actix_web::post("/new_txs_batch")]
 async fn new_txs_batch(
+    request: HttpRequest,
     data: web::Data<AppState>,
     web::Json((txs, eth_signatures)): web::Json<(Vec<SignedZkSyncTx>, Vec<TxEthSignature>)>,
 ) -> actix_web::Result<HttpResponse> {
-    let (sender, receiver) = oneshot::channel();
-    let item = MempoolTransactionRequest::NewTxsBatch(txs, eth_signatures, sender);
-    let mut mempool_sender = data.mempool_tx_sender.clone();
-    mempool_sender.send(item).await.map_err(|err| {
-        InternalError::from_response(err, HttpResponse::InternalServerError().finish())
-    })?;
-
-    let response = receiver.await.map_err(|err| {
-        InternalError::from_response(err, HttpResponse::InternalServerError().finish())
-    })?;
-
-    Ok(HttpResponse::Ok().json(response))
+    let span = tracing::info_span!("private_api.new_txs_batch", batch_size = txs.len());
+    trace_context(&request).attach_to(&span);
+
+    async move {
+        let (sender, receiver) = oneshot::channel();
+        let item = MempoolTransactionRequest::NewTxsBatch(
+            txs,
+            eth_signatures,
+            TraceContext::current(),
+            sender,
+        );
+        let mut mempool_sender = data.mempool_tx_sender.clone();
+        mempool_sender.send(item).await.map_err(|err| {
+            InternalError::from_response(err, HttpResponse::InternalServerError().finish())
+        })?;
+
+        let response = receiver.await.map_err(|err| {
+            InternalError::from_response(err, HttpResponse::InternalServerError().finish())
+        })?;
+
+        Ok(HttpResponse::Ok().json(response))
+    }
+    .instrument(span)
+    .await
 }
 
 #[allow(clippy::too_many_arguments)]
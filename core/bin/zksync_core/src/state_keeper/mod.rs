@@ -4,6 +4,7 @@ use std::time::Instant;
 // External uses
 use futures::{channel::mpsc, stream::StreamExt, SinkExt};
 use tokio::task::JoinHandle;
+use vlog::TraceContext;
 // Workspace uses
 use zksync_state::state::{OpSuccess, ZkSyncState};
 use zksync_types::{
@@ -315,6 +316,14 @@ impl ZkSyncStateKeeper {
             .expect("committer receiver dropped");
     }
 
+    #[tracing::instrument(
+        name = "state_keeper.execute_proposed_block",
+        skip_all,
+        fields(
+            priority_ops = proposed_block.priority_ops.len(),
+            txs = proposed_block.txs.len()
+        )
+    )]
     async fn execute_proposed_block(&mut self, proposed_block: ProposedBlock) {
         let start = Instant::now();
         let mut executed_ops = Vec::new();
@@ -344,13 +353,33 @@ impl ZkSyncStateKeeper {
             }
         }
 
+        let trace_contexts = proposed_block.trace_contexts;
         let mut tx_queue = proposed_block.txs.into_iter().collect::<VecDeque<_>>();
         while let Some(variant) = tx_queue.pop_front() {
+            let tx_hashes = variant.hashes();
+            let trace_context = tx_hashes
+                .first()
+                .and_then(|tx_hash| trace_contexts.get(tx_hash))
+                .cloned()
+                .unwrap_or_default();
+            let span = tracing::info_span!(
+                "state_keeper.apply_tx",
+                txs = tx_hashes.len(),
+                block_number = *self.pending_block.number
+            );
+            if !trace_context.is_empty() {
+                // The span continues the trace of the request which added the transaction,
+                // and is linked to the span of the block execution.
+                trace_context.attach_to(&span);
+                span.follows_from(tracing::Span::current());
+            }
+
             match &variant {
                 SignedTxVariant::Tx(tx) => {
-                    match self.apply_tx(tx) {
+                    match span.in_scope(|| self.apply_tx(tx)) {
                         ApplyOutcome::Included(exec_op) => {
                             executed_ops.push(exec_op);
+                            self.pending_block.push_trace_context(trace_context);
                         }
                         ApplyOutcome::NotIncluded => {
                             // We could not execute the tx due to either of block size limit
@@ -363,9 +392,10 @@ impl ZkSyncStateKeeper {
                     }
                 }
                 SignedTxVariant::Batch(batch) => {
-                    match self.apply_batch(&batch.txs, batch.batch_id) {
+                    match span.in_scope(|| self.apply_batch(&batch.txs, batch.batch_id)) {
                         ApplyOutcome::Included(mut ops) => {
                             executed_ops.append(&mut ops);
+                            self.pending_block.push_trace_context(trace_context);
                         }
                         ApplyOutcome::NotIncluded => {
                             // We could not execute the batch tx due to either of block size limit
@@ -470,6 +500,11 @@ impl ZkSyncStateKeeper {
         ApplyOutcome::Included(exec_result)
     }
 
+    #[tracing::instrument(
+        name = "state_keeper.apply_batch",
+        skip_all,
+        fields(batch_id = batch_id, txs = txs.len())
+    )]
     fn apply_batch(
         &mut self,
         txs: &[SignedZkSyncTx],
@@ -587,6 +622,7 @@ impl ZkSyncStateKeeper {
         ApplyOutcome::Included(executed_operations)
     }
 
+    #[tracing::instrument(name = "state_keeper.apply_tx", skip_all, fields(tx_hash = %tx.hash()))]
     fn apply_tx(&mut self, tx: &SignedZkSyncTx) -> ApplyOutcome<ExecutedOperations> {
         let start = Instant::now();
         let chunks_needed = self.state.chunks_for_tx(tx);
@@ -679,6 +715,11 @@ impl ZkSyncStateKeeper {
     }
 
     /// Finalizes the pending block, transforming it into a full block.
+    #[tracing::instrument(
+        name = "state_keeper.seal_pending_block",
+        skip_all,
+        fields(block_number = *self.pending_block.number)
+    )]
     async fn seal_pending_block(&mut self) {
         let start = Instant::now();
 
@@ -761,6 +802,8 @@ impl ZkSyncStateKeeper {
             block,
             block_metadata,
             accounts_updated: self.pending_block.account_updates.clone(),
+            trace_context: TraceContext::current(),
+            tx_trace_contexts: std::mem::take(&mut self.pending_block.trace_contexts),
         };
         let applied_updates_request = self.pending_block.prepare_applied_updates_request();
         let root_hash_job = BlockRootHashJob {
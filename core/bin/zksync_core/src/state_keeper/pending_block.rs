@@ -1,4 +1,5 @@
 // External uses
+use vlog::TraceContext;
 // Workspace uses
 use zksync_state::state::CollectedFee;
 use zksync_types::{
@@ -30,6 +31,8 @@ pub(super) struct PendingBlock {
     /// Number of stored account updates in the db (from `account_updates` field)
     pub(super) stored_account_updates: usize,
     pub(super) timestamp: u64,
+    /// Trace contexts of the requests which added the executed transactions to the mempool.
+    pub(super) trace_contexts: Vec<TraceContext>,
 
     // Two fields below are for optimization: we don't want to overwrite all the block contents over and over.
     // With these fields we'll be able save the diff between two pending block states only.
@@ -61,6 +64,7 @@ impl PendingBlock {
             collected_fees: Vec::new(),
             stored_account_updates: 0,
             timestamp,
+            trace_contexts: Vec::new(),
 
             success_txs_pending_len: 0,
             failed_txs_pending_len: 0,
@@ -73,6 +77,12 @@ impl PendingBlock {
         }
     }
 
+    pub(super) fn push_trace_context(&mut self, trace_context: TraceContext) {
+        if !trace_context.is_empty() {
+            self.trace_contexts.push(trace_context);
+        }
+    }
+
     pub(super) fn is_empty(&self) -> bool {
         self.failed_txs.is_empty() && self.success_operations.is_empty()
     }
@@ -85,7 +85,7 @@ impl<'a, 'b> StateRestoreDb for StateRestoreStorage<'a, 'b> {
         &mut self,
         block: BlockNumber,
     ) -> SparseMerkleTreeSerializableCacheBN256 {
-        let cache = self.storage
+        self.storage
             .chain()
             .block_schema()
             .get_account_tree_cache_block(block)
@@ -93,8 +93,7 @@ impl<'a, 'b> StateRestoreDb for StateRestoreStorage<'a, 'b> {
             .expect("Can't load account tree cache")
             .unwrap_or_else(|| {
                 panic!("Account tree cache was requested for block {}, for which it was checked to exist", block)
-            });
-        serde_json::from_value(cache).expect("Unable to decode tree cache")
+            })
     }
 
     async fn store_account_tree_cache(
@@ -102,12 +101,10 @@ impl<'a, 'b> StateRestoreDb for StateRestoreStorage<'a, 'b> {
         block: BlockNumber,
         account_tree_cache: SparseMerkleTreeSerializableCacheBN256,
     ) {
-        let encoded_tree_cache =
-            serde_json::to_value(account_tree_cache).expect("Unable to encode account tree cache");
         self.storage
             .chain()
             .block_schema()
-            .store_account_tree_cache(block, encoded_tree_cache)
+            .store_account_tree_cache(block, &account_tree_cache)
             .await
             .expect("Unable to store account tree cache in the database");
     }
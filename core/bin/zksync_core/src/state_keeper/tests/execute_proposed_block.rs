@@ -1,4 +1,5 @@
 use chrono::Utc;
+use vlog::TraceContext;
 use zksync_types::{
     mempool::SignedTxVariant, AccountId, BlockNumber, Nonce, SignedZkSyncTx, TokenId, Transfer,
     ZkSyncTx,
@@ -117,6 +118,7 @@ async fn small() {
             SignedTxVariant::Tx(bad_withdraw),
         ],
         priority_ops: vec![deposit],
+        trace_contexts: Default::default(),
     };
     let pending_block_iteration = tester.state_keeper.pending_block.pending_block_iteration;
     tester
@@ -159,6 +161,7 @@ async fn few_chunks() {
             SignedTxVariant::Tx(bad_withdraw),
         ],
         priority_ops: vec![deposit],
+        trace_contexts: Default::default(),
     };
     tester
         .state_keeper
@@ -196,6 +199,7 @@ async fn few_iterations() {
             SignedTxVariant::Tx(bad_withdraw),
         ],
         priority_ops: vec![deposit],
+        trace_contexts: Default::default(),
     };
     tester
         .state_keeper
@@ -225,6 +229,7 @@ async fn fast_withdrawal() {
     let proposed_block = ProposedBlock {
         priority_ops: Vec::new(),
         txs: vec![withdraw.into()],
+        trace_contexts: Default::default(),
     };
 
     tester
@@ -250,6 +255,7 @@ async fn pending_block_updates() {
     let proposed_block = ProposedBlock {
         txs: vec![],
         priority_ops: vec![],
+        trace_contexts: Default::default(),
     };
 
     tester
@@ -278,6 +284,7 @@ async fn pending_block_updates() {
     let proposed_block = ProposedBlock {
         txs: vec![SignedTxVariant::Tx(bad_withdraw)],
         priority_ops: vec![],
+        trace_contexts: Default::default(),
     };
 
     tester
@@ -306,6 +313,7 @@ async fn pending_block_updates() {
     let proposed_block = ProposedBlock {
         txs: vec![SignedTxVariant::Tx(good_withdraw)],
         priority_ops: vec![],
+        trace_contexts: Default::default(),
     };
 
     let pending_block_iteration = tester.state_keeper.pending_block.pending_block_iteration;
@@ -335,6 +343,7 @@ async fn pending_block_updates() {
     let proposed_block = ProposedBlock {
         txs: vec![SignedTxVariant::Tx(bad_withdraw)],
         priority_ops: vec![],
+        trace_contexts: Default::default(),
     };
 
     let pending_block_iteration = tester.state_keeper.pending_block.pending_block_iteration;
@@ -356,6 +365,7 @@ async fn pending_block_updates() {
     let proposed_block = ProposedBlock {
         txs: vec![],
         priority_ops: vec![],
+        trace_contexts: Default::default(),
     };
 
     let pending_block_iteration = tester.state_keeper.pending_block.pending_block_iteration;
@@ -400,6 +410,7 @@ async fn pending_block_diff() {
             SignedTxVariant::Tx(bad_withdraw_1.clone()),
         ],
         priority_ops: vec![],
+        trace_contexts: Default::default(),
     };
 
     let good_withdraw_2 = create_account_and_withdrawal(
@@ -424,6 +435,7 @@ async fn pending_block_diff() {
             SignedTxVariant::Tx(bad_withdraw_2.clone()),
         ],
         priority_ops: vec![],
+        trace_contexts: Default::default(),
     };
 
     tester
@@ -533,6 +545,7 @@ async fn transfers_with_different_timestamps() {
             SignedTxVariant::Tx(correct_transfer.clone()),
         ],
         priority_ops: vec![],
+        trace_contexts: Default::default(),
     };
 
     tester
@@ -565,3 +578,43 @@ async fn transfers_with_different_timestamps() {
         })
         .await;
 }
+
+/// Checks that the trace contexts of the executed transactions are passed to the committer
+/// along with the sealed block.
+#[tokio::test]
+async fn trace_contexts() {
+    let mut tester = StateKeeperTester::new(20, 3, 3);
+    let withdraw = create_account_and_withdrawal(
+        &mut tester,
+        TokenId(0),
+        AccountId(1),
+        200u32,
+        145u32,
+        Default::default(),
+    );
+    let trace_context = TraceContext::from_headers(vec![(
+        "traceparent",
+        "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+    )]);
+    let proposed_block = ProposedBlock {
+        trace_contexts: vec![(withdraw.hash(), trace_context.clone())]
+            .into_iter()
+            .collect(),
+        txs: vec![SignedTxVariant::Tx(withdraw)],
+        priority_ops: Vec::new(),
+    };
+    tester
+        .state_keeper
+        .execute_proposed_block(proposed_block)
+        .await;
+    tester.assert_pending().await;
+    assert_eq!(
+        tester.state_keeper.pending_block.trace_contexts,
+        vec![trace_context.clone()]
+    );
+
+    tester.state_keeper.seal_pending_block().await;
+    let (block_commit_request, _) = tester.unwrap_sealed_update().await;
+    assert_eq!(block_commit_request.tx_trace_contexts, vec![trace_context]);
+    assert!(tester.state_keeper.pending_block.trace_contexts.is_empty());
+}
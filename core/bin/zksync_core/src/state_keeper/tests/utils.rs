@@ -310,6 +310,7 @@ pub async fn apply_single_transfer(tester: &mut StateKeeperTester) {
     let proposed_block = ProposedBlock {
         txs: vec![SignedTxVariant::Tx(transfer)],
         priority_ops: Vec::new(),
+        trace_contexts: Default::default(),
     };
     tester
         .state_keeper
@@ -329,6 +330,7 @@ pub async fn apply_batch_with_two_transfers(tester: &mut StateKeeperTester) {
             eth_signatures: Vec::new(),
         })],
         priority_ops: Vec::new(),
+        trace_contexts: Default::default(),
     };
     tester
         .state_keeper
@@ -15,7 +15,7 @@ use web3::{
 };
 // Workspace uses
 use zksync_config::ETHSenderConfig;
-use zksync_eth_client::{EthereumGateway, SignedCallResult};
+use zksync_eth_client::{new_heads::NewHeads, EthereumGateway, SignedCallResult};
 use zksync_storage::ConnectionPool;
 use zksync_types::ethereum::ETHOperation;
 // Local uses
@@ -115,6 +115,8 @@ struct ETHSender<DB: DatabaseInterface> {
     db: DB,
     /// Ethereum intermediator.
     ethereum: EthereumGateway,
+    /// Latest Ethereum block, if the node supports subscriptions.
+    new_heads: NewHeads,
     /// Queue for ordered transaction processing.
     tx_queue: TxQueue,
     /// Utility for managing the gas price for transactions.
@@ -172,10 +174,13 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             .expect("Failed commit database transaction");
         drop(connection);
 
+        let new_heads = NewHeads::subscribe(&ethereum, RATE_LIMIT_BACKOFF_PERIOD);
+
         Self {
             ongoing_ops,
             db,
             ethereum,
+            new_heads,
             tx_queue,
             gas_adjuster,
             options,
@@ -188,8 +193,12 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
         // Having `0` as an initial value is to ensure that on the first iteration we will run all the activities.
         let mut last_used_block = 0;
         loop {
-            // We perform a loading routine every X seconds.
-            tokio::time::sleep(self.options.sender.tx_poll_period()).await;
+            // We perform a loading routine every X seconds, or once a new block is received
+            // if the node supports subscriptions.
+            tokio::select! {
+                _ = tokio::time::sleep(self.options.sender.tx_poll_period()) => {}
+                _ = self.new_heads.changed() => {}
+            }
             // If we received an error when loading a new operation, we can't do anything about it and should panic.
             if let Err(error) = self.load_new_operations().await {
                 vlog::error!("Unable to restore operations from the database: {}", error);
@@ -250,12 +259,16 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     async fn proceed_next_operations(&mut self, last_used_block: u64) -> u64 {
         let start = Instant::now();
 
-        let current_block = match self.ethereum.block_number().await {
-            Ok(current_block) => current_block.as_u64(),
-            Err(e) => {
-                Self::process_error(e).await;
-                return last_used_block;
-            }
+        // The node is only polled if there is no subscription to the new blocks.
+        let current_block = match self.new_heads.latest() {
+            Some(current_block) => current_block,
+            None => match self.ethereum.block_number().await {
+                Ok(current_block) => current_block.as_u64(),
+                Err(e) => {
+                    Self::process_error(e).await;
+                    return last_used_block;
+                }
+            },
         };
 
         while let Some(tx) = self.tx_queue.pop_front() {
@@ -336,7 +349,26 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     }
 
     /// Stores the new operation in the database and sends the corresponding transaction.
+    #[tracing::instrument(
+        name = "eth_sender.initialize_operation",
+        skip_all,
+        fields(
+            op_type = ?tx.op_type,
+            first_block = *tx.operation.1.get_block_range().0,
+            last_block = *tx.operation.1.get_block_range().1,
+            eth_tx_hash = tracing::field::Empty
+        )
+    )]
     async fn initialize_operation(&mut self, tx: TxData, current_block: u64) -> anyhow::Result<()> {
+        // Link the operation to the traces of the transactions from its blocks.
+        let (first_block, last_block) = tx.operation.1.get_block_range();
+        let span = tracing::Span::current();
+        for block_number in *first_block..=*last_block {
+            for trace_context in vlog::block_traces(block_number) {
+                trace_context.link_to(&span);
+            }
+        }
+
         let deadline_block = self.get_deadline_block(current_block);
         let gas_price = self
             .gas_adjuster
@@ -379,6 +411,8 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             let signed_tx = Self::sign_new_tx(&self.ethereum, &new_op).await?;
 
             // With signed tx, update the hash in the operation entry and in the db.
+            tracing::Span::current()
+                .record("eth_tx_hash", &format!("{:#x}", signed_tx.hash).as_str());
             new_op.used_tx_hashes.push(signed_tx.hash);
             self.db
                 .add_hash_entry(&mut transaction, new_op.id, &signed_tx.hash)
@@ -443,6 +477,11 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
     /// - If the transaction is stuck, sends a supplement transaction for it.
     /// - If the transaction is failed, handles the failure according to the failure
     ///   processing policy.
+    #[tracing::instrument(
+        name = "eth_sender.perform_commitment_step",
+        skip_all,
+        fields(eth_op_id = op.id)
+    )]
     async fn perform_commitment_step(
         &mut self,
         op: &mut ETHOperation,
@@ -442,6 +442,12 @@ async fn build_eth_sender(
             is_enabled: true,
             operator_commit_eth_addr: Default::default(),
             operator_private_key: Default::default(),
+            operator_keystore_path: None,
+            operator_mnemonic_path: None,
+            operator_derivation_path: None,
+            operator_password_path: None,
+            operator_remote_signer_url: None,
+            operator_remote_signer_token_path: None,
         },
         gas_price_limit: GasLimit {
             default: 1000,
@@ -5,13 +5,16 @@
 
 // Built-in
 use std::clone::Clone;
+use std::time::Duration;
 // Workspace uses
+use zksync_circuit::serialization::ProverData;
+use zksync_crypto::merkle_tree::parallel_smt::SparseMerkleTreeSerializableCacheBN256;
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::Block,
-    prover::{ProverJob, ProverJobType},
+    prover::{FailedProverJob, ProverJob, ProverJobType, ProverJobWorkload, ProverStats},
     AccountMap, AccountUpdates, BlockNumber,
 };
 // Local uses
@@ -58,7 +61,7 @@ impl DatabaseInterface for Database {
         &self,
         connection: &mut StorageProcessor<'_>,
         block_number: BlockNumber,
-    ) -> anyhow::Result<Option<serde_json::Value>> {
+    ) -> anyhow::Result<Option<ProverData>> {
         let witness = connection.prover_schema().get_witness(block_number).await?;
 
         Ok(witness)
@@ -109,12 +112,45 @@ impl DatabaseInterface for Database {
     async fn mark_stale_jobs_as_idle(
         &self,
         connection: &mut StorageProcessor<'_>,
+        max_retries: u32,
     ) -> anyhow::Result<()> {
-        connection.prover_schema().mark_stale_jobs_as_idle().await?;
+        connection
+            .prover_schema()
+            .mark_stale_jobs_as_idle(max_retries)
+            .await?;
 
         Ok(())
     }
 
+    async fn record_prover_job_failure(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+        prover_name: &str,
+        reason: &str,
+        max_retries: u32,
+    ) -> anyhow::Result<bool> {
+        let recorded = connection
+            .prover_schema()
+            .record_prover_job_failure(job_id, prover_name, reason, max_retries)
+            .await?;
+
+        Ok(recorded)
+    }
+
+    async fn requeue_failed_prover_job(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+    ) -> anyhow::Result<bool> {
+        let requeued = connection
+            .prover_schema()
+            .requeue_failed_prover_job(job_id)
+            .await?;
+
+        Ok(requeued)
+    }
+
     async fn load_last_verified_block(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -141,7 +177,7 @@ impl DatabaseInterface for Database {
     async fn load_account_tree_cache(
         &self,
         connection: &mut StorageProcessor<'_>,
-    ) -> anyhow::Result<Option<(BlockNumber, serde_json::Value)>> {
+    ) -> anyhow::Result<Option<(BlockNumber, SparseMerkleTreeSerializableCacheBN256)>> {
         let tree_cache = connection
             .chain()
             .block_schema()
@@ -154,10 +190,12 @@ impl DatabaseInterface for Database {
     async fn load_idle_prover_job_from_job_queue(
         &self,
         connection: &mut StorageProcessor<'_>,
+        prover_name: &str,
+        lease_duration: Duration,
     ) -> anyhow::Result<Option<ProverJob>> {
         let proof = connection
             .prover_schema()
-            .get_idle_prover_job_from_job_queue()
+            .get_idle_prover_job_from_job_queue(prover_name, lease_duration)
             .await?;
 
         Ok(proof)
@@ -168,13 +206,14 @@ impl DatabaseInterface for Database {
         connection: &mut StorageProcessor<'_>,
         job_id: i32,
         prover_name: &str,
-    ) -> anyhow::Result<()> {
-        connection
+        lease_duration: Duration,
+    ) -> anyhow::Result<bool> {
+        let lease_renewed = connection
             .prover_schema()
-            .record_prover_is_working(job_id, prover_name)
+            .record_prover_is_working(job_id, prover_name, lease_duration)
             .await?;
 
-        Ok(())
+        Ok(lease_renewed)
     }
 
     async fn store_proof(
@@ -254,7 +293,7 @@ impl DatabaseInterface for Database {
         &self,
         connection: &mut StorageProcessor<'_>,
         block: BlockNumber,
-        tree_cache: serde_json::Value,
+        tree_cache: &SparseMerkleTreeSerializableCacheBN256,
     ) -> anyhow::Result<()> {
         connection
             .chain()
@@ -275,7 +314,7 @@ impl DatabaseInterface for Database {
         &self,
         connection: &mut StorageProcessor<'_>,
         block: BlockNumber,
-        witness: serde_json::Value,
+        witness: &ProverData,
     ) -> anyhow::Result<()> {
         connection
             .prover_schema()
@@ -293,4 +332,47 @@ impl DatabaseInterface for Database {
 
         Ok(count)
     }
+
+    async fn load_pending_jobs_workload(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<ProverJobWorkload>> {
+        let jobs = connection
+            .prover_schema()
+            .load_pending_jobs_workload()
+            .await?;
+
+        Ok(jobs)
+    }
+
+    async fn load_completed_jobs_workload(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<ProverJobWorkload>> {
+        let jobs = connection
+            .prover_schema()
+            .load_completed_jobs_workload(limit)
+            .await?;
+
+        Ok(jobs)
+    }
+
+    async fn load_prover_stats(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<ProverStats>> {
+        let stats = connection.prover_schema().load_prover_stats().await?;
+
+        Ok(stats)
+    }
+
+    async fn load_failed_prover_jobs(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<FailedProverJob>> {
+        let jobs = connection.prover_schema().load_failed_prover_jobs().await?;
+
+        Ok(jobs)
+    }
 }
@@ -6,13 +6,16 @@
 // Built-in
 use std::clone::Clone;
 use std::marker::{Send, Sync};
+use std::time::Duration;
 // Workspace uses
+use zksync_circuit::serialization::ProverData;
+use zksync_crypto::merkle_tree::parallel_smt::SparseMerkleTreeSerializableCacheBN256;
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
 use zksync_storage::StorageProcessor;
 use zksync_types::aggregated_operations::{AggregatedActionType, AggregatedOperation};
 use zksync_types::{
     block::Block,
-    prover::{ProverJob, ProverJobType},
+    prover::{FailedProverJob, ProverJob, ProverJobType, ProverJobWorkload, ProverStats},
     AccountMap, AccountUpdates, BlockNumber,
 };
 
@@ -34,7 +37,7 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         &self,
         connection: &mut StorageProcessor<'_>,
         block_number: BlockNumber,
-    ) -> anyhow::Result<Option<serde_json::Value>>;
+    ) -> anyhow::Result<Option<ProverData>>;
 
     async fn add_prover_job_to_job_queue(
         &self,
@@ -59,11 +62,33 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         block_number: BlockNumber,
     ) -> anyhow::Result<Option<SingleProof>>;
 
+    /// Returns the jobs with expired leases back to the queue (or marks them as failed
+    /// if they run out of retries).
     async fn mark_stale_jobs_as_idle(
         &self,
         connection: &mut StorageProcessor<'_>,
+        max_retries: u32,
     ) -> anyhow::Result<()>;
 
+    /// Records the failed attempt to prove the job.
+    /// Returns `false` if the prover doesn't own the job anymore.
+    async fn record_prover_job_failure(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+        prover_name: &str,
+        reason: &str,
+        max_retries: u32,
+    ) -> anyhow::Result<bool>;
+
+    /// Returns the job which has reached the retry limit back to the queue.
+    /// Returns `false` if there is no failed job with such an ID.
+    async fn requeue_failed_prover_job(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        job_id: i32,
+    ) -> anyhow::Result<bool>;
+
     async fn load_last_verified_block(
         &self,
         connection: &mut StorageProcessor<'_>,
@@ -78,19 +103,24 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
     async fn load_account_tree_cache(
         &self,
         connection: &mut StorageProcessor<'_>,
-    ) -> anyhow::Result<Option<(BlockNumber, serde_json::Value)>>;
+    ) -> anyhow::Result<Option<(BlockNumber, SparseMerkleTreeSerializableCacheBN256)>>;
 
+    /// Leases the job with the highest priority to the prover.
     async fn load_idle_prover_job_from_job_queue(
         &self,
         connection: &mut StorageProcessor<'_>,
+        prover_name: &str,
+        lease_duration: Duration,
     ) -> anyhow::Result<Option<ProverJob>>;
 
+    /// Renews the lease of the job. Returns `false` if the prover doesn't own the job anymore.
     async fn record_prover_is_working(
         &self,
         connection: &mut StorageProcessor<'_>,
         job_id: i32,
         prover_name: &str,
-    ) -> anyhow::Result<()>;
+        lease_duration: Duration,
+    ) -> anyhow::Result<bool>;
 
     async fn store_proof(
         &self,
@@ -132,18 +162,41 @@ pub trait DatabaseInterface: Send + Sync + Clone + 'static {
         &self,
         connection: &mut StorageProcessor<'_>,
         block: BlockNumber,
-        tree_cache: serde_json::Value,
+        tree_cache: &SparseMerkleTreeSerializableCacheBN256,
     ) -> anyhow::Result<()>;
 
     async fn store_witness(
         &self,
         connection: &mut StorageProcessor<'_>,
         block: BlockNumber,
-        witness: serde_json::Value,
+        witness: &ProverData,
     ) -> anyhow::Result<()>;
 
     async fn pending_jobs_count(
         &self,
         connection: &mut StorageProcessor<'_>,
     ) -> anyhow::Result<u32>;
+
+    /// Returns the workload of the jobs which are not proven yet.
+    async fn load_pending_jobs_workload(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<ProverJobWorkload>>;
+
+    /// Returns the workload and the proving time of the latest completed jobs.
+    async fn load_completed_jobs_workload(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<ProverJobWorkload>>;
+
+    async fn load_prover_stats(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<ProverStats>>;
+
+    async fn load_failed_prover_jobs(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<FailedProverJob>>;
 }
@@ -19,19 +19,19 @@ use tokio::sync::RwLock;
 use zksync_config::ProverConfig;
 // Local deps
 use self::database_interface::DatabaseInterface;
-use self::scaler::ScalerOracle;
+use self::scaler::{ScalerOracle, ScalingForecast};
 use tokio::task::JoinHandle;
-use zksync_circuit::serialization::ProverData;
 use zksync_config::configs::api::ProverApiConfig;
 use zksync_prover_utils::api::{
-    JobRequestData, JobResultData, ProverInputRequest, ProverInputResponse, ProverOutputRequest,
-    WorkingOn,
+    JobRequestData, JobResultData, ProverInputRequest, ProverInputResponse, ProverJobFailed,
+    ProverOutputRequest, RequeueProverJob, WorkingOn,
 };
 use zksync_types::aggregated_operations::{
     AggregatedActionType, AggregatedOperation, BlocksCreateProofOperation,
 };
 use zksync_types::prover::{
-    ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY, SINGLE_PROOF_JOB_PRIORITY,
+    FailedProverJob, ProverJobType, ProverStats, AGGREGATED_PROOF_JOB_PRIORITY,
+    SINGLE_PROOF_JOB_PRIORITY,
 };
 use zksync_types::BlockNumber;
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
@@ -57,19 +57,32 @@ struct AppState<DB: DatabaseInterface> {
     secret_auth: String,
     database: DB,
     scaler_oracle: Arc<RwLock<ScalerOracle<DB>>>,
+    /// Time after which the job is returned to the queue if prover doesn't send heartbeats.
+    lease_duration: Duration,
+    max_job_retries: u32,
 }
 
 impl<DB: DatabaseInterface> AppState<DB> {
-    pub fn new(secret_auth: String, database: DB, idle_provers: u32) -> Self {
+    pub fn new(
+        secret_auth: String,
+        database: DB,
+        idle_provers: u32,
+        prove_deadline: Duration,
+        lease_duration: Duration,
+        max_job_retries: u32,
+    ) -> Self {
         let scaler_oracle = Arc::new(RwLock::new(ScalerOracle::new(
             database.clone(),
             idle_provers,
+            prove_deadline,
         )));
 
         Self {
             secret_auth,
             database,
             scaler_oracle,
+            lease_duration,
+            max_job_retries,
         }
     }
 
@@ -129,7 +142,7 @@ async fn get_job<DB: DatabaseInterface>(
     let mut storage = data.access_storage().await?;
     let ret = data
         .database
-        .load_idle_prover_job_from_job_queue(&mut storage)
+        .load_idle_prover_job_from_job_queue(&mut storage, &r.prover_name, data.lease_duration)
         .await
         .map_err(|e| {
             vlog::warn!("could not get next unverified commit operation: {}", e);
@@ -167,14 +180,91 @@ async fn working_on<DB: DatabaseInterface>(
         .access_storage()
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
-    data.database
-        .record_prover_is_working(&mut storage, r.job_id, &r.prover_name)
+    let lease_renewed = data
+        .database
+        .record_prover_is_working(&mut storage, r.job_id, &r.prover_name, data.lease_duration)
         .await
         .map_err(|e| {
             vlog::warn!("failed to record prover work in progress request: {}", e);
             actix_web::error::ErrorInternalServerError("storage layer error")
         })?;
+    if !lease_renewed {
+        vlog::warn!(
+            "Prover '{}' is working on job {} which is not leased to it anymore",
+            r.prover_name,
+            r.job_id
+        );
+        return Err(actix_web::error::ErrorConflict("job lease is lost"));
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn job_failed<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+    r: web::Json<ProverJobFailed>,
+) -> actix_web::Result<HttpResponse> {
+    vlog::warn!(
+        "Prover '{}' failed to prove job {}: {}",
+        r.prover_name,
+        r.job_id,
+        r.reason
+    );
+    let mut storage = data
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let recorded = data
+        .database
+        .record_prover_job_failure(
+            &mut storage,
+            r.job_id,
+            &r.prover_name,
+            &r.reason,
+            data.max_job_retries,
+        )
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to record prover job failure: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    if !recorded {
+        vlog::warn!(
+            "Prover '{}' reported failure of job {} which is not leased to it",
+            r.prover_name,
+            r.job_id
+        );
+        return Err(actix_web::error::ErrorConflict("job lease is lost"));
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Returns the job which has reached the retry limit back to the queue.
+/// Used by the operator after the cause of the failures is fixed.
+async fn requeue_job<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+    r: web::Json<RequeueProverJob>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let requeued = data
+        .database
+        .requeue_failed_prover_job(&mut storage, r.job_id)
+        .await
+        .map_err(|e| {
+            vlog::warn!("failed to requeue prover job: {}", e);
+            actix_web::error::ErrorInternalServerError("storage layer error")
+        })?;
+    if !requeued {
+        return Err(actix_web::error::ErrorNotFound(
+            "no failed job with such id",
+        ));
+    }
 
+    vlog::info!("Failed prover job {} is returned to the queue", r.job_id);
     Ok(HttpResponse::Ok().finish())
 }
 
@@ -261,32 +351,55 @@ pub struct RequiredReplicasInput {
 }
 
 /// Output of the `/scaler/replicas` endpoint.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RequiredReplicasOutput {
-    /// Amount of the prover entities required for server
-    /// to run optimally.
-    needed_count: u32,
-}
+pub type RequiredReplicasOutput = ScalingForecast;
 
 async fn required_replicas<DB: DatabaseInterface>(
     data: web::Data<AppState<DB>>,
-    _input: web::Json<RequiredReplicasInput>,
+    input: web::Json<RequiredReplicasInput>,
 ) -> actix_web::Result<HttpResponse> {
     let mut oracle = data.scaler_oracle.write().await;
 
-    let needed_count = oracle
-        .provers_required()
+    let response: RequiredReplicasOutput = oracle
+        .provers_required(input.current_count)
         .await
         .map_err(actix_web::error::ErrorInternalServerError)?;
 
-    let response = RequiredReplicasOutput { needed_count };
-
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Output of the `/api/internal/prover/stats` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProverStatsOutput {
+    provers: Vec<ProverStats>,
+    /// Jobs that have exceeded the retry limit and require manual intervention.
+    failed_jobs: Vec<FailedProverJob>,
+}
+
+async fn prover_stats<DB: DatabaseInterface>(
+    data: web::Data<AppState<DB>>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data.access_storage().await?;
+    let provers = data
+        .database
+        .load_prover_stats(&mut storage)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let failed_jobs = data
+        .database
+        .load_failed_prover_jobs(&mut storage)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(ProverStatsOutput {
+        provers,
+        failed_jobs,
+    }))
+}
+
 async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     database: DB,
     prepare_data_interval: Duration,
+    max_job_retries: u32,
 ) {
     // We use `prepare_data_interval` as timeout in this function to align creating prover jobs
     // with witness generator routine.
@@ -295,7 +408,7 @@ async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     loop {
         interval.tick().await;
 
-        update_prover_job_queue(database.clone())
+        update_prover_job_queue(database.clone(), max_job_retries)
             .await
             .unwrap_or_else(|e| {
                 vlog::warn!("Failed to update prover job queue: {}", e);
@@ -303,7 +416,10 @@ async fn update_prover_job_queue_loop<DB: DatabaseInterface>(
     }
 }
 
-async fn update_prover_job_queue<DB: DatabaseInterface>(database: DB) -> anyhow::Result<()> {
+async fn update_prover_job_queue<DB: DatabaseInterface>(
+    database: DB,
+    max_job_retries: u32,
+) -> anyhow::Result<()> {
     let mut connection = database.acquire_connection().await?;
     {
         let next_single_block_to_add = database
@@ -313,9 +429,7 @@ async fn update_prover_job_queue<DB: DatabaseInterface>(database: DB) -> anyhow:
         let witness_for_next_single_block = database
             .load_witness(&mut connection, next_single_block_to_add)
             .await?;
-        if let Some(witness) = witness_for_next_single_block {
-            let prover_data: ProverData =
-                serde_json::from_value(witness).expect("incorrect single block witness");
+        if let Some(prover_data) = witness_for_next_single_block {
             let block_size = prover_data.operations.len();
             let job_data =
                 serde_json::to_value(JobRequestData::BlockProof(prover_data, block_size))
@@ -381,15 +495,20 @@ async fn update_prover_job_queue<DB: DatabaseInterface>(database: DB) -> anyhow:
                 .await?;
         }
     }
-    database.mark_stale_jobs_as_idle(&mut connection).await?;
+    database
+        .mark_stale_jobs_as_idle(&mut connection, max_job_retries)
+        .await?;
 
     Ok(())
 }
 
+/// Runs the prover server. `block_prove_deadline` is the time in which the committed blocks
+/// are expected to be proven, it's used to decide how many provers are required.
 pub fn run_prover_server<DB: DatabaseInterface>(
     database: DB,
     prover_api_opts: ProverApiConfig,
     prover_opts: ProverConfig,
+    block_prove_deadline: Duration,
 ) -> JoinHandle<()> {
     let witness_generator_opts = prover_opts.witness_generator;
     let core_opts = prover_opts.core;
@@ -405,6 +524,7 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                 tokio::spawn(update_prover_job_queue_loop(
                     database.clone(),
                     witness_generator_opts.prepare_data_interval(),
+                    core_opts.max_job_retries,
                 ));
 
                 let last_verified_block = {
@@ -420,29 +540,31 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                         as usize
                 };
 
-                // Start pool maintainer threads.
-                for offset in 0..witness_generator_opts.witness_generators {
-                    let start_block = (last_verified_block + offset + 1) as u32;
-                    let block_step = witness_generator_opts.witness_generators as u32;
-                    vlog::info!(
-                        "Starting witness generator ({},{})",
-                        start_block,
-                        block_step
-                    );
-                    let pool_maintainer = witness_generator::WitnessGenerator::new(
-                        database.clone(),
-                        witness_generator_opts.prepare_data_interval(),
-                        BlockNumber(start_block),
-                        BlockNumber(block_step),
-                    );
-                    pool_maintainer.start(panic_sender.clone());
-                }
+                // Start the witness generator, building the witnesses with the pool of workers.
+                let start_block = BlockNumber(last_verified_block as u32 + 1);
+                let workers = witness_generator_opts.witness_generators;
+                vlog::info!("Starting witness generator ({},{})", *start_block, workers);
+                let pool_maintainer = witness_generator::WitnessGenerator::new(
+                    database.clone(),
+                    witness_generator_opts.prepare_data_interval(),
+                    start_block,
+                    workers,
+                );
+                pool_maintainer.start(panic_sender.clone());
                 // Start HTTP server.
                 let secret_auth = prover_api_opts.secret_auth.clone();
                 let idle_provers = core_opts.idle_provers;
+                let lease_duration = core_opts.gone_timeout();
+                let max_job_retries = core_opts.max_job_retries;
                 HttpServer::new(move || {
-                    let app_state =
-                        AppState::new(secret_auth.clone(), database.clone(), idle_provers);
+                    let app_state = AppState::new(
+                        secret_auth.clone(),
+                        database.clone(),
+                        idle_provers,
+                        block_prove_deadline,
+                        lease_duration,
+                        max_job_retries,
+                    );
 
                     let auth = HttpAuthentication::bearer(move |req, credentials| async {
                         let secret_auth = req
@@ -464,11 +586,20 @@ pub fn run_prover_server<DB: DatabaseInterface>(
                         .route("/get_job", web::get().to(get_job::<DB>))
                         .route("/working_on", web::post().to(working_on::<DB>))
                         .route("/publish", web::post().to(publish::<DB>))
+                        .route("/job_failed", web::post().to(job_failed::<DB>))
                         .route("/stopped", web::post().to(stopped::<DB>))
                         .route(
                             "/api/internal/prover/replicas",
                             web::post().to(required_replicas::<DB>),
                         )
+                        .route(
+                            "/api/internal/prover/stats",
+                            web::get().to(prover_stats::<DB>),
+                        )
+                        .route(
+                            "/api/internal/prover/requeue_job",
+                            web::post().to(requeue_job::<DB>),
+                        )
                 })
                 .bind(&prover_api_opts.bind_addr())
                 .expect("failed to bind")
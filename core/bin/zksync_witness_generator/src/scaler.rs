@@ -1,7 +1,99 @@
 //! Module with utilities for prover scaler service.
 
+// Built-in deps
+use std::time::Duration;
+// External deps
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 // Workspace deps
+use zksync_types::prover::{ProverJobType, ProverJobWorkload};
+// Local deps
 use crate::database_interface::DatabaseInterface;
+
+/// Amount of the latest completed jobs used to measure the proving time.
+const PROVING_TIME_SAMPLES: u32 = 100;
+/// Time to prove a block chunk, used until there are no completed single proofs.
+const DEFAULT_CHUNK_PROVING_TIME: Duration = Duration::from_secs(2);
+/// Time to prove a block in the aggregated proof, used until there are no completed aggregated proofs.
+const DEFAULT_AGGREGATED_BLOCK_PROVING_TIME: Duration = Duration::from_secs(30);
+
+/// Forecast of the prover workload, which is used by the prover scaler service.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScalingForecast {
+    /// Amount of the prover entities required for server
+    /// to prove the pending jobs before the deadline.
+    pub needed_count: u32,
+    /// Amount of jobs which are not proven yet.
+    pub pending_jobs: u32,
+    /// Time required for a single prover to prove all the pending jobs, in seconds.
+    pub backlog_work_secs: u64,
+    /// Estimated time to prove the pending jobs with `needed_count` provers, in seconds.
+    pub backlog_eta_secs: u64,
+    /// Estimated time to prove the pending jobs with the currently running provers, in seconds.
+    /// Absent if there are pending jobs, but no running provers.
+    pub current_backlog_eta_secs: Option<u64>,
+}
+
+/// Measured time required to prove a unit of the job workload.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ProvingTimeModel {
+    /// Time to prove a chunk of the block in the single proof.
+    chunk: Duration,
+    /// Time to prove a block in the aggregated proof.
+    aggregated_block: Duration,
+}
+
+impl Default for ProvingTimeModel {
+    fn default() -> Self {
+        Self {
+            chunk: DEFAULT_CHUNK_PROVING_TIME,
+            aggregated_block: DEFAULT_AGGREGATED_BLOCK_PROVING_TIME,
+        }
+    }
+}
+
+impl ProvingTimeModel {
+    /// Measures the proving time using the completed jobs. Defaults are used for the job types
+    /// which have no completed jobs yet.
+    fn measure(completed_jobs: &[ProverJobWorkload]) -> Self {
+        let default = Self::default();
+        Self {
+            chunk: Self::unit_time(completed_jobs, ProverJobType::SingleProof)
+                .unwrap_or(default.chunk),
+            aggregated_block: Self::unit_time(completed_jobs, ProverJobType::AggregatedProof)
+                .unwrap_or(default.aggregated_block),
+        }
+    }
+
+    fn unit_time(
+        completed_jobs: &[ProverJobWorkload],
+        job_type: ProverJobType,
+    ) -> Option<Duration> {
+        let (total_time, total_size) = completed_jobs
+            .iter()
+            .filter(|job| job.job_type == job_type)
+            .filter_map(|job| Some((job.proving_time?, job.size)))
+            .fold(
+                (Duration::default(), 0),
+                |(total_time, total_size), (time, size)| (total_time + time, total_size + size),
+            );
+
+        if total_size == 0 {
+            None
+        } else {
+            Some(total_time / total_size as u32)
+        }
+    }
+
+    fn estimate(&self, job: &ProverJobWorkload) -> Duration {
+        let unit_time = match job.job_type {
+            ProverJobType::SingleProof => self.chunk,
+            ProverJobType::AggregatedProof => self.aggregated_block,
+        };
+        unit_time * job.size as u32
+    }
+}
+
 /// Scaler oracle provides information for prover scaler
 /// service about required amount of provers for server
 /// to operate optimally.
@@ -12,23 +104,178 @@ pub struct ScalerOracle<DB: DatabaseInterface> {
 
     /// Number of idle provers running for faster up-scaling.
     idle_provers: u32,
+
+    /// Time in which the committed blocks are expected to be proven.
+    prove_deadline: Duration,
 }
 
 impl<DB: DatabaseInterface> ScalerOracle<DB> {
-    pub fn new(db: DB, idle_provers: u32) -> Self {
-        Self { db, idle_provers }
+    pub fn new(db: DB, idle_provers: u32, prove_deadline: Duration) -> Self {
+        Self {
+            db,
+            idle_provers,
+            prove_deadline,
+        }
     }
 
-    /// Decides how many prover entities should be created depending on the amount of pending blocks.
-    pub async fn provers_required(&mut self) -> anyhow::Result<u32> {
-        // Currently the logic of this method is very simple:
-        // We require a prover for each pending block or IDLE_RROVERS amount if there are not so many
-        // pending jobs.
-
+    /// Decides how many prover entities should be created, so that the pending jobs
+    /// are proven before the deadline, and estimates the time to clear the backlog.
+    pub async fn provers_required(
+        &mut self,
+        current_count: u32,
+    ) -> anyhow::Result<ScalingForecast> {
         let mut storage = self.db.acquire_connection().await?;
-        let pending_jobs = self.db.pending_jobs_count(&mut storage).await?;
-        let provers_required = std::cmp::max(pending_jobs, self.idle_provers);
+        let pending_jobs = self.db.load_pending_jobs_workload(&mut storage).await?;
+        let completed_jobs = self
+            .db
+            .load_completed_jobs_workload(&mut storage, PROVING_TIME_SAMPLES)
+            .await?;
+
+        let model = ProvingTimeModel::measure(&completed_jobs);
+        let forecast = forecast(
+            &pending_jobs,
+            model,
+            self.prove_deadline,
+            self.idle_provers,
+            current_count,
+            Utc::now(),
+        );
+
+        metrics::gauge!("prover.scaler.needed_provers", forecast.needed_count as f64);
+        metrics::gauge!("prover.scaler.pending_jobs", forecast.pending_jobs as f64);
+        metrics::gauge!(
+            "prover.scaler.backlog_work",
+            forecast.backlog_work_secs as f64
+        );
+        metrics::gauge!(
+            "prover.scaler.backlog_eta",
+            forecast.backlog_eta_secs as f64
+        );
+        metrics::gauge!(
+            "prover.scaler.chunk_proving_time",
+            model.chunk.as_secs_f64()
+        );
+        metrics::gauge!(
+            "prover.scaler.aggregated_block_proving_time",
+            model.aggregated_block.as_secs_f64()
+        );
+
+        Ok(forecast)
+    }
+}
+
+fn forecast(
+    pending_jobs: &[ProverJobWorkload],
+    model: ProvingTimeModel,
+    prove_deadline: Duration,
+    idle_provers: u32,
+    current_count: u32,
+    now: DateTime<Utc>,
+) -> ScalingForecast {
+    let pending_count = pending_jobs.len() as u32;
+    let estimates: Vec<_> = pending_jobs.iter().map(|job| model.estimate(job)).collect();
+    let backlog_work: Duration = estimates.iter().sum();
+    // Jobs can't be split between provers, so the backlog can't be cleared faster than the longest job.
+    let longest_job = estimates.iter().max().copied().unwrap_or_default();
+
+    // The oldest job determines how much time is left before the deadline is missed.
+    let time_left = pending_jobs
+        .iter()
+        .map(|job| job.created_at)
+        .min()
+        .and_then(|oldest| {
+            (oldest + chrono::Duration::from_std(prove_deadline).ok()? - now)
+                .to_std()
+                .ok()
+        })
+        .unwrap_or_default();
+    let window = std::cmp::max(time_left, longest_job);
+
+    let provers_for_deadline = if window == Duration::default() {
+        pending_count
+    } else {
+        (backlog_work.as_secs_f64() / window.as_secs_f64()).ceil() as u32
+    };
+    // There is no use in more provers than jobs.
+    let needed_count = std::cmp::max(
+        std::cmp::min(provers_for_deadline.max(1), pending_count),
+        idle_provers,
+    );
+
+    let eta = |provers: u32| {
+        if pending_count == 0 {
+            Some(Duration::default())
+        } else if provers == 0 {
+            None
+        } else {
+            Some(std::cmp::max(backlog_work / provers, longest_job))
+        }
+    };
+
+    ScalingForecast {
+        needed_count,
+        pending_jobs: pending_count,
+        backlog_work_secs: backlog_work.as_secs(),
+        backlog_eta_secs: eta(needed_count).unwrap_or_default().as_secs(),
+        current_backlog_eta_secs: eta(current_count).map(|eta| eta.as_secs()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(job_type: ProverJobType, size: usize, age_secs: i64) -> ProverJobWorkload {
+        ProverJobWorkload {
+            job_type,
+            size,
+            created_at: Utc::now() - chrono::Duration::seconds(age_secs),
+            proving_time: None,
+        }
+    }
+
+    #[test]
+    fn proving_time_is_measured() {
+        let mut completed = job(ProverJobType::SingleProof, 10, 0);
+        completed.proving_time = Some(Duration::from_secs(50));
+        let model = ProvingTimeModel::measure(&[completed]);
+
+        assert_eq!(model.chunk, Duration::from_secs(5));
+        assert_eq!(
+            model.aggregated_block,
+            DEFAULT_AGGREGATED_BLOCK_PROVING_TIME
+        );
+    }
+
+    #[test]
+    fn forecast_meets_deadline() {
+        let model = ProvingTimeModel {
+            chunk: Duration::from_secs(1),
+            aggregated_block: Duration::from_secs(10),
+        };
+        let deadline = Duration::from_secs(100);
+
+        // No pending jobs: only the idle provers are required.
+        let idle = forecast(&[], model, deadline, 1, 0, Utc::now());
+        assert_eq!(idle.needed_count, 1);
+        assert_eq!(idle.current_backlog_eta_secs, Some(0));
+
+        // 10 jobs for 40 seconds each, 60 seconds are left before the deadline.
+        let jobs: Vec<_> = (0..10)
+            .map(|_| job(ProverJobType::SingleProof, 40, 40))
+            .collect();
+        let result = forecast(&jobs, model, deadline, 1, 2, Utc::now());
+        assert_eq!(result.needed_count, 7);
+        assert_eq!(result.backlog_work_secs, 400);
+        assert_eq!(result.current_backlog_eta_secs, Some(200));
 
-        Ok(provers_required)
+        // The deadline is already missed, so every job needs a prover.
+        let jobs: Vec<_> = (0..3)
+            .map(|_| job(ProverJobType::AggregatedProof, 2, 200))
+            .collect();
+        let result = forecast(&jobs, model, deadline, 1, 0, Utc::now());
+        assert_eq!(result.needed_count, 3);
+        assert_eq!(result.backlog_eta_secs, 20);
+        assert_eq!(result.current_backlog_eta_secs, None);
     }
 }
@@ -1,23 +1,30 @@
 // Built-in
 use std::clone::Clone;
+use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 // External uses
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use tokio::sync::RwLock;
 use tokio::time::sleep;
 // Workspace uses
+use zksync_circuit::serialization::ProverData;
+use zksync_crypto::binary_encoding;
+use zksync_crypto::merkle_tree::parallel_smt::SparseMerkleTreeSerializableCacheBN256;
 use zksync_crypto::params::account_tree_depth;
 use zksync_crypto::proof::{AggregatedProof, SingleProof};
 use zksync_storage::chain::block::records::AccountTreeCache;
 use zksync_storage::prover::records::{StorageBlockWitness, StorageProverJobQueue, StoredProof};
+use zksync_storage::utils::decode_binary_or_json;
 use zksync_storage::StorageProcessor;
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::Block,
-    prover::{ProverJob, ProverJobStatus, ProverJobType},
-    AccountId, AccountMap, AccountTree, AccountUpdates, Address, BlockNumber,
+    prover::{
+        FailedProverJob, ProverJob, ProverJobStatus, ProverJobType, ProverJobWorkload, ProverStats,
+    },
+    Account, AccountId, AccountMap, AccountTree, AccountUpdates, Address, BlockNumber,
 };
 // Local uses
 use crate::DatabaseInterface;
@@ -31,11 +38,15 @@ pub struct MockDatabase {
     blocks: Arc<RwLock<Vec<Block>>>,
     account_tree_cache: Arc<RwLock<AccountTreeCache>>,
     accounts_state: Arc<RwLock<(u32, AccountMap)>>,
+    /// Account updates made by the blocks on top of `accounts_state`.
+    state_diffs: Arc<RwLock<BTreeMap<BlockNumber, AccountUpdates>>>,
+    prover_stats: Arc<RwLock<HashMap<String, ProverStats>>>,
 }
 
 impl MockDatabase {
     pub fn new() -> Self {
         let (circuit_tree, accounts) = Self::get_default_tree_and_accounts();
+        // The initial cache is stored in the legacy JSON encoding to check that it's still supported.
         let tree_cache = serde_json::to_string(&circuit_tree.get_internals()).unwrap();
 
         Self {
@@ -45,9 +56,12 @@ impl MockDatabase {
             blocks: Arc::new(RwLock::new(Vec::new())),
             account_tree_cache: Arc::new(RwLock::new(AccountTreeCache {
                 block: 0,
-                tree_cache,
+                tree_cache: Some(tree_cache),
+                tree_cache_binary: None,
             })),
             accounts_state: Arc::new(RwLock::new((0, accounts))),
+            state_diffs: Arc::new(RwLock::new(BTreeMap::new())),
+            prover_stats: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -74,6 +88,96 @@ impl MockDatabase {
     pub async fn add_block(&self, block: Block) {
         self.blocks.write().await.push(block);
     }
+
+    /// Adds the block along with the account updates made by it.
+    pub async fn add_block_with_updates(&self, block: Block, updates: AccountUpdates) {
+        self.state_diffs
+            .write()
+            .await
+            .insert(block.block_number, updates);
+        self.add_block(block).await;
+    }
+
+    pub async fn stored_witnesses(&self) -> Vec<BlockNumber> {
+        let mut blocks: Vec<_> = self
+            .block_witness
+            .read()
+            .await
+            .iter()
+            .map(|witness| BlockNumber(witness.block as u32))
+            .collect();
+        blocks.sort_unstable();
+        blocks
+    }
+
+    pub async fn account_tree_cache_block(&self) -> BlockNumber {
+        BlockNumber(self.account_tree_cache.read().await.block as u32)
+    }
+
+    async fn update_prover_stats(&self, prover_name: &str, update: impl FnOnce(&mut ProverStats)) {
+        let mut prover_stats = self.prover_stats.write().await;
+        let stats = prover_stats
+            .entry(prover_name.to_string())
+            .or_insert_with(|| ProverStats {
+                prover_name: prover_name.to_string(),
+                jobs_started: 0,
+                jobs_completed: 0,
+                jobs_failed: 0,
+                proving_time_ms: 0,
+                last_seen_at: Utc::now(),
+            });
+        stats.last_seen_at = Utc::now();
+        update(stats);
+    }
+
+    async fn job_workload(&self, job: &StorageProverJobQueue) -> ProverJobWorkload {
+        let (job_type, size) = if job.job_type == ProverJobType::AggregatedProof.to_string() {
+            let blocks = job.last_block - job.first_block + 1;
+            (ProverJobType::AggregatedProof, blocks as usize)
+        } else {
+            let chunks = self
+                .blocks
+                .read()
+                .await
+                .iter()
+                .find(|block| i64::from(*block.block_number) == job.first_block)
+                .map(|block| block.block_chunks_size)
+                .unwrap_or_default();
+            (ProverJobType::SingleProof, chunks)
+        };
+        let proving_time = if job.job_status == ProverJobStatus::Done.to_number() {
+            job.leased_at
+                .and_then(|leased_at| (job.updated_at - leased_at).to_std().ok())
+        } else {
+            None
+        };
+
+        ProverJobWorkload {
+            job_type,
+            size,
+            created_at: job.created_at,
+            proving_time,
+        }
+    }
+
+    fn lease_expiration(lease_duration: Duration) -> DateTime<Utc> {
+        Utc::now() + chrono::Duration::from_std(lease_duration).unwrap()
+    }
+
+    /// Returns the job to the queue or marks it as failed if it runs out of retries.
+    fn fail_job(job: &mut StorageProverJobQueue, reason: &str, max_retries: u32) {
+        job.retry_count += 1;
+        job.job_status = if job.retry_count as u32 >= max_retries {
+            ProverJobStatus::Failed.to_number()
+        } else {
+            ProverJobStatus::Idle.to_number()
+        };
+        job.failure_reason = Some(reason.to_string());
+        job.updated_at = Utc::now();
+        job.updated_by = "server_job_failure".to_string();
+        job.leased_at = None;
+        job.lease_expires_at = None;
+    }
 }
 
 #[async_trait::async_trait]
@@ -108,6 +212,10 @@ impl DatabaseInterface for MockDatabase {
             id,
             job_priority,
             job_data,
+            retry_count: 0,
+            failure_reason: None,
+            leased_at: None,
+            lease_expires_at: None,
         };
 
         prover_job_queue.1.push(new_job);
@@ -141,7 +249,10 @@ impl DatabaseInterface for MockDatabase {
             .await
             .1
             .iter()
-            .filter(|job| job.job_status == ProverJobStatus::Idle.to_number())
+            .filter(|job| {
+                job.job_status == ProverJobStatus::Idle.to_number()
+                    || job.job_status == ProverJobStatus::InProgress.to_number()
+            })
             .count();
 
         Ok(count as u32)
@@ -170,21 +281,89 @@ impl DatabaseInterface for MockDatabase {
         Ok(single_proof)
     }
 
-    async fn mark_stale_jobs_as_idle(&self, _: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+    async fn mark_stale_jobs_as_idle(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        max_retries: u32,
+    ) -> anyhow::Result<()> {
         let now = Utc::now();
-        let prover_job_queue = &mut self.prover_job_queue.write().await.1;
-
-        for job in prover_job_queue.iter_mut() {
-            if now - job.updated_at > chrono::Duration::seconds(10) {
-                job.job_status = ProverJobStatus::Idle.to_number();
-                job.updated_at = now;
-                job.updated_by = "server_clean_idle".to_string();
+        let mut lost_jobs = Vec::new();
+        {
+            let prover_job_queue = &mut self.prover_job_queue.write().await.1;
+            for job in prover_job_queue.iter_mut() {
+                let lease_expires_at = job
+                    .lease_expires_at
+                    .unwrap_or_else(|| job.updated_at + chrono::Duration::seconds(10));
+                if job.job_status == ProverJobStatus::InProgress.to_number()
+                    && lease_expires_at <= now
+                {
+                    lost_jobs.push(job.updated_by.clone());
+                    Self::fail_job(job, "lease expired", max_retries);
+                }
             }
         }
 
+        for prover_name in lost_jobs {
+            self.update_prover_stats(&prover_name, |stats| stats.jobs_failed += 1)
+                .await;
+        }
+
         Ok(())
     }
 
+    async fn record_prover_job_failure(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        job_id: i32,
+        prover_name: &str,
+        reason: &str,
+        max_retries: u32,
+    ) -> anyhow::Result<bool> {
+        let recorded = {
+            let prover_job_queue = &mut self.prover_job_queue.write().await.1;
+            let prover_job = prover_job_queue.iter_mut().find(|job| {
+                job.id == job_id
+                    && job.job_status == ProverJobStatus::InProgress.to_number()
+                    && job.updated_by == prover_name
+            });
+
+            if let Some(job) = prover_job {
+                Self::fail_job(job, reason, max_retries);
+                true
+            } else {
+                false
+            }
+        };
+        if recorded {
+            self.update_prover_stats(prover_name, |stats| stats.jobs_failed += 1)
+                .await;
+        }
+
+        Ok(recorded)
+    }
+
+    async fn requeue_failed_prover_job(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        job_id: i32,
+    ) -> anyhow::Result<bool> {
+        let prover_job_queue = &mut self.prover_job_queue.write().await.1;
+        let prover_job = prover_job_queue
+            .iter_mut()
+            .find(|job| job.id == job_id && job.job_status == ProverJobStatus::Failed.to_number());
+
+        if let Some(job) = prover_job {
+            job.job_status = ProverJobStatus::Idle.to_number();
+            job.retry_count = 0;
+            job.failure_reason = None;
+            job.updated_at = Utc::now();
+            job.updated_by = "server_requeue".to_string();
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
     async fn load_last_verified_block(
         &self,
         _: &mut StorageProcessor<'_>,
@@ -210,12 +389,14 @@ impl DatabaseInterface for MockDatabase {
     async fn load_account_tree_cache(
         &self,
         _: &mut StorageProcessor<'_>,
-    ) -> anyhow::Result<Option<(BlockNumber, serde_json::Value)>> {
+    ) -> anyhow::Result<Option<(BlockNumber, SparseMerkleTreeSerializableCacheBN256)>> {
         let account_tree_cache = self.account_tree_cache.read().await;
         let result = (
             BlockNumber(account_tree_cache.block as u32),
-            serde_json::from_str(&account_tree_cache.tree_cache)
-                .expect("Failed to deserialize Account Tree Cache"),
+            decode_binary_or_json(
+                account_tree_cache.tree_cache_binary.as_deref(),
+                account_tree_cache.tree_cache.as_deref(),
+            )?,
         );
 
         Ok(Some(result))
@@ -224,28 +405,38 @@ impl DatabaseInterface for MockDatabase {
     async fn load_idle_prover_job_from_job_queue(
         &self,
         _: &mut StorageProcessor<'_>,
+        prover_name: &str,
+        lease_duration: Duration,
     ) -> anyhow::Result<Option<ProverJob>> {
-        let prover_job_queue = &mut self.prover_job_queue.write().await.1;
-        let idle_prover_job = prover_job_queue
-            .iter_mut()
-            .filter(|job| job.job_status == ProverJobStatus::Idle.to_number())
-            .max_by_key(|job| (job.job_priority, job.id));
-
-        let prover_job = if let Some(job) = idle_prover_job {
-            job.job_status = ProverJobStatus::InProgress.to_number();
-            job.updated_at = Utc::now();
-            job.updated_by = "server_give_job".to_string();
-
-            Some(ProverJob::new(
-                job.id,
-                BlockNumber(job.first_block as u32),
-                BlockNumber(job.last_block as u32),
-                job.job_data.clone(),
-            ))
-        } else {
-            None
+        let prover_job = {
+            let prover_job_queue = &mut self.prover_job_queue.write().await.1;
+            let idle_prover_job = prover_job_queue
+                .iter_mut()
+                .filter(|job| job.job_status == ProverJobStatus::Idle.to_number())
+                .min_by_key(|job| (job.job_priority, job.first_block, job.id));
+
+            idle_prover_job.map(|job| {
+                let now = Utc::now();
+                job.job_status = ProverJobStatus::InProgress.to_number();
+                job.updated_at = now;
+                job.updated_by = prover_name.to_string();
+                job.leased_at = Some(now);
+                job.lease_expires_at = Some(Self::lease_expiration(lease_duration));
+
+                ProverJob::new(
+                    job.id,
+                    BlockNumber(job.first_block as u32),
+                    BlockNumber(job.last_block as u32),
+                    job.job_data.clone(),
+                )
+            })
         };
 
+        if prover_job.is_some() {
+            self.update_prover_stats(prover_name, |stats| stats.jobs_started += 1)
+                .await;
+        }
+
         Ok(prover_job)
     }
 
@@ -254,16 +445,28 @@ impl DatabaseInterface for MockDatabase {
         _: &mut StorageProcessor<'_>,
         job_id: i32,
         prover_name: &str,
-    ) -> anyhow::Result<()> {
-        let prover_job_queue = &mut self.prover_job_queue.write().await.1;
-        let prover_job = prover_job_queue.iter_mut().find(|job| job.id == job_id);
+        lease_duration: Duration,
+    ) -> anyhow::Result<bool> {
+        let lease_renewed = {
+            let prover_job_queue = &mut self.prover_job_queue.write().await.1;
+            let prover_job = prover_job_queue.iter_mut().find(|job| {
+                job.id == job_id
+                    && job.job_status == ProverJobStatus::InProgress.to_number()
+                    && job.updated_by == prover_name
+            });
 
-        if let Some(job) = prover_job {
-            job.updated_at = Utc::now();
-            job.updated_by = prover_name.to_string();
-        }
+            if let Some(job) = prover_job {
+                job.updated_at = Utc::now();
+                job.updated_by = prover_name.to_string();
+                job.lease_expires_at = Some(Self::lease_expiration(lease_duration));
+                true
+            } else {
+                false
+            }
+        };
+        self.update_prover_stats(prover_name, |_| {}).await;
 
-        Ok(())
+        Ok(lease_renewed)
     }
 
     async fn store_proof(
@@ -273,13 +476,31 @@ impl DatabaseInterface for MockDatabase {
         block_number: BlockNumber,
         proof: &SingleProof,
     ) -> anyhow::Result<()> {
-        let prover_job_queue = &mut self.prover_job_queue.write().await.1;
-        let prover_job = prover_job_queue.iter_mut().find(|job| job.id == job_id);
+        let completed_job = {
+            let prover_job_queue = &mut self.prover_job_queue.write().await.1;
+            let prover_job = prover_job_queue.iter_mut().find(|job| job.id == job_id);
+
+            prover_job.map(|job| {
+                let now = Utc::now();
+                let prover_name = job.updated_by.clone();
+                let proving_time = job
+                    .leased_at
+                    .map(|leased_at| (now - leased_at).num_milliseconds().max(0) as u64)
+                    .unwrap_or_default();
 
-        if let Some(job) = prover_job {
-            job.updated_at = Utc::now();
-            job.job_status = ProverJobStatus::Done.to_number();
-            job.updated_by = "server_finish_job".to_string();
+                job.updated_at = now;
+                job.job_status = ProverJobStatus::Done.to_number();
+                job.updated_by = "server_finish_job".to_string();
+
+                (prover_name, proving_time)
+            })
+        };
+        if let Some((prover_name, proving_time)) = completed_job {
+            self.update_prover_stats(&prover_name, |stats| {
+                stats.jobs_completed += 1;
+                stats.proving_time_ms += proving_time;
+            })
+            .await;
         }
         let proof = StoredProof {
             block_number: i64::from(*block_number),
@@ -315,6 +536,8 @@ impl DatabaseInterface for MockDatabase {
             {
                 job.job_status = ProverJobStatus::Idle.to_number();
                 job.updated_at = Utc::now();
+                job.leased_at = None;
+                job.lease_expires_at = None;
             }
         }
 
@@ -324,37 +547,70 @@ impl DatabaseInterface for MockDatabase {
     async fn load_committed_state(
         &self,
         _: &mut StorageProcessor<'_>,
-        _block: Option<BlockNumber>,
+        block: Option<BlockNumber>,
     ) -> anyhow::Result<(BlockNumber, AccountMap)> {
-        let (last_block, accounts) = self.accounts_state.read().await.clone();
-        Ok((BlockNumber(last_block), accounts))
+        let (last_block, mut accounts) = self.accounts_state.read().await.clone();
+        let mut last_block = BlockNumber(last_block);
+        let state_diffs = self.state_diffs.read().await;
+        let last_diff = block.unwrap_or(BlockNumber(u32::MAX));
+        for (&block_number, updates) in state_diffs.range(..=last_diff) {
+            for (id, update) in updates {
+                if let Some(account) = Account::apply_update(accounts.remove(id), update.clone()) {
+                    accounts.insert(*id, account);
+                }
+            }
+            last_block = block_number;
+        }
+        Ok((last_block, accounts))
     }
 
     async fn load_state_diff(
         &self,
         _: &mut StorageProcessor<'_>,
-        _from_block: BlockNumber,
-        _to_block: Option<BlockNumber>,
+        from_block: BlockNumber,
+        to_block: Option<BlockNumber>,
     ) -> anyhow::Result<Option<(BlockNumber, AccountUpdates)>> {
-        Ok(None)
+        let state_diffs = self.state_diffs.read().await;
+        let to_block = to_block
+            .or_else(|| state_diffs.keys().next_back().copied())
+            .unwrap_or_default();
+        let account_updates: AccountUpdates = if from_block <= to_block {
+            state_diffs
+                .range(from_block + 1..=to_block)
+                .flat_map(|(_, updates)| updates.iter().cloned())
+                .collect()
+        } else {
+            state_diffs
+                .range(to_block + 1..=from_block)
+                .rev()
+                .flat_map(|(_, updates)| updates.iter().rev())
+                .map(|(id, update)| (*id, update.reversed_update()))
+                .collect()
+        };
+
+        if account_updates.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some((to_block, account_updates)))
+        }
     }
 
     async fn store_account_tree_cache(
         &self,
         _: &mut StorageProcessor<'_>,
         block: BlockNumber,
-        tree_cache: serde_json::Value,
+        tree_cache: &SparseMerkleTreeSerializableCacheBN256,
     ) -> anyhow::Result<()> {
         if *block == 0 {
             return Ok(());
         }
-        let tree_cache =
-            serde_json::to_string(&tree_cache).expect("Failed to serialize Account Tree Cache");
+        let tree_cache = binary_encoding::encode(tree_cache)?;
 
         let mut account_tree_cache = self.account_tree_cache.write().await;
         *account_tree_cache = AccountTreeCache {
             block: i64::from(*block),
-            tree_cache,
+            tree_cache: None,
+            tree_cache_binary: Some(tree_cache),
         };
 
         Ok(())
@@ -364,12 +620,13 @@ impl DatabaseInterface for MockDatabase {
         &self,
         _: &mut StorageProcessor<'_>,
         block_number: BlockNumber,
-    ) -> anyhow::Result<Option<serde_json::Value>> {
+    ) -> anyhow::Result<Option<ProverData>> {
         let block_witness = self.block_witness.read().await;
         let witness = block_witness
             .iter()
             .find(|witness| witness.block == *block_number as i64)
-            .map(|w| serde_json::from_str(&w.witness).expect("Failed to deserialize witness"));
+            .map(|w| decode_binary_or_json(w.witness_binary.as_deref(), w.witness.as_deref()))
+            .transpose()?;
 
         Ok(witness)
     }
@@ -378,9 +635,9 @@ impl DatabaseInterface for MockDatabase {
         &self,
         _: &mut StorageProcessor<'_>,
         block: BlockNumber,
-        witness: serde_json::Value,
+        witness: &ProverData,
     ) -> anyhow::Result<()> {
-        let witness_str = serde_json::to_string(&witness).expect("Failed to serialize witness");
+        let encoded_witness = binary_encoding::encode(witness)?;
         let mut block_witness = self.block_witness.write().await;
         let is_block_not_saved_yet = !block_witness
             .iter()
@@ -389,10 +646,81 @@ impl DatabaseInterface for MockDatabase {
         if is_block_not_saved_yet {
             block_witness.push(StorageBlockWitness {
                 block: *block as i64,
-                witness: witness_str,
+                witness: None,
+                witness_binary: Some(encoded_witness),
             });
         }
 
         Ok(())
     }
+
+    async fn load_pending_jobs_workload(
+        &self,
+        _: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<ProverJobWorkload>> {
+        let prover_job_queue = &self.prover_job_queue.read().await.1;
+        let mut workload = Vec::new();
+        for job in prover_job_queue.iter().filter(|job| {
+            job.job_status == ProverJobStatus::Idle.to_number()
+                || job.job_status == ProverJobStatus::InProgress.to_number()
+        }) {
+            workload.push(self.job_workload(job).await);
+        }
+
+        Ok(workload)
+    }
+
+    async fn load_completed_jobs_workload(
+        &self,
+        _: &mut StorageProcessor<'_>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<ProverJobWorkload>> {
+        let prover_job_queue = &self.prover_job_queue.read().await.1;
+        let mut workload = Vec::new();
+        for job in prover_job_queue
+            .iter()
+            .rev()
+            .filter(|job| job.job_status == ProverJobStatus::Done.to_number())
+            .take(limit as usize)
+        {
+            workload.push(self.job_workload(job).await);
+        }
+
+        Ok(workload)
+    }
+
+    async fn load_prover_stats(
+        &self,
+        _: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<ProverStats>> {
+        let mut stats: Vec<_> = self.prover_stats.read().await.values().cloned().collect();
+        stats.sort_by(|a, b| a.prover_name.cmp(&b.prover_name));
+
+        Ok(stats)
+    }
+
+    async fn load_failed_prover_jobs(
+        &self,
+        _: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<FailedProverJob>> {
+        let failed_jobs = self
+            .prover_job_queue
+            .read()
+            .await
+            .1
+            .iter()
+            .filter(|job| job.job_status == ProverJobStatus::Failed.to_number())
+            .map(|job| FailedProverJob {
+                job_id: job.id,
+                job_type: job.job_type.clone(),
+                first_block: BlockNumber(job.first_block as u32),
+                last_block: BlockNumber(job.last_block as u32),
+                retry_count: job.retry_count as u32,
+                failure_reason: job.failure_reason.clone(),
+                updated_at: job.updated_at,
+            })
+            .collect();
+
+        Ok(failed_jobs)
+    }
 }
@@ -43,6 +43,7 @@ impl Default for MockProverOptions {
             core: Core {
                 gone_timeout: 60000,
                 idle_provers: 1,
+                max_job_retries: 3,
             },
             witness_generator: WitnessGenerator {
                 prepare_data_interval: 500,
@@ -57,7 +58,12 @@ impl Default for MockProverOptions {
 async fn spawn_server(database: MockDatabase) {
     let prover_options = MockProverOptions::default();
 
-    run_prover_server(database, prover_options.0, prover_options.1);
+    run_prover_server(
+        database,
+        prover_options.0,
+        prover_options.1,
+        Duration::from_secs(60),
+    );
 }
 
 #[tokio::test]
@@ -1,9 +1,11 @@
-use std::time::Instant;
 // Built-in
+use std::collections::BTreeSet;
+use std::time::Instant;
 use std::{thread, time};
 // External
-use futures::channel::mpsc;
-use tokio::time::sleep;
+use anyhow::ensure;
+use futures::{channel::mpsc, stream::FuturesUnordered, StreamExt};
+use tokio::{task::JoinHandle, time::sleep};
 // Workspace deps
 use crate::database_interface::DatabaseInterface;
 use zksync_circuit::serialization::ProverData;
@@ -11,14 +13,23 @@ use zksync_circuit::witness::utils::build_block_witness;
 use zksync_crypto::circuit::CircuitAccountTree;
 use zksync_crypto::params::account_tree_depth;
 use zksync_types::block::Block;
-use zksync_types::BlockNumber;
+use zksync_types::{Account, AccountMap, BlockNumber};
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
+/// The account tree cache is stored once the witnesses for this amount of blocks
+/// after the previously cached block are stored.
+const ACCOUNT_TREE_CACHE_INTERVAL: u32 = 10;
+
 /// The essential part of this structure is `maintain` function
 /// which runs forever and adds data to the database.
 ///
-/// This will generate and store in db witnesses for blocks with indexes
-/// start_block, start_block + block_step, start_block + 2*block_step, ...
+/// Witnesses are generated for the blocks starting with `start_block` in a pipeline:
+/// the generator keeps a single account tree in memory and advances it block by block
+/// by applying the state diffs, while the witnesses are built in parallel by the worker threads,
+/// each one using the snapshot of the tree taken before the corresponding block.
+/// Workers store the witnesses as soon as they are ready, so the witnesses may be stored out of order.
+/// The account tree cache is updated periodically as the witnesses are stored,
+/// so the generator doesn't have to restore the tree from the outdated cache after the restart.
 pub struct WitnessGenerator<DB: DatabaseInterface> {
     /// Connection to the database.
     database: DB,
@@ -26,29 +37,58 @@ pub struct WitnessGenerator<DB: DatabaseInterface> {
     rounds_interval: time::Duration,
 
     start_block: BlockNumber,
-    block_step: BlockNumber,
+    /// Maximum amount of witnesses built in parallel.
+    workers: usize,
 }
 
 #[derive(Debug)]
 enum BlockInfo {
     NotReadyBlock,
-    WithWitness,
+    WithWitness(Block),
     NoWitness(Block),
 }
 
+/// Keeps track of the stored witnesses, which may be stored out of order.
+#[derive(Debug)]
+struct WitnessProgress {
+    /// Witnesses for all the blocks up to this one are stored.
+    last_stored_block: BlockNumber,
+    /// Blocks after `last_stored_block` with the stored witnesses.
+    stored_blocks: BTreeSet<BlockNumber>,
+}
+
+impl WitnessProgress {
+    fn new(last_stored_block: BlockNumber) -> Self {
+        Self {
+            last_stored_block,
+            stored_blocks: BTreeSet::new(),
+        }
+    }
+
+    fn mark_stored(&mut self, block: BlockNumber) {
+        if block <= self.last_stored_block {
+            return;
+        }
+        self.stored_blocks.insert(block);
+        while self.stored_blocks.remove(&(self.last_stored_block + 1)) {
+            self.last_stored_block = self.last_stored_block + 1;
+        }
+    }
+}
+
 impl<DB: DatabaseInterface> WitnessGenerator<DB> {
     /// Creates a new `WitnessGenerator` object.
     pub fn new(
         database: DB,
         rounds_interval: time::Duration,
         start_block: BlockNumber,
-        block_step: BlockNumber,
+        workers: usize,
     ) -> Self {
         Self {
             database,
             rounds_interval,
             start_block,
-            block_step,
+            workers: workers.max(1),
         }
     }
 
@@ -90,7 +130,7 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
             if witness.is_none() {
                 BlockInfo::NoWitness(block)
             } else {
-                BlockInfo::WithWitness
+                BlockInfo::WithWitness(block)
             }
         } else {
             BlockInfo::NotReadyBlock
@@ -100,10 +140,11 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         Ok(block_info)
     }
 
+    /// Loads the account tree and the accounts state after the given block.
     async fn load_account_tree(
         &self,
         block: BlockNumber,
-    ) -> Result<CircuitAccountTree, anyhow::Error> {
+    ) -> Result<(CircuitAccountTree, AccountMap), anyhow::Error> {
         let fn_start = Instant::now();
 
         let mut storage = self.database.acquire_connection().await?;
@@ -114,25 +155,21 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         metrics::histogram!("witness_generator", start.elapsed(), "stage" => "load_cache");
 
         let start = Instant::now();
+        let (_, accounts) = self
+            .database
+            .load_committed_state(&mut storage, Some(block))
+            .await?;
+        for (id, account) in &accounts {
+            circuit_account_tree.insert(**id, account.clone().into());
+        }
         if let Some((cached_block, account_tree_cache)) = cache {
-            let (_, accounts) = self
-                .database
-                .load_committed_state(&mut storage, Some(block))
-                .await?;
-            for (id, account) in accounts {
-                circuit_account_tree.insert(*id, account.into());
-            }
-            circuit_account_tree.set_internals(serde_json::from_value(account_tree_cache)?);
+            circuit_account_tree.set_internals(account_tree_cache);
             if block != cached_block {
                 // There is no relevant cache, so we have to use some outdated cache and update the tree.
                 metrics::increment_counter!("witness_generator.cache_access", "type" => "miss");
 
                 vlog::info!("Reconstructing the cache for the block {} using the cached tree for the block {}", block, cached_block);
 
-                let (_, accounts) = self
-                    .database
-                    .load_committed_state(&mut storage, Some(block))
-                    .await?;
                 if let Some((_, account_updates)) = self
                     .database
                     .load_state_diff(&mut storage, block, Some(cached_block))
@@ -155,11 +192,7 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
                 let start = Instant::now();
                 let account_tree_cache = circuit_account_tree.get_internals();
                 self.database
-                    .store_account_tree_cache(
-                        &mut storage,
-                        block,
-                        serde_json::to_value(account_tree_cache)?,
-                    )
+                    .store_account_tree_cache(&mut storage, block, &account_tree_cache)
                     .await?;
                 metrics::histogram!("witness_generator", start.elapsed(), "stage" => "store_cache");
             } else {
@@ -168,24 +201,14 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
             }
         } else {
             // There are no caches at all.
-            let (_, accounts) = self
-                .database
-                .load_committed_state(&mut storage, Some(block))
-                .await?;
-            for (id, account) in accounts {
-                circuit_account_tree.insert(*id, account.into());
-            }
             circuit_account_tree.root_hash();
 
             metrics::histogram!("witness_generator", start.elapsed(), "stage" => "recreate_tree_from_scratch");
 
             let start = Instant::now();
-            let tree_cache = serde_json::to_value(circuit_account_tree.get_internals())?;
-            metrics::histogram!("witness_generator", start.elapsed(), "stage" => "serialize_cache");
-
-            let start = Instant::now();
+            let tree_cache = circuit_account_tree.get_internals();
             self.database
-                .store_account_tree_cache(&mut storage, block, tree_cache)
+                .store_account_tree_cache(&mut storage, block, &tree_cache)
                 .await?;
             metrics::histogram!("witness_generator", start.elapsed(), "stage" => "store_cache");
         }
@@ -206,46 +229,172 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
         metrics::histogram!("witness_generator", start.elapsed(), "stage" => "ensure_root_hash");
 
         metrics::histogram!("witness_generator", fn_start.elapsed(), "stage" => "load_account_tree");
-        Ok(circuit_account_tree)
+        Ok((circuit_account_tree, accounts))
     }
 
-    async fn prepare_witness_and_save_it(&self, block: Block) -> anyhow::Result<()> {
-        let fn_start = Instant::now();
+    /// Applies the changes made by the block to the tree and the accounts state.
+    /// Unlike building the witness, only the final state of the updated accounts is inserted into the tree.
+    async fn advance_account_tree(
+        &self,
+        circuit_account_tree: &mut CircuitAccountTree,
+        accounts: &mut AccountMap,
+        block: &Block,
+    ) -> anyhow::Result<()> {
+        let start = Instant::now();
         let mut storage = self.database.acquire_connection().await?;
+        let state_diff = self
+            .database
+            .load_state_diff(
+                &mut storage,
+                block.block_number - 1,
+                Some(block.block_number),
+            )
+            .await?;
 
-        let start = Instant::now();
-        let mut circuit_account_tree = self.load_account_tree(block.block_number - 1).await?;
-        metrics::histogram!("witness_generator", start.elapsed(), "stage" => "load_tree_full");
+        if let Some((_, account_updates)) = state_diff {
+            let mut updated_accounts = Vec::with_capacity(account_updates.len());
+            for (id, update) in account_updates {
+                if let Some(account) = Account::apply_update(accounts.remove(&id), update) {
+                    accounts.insert(id, account);
+                }
+                updated_accounts.push(id);
+            }
+            updated_accounts.sort_unstable();
+            updated_accounts.dedup();
+            for id in updated_accounts {
+                circuit_account_tree
+                    .insert(*id, accounts.get(&id).cloned().unwrap_or_default().into());
+            }
+        }
 
-        let start = Instant::now();
-        let witness: ProverData = build_block_witness(&mut circuit_account_tree, &block)?.into();
-        metrics::histogram!("witness_generator", start.elapsed(), "stage" => "build_witness");
+        ensure!(
+            circuit_account_tree.root_hash() == block.new_root_hash,
+            "account tree root hash after block {} doesn't match the block root hash",
+            block.block_number
+        );
+        metrics::histogram!("witness_generator", start.elapsed(), "stage" => "advance_tree");
+        Ok(())
+    }
+
+    /// Builds the witness for the block in a separate thread and stores it.
+    /// The tree is expected to be the snapshot of the account tree before the block,
+    /// the job returns the tree after the block.
+    fn spawn_witness_job(
+        &self,
+        mut circuit_account_tree: CircuitAccountTree,
+        block: Block,
+    ) -> JoinHandle<anyhow::Result<(BlockNumber, CircuitAccountTree)>> {
+        let database = self.database.clone();
+        tokio::spawn(async move {
+            let fn_start = Instant::now();
+            let block_number = block.block_number;
 
+            let (witness, circuit_account_tree) = tokio::task::spawn_blocking(
+                move || -> anyhow::Result<(ProverData, CircuitAccountTree)> {
+                    let start = Instant::now();
+                    let witness = build_block_witness(&mut circuit_account_tree, &block)?.into();
+                    metrics::histogram!("witness_generator", start.elapsed(), "stage" => "build_witness");
+                    Ok((witness, circuit_account_tree))
+                },
+            )
+            .await??;
+
+            let start = Instant::now();
+            let mut storage = database.acquire_connection().await?;
+            database
+                .store_witness(&mut storage, block_number, &witness)
+                .await?;
+            metrics::histogram!("witness_generator", start.elapsed(), "stage" => "store_witness");
+
+            metrics::histogram!("witness_generator", fn_start.elapsed(), "stage" => "prepare_witness_and_save_it");
+            Ok((block_number, circuit_account_tree))
+        })
+    }
+
+    /// Stores the cache of the account tree after the given block.
+    async fn store_account_tree_cache(
+        &self,
+        circuit_account_tree: &mut CircuitAccountTree,
+        block: BlockNumber,
+    ) -> anyhow::Result<()> {
         let start = Instant::now();
+        circuit_account_tree.root_hash();
+        let tree_cache = circuit_account_tree.get_internals();
+        let mut storage = self.database.acquire_connection().await?;
         self.database
-            .store_witness(
-                &mut storage,
-                block.block_number,
-                serde_json::to_value(witness).expect("Witness serialize to json"),
-            )
+            .store_account_tree_cache(&mut storage, block, &tree_cache)
             .await?;
-        metrics::histogram!("witness_generator", start.elapsed(), "stage" => "store_witness");
-
-        metrics::histogram!("witness_generator", fn_start.elapsed(), "stage" => "prepare_witness_and_save_it");
+        metrics::histogram!("witness_generator", start.elapsed(), "stage" => "store_cache");
         Ok(())
     }
 
-    /// Returns next block for generating witness
-    fn next_witness_block(
-        current_block: BlockNumber,
-        block_step: BlockNumber,
-        block_info: &BlockInfo,
-    ) -> BlockNumber {
-        match block_info {
-            BlockInfo::NotReadyBlock => current_block, // Keep waiting
-            BlockInfo::WithWitness | BlockInfo::NoWitness(_) => {
-                BlockNumber(*current_block + *block_step)
-            } // Go to the next block
+    /// Runs the pipeline starting with the tree after the last block with all the preceding witnesses stored.
+    /// Returns an error if any of the witnesses failed, so the pipeline is restarted.
+    async fn run_pipeline(&self, progress: &mut WitnessProgress) -> anyhow::Result<()> {
+        let mut jobs = FuturesUnordered::new();
+        let result = self.process_blocks(progress, &mut jobs).await;
+        // The pipeline is restarted from the last stored block, so the outstanding jobs are of no use.
+        for job in jobs.iter() {
+            job.abort();
+        }
+        result
+    }
+
+    async fn process_blocks(
+        &self,
+        progress: &mut WitnessProgress,
+        jobs: &mut FuturesUnordered<JoinHandle<anyhow::Result<(BlockNumber, CircuitAccountTree)>>>,
+    ) -> anyhow::Result<()> {
+        let mut tree_block = progress.last_stored_block;
+        let (mut circuit_account_tree, mut accounts) = self.load_account_tree(tree_block).await?;
+        // `load_account_tree` makes sure that the cache for the initial block is stored.
+        let mut cached_block = tree_block;
+
+        loop {
+            // Start the jobs for the new blocks while there are free workers.
+            while jobs.len() < self.workers {
+                let next_block = tree_block + 1;
+                let block = match self.should_work_on_block(next_block).await? {
+                    BlockInfo::NotReadyBlock => break,
+                    BlockInfo::WithWitness(block) => {
+                        progress.mark_stored(next_block);
+                        block
+                    }
+                    BlockInfo::NoWitness(block) => {
+                        jobs.push(
+                            self.spawn_witness_job(circuit_account_tree.clone(), block.clone()),
+                        );
+                        block
+                    }
+                };
+                self.advance_account_tree(&mut circuit_account_tree, &mut accounts, &block)
+                    .await?;
+                tree_block = next_block;
+            }
+
+            // Wait for a job to complete, periodically checking for the new blocks if there are free workers.
+            let has_free_workers = jobs.len() < self.workers;
+            tokio::select! {
+                Some(result) = jobs.next() => {
+                    let (block_number, mut block_tree) = result??;
+                    progress.mark_stored(block_number);
+                    metrics::gauge!(
+                        "witness_generator.last_stored_block",
+                        *progress.last_stored_block as f64
+                    );
+
+                    // Only the cache for the block with all the preceding witnesses stored
+                    // is of use when the pipeline is restarted.
+                    if block_number <= progress.last_stored_block
+                        && *block_number >= *cached_block + ACCOUNT_TREE_CACHE_INTERVAL
+                    {
+                        self.store_account_tree_cache(&mut block_tree, block_number)
+                            .await?;
+                        cached_block = block_number;
+                    }
+                }
+                _ = sleep(self.rounds_interval), if has_free_workers => {}
+            }
         }
     }
 
@@ -253,33 +402,20 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
     /// awaiting `rounds_interval` time between updates.
     async fn maintain(self) {
         vlog::info!(
-            "preparing prover data routine started with start_block({}), block_step({})",
+            "preparing prover data routine started with start_block({}), workers({})",
             *self.start_block,
-            *self.block_step
+            self.workers
         );
-        let mut current_block = self.start_block;
+        let mut progress = WitnessProgress::new(self.start_block - 1);
         loop {
-            sleep(self.rounds_interval).await;
-            let should_work = match self.should_work_on_block(current_block).await {
-                Ok(should_work) => should_work,
-                Err(err) => {
-                    vlog::warn!("witness for block {} check failed: {}", current_block, err);
-                    continue;
-                }
-            };
-
-            let next_block = Self::next_witness_block(current_block, self.block_step, &should_work);
-            if let BlockInfo::NoWitness(block) = should_work {
-                let block_number = block.block_number;
-                if let Err(err) = self.prepare_witness_and_save_it(block).await {
-                    vlog::warn!("Witness generator ({},{}) failed to prepare witness for block: {}, err: {}",
-                        self.start_block, self.block_step, block_number, err);
-                    continue; // Retry the same block on the next iteration.
-                }
+            if let Err(err) = self.run_pipeline(&mut progress).await {
+                vlog::warn!(
+                    "Witness generator failed, restarting from the block {}: {}",
+                    *progress.last_stored_block + 1,
+                    err
+                );
             }
-
-            // Update current block.
-            current_block = next_block;
+            sleep(self.rounds_interval).await;
         }
     }
 }
@@ -287,47 +423,179 @@ impl<DB: DatabaseInterface> WitnessGenerator<DB> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::database::Database;
-    use zksync_crypto::Fr;
-    use zksync_types::{AccountId, H256, U256};
+    use crate::tests::mock::MockDatabase;
+    use num::BigUint;
+    use zksync_state::state::ZkSyncState;
+    use zksync_types::{
+        AccountId, AccountUpdates, Address, Deposit, ExecutedOperations, ExecutedPriorityOp,
+        PriorityOp, TokenId, ZkSyncPriorityOp, H256,
+    };
 
-    #[test]
-    fn test_next_witness_block() {
-        assert_eq!(
-            WitnessGenerator::<Database>::next_witness_block(
-                BlockNumber(3),
-                BlockNumber(4),
-                &BlockInfo::NotReadyBlock
-            ),
-            BlockNumber(3)
+    /// Creates the chain of blocks with a deposit to a new account in each one,
+    /// along with the account updates made by the blocks.
+    fn build_test_blocks(count: u32) -> Vec<(Block, AccountUpdates)> {
+        let (_, accounts) = MockDatabase::get_default_tree_and_accounts();
+        let fee_account_id = AccountId(0);
+        let fee_account_address = accounts[&fee_account_id].address;
+        let mut state = ZkSyncState::from_acc_map(accounts);
+        let mut previous_root_hash = Block::encode_fr_for_eth(state.root_hash());
+
+        let mut blocks = Vec::new();
+        for block_number in 1..=count {
+            let serial_id = u64::from(block_number - 1);
+            let deposit = ZkSyncPriorityOp::Deposit(Deposit {
+                from: fee_account_address,
+                token: TokenId(0),
+                amount: BigUint::from(block_number),
+                to: Address::from_low_u64_be(u64::from(block_number)),
+            });
+            let op_success = state.execute_priority_op(deposit.clone());
+            let op = ExecutedOperations::PriorityOp(Box::new(ExecutedPriorityOp {
+                op: op_success.executed_op,
+                priority_op: PriorityOp {
+                    serial_id,
+                    data: deposit,
+                    deadline_block: 0,
+                    eth_hash: H256::zero(),
+                    eth_block: 10,
+                    eth_block_index: Some(1),
+                },
+                block_index: 0,
+                created_at: chrono::Utc::now(),
+            }));
+
+            let block = Block::new_from_available_block_sizes(
+                BlockNumber(block_number),
+                state.root_hash(),
+                fee_account_id,
+                vec![op],
+                (serial_id, serial_id + 1),
+                &[10],
+                1_000_000.into(),
+                1_500_000.into(),
+                previous_root_hash,
+                0,
+            );
+            previous_root_hash = block.get_eth_encoded_root();
+            blocks.push((block, op_success.updates));
+        }
+        blocks
+    }
+
+    /// Builds the witness for the block using the committed state before the block.
+    async fn build_witness(database: &MockDatabase, block: &Block) -> ProverData {
+        let mut storage = database.acquire_connection().await.unwrap();
+        let (_, accounts) = database
+            .load_committed_state(&mut storage, Some(block.block_number - 1))
+            .await
+            .unwrap();
+        let mut circuit_account_tree = CircuitAccountTree::new(account_tree_depth());
+        for (id, account) in accounts {
+            circuit_account_tree.insert(*id, account.into());
+        }
+        build_block_witness(&mut circuit_account_tree, block)
+            .unwrap()
+            .into()
+    }
+
+    #[tokio::test]
+    async fn test_witness_pipeline() {
+        const BLOCKS: u32 = 25;
+        const WORKERS: usize = 3;
+
+        let database = MockDatabase::new();
+        let blocks = build_test_blocks(BLOCKS);
+        // Only a part of the blocks is available when the generator starts.
+        for (block, updates) in blocks.iter().take(15).cloned() {
+            database.add_block_with_updates(block, updates).await;
+        }
+        // The witness stored in advance must be skipped, so the following witnesses are stored
+        // before the preceding ones.
+        let stored_in_advance = build_witness(&database, &blocks[5].0).await;
+        let mut storage = database.acquire_connection().await.unwrap();
+        database
+            .store_witness(&mut storage, BlockNumber(6), &stored_in_advance)
+            .await
+            .unwrap();
+
+        let generator = WitnessGenerator::new(
+            database.clone(),
+            time::Duration::from_millis(100),
+            BlockNumber(1),
+            WORKERS,
         );
+        let (panic_sender, _panic_receiver) = mpsc::channel(1);
+        generator.start(panic_sender);
+
+        sleep(time::Duration::from_secs(1)).await;
+        for (block, updates) in blocks.iter().skip(15).cloned() {
+            database.add_block_with_updates(block, updates).await;
+        }
+
+        // Wait for all the witnesses to be stored and the account tree cache to be updated.
+        let deadline = Instant::now() + time::Duration::from_secs(120);
+        while database.stored_witnesses().await.len() < BLOCKS as usize
+            || *database.account_tree_cache_block().await < ACCOUNT_TREE_CACHE_INTERVAL
+        {
+            assert!(
+                Instant::now() < deadline,
+                "witness generator hasn't processed the blocks in time"
+            );
+            sleep(time::Duration::from_millis(100)).await;
+        }
+
         assert_eq!(
-            WitnessGenerator::<Database>::next_witness_block(
-                BlockNumber(3),
-                BlockNumber(4),
-                &BlockInfo::WithWitness
-            ),
-            BlockNumber(7)
+            database.stored_witnesses().await,
+            (1..=BLOCKS).map(BlockNumber).collect::<Vec<_>>()
         );
-        let empty_block = Block::new(
-            BlockNumber(0),
-            Fr::default(),
-            AccountId(0),
-            vec![],
-            (0, 0),
-            0,
-            U256::default(),
-            U256::default(),
-            H256::default(),
-            0,
+        // Every witness must be built from the snapshot of the tree before the block.
+        for (block, _) in &blocks {
+            let witness = database
+                .load_witness(&mut storage, block.block_number)
+                .await
+                .unwrap()
+                .unwrap();
+            let expected = build_witness(&database, block).await;
+            assert_eq!(witness.old_root, expected.old_root);
+            assert_eq!(witness.new_root, block.new_root_hash);
+            assert_eq!(
+                witness.public_data_commitment,
+                expected.public_data_commitment
+            );
+        }
+
+        // The stored cache must be consistent with the state after the cached block.
+        let cache_block = database.account_tree_cache_block().await;
+        assert!(cache_block <= BlockNumber(BLOCKS));
+        let generator = WitnessGenerator::new(
+            database.clone(),
+            time::Duration::from_millis(100),
+            BlockNumber(1),
+            WORKERS,
         );
+        generator.load_account_tree(cache_block).await.unwrap();
+    }
+
+    #[test]
+    fn test_witness_progress() {
+        let mut progress = WitnessProgress::new(BlockNumber(3));
+
+        // Witnesses stored out of order don't move the progress until the gap is filled.
+        progress.mark_stored(BlockNumber(5));
+        progress.mark_stored(BlockNumber(6));
+        assert_eq!(progress.last_stored_block, BlockNumber(3));
+
+        progress.mark_stored(BlockNumber(4));
+        assert_eq!(progress.last_stored_block, BlockNumber(6));
+        assert!(progress.stored_blocks.is_empty());
+
+        // Already accounted blocks are ignored.
+        progress.mark_stored(BlockNumber(2));
+        progress.mark_stored(BlockNumber(8));
+        assert_eq!(progress.last_stored_block, BlockNumber(6));
         assert_eq!(
-            WitnessGenerator::<Database>::next_witness_block(
-                BlockNumber(3),
-                BlockNumber(4),
-                &BlockInfo::NoWitness(empty_block)
-            ),
-            BlockNumber(7)
+            progress.stored_blocks.iter().copied().collect::<Vec<_>>(),
+            vec![BlockNumber(8)]
         );
     }
 }
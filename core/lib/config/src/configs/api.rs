@@ -7,7 +7,7 @@ use zksync_utils::scaled_u64_to_ratio;
 // Workspace uses
 use zksync_types::AccountId;
 // Local uses
-use crate::envy_load;
+use crate::{envy_load, ConfigErrors, ConfigVars};
 
 /// API configuration.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -43,6 +43,43 @@ impl ApiConfig {
             prometheus: envy_load!("prometheus", "API_PROMETHEUS_"),
         }
     }
+
+    pub fn from_vars(vars: &ConfigVars) -> Result<Self, ConfigErrors> {
+        let mut errors = ConfigErrors::default();
+        let common = errors.collect(vars.load("common", "API_COMMON_"));
+        let admin = errors.collect(vars.load("admin", "API_ADMIN_"));
+        let rest = errors.collect(vars.load("rest", "API_REST_"));
+        let json_rpc = errors.collect(vars.load("json_rpc", "API_JSON_RPC_"));
+        let web3 = errors.collect(vars.load("web3", "API_WEB3_"));
+        let private = errors.collect(vars.load("private", "API_PRIVATE_"));
+        let prover = errors.collect(vars.load("prover", "API_PROVER_"));
+        let prometheus = errors.collect(vars.load("prometheus", "API_PROMETHEUS_"));
+
+        match (
+            common, admin, rest, json_rpc, web3, private, prover, prometheus,
+        ) {
+            (
+                Some(common),
+                Some(admin),
+                Some(rest),
+                Some(json_rpc),
+                Some(web3),
+                Some(private),
+                Some(prover),
+                Some(prometheus),
+            ) => Ok(Self {
+                common,
+                admin,
+                rest,
+                json_rpc,
+                web3,
+                private,
+                prover,
+                prometheus,
+            }),
+            _ => Err(errors),
+        }
+    }
 }
 
 impl CommonApiConfig {
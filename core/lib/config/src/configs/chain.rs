@@ -6,7 +6,7 @@ use std::time::Duration;
 use zksync_types::network::Network;
 use zksync_types::Address;
 
-use crate::envy_load;
+use crate::{envy_load, ConfigErrors, ConfigVars};
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ChainConfig {
@@ -26,6 +26,23 @@ impl ChainConfig {
             state_keeper: envy_load!("state_keeper", "CHAIN_STATE_KEEPER_"),
         }
     }
+
+    pub fn from_vars(vars: &ConfigVars) -> Result<Self, ConfigErrors> {
+        let mut errors = ConfigErrors::default();
+        let circuit = errors.collect(vars.load("circuit", "CHAIN_CIRCUIT_"));
+        let eth = errors.collect(vars.load("eth", "CHAIN_ETH_"));
+        let state_keeper = errors.collect(vars.load("state_keeper", "CHAIN_STATE_KEEPER_"));
+
+        match (circuit, eth, state_keeper) {
+            (Some(circuit), Some(eth), Some(state_keeper)) => Ok(Self {
+                circuit,
+                eth,
+                state_keeper,
+            }),
+            _ => Err(errors),
+        }
+    }
+
     pub fn max_blocks_to_aggregate(&self) -> u32 {
         std::cmp::max(
             self.state_keeper.max_aggregated_blocks_to_commit,
@@ -182,6 +199,14 @@ CHAIN_STATE_KEEPER_MAX_AGGREGATED_TX_GAS="4000000"
         assert_eq!(actual, expected_config());
     }
 
+    #[test]
+    fn custom_network_from_env() {
+        let vars = vec![("CHAIN_ETH_NETWORK".to_string(), "custom-1337".to_string())];
+        let eth: Eth = envy::prefixed("CHAIN_ETH_").from_iter(vars).unwrap();
+        assert_eq!(eth.network, Network::Custom(1337));
+        assert_eq!(eth.network.to_string(), "custom-1337");
+    }
+
     /// Checks the correctness of the config helper methods.
     #[test]
     fn methods() {
@@ -3,7 +3,7 @@ use serde::Deserialize;
 // Workspace uses
 use zksync_types::{Address, H256};
 // Local uses
-use crate::envy_load;
+use crate::{envy_load, ConfigErrors, ConfigVars};
 
 /// Data about deployed contracts.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -27,6 +27,10 @@ impl ContractsConfig {
     pub fn from_env() -> Self {
         envy_load!("contracts", "CONTRACTS_")
     }
+
+    pub fn from_vars(vars: &ConfigVars) -> Result<Self, ConfigErrors> {
+        vars.load("contracts", "CONTRACTS_")
+    }
 }
 
 #[cfg(test)]
@@ -5,7 +5,7 @@ use std::time;
 use serde::Deserialize;
 
 // Local uses
-use crate::envy_load;
+use crate::{envy_load, ConfigErrors, ConfigVars};
 
 /// Used database configuration.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -27,6 +27,10 @@ impl DBConfig {
         envy_load!("database", "DATABASE_")
     }
 
+    pub fn from_vars(vars: &ConfigVars) -> Result<Self, ConfigErrors> {
+        vars.load("database", "DATABASE_")
+    }
+
     pub fn rejected_transactions_max_age(&self) -> time::Duration {
         time::Duration::from_secs(self.rejected_transactions_max_age * Self::SECS_PER_HOUR)
     }
@@ -1,19 +1,33 @@
+// Built-in uses
+use std::time::Duration;
 // External uses
 use serde::Deserialize;
 // Local uses
-use crate::envy_load;
+use crate::{envy_load, ConfigErrors, ConfigVars};
 
 /// Configuration for the Ethereum gateways.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ETHClientConfig {
     /// Numeric identifier of the L1 network (e.g. `9` for localhost).
-    pub chain_id: u8,
+    pub chain_id: u64,
     /// How much do we want to increase gas price provided by the network?
     /// Normally it's 1, we use the network-provided price (and limit it with the gas adjuster in eth sender).
     /// However, it can be increased to speed up the transaction mining time.
     pub gas_price_factor: f64,
     /// Address of the Ethereum node API.
+    /// HTTP, WebSocket and IPC (`ipc://` followed by the socket path) URLs are supported.
     pub web3_url: Vec<String>,
+    /// Timeout of a single request to the Ethereum node when several nodes are used.
+    /// Requests that take longer are considered failed and retried on the next node.
+    /// Value in milliseconds.
+    pub request_timeout: u64,
+    /// Number of consecutive failures after which a node is excluded from the requests.
+    pub circuit_breaker_threshold: u32,
+    /// How long an excluded node is not used before it is tried again.
+    /// Value in milliseconds.
+    pub circuit_breaker_cooldown: u64,
+    /// Whether read requests are sent to two nodes at once, using the first successful response.
+    pub hedged_reads: bool,
 }
 
 impl ETHClientConfig {
@@ -21,6 +35,10 @@ impl ETHClientConfig {
         envy_load!("eth_client", "ETH_CLIENT_")
     }
 
+    pub fn from_vars(vars: &ConfigVars) -> Result<Self, ConfigErrors> {
+        vars.load("eth_client", "ETH_CLIENT_")
+    }
+
     /// Get first web3 url, useful in direct web3 clients, which don't need any multiplexers
     pub fn web3_url(&self) -> String {
         self.web3_url
@@ -28,6 +46,16 @@ impl ETHClientConfig {
             .cloned()
             .expect("Should be at least one")
     }
+
+    /// Converts `self.request_timeout` into `Duration`
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout)
+    }
+
+    /// Converts `self.circuit_breaker_cooldown` into `Duration`
+    pub fn circuit_breaker_cooldown(&self) -> Duration {
+        Duration::from_millis(self.circuit_breaker_cooldown)
+    }
 }
 
 #[cfg(test)]
@@ -43,6 +71,10 @@ mod tests {
                 "http://127.0.0.1:8545".into(),
                 "http://127.0.0.1:8546".into(),
             ],
+            request_timeout: 5000,
+            circuit_breaker_threshold: 3,
+            circuit_breaker_cooldown: 30000,
+            hedged_reads: true,
         }
     }
 
@@ -52,11 +84,17 @@ mod tests {
 ETH_CLIENT_CHAIN_ID="9"
 ETH_CLIENT_GAS_PRICE_FACTOR="1"
 ETH_CLIENT_WEB3_URL="http://127.0.0.1:8545,http://127.0.0.1:8546"
+ETH_CLIENT_REQUEST_TIMEOUT="5000"
+ETH_CLIENT_CIRCUIT_BREAKER_THRESHOLD="3"
+ETH_CLIENT_CIRCUIT_BREAKER_COOLDOWN="30000"
+ETH_CLIENT_HEDGED_READS="true"
         "#;
         set_env(config);
 
         let actual = ETHClientConfig::from_env();
         assert_eq!(actual, expected_config());
         assert_eq!(actual.web3_url(), "http://127.0.0.1:8545");
+        assert_eq!(actual.request_timeout(), Duration::from_secs(5));
+        assert_eq!(actual.circuit_breaker_cooldown(), Duration::from_secs(30));
     }
 }
@@ -1,11 +1,11 @@
 // Built-in uses
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 // External uses
 use serde::Deserialize;
 // Workspace uses
 use zksync_types::{Address, H256};
 // Local uses
-use crate::envy_load;
+use crate::{envy_load, ConfigErrors, ConfigVars};
 
 /// Configuration for the Ethereum sender crate.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -26,12 +26,42 @@ impl ETHSenderConfig {
             ),
         }
     }
+
+    pub fn from_vars(vars: &ConfigVars) -> Result<Self, ConfigErrors> {
+        let mut errors = ConfigErrors::default();
+        let sender = errors.collect(vars.load("eth_sender", "ETH_SENDER_SENDER_"));
+        let gas_price_limit =
+            errors.collect(vars.load("eth_sender.gas_price_limit", "ETH_SENDER_GAS_PRICE_LIMIT_"));
+
+        match (sender, gas_price_limit) {
+            (Some(sender), Some(gas_price_limit)) => Ok(Self {
+                sender,
+                gas_price_limit,
+            }),
+            _ => Err(errors),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Sender {
     /// Private key of the operator account.
-    pub operator_private_key: H256,
+    /// Must not be set if the key is loaded from the keystore or derived from the mnemonic.
+    pub operator_private_key: Option<H256>,
+    /// Path to the Web3 Secret Storage keystore with the operator private key.
+    pub operator_keystore_path: Option<PathBuf>,
+    /// Path to the BIP-39 mnemonic phrase file to derive the operator private key from.
+    pub operator_mnemonic_path: Option<PathBuf>,
+    /// BIP-32 derivation path used with the mnemonic. `m/44'/60'/0'/0/0` is used if not set.
+    pub operator_derivation_path: Option<String>,
+    /// Path to the file with the keystore password or the mnemonic passphrase.
+    pub operator_password_path: Option<PathBuf>,
+    /// URL of the remote signing daemon holding the operator key.
+    /// If set, the operator key must not be available to the server in any other way.
+    pub operator_remote_signer_url: Option<String>,
+    /// Path to the file with the token the server authenticates with at the remote signing daemon.
+    /// Must be set if the remote signer is used.
+    pub operator_remote_signer_token_path: Option<PathBuf>,
     /// Address of the operator account.
     pub operator_commit_eth_addr: Address,
     /// mount of confirmations required to consider L1 transaction committed.
@@ -90,9 +120,15 @@ mod tests {
                 tx_poll_period: 3,
                 max_txs_in_flight: 3,
                 is_enabled: true,
-                operator_private_key: hash(
+                operator_private_key: Some(hash(
                     "27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be",
-                ),
+                )),
+                operator_keystore_path: None,
+                operator_mnemonic_path: None,
+                operator_derivation_path: None,
+                operator_password_path: None,
+                operator_remote_signer_url: None,
+                operator_remote_signer_token_path: None,
                 operator_commit_eth_addr: addr("de03a0B5963f75f1C8485B355fF6D30f3093BDE7"),
             },
             gas_price_limit: GasLimit {
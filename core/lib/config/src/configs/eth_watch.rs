@@ -3,7 +3,7 @@ use std::time::Duration;
 // External uses
 use serde::Deserialize;
 // Local uses
-use crate::envy_load;
+use crate::{envy_load, ConfigErrors, ConfigVars};
 
 /// Configuration for the Ethereum sender crate.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -21,6 +21,10 @@ impl ETHWatchConfig {
         envy_load!("eth_watch", "ETH_WATCH_")
     }
 
+    pub fn from_vars(vars: &ConfigVars) -> Result<Self, ConfigErrors> {
+        vars.load("eth_watch", "ETH_WATCH_")
+    }
+
     /// Converts `self.eth_node_poll_interval` into `Duration`.
     pub fn poll_interval(&self) -> Duration {
         Duration::from_millis(self.eth_node_poll_interval)
@@ -5,7 +5,7 @@ use std::net::SocketAddr;
 use serde::Deserialize;
 
 // Local uses
-use crate::envy_load;
+use crate::{envy_load, ConfigErrors, ConfigVars};
 
 /// Configuration for the Event listener crate.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -24,6 +24,10 @@ impl EventListenerConfig {
         envy_load!("event_listener", "EVENT_LISTENER_")
     }
 
+    pub fn from_vars(vars: &ConfigVars) -> Result<Self, ConfigErrors> {
+        vars.load("event_listener", "EVENT_LISTENER_")
+    }
+
     pub fn ws_bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.ws_port)
     }
@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use crate::envy_load;
+use crate::{envy_load, ConfigError, ConfigErrors, ConfigVars};
 /// External uses
 use serde::Deserialize;
 use zksync_types::{Address, H256};
@@ -54,13 +54,18 @@ pub struct ForcedExitRequestsConfig {
 //
 // Thus we need to check that at least digits_in_id first digits
 // are equal to zeroes in price_per_token
-fn validate_price_with_id_space(price: i64, digits_in_id: u8) {
+fn validate_price_with_id_space(price: i64, digits_in_id: u8) -> Result<(), ConfigErrors> {
     let id_space = (10_i64).saturating_pow(digits_in_id.into());
 
-    assert!(
-        price % id_space == 0,
-        "The price per token may overlap with request id"
-    )
+    if price % id_space == 0 {
+        Ok(())
+    } else {
+        Err(ConfigError::new(
+            "FORCED_EXIT_REQUESTS_PRICE_PER_TOKEN",
+            "The price per token may overlap with request id",
+        )
+        .into())
+    }
 }
 
 impl ForcedExitRequestsConfig {
@@ -68,12 +73,23 @@ impl ForcedExitRequestsConfig {
         let config: ForcedExitRequestsInternalConfig =
             envy_load!("forced_exit_requests", "FORCED_EXIT_REQUESTS_");
 
+        Self::from_internal(config).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn from_vars(vars: &ConfigVars) -> Result<Self, ConfigErrors> {
+        let config: ForcedExitRequestsInternalConfig =
+            vars.load("forced_exit_requests", "FORCED_EXIT_REQUESTS_")?;
+
+        Self::from_internal(config)
+    }
+
+    fn from_internal(config: ForcedExitRequestsInternalConfig) -> Result<Self, ConfigErrors> {
         let max_tx_interval: f64 =
             (config.recomended_tx_interval as f64) * config.tx_interval_scaling_factor;
 
-        validate_price_with_id_space(config.price_per_token, config.digits_in_id);
+        validate_price_with_id_space(config.price_per_token, config.digits_in_id)?;
 
-        ForcedExitRequestsConfig {
+        Ok(ForcedExitRequestsConfig {
             enabled: config.enabled,
             max_tokens_per_request: config.max_tokens_per_request,
             recomended_tx_interval: config.recomended_tx_interval,
@@ -87,7 +103,7 @@ impl ForcedExitRequestsConfig {
             expiration_period: config.expiration_period,
             blocks_check_amount: config.blocks_check_amount,
             eth_node_poll_interval: config.eth_node_poll_interval,
-        }
+        })
     }
 
     pub fn poll_interval(&self) -> Duration {
@@ -3,7 +3,7 @@ use std::time::Duration;
 // External uses
 use serde::Deserialize;
 // Local uses
-use crate::envy_load;
+use crate::{envy_load, ConfigErrors, ConfigVars};
 
 /// Configuration of the Gateway Watch.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -27,6 +27,10 @@ impl GatewayWatcherConfig {
         envy_load!("gateway_watcher", "GATEWAY_WATCHER_")
     }
 
+    pub fn from_vars(vars: &ConfigVars) -> Result<Self, ConfigErrors> {
+        vars.load("gateway_watcher", "GATEWAY_WATCHER_")
+    }
+
     /// Converts `self.gateway_check_interval` into `Duration`
     pub fn check_interval(&self) -> Duration {
         Duration::from_millis(self.gateway_check_interval)
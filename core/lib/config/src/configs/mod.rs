@@ -4,8 +4,8 @@ pub use self::{
     dev_liquidity_token_watcher::DevLiquidityTokenWatcherConfig, eth_client::ETHClientConfig,
     eth_sender::ETHSenderConfig, eth_watch::ETHWatchConfig, event_listener::EventListenerConfig,
     forced_exit_requests::ForcedExitRequestsConfig, gateway_watcher::GatewayWatcherConfig,
-    misc::MiscConfig, prover::ProverConfig, ticker::TickerConfig,
-    token_handler::TokenHandlerConfig,
+    misc::MiscConfig, prover::ProverConfig, remote_signer::RemoteSignerConfig,
+    ticker::TickerConfig, token_handler::TokenHandlerConfig,
 };
 
 pub mod api;
@@ -21,6 +21,7 @@ pub mod forced_exit_requests;
 pub mod gateway_watcher;
 pub mod misc;
 pub mod prover;
+pub mod remote_signer;
 pub mod ticker;
 pub mod token_handler;
 
@@ -32,11 +33,12 @@ pub(crate) mod test_utils;
 /// # Panics
 ///
 /// Panics if the config cannot be loaded from the environment variables.
+/// All the problems found in the section are reported at once.
 #[macro_export]
 macro_rules! envy_load {
     ($name:expr, $prefix:expr) => {
-        envy::prefixed($prefix)
-            .from_env()
-            .unwrap_or_else(|err| panic!("Cannot load config <{}>: {}", $name, err))
+        $crate::ConfigVars::from_env()
+            .load($name, $prefix)
+            .unwrap_or_else(|errors| panic!("Cannot load config <{}>: {}", $name, errors))
     };
 }
@@ -3,7 +3,7 @@ use std::time::Duration;
 // External uses
 use serde::Deserialize;
 // Local uses
-use crate::envy_load;
+use crate::{envy_load, ConfigErrors, ConfigVars};
 
 /// Configuration for the prover application and part of the server that interact with it.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -21,6 +21,23 @@ impl ProverConfig {
             witness_generator: envy_load!("prover.witness_generator", "PROVER_WITNESS_GENERATOR_"),
         }
     }
+
+    pub fn from_vars(vars: &ConfigVars) -> Result<Self, ConfigErrors> {
+        let mut errors = ConfigErrors::default();
+        let prover = errors.collect(vars.load("prover.prover", "PROVER_PROVER_"));
+        let core = errors.collect(vars.load("prover.core", "PROVER_CORE_"));
+        let witness_generator =
+            errors.collect(vars.load("prover.witness_generator", "PROVER_WITNESS_GENERATOR_"));
+
+        match (prover, core, witness_generator) {
+            (Some(prover), Some(core), Some(witness_generator)) => Ok(Self {
+                prover,
+                core,
+                witness_generator,
+            }),
+            _ => Err(errors),
+        }
+    }
 }
 
 /// Actual prover application settings.
@@ -56,10 +73,13 @@ impl Prover {
 /// Core settings related to the prover applications interacting with it.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Core {
-    /// Timeout to consider prover gone in ms.
+    /// Timeout to consider prover gone in ms. Prover job leases are renewed by heartbeats
+    /// and expire after this timeout.
     pub gone_timeout: u64,
     /// Amount of provers in the cluser if there is no pending jobs.
     pub idle_provers: u32,
+    /// Amount of attempts to prove a job before it's considered failed.
+    pub max_job_retries: u32,
 }
 
 impl Core {
@@ -73,7 +93,7 @@ impl Core {
 pub struct WitnessGenerator {
     /// Interval to check whether a new witness generation job should be started in ms.
     pub prepare_data_interval: u64,
-    /// Amount of witness generator threads.
+    /// Amount of witnesses built in parallel by the witness generator.
     pub witness_generators: usize,
 }
 
@@ -100,6 +120,7 @@ mod tests {
             core: Core {
                 gone_timeout: 60000,
                 idle_provers: 1,
+                max_job_retries: 3,
             },
             witness_generator: WitnessGenerator {
                 prepare_data_interval: 500,
@@ -117,6 +138,7 @@ PROVER_PROVER_REQUEST_TIMEOUT="10"
 PROVER_PROVER_DIE_AFTER_PROOF=false
 PROVER_CORE_GONE_TIMEOUT="60000"
 PROVER_CORE_IDLE_PROVERS="1"
+PROVER_CORE_MAX_JOB_RETRIES="3"
 PROVER_WITNESS_GENERATOR_PREPARE_DATA_INTERVAL="500"
 PROVER_WITNESS_GENERATOR_WITNESS_GENERATORS="2"
         "#;
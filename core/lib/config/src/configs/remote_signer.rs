@@ -0,0 +1,77 @@
+// Built-in uses
+use std::{
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+};
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Configuration for the remote signing daemon holding the operator key.
+/// The key itself is loaded the same way as by the server, see `ETHSenderConfig`.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct RemoteSignerConfig {
+    /// Address of the interface the signing API server is listening on.
+    pub host: IpAddr,
+    /// Port to which the signing API server is listening.
+    pub port: u16,
+    /// Path to the file with the token every request must carry in the `Authorization: Bearer` header.
+    pub auth_token_path: PathBuf,
+    /// Maximum gas price (in wei) of the signed transaction.
+    pub max_gas_price: u64,
+    /// Maximum gas limit of the signed transaction.
+    pub max_gas_limit: u64,
+    /// If set, transactions with greater nonces are never signed.
+    pub max_nonce: Option<u64>,
+    /// Maximum distance between the nonce of the signed transaction and the greatest nonce signed so far.
+    pub max_nonce_gap: u64,
+    /// File to which all the signing requests and decisions are appended.
+    pub audit_log_path: PathBuf,
+}
+
+impl RemoteSignerConfig {
+    pub fn from_env() -> Self {
+        envy_load!("remote_signer", "REMOTE_SIGNER_")
+    }
+
+    pub fn bind_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.host, self.port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> RemoteSignerConfig {
+        RemoteSignerConfig {
+            host: "127.0.0.1".parse().unwrap(),
+            port: 3031,
+            auth_token_path: "/etc/zksync/remote_signer.token".into(),
+            max_gas_price: 500_000_000_000,
+            max_gas_limit: 10_000_000,
+            max_nonce: None,
+            max_nonce_gap: 30,
+            audit_log_path: "/var/log/zksync/remote_signer_audit.log".into(),
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+REMOTE_SIGNER_HOST="127.0.0.1"
+REMOTE_SIGNER_PORT="3031"
+REMOTE_SIGNER_AUTH_TOKEN_PATH="/etc/zksync/remote_signer.token"
+REMOTE_SIGNER_MAX_GAS_PRICE="500000000000"
+REMOTE_SIGNER_MAX_GAS_LIMIT="10000000"
+REMOTE_SIGNER_MAX_NONCE_GAP="30"
+REMOTE_SIGNER_AUDIT_LOG_PATH="/var/log/zksync/remote_signer_audit.log"
+        "#;
+        set_env(config);
+
+        let actual = RemoteSignerConfig::from_env();
+        assert_eq!(actual, expected_config());
+    }
+}
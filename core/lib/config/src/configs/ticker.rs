@@ -5,7 +5,7 @@ use serde::Deserialize;
 use zksync_types::Address;
 use zksync_utils::scaled_u64_to_ratio;
 // Local uses
-use crate::envy_load;
+use crate::{envy_load, ConfigErrors, ConfigVars};
 
 #[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
 pub enum TokenPriceSource {
@@ -51,6 +51,10 @@ impl TickerConfig {
         envy_load!("fee_ticker", "FEE_TICKER_")
     }
 
+    pub fn from_vars(vars: &ConfigVars) -> Result<Self, ConfigErrors> {
+        vars.load("fee_ticker", "FEE_TICKER_")
+    }
+
     /// Returns the token price source type and the corresponding API URL.
     pub fn price_source(&self) -> (TokenPriceSource, &str) {
         let url = match self.token_price_source {
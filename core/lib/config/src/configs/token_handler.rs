@@ -6,7 +6,7 @@ use serde::Deserialize;
 // Workspace uses
 use zksync_types::TokenInfo;
 // Local uses
-use crate::envy_load;
+use crate::{envy_load, ConfigErrors, ConfigVars};
 
 /// Configuration for the Ethereum sender crate.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -24,6 +24,10 @@ impl TokenHandlerConfig {
         envy_load!("token_handler", "TOKEN_HANDLER_")
     }
 
+    pub fn from_vars(vars: &ConfigVars) -> Result<Self, ConfigErrors> {
+        vars.load("token_handler", "TOKEN_HANDLER_")
+    }
+
     pub fn token_list_file(&self) -> String {
         self.token_list_name.clone()
     }
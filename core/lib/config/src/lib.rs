@@ -1,11 +1,13 @@
 pub use crate::configs::{
     ApiConfig, ChainConfig, ContractsConfig, DBConfig, DevLiquidityTokenWatcherConfig,
     ETHClientConfig, ETHSenderConfig, ETHWatchConfig, EventListenerConfig,
-    ForcedExitRequestsConfig, GatewayWatcherConfig, MiscConfig, ProverConfig, TickerConfig,
-    TokenHandlerConfig,
+    ForcedExitRequestsConfig, GatewayWatcherConfig, MiscConfig, ProverConfig, RemoteSignerConfig,
+    TickerConfig, TokenHandlerConfig,
 };
+pub use crate::loader::{ConfigError, ConfigErrors, ConfigLoader, ConfigVars};
 
 pub mod configs;
+pub mod loader;
 pub mod test_config;
 
 #[derive(Debug, Clone)]
@@ -26,21 +28,180 @@ pub struct ZkSyncConfig {
 }
 
 impl ZkSyncConfig {
+    /// Prefixes of the variables used by the zkSync configuration.
+    pub const PREFIXES: &'static [&'static str] = &[
+        "API_",
+        "CHAIN_",
+        "CONTRACTS_",
+        "DATABASE_",
+        "ETH_CLIENT_",
+        "ETH_SENDER_",
+        "ETH_WATCH_",
+        "TOKEN_HANDLER_",
+        "EVENT_LISTENER_",
+        "GATEWAY_WATCHER_",
+        "PROVER_",
+        "FEE_TICKER_",
+        "FORCED_EXIT_REQUESTS_",
+    ];
+
+    /// Loads the config from the environment variables.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the config is not valid. All the problems found are reported at once.
     pub fn from_env() -> Self {
-        Self {
-            api: ApiConfig::from_env(),
-            chain: ChainConfig::from_env(),
-            contracts: ContractsConfig::from_env(),
-            db: DBConfig::from_env(),
-            eth_client: ETHClientConfig::from_env(),
-            eth_sender: ETHSenderConfig::from_env(),
-            eth_watch: ETHWatchConfig::from_env(),
-            token_handler: TokenHandlerConfig::from_env(),
-            event_listener: EventListenerConfig::from_env(),
-            gateway_watcher: GatewayWatcherConfig::from_env(),
-            prover: ProverConfig::from_env(),
-            ticker: TickerConfig::from_env(),
-            forced_exit_requests: ForcedExitRequestsConfig::from_env(),
+        Self::from_vars(&ConfigVars::from_env())
+            .unwrap_or_else(|errors| panic!("Cannot load config: {}", errors))
+    }
+
+    /// Loads the config from the provided set of variables and validates it.
+    /// Unlike `from_env`, doesn't panic and reports all the problems found at once.
+    pub fn from_vars(vars: &ConfigVars) -> Result<Self, ConfigErrors> {
+        let mut errors = ConfigErrors::default();
+        let api = errors.collect(ApiConfig::from_vars(vars));
+        let chain = errors.collect(ChainConfig::from_vars(vars));
+        let contracts = errors.collect(ContractsConfig::from_vars(vars));
+        let db = errors.collect(DBConfig::from_vars(vars));
+        let eth_client = errors.collect(ETHClientConfig::from_vars(vars));
+        let eth_sender = errors.collect(ETHSenderConfig::from_vars(vars));
+        let eth_watch = errors.collect(ETHWatchConfig::from_vars(vars));
+        let token_handler = errors.collect(TokenHandlerConfig::from_vars(vars));
+        let event_listener = errors.collect(EventListenerConfig::from_vars(vars));
+        let gateway_watcher = errors.collect(GatewayWatcherConfig::from_vars(vars));
+        let prover = errors.collect(ProverConfig::from_vars(vars));
+        let ticker = errors.collect(TickerConfig::from_vars(vars));
+        let forced_exit_requests = errors.collect(ForcedExitRequestsConfig::from_vars(vars));
+
+        let config = match (
+            api,
+            chain,
+            contracts,
+            db,
+            eth_client,
+            eth_sender,
+            eth_watch,
+            token_handler,
+            event_listener,
+            gateway_watcher,
+            prover,
+            ticker,
+            forced_exit_requests,
+        ) {
+            (
+                Some(api),
+                Some(chain),
+                Some(contracts),
+                Some(db),
+                Some(eth_client),
+                Some(eth_sender),
+                Some(eth_watch),
+                Some(token_handler),
+                Some(event_listener),
+                Some(gateway_watcher),
+                Some(prover),
+                Some(ticker),
+                Some(forced_exit_requests),
+            ) => Self {
+                api,
+                chain,
+                contracts,
+                db,
+                eth_client,
+                eth_sender,
+                eth_watch,
+                token_handler,
+                event_listener,
+                gateway_watcher,
+                prover,
+                ticker,
+                forced_exit_requests,
+            },
+            _ => return Err(errors),
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks the consistency of the loaded values.
+    pub fn validate(&self) -> Result<(), ConfigErrors> {
+        let mut errors = ConfigErrors::default();
+
+        let circuit = &self.chain.circuit;
+        let state_keeper = &self.chain.state_keeper;
+        if circuit.supported_block_chunks_sizes.len()
+            != circuit.supported_block_chunks_sizes_setup_powers.len()
+        {
+            errors.push(ConfigError::new(
+                "CHAIN_CIRCUIT_SUPPORTED_BLOCK_CHUNKS_SIZES_SETUP_POWERS",
+                "must have the same length as CHAIN_CIRCUIT_SUPPORTED_BLOCK_CHUNKS_SIZES",
+            ));
         }
+        if circuit.supported_aggregated_proof_sizes.len()
+            != circuit.supported_aggregated_proof_sizes_setup_power2.len()
+        {
+            errors.push(ConfigError::new(
+                "CHAIN_CIRCUIT_SUPPORTED_AGGREGATED_PROOF_SIZES_SETUP_POWER2",
+                "must have the same length as CHAIN_CIRCUIT_SUPPORTED_AGGREGATED_PROOF_SIZES",
+            ));
+        }
+        for size in &state_keeper.block_chunk_sizes {
+            if !circuit.supported_block_chunks_sizes.contains(size) {
+                errors.push(ConfigError::new(
+                    "CHAIN_STATE_KEEPER_BLOCK_CHUNK_SIZES",
+                    format!(
+                        "block size {} is not in CHAIN_CIRCUIT_SUPPORTED_BLOCK_CHUNKS_SIZES",
+                        size
+                    ),
+                ));
+            }
+        }
+        for size in &state_keeper.aggregated_proof_sizes {
+            if !circuit.supported_aggregated_proof_sizes.contains(size) {
+                errors.push(ConfigError::new(
+                    "CHAIN_STATE_KEEPER_AGGREGATED_PROOF_SIZES",
+                    format!(
+                        "aggregated proof size {} is not in CHAIN_CIRCUIT_SUPPORTED_AGGREGATED_PROOF_SIZES",
+                        size
+                    ),
+                ));
+            }
+        }
+
+        if self.eth_client.web3_url.is_empty() {
+            errors.push(ConfigError::new(
+                "ETH_CLIENT_WEB3_URL",
+                "at least one URL is required",
+            ));
+        }
+        let urls = self
+            .eth_client
+            .web3_url
+            .iter()
+            .map(|url| ("ETH_CLIENT_WEB3_URL", url))
+            .chain(vec![
+                (
+                    "FEE_TICKER_COINMARKETCAP_BASE_URL",
+                    &self.ticker.coinmarketcap_base_url,
+                ),
+                (
+                    "FEE_TICKER_COINGECKO_BASE_URL",
+                    &self.ticker.coingecko_base_url,
+                ),
+                ("FEE_TICKER_UNISWAP_URL", &self.ticker.uniswap_url),
+                ("API_PRIVATE_URL", &self.api.private.url),
+                ("API_PROVER_URL", &self.api.prover.url),
+            ]);
+        for (field, url) in urls {
+            if let Err(err) = url::Url::parse(url) {
+                errors.push(ConfigError::new(
+                    field,
+                    format!("invalid URL '{}': {}", url, err),
+                ));
+            }
+        }
+
+        errors.into_result()
     }
 }
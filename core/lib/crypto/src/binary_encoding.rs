@@ -0,0 +1,119 @@
+//! Compact binary encoding for the large structures stored in the database,
+//! such as block witnesses and account tree caches.
+//!
+//! Encoded value consists of a header (magic bytes followed by the format version)
+//! and the zlib-compressed CBOR representation of the value. Since CBOR is not a human-readable
+//! format, field elements serialized via [`crate::serialization`] helpers are stored as raw bytes.
+//!
+//! The header allows to distinguish the encoded values from the legacy JSON ones
+//! and to change the encoding later without breaking the already stored values.
+
+// External deps
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_cbor::ser::IoWrite;
+// Local deps
+use crate::error::BinaryEncodingError;
+
+/// Prefix of every encoded value.
+const MAGIC: [u8; 4] = *b"ZKBE";
+
+/// Version of the encoding used for the new values.
+pub const BINARY_ENCODING_VERSION: u8 = 1;
+
+/// Returns `true` if the bytes look like a value produced by [`encode`].
+pub fn is_binary_encoded(bytes: &[u8]) -> bool {
+    bytes.starts_with(&MAGIC)
+}
+
+/// Encodes the value using the latest version of the encoding.
+pub fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, BinaryEncodingError> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(BINARY_ENCODING_VERSION);
+
+    // The values are encoded on the hot path of the witness generation,
+    // so the speed is preferred over the compression ratio.
+    let mut encoder = ZlibEncoder::new(bytes, Compression::fast());
+    value
+        .serialize(&mut serde_cbor::Serializer::new(IoWrite::new(&mut encoder)).packed_format())?;
+
+    Ok(encoder.finish()?)
+}
+
+/// Decodes the value encoded by any of the supported versions of the encoding.
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, BinaryEncodingError> {
+    if !is_binary_encoded(bytes) {
+        return Err(BinaryEncodingError::UnknownHeader);
+    }
+    let (version, payload) = bytes[MAGIC.len()..]
+        .split_first()
+        .ok_or(BinaryEncodingError::UnknownHeader)?;
+
+    match *version {
+        1 => Ok(serde_cbor::from_reader(ZlibDecoder::new(payload))?),
+        version => Err(BinaryEncodingError::UnsupportedVersion(version)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ff::PrimeField,
+        serialization::{FrSerde, VecOptionalFrSerde},
+        Fr,
+    };
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Reference {
+        #[serde(with = "FrSerde")]
+        value: Fr,
+        #[serde(with = "VecOptionalFrSerde")]
+        path: Vec<Option<Fr>>,
+        flags: Vec<Option<bool>>,
+    }
+
+    fn reference() -> Reference {
+        Reference {
+            value: Fr::from_str("42").unwrap(),
+            path: (0..32u64)
+                .map(|index| Fr::from_str(&index.to_string()).filter(|_| index % 2 == 0))
+                .collect(),
+            flags: vec![Some(true), None, Some(false)],
+        }
+    }
+
+    #[test]
+    fn encoding_roundtrip() {
+        let value = reference();
+        let encoded = encode(&value).unwrap();
+        assert!(is_binary_encoded(&encoded));
+        assert_eq!(decode::<Reference>(&encoded).unwrap(), value);
+
+        // Field elements are stored as bytes, so the encoded value is much smaller than JSON.
+        let json = serde_json::to_vec(&value).unwrap();
+        assert!(encoded.len() * 2 < json.len());
+    }
+
+    #[test]
+    fn invalid_header() {
+        let json = serde_json::to_vec(&reference()).unwrap();
+        assert!(!is_binary_encoded(&json));
+        assert!(matches!(
+            decode::<Reference>(&json),
+            Err(BinaryEncodingError::UnknownHeader)
+        ));
+
+        let mut encoded = encode(&reference()).unwrap();
+        encoded[MAGIC.len()] = BINARY_ENCODING_VERSION + 1;
+        assert!(matches!(
+            decode::<Reference>(&encoded),
+            Err(BinaryEncodingError::UnsupportedVersion(_))
+        ));
+        assert!(matches!(
+            decode::<Reference>(&MAGIC),
+            Err(BinaryEncodingError::UnknownHeader)
+        ));
+    }
+}
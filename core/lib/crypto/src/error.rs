@@ -19,3 +19,15 @@ pub enum ConversionError {
     #[error("Cannot convert into prime field value: {0}")]
     PrimeFieldDecodingError(#[from] ff::PrimeFieldDecodingError),
 }
+
+#[derive(Debug, Error)]
+pub enum BinaryEncodingError {
+    #[error("Value is not binary encoded")]
+    UnknownHeader,
+    #[error("Unsupported binary encoding version {0}")]
+    UnsupportedVersion(u8),
+    #[error("Cannot compress or decompress value: {0}")]
+    CompressionError(#[from] std::io::Error),
+    #[error("Cannot serialize or deserialize value: {0}")]
+    SerializationError(#[from] serde_cbor::Error),
+}
@@ -6,9 +6,11 @@ use crate::{
     Fr,
 };
 
+use crate::serialization::BytesVisitor;
 use fnv::FnvHashMap;
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::{
+    convert::TryFrom,
     fmt::Debug,
     sync::{RwLock, RwLockReadGuard},
 };
@@ -705,7 +707,42 @@ where
 pub struct SparseMerkleTreeSerializableCacheBN256 {
     root: NodeRef,
     nodes: Vec<Node>,
-    cache: Vec<(NodeIndex, [u8; 32])>,
+    cache: Vec<(NodeIndex, NodeHashBytes)>,
+}
+
+/// Serialized hash of the tree node.
+///
+/// Hashes are encoded as byte strings in the binary formats and as arrays of numbers
+/// in the human-readable ones, which is how the caches were historically stored in JSON.
+#[derive(Debug, Clone, Copy)]
+struct NodeHashBytes([u8; 32]);
+
+impl Serialize for NodeHashBytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            self.0.serialize(serializer)
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for NodeHashBytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            return <[u8; 32]>::deserialize(deserializer).map(Self);
+        }
+        let bytes = deserializer.deserialize_bytes(BytesVisitor)?;
+        <[u8; 32]>::try_from(bytes.as_slice())
+            .map(Self)
+            .map_err(|_| de::Error::invalid_length(bytes.len(), &"32 bytes"))
+    }
 }
 
 impl<T, H> SparseMerkleTree<T, Fr, H>
@@ -727,7 +764,7 @@ where
                     fr.into_repr()
                         .write_be(&mut fr_bytes[..])
                         .expect("Fr write error");
-                    (*idx, fr_bytes)
+                    (*idx, NodeHashBytes(fr_bytes))
                 })
                 .collect(),
         }
@@ -740,7 +777,7 @@ where
             internals
                 .cache
                 .into_iter()
-                .map(|(idx, fr_bytes)| {
+                .map(|(idx, NodeHashBytes(fr_bytes))| {
                     let mut fr_repr = <Fr as PrimeField>::Repr::default();
                     fr_repr.read_be(&fr_bytes[..]).expect("Fr read error");
                     (idx, Fr::from_repr(fr_repr).expect("Fr decode error"))
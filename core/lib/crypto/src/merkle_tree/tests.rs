@@ -1,4 +1,5 @@
 use crate::{
+    binary_encoding,
     merkle_tree::{parallel_smt, RescueHasher},
     rand::{Rng, SeedableRng, XorShiftRng},
     Engine, Fr,
@@ -169,3 +170,31 @@ fn big_test() {
     let root_hash: Fr = crate::ff::from_hex(&input.root_hash).unwrap();
     assert_eq!(root_hash, tree.root_hash());
 }
+
+/// Checks that the tree cache can be restored from both the binary and the legacy JSON encodings.
+#[test]
+fn tree_cache_encoding() {
+    let depth = 4;
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut tree = parallel_smt::SparseMerkleTree::<u64, Fr, RescueHasher<Engine>>::new(depth);
+    for (idx, item) in rng.gen_iter::<u64>().take(1 << depth).enumerate() {
+        tree.insert(idx as u32, item);
+    }
+    let root_hash = tree.root_hash();
+    let cache = tree.get_internals();
+
+    let encoded = binary_encoding::encode(&cache).unwrap();
+    let json = serde_json::to_vec(&cache).unwrap();
+    assert!(encoded.len() < json.len());
+
+    for cache in vec![
+        binary_encoding::decode(&encoded).unwrap(),
+        serde_json::from_slice(&json).unwrap(),
+    ] {
+        let mut restored_tree =
+            parallel_smt::SparseMerkleTree::<u64, Fr, RescueHasher<Engine>>::new(depth);
+        restored_tree.set_internals(cache);
+        assert_eq!(restored_tree.root_hash(), root_hash);
+    }
+}
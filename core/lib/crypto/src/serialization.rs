@@ -15,6 +15,7 @@ use crate::{
     Engine, Fr,
 };
 use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
 use zksync_basic_types::U256;
 
 /// Blanket structure implementing serializing/deserializing methods for `Fr`.
@@ -42,23 +43,14 @@ impl FrSerde {
     where
         S: Serializer,
     {
-        // First, serialize `Fr` to hexadecimal string.
-        let hex_value = value.to_hex();
-
-        // Then, serialize it using `Serialize` trait implementation for `String`.
-        String::serialize(&hex_value, serializer)
+        FrWrapper(*value).serialize(serializer)
     }
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Fr, D::Error>
     where
         D: Deserializer<'de>,
     {
-        // First, deserialize a string value. It is expected to be a
-        // hexadecimal representation of `Fr`.
-        let deserialized_string = String::deserialize(deserializer)?;
-
-        // Then, parse hexadecimal string to obtain `Fr`.
-        Fr::from_hex(&deserialized_string).map_err(de::Error::custom)
+        FrWrapper::deserialize(deserializer).map(|FrWrapper(fr)| fr)
     }
 }
 
@@ -84,23 +76,15 @@ impl OptionalFrSerde {
     where
         S: Serializer,
     {
-        let optional_hex_value = value.map(|fr| fr.to_hex());
-
-        Option::serialize(&optional_hex_value, serializer)
+        Option::serialize(&value.map(FrWrapper), serializer)
     }
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Fr>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let optional_deserialized_string: Option<String> = Option::deserialize(deserializer)?;
-
-        // Apply `fe_from_hex` to the contents of `Option`, then transpose result to have
-        // `Result<Option<..>, ..>` and adapt error to the expected format.
-        optional_deserialized_string
-            .map(|v| Fr::from_hex(&v))
-            .transpose()
-            .map_err(de::Error::custom)
+        let value: Option<FrWrapper> = Option::deserialize(deserializer)?;
+        Ok(value.map(|FrWrapper(fr)| fr))
     }
 }
 
@@ -126,11 +110,10 @@ impl VecOptionalFrSerde {
     where
         S: Serializer,
     {
-        let mut res = Vec::with_capacity(operations.len());
-        for value in operations.iter() {
-            let v = value.map(|fr| fr.to_hex());
-            res.push(v);
-        }
+        let res: Vec<_> = operations
+            .iter()
+            .map(|value| value.map(FrWrapper))
+            .collect();
         Vec::serialize(&res, ser)
     }
 
@@ -138,17 +121,11 @@ impl VecOptionalFrSerde {
     where
         D: Deserializer<'de>,
     {
-        let str_vec: Vec<Option<String>> = Vec::deserialize(deserializer)?;
-        let mut res = Vec::with_capacity(str_vec.len());
-        for s in str_vec.into_iter() {
-            if let Some(a) = s {
-                let v = Fr::from_hex(&a).map_err(de::Error::custom)?;
-                res.push(Some(v));
-            } else {
-                res.push(None);
-            }
-        }
-        Ok(res)
+        let values: Vec<Option<FrWrapper>> = Vec::deserialize(deserializer)?;
+        Ok(values
+            .into_iter()
+            .map(|value| value.map(|FrWrapper(fr)| fr))
+            .collect())
     }
 }
 
@@ -174,10 +151,7 @@ impl VecFrSerde {
     where
         S: Serializer,
     {
-        let mut res = Vec::with_capacity(operations.len());
-        for fr in operations.iter() {
-            res.push(fr.to_hex());
-        }
+        let res: Vec<_> = operations.iter().copied().map(FrWrapper).collect();
         Vec::serialize(&res, ser)
     }
 
@@ -185,13 +159,74 @@ impl VecFrSerde {
     where
         D: Deserializer<'de>,
     {
-        let str_vec: Vec<String> = Vec::deserialize(deserializer)?;
-        let mut res = Vec::with_capacity(str_vec.len());
-        for s in str_vec.into_iter() {
-            let v = Fr::from_hex(&s).map_err(de::Error::custom)?;
-            res.push(v);
+        let values: Vec<FrWrapper> = Vec::deserialize(deserializer)?;
+        Ok(values.into_iter().map(|FrWrapper(fr)| fr).collect())
+    }
+}
+
+/// Wrapper defining the encoding of `Fr` for all the blanket structures above.
+///
+/// In the human-readable formats (e.g. JSON) `Fr` is encoded as a hexadecimal string.
+/// Binary formats (see [`crate::binary_encoding`]) store the big-endian representation
+/// as a byte string instead, which is twice as compact and doesn't require parsing.
+#[derive(Clone, Copy)]
+struct FrWrapper(Fr);
+
+impl Serialize for FrWrapper {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            String::serialize(&self.0.to_hex(), serializer)
+        } else {
+            serializer.serialize_bytes(&self.0.to_bytes())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for FrWrapper {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let fr = if deserializer.is_human_readable() {
+            // The string is expected to be a hexadecimal representation of `Fr`.
+            let deserialized_string = String::deserialize(deserializer)?;
+            Fr::from_hex(&deserialized_string)
+        } else {
+            let bytes = deserializer.deserialize_bytes(BytesVisitor)?;
+            Fr::from_bytes(&bytes)
+        };
+        fr.map(FrWrapper).map_err(de::Error::custom)
+    }
+}
+
+/// Visitor accepting both byte strings and sequences of bytes, since not all the binary formats
+/// distinguish them.
+pub(crate) struct BytesVisitor;
+
+impl<'de> de::Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a byte string")
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+        Ok(value.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(value)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
         }
-        Ok(res)
+        Ok(bytes)
     }
 }
 
@@ -0,0 +1,194 @@
+//! Health tracking of the Ethereum nodes used by `MultiplexerEthereumClient`.
+//!
+//! Every node gets a score based on its latency, error rate and the number of blocks it lags behind
+//! the other nodes. Lower score is better. Nodes failing several times in a row are excluded from the
+//! requests for a while (the circuit breaker is "open"); once the cooldown passes, the node is tried
+//! again and either recovers after a successful request or is excluded again after a single failure.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use zksync_config::ETHClientConfig;
+
+/// Weight of the latest observation in the moving average of the latency.
+const LATENCY_SMOOTHING: f64 = 0.2;
+/// Weight of the latest observation in the moving average of the error rate.
+const ERROR_RATE_SMOOTHING: f64 = 0.1;
+/// Score penalty of a node failing every request, in milliseconds of latency.
+const ERROR_PENALTY_MS: f64 = 10_000.0;
+/// Score penalty for each block the node lags behind, in milliseconds of latency.
+const HEAD_LAG_PENALTY_MS: f64 = 1_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealthConfig {
+    /// Timeout of a single request, slower requests are considered failed.
+    pub request_timeout: Duration,
+    /// Number of consecutive failures after which the node is excluded.
+    pub circuit_breaker_threshold: u32,
+    /// How long an excluded node is not used.
+    pub circuit_breaker_cooldown: Duration,
+    /// Whether read requests are sent to two nodes at once.
+    pub hedged_reads: bool,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            request_timeout: Duration::from_secs(5),
+            circuit_breaker_threshold: 3,
+            circuit_breaker_cooldown: Duration::from_secs(30),
+            hedged_reads: false,
+        }
+    }
+}
+
+impl From<&ETHClientConfig> for HealthConfig {
+    fn from(config: &ETHClientConfig) -> Self {
+        Self {
+            request_timeout: config.request_timeout(),
+            circuit_breaker_threshold: config.circuit_breaker_threshold,
+            circuit_breaker_cooldown: config.circuit_breaker_cooldown(),
+            hedged_reads: config.hedged_reads,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct HealthState {
+    /// Moving average of the request latency, `None` until the first successful request.
+    latency_ms: Option<f64>,
+    /// Moving average of the share of failed requests.
+    error_rate: f64,
+    consecutive_failures: u32,
+    /// The node is excluded from the requests until this moment.
+    open_until: Option<Instant>,
+    /// How many blocks the node lags behind the others, as seen by the gateway watcher.
+    head_lag: u64,
+}
+
+#[derive(Debug)]
+pub struct EndpointHealth {
+    config: HealthConfig,
+    state: Mutex<HealthState>,
+}
+
+impl EndpointHealth {
+    pub fn new(config: HealthConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::default(),
+        }
+    }
+
+    pub fn record_success(&self, latency: Duration) {
+        let mut state = self.state.lock().unwrap();
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        state.latency_ms = Some(match state.latency_ms {
+            Some(average) => average + LATENCY_SMOOTHING * (latency_ms - average),
+            None => latency_ms,
+        });
+        state.error_rate -= ERROR_RATE_SMOOTHING * state.error_rate;
+        state.consecutive_failures = 0;
+        state.open_until = None;
+    }
+
+    /// Records the failed request. Returns `true` if the node has been excluded because of it.
+    pub fn record_failure(&self) -> bool {
+        self.record_failure_at(Instant::now())
+    }
+
+    fn record_failure_at(&self, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.error_rate += ERROR_RATE_SMOOTHING * (1.0 - state.error_rate);
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        // After the cooldown a single failure is enough to exclude the node again.
+        if state.consecutive_failures >= self.config.circuit_breaker_threshold {
+            state.open_until = Some(now + self.config.circuit_breaker_cooldown);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn set_head_lag(&self, head_lag: u64) {
+        self.state.lock().unwrap().head_lag = head_lag;
+    }
+
+    /// Whether the node can be used, i.e. it's not excluded by the circuit breaker.
+    pub fn is_available(&self) -> bool {
+        self.is_available_at(Instant::now())
+    }
+
+    fn is_available_at(&self, now: Instant) -> bool {
+        match self.state.lock().unwrap().open_until {
+            Some(open_until) => now >= open_until,
+            None => true,
+        }
+    }
+
+    /// Score of the node, lower is better.
+    pub fn score(&self) -> f64 {
+        let state = self.state.lock().unwrap();
+        state.latency_ms.unwrap_or_default()
+            + state.error_rate * ERROR_PENALTY_MS
+            + state.head_lag as f64 * HEAD_LAG_PENALTY_MS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health() -> EndpointHealth {
+        EndpointHealth::new(HealthConfig {
+            circuit_breaker_threshold: 2,
+            circuit_breaker_cooldown: Duration::from_secs(10),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn score() {
+        let fast = health();
+        let slow = health();
+        fast.record_success(Duration::from_millis(50));
+        slow.record_success(Duration::from_millis(200));
+        assert!(fast.score() < slow.score());
+
+        // Errors outweigh the latency difference.
+        fast.record_failure();
+        assert!(fast.score() > slow.score());
+        for _ in 0..50 {
+            fast.record_success(Duration::from_millis(50));
+        }
+        assert!(fast.score() < slow.score());
+
+        // So does the lagging head.
+        fast.set_head_lag(1);
+        assert!(fast.score() > slow.score());
+    }
+
+    #[test]
+    fn circuit_breaker() {
+        let health = health();
+        let start = Instant::now();
+
+        assert!(!health.record_failure_at(start));
+        assert!(health.is_available_at(start));
+        assert!(health.record_failure_at(start));
+        assert!(!health.is_available_at(start));
+        assert!(!health.is_available_at(start + Duration::from_secs(9)));
+
+        // After the cooldown the node is tried again, and a single failure excludes it once more.
+        let retry = start + Duration::from_secs(10);
+        assert!(health.is_available_at(retry));
+        assert!(health.record_failure_at(retry));
+        assert!(!health.is_available_at(retry + Duration::from_secs(9)));
+
+        // The successful request closes the breaker.
+        health.record_success(Duration::from_millis(100));
+        assert!(health.is_available_at(retry));
+        assert!(!health.record_failure_at(retry));
+        assert!(health.is_available_at(retry));
+    }
+}
@@ -7,16 +7,17 @@ use std::time::Instant;
 
 // External uses
 use web3::{
+    api::SubscriptionStream,
     contract::{
         tokens::{Detokenize, Tokenize},
         Contract, Options,
     },
     transports::Http,
     types::{
-        Address, BlockId, BlockNumber, Bytes, Filter, Log, Transaction, TransactionId,
+        Address, BlockHeader, BlockId, BlockNumber, Bytes, Filter, Log, Transaction, TransactionId,
         TransactionReceipt, H160, H256, U256, U64,
     },
-    Web3,
+    DuplexTransport, Transport, Web3,
 };
 
 // Workspace uses
@@ -29,22 +30,23 @@ use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, SignedCallResult};
 /// This is an emergency value, which will not be used normally.
 const FALLBACK_GAS_LIMIT: u64 = 3_000_000;
 
-struct ETHDirectClientInner<S: EthereumSigner> {
+struct ETHDirectClientInner<S: EthereumSigner, T: Transport> {
     eth_signer: S,
     sender_account: Address,
     contract_addr: H160,
     contract: ethabi::Contract,
-    chain_id: u8,
+    chain_id: u64,
     gas_price_factor: f64,
-    web3: Web3<Http>,
+    web3: Web3<T>,
 }
 
+/// Ethereum client using a single node. The node is accessed via HTTP unless another transport is specified.
 #[derive(Clone)]
-pub struct ETHDirectClient<S: EthereumSigner> {
-    inner: Arc<ETHDirectClientInner<S>>,
+pub struct ETHDirectClient<S: EthereumSigner, T: Transport = Http> {
+    inner: Arc<ETHDirectClientInner<S, T>>,
 }
 
-impl<S: EthereumSigner> fmt::Debug for ETHDirectClient<S> {
+impl<S: EthereumSigner, T: Transport> fmt::Debug for ETHDirectClient<S, T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // We do not want to have a private key in the debug representation.
 
@@ -57,14 +59,14 @@ impl<S: EthereumSigner> fmt::Debug for ETHDirectClient<S> {
     }
 }
 
-impl<S: EthereumSigner> ETHDirectClient<S> {
+impl<S: EthereumSigner, T: Transport> ETHDirectClient<S, T> {
     pub fn new(
-        transport: Http,
+        transport: T,
         contract: ethabi::Contract,
         operator_eth_addr: H160,
         eth_signer: S,
         contract_eth_addr: H160,
-        chain_id: u8,
+        chain_id: u64,
         gas_price_factor: f64,
     ) -> Self {
         Self {
@@ -80,15 +82,15 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         }
     }
 
-    pub fn main_contract_with_address(&self, address: Address) -> Contract<Http> {
+    pub fn main_contract_with_address(&self, address: Address) -> Contract<T> {
         Contract::new(self.inner.web3.eth(), address, self.inner.contract.clone())
     }
 
-    pub fn main_contract(&self) -> Contract<Http> {
+    pub fn main_contract(&self) -> Contract<T> {
         self.main_contract_with_address(self.inner.contract_addr)
     }
 
-    pub fn create_contract(&self, address: Address, contract: ethabi::Contract) -> Contract<Http> {
+    pub fn create_contract(&self, address: Address, contract: ethabi::Contract) -> Contract<T> {
         Contract::new(self.inner.web3.eth(), address, contract)
     }
 
@@ -243,6 +245,32 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         Ok(tx)
     }
 
+    /// Estimates the gas required to execute the main contract call with the given data
+    /// sent from the operator account.
+    pub async fn estimate_gas(&self, data: Vec<u8>) -> Result<U256, anyhow::Error> {
+        #[cfg(feature = "with-metrics")]
+        let start = Instant::now();
+        let call_request = web3::types::CallRequest {
+            from: Some(self.inner.sender_account),
+            to: Some(self.inner.contract_addr),
+            gas: None,
+            gas_price: None,
+            value: None,
+            data: Some(Bytes(data)),
+            transaction_type: None,
+            access_list: None,
+        };
+        let gas = self
+            .inner
+            .web3
+            .eth()
+            .estimate_gas(call_request, None)
+            .await?;
+        #[cfg(feature = "with-metrics")]
+        metrics::histogram!("eth_client.direct.estimate_gas", start.elapsed());
+        Ok(gas)
+    }
+
     pub async fn tx_receipt(
         &self,
         tx_hash: H256,
@@ -474,7 +502,7 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         self.inner.contract_addr
     }
 
-    pub fn chain_id(&self) -> u8 {
+    pub fn chain_id(&self) -> u64 {
         self.inner.chain_id
     }
 
@@ -492,7 +520,7 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
             .expect("failed to encode parameters")
     }
 
-    pub fn get_web3_transport(&self) -> &Http {
+    pub fn get_web3_transport(&self) -> &T {
         self.inner.web3.transport()
     }
 
@@ -510,3 +538,32 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         Ok(tx)
     }
 }
+
+impl<S: EthereumSigner, T: DuplexTransport> ETHDirectClient<S, T> {
+    /// Subscribes to the new blocks. Fails if the transport doesn't support subscriptions.
+    pub async fn subscribe_new_heads(
+        &self,
+    ) -> Result<SubscriptionStream<T, BlockHeader>, anyhow::Error> {
+        let stream = self
+            .inner
+            .web3
+            .eth_subscribe()
+            .subscribe_new_heads()
+            .await?;
+        Ok(stream)
+    }
+
+    /// Subscribes to the logs matching the filter. Fails if the transport doesn't support subscriptions.
+    pub async fn subscribe_logs(
+        &self,
+        filter: Filter,
+    ) -> Result<SubscriptionStream<T, Log>, anyhow::Error> {
+        let stream = self
+            .inner
+            .web3
+            .eth_subscribe()
+            .subscribe_logs(filter)
+            .await?;
+        Ok(stream)
+    }
+}
@@ -1,27 +1,41 @@
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
 
-use anyhow::Error;
+use anyhow::{format_err, Error};
 use ethabi::{Address, Contract};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 use std::collections::{HashMap, HashSet};
 use tokio::sync::RwLock;
 use web3::contract::tokens::{Detokenize, Tokenize};
 use web3::contract::Options;
-use web3::transports::Http;
-use web3::types::{BlockId, Filter, Log, Transaction, U64};
+use web3::types::{Block, BlockId, BlockNumber, Filter, Log, Transaction, U64};
 
 use zksync_types::{TransactionReceipt, H160, H256, U256};
 
 use crate::{
+    clients::transport::EthTransport,
     ethereum_gateway::{ExecutedTxStatus, FailureInfo},
     SignedCallResult,
 };
 
 #[derive(Debug)]
 struct MockEthereumInner {
-    block_number: u64,
+    block_number: AtomicU64,
     gas_price: U256,
+    /// Gas returned by the `estimate_gas` call.
+    gas_estimate: RwLock<U256>,
     tx_statuses: Arc<RwLock<HashMap<H256, ExecutedTxStatus>>>,
     sent_txs: Arc<RwLock<HashSet<Vec<u8>>>>,
+    /// Events emitted by the contracts.
+    logs: RwLock<Vec<Log>>,
+    /// Addresses of the contracts which respond to the ERC20 calls.
+    erc20_tokens: RwLock<HashSet<Address>>,
+    /// If set, every sent transaction is executed successfully with the given
+    /// amount of confirmations.
+    auto_execution_confirmations: Option<u64>,
 }
 
 /// Mock Ethereum client is capable of recording all the incoming requests for the further analysis.
@@ -33,15 +47,30 @@ pub struct MockEthereum {
 impl Default for MockEthereumInner {
     fn default() -> Self {
         Self {
-            block_number: 1,
+            block_number: AtomicU64::new(1),
             gas_price: 100.into(),
+            gas_estimate: RwLock::new(1_000_000.into()),
             tx_statuses: Default::default(),
             sent_txs: Default::default(),
+            logs: Default::default(),
+            erc20_tokens: Default::default(),
+            auto_execution_confirmations: None,
         }
     }
 }
 
 impl MockEthereum {
+    /// Creates a mock which executes every sent transaction right away, so it can
+    /// be used instead of the real Ethereum node.
+    pub fn with_auto_execution(confirmations: u64) -> Self {
+        Self {
+            inner: Arc::new(MockEthereumInner {
+                auto_execution_confirmations: Some(confirmations),
+                ..Default::default()
+            }),
+        }
+    }
+
     /// A fake `sha256` hasher, which calculates an `std::hash` instead.
     /// This is done for simplicity and it's also much faster.
     pub fn fake_sha256(data: &[u8]) -> H256 {
@@ -56,6 +85,11 @@ impl MockEthereum {
         H256::from_low_u64_ne(result)
     }
 
+    /// Sets the gas returned by the subsequent `estimate_gas` calls.
+    pub async fn set_gas_estimate(&self, gas: U256) {
+        *self.inner.gas_estimate.write().await = gas;
+    }
+
     /// Checks that there was a request to send the provided transaction.
     pub async fn assert_sent(&self, tx: &[u8]) {
         assert!(
@@ -77,7 +111,9 @@ impl MockEthereum {
     /// Increments the blocks by a provided `confirmations` and marks the sent transaction
     /// as a success.
     pub async fn add_successfull_execution(&mut self, tx_hash: H256, confirmations: u64) {
-        Arc::get_mut(&mut self.inner).unwrap().block_number += confirmations;
+        self.inner
+            .block_number
+            .fetch_add(confirmations, Ordering::SeqCst);
 
         let status = ExecutedTxStatus {
             confirmations,
@@ -89,7 +125,9 @@ impl MockEthereum {
 
     /// Same as `add_successfull_execution`, but marks the transaction as a failure.
     pub async fn add_failed_execution(&mut self, hash: &H256, confirmations: u64) {
-        Arc::get_mut(&mut self.inner).unwrap().block_number += confirmations;
+        self.inner
+            .block_number
+            .fetch_add(confirmations, Ordering::SeqCst);
 
         let status = ExecutedTxStatus {
             confirmations,
@@ -98,6 +136,30 @@ impl MockEthereum {
         };
         self.inner.tx_statuses.write().await.insert(*hash, status);
     }
+    /// Adds the contract events into the block following the current one
+    /// and returns the number of this block.
+    pub async fn add_logs(&self, logs: Vec<Log>) -> u64 {
+        let mut stored_logs = self.inner.logs.write().await;
+        let block_number = self.inner.block_number.fetch_add(1, Ordering::SeqCst) + 1;
+        let first_log_index = stored_logs.len();
+        for (index, mut log) in logs.into_iter().enumerate() {
+            log.block_number = Some(block_number.into());
+            log.log_index = Some((first_log_index + index).into());
+            stored_logs.push(log);
+        }
+        block_number
+    }
+
+    /// Makes the contract respond to the ERC20 calls.
+    pub async fn add_erc20_token(&self, address: Address) {
+        self.inner.erc20_tokens.write().await.insert(address);
+    }
+
+    /// Increments the block number by the provided amount of blocks.
+    pub fn mine_blocks(&self, blocks: u64) -> U64 {
+        (self.inner.block_number.fetch_add(blocks, Ordering::SeqCst) + blocks).into()
+    }
+
     pub async fn get_tx_status(
         &self,
         hash: H256,
@@ -107,12 +169,41 @@ impl MockEthereum {
     }
 
     pub async fn block_number(&self) -> anyhow::Result<U64> {
-        Ok(self.inner.block_number.into())
+        Ok(self.inner.block_number.load(Ordering::SeqCst).into())
+    }
+
+    /// Returns the block with the given number. The mock chain is never reorganized,
+    /// so the block hash is derived from the block number.
+    pub async fn block(&self, id: BlockId) -> anyhow::Result<Option<Block<H256>>> {
+        let current_block = self.inner.block_number.load(Ordering::SeqCst);
+        let number = match id {
+            BlockId::Number(BlockNumber::Number(number)) => number.as_u64(),
+            BlockId::Number(BlockNumber::Earliest) => 0,
+            BlockId::Number(_) => current_block,
+            BlockId::Hash(hash) => {
+                return Err(format_err!(
+                    "The mock doesn't support blocks by hash: {:?}",
+                    hash
+                ))
+            }
+        };
+        if number > current_block {
+            return Ok(None);
+        }
+
+        Ok(Some(Block {
+            number: Some(number.into()),
+            hash: Some(H256::from_low_u64_be(number)),
+            parent_hash: H256::from_low_u64_be(number.saturating_sub(1)),
+            ..Default::default()
+        }))
     }
 
     pub async fn set_block_number(&mut self, val: U64) -> anyhow::Result<U64> {
-        Arc::get_mut(&mut self.inner).unwrap().block_number = val.as_u64();
-        Ok(self.inner.block_number.into())
+        self.inner
+            .block_number
+            .store(val.as_u64(), Ordering::SeqCst);
+        Ok(val)
     }
 
     pub async fn get_gas_price(&self) -> anyhow::Result<U256> {
@@ -129,6 +220,20 @@ impl MockEthereum {
         let mut hash: [u8; 32] = Default::default();
         hash.copy_from_slice(&tx[..32]);
         self.inner.sent_txs.write().await.insert(hash.to_vec());
+
+        if let Some(confirmations) = self.inner.auto_execution_confirmations {
+            let status = ExecutedTxStatus {
+                confirmations,
+                success: true,
+                receipt: None,
+            };
+            self.mine_blocks(confirmations);
+            self.inner
+                .tx_statuses
+                .write()
+                .await
+                .insert(H256::from(hash), status);
+        }
         Ok(H256::from(hash))
     }
 
@@ -185,6 +290,10 @@ impl MockEthereum {
         unreachable!()
     }
 
+    pub async fn estimate_gas(&self, _data: Vec<u8>) -> Result<U256, Error> {
+        Ok(*self.inner.gas_estimate.read().await)
+    }
+
     pub async fn tx_receipt(&self, _tx_hash: H256) -> Result<Option<TransactionReceipt>, Error> {
         unreachable!()
     }
@@ -231,22 +340,55 @@ impl MockEthereum {
         B: Into<Option<BlockId>>,
         P: Tokenize,
     {
-        todo!()
+        Err(format_err!(
+            "The mock doesn't support the main contract calls"
+        ))
     }
 
-    pub async fn logs(&self, _filter: Filter) -> anyhow::Result<Vec<Log>> {
-        todo!()
+    pub async fn logs(&self, filter: Filter) -> anyhow::Result<Vec<Log>> {
+        let filter = serde_json::to_value(filter)?;
+        let addresses: Vec<Address> = parse_value_or_array(filter.get("address"))?;
+        let topics: Vec<H256> = match filter.get("topics") {
+            Some(Value::Array(topics)) => parse_value_or_array(topics.first())?,
+            _ => Vec::new(),
+        };
+        let last_block = self.inner.block_number.load(Ordering::SeqCst);
+        let from_block = parse_block_number(filter.get("fromBlock"), 0, last_block)?;
+        let to_block = parse_block_number(filter.get("toBlock"), last_block, last_block)?;
+
+        let logs = self
+            .inner
+            .logs
+            .read()
+            .await
+            .iter()
+            .filter(|log| addresses.is_empty() || addresses.contains(&log.address))
+            .filter(|log| {
+                topics.is_empty()
+                    || log
+                        .topics
+                        .first()
+                        .map_or(false, |topic| topics.contains(topic))
+            })
+            .filter(|log| {
+                log.block_number.map_or(false, |number| {
+                    (from_block..=to_block).contains(&number.as_u64())
+                })
+            })
+            .cloned()
+            .collect();
+        Ok(logs)
     }
 
     #[allow(clippy::too_many_arguments)]
     pub async fn call_contract_function<R, A, B, P>(
         &self,
-        _func: &str,
+        func: &str,
         _params: P,
         _from: A,
         _options: Options,
         _block: B,
-        _token_address: Address,
+        token_address: Address,
         _erc20_abi: ethabi::Contract,
     ) -> Result<R, anyhow::Error>
     where
@@ -255,14 +397,29 @@ impl MockEthereum {
         B: Into<Option<BlockId>>,
         P: Tokenize,
     {
-        todo!()
+        // Known tokens have no balances, since the deposits are added directly as events.
+        if func == "balanceOf"
+            && self
+                .inner
+                .erc20_tokens
+                .read()
+                .await
+                .contains(&token_address)
+        {
+            return Ok(R::from_tokens(vec![ethabi::Token::Uint(U256::zero())])?);
+        }
+        Err(format_err!(
+            "The mock doesn't support the call of {} on {:?}",
+            func,
+            token_address
+        ))
     }
 
     pub fn create_contract(
         &self,
         _address: Address,
         _contract: ethabi::Contract,
-    ) -> web3::contract::Contract<Http> {
+    ) -> web3::contract::Contract<EthTransport> {
         unreachable!()
     }
 
@@ -270,3 +427,25 @@ impl MockEthereum {
         unreachable!()
     }
 }
+
+/// Parses the block number of the logs filter.
+fn parse_block_number(value: Option<&Value>, default: u64, last_block: u64) -> anyhow::Result<u64> {
+    match value {
+        None | Some(Value::Null) => Ok(default),
+        Some(Value::String(tag)) if tag == "earliest" => Ok(0),
+        Some(Value::String(tag)) if tag == "latest" || tag == "pending" => Ok(last_block),
+        Some(value) => Ok(serde_json::from_value::<U64>(value.clone())?.as_u64()),
+    }
+}
+
+/// Parses the filter field, which is either a single value or an array of them.
+fn parse_value_or_array<T: DeserializeOwned>(value: Option<&Value>) -> anyhow::Result<Vec<T>> {
+    match value {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Array(values)) => values
+            .iter()
+            .map(|value| Ok(serde_json::from_value(value.clone())?))
+            .collect(),
+        Some(value) => Ok(vec![serde_json::from_value(value.clone())?]),
+    }
+}
@@ -1,3 +1,5 @@
+pub mod health;
 pub mod http_client;
 pub mod mock;
 pub mod multiplexer;
+pub mod transport;
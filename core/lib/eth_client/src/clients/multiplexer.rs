@@ -1,35 +1,119 @@
 use ethabi::Contract;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use futures::{
+    future::{join_all, select_ok},
+    StreamExt,
+};
 use std::sync::Arc;
+use std::time::Instant;
+use tokio::time;
 use web3::{
     contract::tokens::{Detokenize, Tokenize},
     contract::Options,
-    transports::Http,
-    types::{Address, BlockId, Filter, Log, Transaction, U64},
+    types::{Address, Block, BlockHeader, BlockId, Filter, Log, Transaction, U64},
 };
-use zksync_eth_signer::PrivateKeySigner;
+use zksync_eth_signer::OperatorSigner;
 use zksync_types::{TransactionReceipt, H160, H256, U256};
 
-use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, SignedCallResult};
+use crate::clients::health::{EndpointHealth, HealthConfig};
+use crate::clients::transport::EthTransport;
+use crate::ethereum_gateway::{ExecutedTxStatus, FailureInfo, SignedCallResult, Subscription};
 use crate::ETHDirectClient;
 
+#[derive(Debug)]
+struct Endpoint {
+    name: String,
+    client: ETHDirectClient<OperatorSigner, EthTransport>,
+    health: EndpointHealth,
+}
+
+impl Endpoint {
+    /// Updates the health of the endpoint with the outcome of the request.
+    fn record<T>(
+        &self,
+        result: Result<Result<T, anyhow::Error>, time::error::Elapsed>,
+        start: Instant,
+    ) -> Result<T, anyhow::Error> {
+        let result = result.unwrap_or_else(|_| Err(anyhow::format_err!("request timed out")));
+        match &result {
+            Ok(_) => self.health.record_success(start.elapsed()),
+            Err(err) => {
+                vlog::error!("Error in interface: {}, {} ", self.name, err);
+                if self.health.record_failure() {
+                    vlog::warn!("Ethereum Gateway `{}` is temporarily excluded", self.name);
+                }
+                #[cfg(feature = "with-metrics")]
+                metrics::increment_counter!("eth_client.multiplexed.failure", "address" => self.name.clone());
+            }
+        }
+        result
+    }
+}
+
 #[derive(Debug, Default)]
 struct MultiplexerEthereumClientInner {
-    clients: Vec<(String, ETHDirectClient<PrivateKeySigner>)>,
-    preferred: AtomicUsize,
+    endpoints: Vec<Endpoint>,
+    config: HealthConfig,
 }
 
+/// Ethereum client using several nodes at once.
+///
+/// Requests are sent to the healthiest node first (see `EndpointHealth`), falling back to the other ones
+/// on failure. Read requests can be sent to two nodes at once if `hedged_reads` is enabled, and signed
+/// transactions are broadcasted to all the available nodes.
 #[derive(Debug, Default, Clone)]
 pub struct MultiplexerEthereumClient {
     inner: Arc<MultiplexerEthereumClientInner>,
 }
 
+/// Sends the request to the available endpoints one by one until one of them succeeds.
 macro_rules! multiple_call {
     ($self:expr, $func:ident($($attr:expr),*)) => {
-        for (name, client) in $self.clients() {
-            match client.$func($($attr.clone()),*).await {
-                Ok(res) => return Ok(res),
-                Err(err) => vlog::error!("Error in interface: {}, {} ", name, err),
+        for endpoint in $self.available_endpoints() {
+            let start = Instant::now();
+            let result = time::timeout(
+                $self.inner.config.request_timeout,
+                endpoint.client.$func($($attr.clone()),*),
+            )
+            .await;
+            if let Ok(res) = endpoint.record(result, start) {
+                return Ok(res);
+            }
+        }
+        anyhow::bail!("All interfaces was wrong please try again")
+    };
+}
+
+/// Same as `multiple_call!`, but sends the request to the two best endpoints at once if hedged reads are enabled.
+/// Must only be used for the requests without side effects.
+macro_rules! hedged_call {
+    ($self:expr, $func:ident($($attr:ident),*)) => {
+        let mut endpoints = $self.available_endpoints();
+        if $self.inner.config.hedged_reads && endpoints.len() >= 2 {
+            let requests = endpoints.drain(..2).map(|endpoint| {
+                $(let $attr = &$attr;)*
+                Box::pin(async move {
+                    let start = Instant::now();
+                    let result = time::timeout(
+                        $self.inner.config.request_timeout,
+                        endpoint.client.$func($($attr.clone()),*),
+                    )
+                    .await;
+                    endpoint.record(result, start)
+                })
+            });
+            if let Ok((res, _)) = select_ok(requests).await {
+                return Ok(res);
+            }
+        }
+        for endpoint in endpoints {
+            let start = Instant::now();
+            let result = time::timeout(
+                $self.inner.config.request_timeout,
+                endpoint.client.$func($($attr.clone()),*),
+            )
+            .await;
+            if let Ok(res) = endpoint.record(result, start) {
+                return Ok(res);
             }
         }
         anyhow::bail!("All interfaces was wrong please try again")
@@ -41,48 +125,109 @@ impl MultiplexerEthereumClient {
         Self::default()
     }
 
+    pub fn with_config(config: HealthConfig) -> Self {
+        Self {
+            inner: Arc::new(MultiplexerEthereumClientInner {
+                endpoints: Vec::new(),
+                config,
+            }),
+        }
+    }
+
     pub fn add_client(
         &mut self,
         name: String,
-        client: ETHDirectClient<PrivateKeySigner>,
+        client: ETHDirectClient<OperatorSigner, EthTransport>,
     ) -> &mut Self {
-        Arc::get_mut(&mut self.inner)
-            .unwrap()
-            .clients
-            .push((name, client));
+        let inner = Arc::get_mut(&mut self.inner).unwrap();
+        let health = EndpointHealth::new(inner.config);
+        inner.endpoints.push(Endpoint {
+            name,
+            client,
+            health,
+        });
         self
     }
 
-    pub fn prioritize_client(&self, name: &str) -> bool {
-        if let Some(idx) = self.inner.clients.iter().position(|(key, _)| key == name) {
-            self.inner.preferred.swap(idx, Ordering::Acquire) != idx
-        } else {
-            false
+    /// Sets the number of blocks the client lags behind the others.
+    pub fn set_head_lag(&self, name: &str, head_lag: u64) {
+        if let Some(endpoint) = self.endpoint(name) {
+            endpoint.health.set_head_lag(head_lag);
         }
     }
 
-    pub fn clients(&self) -> impl Iterator<Item = (&str, &ETHDirectClient<PrivateKeySigner>)> {
-        let preferred = self.inner.preferred.load(Ordering::Relaxed);
+    /// Records the outcome of the request made to the client outside of the multiplexer, e.g. by the gateway watcher.
+    pub fn record_request(&self, name: &str, result: Result<(), anyhow::Error>, start: Instant) {
+        if let Some(endpoint) = self.endpoint(name) {
+            let _ = endpoint.record(Ok(result), start);
+        }
+    }
+
+    fn endpoint(&self, name: &str) -> Option<&Endpoint> {
         self.inner
-            .clients
-            .get(preferred)
+            .endpoints
+            .iter()
+            .find(|endpoint| endpoint.name == name)
+    }
+
+    /// Returns all the endpoints ordered by their health: the available ones by their score,
+    /// followed by the ones excluded by the circuit breaker.
+    fn endpoints(&self) -> Vec<&Endpoint> {
+        let mut endpoints: Vec<_> = self
+            .inner
+            .endpoints
+            .iter()
+            .map(|endpoint| {
+                (
+                    !endpoint.health.is_available(),
+                    endpoint.health.score(),
+                    endpoint,
+                )
+            })
+            .collect();
+        endpoints.sort_by(|(excluded1, score1, _), (excluded2, score2, _)| {
+            excluded1.cmp(excluded2).then_with(|| {
+                score1
+                    .partial_cmp(score2)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+        });
+        endpoints
             .into_iter()
-            .chain(self.inner.clients.get(..preferred).unwrap_or(&[]).iter())
-            .chain(
-                self.inner
-                    .clients
-                    .get(1 + preferred..)
-                    .unwrap_or(&[])
-                    .iter(),
-            )
-            .map(|(name, client)| (name.as_str(), client))
+            .map(|(_, _, endpoint)| endpoint)
+            .collect()
+    }
+
+    /// Returns the endpoints to send the requests to. If all the endpoints are excluded by the circuit breaker,
+    /// all of them are returned, since there is nothing to fall back to.
+    fn available_endpoints(&self) -> Vec<&Endpoint> {
+        let endpoints = self.endpoints();
+        let available: Vec<_> = endpoints
+            .iter()
+            .copied()
+            .filter(|endpoint| endpoint.health.is_available())
+            .collect();
+        if available.is_empty() {
+            endpoints
+        } else {
+            available
+        }
+    }
+
+    /// Returns all the clients, from the healthiest to the least healthy one.
+    pub fn clients(
+        &self,
+    ) -> impl Iterator<Item = (&str, &ETHDirectClient<OperatorSigner, EthTransport>)> {
+        self.endpoints()
+            .into_iter()
+            .map(|endpoint| (endpoint.name.as_str(), &endpoint.client))
     }
 
     pub fn create_contract(
         &self,
         address: Address,
         contract: ethabi::Contract,
-    ) -> web3::contract::Contract<Http> {
+    ) -> web3::contract::Contract<EthTransport> {
         let client = self
             .clients()
             .next()
@@ -91,23 +236,27 @@ impl MultiplexerEthereumClient {
     }
 
     pub async fn pending_nonce(&self) -> Result<U256, anyhow::Error> {
-        multiple_call!(self, pending_nonce());
+        hedged_call!(self, pending_nonce());
     }
 
     pub async fn current_nonce(&self) -> Result<U256, anyhow::Error> {
-        multiple_call!(self, current_nonce());
+        hedged_call!(self, current_nonce());
     }
 
     pub async fn block_number(&self) -> Result<U64, anyhow::Error> {
-        multiple_call!(self, block_number());
+        hedged_call!(self, block_number());
+    }
+
+    pub async fn block(&self, id: BlockId) -> Result<Option<Block<H256>>, anyhow::Error> {
+        hedged_call!(self, block(id));
     }
 
     pub async fn get_gas_price(&self) -> Result<U256, anyhow::Error> {
-        multiple_call!(self, get_gas_price());
+        hedged_call!(self, get_gas_price());
     }
 
     pub async fn sender_eth_balance(&self) -> Result<U256, anyhow::Error> {
-        multiple_call!(self, sender_eth_balance());
+        hedged_call!(self, sender_eth_balance());
     }
 
     pub async fn sign_prepared_tx(
@@ -130,26 +279,48 @@ impl MultiplexerEthereumClient {
         );
     }
 
+    /// Broadcasts the transaction to all the available clients, so it's not lost if one of them drops it.
+    /// Succeeds if at least one of the clients has accepted the transaction.
     pub async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, anyhow::Error> {
-        multiple_call!(self, send_raw_tx(tx));
+        let requests = self.available_endpoints().into_iter().map(|endpoint| {
+            let tx = tx.clone();
+            async move {
+                let start = Instant::now();
+                let result = time::timeout(
+                    self.inner.config.request_timeout,
+                    endpoint.client.send_raw_tx(tx),
+                )
+                .await;
+                endpoint.record(result, start)
+            }
+        });
+        join_all(requests)
+            .await
+            .into_iter()
+            .find_map(Result::ok)
+            .ok_or_else(|| anyhow::format_err!("All interfaces was wrong please try again"))
+    }
+
+    pub async fn estimate_gas(&self, data: Vec<u8>) -> Result<U256, anyhow::Error> {
+        hedged_call!(self, estimate_gas(data));
     }
 
     pub async fn tx_receipt(
         &self,
         tx_hash: H256,
     ) -> Result<Option<TransactionReceipt>, anyhow::Error> {
-        multiple_call!(self, tx_receipt(tx_hash));
+        hedged_call!(self, tx_receipt(tx_hash));
     }
 
     pub async fn failure_reason(
         &self,
         tx_hash: H256,
     ) -> Result<Option<FailureInfo>, anyhow::Error> {
-        multiple_call!(self, failure_reason(tx_hash));
+        hedged_call!(self, failure_reason(tx_hash));
     }
 
     pub async fn eth_balance(&self, address: Address) -> Result<U256, anyhow::Error> {
-        multiple_call!(self, eth_balance(address));
+        hedged_call!(self, eth_balance(address));
     }
 
     pub async fn allowance(
@@ -157,7 +328,7 @@ impl MultiplexerEthereumClient {
         token_address: Address,
         erc20_abi: Contract,
     ) -> Result<U256, anyhow::Error> {
-        multiple_call!(self, allowance(token_address, erc20_abi));
+        hedged_call!(self, allowance(token_address, erc20_abi));
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -177,7 +348,7 @@ impl MultiplexerEthereumClient {
         B: Into<Option<BlockId>> + Clone,
         P: Tokenize + Clone,
     {
-        multiple_call!(
+        hedged_call!(
             self,
             call_contract_function(func, params, from, options, block, token_address, erc20_abi)
         );
@@ -197,7 +368,7 @@ impl MultiplexerEthereumClient {
         B: Into<Option<BlockId>> + Clone,
         P: Tokenize + Clone,
     {
-        multiple_call!(
+        hedged_call!(
             self,
             call_main_contract_function(func, params, from, options, block)
         );
@@ -208,11 +379,51 @@ impl MultiplexerEthereumClient {
         hash: H256,
         current_block: Option<u64>,
     ) -> Result<Option<ExecutedTxStatus>, anyhow::Error> {
-        multiple_call!(self, get_tx_status(hash, current_block));
+        hedged_call!(self, get_tx_status(hash, current_block));
     }
 
     pub async fn logs(&self, filter: Filter) -> anyhow::Result<Vec<Log>> {
-        multiple_call!(self, logs(filter));
+        hedged_call!(self, logs(filter));
+    }
+
+    /// Whether any of the clients supports subscriptions.
+    pub fn supports_subscriptions(&self) -> bool {
+        self.inner
+            .endpoints
+            .iter()
+            .any(|endpoint| endpoint.client.get_web3_transport().is_duplex())
+    }
+
+    /// Subscribes to the new blocks using the healthiest client supporting subscriptions.
+    pub async fn subscribe_new_heads(&self) -> anyhow::Result<Subscription<BlockHeader>> {
+        for endpoint in self.duplex_endpoints() {
+            match endpoint.client.subscribe_new_heads().await {
+                Ok(stream) => {
+                    return Ok(stream.map(|head| head.map_err(anyhow::Error::from)).boxed())
+                }
+                Err(err) => vlog::error!("Error in interface: {}, {} ", endpoint.name, err),
+            }
+        }
+        anyhow::bail!("Unable to subscribe to the new blocks")
+    }
+
+    /// Subscribes to the logs using the healthiest client supporting subscriptions.
+    pub async fn subscribe_logs(&self, filter: Filter) -> anyhow::Result<Subscription<Log>> {
+        for endpoint in self.duplex_endpoints() {
+            match endpoint.client.subscribe_logs(filter.clone()).await {
+                Ok(stream) => {
+                    return Ok(stream.map(|log| log.map_err(anyhow::Error::from)).boxed())
+                }
+                Err(err) => vlog::error!("Error in interface: {}, {} ", endpoint.name, err),
+            }
+        }
+        anyhow::bail!("Unable to subscribe to the logs")
+    }
+
+    fn duplex_endpoints(&self) -> impl Iterator<Item = &Endpoint> {
+        self.available_endpoints()
+            .into_iter()
+            .filter(|endpoint| endpoint.client.get_web3_transport().is_duplex())
     }
 
     pub fn encode_tx_data<P: Tokenize + Clone>(&self, func: &str, params: P) -> Vec<u8> {
@@ -224,6 +435,6 @@ impl MultiplexerEthereumClient {
     }
 
     pub async fn get_tx(&self, hash: H256) -> Result<Option<Transaction>, anyhow::Error> {
-        multiple_call!(self, get_tx(hash));
+        hedged_call!(self, get_tx(hash));
     }
 }
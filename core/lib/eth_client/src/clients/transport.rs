@@ -0,0 +1,114 @@
+//! Transport used to communicate with the Ethereum node.
+//!
+//! The transport is chosen by the scheme of the node URL: `http(s)://` for HTTP, `ws(s)://` for WebSocket
+//! and `ipc://` (or a plain file path) for the IPC socket. Only WebSocket and IPC support subscriptions,
+//! so with HTTP the components fall back to polling the node.
+
+use futures::{
+    future::BoxFuture,
+    stream::{BoxStream, Stream},
+    FutureExt, StreamExt,
+};
+use web3::{
+    api::SubscriptionId,
+    rpc,
+    transports::{Http, Ipc, WebSocket},
+    DuplexTransport, RequestId, Transport,
+};
+
+type Notification = <<WebSocket as DuplexTransport>::NotificationStream as Stream>::Item;
+
+#[derive(Debug, Clone)]
+pub enum EthTransport {
+    Http(Http),
+    WebSocket(WebSocket),
+    Ipc(Ipc),
+}
+
+impl EthTransport {
+    /// Connects to the node using the transport corresponding to the URL scheme.
+    pub async fn connect(url: &str) -> Result<Self, web3::Error> {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            Ok(Self::Http(Http::new(url)?))
+        } else if url.starts_with("ws://") || url.starts_with("wss://") {
+            Ok(Self::WebSocket(WebSocket::new(url).await?))
+        } else {
+            let path = url.strip_prefix("ipc://").unwrap_or(url);
+            Ok(Self::Ipc(Ipc::new(path).await?))
+        }
+    }
+
+    /// Whether the transport supports subscriptions.
+    pub fn is_duplex(&self) -> bool {
+        !matches!(self, Self::Http(_))
+    }
+}
+
+impl From<Http> for EthTransport {
+    fn from(transport: Http) -> Self {
+        Self::Http(transport)
+    }
+}
+
+impl Transport for EthTransport {
+    type Out = BoxFuture<'static, web3::Result<rpc::Value>>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        match self {
+            Self::Http(transport) => transport.prepare(method, params),
+            Self::WebSocket(transport) => transport.prepare(method, params),
+            Self::Ipc(transport) => transport.prepare(method, params),
+        }
+    }
+
+    fn send(&self, id: RequestId, request: rpc::Call) -> Self::Out {
+        match self {
+            Self::Http(transport) => transport.send(id, request).boxed(),
+            Self::WebSocket(transport) => transport.send(id, request).boxed(),
+            Self::Ipc(transport) => transport.send(id, request).boxed(),
+        }
+    }
+}
+
+impl DuplexTransport for EthTransport {
+    type NotificationStream = BoxStream<'static, Notification>;
+
+    fn subscribe(&self, id: SubscriptionId) -> web3::Result<Self::NotificationStream> {
+        match self {
+            Self::Http(_) => Err(web3::Error::Unreachable),
+            Self::WebSocket(transport) => Ok(transport.subscribe(id)?.boxed()),
+            Self::Ipc(transport) => Ok(transport.subscribe(id)?.boxed()),
+        }
+    }
+
+    fn unsubscribe(&self, id: SubscriptionId) -> web3::Result<()> {
+        match self {
+            Self::Http(_) => Err(web3::Error::Unreachable),
+            Self::WebSocket(transport) => transport.unsubscribe(id),
+            Self::Ipc(transport) => transport.unsubscribe(id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn transport_by_scheme() {
+        let transport = EthTransport::connect("http://127.0.0.1:8545")
+            .await
+            .unwrap();
+        assert!(matches!(transport, EthTransport::Http(_)));
+        assert!(!transport.is_duplex());
+        assert!(transport
+            .subscribe(SubscriptionId::from("0x1".to_owned()))
+            .is_err());
+
+        // Unlike HTTP, WebSocket and IPC transports connect to the node right away.
+        assert!(EthTransport::connect("ws://127.0.0.1:1").await.is_err());
+        assert!(EthTransport::connect("ipc:///nonexistent/geth.ipc")
+            .await
+            .is_err());
+    }
+}
@@ -1,18 +1,22 @@
+use futures::{stream::BoxStream, StreamExt};
 use web3::contract::tokens::{Detokenize, Tokenize};
 use web3::contract::{Contract, Options};
-use web3::transports::Http;
-use web3::types::{Address, BlockId, Filter, Log, Transaction, U64};
+use web3::types::{Address, Block, BlockHeader, BlockId, Filter, Log, Transaction, U64};
 
-use std::fmt::Debug;
-use zksync_config::{ETHClientConfig, ETHSenderConfig};
+use std::{fmt::Debug, fs, path::Path};
+use zksync_config::{configs::eth_sender::Sender, ETHClientConfig, ETHSenderConfig};
 use zksync_contracts::zksync_contract;
-use zksync_eth_signer::PrivateKeySigner;
+use zksync_eth_signer::{DerivationPath, Keystore, OperatorSigner, PrivateKeySigner, RemoteSigner};
 use zksync_types::{TransactionReceipt, H160, H256, U256};
 
 use crate::clients::mock::MockEthereum;
 use crate::clients::multiplexer::MultiplexerEthereumClient;
+use crate::clients::transport::EthTransport;
 use crate::ETHDirectClient;
 
+/// Stream of the notifications received via subscription.
+pub type Subscription<T> = BoxStream<'static, anyhow::Result<T>>;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SignedCallResult {
     pub raw_tx: Vec<u8>,
@@ -43,42 +47,52 @@ pub struct FailureInfo {
 
 #[derive(Debug, Clone)]
 pub enum EthereumGateway {
-    Direct(ETHDirectClient<PrivateKeySigner>),
+    Direct(ETHDirectClient<OperatorSigner, EthTransport>),
     Multiplexed(MultiplexerEthereumClient),
     Mock(MockEthereum),
 }
 
 impl EthereumGateway {
-    pub fn from_config(
+    /// Connects to the Ethereum nodes from the config.
+    /// The transport for each node is chosen by the URL scheme, see `EthTransport`.
+    pub async fn from_config(
         eth_client_config: &ETHClientConfig,
         eth_sender_config: &ETHSenderConfig,
         main_contract: Address,
     ) -> Self {
+        let operator_signer = operator_signer(&eth_sender_config.sender)
+            .await
+            .expect("Unable to load operator key");
+
         if eth_client_config.web3_url.len() == 1 {
-            let transport = web3::transports::Http::new(&eth_client_config.web3_url()).unwrap();
+            let transport = EthTransport::connect(&eth_client_config.web3_url())
+                .await
+                .expect("Unable to connect to the Ethereum node");
 
             EthereumGateway::Direct(ETHDirectClient::new(
                 transport,
                 zksync_contract(),
                 eth_sender_config.sender.operator_commit_eth_addr,
-                PrivateKeySigner::new(eth_sender_config.sender.operator_private_key),
+                operator_signer,
                 main_contract,
                 eth_client_config.chain_id,
                 eth_client_config.gas_price_factor,
             ))
         } else {
-            let mut client = MultiplexerEthereumClient::new();
+            let mut client = MultiplexerEthereumClient::with_config(eth_client_config.into());
 
             let contract = zksync_contract();
             for web3_url in eth_client_config.web3_url.iter().cloned() {
-                let transport = web3::transports::Http::new(&web3_url).unwrap();
+                let transport = EthTransport::connect(&web3_url)
+                    .await
+                    .expect("Unable to connect to the Ethereum node");
                 client.add_client(
                     web3_url,
                     ETHDirectClient::new(
                         transport,
                         contract.clone(),
                         eth_sender_config.sender.operator_commit_eth_addr,
-                        PrivateKeySigner::new(eth_sender_config.sender.operator_private_key),
+                        operator_signer.clone(),
                         main_contract,
                         eth_client_config.chain_id,
                         eth_client_config.gas_price_factor,
@@ -90,6 +104,80 @@ impl EthereumGateway {
     }
 }
 
+/// Creates the operator signer from the config: either the transactions are signed by the remote
+/// signing daemon, or the key is loaded by the server itself (see `local_operator_signer`).
+/// The remote signer is checked to manage the key of the operator address, so a misconfigured
+/// daemon is detected at startup rather than on the first transaction.
+pub async fn operator_signer(config: &Sender) -> anyhow::Result<OperatorSigner> {
+    match &config.operator_remote_signer_url {
+        Some(url) => {
+            if config.operator_private_key.is_some()
+                || config.operator_keystore_path.is_some()
+                || config.operator_mnemonic_path.is_some()
+            {
+                anyhow::bail!("Operator key must not be set if the remote signer is used");
+            }
+            let token_path = config
+                .operator_remote_signer_token_path
+                .as_ref()
+                .ok_or_else(|| {
+                    anyhow::format_err!(
+                        "Remote signer token path must be set if the remote signer is used"
+                    )
+                })?;
+            let signer = RemoteSigner::connect(
+                url.clone(),
+                config.operator_commit_eth_addr,
+                read_secret(token_path)?,
+            )
+            .await?;
+            Ok(signer.into())
+        }
+        None => local_operator_signer(config).map(Into::into),
+    }
+}
+
+/// Creates the operator signer from the key source set in the config: either the plain private key,
+/// the encrypted keystore or the mnemonic phrase. Exactly one of them must be set.
+pub fn local_operator_signer(config: &Sender) -> anyhow::Result<PrivateKeySigner> {
+    let read_password = || -> anyhow::Result<String> {
+        match &config.operator_password_path {
+            Some(path) => read_secret(path),
+            None => Ok(String::new()),
+        }
+    };
+
+    let signer = match (
+        config.operator_private_key,
+        &config.operator_keystore_path,
+        &config.operator_mnemonic_path,
+    ) {
+        (Some(private_key), None, None) => PrivateKeySigner::new(private_key),
+        (None, Some(keystore_path), None) => {
+            let keystore = Keystore::from_file(keystore_path)?;
+            PrivateKeySigner::from_keystore(&keystore, &read_password()?)?
+        }
+        (None, None, Some(mnemonic_path)) => {
+            let path = match &config.operator_derivation_path {
+                Some(path) => path.parse()?,
+                None => DerivationPath::default(),
+            };
+            PrivateKeySigner::from_mnemonic(&read_secret(mnemonic_path)?, &read_password()?, &path)?
+        }
+        _ => anyhow::bail!(
+            "Exactly one of the operator private key, keystore path or mnemonic path must be set"
+        ),
+    };
+    Ok(signer)
+}
+
+/// Reads the secret from the file, ignoring the trailing newline.
+pub fn read_secret(path: &Path) -> anyhow::Result<String> {
+    let secret = fs::read_to_string(path)
+        .map_err(|err| anyhow::format_err!("Unable to read {}: {}", path.display(), err))?;
+    Ok(secret.trim_end_matches(&['\r', '\n'][..]).to_owned())
+}
+
 macro_rules! delegate_call {
     ($self:ident.$method:ident($($args:ident),*)) => {
         match $self {
@@ -123,6 +211,10 @@ impl EthereumGateway {
         delegate_call!(self.block_number())
     }
 
+    pub async fn block(&self, id: BlockId) -> Result<Option<Block<H256>>, anyhow::Error> {
+        delegate_call!(self.block(id))
+    }
+
     pub async fn get_gas_price(&self) -> Result<U256, anyhow::Error> {
         delegate_call!(self.get_gas_price())
     }
@@ -158,6 +250,11 @@ impl EthereumGateway {
         delegate_call!(self.send_raw_tx(tx))
     }
 
+    /// Estimates the gas required to execute the main contract call with the given data.
+    pub async fn estimate_gas(&self, data: Vec<u8>) -> Result<U256, anyhow::Error> {
+        delegate_call!(self.estimate_gas(data))
+    }
+
     /// Gets the Ethereum transaction receipt.
     pub async fn tx_receipt(
         &self,
@@ -253,7 +350,11 @@ impl EthereumGateway {
         }
     }
 
-    pub fn create_contract(&self, address: Address, contract: ethabi::Contract) -> Contract<Http> {
+    pub fn create_contract(
+        &self,
+        address: Address,
+        contract: ethabi::Contract,
+    ) -> Contract<EthTransport> {
         match self {
             EthereumGateway::Multiplexed(c) => c.create_contract(address, contract),
             EthereumGateway::Direct(c) => c.create_contract(address, contract),
@@ -265,6 +366,40 @@ impl EthereumGateway {
         delegate_call!(self.get_tx(hash))
     }
 
+    /// Whether the gateway is able to subscribe to the node events.
+    /// If it's not, the events have to be polled.
+    pub fn supports_subscriptions(&self) -> bool {
+        match self {
+            EthereumGateway::Multiplexed(c) => c.supports_subscriptions(),
+            EthereumGateway::Direct(c) => c.get_web3_transport().is_duplex(),
+            EthereumGateway::Mock(_) => false,
+        }
+    }
+
+    /// Subscribes to the new blocks.
+    pub async fn subscribe_new_heads(&self) -> anyhow::Result<Subscription<BlockHeader>> {
+        match self {
+            EthereumGateway::Multiplexed(c) => c.subscribe_new_heads().await,
+            EthereumGateway::Direct(c) => {
+                let stream = c.subscribe_new_heads().await?;
+                Ok(stream.map(|head| head.map_err(anyhow::Error::from)).boxed())
+            }
+            EthereumGateway::Mock(_) => anyhow::bail!("The mock doesn't support subscriptions"),
+        }
+    }
+
+    /// Subscribes to the logs matching the filter.
+    pub async fn subscribe_logs(&self, filter: Filter) -> anyhow::Result<Subscription<Log>> {
+        match self {
+            EthereumGateway::Multiplexed(c) => c.subscribe_logs(filter).await,
+            EthereumGateway::Direct(c) => {
+                let stream = c.subscribe_logs(filter).await?;
+                Ok(stream.map(|log| log.map_err(anyhow::Error::from)).boxed())
+            }
+            EthereumGateway::Mock(_) => anyhow::bail!("The mock doesn't support subscriptions"),
+        }
+    }
+
     pub fn is_multiplexed(&self) -> bool {
         matches!(self, EthereumGateway::Multiplexed(_))
     }
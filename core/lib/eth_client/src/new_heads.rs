@@ -0,0 +1,96 @@
+//! Tracking of the latest Ethereum block via the `newHeads` subscription.
+
+use std::time::Duration;
+
+use futures::{future, StreamExt};
+use tokio::{sync::watch, time};
+
+use crate::EthereumGateway;
+
+/// Number of the latest Ethereum block, as received via the subscription.
+///
+/// The subscription is kept alive by a background task: if it fails, the task resubscribes after a delay.
+/// While there is no active subscription, the latest block is unknown and the node has to be polled.
+#[derive(Debug, Clone)]
+pub struct NewHeads {
+    receiver: Option<watch::Receiver<Option<u64>>>,
+}
+
+impl NewHeads {
+    /// Subscribes to the new blocks if the gateway supports subscriptions.
+    pub fn subscribe(gateway: &EthereumGateway, resubscribe_delay: Duration) -> Self {
+        if !gateway.supports_subscriptions() {
+            return Self::disabled();
+        }
+
+        let gateway = gateway.clone();
+        let (sender, receiver) = watch::channel(None);
+        tokio::spawn(async move {
+            loop {
+                match gateway.subscribe_new_heads().await {
+                    Ok(mut heads) => {
+                        vlog::info!("Subscribed to the new Ethereum blocks");
+                        while let Some(head) = heads.next().await {
+                            match head {
+                                Ok(head) => {
+                                    let number = head.number.map(|number| number.as_u64());
+                                    if number.is_some() && sender.send(number).is_err() {
+                                        // All the receivers are dropped.
+                                        return;
+                                    }
+                                }
+                                Err(err) => {
+                                    vlog::warn!("New Ethereum blocks subscription failed: {}", err);
+                                    break;
+                                }
+                            }
+                        }
+                        vlog::warn!(
+                            "New Ethereum blocks subscription has ended, falling back to polling"
+                        );
+                    }
+                    Err(err) => {
+                        vlog::warn!("Unable to subscribe to the new Ethereum blocks: {}", err);
+                    }
+                }
+
+                let was_subscribed = sender.borrow().is_some();
+                if was_subscribed && sender.send(None).is_err() {
+                    return;
+                }
+                time::sleep(resubscribe_delay).await;
+            }
+        });
+
+        Self {
+            receiver: Some(receiver),
+        }
+    }
+
+    /// Never receives any blocks, so the node is always polled.
+    pub fn disabled() -> Self {
+        Self { receiver: None }
+    }
+
+    /// Number of the latest block, or `None` if there is no active subscription.
+    pub fn latest(&self) -> Option<u64> {
+        self.receiver
+            .as_ref()
+            .and_then(|receiver| *receiver.borrow())
+    }
+
+    /// Waits for the next block, returning its number.
+    /// Returns `None` if the subscription is lost; never resolves if the subscriptions aren't supported.
+    pub async fn changed(&mut self) -> Option<u64> {
+        match &mut self.receiver {
+            Some(receiver) => {
+                if receiver.changed().await.is_err() {
+                    self.receiver = None;
+                    return None;
+                }
+                *receiver.borrow()
+            }
+            None => future::pending().await,
+        }
+    }
+}
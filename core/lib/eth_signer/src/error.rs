@@ -29,6 +29,10 @@ pub enum SignerError {
     DefineAddress,
     #[error("Recover address from signature failed: {0}")]
     RecoverAddress(String),
+    #[error("Invalid keystore: {0}")]
+    InvalidKeystore(String),
+    #[error("Invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
     #[error("{0}")]
     CustomError(String),
 }
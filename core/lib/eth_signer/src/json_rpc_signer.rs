@@ -4,7 +4,7 @@ use crate::EthereumSigner;
 use crate::RawTransaction;
 
 use jsonrpc_core::types::response::Output;
-use zksync_types::tx::{PackedEthSignature, TxEthSignature};
+use zksync_types::tx::{Eip712TypedData, PackedEthSignature, TxEthSignature};
 use zksync_types::Address;
 
 use serde_json::Value;
@@ -84,6 +84,32 @@ impl EthereumSigner for JsonRpcSigner {
         }
     }
 
+    /// Signs the EIP-712 typed data via `eth_signTypedData_v4`
+    /// and checks that the signature was made by the expected address.
+    async fn sign_typed_data(
+        &self,
+        typed_data: &Eip712TypedData,
+    ) -> Result<TxEthSignature, SignerError> {
+        let message = JsonRpcRequest::sign_typed_data(self.address()?, typed_data);
+        let ret = self
+            .post(&message)
+            .await
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+        let signature: PackedEthSignature = serde_json::from_value(ret)
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+
+        let signer = signature
+            .signature_recover_signer_from_hash(typed_data.signing_hash)
+            .map_err(|err| SignerError::RecoverAddress(err.to_string()))?;
+        if signer == self.address()? {
+            Ok(TxEthSignature::EIP712Signature(signature))
+        } else {
+            Err(SignerError::SigningFailed(
+                "Invalid typed data signature from JsonRpcSigner".to_string(),
+            ))
+        }
+    }
+
     /// Signs and returns the RLP-encoded transaction.
     async fn sign_transaction(&self, raw_tx: RawTransaction) -> Result<Vec<u8>, SignerError> {
         let msg = JsonRpcRequest::sign_transaction(self.address()?, raw_tx);
@@ -290,7 +316,7 @@ impl JsonRpcSigner {
 mod messages {
     use crate::RawTransaction;
     use hex::encode;
-    use zksync_types::Address;
+    use zksync_types::{tx::Eip712TypedData, Address};
 
     #[derive(Debug, Serialize, Deserialize)]
     pub struct JsonRpcRequest {
@@ -336,6 +362,16 @@ mod messages {
             Self::create("eth_sign", params)
         }
 
+        /// Signs the EIP-712 typed data, which is passed as a JSON string.
+        /// The address to sign with must be unlocked.
+        pub fn sign_typed_data(address: Address, typed_data: &Eip712TypedData) -> Self {
+            let params = vec![
+                serde_json::to_value(address).expect("serialization fail"),
+                serde_json::Value::String(typed_data.json.to_string()),
+            ];
+            Self::create("eth_signTypedData_v4", params)
+        }
+
         /// Signs a transaction that can be submitted to the network.
         /// The address to sign with must be unlocked.
         pub fn sign_transaction(from: Address, tx_data: RawTransaction) -> Self {
@@ -0,0 +1,384 @@
+//! Web3 Secret Storage (version 3) keystores.
+//!
+//! The format is described in the [Web3 Secret Storage Definition](https://github.com/ethereum/wiki/wiki/Web3-Secret-Storage-Definition)
+//! and is the one used by `geth`, `parity` and most of the wallets. The private key is encrypted
+//! with `aes-128-ctr` using a key derived from the password either by `scrypt` or by `pbkdf2`.
+
+use std::{fs, num::NonZeroU32, path::Path};
+
+use parity_crypto::{aes, scrypt, Keccak256};
+use zksync_types::{tx::PackedEthSignature, Address, H256};
+
+use crate::error::SignerError;
+
+/// The only cipher supported by the version 3 of the keystore format.
+const AES_128_CTR: &str = "aes-128-ctr";
+/// The only pseudo-random function supported for the `pbkdf2` key derivation.
+const HMAC_SHA256: &str = "hmac-sha256";
+/// Length of the derived key. The first half of it is used as the cipher key, and the second one for the MAC.
+const DERIVED_KEY_LENGTH: usize = 32;
+
+/// Encrypted keystore in the Web3 Secret Storage format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version: u8,
+    pub id: String,
+    /// Address is optional as per specification, yet most of the tools put it into the file.
+    #[serde(default, with = "hex_address", skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    #[serde(alias = "Crypto")]
+    pub crypto: KeystoreCrypto,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeystoreCrypto {
+    pub cipher: String,
+    pub cipherparams: CipherParams,
+    #[serde(with = "hex_bytes")]
+    pub ciphertext: Vec<u8>,
+    #[serde(flatten)]
+    pub kdf: KdfParams,
+    #[serde(with = "hex_bytes")]
+    pub mac: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CipherParams {
+    #[serde(with = "hex_bytes")]
+    pub iv: Vec<u8>,
+}
+
+/// Parameters of the function used to derive the encryption key from the password.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kdf", content = "kdfparams", rename_all = "lowercase")]
+pub enum KdfParams {
+    Scrypt {
+        dklen: usize,
+        n: u32,
+        r: u32,
+        p: u32,
+        #[serde(with = "hex_bytes")]
+        salt: Vec<u8>,
+    },
+    Pbkdf2 {
+        dklen: usize,
+        c: u32,
+        prf: String,
+        #[serde(with = "hex_bytes")]
+        salt: Vec<u8>,
+    },
+}
+
+impl KdfParams {
+    /// `scrypt` parameters recommended by the specification (and used by `geth` by default).
+    pub fn scrypt() -> Self {
+        Self::Scrypt {
+            dklen: DERIVED_KEY_LENGTH,
+            n: 262_144,
+            r: 8,
+            p: 1,
+            salt: rand::random::<[u8; 32]>().to_vec(),
+        }
+    }
+
+    /// Derives the `(cipher_key, mac_key)` pair from the password.
+    fn derive_keys(&self, password: &str) -> Result<(Vec<u8>, Vec<u8>), SignerError> {
+        match self {
+            Self::Scrypt {
+                dklen,
+                n,
+                r,
+                p,
+                salt,
+            } => {
+                check_dklen(*dklen)?;
+                scrypt::derive_key(password.as_bytes(), salt, *n, *p, *r)
+                    .map_err(|err| SignerError::InvalidKeystore(err.to_string()))
+            }
+            Self::Pbkdf2 {
+                dklen,
+                c,
+                prf,
+                salt,
+            } => {
+                check_dklen(*dklen)?;
+                if prf != HMAC_SHA256 {
+                    return Err(SignerError::InvalidKeystore(format!(
+                        "unsupported pbkdf2 function: {}",
+                        prf
+                    )));
+                }
+                let iterations = NonZeroU32::new(*c).ok_or_else(|| {
+                    SignerError::InvalidKeystore("pbkdf2 iteration count is zero".into())
+                })?;
+                Ok(parity_crypto::derive_key_iterations(
+                    password.as_bytes(),
+                    salt,
+                    iterations,
+                ))
+            }
+        }
+    }
+}
+
+impl Keystore {
+    /// Parses the keystore from its JSON representation.
+    pub fn from_json(json: &str) -> Result<Self, SignerError> {
+        let keystore: Self = serde_json::from_str(json)
+            .map_err(|err| SignerError::InvalidKeystore(err.to_string()))?;
+        if keystore.version != 3 {
+            return Err(SignerError::InvalidKeystore(format!(
+                "unsupported keystore version: {}",
+                keystore.version
+            )));
+        }
+        if keystore.crypto.cipher != AES_128_CTR {
+            return Err(SignerError::InvalidKeystore(format!(
+                "unsupported cipher: {}",
+                keystore.crypto.cipher
+            )));
+        }
+        Ok(keystore)
+    }
+
+    /// Reads the keystore from the file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, SignerError> {
+        let json = fs::read_to_string(path.as_ref()).map_err(|err| {
+            SignerError::InvalidKeystore(format!(
+                "unable to read {}: {}",
+                path.as_ref().display(),
+                err
+            ))
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// Encrypts the private key with the password.
+    pub fn encrypt(private_key: H256, password: &str, kdf: KdfParams) -> Result<Self, SignerError> {
+        let (cipher_key, mac_key) = kdf.derive_keys(password)?;
+        let iv = rand::random::<[u8; 16]>().to_vec();
+
+        let mut ciphertext = vec![0u8; private_key.as_bytes().len()];
+        aes::encrypt_128_ctr(&cipher_key, &iv, private_key.as_bytes(), &mut ciphertext)
+            .map_err(|err| SignerError::CustomError(err.to_string()))?;
+        let mac = derive_mac(&mac_key, &ciphertext);
+
+        Ok(Self {
+            version: 3,
+            id: uuid::Uuid::new_v4().to_string(),
+            address: Some(address_of(&private_key)?),
+            crypto: KeystoreCrypto {
+                cipher: AES_128_CTR.into(),
+                cipherparams: CipherParams { iv },
+                ciphertext,
+                kdf,
+                mac,
+            },
+        })
+    }
+
+    /// Decrypts the private key stored in the keystore.
+    /// If the keystore contains the address, it is checked to match the decrypted key.
+    pub fn decrypt(&self, password: &str) -> Result<H256, SignerError> {
+        let (cipher_key, mac_key) = self.crypto.kdf.derive_keys(password)?;
+        if !parity_crypto::is_equal(
+            &derive_mac(&mac_key, &self.crypto.ciphertext),
+            &self.crypto.mac,
+        ) {
+            return Err(SignerError::UnlockingFailed("invalid password".into()));
+        }
+
+        let mut plain = vec![0u8; self.crypto.ciphertext.len()];
+        aes::decrypt_128_ctr(
+            &cipher_key,
+            &self.crypto.cipherparams.iv,
+            &self.crypto.ciphertext,
+            &mut plain,
+        )
+        .map_err(|err| SignerError::InvalidKeystore(err.to_string()))?;
+        if plain.len() != 32 {
+            return Err(SignerError::InvalidKeystore(format!(
+                "private key has invalid length: {}",
+                plain.len()
+            )));
+        }
+        let private_key = H256::from_slice(&plain);
+
+        if let Some(address) = self.address {
+            if address_of(&private_key)? != address {
+                return Err(SignerError::InvalidKeystore(
+                    "decrypted key doesn't match the keystore address".into(),
+                ));
+            }
+        }
+        Ok(private_key)
+    }
+
+    /// Serializes the keystore into JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("keystore serialization can't fail")
+    }
+}
+
+fn address_of(private_key: &H256) -> Result<Address, SignerError> {
+    PackedEthSignature::address_from_private_key(private_key)
+        .map_err(|_| SignerError::DefineAddress)
+}
+
+fn check_dklen(dklen: usize) -> Result<(), SignerError> {
+    if dklen != DERIVED_KEY_LENGTH {
+        return Err(SignerError::InvalidKeystore(format!(
+            "unsupported derived key length: {}",
+            dklen
+        )));
+    }
+    Ok(())
+}
+
+/// MAC is `keccak256(mac_key ++ ciphertext)`.
+fn derive_mac(mac_key: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(mac_key.len() + ciphertext.len());
+    data.extend_from_slice(mac_key);
+    data.extend_from_slice(ciphertext);
+    data.keccak256().to_vec()
+}
+
+/// Keystores use hex strings without `0x` prefix for binary fields.
+mod hex_bytes {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        hex::decode(string.trim_start_matches("0x")).map_err(D::Error::custom)
+    }
+}
+
+/// Same as `hex_bytes`, but for the optional address field.
+mod hex_address {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use zksync_types::Address;
+
+    pub fn serialize<S: Serializer>(
+        address: &Option<Address>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match address {
+            Some(address) => super::hex_bytes::serialize(address.as_bytes(), serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Address>, D::Error> {
+        let bytes = super::hex_bytes::deserialize(deserializer)?;
+        if bytes.len() != Address::len_bytes() {
+            return Err(D::Error::custom(format!(
+                "invalid address length: {}",
+                bytes.len()
+            )));
+        }
+        Ok(Some(Address::from_slice(&bytes)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const PASSWORD: &str = "testpassword";
+    const PRIVATE_KEY: &str = "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d";
+
+    /// Test vector from the Web3 Secret Storage specification.
+    const PBKDF2_KEYSTORE: &str = r#"{
+        "crypto" : {
+            "cipher" : "aes-128-ctr",
+            "cipherparams" : {
+                "iv" : "6087dab2f9fdbbfaddc31a909735c1e6"
+            },
+            "ciphertext" : "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+            "kdf" : "pbkdf2",
+            "kdfparams" : {
+                "c" : 262144,
+                "dklen" : 32,
+                "prf" : "hmac-sha256",
+                "salt" : "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+            },
+            "mac" : "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+        },
+        "id" : "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version" : 3
+    }"#;
+
+    /// Same key encrypted with the `scrypt` parameters lowered to keep the test fast.
+    const SCRYPT_KEYSTORE: &str = r#"{
+        "address" : "008aeeda4d805471df9b2a5b0f38a0c3bcba786b",
+        "Crypto" : {
+            "cipher" : "aes-128-ctr",
+            "cipherparams" : {
+                "iv" : "83dbcc02d8ccb40e466191a123791e0e"
+            },
+            "ciphertext" : "01a05c7f05b697274227d8bd0825a6caa89967e24643426c0fcfa2fb663052d7",
+            "kdf" : "scrypt",
+            "kdfparams" : {
+                "dklen" : 32,
+                "n" : 1024,
+                "r" : 8,
+                "p" : 1,
+                "salt" : "ab0c7876052600dd703518d6fc3fe8984592145b591fc8fb5c6d43190334ba19"
+            },
+            "mac" : "d60a6540bbdeaa746e4c7b4359c74e4bb0b679bedce5b4d129ad96150d200274"
+        },
+        "id" : "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version" : 3
+    }"#;
+
+    #[test]
+    fn decrypt_pbkdf2() {
+        let keystore = Keystore::from_json(PBKDF2_KEYSTORE).unwrap();
+        let private_key = keystore.decrypt(PASSWORD).unwrap();
+        assert_eq!(private_key, H256::from_str(PRIVATE_KEY).unwrap());
+    }
+
+    #[test]
+    fn decrypt_scrypt() {
+        let keystore = Keystore::from_json(SCRYPT_KEYSTORE).unwrap();
+        let private_key = keystore.decrypt(PASSWORD).unwrap();
+        assert_eq!(private_key, H256::from_str(PRIVATE_KEY).unwrap());
+    }
+
+    #[test]
+    fn wrong_password() {
+        let keystore = Keystore::from_json(SCRYPT_KEYSTORE).unwrap();
+        assert_eq!(
+            keystore.decrypt("wrongpassword").unwrap_err(),
+            SignerError::UnlockingFailed("invalid password".into())
+        );
+    }
+
+    #[test]
+    fn encrypt_roundtrip() {
+        let private_key = H256::from_str(PRIVATE_KEY).unwrap();
+        let kdf = KdfParams::Scrypt {
+            dklen: 32,
+            n: 1024,
+            r: 8,
+            p: 1,
+            salt: vec![1; 32],
+        };
+        let keystore = Keystore::encrypt(private_key, PASSWORD, kdf).unwrap();
+        assert_eq!(
+            keystore.address,
+            Some(Address::from_str("008aeeda4d805471df9b2a5b0f38a0c3bcba786b").unwrap())
+        );
+
+        let restored = Keystore::from_json(&keystore.to_json()).unwrap();
+        assert_eq!(restored, keystore);
+        assert_eq!(restored.decrypt(PASSWORD).unwrap(), private_key);
+    }
+}
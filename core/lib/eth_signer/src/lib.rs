@@ -3,21 +3,39 @@ extern crate serde_derive;
 
 use async_trait::async_trait;
 use error::SignerError;
-use zksync_types::tx::TxEthSignature;
+use zksync_types::tx::{Eip712TypedData, TxEthSignature};
 use zksync_types::Address;
 
 pub use json_rpc_signer::JsonRpcSigner;
+pub use keystore::Keystore;
+pub use mnemonic::DerivationPath;
+pub use operator_signer::OperatorSigner;
 pub use pk_signer::PrivateKeySigner;
 pub use raw_ethereum_tx::RawTransaction;
+pub use remote_signer::RemoteSigner;
 
 pub mod error;
 pub mod json_rpc_signer;
+pub mod keystore;
+pub mod mnemonic;
+pub mod operator_signer;
 pub mod pk_signer;
 pub mod raw_ethereum_tx;
+pub mod remote_signer;
 
 #[async_trait]
 pub trait EthereumSigner: Send + Sync + Clone {
     async fn sign_message(&self, message: &[u8]) -> Result<TxEthSignature, SignerError>;
+    /// Signs the EIP-712 typed data. Signers which can't sign typed data return an error,
+    /// so the caller can fall back to the plain message signature.
+    async fn sign_typed_data(
+        &self,
+        _typed_data: &Eip712TypedData,
+    ) -> Result<TxEthSignature, SignerError> {
+        Err(SignerError::SigningFailed(
+            "Signer doesn't support EIP-712 typed data".to_owned(),
+        ))
+    }
     async fn sign_transaction(&self, raw_tx: RawTransaction) -> Result<Vec<u8>, SignerError>;
     async fn get_address(&self) -> Result<Address, SignerError>;
 }
@@ -0,0 +1,230 @@
+//! BIP-39 mnemonic phrases and BIP-32 hierarchical key derivation.
+//!
+//! Allows to derive Ethereum private keys the same way hardware wallets and `MetaMask` do, e.g. the
+//! first account of the wallet uses the `m/44'/60'/0'/0/0` derivation path.
+
+use std::{fmt, str::FromStr};
+
+use hmac::{Hmac, Mac, NewMac};
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use sha2::Sha512;
+use unicode_normalization::UnicodeNormalization;
+use zksync_types::H256;
+
+use crate::error::SignerError;
+
+/// Indices starting from this one denote hardened derivation.
+const HARDENED_OFFSET: u32 = 1 << 31;
+/// Key of the HMAC used to derive the master key from the seed.
+const MASTER_KEY_SALT: &[u8] = b"Bitcoin seed";
+
+/// BIP-32 derivation path, e.g. `m/44'/60'/0'/0/0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// Path used for Ethereum accounts by the most of the wallets: `m/44'/60'/0'/0/{account_index}`.
+    pub fn ethereum(account_index: u32) -> Self {
+        Self(vec![
+            44 + HARDENED_OFFSET,
+            60 + HARDENED_OFFSET,
+            HARDENED_OFFSET,
+            0,
+            account_index,
+        ])
+    }
+
+    /// Creates the path from the raw child indices.
+    /// Indices greater or equal to `2^31` denote hardened derivation.
+    pub fn from_indices(indices: Vec<u32>) -> Self {
+        Self(indices)
+    }
+
+    pub fn indices(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+impl Default for DerivationPath {
+    fn default() -> Self {
+        Self::ethereum(0)
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = SignerError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        let invalid = || SignerError::InvalidMnemonic(format!("invalid derivation path: {}", path));
+
+        let mut parts = path.trim().split('/');
+        if parts.next() != Some("m") {
+            return Err(invalid());
+        }
+        parts
+            .map(|part| {
+                let (index, hardened) = match part.strip_suffix('\'') {
+                    Some(index) => (index, true),
+                    None => (part, false),
+                };
+                let index: u32 = index.parse().map_err(|_| invalid())?;
+                if index >= HARDENED_OFFSET {
+                    return Err(invalid());
+                }
+                Ok(if hardened {
+                    index + HARDENED_OFFSET
+                } else {
+                    index
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            if *index >= HARDENED_OFFSET {
+                write!(f, "/{}'", index - HARDENED_OFFSET)?;
+            } else {
+                write!(f, "/{}", index)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Validates the BIP-39 mnemonic phrase (including its checksum) and converts it into the seed.
+///
+/// Both the phrase and the passphrase are NFKD-normalized first, as required by BIP-39.
+pub fn seed_from_mnemonic(phrase: &str, passphrase: &str) -> Result<[u8; 64], SignerError> {
+    let phrase: String = phrase.nfkd().collect();
+    let passphrase: String = passphrase.nfkd().collect();
+    let mnemonic = bip39::Mnemonic::parse_normalized(&phrase)
+        .map_err(|err| SignerError::InvalidMnemonic(err.to_string()))?;
+    Ok(mnemonic.to_seed_normalized(&passphrase))
+}
+
+/// Derives the private key from the seed according to BIP-32.
+pub fn derive_private_key(seed: &[u8], path: &DerivationPath) -> Result<H256, SignerError> {
+    let secp = Secp256k1::signing_only();
+    let derivation_failed = || {
+        SignerError::InvalidMnemonic(format!(
+            "unable to derive the key for the path {}, try the next index",
+            path
+        ))
+    };
+
+    let (mut key, mut chain_code) =
+        split_key(&hmac_sha512(MASTER_KEY_SALT, seed)).ok_or_else(derivation_failed)?;
+    for &index in path.indices() {
+        let mut data = Vec::with_capacity(37);
+        if index >= HARDENED_OFFSET {
+            data.push(0);
+            data.extend_from_slice(&key[..]);
+        } else {
+            data.extend_from_slice(&PublicKey::from_secret_key(&secp, &key).serialize());
+        }
+        data.extend_from_slice(&index.to_be_bytes());
+
+        let (tweak, child_chain_code) =
+            split_key(&hmac_sha512(&chain_code, &data)).ok_or_else(derivation_failed)?;
+        // Child key is `parse256(IL) + parent_key (mod n)`.
+        key.add_assign(&tweak[..])
+            .map_err(|_| derivation_failed())?;
+        chain_code = child_chain_code;
+    }
+
+    Ok(H256::from_slice(&key[..]))
+}
+
+/// Derives the private key from the BIP-39 mnemonic phrase and BIP-32 derivation path.
+pub fn private_key_from_mnemonic(
+    phrase: &str,
+    passphrase: &str,
+    path: &DerivationPath,
+) -> Result<H256, SignerError> {
+    let seed = seed_from_mnemonic(phrase, passphrase)?;
+    derive_private_key(&seed, path)
+}
+
+fn hmac_sha512(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Splits the HMAC output into the key and the chain code.
+/// Returns `None` if the key is not a valid secp256k1 scalar.
+fn split_key(output: &[u8]) -> Option<(SecretKey, [u8; 32])> {
+    let key = SecretKey::from_slice(&output[..32]).ok()?;
+    let mut chain_code = [0u8; 32];
+    chain_code.copy_from_slice(&output[32..]);
+    Some((key, chain_code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::tx::PackedEthSignature;
+    use zksync_types::Address;
+
+    const PHRASE: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn derivation_path_parsing() {
+        let path: DerivationPath = "m/44'/60'/0'/0/0".parse().unwrap();
+        assert_eq!(path, DerivationPath::ethereum(0));
+        assert_eq!(path.to_string(), "m/44'/60'/0'/0/0");
+
+        for invalid in &["", "44'/60'", "m/44'/x", "m/2147483648", "m//0"] {
+            assert!(invalid.parse::<DerivationPath>().is_err(), "{}", invalid);
+        }
+    }
+
+    /// BIP-39 test vector from the reference implementation.
+    #[test]
+    fn mnemonic_seed() {
+        let seed = seed_from_mnemonic(PHRASE, "TREZOR").unwrap();
+        assert_eq!(
+            hex::encode(&seed[..]),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+
+        // Last word breaks the checksum.
+        let invalid = PHRASE.replace("about", "abandon");
+        assert!(matches!(
+            seed_from_mnemonic(&invalid, ""),
+            Err(SignerError::InvalidMnemonic(_))
+        ));
+    }
+
+    /// Keys are checked against the ones produced by the popular wallets for the same mnemonic.
+    #[test]
+    fn ethereum_keys() {
+        let expected = [
+            (
+                "1ab42cc412b618bdea3a599e3c9bae199ebf030895b039e9db1e30dafb12b727",
+                "9858effd232b4033e47d90003d41ec34ecaeda94",
+            ),
+            (
+                "9a983cb3d832fbde5ab49d692b7a8bf5b5d232479c99333d0fc8e1d21f1b55b6",
+                "6fac4d18c912343bf86fa7049364dd4e424ab9c0",
+            ),
+        ];
+
+        for (index, (private_key, address)) in expected.iter().enumerate() {
+            let derived =
+                private_key_from_mnemonic(PHRASE, "", &DerivationPath::ethereum(index as u32))
+                    .unwrap();
+            assert_eq!(derived, private_key.parse::<H256>().unwrap());
+            assert_eq!(
+                PackedEthSignature::address_from_private_key(&derived).unwrap(),
+                address.parse::<Address>().unwrap()
+            );
+        }
+    }
+}
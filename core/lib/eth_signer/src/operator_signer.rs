@@ -0,0 +1,56 @@
+use crate::error::SignerError;
+use crate::{EthereumSigner, PrivateKeySigner, RawTransaction, RemoteSigner};
+
+use zksync_types::tx::{Eip712TypedData, TxEthSignature};
+use zksync_types::Address;
+
+/// Signer of the operator account: either the key is held by the server itself,
+/// or the transactions are signed by the remote signing daemon.
+#[derive(Debug, Clone)]
+pub enum OperatorSigner {
+    Local(PrivateKeySigner),
+    Remote(RemoteSigner),
+}
+
+impl From<PrivateKeySigner> for OperatorSigner {
+    fn from(signer: PrivateKeySigner) -> Self {
+        Self::Local(signer)
+    }
+}
+
+impl From<RemoteSigner> for OperatorSigner {
+    fn from(signer: RemoteSigner) -> Self {
+        Self::Remote(signer)
+    }
+}
+
+macro_rules! delegate_call {
+    ($self:ident.$method:ident($($args:ident),*)) => {
+        match $self {
+            Self::Local(signer) => signer.$method($($args),*).await,
+            Self::Remote(signer) => signer.$method($($args),*).await,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EthereumSigner for OperatorSigner {
+    async fn sign_message(&self, message: &[u8]) -> Result<TxEthSignature, SignerError> {
+        delegate_call!(self.sign_message(message))
+    }
+
+    async fn sign_typed_data(
+        &self,
+        typed_data: &Eip712TypedData,
+    ) -> Result<TxEthSignature, SignerError> {
+        delegate_call!(self.sign_typed_data(typed_data))
+    }
+
+    async fn sign_transaction(&self, raw_tx: RawTransaction) -> Result<Vec<u8>, SignerError> {
+        delegate_call!(self.sign_transaction(raw_tx))
+    }
+
+    async fn get_address(&self) -> Result<Address, SignerError> {
+        delegate_call!(self.get_address())
+    }
+}
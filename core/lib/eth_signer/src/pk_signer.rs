@@ -1,9 +1,11 @@
+use crate::keystore::Keystore;
+use crate::mnemonic::{private_key_from_mnemonic, DerivationPath};
 use crate::raw_ethereum_tx::RawTransaction;
 use crate::{EthereumSigner, SignerError};
 
 use parity_crypto::publickey::sign;
 
-use zksync_types::tx::{PackedEthSignature, TxEthSignature};
+use zksync_types::tx::{Eip712TypedData, PackedEthSignature, TxEthSignature};
 use zksync_types::{Address, H256};
 
 #[derive(Clone)]
@@ -21,6 +23,20 @@ impl PrivateKeySigner {
     pub fn new(private_key: H256) -> Self {
         Self { private_key }
     }
+
+    /// Creates the signer from the private key stored in the Web3 Secret Storage keystore.
+    pub fn from_keystore(keystore: &Keystore, password: &str) -> Result<Self, SignerError> {
+        keystore.decrypt(password).map(Self::new)
+    }
+
+    /// Creates the signer from the private key derived from the BIP-39 mnemonic phrase.
+    pub fn from_mnemonic(
+        phrase: &str,
+        passphrase: &str,
+        path: &DerivationPath,
+    ) -> Result<Self, SignerError> {
+        private_key_from_mnemonic(phrase, passphrase, path).map(Self::new)
+    }
 }
 
 #[async_trait::async_trait]
@@ -39,11 +55,21 @@ impl EthereumSigner for PrivateKeySigner {
         Ok(TxEthSignature::EthereumSignature(pack))
     }
 
+    /// Signs the EIP-712 typed data: sign(keccak256("\x19\x01" + domainSeparator + hashStruct(message))).
+    async fn sign_typed_data(
+        &self,
+        typed_data: &Eip712TypedData,
+    ) -> Result<TxEthSignature, SignerError> {
+        let pack = PackedEthSignature::sign_raw(&self.private_key, &typed_data.signing_hash)
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+        Ok(TxEthSignature::EIP712Signature(pack))
+    }
+
     /// Signs and returns the RLP-encoded transaction.
     async fn sign_transaction(&self, raw_tx: RawTransaction) -> Result<Vec<u8>, SignerError> {
         let sig = sign(&self.private_key.into(), &raw_tx.hash().into())
             .map_err(|_| SignerError::NoSigningKey)?;
-        Ok(raw_tx.rlp_encode_tx(sig))
+        raw_tx.rlp_encode_tx(sig)
     }
 }
 
@@ -52,6 +78,7 @@ mod test {
     use super::PrivateKeySigner;
     use super::RawTransaction;
     use crate::EthereumSigner;
+    use parity_crypto::publickey::{public_to_address, recover, Signature};
     use zksync_types::{H160, H256, U256};
 
     #[tokio::test]
@@ -82,4 +109,59 @@ mod test {
         ];
         assert_eq!(signature, precalculated_signature);
     }
+
+    #[tokio::test]
+    async fn test_signature_with_wide_chain_id() {
+        let private_key = H256::from([5; 32]);
+        let signer = PrivateKeySigner::new(private_key);
+        let chain_id: u64 = 1_337_000_000_000;
+        let raw_transaction = RawTransaction {
+            chain_id,
+            nonce: U256::from(1),
+            to: Some(H160::zero()),
+            value: U256::from(10),
+            gas_price: U256::from(1),
+            gas: U256::from(2),
+            data: vec![1, 2, 3],
+        };
+        let signed = signer
+            .sign_transaction(raw_transaction.clone())
+            .await
+            .unwrap();
+
+        // `v` must be encoded as a full-width integer: `recovery_id + chain_id * 2 + 35`.
+        let rlp = rlp::Rlp::new(&signed);
+        let v: u64 = rlp.val_at(6).unwrap();
+        let recovery_id = v - chain_id * 2 - 35;
+        assert!(recovery_id <= 1);
+
+        let r: U256 = rlp.val_at(7).unwrap();
+        let s: U256 = rlp.val_at(8).unwrap();
+        let mut r_bytes = [0u8; 32];
+        let mut s_bytes = [0u8; 32];
+        r.to_big_endian(&mut r_bytes);
+        s.to_big_endian(&mut s_bytes);
+        let signature = Signature::from_rsv(&r_bytes.into(), &s_bytes.into(), recovery_id as u8);
+        let public = recover(&signature, &raw_transaction.hash().into()).unwrap();
+        assert_eq!(
+            public_to_address(&public).as_bytes(),
+            signer.get_address().await.unwrap().as_bytes()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_signature_with_overflowing_chain_id() {
+        let signer = PrivateKeySigner::new(H256::from([5; 32]));
+        let raw_transaction = RawTransaction {
+            chain_id: u64::MAX / 2,
+            nonce: U256::from(1),
+            to: Some(H160::zero()),
+            value: U256::from(10),
+            gas_price: U256::from(1),
+            gas: U256::from(2),
+            data: vec![1, 2, 3],
+        };
+        // `v` doesn't fit into `u64`, the transaction can't be signed.
+        assert!(signer.sign_transaction(raw_transaction).await.is_err());
+    }
 }
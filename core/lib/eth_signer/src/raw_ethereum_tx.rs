@@ -3,11 +3,13 @@ use rlp::RlpStream;
 use serde::{Deserialize, Serialize};
 use zksync_types::{H160, U256};
 
+use crate::error::SignerError;
+
 /// Description of a Transaction, pending or in the chain.
 #[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
 pub struct RawTransaction {
     /// Chain id: mainnet => 1, rinkeby => 4, ropsten => 43, etc.
-    pub chain_id: u8,
+    pub chain_id: u64,
     /// Nonce
     pub nonce: U256,
     /// Recipient (None when contract creation)
@@ -37,8 +39,8 @@ fn find_first_nonzero(vector: &[u8]) -> usize {
 }
 
 impl RawTransaction {
-    pub fn rlp_encode_tx(&self, sig: Signature) -> Vec<u8> {
-        let signature = to_ecdsa(sig, self.chain_id);
+    pub fn rlp_encode_tx(&self, sig: Signature) -> Result<Vec<u8>, SignerError> {
+        let signature = to_ecdsa(sig, self.chain_id)?;
         let mut tx = RlpStream::new();
         tx.begin_unbounded_list();
         self.encode(&mut tx);
@@ -50,14 +52,14 @@ impl RawTransaction {
         let s = &signature.s[s_start..];
         tx.append(&s);
         tx.finalize_unbounded_list();
-        tx.out().to_vec()
+        Ok(tx.out().to_vec())
     }
 
     pub fn hash(&self) -> [u8; 32] {
         let mut hash = RlpStream::new();
         hash.begin_unbounded_list();
         self.encode(&mut hash);
-        hash.append(&vec![self.chain_id]);
+        hash.append(&self.chain_id);
         hash.append(&U256::zero());
         hash.append(&U256::zero());
         hash.finalize_unbounded_list();
@@ -78,16 +80,24 @@ impl RawTransaction {
     }
 }
 
-fn to_ecdsa(sig: Signature, chain_id: u8) -> EcdsaSig {
-    EcdsaSig {
-        v: vec![sig.v() as u8 + chain_id * 2 + 35],
+/// Converts the signature into the EIP-155 form, where `v` is `recovery_id + chain_id * 2 + 35`.
+/// For large chain ids `v` doesn't fit into a single byte, so it's encoded as an RLP integer.
+fn to_ecdsa(sig: Signature, chain_id: u64) -> Result<EcdsaSig, SignerError> {
+    let v = chain_id
+        .checked_mul(2)
+        .and_then(|v| v.checked_add(35 + sig.v() as u64))
+        .ok_or_else(|| {
+            SignerError::SigningFailed(format!("Chain id {} is too big for EIP-155", chain_id))
+        })?;
+    Ok(EcdsaSig {
+        v,
         r: sig.r().to_vec(),
         s: sig.s().to_vec(),
-    }
+    })
 }
 
 pub struct EcdsaSig {
-    v: Vec<u8>,
+    v: u64,
     r: Vec<u8>,
     s: Vec<u8>,
 }
@@ -0,0 +1,164 @@
+//! Signer delegating the operator transactions to the remote signing daemon (`zksync_remote_signer`).
+//!
+//! Unlike `JsonRpcSigner`, the daemon doesn't sign arbitrary data: every transaction is checked
+//! against the signing policy (allowed contract and methods, gas price and nonce ceilings)
+//! and is written to the audit log, so the host running the server never has access to the key.
+//! Every request is authenticated with the bearer token shared with the daemon.
+
+use crate::error::SignerError;
+use crate::remote_signer::messages::{
+    AddressResponse, RejectionResponse, SignTransactionRequest, SignTransactionResponse,
+};
+use crate::{EthereumSigner, RawTransaction};
+
+use zksync_types::tx::{Eip712TypedData, TxEthSignature};
+use zksync_types::Address;
+
+#[derive(Clone)]
+pub struct RemoteSigner {
+    url: String,
+    client: reqwest::Client,
+    address: Address,
+    auth_token: String,
+}
+
+impl std::fmt::Debug for RemoteSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteSigner")
+            .field("url", &self.url)
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+impl RemoteSigner {
+    /// Creates a signer for the daemon managing the key of the given address.
+    pub fn new(url: impl Into<String>, address: Address, auth_token: impl Into<String>) -> Self {
+        Self {
+            url: url.into().trim_end_matches('/').to_owned(),
+            client: reqwest::Client::new(),
+            address,
+            auth_token: auth_token.into(),
+        }
+    }
+
+    /// Creates a signer and checks that the daemon actually manages the key of the given address.
+    pub async fn connect(
+        url: impl Into<String>,
+        address: Address,
+        auth_token: impl Into<String>,
+    ) -> Result<Self, SignerError> {
+        let signer = Self::new(url, address, auth_token);
+        let response: AddressResponse = signer
+            .client
+            .get(&format!("{}/address", signer.url))
+            .bearer_auth(&signer.auth_token)
+            .send()
+            .await
+            .map_err(|err| SignerError::CustomError(err.to_string()))?
+            .error_for_status()
+            .map_err(|err| SignerError::CustomError(err.to_string()))?
+            .json()
+            .await
+            .map_err(|err| SignerError::CustomError(err.to_string()))?;
+
+        if response.address != address {
+            return Err(SignerError::CustomError(format!(
+                "Remote signer manages the key of {:?}, expected {:?}",
+                response.address, address
+            )));
+        }
+        Ok(signer)
+    }
+}
+
+#[async_trait::async_trait]
+impl EthereumSigner for RemoteSigner {
+    /// Messages are never signed by the daemon, since the operator doesn't need it.
+    async fn sign_message(&self, _message: &[u8]) -> Result<TxEthSignature, SignerError> {
+        Err(SignerError::SigningFailed(
+            "Remote signer only signs operator transactions".into(),
+        ))
+    }
+
+    /// Typed data is never signed by the daemon, since the operator doesn't need it.
+    async fn sign_typed_data(
+        &self,
+        _typed_data: &Eip712TypedData,
+    ) -> Result<TxEthSignature, SignerError> {
+        Err(SignerError::SigningFailed(
+            "Remote signer only signs operator transactions".into(),
+        ))
+    }
+
+    /// Sends the transaction to the daemon and returns the RLP-encoded signed transaction.
+    /// Transactions violating the daemon policy are rejected with the `SigningFailed` error.
+    async fn sign_transaction(&self, raw_tx: RawTransaction) -> Result<Vec<u8>, SignerError> {
+        let response = self
+            .client
+            .post(&format!("{}/sign_transaction", self.url))
+            .bearer_auth(&self.auth_token)
+            .json(&SignTransactionRequest { tx: raw_tx })
+            .send()
+            .await
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+
+        match response.status() {
+            reqwest::StatusCode::OK => {
+                let response: SignTransactionResponse = response
+                    .json()
+                    .await
+                    .map_err(|err| SignerError::DecodeRawTxFailed(err.to_string()))?;
+                Ok(response.signed_tx)
+            }
+            reqwest::StatusCode::FORBIDDEN => {
+                let rejection: RejectionResponse = response
+                    .json()
+                    .await
+                    .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+                Err(SignerError::SigningFailed(format!(
+                    "Rejected by the remote signer: {}",
+                    rejection.reason
+                )))
+            }
+            status => Err(SignerError::SigningFailed(format!(
+                "Remote signer responded with a non-OK response: {}",
+                status
+            ))),
+        }
+    }
+
+    async fn get_address(&self) -> Result<Address, SignerError> {
+        Ok(self.address)
+    }
+}
+
+/// Messages of the remote signer HTTP API.
+pub mod messages {
+    use crate::RawTransaction;
+    use zksync_types::Address;
+
+    /// Response of the `GET /address` endpoint.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct AddressResponse {
+        pub address: Address,
+    }
+
+    /// Body of the `POST /sign_transaction` request.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct SignTransactionRequest {
+        pub tx: RawTransaction,
+    }
+
+    /// Successful response of the `POST /sign_transaction` endpoint.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct SignTransactionResponse {
+        pub signed_tx: Vec<u8>,
+    }
+
+    /// Response sent with the `403 Forbidden` status when the transaction violates the policy.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RejectionResponse {
+        pub reason: String,
+    }
+}
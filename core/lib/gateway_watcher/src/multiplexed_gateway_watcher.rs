@@ -115,8 +115,8 @@ impl MultiplexedGatewayWatcher {
         }
     }
 
-    /// Checks multiplexed client gateways and prioritizes one with longest chain,
-    /// most frequent hash and lowest latency.
+    /// Checks multiplexed client gateways and updates their health in the multiplexed client:
+    /// the head lag is counted from the gateway with longest chain, most frequent hash and lowest latency.
     async fn check_client_gateways(&self) {
         // Fetch latest block for each client.
         // Each request will resolve to (client key, client latest block) pair.
@@ -140,6 +140,7 @@ impl MultiplexedGatewayWatcher {
                     if let Ok(block) = block_fut.await {
                         let req_time = start.elapsed();
                         metrics::histogram!("eth_client.multiplexed.block", req_time, &[("address", key.to_owned())]);
+                        self.client.record_request(key, Ok(()), start);
 
                         Some((key, block, req_time))
                     } else {
@@ -147,6 +148,11 @@ impl MultiplexedGatewayWatcher {
                             "Failed to get latest block from Ethereum Gateway `{}` within specified timeout",
                             key
                         );
+                        self.client.record_request(
+                            key,
+                            Err(anyhow::format_err!("failed to get latest block")),
+                            start,
+                        );
                         None
                     }
                 })
@@ -188,13 +194,18 @@ impl MultiplexedGatewayWatcher {
                     }
                 });
 
-        if let Some((preferred_client_key, latest_block, _)) = preferred_client {
-            if self.client.prioritize_client(preferred_client_key) {
-                vlog::info!("Prioritized Ethereum Gateway: `{}`", preferred_client_key);
-            }
+        // Gateways lagging behind or following another chain are penalized in the multiplexed client.
+        if let Some((_, latest_block, _)) = preferred_client {
+            let latest_number = latest_block.number.unwrap_or_default();
             for (key, block, _) in &client_latest_blocks {
+                let head_lag = latest_number.saturating_sub(block.number.unwrap_or_default());
+                self.client.set_head_lag(key, head_lag.as_u64());
                 if let Err(err) = Self::verify_blocks(latest_block, block) {
                     vlog::error!("Ethereum Gateway `{}` - check failed: {}", key, err);
+                    if let BlockVerificationError::IncorrectHash(..) = err {
+                        self.client
+                            .record_request(key, Err(err.into()), Instant::now());
+                    }
                 }
             }
         }
@@ -0,0 +1,184 @@
+//! Verifies the stored single and aggregated proofs against the commitments of the blocks in the database.
+//! Verification keys are read from the `key_dir`, so they must be the same as the ones used by the prover.
+//! Exits with an error if any of the checked proofs is invalid.
+
+use structopt::StructOpt;
+use zksync_config::configs::ChainConfig;
+use zksync_prover_utils::verification::{verify, ProofToVerify, ProvenBlock, VerificationKeys};
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::BlockNumber;
+
+#[derive(StructOpt)]
+#[structopt(
+    name = "zkSync proof verifier",
+    author = "Matter Labs",
+    rename_all = "snake_case"
+)]
+struct Opt {
+    /// First block to check the proofs for
+    #[structopt(long, default_value = "1")]
+    from_block: u32,
+
+    /// Last block to check the proofs for, by default the proofs of all the blocks are checked
+    #[structopt(long)]
+    to_block: Option<u32>,
+
+    /// Don't check the proofs of the single blocks
+    #[structopt(long)]
+    skip_single: bool,
+
+    /// Don't check the aggregated proofs
+    #[structopt(long)]
+    skip_aggregated: bool,
+}
+
+impl Opt {
+    fn contains(&self, block_number: BlockNumber) -> bool {
+        *block_number >= self.from_block
+            && self
+                .to_block
+                .map_or(true, |to_block| *block_number <= to_block)
+    }
+}
+
+async fn load_block(
+    storage: &mut StorageProcessor<'_>,
+    block_number: BlockNumber,
+) -> anyhow::Result<ProvenBlock> {
+    let block = storage
+        .chain()
+        .block_schema()
+        .get_block(block_number)
+        .await?
+        .ok_or_else(|| anyhow::format_err!("Block {} is not stored", block_number))?;
+    Ok(ProvenBlock::from(&block))
+}
+
+async fn load_blocks(
+    storage: &mut StorageProcessor<'_>,
+    first_block: BlockNumber,
+    last_block: BlockNumber,
+) -> anyhow::Result<Vec<ProvenBlock>> {
+    let mut blocks = Vec::new();
+    for block_number in *first_block..=*last_block {
+        blocks.push(load_block(storage, BlockNumber(block_number)).await?);
+    }
+    Ok(blocks)
+}
+
+async fn verify_single_proofs(
+    storage: &mut StorageProcessor<'_>,
+    keys: &VerificationKeys,
+    opt: &Opt,
+) -> anyhow::Result<(usize, usize)> {
+    let mut checked = 0;
+    let mut failed = 0;
+    for block_number in storage.prover_schema().get_proof_blocks().await? {
+        if !opt.contains(block_number) {
+            continue;
+        }
+        let proof = storage
+            .prover_schema()
+            .load_proof(block_number)
+            .await?
+            .ok_or_else(|| anyhow::format_err!("Proof of block {} is not stored", block_number))?;
+        let result = load_block(storage, block_number)
+            .await
+            .and_then(|block| verify(keys, ProofToVerify::Single(&proof), &[block]));
+
+        checked += 1;
+        match result {
+            Ok(()) => vlog::info!("Proof of block {} is valid", block_number),
+            Err(err) => {
+                failed += 1;
+                vlog::error!("Proof of block {} is not valid: {}", block_number, err);
+            }
+        }
+    }
+    Ok((checked, failed))
+}
+
+async fn verify_aggregated_proofs(
+    storage: &mut StorageProcessor<'_>,
+    keys: &VerificationKeys,
+    opt: &Opt,
+) -> anyhow::Result<(usize, usize)> {
+    let mut checked = 0;
+    let mut failed = 0;
+    for (first_block, last_block) in storage
+        .prover_schema()
+        .get_aggregated_proof_ranges()
+        .await?
+    {
+        if !opt.contains(first_block) || !opt.contains(last_block) {
+            continue;
+        }
+        let proof = storage
+            .prover_schema()
+            .load_aggregated_proof(first_block, last_block)
+            .await?
+            .ok_or_else(|| {
+                anyhow::format_err!(
+                    "Aggregated proof of blocks {}-{} is not stored",
+                    first_block,
+                    last_block
+                )
+            })?;
+        let result = load_blocks(storage, first_block, last_block)
+            .await
+            .and_then(|blocks| verify(keys, ProofToVerify::Aggregated(&proof), &blocks));
+
+        checked += 1;
+        match result {
+            Ok(()) => vlog::info!(
+                "Aggregated proof of blocks {}-{} is valid",
+                first_block,
+                last_block
+            ),
+            Err(err) => {
+                failed += 1;
+                vlog::error!(
+                    "Aggregated proof of blocks {}-{} is not valid: {}",
+                    first_block,
+                    last_block,
+                    err
+                );
+            }
+        }
+    }
+    Ok((checked, failed))
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    vlog::init();
+
+    let opt = Opt::from_args();
+    let config = ChainConfig::from_env();
+
+    let keys = VerificationKeys::load(
+        &config.circuit.supported_block_chunks_sizes,
+        &config.circuit.supported_aggregated_proof_sizes,
+    )?;
+    let connection_pool = ConnectionPool::new(Some(1));
+    let mut storage = connection_pool.access_storage().await?;
+
+    let mut checked = 0;
+    let mut failed = 0;
+    if !opt.skip_single {
+        let (single_checked, single_failed) =
+            verify_single_proofs(&mut storage, &keys, &opt).await?;
+        checked += single_checked;
+        failed += single_failed;
+    }
+    if !opt.skip_aggregated {
+        let (aggregated_checked, aggregated_failed) =
+            verify_aggregated_proofs(&mut storage, &keys, &opt).await?;
+        checked += aggregated_checked;
+        failed += aggregated_failed;
+    }
+
+    vlog::info!("Checked {} proofs, {} are not valid", checked, failed);
+    anyhow::ensure!(failed == 0, "{} proofs are not valid", failed);
+    Ok(())
+}
@@ -54,6 +54,18 @@ pub struct WorkingOn {
     pub job_id: i32,
 }
 
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProverJobFailed {
+    pub prover_name: String,
+    pub job_id: i32,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RequeueProverJob {
+    pub job_id: i32,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ProverStopped {
     pub prover_name: String,
@@ -30,6 +30,7 @@ pub mod api;
 pub mod exit_proof;
 pub mod fs_utils;
 pub mod network_utils;
+pub mod verification;
 
 pub const SETUP_MIN_POW2: u32 = 20;
 pub const SETUP_MAX_POW2: u32 = 26;
@@ -60,9 +61,17 @@ impl PlonkVerificationKey {
                     .0
             })
             .collect::<Vec<_>>();
-        let (_, (vk_tree, _)) = create_vks_tree(&block_vks, RECURSIVE_CIRCUIT_VK_TREE_DEPTH)
-            .expect("Failed to create vk tree");
-        vk_tree.get_commitment()
+        Self::vk_tree_root_hash(&block_vks).expect("Failed to create vk tree")
+    }
+
+    /// Returns the root of the tree of the main circuit verification keys, which is used
+    /// by the recursive circuit and the verifier contract.
+    pub fn vk_tree_root_hash(
+        block_vks: &[VerificationKey<Engine, PlonkCsWidth4WithNextStepParams>],
+    ) -> Result<Fr, anyhow::Error> {
+        let (_, (vk_tree, _)) = create_vks_tree(block_vks, RECURSIVE_CIRCUIT_VK_TREE_DEPTH)
+            .map_err(|err| anyhow::format_err!("Failed to create vk tree: {:?}", err))?;
+        Ok(vk_tree.get_commitment())
     }
 }
 
@@ -0,0 +1,543 @@
+//! Verification of the stored proofs against the blocks they were generated for.
+//!
+//! Performs the same checks as `verifyAggregatedBlockProof` and `verify_recursive` of the verifier
+//! contract: the public inputs of the proofs must match the block commitments, the verification keys
+//! of the aggregated proof must correspond to the block sizes, and the proofs themselves must be valid
+//! for the verification keys from the `key_dir`. The recursive circuit doesn't check the pairings of
+//! the subproofs, it aggregates them into two G1 points encoded with the limbs of the aggregated proof
+//! instead, so the deferred pairing check is combined with the pairing check of the aggregated proof
+//! the same way the contract does.
+
+// Built-in deps
+use std::collections::HashMap;
+use std::fs::File;
+// External deps
+use anyhow::{ensure, format_err};
+use crypto::{digest::Digest, sha2::Sha256};
+// Workspace deps
+use zksync_crypto::bellman::plonk::better_better_cs::{
+    setup::VerificationKey, verifier::aggregate,
+};
+use zksync_crypto::bellman::plonk::better_cs::verifier::verify as verify_single;
+use zksync_crypto::bellman::plonk::commitments::transcript::{
+    keccak_transcript::RollingKeccakTranscript, Prng, Transcript,
+};
+use zksync_crypto::convert::FeConvert;
+use zksync_crypto::ff::{Field, PrimeField, PrimeFieldRepr};
+use zksync_crypto::franklin_crypto::plonk::circuit::bigint::field::RnsParameters;
+use zksync_crypto::franklin_crypto::rescue::bn256::Bn256RescueParams;
+use zksync_crypto::franklin_crypto::rescue::rescue_transcript::RescueTranscriptForRNS;
+use zksync_crypto::pairing::bn256::{Fq, Fq12, G1Affine};
+use zksync_crypto::pairing::{CurveAffine, CurveProjective, Engine as EngineTrait};
+use zksync_crypto::primitives::EthereumSerializer;
+use zksync_crypto::proof::{AggregatedProof, SingleProof, Vk};
+use zksync_crypto::recursive_aggregation_circuit::circuit::RecursiveAggregationCircuitBn256;
+use zksync_crypto::{Engine, Fr};
+use zksync_types::{block::Block, BlockNumber, H256, U256};
+// Local deps
+use crate::fs_utils::get_recursive_verification_key_path;
+use crate::PlonkVerificationKey;
+
+/// Amount of limbs the aggregated G1 points of the subproofs are encoded with.
+const AGGREGATED_LIMBS_COUNT: usize = 16;
+/// Amount of limbs encoding a single coordinate of the aggregated G1 point, the lowest limb goes first.
+const LIMBS_PER_COORDINATE: usize = 4;
+/// Bit width of the aggregated limbs, `LIMB_WIDTH` of the verifier contract.
+const LIMB_WIDTH: usize = 68;
+
+type AggregatedVk = VerificationKey<Engine, RecursiveAggregationCircuitBn256<'static>>;
+
+/// Verification keys of the main circuit for all the supported block sizes and
+/// verification keys of the recursive circuit for all the supported aggregated proof sizes.
+pub struct VerificationKeys {
+    supported_block_chunks_sizes: Vec<usize>,
+    block_vks: Vec<Vk>,
+    vk_tree_root: Fr,
+    /// Verification keys of the recursive circuit by the amount of aggregated blocks.
+    aggregated_vks: HashMap<usize, AggregatedVk>,
+}
+
+impl VerificationKeys {
+    /// Loads the verification keys from the `key_dir`.
+    /// Sizes must be the same as the ones the verifier contract was generated for.
+    pub fn load(
+        supported_block_chunks_sizes: &[usize],
+        supported_aggregated_proof_sizes: &[usize],
+    ) -> anyhow::Result<Self> {
+        // Indexes of the keys are encoded as `uint8` by the contract.
+        ensure!(
+            !supported_block_chunks_sizes.is_empty()
+                && supported_block_chunks_sizes.len() <= u8::MAX as usize + 1,
+            "Unsupported amount of block sizes: {}",
+            supported_block_chunks_sizes.len()
+        );
+        let block_vks = supported_block_chunks_sizes
+            .iter()
+            .map(|&chunks| {
+                PlonkVerificationKey::read_verification_key_for_main_circuit(chunks)
+                    .map(|vk| vk.0)
+                    .map_err(|err| {
+                        format_err!(
+                            "Failed to read verification key for {} chunks: {}",
+                            chunks,
+                            err
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let vk_tree_root = PlonkVerificationKey::vk_tree_root_hash(&block_vks)?;
+
+        let mut aggregated_vks = HashMap::new();
+        for &blocks in supported_aggregated_proof_sizes {
+            let vk = AggregatedVk::read(File::open(get_recursive_verification_key_path(blocks))?)
+                .map_err(|err| {
+                format_err!(
+                    "Failed to read aggregated verification key for {} blocks: {}",
+                    blocks,
+                    err
+                )
+            })?;
+            aggregated_vks.insert(blocks, vk);
+        }
+
+        Ok(Self {
+            supported_block_chunks_sizes: supported_block_chunks_sizes.to_vec(),
+            block_vks,
+            vk_tree_root,
+            aggregated_vks,
+        })
+    }
+
+    /// Largest valid index of the block verification key, `VK_MAX_INDEX` of the verifier contract.
+    fn vk_max_index(&self) -> usize {
+        self.supported_block_chunks_sizes.len() - 1
+    }
+
+    /// Returns the index of the verification key for the block of the given size.
+    fn vk_index(&self, block_chunks_size: usize) -> anyhow::Result<usize> {
+        self.supported_block_chunks_sizes
+            .iter()
+            .position(|&size| size == block_chunks_size)
+            .ok_or_else(|| format_err!("Unsupported block size: {} chunks", block_chunks_size))
+    }
+
+    /// Returns the verification key of the recursive circuit for the given amount of blocks.
+    fn aggregated_vk(&self, blocks: usize) -> anyhow::Result<&AggregatedVk> {
+        self.aggregated_vks
+            .get(&blocks)
+            .ok_or_else(|| format_err!("Unsupported aggregated proof size: {} blocks", blocks))
+    }
+}
+
+/// Block data the proof is checked against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProvenBlock {
+    pub block_number: BlockNumber,
+    pub block_chunks_size: usize,
+    pub commitment: H256,
+}
+
+impl From<&Block> for ProvenBlock {
+    fn from(block: &Block) -> Self {
+        Self {
+            block_number: block.block_number,
+            block_chunks_size: block.block_chunks_size,
+            commitment: block.block_commitment,
+        }
+    }
+}
+
+/// Proof to verify with the `verify` function.
+#[derive(Debug, Clone, Copy)]
+pub enum ProofToVerify<'a> {
+    /// Proof of a single block.
+    Single(&'a SingleProof),
+    /// Proof of the consecutive blocks.
+    Aggregated(&'a AggregatedProof),
+}
+
+/// Verifies the proof of the given blocks.
+/// The single proof must be given exactly one block.
+pub fn verify(
+    keys: &VerificationKeys,
+    proof: ProofToVerify<'_>,
+    blocks: &[ProvenBlock],
+) -> anyhow::Result<()> {
+    match proof {
+        ProofToVerify::Single(proof) => {
+            ensure!(
+                blocks.len() == 1,
+                "Single proof is verified against {} blocks",
+                blocks.len()
+            );
+            verify_single_proof(keys, proof, &blocks[0])
+        }
+        ProofToVerify::Aggregated(proof) => verify_aggregated_proof(keys, proof, blocks),
+    }
+}
+
+/// Verifies the proof of the single block.
+pub fn verify_single_proof(
+    keys: &VerificationKeys,
+    proof: &SingleProof,
+    block: &ProvenBlock,
+) -> anyhow::Result<()> {
+    ensure!(
+        proof.0.input_values.len() == 1,
+        "Single proof should have one input, found {}",
+        proof.0.input_values.len()
+    );
+    ensure!(
+        proof.0.input_values[0] == commitment_to_fr(block.commitment),
+        "Proof input doesn't match the commitment of block {}",
+        block.block_number
+    );
+
+    let vk = &keys.block_vks[keys.vk_index(block.block_chunks_size)?];
+    let rns_params =
+        RnsParameters::<Engine, <Engine as EngineTrait>::Fq>::new_for_field(68, 110, 4);
+    let rescue_params = Bn256RescueParams::new_checked_2_into_1();
+    let is_valid = verify_single::<_, _, RescueTranscriptForRNS<Engine>>(
+        &proof.0,
+        vk,
+        Some((&rescue_params, &rns_params)),
+    )
+    .map_err(|err| format_err!("Failed to verify proof: {:?}", err))?;
+    ensure!(is_valid, "Proof of block {} is invalid", block.block_number);
+    Ok(())
+}
+
+/// Verifies the aggregated proof of the consecutive blocks.
+pub fn verify_aggregated_proof(
+    keys: &VerificationKeys,
+    proof: &AggregatedProof,
+    blocks: &[ProvenBlock],
+) -> anyhow::Result<()> {
+    ensure!(
+        proof.individual_vk_inputs.len() == blocks.len()
+            && proof.individual_vk_idxs.len() == blocks.len(),
+        "Aggregated proof has {} inputs and {} vk indexes for {} blocks",
+        proof.individual_vk_inputs.len(),
+        proof.individual_vk_idxs.len(),
+        blocks.len()
+    );
+    ensure!(
+        proof.aggr_limbs.len() == AGGREGATED_LIMBS_COUNT,
+        "Aggregated proof should have {} limbs, found {}",
+        AGGREGATED_LIMBS_COUNT,
+        proof.aggr_limbs.len()
+    );
+    for ((block, input), vk_idx) in blocks
+        .iter()
+        .zip(&proof.individual_vk_inputs)
+        .zip(&proof.individual_vk_idxs)
+    {
+        ensure!(
+            *input == commitment_to_fr(block.commitment),
+            "Proof input doesn't match the commitment of block {}",
+            block.block_number
+        );
+        ensure!(
+            *vk_idx <= keys.vk_max_index(),
+            "Verification key index {} of block {} exceeds the maximum index {}",
+            vk_idx,
+            block.block_number,
+            keys.vk_max_index()
+        );
+        ensure!(
+            *vk_idx == keys.vk_index(block.block_chunks_size)?,
+            "Verification key {} doesn't match the size of block {}",
+            vk_idx,
+            block.block_number
+        );
+    }
+
+    ensure!(
+        proof.proof.inputs.len() == 1,
+        "Aggregated proof should have one input, found {}",
+        proof.proof.inputs.len()
+    );
+    let expected_input = recursive_input(
+        keys.vk_tree_root,
+        &proof.individual_vk_idxs,
+        &proof.individual_vk_inputs,
+        &proof.aggr_limbs,
+    );
+    ensure!(
+        proof.proof.inputs[0] == expected_input,
+        "Aggregated proof input doesn't match its individual inputs"
+    );
+
+    let aggregated_g1s = aggregated_g1s_from_limbs(&proof.aggr_limbs)?;
+
+    let vk = keys.aggregated_vk(blocks.len())?;
+    let (is_valid, recursive_proof_part) =
+        aggregate::<_, _, RollingKeccakTranscript<Fr>>(vk, &proof.proof, None)
+            .map_err(|err| format_err!("Failed to verify aggregated proof: {:?}", err))?;
+    ensure!(is_valid, "Aggregated proof is invalid");
+
+    let [pair_with_generator, pair_with_x] =
+        combine_inner_and_outer(aggregated_g1s, recursive_proof_part);
+    let pairing = Engine::final_exponentiation(&Engine::miller_loop(&[
+        (&pair_with_generator.prepare(), &vk.g2_elements[0].prepare()),
+        (&pair_with_x.prepare(), &vk.g2_elements[1].prepare()),
+    ]));
+    ensure!(
+        pairing == Some(Fq12::one()),
+        "Pairing check of the aggregated proof failed"
+    );
+    Ok(())
+}
+
+/// Reconstructs the two G1 points the recursive circuit aggregated the subproofs into
+/// from `AGGREGATED_LIMBS_COUNT` limbs. Limbs are summed as `uint256` the same way
+/// the contract does, so the overflowing bits are dropped.
+fn aggregated_g1s_from_limbs(limbs: &[Fr]) -> anyhow::Result<[G1Affine; 2]> {
+    let coordinates: Vec<U256> = limbs
+        .chunks(LIMBS_PER_COORDINATE)
+        .map(|coordinate_limbs| {
+            coordinate_limbs
+                .iter()
+                .enumerate()
+                .fold(U256::zero(), |coordinate, (i, limb)| {
+                    let limb = EthereumSerializer::serialize_fe(limb) << (i * LIMB_WIDTH);
+                    coordinate.overflowing_add(limb).0
+                })
+        })
+        .collect();
+
+    Ok([
+        g1_from_coordinates(coordinates[0], coordinates[1])?,
+        g1_from_coordinates(coordinates[2], coordinates[3])?,
+    ])
+}
+
+/// Decodes the G1 point checking that it's on the curve, `(0, 0)` is the point at infinity.
+fn g1_from_coordinates(x: U256, y: U256) -> anyhow::Result<G1Affine> {
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::zero());
+    }
+    G1Affine::from_xy_checked(u256_to_fq(x)?, u256_to_fq(y)?)
+        .map_err(|err| format_err!("Aggregated G1 point is invalid: {}", err))
+}
+
+fn u256_to_fq(value: U256) -> anyhow::Result<Fq> {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    let mut repr = <Fq as PrimeField>::Repr::default();
+    repr.read_be(&bytes[..])?;
+    Fq::from_repr(repr)
+        .map_err(|err| format_err!("Aggregated G1 coordinate is not in the field: {}", err))
+}
+
+/// Combines the aggregated G1 points of the subproofs (inner) with the ones of the aggregated proof (outer),
+/// so both pairing checks are done at once: `inner + challenge * outer`.
+fn combine_inner_and_outer(inner: [G1Affine; 2], outer: [G1Affine; 2]) -> [G1Affine; 2] {
+    let mut transcript = <RollingKeccakTranscript<Fr> as Transcript<Fr>>::new();
+    for point in inner.iter().chain(&outer) {
+        // The contract encodes the point at infinity as `(0, 0)`.
+        let (x, y) = if point.is_zero() {
+            (Fq::zero(), Fq::zero())
+        } else {
+            point.into_xy_unchecked()
+        };
+        transcript.commit_fe(&x);
+        transcript.commit_fe(&y);
+    }
+    let challenge: Fr = transcript.get_challenge();
+
+    let combine = |inner: &G1Affine, outer: &G1Affine| {
+        let mut point = outer.mul(challenge.into_repr());
+        point.add_assign_mixed(inner);
+        point.into_affine()
+    };
+    [combine(&inner[0], &outer[0]), combine(&inner[1], &outer[1])]
+}
+
+/// Converts the block commitment into the public input of its proof.
+/// The commitment doesn't fit into the field, so the highest 3 bits are cut.
+fn commitment_to_fr(commitment: H256) -> Fr {
+    let mut bytes = commitment.to_fixed_bytes();
+    bytes[0] &= 0xffu8 >> 3;
+    Fr::from_bytes(&bytes).expect("masked commitment fits into the field")
+}
+
+/// Calculates the public input of the aggregated proof the same way as the verifier contract:
+/// the SHA256 hash of the vk tree root, vk indexes, individual inputs and aggregated limbs
+/// with the highest byte cut.
+fn recursive_input(vk_tree_root: Fr, vk_idxs: &[usize], inputs: &[Fr], limbs: &[Fr]) -> Fr {
+    let mut hasher = Sha256::new();
+    hasher.input(&vk_tree_root.to_bytes());
+    for &vk_idx in vk_idxs {
+        hasher.input(&[vk_idx as u8]);
+    }
+    for input in inputs.iter().chain(limbs) {
+        hasher.input(&input.to_bytes());
+    }
+
+    let mut hash = [0u8; 32];
+    hasher.result(&mut hash);
+    hash[0] = 0;
+    Fr::from_bytes(&hash).expect("masked hash fits into the field")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commitment_is_masked() {
+        let commitment = H256::repeat_byte(0xff);
+        let input = commitment_to_fr(commitment).to_bytes();
+        assert_eq!(input[0], 0x1f);
+        assert!(input[1..].iter().all(|&byte| byte == 0xff));
+
+        let small_commitment = H256::from_low_u64_be(42);
+        assert_eq!(
+            commitment_to_fr(small_commitment),
+            Fr::from_bytes(small_commitment.as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn recursive_input_depends_on_all_the_data() {
+        let root = Fr::from_bytes(H256::from_low_u64_be(1).as_bytes()).unwrap();
+        let inputs = vec![commitment_to_fr(H256::repeat_byte(0xab))];
+        let limbs = vec![Fr::from_bytes(H256::from_low_u64_be(7).as_bytes()).unwrap(); 16];
+
+        let input = recursive_input(root, &[0], &inputs, &limbs);
+        assert_eq!(input.to_bytes()[0], 0);
+        assert_eq!(input, recursive_input(root, &[0], &inputs, &limbs));
+        assert_ne!(input, recursive_input(root, &[1], &inputs, &limbs));
+        assert_ne!(
+            input,
+            recursive_input(Fr::from_bytes(&[0; 32]).unwrap(), &[0], &inputs, &limbs)
+        );
+        assert_ne!(input, recursive_input(root, &[0], &inputs, &limbs[1..]));
+    }
+
+    fn test_keys() -> VerificationKeys {
+        VerificationKeys {
+            supported_block_chunks_sizes: vec![6, 30, 74],
+            block_vks: Vec::new(),
+            vk_tree_root: Fr::from_bytes(&[0; 32]).unwrap(),
+            aggregated_vks: HashMap::new(),
+        }
+    }
+
+    fn test_points() -> [G1Affine; 2] {
+        let generator = G1Affine::one();
+        let point = generator.mul(Fr::from_str("5").unwrap().into_repr());
+        [generator, point.into_affine()]
+    }
+
+    /// Encodes the G1 points into the limbs the same way the recursive circuit does.
+    fn limbs_of(points: &[G1Affine; 2]) -> Vec<Fr> {
+        let limb_mask = (U256::one() << LIMB_WIDTH) - U256::one();
+        let mut limbs = Vec::new();
+        for point in points {
+            let (x, y) = point.into_xy_unchecked();
+            for coordinate in &[x, y] {
+                let mut bytes = [0u8; 32];
+                coordinate.into_repr().write_be(&mut bytes[..]).unwrap();
+                let coordinate = U256::from_big_endian(&bytes);
+                for i in 0..LIMBS_PER_COORDINATE {
+                    ((coordinate >> (i * LIMB_WIDTH)) & limb_mask).to_big_endian(&mut bytes);
+                    limbs.push(Fr::from_bytes(&bytes).unwrap());
+                }
+            }
+        }
+        limbs
+    }
+
+    fn test_aggregated_proof(keys: &VerificationKeys, block: &ProvenBlock) -> AggregatedProof {
+        let mut proof = AggregatedProof {
+            individual_vk_inputs: vec![commitment_to_fr(block.commitment)],
+            individual_vk_idxs: vec![keys.vk_index(block.block_chunks_size).unwrap()],
+            aggr_limbs: limbs_of(&test_points()),
+            ..Default::default()
+        };
+        proof.proof.inputs = vec![recursive_input(
+            keys.vk_tree_root,
+            &proof.individual_vk_idxs,
+            &proof.individual_vk_inputs,
+            &proof.aggr_limbs,
+        )];
+        proof
+    }
+
+    #[test]
+    fn unsupported_block_size() {
+        let keys = test_keys();
+        assert_eq!(keys.vk_index(30).unwrap(), 1);
+        assert!(keys.vk_index(50).is_err());
+        assert_eq!(keys.vk_max_index(), 2);
+    }
+
+    #[test]
+    fn aggregated_points_from_limbs() {
+        let points = test_points();
+        let limbs = limbs_of(&points);
+        assert_eq!(limbs.len(), AGGREGATED_LIMBS_COUNT);
+        assert_eq!(aggregated_g1s_from_limbs(&limbs).unwrap(), points);
+
+        let zero_limbs = vec![Fr::zero(); AGGREGATED_LIMBS_COUNT];
+        assert_eq!(
+            aggregated_g1s_from_limbs(&zero_limbs).unwrap(),
+            [G1Affine::zero(); 2]
+        );
+
+        // The generator is `(1, 2)`, so `(2, 2)` is not on the curve.
+        let mut tampered_limbs = limbs;
+        tampered_limbs[0].add_assign(&Fr::one());
+        assert!(aggregated_g1s_from_limbs(&tampered_limbs).is_err());
+    }
+
+    #[test]
+    fn tampered_limb_is_rejected() {
+        let keys = test_keys();
+        let block = ProvenBlock {
+            block_number: BlockNumber(1),
+            block_chunks_size: 30,
+            commitment: H256::repeat_byte(0xab),
+        };
+        let mut proof = test_aggregated_proof(&keys, &block);
+
+        // Inputs and limbs of the intact proof are correct, so the verification gets to the proof itself.
+        let err = verify_aggregated_proof(&keys, &proof, &[block]).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Unsupported aggregated proof size: 1 blocks"),
+            "{}",
+            err
+        );
+
+        proof.aggr_limbs[5].add_assign(&Fr::one());
+        let err = verify_aggregated_proof(&keys, &proof, &[block]).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Aggregated proof input doesn't match its individual inputs"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn vk_index_is_bounded() {
+        let keys = test_keys();
+        let block = ProvenBlock {
+            block_number: BlockNumber(1),
+            block_chunks_size: 30,
+            commitment: H256::repeat_byte(0xab),
+        };
+        let mut proof = test_aggregated_proof(&keys, &block);
+        proof.individual_vk_idxs = vec![3];
+
+        let err = verify_aggregated_proof(&keys, &proof, &[block]).unwrap_err();
+        assert!(
+            err.to_string().contains("exceeds the maximum index 2"),
+            "{}",
+            err
+        );
+    }
+}
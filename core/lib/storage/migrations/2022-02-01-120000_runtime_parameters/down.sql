@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS notify_runtime_parameters_listener ON runtime_parameters;
+DROP FUNCTION IF EXISTS notify_runtime_parameters_channel;
+DROP TABLE IF EXISTS runtime_parameters;
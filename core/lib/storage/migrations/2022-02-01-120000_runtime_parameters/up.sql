@@ -0,0 +1,20 @@
+-- Parameters of the server which can be changed at runtime without restarting it.
+-- Value is stored as JSON, interpretation of the value depends on the parameter name.
+CREATE TABLE runtime_parameters (
+    name TEXT PRIMARY KEY,
+    value jsonb NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE OR REPLACE FUNCTION notify_runtime_parameters_channel() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM (
+        SELECT pg_notify('runtime_parameters_channel', TG_OP)
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notify_runtime_parameters_listener
+AFTER INSERT OR UPDATE OR DELETE ON runtime_parameters
+FOR EACH STATEMENT EXECUTE PROCEDURE notify_runtime_parameters_channel();
@@ -0,0 +1,8 @@
+DROP TABLE IF EXISTS prover_stats;
+DROP INDEX IF EXISTS prover_job_queue_status_priority_idx;
+
+ALTER TABLE prover_job_queue
+    DROP COLUMN IF EXISTS retry_count,
+    DROP COLUMN IF EXISTS failure_reason,
+    DROP COLUMN IF EXISTS leased_at,
+    DROP COLUMN IF EXISTS lease_expires_at;
@@ -0,0 +1,19 @@
+-- Jobs are leased to provers for a limited time, the lease is renewed by the prover heartbeats.
+-- Jobs which are failed or lost too many times are moved to the `failed` status (3).
+ALTER TABLE prover_job_queue
+    ADD COLUMN retry_count INT NOT NULL DEFAULT 0,
+    ADD COLUMN failure_reason TEXT,
+    ADD COLUMN leased_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN lease_expires_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS prover_job_queue_status_priority_idx
+    ON prover_job_queue (job_status, job_priority, first_block, id);
+
+CREATE TABLE prover_stats (
+    prover_name TEXT PRIMARY KEY,
+    jobs_started BIGINT NOT NULL DEFAULT 0,
+    jobs_completed BIGINT NOT NULL DEFAULT 0,
+    jobs_failed BIGINT NOT NULL DEFAULT 0,
+    proving_time_ms BIGINT NOT NULL DEFAULT 0,
+    last_seen_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1 @@
+ALTER TABLE data_restore_rollup_blocks ADD new_root_hash bytea;
@@ -0,0 +1,9 @@
+-- Binary encoded rows can't be converted back in SQL, so they are removed.
+-- Witnesses and caches are regenerated by the witness generator.
+DELETE FROM block_witness WHERE witness IS NULL;
+ALTER TABLE block_witness DROP COLUMN witness_binary;
+ALTER TABLE block_witness ALTER COLUMN witness SET NOT NULL;
+
+DELETE FROM account_tree_cache WHERE tree_cache IS NULL;
+ALTER TABLE account_tree_cache DROP COLUMN tree_cache_binary;
+ALTER TABLE account_tree_cache ALTER COLUMN tree_cache SET NOT NULL;
@@ -0,0 +1,7 @@
+-- Witnesses and account tree caches are stored in the compact binary encoding.
+-- The JSON columns are kept to read the rows stored before the migration.
+ALTER TABLE block_witness ALTER COLUMN witness DROP NOT NULL;
+ALTER TABLE block_witness ADD witness_binary bytea;
+
+ALTER TABLE account_tree_cache ALTER COLUMN tree_cache DROP NOT NULL;
+ALTER TABLE account_tree_cache ADD tree_cache_binary bytea;
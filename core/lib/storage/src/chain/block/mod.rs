@@ -1,6 +1,7 @@
 // Built-in deps
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 // External imports
+use serde::{de::DeserializeOwned, Serialize};
 // Workspace imports
 use zksync_api_types::{
     v02::{
@@ -9,7 +10,7 @@ use zksync_api_types::{
     },
     Either,
 };
-use zksync_crypto::convert::FeConvert;
+use zksync_crypto::{binary_encoding, convert::FeConvert};
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
     block::{Block, BlockMetadata, ExecutedOperations, IncompleteBlock, PendingBlock},
@@ -30,6 +31,7 @@ use crate::{
         OperationsSchema,
     },
     chain::{account::records::EthAccountType, block::records::StorageIncompleteBlock},
+    utils::decode_binary_or_json,
     QueryResult, StorageProcessor,
 };
 
@@ -634,12 +636,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
 
     /// Returns the number of existing incomplete block.
     /// Returns `None` if there are no incomplte blocks in the database.
-    ///
-    /// Note: Used only for testing.
-    #[cfg(test)]
-    pub(crate) async fn get_last_incomplete_block_number(
-        &mut self,
-    ) -> QueryResult<Option<BlockNumber>> {
+    pub async fn get_last_incomplete_block_number(&mut self) -> QueryResult<Option<BlockNumber>> {
         let start = Instant::now();
         let result = sqlx::query!("SELECT max(number) FROM incomplete_blocks")
             .fetch_one(self.0.conn())
@@ -1155,28 +1152,28 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         Ok(())
     }
 
-    /// Stores account tree cache for a block
-    pub async fn store_account_tree_cache(
+    /// Stores account tree cache for a block.
+    /// The cache is stored in the binary encoding, see `zksync_crypto::binary_encoding`.
+    pub async fn store_account_tree_cache<T: Serialize>(
         &mut self,
         block: BlockNumber,
-        tree_cache: serde_json::Value,
+        tree_cache: &T,
     ) -> QueryResult<()> {
         let start = Instant::now();
         if *block == 0 {
             return Ok(());
         }
 
-        let tree_cache_str =
-            serde_json::to_string(&tree_cache).expect("Failed to serialize Account Tree Cache");
+        let encoded_tree_cache = binary_encoding::encode(tree_cache)?;
         sqlx::query!(
             "
-            INSERT INTO account_tree_cache (block, tree_cache)
+            INSERT INTO account_tree_cache (block, tree_cache_binary)
             VALUES ($1, $2)
             ON CONFLICT (block)
             DO NOTHING
             ",
             *block as i64,
-            tree_cache_str,
+            encoded_tree_cache,
         )
         .execute(self.0.conn())
         .await?;
@@ -1207,9 +1204,9 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
     /// Gets the latest stored account tree cache.
     /// Returns `None` if there are no caches in the database.
     /// Returns the block number and associated cache otherwise.
-    pub async fn get_account_tree_cache(
+    pub async fn get_account_tree_cache<T: DeserializeOwned>(
         &mut self,
-    ) -> QueryResult<Option<(BlockNumber, serde_json::Value)>> {
+    ) -> QueryResult<Option<(BlockNumber, T)>> {
         let start = Instant::now();
         let account_tree_cache = sqlx::query_as!(
             AccountTreeCache,
@@ -1222,22 +1219,23 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
+        let result = account_tree_cache
+            .map(|w| {
+                decode_binary_or_json(w.tree_cache_binary.as_deref(), w.tree_cache.as_deref())
+                    .map(|tree_cache| (BlockNumber(w.block as u32), tree_cache))
+            })
+            .transpose()?;
+
         metrics::histogram!("sql.chain.block.get_account_tree_cache", start.elapsed());
-        Ok(account_tree_cache.map(|w| {
-            (
-                BlockNumber(w.block as u32),
-                serde_json::from_str(&w.tree_cache)
-                    .expect("Failed to deserialize Account Tree Cache"),
-            )
-        }))
+        Ok(result)
     }
 
     /// Gets stored account tree cache for a certain block.
     /// Returns `None` if there is no cache for requested block.
-    pub async fn get_account_tree_cache_block(
+    pub async fn get_account_tree_cache_block<T: DeserializeOwned>(
         &mut self,
         block: BlockNumber,
-    ) -> QueryResult<Option<serde_json::Value>> {
+    ) -> QueryResult<Option<T>> {
         let start = Instant::now();
         let account_tree_cache = sqlx::query_as!(
             AccountTreeCache,
@@ -1250,13 +1248,32 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
+        let result = account_tree_cache
+            .map(|w| decode_binary_or_json(w.tree_cache_binary.as_deref(), w.tree_cache.as_deref()))
+            .transpose()?;
+
         metrics::histogram!(
             "sql.chain.block.get_account_tree_cache_block",
             start.elapsed()
         );
-        Ok(account_tree_cache.map(|w| {
-            serde_json::from_str(&w.tree_cache).expect("Failed to deserialize Account Tree Cache")
-        }))
+        Ok(result)
+    }
+
+    /// Returns the numbers of all the blocks for which the account tree cache is stored.
+    pub async fn get_account_tree_cache_blocks(&mut self) -> QueryResult<Vec<BlockNumber>> {
+        let start = Instant::now();
+        let blocks = sqlx::query!("SELECT block FROM account_tree_cache ORDER BY block")
+            .fetch_all(self.0.conn())
+            .await?
+            .into_iter()
+            .map(|record| BlockNumber(record.block as u32))
+            .collect();
+
+        metrics::histogram!(
+            "sql.chain.block.get_account_tree_cache_blocks",
+            start.elapsed()
+        );
+        Ok(blocks)
     }
 
     pub async fn save_genesis_block(&mut self, root_hash: Fr) -> QueryResult<()> {
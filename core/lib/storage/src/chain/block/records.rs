@@ -104,7 +104,10 @@ pub struct TransactionItem {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountTreeCache {
     pub block: i64,
-    pub tree_cache: String,
+    /// Cache in the legacy JSON encoding.
+    pub tree_cache: Option<String>,
+    /// Cache in the binary encoding, see `zksync_crypto::binary_encoding`.
+    pub tree_cache_binary: Option<Vec<u8>>,
 }
 
 impl StorageBlockDetails {
@@ -587,6 +587,21 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(ops.into_iter().map(|op| op.into()).collect())
     }
 
+    /// Removes the priority operations which have not been confirmed yet, e.g. because the L1 blocks
+    /// they were included in have been reorganized. Confirmed operations with the same ids are kept.
+    pub async fn remove_unconfirmed_priority_ops(&mut self, ids: &[u64]) -> QueryResult<()> {
+        let start = Instant::now();
+        let ids: Vec<_> = ids.iter().map(|v| *v as i64).collect();
+        sqlx::query!(
+            "DELETE FROM mempool_priority_operations WHERE serial_id=ANY($1) AND confirmed = false",
+            &ids
+        )
+        .execute(self.0.conn())
+        .await?;
+        metrics::histogram!("sql.chain", start.elapsed(), "schema" => "mempool", "method" => "remove_unconfirmed_priority_ops");
+        Ok(())
+    }
+
     pub async fn remove_priority_ops_from_mempool(&mut self, ids: &[u64]) -> QueryResult<()> {
         let ids: Vec<_> = ids.iter().map(|v| *v as i64).collect();
         sqlx::query!(
@@ -11,7 +11,8 @@ use zksync_types::{
 // Local imports
 use self::records::{
     NewExecutedPriorityOperation, NewExecutedTransaction, StoredAggregatedOperation,
-    StoredCompleteWithdrawalsTransaction, StoredExecutedPriorityOperation, StoredPendingWithdrawal,
+    StoredAggregatedOperationRange, StoredCompleteWithdrawalsTransaction,
+    StoredExecutedPriorityOperation, StoredPendingWithdrawal,
 };
 use crate::chain::operations::records::StoredExecutedTransaction;
 use crate::chain::operations_ext::OperationsExtSchema;
@@ -609,6 +610,56 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Returns the amount of the executed transactions and priority operations for every
+    /// block number they are stored with, including the incomplete and pending blocks.
+    pub async fn get_executed_operations_count_by_block(
+        &mut self,
+    ) -> QueryResult<Vec<(BlockNumber, u64)>> {
+        let start = Instant::now();
+        let counts = sqlx::query!(
+            r#"
+            SELECT block_number AS "block_number!", COUNT(*) AS "count!"
+            FROM (
+                SELECT block_number FROM executed_transactions
+                UNION ALL
+                SELECT block_number FROM executed_priority_operations
+            ) operations
+            GROUP BY block_number
+            ORDER BY block_number
+            "#
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| (BlockNumber(record.block_number as u32), record.count as u64))
+        .collect();
+
+        metrics::histogram!(
+            "sql.chain.operations.get_executed_operations_count_by_block",
+            start.elapsed()
+        );
+        Ok(counts)
+    }
+
+    /// Loads the block ranges of all the stored aggregated operations ordered by their IDs.
+    pub async fn get_aggregated_operation_ranges(
+        &mut self,
+    ) -> QueryResult<Vec<StoredAggregatedOperationRange>> {
+        let start = Instant::now();
+        let ranges = sqlx::query_as!(
+            StoredAggregatedOperationRange,
+            "SELECT id, action_type, from_block, to_block, confirmed FROM aggregate_operations ORDER BY id"
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.get_aggregated_operation_ranges",
+            start.elapsed()
+        );
+        Ok(ranges)
+    }
+
     // Removes executed priority operations for blocks with number greater than `last_block`
     pub async fn return_executed_priority_operations_to_mempool(
         &mut self,
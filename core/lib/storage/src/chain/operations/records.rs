@@ -126,6 +126,16 @@ pub struct StoredCompleteWithdrawalsTransaction {
     pub pending_withdrawals_queue_end_index: i64,
 }
 
+/// Block range of the aggregated operation, without its arguments.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StoredAggregatedOperationRange {
+    pub id: i64,
+    pub action_type: String,
+    pub from_block: i64,
+    pub to_block: i64,
+    pub confirmed: bool,
+}
+
 #[derive(Debug, Clone, FromRow)]
 pub struct StoredAggregatedOperation {
     pub id: i64,
@@ -4,16 +4,24 @@ use std::time::Instant;
 // Workspace imports
 use zksync_types::Address;
 // Local imports
-use self::records::ServerConfig;
+use self::records::{RuntimeParameter, ServerConfig};
 use crate::{QueryResult, StorageProcessor};
 
 pub mod records;
 
+/// Name of the channel used to notify about changes in the `runtime_parameters` table.
+/// Must be equal to the one hardcoded into database migrations.
+pub const RUNTIME_PARAMETERS_CHANNEL: &str = "runtime_parameters_channel";
+
 /// Schema for loading the server config.
-/// Note that there is no setter in this schema, since the config
+/// Note that there is no setter for the server config in this schema, since it
 /// isn't expected to be writable within application.
 ///
 /// Currently config is added to ZKSync by the `db-insert-contract.sh` script.
+///
+/// Runtime parameters, on the contrary, are expected to be changed while the server is running.
+/// Every change is announced through the `RUNTIME_PARAMETERS_CHANNEL`, which can be listened to
+/// with the `StorageListener`.
 #[derive(Debug)]
 pub struct ConfigSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
 
@@ -29,7 +37,7 @@ impl<'a, 'c> ConfigSchema<'a, 'c> {
         Ok(config)
     }
 
-    // Stores the server configuration for tests.
+    // Stores the server configuration for tests and the devnet.
     #[doc(hidden)]
     #[allow(dead_code)]
     pub async fn store_config(
@@ -52,4 +60,49 @@ impl<'a, 'c> ConfigSchema<'a, 'c> {
         metrics::histogram!("sql.store_config", start.elapsed());
         Ok(())
     }
+
+    /// Loads all the runtime parameters stored in the database.
+    pub async fn load_runtime_parameters(&mut self) -> QueryResult<Vec<RuntimeParameter>> {
+        let start = Instant::now();
+        let parameters = sqlx::query_as!(
+            RuntimeParameter,
+            "SELECT * FROM runtime_parameters ORDER BY name"
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.load_runtime_parameters", start.elapsed());
+        Ok(parameters)
+    }
+
+    /// Sets the value of the runtime parameter, replacing the previous one if any.
+    pub async fn store_runtime_parameter(
+        &mut self,
+        name: &str,
+        value: serde_json::Value,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO runtime_parameters (name, value) VALUES ($1, $2)
+            ON CONFLICT (name) DO UPDATE SET value = $2, updated_at = now()",
+            name,
+            value
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.store_runtime_parameter", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the runtime parameter, so the application will fall back to its default value.
+    pub async fn remove_runtime_parameter(&mut self, name: &str) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!("DELETE FROM runtime_parameters WHERE name = $1", name)
+            .execute(self.0.conn())
+            .await?;
+
+        metrics::histogram!("sql.remove_runtime_parameter", start.elapsed());
+        Ok(())
+    }
 }
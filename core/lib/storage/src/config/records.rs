@@ -1,4 +1,5 @@
 // External imports
+use chrono::{DateTime, Utc};
 use sqlx::FromRow;
 // Workspace imports
 // Local imports
@@ -10,3 +11,10 @@ pub struct ServerConfig {
     pub gov_contract_addr: Option<String>,
     pub nft_factory_addr: Option<String>,
 }
+
+#[derive(Debug, Clone, FromRow)]
+pub struct RuntimeParameter {
+    pub name: String,
+    pub value: serde_json::Value,
+    pub updated_at: DateTime<Utc>,
+}
@@ -105,7 +105,7 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         let stored_blocks = sqlx::query_as!(
             StoredRollupOpsBlock,
             "SELECT blocks.block_num AS block_num, ops, fee_account,
-            timestamp, previous_block_root_hash, contract_version
+            timestamp, previous_block_root_hash, new_root_hash, contract_version
             FROM data_restore_rollup_blocks AS blocks
             JOIN (
                 SELECT block_num, array_agg(operation ORDER BY id) as ops
@@ -324,11 +324,12 @@ impl<'a, 'c> DataRestoreSchema<'a, 'c> {
         for block in rollup_blocks {
             sqlx::query!(
                 "INSERT INTO data_restore_rollup_blocks
-                VALUES ($1, $2, $3, $4)",
+                VALUES ($1, $2, $3, $4, $5)",
                 i64::from(*block.block_num),
                 i64::from(*block.fee_account),
                 block.timestamp.map(|t| t as i64),
-                Some(block.previous_block_root_hash.as_bytes().to_vec())
+                Some(block.previous_block_root_hash.as_bytes().to_vec()),
+                block.new_root_hash.map(|hash| hash.as_bytes().to_vec())
             )
             .execute(transaction.conn())
             .await?;
@@ -20,6 +20,7 @@ pub struct NewRollupOpsBlock<'a> {
     pub fee_account: AccountId,
     pub timestamp: Option<u64>,
     pub previous_block_root_hash: H256,
+    pub new_root_hash: Option<H256>,
 }
 
 #[derive(Debug)]
@@ -35,6 +36,7 @@ pub struct StoredRollupOpsBlock {
     pub fee_account: i64,
     pub timestamp: Option<i64>,
     pub previous_block_root_hash: Option<Vec<u8>>,
+    pub new_root_hash: Option<Vec<u8>>,
     pub contract_version: i32,
 }
 
@@ -1,18 +1,27 @@
 // Built-in deps
-use std::time::Instant;
+use std::time::{Duration, Instant};
 // External imports
 use anyhow::format_err;
+use serde::{de::DeserializeOwned, Serialize};
 // Workspace imports
 use zksync_types::BlockNumber;
 // Local imports
-use self::records::{StorageProverJobQueue, StoredAggregatedProof, StoredProof};
+use self::records::{
+    StorageProverJobQueue, StorageProverJobWorkload, StoredAggregatedProof, StoredProof,
+    StoredProverStats,
+};
 use crate::chain::operations::OperationsSchema;
 use crate::prover::records::StorageBlockWitness;
-use crate::{QueryResult, StorageProcessor};
+use crate::{utils::decode_binary_or_json, QueryResult, StorageProcessor};
 use chrono::{TimeZone, Utc};
-use zksync_crypto::proof::{AggregatedProof, SingleProof};
+use zksync_crypto::{
+    binary_encoding,
+    proof::{AggregatedProof, SingleProof},
+};
 use zksync_types::aggregated_operations::AggregatedActionType;
-use zksync_types::prover::{ProverJob, ProverJobStatus, ProverJobType};
+use zksync_types::prover::{
+    FailedProverJob, ProverJob, ProverJobStatus, ProverJobType, ProverJobWorkload, ProverStats,
+};
 
 pub mod records;
 
@@ -23,12 +32,13 @@ pub struct ProverSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
 
 impl<'a, 'c> ProverSchema<'a, 'c> {
     /// Returns the amount of blocks which await for proof, but have
-    /// no assigned prover run.
+    /// no assigned prover run. Failed jobs are not taken into account.
     pub async fn pending_jobs_count(&mut self) -> QueryResult<u32> {
         let start = Instant::now();
         let pending_jobs_count = sqlx::query!(
-            "SELECT COUNT(*) FROM prover_job_queue WHERE job_status != $1",
-            ProverJobStatus::Done.to_number()
+            "SELECT COUNT(*) FROM prover_job_queue WHERE job_status != $1 AND job_status != $2",
+            ProverJobStatus::Done.to_number(),
+            ProverJobStatus::Failed.to_number()
         )
         .fetch_one(self.0.conn())
         .await?
@@ -38,6 +48,58 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(pending_jobs_count)
     }
 
+    /// Returns the workload of the jobs which are not proven yet (both idle and in progress).
+    pub async fn load_pending_jobs_workload(&mut self) -> QueryResult<Vec<ProverJobWorkload>> {
+        let start = Instant::now();
+        let jobs = sqlx::query_as!(
+            StorageProverJobWorkload,
+            r#"SELECT job.job_status, job.job_type, job.first_block, job.last_block,
+                blocks.block_size as "block_size?", job.created_at, job.updated_at, job.leased_at
+            FROM prover_job_queue job
+            LEFT JOIN blocks ON blocks.number = job.first_block
+            WHERE job.job_status = $1 OR job.job_status = $2
+            ORDER BY job.id"#,
+            ProverJobStatus::Idle.to_number(),
+            ProverJobStatus::InProgress.to_number(),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(ProverJobWorkload::from)
+        .collect();
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_pending_jobs_workload");
+        Ok(jobs)
+    }
+
+    /// Returns the workload and the measured proving time of the latest completed jobs.
+    pub async fn load_completed_jobs_workload(
+        &mut self,
+        limit: u32,
+    ) -> QueryResult<Vec<ProverJobWorkload>> {
+        let start = Instant::now();
+        let jobs = sqlx::query_as!(
+            StorageProverJobWorkload,
+            r#"SELECT job.job_status, job.job_type, job.first_block, job.last_block,
+                blocks.block_size as "block_size?", job.created_at, job.updated_at, job.leased_at
+            FROM prover_job_queue job
+            LEFT JOIN blocks ON blocks.number = job.first_block
+            WHERE job.job_status = $1 AND job.leased_at IS NOT NULL
+            ORDER BY job.id DESC
+            LIMIT $2"#,
+            ProverJobStatus::Done.to_number(),
+            i64::from(limit),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(ProverJobWorkload::from)
+        .collect();
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_completed_jobs_workload");
+        Ok(jobs)
+    }
+
     pub async fn add_prover_job_to_job_queue(
         &mut self,
         first_block: BlockNumber,
@@ -68,22 +130,125 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(())
     }
 
-    pub async fn mark_stale_jobs_as_idle(&mut self) -> QueryResult<()> {
+    /// Returns the jobs with expired leases back to the queue. Every lost lease counts as a failed attempt,
+    /// so the job is marked as failed once it reaches `max_retries` attempts.
+    pub async fn mark_stale_jobs_as_idle(&mut self, max_retries: u32) -> QueryResult<()> {
         let start = Instant::now();
-        let result = sqlx::query!(
-            "UPDATE prover_job_queue SET (job_status, updated_at, updated_by) = ($1, now(), 'server_clean_idle')
-            WHERE job_status = $2 and (now() - updated_at) >= interval '120 seconds' RETURNING id",
-            ProverJobStatus::Idle.to_number(),
+        let mut transaction = self.0.start_transaction().await?;
+        // Jobs leased before the leases were introduced have no expiration time.
+        let stale_jobs = sqlx::query!(
+            "SELECT id, updated_by FROM prover_job_queue
+            WHERE job_status = $1
+                AND COALESCE(lease_expires_at, updated_at + interval '120 seconds') <= now()
+            FOR UPDATE",
             ProverJobStatus::InProgress.to_number(),
         )
-        .fetch_all(self.0.conn())
+        .fetch_all(transaction.conn())
         .await?;
-        metrics::counter!("stale_jobs", result.len() as u64);
+
+        for job in &stale_jobs {
+            ProverSchema(&mut transaction)
+                .record_prover_job_failure(job.id, &job.updated_by, "lease expired", max_retries)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::counter!("stale_jobs", stale_jobs.len() as u64);
         metrics::histogram!("sql", start.elapsed(), "prover" => "mark_stale_jobs_as_idle");
         Ok(())
     }
 
-    pub async fn get_idle_prover_job_from_job_queue(&mut self) -> QueryResult<Option<ProverJob>> {
+    /// Records the failed attempt to prove the job and returns the job to the queue,
+    /// unless it has reached `max_retries` attempts.
+    /// Returns `false` if the job is not leased to the prover (e.g. the lease has expired
+    /// and the job was given to another prover).
+    pub async fn record_prover_job_failure(
+        &mut self,
+        job_id: i32,
+        prover_name: &str,
+        reason: &str,
+        max_retries: u32,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let updated_job = sqlx::query!(
+            "UPDATE prover_job_queue
+            SET job_status = CASE WHEN retry_count + 1 >= $1 THEN $2 ELSE $3 END,
+                retry_count = retry_count + 1,
+                failure_reason = $4,
+                leased_at = NULL,
+                lease_expires_at = NULL,
+                updated_at = now(),
+                updated_by = 'server_job_failure'
+            WHERE id = $5 AND job_status = $6 AND updated_by = $7
+            RETURNING job_status, first_block, last_block",
+            max_retries as i32,
+            ProverJobStatus::Failed.to_number(),
+            ProverJobStatus::Idle.to_number(),
+            reason,
+            job_id,
+            ProverJobStatus::InProgress.to_number(),
+            prover_name,
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+
+        let recorded = updated_job.is_some();
+        if let Some(job) = updated_job {
+            sqlx::query!(
+                "UPDATE prover_stats SET jobs_failed = jobs_failed + 1, last_seen_at = now()
+                WHERE prover_name = $1",
+                prover_name,
+            )
+            .execute(transaction.conn())
+            .await?;
+
+            if job.job_status == ProverJobStatus::Failed.to_number() {
+                vlog::error!(
+                    "Prover job {} for blocks [{}, {}] has reached the retry limit, last failure: {}",
+                    job_id,
+                    job.first_block,
+                    job.last_block,
+                    reason
+                );
+                metrics::increment_counter!("prover.failed_jobs");
+            }
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "record_prover_job_failure");
+        Ok(recorded)
+    }
+
+    /// Returns the job which has reached the retry limit back to the queue, resetting its retries.
+    /// Returns `false` if there is no failed job with such an ID.
+    pub async fn requeue_failed_prover_job(&mut self, job_id: i32) -> QueryResult<bool> {
+        let start = Instant::now();
+        let requeued = sqlx::query!(
+            "UPDATE prover_job_queue
+            SET (job_status, retry_count, failure_reason, updated_at, updated_by) =
+                ($1, 0, NULL, now(), 'server_requeue')
+            WHERE id = $2 AND job_status = $3",
+            ProverJobStatus::Idle.to_number(),
+            job_id,
+            ProverJobStatus::Failed.to_number(),
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            == 1;
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "requeue_failed_prover_job");
+        Ok(requeued)
+    }
+
+    /// Leases the job with the highest priority to the prover.
+    /// The lease should be renewed with `record_prover_is_working` until the proof is stored.
+    pub async fn get_idle_prover_job_from_job_queue(
+        &mut self,
+        prover_name: &str,
+        lease_duration: Duration,
+    ) -> QueryResult<Option<ProverJob>> {
         let start = Instant::now();
         // Select the block to prove.
         let mut transaction = self.0.start_transaction().await?;
@@ -96,7 +261,7 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             r#"
                 SELECT * FROM prover_job_queue
                 WHERE job_status = $1
-                ORDER BY (job_priority, id, first_block)
+                ORDER BY job_priority, first_block, id
                 LIMIT 1
             "#,
             ProverJobStatus::Idle.to_number()
@@ -108,15 +273,27 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
             sqlx::query!(
                 r#"
                 UPDATE prover_job_queue
-                SET (job_status, updated_at, updated_by) = ($1, now(), 'server_give_job')
-                WHERE id = $2;
+                SET (job_status, updated_at, updated_by, leased_at, lease_expires_at) =
+                    ($1, now(), $2, now(), now() + $3 * interval '1 millisecond')
+                WHERE id = $4;
             "#,
                 ProverJobStatus::InProgress.to_number(),
+                prover_name,
+                lease_duration.as_millis() as f64,
                 job.id,
             )
             .execute(transaction.conn())
             .await?;
 
+            sqlx::query!(
+                "INSERT INTO prover_stats (prover_name, jobs_started) VALUES ($1, 1)
+                ON CONFLICT (prover_name)
+                DO UPDATE SET jobs_started = prover_stats.jobs_started + 1, last_seen_at = now()",
+                prover_name,
+            )
+            .execute(transaction.conn())
+            .await?;
+
             Some(ProverJob::new(
                 job.id,
                 BlockNumber(job.first_block as u32),
@@ -131,33 +308,111 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(prover_job)
     }
 
-    /// Updates the state of ongoing prover job.
+    /// Updates the state of ongoing prover job and renews its lease.
+    /// Returns `false` if the job is not leased anymore (e.g. the lease has expired and the job
+    /// was returned to the queue).
     pub async fn record_prover_is_working(
         &mut self,
         job_id: i32,
         prover_name: &str,
-    ) -> QueryResult<()> {
+        lease_duration: Duration,
+    ) -> QueryResult<bool> {
         let start = Instant::now();
-        sqlx::query!(
+        let mut transaction = self.0.start_transaction().await?;
+        let renewed = sqlx::query!(
             "UPDATE prover_job_queue
-            SET (updated_at, updated_by) = (now(), $1)
-            WHERE id = $2",
-            prover_name.to_string(),
+            SET (updated_at, updated_by, lease_expires_at) =
+                (now(), $1, now() + $2 * interval '1 millisecond')
+            WHERE id = $3 AND job_status = $4 AND updated_by = $1",
+            prover_name,
+            lease_duration.as_millis() as f64,
             job_id,
+            ProverJobStatus::InProgress.to_number(),
         )
-        .execute(self.0.conn())
+        .execute(transaction.conn())
+        .await?
+        .rows_affected()
+            == 1;
+
+        sqlx::query!(
+            "UPDATE prover_stats SET last_seen_at = now() WHERE prover_name = $1",
+            prover_name,
+        )
+        .execute(transaction.conn())
         .await?;
+        transaction.commit().await?;
 
         metrics::histogram!("sql", start.elapsed(), "prover" => "record_prover_is_working");
+        Ok(renewed)
+    }
+
+    /// Adds the completed job to the statistics of the prover which holds the job lease.
+    async fn record_prover_job_completed(&mut self, job_id: i32) -> QueryResult<()> {
+        sqlx::query!(
+            "UPDATE prover_stats
+            SET jobs_completed = prover_stats.jobs_completed + 1,
+                proving_time_ms = prover_stats.proving_time_ms
+                    + COALESCE((EXTRACT(EPOCH FROM (now() - job.leased_at)) * 1000)::bigint, 0),
+                last_seen_at = now()
+            FROM prover_job_queue job
+            WHERE job.id = $1 AND job.job_status = $2 AND prover_stats.prover_name = job.updated_by",
+            job_id,
+            ProverJobStatus::InProgress.to_number(),
+        )
+        .execute(self.0.conn())
+        .await?;
         Ok(())
     }
 
+    /// Returns the statistics of all the provers that have ever taken a job.
+    pub async fn load_prover_stats(&mut self) -> QueryResult<Vec<ProverStats>> {
+        let start = Instant::now();
+        let stats = sqlx::query_as!(
+            StoredProverStats,
+            "SELECT * FROM prover_stats ORDER BY prover_name"
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(ProverStats::from)
+        .collect();
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_prover_stats");
+        Ok(stats)
+    }
+
+    /// Returns the jobs which have reached the retry limit.
+    pub async fn load_failed_prover_jobs(&mut self) -> QueryResult<Vec<FailedProverJob>> {
+        let start = Instant::now();
+        let jobs = sqlx::query_as!(
+            StorageProverJobQueue,
+            "SELECT * FROM prover_job_queue WHERE job_status = $1 ORDER BY id",
+            ProverJobStatus::Failed.to_number(),
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|job| FailedProverJob {
+            job_id: job.id,
+            job_type: job.job_type,
+            first_block: BlockNumber(job.first_block as u32),
+            last_block: BlockNumber(job.last_block as u32),
+            retry_count: job.retry_count as u32,
+            failure_reason: job.failure_reason,
+            updated_at: job.updated_at,
+        })
+        .collect();
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "load_failed_prover_jobs");
+        Ok(jobs)
+    }
+
     /// Marks the prover as stopped.
     pub async fn record_prover_stop(&mut self, prover_name: &str) -> QueryResult<()> {
         let start = Instant::now();
         sqlx::query!(
             "UPDATE prover_job_queue
-            SET (updated_at, job_status) = (now(), $1)
+            SET (updated_at, job_status, leased_at, lease_expires_at) = (now(), $1, NULL, NULL)
             WHERE updated_by = $2 and job_status = $3",
             ProverJobStatus::Idle.to_number(),
             prover_name,
@@ -179,6 +434,10 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
     ) -> QueryResult<()> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
+        transaction
+            .prover_schema()
+            .record_prover_job_completed(job_id)
+            .await?;
         let updated_rows = sqlx::query!(
             "UPDATE prover_job_queue
             SET (updated_at, job_status, updated_by) = (now(), $1, 'server_finish_job')
@@ -249,6 +508,10 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
     ) -> QueryResult<()> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
+        transaction
+            .prover_schema()
+            .record_prover_job_completed(job_id)
+            .await?;
         let updated_rows = sqlx::query!(
             "UPDATE prover_job_queue
             SET (updated_at, job_status, updated_by) = (now(), $1, 'server_finish_job')
@@ -324,21 +587,22 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(proof)
     }
 
-    /// Stores witness for a block
-    pub async fn store_witness(
+    /// Stores witness for a block.
+    /// The witness is stored in the binary encoding, see `zksync_crypto::binary_encoding`.
+    pub async fn store_witness<T: Serialize>(
         &mut self,
         block: BlockNumber,
-        witness: serde_json::Value,
+        witness: &T,
     ) -> QueryResult<()> {
         let start = Instant::now();
-        let witness_str = serde_json::to_string(&witness).expect("Failed to serialize witness");
+        let encoded_witness = binary_encoding::encode(witness)?;
         sqlx::query!(
-            "INSERT INTO block_witness (block, witness)
+            "INSERT INTO block_witness (block, witness_binary)
             VALUES ($1, $2)
             ON CONFLICT (block)
             DO NOTHING",
             i64::from(*block),
-            witness_str
+            encoded_witness
         )
         .execute(self.0.conn())
         .await?;
@@ -348,10 +612,10 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
     }
 
     /// Gets stored witness for a block.
-    pub async fn get_witness(
+    pub async fn get_witness<T: DeserializeOwned>(
         &mut self,
         block_number: BlockNumber,
-    ) -> QueryResult<Option<serde_json::Value>> {
+    ) -> QueryResult<Option<T>> {
         let start = Instant::now();
         let block_witness = sqlx::query_as!(
             StorageBlockWitness,
@@ -361,9 +625,12 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
+        let witness = block_witness
+            .map(|w| decode_binary_or_json(w.witness_binary.as_deref(), w.witness.as_deref()))
+            .transpose()?;
+
         metrics::histogram!("sql", start.elapsed(), "prover" => "get_witness");
-        Ok(block_witness
-            .map(|w| serde_json::from_str(&w.witness).expect("Failed to deserialize witness")))
+        Ok(witness)
     }
 
     pub async fn get_last_block_prover_job_queue(
@@ -408,6 +675,57 @@ impl<'a, 'c> ProverSchema<'a, 'c> {
         Ok(result)
     }
 
+    /// Returns the numbers of the blocks for which the proofs are stored.
+    pub async fn get_proof_blocks(&mut self) -> QueryResult<Vec<BlockNumber>> {
+        let start = Instant::now();
+        let blocks = sqlx::query!("SELECT block_number FROM proofs ORDER BY block_number")
+            .fetch_all(self.0.conn())
+            .await?
+            .into_iter()
+            .map(|record| BlockNumber(record.block_number as u32))
+            .collect();
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "get_proof_blocks");
+        Ok(blocks)
+    }
+
+    /// Returns the numbers of the blocks for which the witnesses are stored.
+    pub async fn get_witness_blocks(&mut self) -> QueryResult<Vec<BlockNumber>> {
+        let start = Instant::now();
+        let blocks = sqlx::query!("SELECT block FROM block_witness ORDER BY block")
+            .fetch_all(self.0.conn())
+            .await?
+            .into_iter()
+            .map(|record| BlockNumber(record.block as u32))
+            .collect();
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "get_witness_blocks");
+        Ok(blocks)
+    }
+
+    /// Returns the inclusive block ranges of the stored aggregated proofs.
+    pub async fn get_aggregated_proof_ranges(
+        &mut self,
+    ) -> QueryResult<Vec<(BlockNumber, BlockNumber)>> {
+        let start = Instant::now();
+        let ranges = sqlx::query!(
+            "SELECT first_block, last_block FROM aggregated_proofs ORDER BY first_block"
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| {
+            (
+                BlockNumber(record.first_block as u32),
+                BlockNumber(record.last_block as u32),
+            )
+        })
+        .collect();
+
+        metrics::histogram!("sql", start.elapsed(), "prover" => "get_aggregated_proof_ranges");
+        Ok(ranges)
+    }
+
     // Removes witnesses for blocks with number greater than `last_block`
     pub async fn remove_witnesses(&mut self, last_block: BlockNumber) -> QueryResult<()> {
         let start = Instant::now();
@@ -3,6 +3,7 @@ use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 // Workspace imports
+use zksync_types::prover::{ProverJobStatus, ProverJobType, ProverJobWorkload, ProverStats};
 // Local imports
 
 #[derive(Debug, FromRow)]
@@ -53,7 +54,10 @@ pub struct IntegerNumber {
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct StorageBlockWitness {
     pub block: i64,
-    pub witness: String,
+    /// Witness in the legacy JSON encoding.
+    pub witness: Option<String>,
+    /// Witness in the binary encoding, see `zksync_crypto::binary_encoding`.
+    pub witness_binary: Option<Vec<u8>>,
 }
 
 #[derive(Debug, FromRow)]
@@ -68,4 +72,69 @@ pub struct StorageProverJobQueue {
     pub first_block: i64,
     pub last_block: i64,
     pub job_data: serde_json::Value,
+    pub retry_count: i32,
+    pub failure_reason: Option<String>,
+    pub leased_at: Option<DateTime<Utc>>,
+    pub lease_expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct StoredProverStats {
+    pub prover_name: String,
+    pub jobs_started: i64,
+    pub jobs_completed: i64,
+    pub jobs_failed: i64,
+    pub proving_time_ms: i64,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+impl From<StoredProverStats> for ProverStats {
+    fn from(stats: StoredProverStats) -> Self {
+        Self {
+            prover_name: stats.prover_name,
+            jobs_started: stats.jobs_started as u64,
+            jobs_completed: stats.jobs_completed as u64,
+            jobs_failed: stats.jobs_failed as u64,
+            proving_time_ms: stats.proving_time_ms as u64,
+            last_seen_at: stats.last_seen_at,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+pub struct StorageProverJobWorkload {
+    pub job_status: i32,
+    pub job_type: String,
+    pub first_block: i64,
+    pub last_block: i64,
+    pub block_size: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub leased_at: Option<DateTime<Utc>>,
+}
+
+impl From<StorageProverJobWorkload> for ProverJobWorkload {
+    fn from(job: StorageProverJobWorkload) -> Self {
+        let (job_type, size) = if job.job_type == ProverJobType::AggregatedProof.to_string() {
+            let blocks = job.last_block - job.first_block + 1;
+            (ProverJobType::AggregatedProof, blocks as usize)
+        } else {
+            let chunks = job.block_size.unwrap_or_default();
+            (ProverJobType::SingleProof, chunks as usize)
+        };
+        // Jobs keep the lease timestamp after completion, so it can be used to measure proving time.
+        let proving_time = if job.job_status == ProverJobStatus::Done.to_number() {
+            job.leased_at
+                .and_then(|leased_at| (job.updated_at - leased_at).to_std().ok())
+        } else {
+            None
+        };
+
+        Self {
+            job_type,
+            size,
+            created_at: job.created_at,
+            proving_time,
+        }
+    }
 }
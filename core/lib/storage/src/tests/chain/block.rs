@@ -1275,7 +1275,7 @@ async fn test_remove_new_account_tree_cache(mut storage: StorageProcessor<'_>) -
             ))
             .await?;
         BlockSchema(&mut storage)
-            .store_account_tree_cache(BlockNumber(block_number), serde_json::Value::default())
+            .store_account_tree_cache(BlockNumber(block_number), &serde_json::Value::default())
             .await?;
     }
 
@@ -1286,11 +1286,11 @@ async fn test_remove_new_account_tree_cache(mut storage: StorageProcessor<'_>) -
 
     // Check if account tree cache for the 2nd block is present, and for the 3rd is not.
     assert!(BlockSchema(&mut storage)
-        .get_account_tree_cache_block(BlockNumber(2))
+        .get_account_tree_cache_block::<serde_json::Value>(BlockNumber(2))
         .await?
         .is_some());
     assert!(BlockSchema(&mut storage)
-        .get_account_tree_cache_block(BlockNumber(3))
+        .get_account_tree_cache_block::<serde_json::Value>(BlockNumber(3))
         .await?
         .is_none());
 
@@ -1361,7 +1361,7 @@ async fn test_remove_old_account_tree_cache(mut storage: StorageProcessor<'_>) -
             ))
             .await?;
         BlockSchema(&mut storage)
-            .store_account_tree_cache(BlockNumber(block_number), serde_json::Value::default())
+            .store_account_tree_cache(BlockNumber(block_number), &serde_json::Value::default())
             .await?;
     }
 
@@ -1372,11 +1372,11 @@ async fn test_remove_old_account_tree_cache(mut storage: StorageProcessor<'_>) -
 
     // Check that the account tree cache for block #3 is present, and for block #1 is not.
     assert!(BlockSchema(&mut storage)
-        .get_account_tree_cache_block(BlockNumber(3))
+        .get_account_tree_cache_block::<serde_json::Value>(BlockNumber(3))
         .await?
         .is_some());
     assert!(BlockSchema(&mut storage)
-        .get_account_tree_cache_block(BlockNumber(1))
+        .get_account_tree_cache_block::<serde_json::Value>(BlockNumber(1))
         .await?
         .is_none());
 
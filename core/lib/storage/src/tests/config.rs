@@ -17,3 +17,40 @@ async fn test_load_config(mut storage: StorageProcessor<'_>) -> QueryResult<()>
 
     Ok(())
 }
+
+/// Runtime parameters should be stored, updated and removed.
+#[db_test]
+async fn test_runtime_parameters(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let parameters = storage.config_schema().load_runtime_parameters().await?;
+    assert!(parameters.is_empty());
+
+    storage
+        .config_schema()
+        .store_runtime_parameter("scale_fee_percent", serde_json::json!(150))
+        .await?;
+    storage
+        .config_schema()
+        .store_runtime_parameter("subsidized_ips", serde_json::json!(["127.0.0.1"]))
+        .await?;
+    // Storing the parameter once again should replace the value.
+    storage
+        .config_schema()
+        .store_runtime_parameter("scale_fee_percent", serde_json::json!(120))
+        .await?;
+
+    let parameters = storage.config_schema().load_runtime_parameters().await?;
+    assert_eq!(parameters.len(), 2);
+    assert_eq!(parameters[0].name, "scale_fee_percent");
+    assert_eq!(parameters[0].value, serde_json::json!(120));
+    assert_eq!(parameters[1].name, "subsidized_ips");
+
+    storage
+        .config_schema()
+        .remove_runtime_parameter("scale_fee_percent")
+        .await?;
+    let parameters = storage.config_schema().load_runtime_parameters().await?;
+    assert_eq!(parameters.len(), 1);
+    assert_eq!(parameters[0].name, "subsidized_ips");
+
+    Ok(())
+}
@@ -1,10 +1,12 @@
+// Built-in imports
+use std::time::Duration;
 // External imports
 use anyhow::format_err;
 use once_cell::sync::Lazy;
 use tokio::sync::Mutex;
 // Workspace imports
 use zksync_types::{
-    prover::{ProverJob, ProverJobType},
+    prover::{ProverJob, ProverJobType, AGGREGATED_PROOF_JOB_PRIORITY, SINGLE_PROOF_JOB_PRIORITY},
     BlockNumber,
 };
 // Local imports
@@ -14,9 +16,11 @@ use crate::{prover::ProverSchema, QueryResult, StorageProcessor};
 
 static MUTEX: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 
+const LEASE_DURATION: Duration = Duration::from_secs(60);
+
 async fn get_idle_job_from_queue(storage: &mut StorageProcessor<'_>) -> QueryResult<ProverJob> {
     let job = ProverSchema(storage)
-        .get_idle_prover_job_from_job_queue()
+        .get_idle_prover_job_from_job_queue("test_prover", LEASE_DURATION)
         .await?;
 
     job.ok_or_else(|| format_err!("expect idle job from job queue"))
@@ -35,6 +39,170 @@ async fn test_prover_job_queue(mut storage: StorageProcessor<'_>) -> QueryResult
     Ok(())
 }
 
+/// Checks that jobs are leased according to their priorities, expired leases and reported failures
+/// are retried a limited number of times, and the prover statistics are collected.
+#[db_test]
+async fn test_prover_job_leases(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Lock to prevent database deadlock
+    let _lock = MUTEX.lock().await;
+    const MAX_RETRIES: u32 = 2;
+
+    for (first_block, last_block, priority, job_type) in vec![
+        (2, 2, SINGLE_PROOF_JOB_PRIORITY, ProverJobType::SingleProof),
+        (1, 1, SINGLE_PROOF_JOB_PRIORITY, ProverJobType::SingleProof),
+        (
+            1,
+            2,
+            AGGREGATED_PROOF_JOB_PRIORITY,
+            ProverJobType::AggregatedProof,
+        ),
+    ] {
+        ProverSchema(&mut storage)
+            .add_prover_job_to_job_queue(
+                BlockNumber(first_block),
+                BlockNumber(last_block),
+                Default::default(),
+                priority,
+                job_type,
+            )
+            .await?;
+    }
+
+    // Aggregated proofs go first, then single proofs for the oldest blocks.
+    let aggregated_job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(
+        (aggregated_job.first_block, aggregated_job.last_block),
+        (BlockNumber(1), BlockNumber(2))
+    );
+    let first_block_job = ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue("second_prover", Duration::from_secs(0))
+        .await?
+        .unwrap();
+    assert_eq!(first_block_job.first_block, BlockNumber(1));
+
+    // The lease of the second prover has expired, so the job is returned to the queue.
+    ProverSchema(&mut storage)
+        .mark_stale_jobs_as_idle(MAX_RETRIES)
+        .await?;
+    assert!(
+        !ProverSchema(&mut storage)
+            .record_prover_is_working(first_block_job.job_id, "second_prover", LEASE_DURATION)
+            .await?
+    );
+    assert!(
+        ProverSchema(&mut storage)
+            .record_prover_is_working(aggregated_job.job_id, "test_prover", LEASE_DURATION)
+            .await?
+    );
+
+    // The job is given again and fails for the second time, so it's not retried anymore.
+    let retried_job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(retried_job.job_id, first_block_job.job_id);
+    // The failure is only accepted from the prover which holds the lease.
+    assert!(
+        !ProverSchema(&mut storage)
+            .record_prover_job_failure(
+                retried_job.job_id,
+                "second_prover",
+                "bad witness",
+                MAX_RETRIES,
+            )
+            .await?
+    );
+    assert!(
+        ProverSchema(&mut storage)
+            .record_prover_job_failure(
+                retried_job.job_id,
+                "test_prover",
+                "bad witness",
+                MAX_RETRIES,
+            )
+            .await?
+    );
+
+    let failed_jobs = ProverSchema(&mut storage).load_failed_prover_jobs().await?;
+    assert_eq!(failed_jobs.len(), 1);
+    assert_eq!(failed_jobs[0].job_id, first_block_job.job_id);
+    assert_eq!(failed_jobs[0].retry_count, MAX_RETRIES);
+    assert_eq!(
+        failed_jobs[0].failure_reason.as_deref(),
+        Some("bad witness")
+    );
+    assert_eq!(ProverSchema(&mut storage).pending_jobs_count().await?, 2);
+
+    // The failed job is skipped.
+    let last_job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(last_job.first_block, BlockNumber(2));
+    ProverSchema(&mut storage)
+        .store_proof(
+            last_job.job_id,
+            last_job.first_block,
+            &get_sample_single_proof(),
+        )
+        .await?;
+    assert!(ProverSchema(&mut storage)
+        .get_idle_prover_job_from_job_queue("test_prover", LEASE_DURATION)
+        .await?
+        .is_none());
+
+    let stats = ProverSchema(&mut storage).load_prover_stats().await?;
+    assert_eq!(stats.len(), 2);
+    let (second_prover, test_prover) = (&stats[0], &stats[1]);
+    assert_eq!(second_prover.prover_name, "second_prover");
+    assert_eq!(
+        (
+            second_prover.jobs_started,
+            second_prover.jobs_completed,
+            second_prover.jobs_failed
+        ),
+        (1, 0, 1)
+    );
+    assert_eq!(test_prover.prover_name, "test_prover");
+    assert_eq!(
+        (
+            test_prover.jobs_started,
+            test_prover.jobs_completed,
+            test_prover.jobs_failed
+        ),
+        (3, 1, 1)
+    );
+
+    // Only the aggregated job is still pending, and the proving time is known for the completed one.
+    let pending_workload = ProverSchema(&mut storage)
+        .load_pending_jobs_workload()
+        .await?;
+    assert_eq!(pending_workload.len(), 1);
+    assert_eq!(pending_workload[0].job_type, ProverJobType::AggregatedProof);
+    assert_eq!(pending_workload[0].size, 2);
+    assert!(pending_workload[0].proving_time.is_none());
+    let completed_workload = ProverSchema(&mut storage)
+        .load_completed_jobs_workload(10)
+        .await?;
+    assert_eq!(completed_workload.len(), 1);
+    assert_eq!(completed_workload[0].job_type, ProverJobType::SingleProof);
+    assert!(completed_workload[0].proving_time.is_some());
+
+    // Only the failed jobs can be returned to the queue manually.
+    assert!(
+        !ProverSchema(&mut storage)
+            .requeue_failed_prover_job(aggregated_job.job_id)
+            .await?
+    );
+    assert!(
+        ProverSchema(&mut storage)
+            .requeue_failed_prover_job(first_block_job.job_id)
+            .await?
+    );
+    assert!(ProverSchema(&mut storage)
+        .load_failed_prover_jobs()
+        .await?
+        .is_empty());
+    let requeued_job = get_idle_job_from_queue(&mut storage).await?;
+    assert_eq!(requeued_job.job_id, first_block_job.job_id);
+
+    Ok(())
+}
+
 /// Checks that the single and aggregated proof can be stored and loaded.
 async fn test_store_proof(storage: &mut StorageProcessor<'_>) -> QueryResult<()> {
     // Attempt to load the proof that was not stored should result in None.
@@ -183,13 +351,13 @@ async fn pending_jobs_count(storage: &mut StorageProcessor<'_>) -> QueryResult<(
 
     // Record prover is working and stopped it.
     ProverSchema(storage)
-        .record_prover_is_working(first_job.job_id, "test_prover")
+        .record_prover_is_working(first_job.job_id, "test_prover", LEASE_DURATION)
         .await?;
     ProverSchema(storage)
-        .record_prover_is_working(second_job.job_id, "test_prover")
+        .record_prover_is_working(second_job.job_id, "test_prover", LEASE_DURATION)
         .await?;
     ProverSchema(storage)
-        .record_prover_is_working(third_job.job_id, "test_prover")
+        .record_prover_is_working(third_job.job_id, "test_prover", LEASE_DURATION)
         .await?;
 
     // Store one proof and then turn off the prover.
@@ -221,7 +389,7 @@ async fn test_store_witness(mut storage: StorageProcessor<'_>) -> QueryResult<()
     // No witness stored for the block.
     assert!(storage
         .prover_schema()
-        .get_witness(BLOCK_NUMBER)
+        .get_witness::<String>(BLOCK_NUMBER)
         .await?
         .is_none());
 
@@ -238,39 +406,56 @@ async fn test_store_witness(mut storage: StorageProcessor<'_>) -> QueryResult<()
 
     // Store the witness.
     let expected = String::from("test");
-    let witness = serde_json::to_value(expected.clone()).unwrap();
     storage
         .prover_schema()
-        .store_witness(BLOCK_NUMBER, witness)
+        .store_witness(BLOCK_NUMBER, &expected)
         .await?;
 
     // Now load it.
-    let loaded = storage
-        .prover_schema()
-        .get_witness(BLOCK_NUMBER)
-        .await?
-        .map(|value| serde_json::from_value(value).unwrap());
+    let loaded = storage.prover_schema().get_witness(BLOCK_NUMBER).await?;
     assert_eq!(loaded.as_ref(), Some(&expected));
 
     // Do nothing on conflict.
     let not_expected = String::from("__test");
-    let witness = serde_json::to_value(expected.clone()).unwrap();
     storage
         .prover_schema()
-        .store_witness(BLOCK_NUMBER, witness)
+        .store_witness(BLOCK_NUMBER, &not_expected)
         .await?;
 
-    let loaded = storage
-        .prover_schema()
-        .get_witness(BLOCK_NUMBER)
-        .await?
-        .map(|value| serde_json::from_value(value).unwrap());
+    let loaded = storage.prover_schema().get_witness(BLOCK_NUMBER).await?;
     assert_ne!(loaded, Some(not_expected));
     assert_eq!(loaded, Some(expected));
 
     Ok(())
 }
 
+/// Checks that the witnesses stored in the legacy JSON encoding can still be loaded.
+#[db_test]
+async fn test_load_json_witness(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    const BLOCK_NUMBER: BlockNumber = BlockNumber(1);
+
+    storage
+        .chain()
+        .block_schema()
+        .save_full_block(gen_sample_block(BLOCK_NUMBER, 100, Default::default()))
+        .await?;
+    sqlx::query!(
+        "INSERT INTO block_witness (block, witness) VALUES ($1, $2)",
+        i64::from(*BLOCK_NUMBER),
+        serde_json::to_string("test").unwrap()
+    )
+    .execute(storage.conn())
+    .await?;
+
+    let loaded = storage
+        .prover_schema()
+        .get_witness::<String>(BLOCK_NUMBER)
+        .await?;
+    assert_eq!(loaded.as_deref(), Some("test"));
+
+    Ok(())
+}
+
 /// Checks that block witnesses are removed correctly.
 #[db_test]
 async fn test_remove_witnesses(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -285,10 +470,9 @@ async fn test_remove_witnesses(mut storage: StorageProcessor<'_>) -> QueryResult
                 Default::default(),
             ))
             .await?;
-        let witness = serde_json::to_value(String::from("test")).unwrap();
         storage
             .prover_schema()
-            .store_witness(BlockNumber(block_number), witness)
+            .store_witness(BlockNumber(block_number), &String::from("test"))
             .await?;
     }
     // Remove witnesses for the 4th and 5th blocks.
@@ -300,12 +484,12 @@ async fn test_remove_witnesses(mut storage: StorageProcessor<'_>) -> QueryResult
     // Check that there is a witness for the 3rd block and no witness for the 4th.
     assert!(storage
         .prover_schema()
-        .get_witness(BlockNumber(3))
+        .get_witness::<String>(BlockNumber(3))
         .await?
         .is_some());
     assert!(storage
         .prover_schema()
-        .get_witness(BlockNumber(4))
+        .get_witness::<String>(BlockNumber(4))
         .await?
         .is_none());
 
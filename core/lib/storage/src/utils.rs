@@ -1,4 +1,6 @@
 use crate::{QueryResult, StorageProcessor};
+use serde::de::DeserializeOwned;
+use zksync_crypto::binary_encoding;
 use zksync_types::{Address, ZkSyncTx};
 
 pub fn address_to_stored_string(address: &Address) -> String {
@@ -12,6 +14,19 @@ pub fn stored_str_address_to_address(address: &str) -> Address {
         .expect("failed to parse stored db address")
 }
 
+/// Decodes the value stored either in the binary encoding or in the legacy JSON one.
+/// Rows stored before the binary encoding was introduced only have the JSON value.
+pub fn decode_binary_or_json<T: DeserializeOwned>(
+    binary: Option<&[u8]>,
+    json: Option<&str>,
+) -> anyhow::Result<T> {
+    match (binary, json) {
+        (Some(binary), _) => Ok(binary_encoding::decode(binary)?),
+        (None, Some(json)) => Ok(serde_json::from_str(json)?),
+        (None, None) => Err(anyhow::format_err!("Stored value is empty")),
+    }
+}
+
 pub async fn affected_accounts(
     tx: &ZkSyncTx,
     storage: &mut StorageProcessor<'_>,
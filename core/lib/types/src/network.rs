@@ -5,7 +5,7 @@
 use std::{fmt, str::FromStr};
 
 // External uses
-use serde::{Deserialize, Serialize};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 
 // Workspace uses
 
@@ -13,8 +13,9 @@ use serde::{Deserialize, Serialize};
 
 /// Network to be used for a zkSync client.
 ///
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
-#[serde(rename_all = "camelCase")]
+/// Serialized as its string representation (e.g. `rinkeby` or `custom-1337`),
+/// so it can be loaded from the environment variables.
+#[derive(Debug, SerializeDisplay, DeserializeFromStr, Clone, Copy, PartialEq, Eq)]
 pub enum Network {
     /// Ethereum Mainnet.
     Mainnet,
@@ -24,6 +25,8 @@ pub enum Network {
     Ropsten,
     /// Self-hosted Ethereum & zkSync networks.
     Localhost,
+    /// Network with the given Ethereum chain ID which is not known in advance.
+    Custom(u64),
     /// Unknown network type.
     Unknown,
     /// Test network for testkit purposes
@@ -39,8 +42,15 @@ impl FromStr for Network {
             "rinkeby" => Self::Rinkeby,
             "ropsten" => Self::Ropsten,
             "localhost" => Self::Localhost,
+            "unknown" => Self::Unknown,
             "test" => Self::Test,
-            another => return Err(another.to_owned()),
+            another => match another
+                .strip_prefix("custom-")
+                .and_then(|chain_id| chain_id.parse().ok())
+            {
+                Some(chain_id) => Self::Custom(chain_id),
+                None => return Err(another.to_owned()),
+            },
         })
     }
 }
@@ -52,6 +62,7 @@ impl fmt::Display for Network {
             Self::Rinkeby => write!(f, "rinkeby"),
             Self::Ropsten => write!(f, "ropsten"),
             Self::Localhost => write!(f, "localhost"),
+            Self::Custom(chain_id) => write!(f, "custom-{}", chain_id),
             Self::Unknown => write!(f, "unknown"),
             Self::Test => write!(f, "test"),
         }
@@ -60,14 +71,64 @@ impl fmt::Display for Network {
 
 impl Network {
     /// Returns the network chain ID on the Ethereum side.
-    pub fn chain_id(self) -> u8 {
+    /// The chain ID of the unknown and test networks is not defined.
+    pub fn chain_id(self) -> Option<u64> {
         match self {
-            Network::Mainnet => 1,
-            Network::Ropsten => 3,
-            Network::Rinkeby => 4,
-            Network::Localhost => 9,
-            Network::Unknown => panic!("Unknown chain ID"),
-            Network::Test => panic!("Test chain ID"),
+            Network::Mainnet => Some(1),
+            Network::Ropsten => Some(3),
+            Network::Rinkeby => Some(4),
+            Network::Localhost => Some(9),
+            Network::Custom(chain_id) => Some(chain_id),
+            Network::Unknown | Network::Test => None,
         }
     }
+
+    /// Returns the network with the given chain ID on the Ethereum side.
+    /// Chain IDs that don't belong to any of the known networks map to `Network::Custom`.
+    pub fn from_chain_id(chain_id: u64) -> Self {
+        match chain_id {
+            1 => Network::Mainnet,
+            3 => Network::Ropsten,
+            4 => Network::Rinkeby,
+            9 => Network::Localhost,
+            _ => Network::Custom(chain_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_network_chain_id() {
+        let chain_id = 1337;
+        let network = Network::from_chain_id(chain_id);
+        assert_eq!(network, Network::Custom(chain_id));
+        assert_eq!(network.chain_id(), Some(chain_id));
+        assert_eq!(network.to_string().parse::<Network>(), Ok(network));
+
+        assert_eq!(Network::from_chain_id(4), Network::Rinkeby);
+        assert_eq!(Network::Unknown.chain_id(), None);
+    }
+
+    #[test]
+    fn network_serde() {
+        let networks = [
+            (Network::Mainnet, "\"mainnet\""),
+            (Network::Rinkeby, "\"rinkeby\""),
+            (Network::Ropsten, "\"ropsten\""),
+            (Network::Localhost, "\"localhost\""),
+            (Network::Custom(1337), "\"custom-1337\""),
+            (Network::Unknown, "\"unknown\""),
+            (Network::Test, "\"test\""),
+        ];
+        for (network, json) in networks.iter() {
+            assert_eq!(serde_json::to_string(network).unwrap(), *json);
+            assert_eq!(serde_json::from_str::<Network>(json).unwrap(), *network);
+        }
+
+        assert!(serde_json::from_str::<Network>("\"custom-\"").is_err());
+        assert!(serde_json::from_str::<Network>("{\"custom\":1337}").is_err());
+    }
 }
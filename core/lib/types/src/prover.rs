@@ -1,3 +1,6 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use thiserror::Error;
 use zksync_basic_types::BlockNumber;
 
@@ -6,6 +9,8 @@ pub enum ProverJobStatus {
     Idle = 0,
     InProgress = 1,
     Done = 2,
+    /// Job has exceeded the retry limit and won't be given to provers anymore.
+    Failed = 3,
 }
 
 impl ProverJobStatus {
@@ -14,6 +19,7 @@ impl ProverJobStatus {
             ProverJobStatus::Idle => 0,
             ProverJobStatus::InProgress => 1,
             ProverJobStatus::Done => 2,
+            ProverJobStatus::Failed => 3,
         }
     }
 
@@ -22,11 +28,14 @@ impl ProverJobStatus {
             0 => Self::Idle,
             1 => Self::InProgress,
             2 => Self::Done,
+            3 => Self::Failed,
             _ => return Err(IncorrectProverJobStatus(num)),
         })
     }
 }
 
+// Jobs with the lower priority value are given to provers first.
+// Among the jobs with the same priority, jobs for the oldest blocks go first.
 pub const SINGLE_PROOF_JOB_PRIORITY: i32 = 1;
 pub const AGGREGATED_PROOF_JOB_PRIORITY: i32 = 0;
 
@@ -54,7 +63,7 @@ impl ProverJob {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProverJobType {
     SingleProof,
     AggregatedProof,
@@ -69,6 +78,49 @@ impl ToString for ProverJobType {
     }
 }
 
+/// Amount of work required by the prover job, used to forecast the proving workload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProverJobWorkload {
+    pub job_type: ProverJobType,
+    /// Amount of chunks in the block for a single proof, amount of blocks for an aggregated proof.
+    pub size: usize,
+    pub created_at: DateTime<Utc>,
+    /// Time spent by the prover on the job, known only for the completed jobs.
+    pub proving_time: Option<Duration>,
+}
+
+/// Job which has exceeded the retry limit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedProverJob {
+    pub job_id: i32,
+    pub job_type: String,
+    pub first_block: BlockNumber,
+    pub last_block: BlockNumber,
+    pub retry_count: u32,
+    pub failure_reason: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Throughput and failure statistics of a single prover.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProverStats {
+    pub prover_name: String,
+    pub jobs_started: u64,
+    pub jobs_completed: u64,
+    /// Jobs reported as failed by the prover or lost due to the expired lease.
+    pub jobs_failed: u64,
+    /// Total time spent on the completed jobs.
+    pub proving_time_ms: u64,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+impl ProverStats {
+    /// Average time spent on a completed job.
+    pub fn average_proving_time_ms(&self) -> Option<u64> {
+        self.proving_time_ms.checked_div(self.jobs_completed)
+    }
+}
+
 #[derive(Debug, Error, PartialEq)]
 #[error("Incorrect ProverJobStatus number: {0}")]
 pub struct IncorrectProverJobStatus(pub i32);
@@ -1,4 +1,5 @@
 use num::{BigUint, Zero};
+use std::convert::TryInto;
 use std::fmt::{Display, Formatter};
 
 use parity_crypto::Keccak256;
@@ -12,7 +13,10 @@ use zksync_crypto::{
 };
 use zksync_utils::{format_units, BigUintSerdeAsRadix10Str};
 
-use super::{PackedEthSignature, TimeRange, TxSignature, VerifiedSignatureCache};
+use super::{
+    Eip712Error, Eip712TypedStructure, Eip712Value, PackedEthSignature, TimeRange, TxSignature,
+    VerifiedSignatureCache,
+};
 use crate::tx::error::{
     FEE_AMOUNT_IS_NOT_PACKABLE, INVALID_AUTH_DATA, WRONG_ACCOUNT_ID, WRONG_FEE_ERROR,
     WRONG_SIGNATURE, WRONG_TIME_RANGE, WRONG_TOKEN_FOR_PAYING_FEE,
@@ -496,6 +500,34 @@ impl ChangePubKey {
     }
 }
 
+impl Eip712TypedStructure for ChangePubKey {
+    const TYPE_NAME: &'static str = "ChangePubKey";
+    const MEMBERS: &'static [(&'static str, &'static str)] = &[
+        ("accountId", "uint32"),
+        ("account", "address"),
+        ("newPkHash", "bytes20"),
+        ("feeToken", "uint32"),
+        ("fee", "uint256"),
+        ("nonce", "uint32"),
+        ("validFrom", "uint64"),
+        ("validUntil", "uint64"),
+    ];
+
+    fn values(&self) -> Result<Vec<Eip712Value>, Eip712Error> {
+        let time_range = self.time_range.unwrap_or_default();
+        Ok(vec![
+            self.account_id.0.into(),
+            self.account.into(),
+            Eip712Value::FixedBytes(self.new_pk_hash.data.to_vec()),
+            self.fee_token.0.into(),
+            (&self.fee).try_into()?,
+            self.nonce.0.into(),
+            time_range.valid_from.into(),
+            time_range.valid_until.into(),
+        ])
+    }
+}
+
 #[derive(Error, Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum TransactionError {
     InvalidAuthData,
@@ -1,5 +1,6 @@
 use num::{BigUint, Zero};
 use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
 use std::fmt::{Display, Formatter};
 use thiserror::Error;
 
@@ -10,7 +11,7 @@ use zksync_crypto::{
 };
 use zksync_utils::{format_units, BigUintSerdeAsRadix10Str};
 
-use super::{TxSignature, VerifiedSignatureCache};
+use super::{Eip712Error, Eip712TypedStructure, Eip712Value, TxSignature, VerifiedSignatureCache};
 use crate::tx::error::{
     FEE_AMOUNT_IS_NOT_PACKABLE, WRONG_ACCOUNT_ID, WRONG_FEE_ERROR, WRONG_SIGNATURE,
     WRONG_TIME_RANGE, WRONG_TOKEN, WRONG_TOKEN_FOR_PAYING_FEE,
@@ -247,6 +248,32 @@ impl ForcedExit {
     }
 }
 
+impl Eip712TypedStructure for ForcedExit {
+    const TYPE_NAME: &'static str = "ForcedExit";
+    const MEMBERS: &'static [(&'static str, &'static str)] = &[
+        ("initiatorAccountId", "uint32"),
+        ("target", "address"),
+        ("token", "uint32"),
+        ("fee", "uint256"),
+        ("nonce", "uint32"),
+        ("validFrom", "uint64"),
+        ("validUntil", "uint64"),
+    ];
+
+    fn values(&self) -> Result<Vec<Eip712Value>, Eip712Error> {
+        let time_range = self.time_range.unwrap_or_default();
+        Ok(vec![
+            self.initiator_account_id.0.into(),
+            self.target.into(),
+            self.token.0.into(),
+            (&self.fee).try_into()?,
+            self.nonce.0.into(),
+            time_range.valid_from.into(),
+            time_range.valid_until.into(),
+        ])
+    }
+}
+
 #[derive(Error, Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum TransactionError {
     WrongFee,
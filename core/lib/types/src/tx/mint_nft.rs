@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
 use std::fmt::{Display, Formatter};
 
 use num::{BigUint, Zero};
@@ -21,7 +22,7 @@ use crate::tx::error::{
 use crate::tx::version::TxVersion;
 use crate::{
     helpers::{is_fee_amount_packable, pack_fee_amount},
-    tx::{TxSignature, VerifiedSignatureCache},
+    tx::{Eip712Error, Eip712TypedStructure, Eip712Value, TxSignature, VerifiedSignatureCache},
     AccountId, Address, Nonce, PubKeyHash, TokenId, H256,
 };
 
@@ -213,6 +214,31 @@ impl MintNFT {
     }
 }
 
+impl Eip712TypedStructure for MintNFT {
+    const TYPE_NAME: &'static str = "MintNFT";
+    const MEMBERS: &'static [(&'static str, &'static str)] = &[
+        ("creatorId", "uint32"),
+        ("creatorAddress", "address"),
+        ("contentHash", "bytes32"),
+        ("recipient", "address"),
+        ("feeToken", "uint32"),
+        ("fee", "uint256"),
+        ("nonce", "uint32"),
+    ];
+
+    fn values(&self) -> Result<Vec<Eip712Value>, Eip712Error> {
+        Ok(vec![
+            self.creator_id.0.into(),
+            self.creator_address.into(),
+            self.content_hash.into(),
+            self.recipient.into(),
+            self.fee_token.0.into(),
+            (&self.fee).try_into()?,
+            self.nonce.0.into(),
+        ])
+    }
+}
+
 #[derive(Error, Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum TransactionError {
     WrongFee,
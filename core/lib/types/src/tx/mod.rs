@@ -38,7 +38,13 @@ pub use self::{
 // Re-export primitives associated with transactions.
 pub use self::primitives::{
     eip1271_signature::EIP1271Signature,
-    eth_batch_sign_data::EthBatchSignData,
+    eip712_signature::{
+        Eip712Domain, Eip712Error, Eip712TypedData, Eip712TypedStructure, Eip712Value,
+        EIP712_DOMAIN_NAME, EIP712_DOMAIN_VERSION,
+    },
+    eth_batch_sign_data::{
+        Eip712Batch, Eip712BatchError, Eip712BatchTransaction, EthBatchSignData,
+    },
     eth_batch_signature::EthBatchSignatures,
     eth_signature::{TxEthSignature, TxEthSignatureVariant},
     packed_eth_signature::PackedEthSignature,
@@ -0,0 +1,308 @@
+//! Utilities for the EIP-712 typed structured data signatures.
+//!
+//! Unlike the human-readable messages signed with `eth_sign`, typed data refers to the
+//! tokens by their IDs and to the amounts in their base units, so the signature can be
+//! verified without the token metadata.
+
+use std::convert::TryFrom;
+
+use num::BigUint;
+use parity_crypto::Keccak256;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+use zksync_basic_types::{Address, H256, U256};
+
+/// Name of the signing domain, shown to the user by the wallet.
+pub const EIP712_DOMAIN_NAME: &str = "zkSync";
+/// Version of the signing domain. Must be changed along with the signed structures layout.
+pub const EIP712_DOMAIN_VERSION: &str = "1";
+
+const EIP712_DOMAIN_TYPE_NAME: &str = "EIP712Domain";
+const EIP712_DOMAIN_MEMBERS: &[(&str, &str)] = &[
+    ("name", "string"),
+    ("version", "string"),
+    ("chainId", "uint256"),
+    ("verifyingContract", "address"),
+];
+
+/// Signing domain of the zkSync typed data. Binds signatures to the specific network
+/// and zkSync contract, so they can't be replayed on another deployment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip712Domain {
+    /// Chain ID of the Ethereum network.
+    pub chain_id: u64,
+    /// Address of the zkSync contract.
+    pub verifying_contract: Address,
+}
+
+impl Eip712Domain {
+    pub fn new(chain_id: u64, verifying_contract: Address) -> Self {
+        Self {
+            chain_id,
+            verifying_contract,
+        }
+    }
+
+    /// Returns the domain separator, which is the `hashStruct` of the domain.
+    pub fn separator(&self) -> H256 {
+        let type_hash = encode_type(EIP712_DOMAIN_TYPE_NAME, EIP712_DOMAIN_MEMBERS)
+            .as_bytes()
+            .keccak256();
+
+        let mut bytes = Vec::with_capacity(32 * 5);
+        bytes.extend_from_slice(&type_hash);
+        bytes.extend_from_slice(&EIP712_DOMAIN_NAME.as_bytes().keccak256());
+        bytes.extend_from_slice(&EIP712_DOMAIN_VERSION.as_bytes().keccak256());
+        bytes.extend_from_slice(&Eip712Value::from(self.chain_id).encode());
+        bytes.extend_from_slice(&Eip712Value::Address(self.verifying_contract).encode());
+        bytes.keccak256().into()
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "name": EIP712_DOMAIN_NAME,
+            "version": EIP712_DOMAIN_VERSION,
+            "chainId": self.chain_id,
+            "verifyingContract": format!("{:?}", self.verifying_contract),
+        })
+    }
+}
+
+/// Value of the typed structure member.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Eip712Value {
+    Address(Address),
+    Uint(U256),
+    Bool(bool),
+    /// Fixed-size byte array (`bytes1`..`bytes32`).
+    FixedBytes(Vec<u8>),
+    /// Nested structure, represented by its `hashStruct` and JSON value.
+    Struct(H256, Value),
+    /// Array of the nested structures, represented by their `hashStruct` and JSON value.
+    StructArray(Vec<(H256, Value)>),
+}
+
+impl Eip712Value {
+    /// Creates a value out of the nested typed structure.
+    pub fn structure<T: Eip712TypedStructure>(item: &T) -> Result<Self, Eip712Error> {
+        Ok(Self::Struct(item.hash_struct()?, item.to_json()?))
+    }
+
+    /// Creates an array value out of the typed structures.
+    pub fn struct_array<T: Eip712TypedStructure>(items: &[T]) -> Result<Self, Eip712Error> {
+        let items = items
+            .iter()
+            .map(|item| Ok((item.hash_struct()?, item.to_json()?)))
+            .collect::<Result<_, Eip712Error>>()?;
+        Ok(Self::StructArray(items))
+    }
+
+    /// Encodes the value as a 32-byte word according to the `encodeData` rules.
+    pub fn encode(&self) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        match self {
+            Self::Address(address) => word[12..].copy_from_slice(address.as_bytes()),
+            Self::Uint(value) => value.to_big_endian(&mut word),
+            Self::Bool(value) => word[31] = *value as u8,
+            Self::FixedBytes(bytes) => word[..bytes.len()].copy_from_slice(bytes),
+            Self::Struct(hash, _) => word = hash.to_fixed_bytes(),
+            Self::StructArray(items) => {
+                let hashes: Vec<u8> = items
+                    .iter()
+                    .flat_map(|(hash, _)| hash.as_bytes().to_vec())
+                    .collect();
+                word = hashes.keccak256();
+            }
+        }
+        word
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            Self::Address(address) => Value::String(format!("{:?}", address)),
+            Self::Uint(value) => Value::String(value.to_string()),
+            Self::Bool(value) => Value::Bool(*value),
+            Self::FixedBytes(bytes) => Value::String(format!("0x{}", hex::encode(bytes))),
+            Self::Struct(_, json) => json.clone(),
+            Self::StructArray(items) => {
+                Value::Array(items.iter().map(|(_, json)| json.clone()).collect())
+            }
+        }
+    }
+}
+
+impl From<Address> for Eip712Value {
+    fn from(value: Address) -> Self {
+        Self::Address(value)
+    }
+}
+
+impl From<bool> for Eip712Value {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<u32> for Eip712Value {
+    fn from(value: u32) -> Self {
+        Self::Uint(value.into())
+    }
+}
+
+impl From<u64> for Eip712Value {
+    fn from(value: u64) -> Self {
+        Self::Uint(value.into())
+    }
+}
+
+impl TryFrom<&BigUint> for Eip712Value {
+    type Error = Eip712Error;
+
+    fn try_from(value: &BigUint) -> Result<Self, Self::Error> {
+        // Amounts come from the user and are only checked for packability later,
+        // so they may not fit into `uint256`.
+        if value.bits() > 256 {
+            return Err(Eip712Error::UintOverflow(value.clone()));
+        }
+        Ok(Self::Uint(U256::from_big_endian(&value.to_bytes_be())))
+    }
+}
+
+impl From<H256> for Eip712Value {
+    fn from(value: H256) -> Self {
+        Self::FixedBytes(value.as_bytes().to_vec())
+    }
+}
+
+/// Structure which can be signed as EIP-712 typed data.
+pub trait Eip712TypedStructure {
+    /// Name of the structure type.
+    const TYPE_NAME: &'static str;
+    /// Names and types of the structure members.
+    const MEMBERS: &'static [(&'static str, &'static str)];
+
+    /// Types of the structures referenced by the members, as `(name, members)` pairs.
+    fn referenced_types() -> Vec<(&'static str, &'static [(&'static str, &'static str)])> {
+        Vec::new()
+    }
+
+    /// Values of the members, in the same order as `MEMBERS`.
+    fn values(&self) -> Result<Vec<Eip712Value>, Eip712Error>;
+
+    /// Returns the `encodeType` of the structure: the primary type followed by the
+    /// referenced types sorted by name.
+    fn encode_type() -> String {
+        let mut referenced = Self::referenced_types();
+        referenced.sort_by_key(|(name, _)| *name);
+
+        let mut encoded = encode_type(Self::TYPE_NAME, Self::MEMBERS);
+        for (name, members) in referenced {
+            encoded.push_str(&encode_type(name, members));
+        }
+        encoded
+    }
+
+    fn type_hash() -> H256 {
+        Self::encode_type().as_bytes().keccak256().into()
+    }
+
+    fn hash_struct(&self) -> Result<H256, Eip712Error> {
+        let values = self.values()?;
+        let mut bytes = Vec::with_capacity(32 * (values.len() + 1));
+        bytes.extend_from_slice(Self::type_hash().as_bytes());
+        for value in values {
+            bytes.extend_from_slice(&value.encode());
+        }
+        Ok(bytes.keccak256().into())
+    }
+
+    /// Returns the message hash that has to be signed by the Ethereum private key.
+    fn signing_hash(&self, domain: &Eip712Domain) -> Result<H256, Eip712Error> {
+        let mut bytes = Vec::with_capacity(2 + 32 * 2);
+        bytes.extend_from_slice(&[0x19, 0x01]);
+        bytes.extend_from_slice(domain.separator().as_bytes());
+        bytes.extend_from_slice(self.hash_struct()?.as_bytes());
+        Ok(bytes.keccak256().into())
+    }
+
+    fn to_json(&self) -> Result<Value, Eip712Error> {
+        let message: Map<String, Value> = Self::MEMBERS
+            .iter()
+            .zip(self.values()?)
+            .map(|((name, _), value)| (name.to_string(), value.to_json()))
+            .collect();
+        Ok(Value::Object(message))
+    }
+
+    /// Returns the typed data to be signed.
+    fn typed_data(&self, domain: &Eip712Domain) -> Result<Eip712TypedData, Eip712Error>
+    where
+        Self: Sized,
+    {
+        Eip712TypedData::new(self, domain)
+    }
+}
+
+/// Typed data to be signed, along with its signing hash.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Eip712TypedData {
+    /// Hash to be signed by the Ethereum private key.
+    pub signing_hash: H256,
+    /// Typed data in the format of the `eth_signTypedData_v4` JSON RPC method.
+    pub json: Value,
+}
+
+impl Eip712TypedData {
+    pub fn new<T: Eip712TypedStructure>(
+        message: &T,
+        domain: &Eip712Domain,
+    ) -> Result<Self, Eip712Error> {
+        let mut types = Map::new();
+        types.insert(
+            EIP712_DOMAIN_TYPE_NAME.to_string(),
+            members_to_json(EIP712_DOMAIN_MEMBERS),
+        );
+        types.insert(T::TYPE_NAME.to_string(), members_to_json(T::MEMBERS));
+        for (name, members) in T::referenced_types() {
+            types.insert(name.to_string(), members_to_json(members));
+        }
+
+        Ok(Self {
+            signing_hash: message.signing_hash(domain)?,
+            json: json!({
+                "types": types,
+                "primaryType": T::TYPE_NAME,
+                "domain": domain.to_json(),
+                "message": message.to_json()?,
+            }),
+        })
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum Eip712Error {
+    #[error("Value {0} doesn't fit into uint256")]
+    UintOverflow(BigUint),
+    #[error("{0} transactions can't be signed as typed data")]
+    UnsupportedTransaction(String),
+}
+
+fn encode_type(name: &str, members: &[(&str, &str)]) -> String {
+    let members: Vec<_> = members
+        .iter()
+        .map(|(member, member_type)| format!("{} {}", member_type, member))
+        .collect();
+    format!("{}({})", name, members.join(","))
+}
+
+fn members_to_json(members: &[(&str, &str)]) -> Value {
+    Value::Array(
+        members
+            .iter()
+            .map(|(name, member_type)| json!({ "name": name, "type": member_type }))
+            .collect(),
+    )
+}
@@ -3,7 +3,9 @@ use itertools::Itertools;
 // Workspace uses
 use zksync_basic_types::Address;
 // Local uses
+use super::eip712_signature::{Eip712Error, Eip712TypedStructure, Eip712Value};
 use super::eth_signature::TxEthSignature;
+use crate::tx::{ChangePubKey, ForcedExit, MintNFT, Order, Swap, Transfer, Withdraw, WithdrawNFT};
 use crate::{Token, ZkSyncTx};
 use thiserror::Error;
 
@@ -109,6 +111,119 @@ impl EthBatchSignData {
     }
 }
 
+/// Transactions batch represented as the EIP-712 typed structure.
+/// Transactions are signed in the same order they're sent in the batch.
+#[derive(Debug, Clone)]
+pub struct Eip712Batch {
+    pub transactions: Vec<Eip712BatchTransaction>,
+}
+
+impl Eip712Batch {
+    /// Creates the typed structure for the batch. Returns an error if the batch is empty
+    /// or contains transactions which can't be signed as typed data.
+    pub fn new<'a, I>(txs: I) -> Result<Self, Eip712BatchError>
+    where
+        I: IntoIterator<Item = &'a ZkSyncTx>,
+    {
+        let transactions = txs
+            .into_iter()
+            .map(Eip712BatchTransaction::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        if transactions.is_empty() {
+            return Err(Eip712BatchError::EmptyBatch);
+        }
+
+        Ok(Self { transactions })
+    }
+}
+
+impl Eip712TypedStructure for Eip712Batch {
+    const TYPE_NAME: &'static str = "Batch";
+    const MEMBERS: &'static [(&'static str, &'static str)] =
+        &[("transactions", "BatchTransaction[]")];
+
+    fn referenced_types() -> Vec<(&'static str, &'static [(&'static str, &'static str)])> {
+        let mut types = vec![(
+            Eip712BatchTransaction::TYPE_NAME,
+            Eip712BatchTransaction::MEMBERS,
+        )];
+        types.extend(Eip712BatchTransaction::referenced_types());
+        types
+    }
+
+    fn values(&self) -> Result<Vec<Eip712Value>, Eip712Error> {
+        Ok(vec![Eip712Value::struct_array(&self.transactions)?])
+    }
+}
+
+/// Transaction of the batch. EIP-712 has no union types, so the transaction is encoded as
+/// a tagged union: the member of its type holds the transaction, while the other members are empty.
+#[derive(Debug, Clone)]
+pub struct Eip712BatchTransaction(ZkSyncTx);
+
+impl Eip712BatchTransaction {
+    pub fn new(tx: &ZkSyncTx) -> Result<Self, Eip712BatchError> {
+        match tx {
+            ZkSyncTx::Close(_) => Err(Eip712BatchError::UnsupportedTransaction(tx.variance_name())),
+            _ => Ok(Self(tx.clone())),
+        }
+    }
+}
+
+impl Eip712TypedStructure for Eip712BatchTransaction {
+    const TYPE_NAME: &'static str = "BatchTransaction";
+    const MEMBERS: &'static [(&'static str, &'static str)] = &[
+        ("changePubKey", "ChangePubKey[]"),
+        ("forcedExit", "ForcedExit[]"),
+        ("mintNFT", "MintNFT[]"),
+        ("swap", "Swap[]"),
+        ("transfer", "Transfer[]"),
+        ("withdraw", "Withdraw[]"),
+        ("withdrawNFT", "WithdrawNFT[]"),
+    ];
+
+    fn referenced_types() -> Vec<(&'static str, &'static [(&'static str, &'static str)])> {
+        vec![
+            (ChangePubKey::TYPE_NAME, ChangePubKey::MEMBERS),
+            (ForcedExit::TYPE_NAME, ForcedExit::MEMBERS),
+            (MintNFT::TYPE_NAME, MintNFT::MEMBERS),
+            (Order::TYPE_NAME, Order::MEMBERS),
+            (Swap::TYPE_NAME, Swap::MEMBERS),
+            (Transfer::TYPE_NAME, Transfer::MEMBERS),
+            (Withdraw::TYPE_NAME, Withdraw::MEMBERS),
+            (WithdrawNFT::TYPE_NAME, WithdrawNFT::MEMBERS),
+        ]
+    }
+
+    fn values(&self) -> Result<Vec<Eip712Value>, Eip712Error> {
+        fn single<T: Eip712TypedStructure>(tx: &T) -> Result<Eip712Value, Eip712Error> {
+            Eip712Value::struct_array(std::slice::from_ref(tx))
+        }
+
+        let (member, value) = match &self.0 {
+            ZkSyncTx::ChangePubKey(tx) => (0, single(tx.as_ref())),
+            ZkSyncTx::ForcedExit(tx) => (1, single(tx.as_ref())),
+            ZkSyncTx::MintNFT(tx) => (2, single(tx.as_ref())),
+            ZkSyncTx::Swap(tx) => (3, single(tx.as_ref())),
+            ZkSyncTx::Transfer(tx) => (4, single(tx.as_ref())),
+            ZkSyncTx::Withdraw(tx) => (5, single(tx.as_ref())),
+            ZkSyncTx::WithdrawNFT(tx) => (6, single(tx.as_ref())),
+            ZkSyncTx::Close(_) => unreachable!("Close transactions are rejected on creation"),
+        };
+        let mut values = vec![Eip712Value::StructArray(Vec::new()); Self::MEMBERS.len()];
+        values[member] = value?;
+        Ok(values)
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum Eip712BatchError {
+    #[error("Transaction batch cannot be empty")]
+    EmptyBatch,
+    #[error("{0} transactions can't be signed as typed data")]
+    UnsupportedTransaction(String),
+}
+
 #[derive(Debug, Error, PartialEq)]
 #[error("Transaction batch cannot be empty")]
 pub struct EmptyTxBatch;
@@ -7,11 +7,25 @@ use crate::tx::{EIP1271Signature, PackedEthSignature};
 /// May be either a signature generated via Ethereum private key
 /// corresponding to the account address,
 /// or on-chain signature via EIP-1271.
+/// Both of them may be created either for the human-readable message,
+/// or for the EIP-712 typed data.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", content = "signature")]
 pub enum TxEthSignature {
     EthereumSignature(PackedEthSignature),
     EIP1271Signature(EIP1271Signature),
+    EIP712Signature(PackedEthSignature),
+    EIP712EIP1271Signature(EIP1271Signature),
+}
+
+impl TxEthSignature {
+    /// Returns `true` if the signature was created for the EIP-712 typed data.
+    pub fn is_eip712(&self) -> bool {
+        matches!(
+            self,
+            Self::EIP712Signature(_) | Self::EIP712EIP1271Signature(_)
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -61,10 +75,12 @@ impl TxEthSignatureVariant {
 impl Display for TxEthSignature {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::EthereumSignature(sign) => {
+            Self::EthereumSignature(sign) | Self::EIP712Signature(sign) => {
                 write!(f, "0x{}", hex::encode(sign.serialize_packed()))
             }
-            Self::EIP1271Signature(sign) => write!(f, "0x{}", hex::encode(sign.0.clone())),
+            Self::EIP1271Signature(sign) | Self::EIP712EIP1271Signature(sign) => {
+                write!(f, "0x{}", hex::encode(sign.0.clone()))
+            }
         }
     }
 }
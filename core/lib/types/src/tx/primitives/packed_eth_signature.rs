@@ -69,6 +69,17 @@ impl PackedEthSignature {
         Ok(PackedEthSignature(signature))
     }
 
+    /// Signs the 32-byte hash as is, without the `eth_sign` prefix.
+    /// Used to sign the EIP-712 typed data, which is already hashed with its own prefix.
+    pub fn sign_raw(
+        private_key: &H256,
+        signed_bytes: &H256,
+    ) -> Result<PackedEthSignature, PackedETHSignatureError> {
+        let secret_key = (*private_key).into();
+        let signature = sign(&secret_key, signed_bytes)?;
+        Ok(PackedEthSignature(signature))
+    }
+
     fn message_to_signed_bytes(msg: &[u8]) -> H256 {
         let prefix = format!("\x19Ethereum Signed Message:\n{}", msg.len());
         let mut bytes = Vec::with_capacity(prefix.len() + msg.len());
@@ -86,6 +97,16 @@ impl PackedEthSignature {
         Ok(public_to_address(&public_key))
     }
 
+    /// Checks signature of the 32-byte hash created by `sign_raw` and returns
+    /// ethereum address of the signer.
+    pub fn signature_recover_signer_from_hash(
+        &self,
+        signed_bytes: H256,
+    ) -> Result<Address, PackedETHSignatureError> {
+        let public_key = recover(&self.0, &signed_bytes)?;
+        Ok(public_to_address(&public_key))
+    }
+
     /// Get Ethereum address from private key.
     pub fn address_from_private_key(
         private_key: &H256,
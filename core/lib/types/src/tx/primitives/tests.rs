@@ -1,7 +1,8 @@
 use std::str::FromStr;
 // External uses
+use num::BigUint;
 // Workspace uses
-use zksync_basic_types::Address;
+use zksync_basic_types::{Address, H256};
 use zksync_utils::format_units;
 // Local uses
 use crate::{tx::*, AccountId, Nonce, Token, TokenId, TokenKind, Transfer, Withdraw, ZkSyncTx};
@@ -130,3 +131,115 @@ fn test_batch_message() {
     let message = EthBatchSignData::get_batch_sign_message(txs);
     assert_eq!(message, expected.into_bytes());
 }
+
+#[test]
+fn test_eip712_encode_type() {
+    assert_eq!(
+        Transfer::encode_type(),
+        "Transfer(uint32 accountId,address from,address to,uint32 token,uint256 amount,\
+        uint256 fee,uint32 nonce,uint64 validFrom,uint64 validUntil)"
+    );
+
+    // Orders of the swap are encoded as the nested structures.
+    assert_eq!(
+        Swap::encode_type(),
+        format!(
+            "Swap(uint32 submitterId,address submitterAddress,uint32 nonce,Order orderA,\
+            Order orderB,uint256 amountA,uint256 amountB,uint32 feeToken,uint256 fee){}",
+            Order::encode_type()
+        )
+    );
+
+    // Referenced types must follow the primary type, sorted by name.
+    let batch_type = Eip712Batch::encode_type();
+    let referenced: Vec<_> = batch_type
+        .split(')')
+        .filter(|part| !part.is_empty())
+        .map(|part| part.split('(').next().unwrap())
+        .collect();
+    assert_eq!(
+        referenced,
+        vec![
+            "Batch",
+            "BatchTransaction",
+            "ChangePubKey",
+            "ForcedExit",
+            "MintNFT",
+            "Order",
+            "Swap",
+            "Transfer",
+            "Withdraw",
+            "WithdrawNFT"
+        ]
+    );
+}
+
+#[test]
+fn test_eip712_signature() {
+    let private_key = H256::random();
+    let mut transfer = get_transfer();
+    transfer.from = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+    let tx = ZkSyncTx::from(transfer.clone());
+
+    let domain = Eip712Domain::new(9, Address::random());
+    let signing_hash = tx.eip712_signing_hash(&domain).unwrap();
+    assert_eq!(signing_hash, transfer.signing_hash(&domain).unwrap());
+    assert_eq!(
+        tx.eip712_typed_data(&domain).unwrap().signing_hash,
+        signing_hash
+    );
+
+    // Signature must not be valid for another network.
+    let other_domain = Eip712Domain::new(1, domain.verifying_contract);
+    assert_ne!(transfer.signing_hash(&other_domain).unwrap(), signing_hash);
+
+    let signature = PackedEthSignature::sign_raw(&private_key, &signing_hash).unwrap();
+    let signer = signature
+        .signature_recover_signer_from_hash(signing_hash)
+        .unwrap();
+    assert_eq!(signer, transfer.from);
+
+    // Batch hash covers all of its transactions.
+    let withdraw = ZkSyncTx::from(get_withdraw());
+    let batch_hash = Eip712Batch::new(&[tx.clone(), withdraw.clone()])
+        .unwrap()
+        .signing_hash(&domain)
+        .unwrap();
+    let other_batch_hash = Eip712Batch::new(&[tx.clone()])
+        .unwrap()
+        .signing_hash(&domain)
+        .unwrap();
+    assert_ne!(batch_hash, other_batch_hash);
+    // Transactions are signed in the order they're sent.
+    let reordered_batch_hash = Eip712Batch::new(&[withdraw, tx])
+        .unwrap()
+        .signing_hash(&domain)
+        .unwrap();
+    assert_ne!(batch_hash, reordered_batch_hash);
+    assert_eq!(
+        Eip712Batch::new(Vec::<ZkSyncTx>::new().iter()).unwrap_err(),
+        Eip712BatchError::EmptyBatch
+    );
+}
+
+#[test]
+fn test_eip712_uint_overflow() {
+    let mut transfer = get_transfer();
+    transfer.amount = BigUint::from(1u32) << 256;
+    let tx = ZkSyncTx::from(transfer);
+
+    let domain = Eip712Domain::new(9, Address::random());
+    assert!(matches!(
+        tx.eip712_signing_hash(&domain),
+        Err(Eip712Error::UintOverflow(_))
+    ));
+    assert!(matches!(
+        Eip712Batch::new(&[tx]).unwrap().signing_hash(&domain),
+        Err(Eip712Error::UintOverflow(_))
+    ));
+
+    // The largest `uint256` value is still encoded.
+    let mut transfer = get_transfer();
+    transfer.amount = (BigUint::from(1u32) << 256) - 1u32;
+    assert!(transfer.signing_hash(&domain).is_ok());
+}
@@ -1,5 +1,6 @@
 use num::{BigUint, Zero};
 use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
 use std::fmt::{Display, Formatter};
 use thiserror::Error;
 
@@ -13,7 +14,7 @@ use zksync_crypto::{
 };
 use zksync_utils::{format_units, BigUintPairSerdeAsRadix10Str, BigUintSerdeAsRadix10Str};
 
-use super::{TxSignature, VerifiedSignatureCache};
+use super::{Eip712Error, Eip712TypedStructure, Eip712Value, TxSignature, VerifiedSignatureCache};
 use crate::account::PubKeyHash;
 use crate::tx::error::{
     AMOUNT_IS_NOT_PACKABLE, FEE_AMOUNT_IS_NOT_PACKABLE, WRONG_ACCOUNT_ID, WRONG_AMOUNT_ERROR,
@@ -196,6 +197,70 @@ pub enum OrderError {
     WrongTimeRange,
 }
 
+impl Eip712TypedStructure for Order {
+    const TYPE_NAME: &'static str = "Order";
+    const MEMBERS: &'static [(&'static str, &'static str)] = &[
+        ("accountId", "uint32"),
+        ("recipient", "address"),
+        ("nonce", "uint32"),
+        ("tokenSell", "uint32"),
+        ("tokenBuy", "uint32"),
+        ("ratioSell", "uint256"),
+        ("ratioBuy", "uint256"),
+        ("amount", "uint256"),
+        ("validFrom", "uint64"),
+        ("validUntil", "uint64"),
+    ];
+
+    fn values(&self) -> Result<Vec<Eip712Value>, Eip712Error> {
+        Ok(vec![
+            self.account_id.0.into(),
+            self.recipient_address.into(),
+            self.nonce.0.into(),
+            self.token_sell.0.into(),
+            self.token_buy.0.into(),
+            (&self.price.0).try_into()?,
+            (&self.price.1).try_into()?,
+            (&self.amount).try_into()?,
+            self.time_range.valid_from.into(),
+            self.time_range.valid_until.into(),
+        ])
+    }
+}
+
+impl Eip712TypedStructure for Swap {
+    const TYPE_NAME: &'static str = "Swap";
+    const MEMBERS: &'static [(&'static str, &'static str)] = &[
+        ("submitterId", "uint32"),
+        ("submitterAddress", "address"),
+        ("nonce", "uint32"),
+        ("orderA", "Order"),
+        ("orderB", "Order"),
+        ("amountA", "uint256"),
+        ("amountB", "uint256"),
+        ("feeToken", "uint32"),
+        ("fee", "uint256"),
+    ];
+
+    fn referenced_types() -> Vec<(&'static str, &'static [(&'static str, &'static str)])> {
+        vec![(Order::TYPE_NAME, Order::MEMBERS)]
+    }
+
+    fn values(&self) -> Result<Vec<Eip712Value>, Eip712Error> {
+        Ok(vec![
+            self.submitter_id.0.into(),
+            self.submitter_address.into(),
+            self.nonce.0.into(),
+            Eip712Value::structure(&self.orders.0)?,
+            Eip712Value::structure(&self.orders.1)?,
+            (&self.amounts.0).try_into()?,
+            (&self.amounts.1).try_into()?,
+            self.fee_token.0.into(),
+            (&self.fee).try_into()?,
+        ])
+    }
+}
+
 impl Swap {
     /// Unique identifier of the transaction type in zkSync network.
     pub const TX_TYPE: u8 = 11;
@@ -1,4 +1,4 @@
-use std::convert::TryFrom;
+use std::convert::{TryFrom, TryInto};
 use std::fmt::{Display, Formatter};
 
 use num::{BigUint, Zero};
@@ -14,7 +14,7 @@ use zksync_crypto::{
 };
 use zksync_utils::{format_units, BigUintSerdeAsRadix10Str};
 
-use super::{TxSignature, VerifiedSignatureCache};
+use super::{Eip712Error, Eip712TypedStructure, Eip712Value, TxSignature, VerifiedSignatureCache};
 use crate::{
     helpers::{
         is_fee_amount_packable, is_token_amount_packable, pack_fee_amount, pack_token_amount,
@@ -289,6 +289,36 @@ impl Transfer {
     }
 }
 
+impl Eip712TypedStructure for Transfer {
+    const TYPE_NAME: &'static str = "Transfer";
+    const MEMBERS: &'static [(&'static str, &'static str)] = &[
+        ("accountId", "uint32"),
+        ("from", "address"),
+        ("to", "address"),
+        ("token", "uint32"),
+        ("amount", "uint256"),
+        ("fee", "uint256"),
+        ("nonce", "uint32"),
+        ("validFrom", "uint64"),
+        ("validUntil", "uint64"),
+    ];
+
+    fn values(&self) -> Result<Vec<Eip712Value>, Eip712Error> {
+        let time_range = self.time_range.unwrap_or_default();
+        Ok(vec![
+            self.account_id.0.into(),
+            self.from.into(),
+            self.to.into(),
+            self.token.0.into(),
+            (&self.amount).try_into()?,
+            (&self.fee).try_into()?,
+            self.nonce.0.into(),
+            time_range.valid_from.into(),
+            time_range.valid_until.into(),
+        ])
+    }
+}
+
 #[derive(Error, Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum TransactionError {
     WrongAmount,
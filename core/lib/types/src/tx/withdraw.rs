@@ -1,5 +1,6 @@
 use num::{BigUint, ToPrimitive, Zero};
 use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
 use std::fmt::{Display, Formatter};
 use thiserror::Error;
 
@@ -19,7 +20,9 @@ use crate::{
     AccountId, Nonce, TokenId,
 };
 
-use super::{TimeRange, TxSignature, VerifiedSignatureCache};
+use super::{
+    Eip712Error, Eip712TypedStructure, Eip712Value, TimeRange, TxSignature, VerifiedSignatureCache,
+};
 use crate::tx::error::{
     AMOUNT_IS_NOT_PACKABLE, FEE_AMOUNT_IS_NOT_PACKABLE, WRONG_ACCOUNT_ID, WRONG_AMOUNT_ERROR,
     WRONG_FEE_ERROR, WRONG_SIGNATURE, WRONG_TIME_RANGE, WRONG_TOKEN, WRONG_TOKEN_FOR_PAYING_FEE,
@@ -285,6 +288,37 @@ impl Withdraw {
     }
 }
 
+impl Eip712TypedStructure for Withdraw {
+    const TYPE_NAME: &'static str = "Withdraw";
+    // `fast` is set by the server, so it's not a part of the signed data.
+    const MEMBERS: &'static [(&'static str, &'static str)] = &[
+        ("accountId", "uint32"),
+        ("from", "address"),
+        ("to", "address"),
+        ("token", "uint32"),
+        ("amount", "uint256"),
+        ("fee", "uint256"),
+        ("nonce", "uint32"),
+        ("validFrom", "uint64"),
+        ("validUntil", "uint64"),
+    ];
+
+    fn values(&self) -> Result<Vec<Eip712Value>, Eip712Error> {
+        let time_range = self.time_range.unwrap_or_default();
+        Ok(vec![
+            self.account_id.0.into(),
+            self.from.into(),
+            self.to.into(),
+            self.token.0.into(),
+            (&self.amount).try_into()?,
+            (&self.fee).try_into()?,
+            self.nonce.0.into(),
+            time_range.valid_from.into(),
+            time_range.valid_until.into(),
+        ])
+    }
+}
+
 #[derive(Error, Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum TransactionError {
     WrongAmount,
@@ -1,5 +1,6 @@
 use num::{BigUint, Zero};
 use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
 use std::fmt::{Display, Formatter};
 use thiserror::Error;
 
@@ -12,7 +13,9 @@ use zksync_crypto::{
 
 use zksync_utils::{format_units, BigUintSerdeAsRadix10Str};
 
-use super::{TimeRange, TxSignature, VerifiedSignatureCache};
+use super::{
+    Eip712Error, Eip712TypedStructure, Eip712Value, TimeRange, TxSignature, VerifiedSignatureCache,
+};
 use crate::tx::error::{
     FEE_AMOUNT_IS_NOT_PACKABLE, WRONG_ACCOUNT_ID, WRONG_FEE_ERROR, WRONG_SIGNATURE,
     WRONG_TIME_RANGE, WRONG_TOKEN, WRONG_TOKEN_FOR_PAYING_FEE,
@@ -230,6 +233,36 @@ impl WithdrawNFT {
     }
 }
 
+impl Eip712TypedStructure for WithdrawNFT {
+    const TYPE_NAME: &'static str = "WithdrawNFT";
+    // `fast` is set by the server, so it's not a part of the signed data.
+    const MEMBERS: &'static [(&'static str, &'static str)] = &[
+        ("accountId", "uint32"),
+        ("from", "address"),
+        ("to", "address"),
+        ("token", "uint32"),
+        ("feeToken", "uint32"),
+        ("fee", "uint256"),
+        ("nonce", "uint32"),
+        ("validFrom", "uint64"),
+        ("validUntil", "uint64"),
+    ];
+
+    fn values(&self) -> Result<Vec<Eip712Value>, Eip712Error> {
+        Ok(vec![
+            self.account_id.0.into(),
+            self.from.into(),
+            self.to.into(),
+            self.token.0.into(),
+            self.fee_token.0.into(),
+            (&self.fee).try_into()?,
+            self.nonce.0.into(),
+            self.time_range.valid_from.into(),
+            self.time_range.valid_until.into(),
+        ])
+    }
+}
+
 #[derive(Error, Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum TransactionError {
     WrongFee,
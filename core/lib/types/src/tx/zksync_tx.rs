@@ -4,15 +4,16 @@ use parity_crypto::digest::sha256;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
-use zksync_basic_types::{AccountId, Address};
+use zksync_basic_types::{AccountId, Address, H256};
 use zksync_crypto::params::ETH_TOKEN_ID;
 
 use crate::{
     operations::{ChangePubKeyOp, MintNFTOp},
     tx::{
         error::{CloseOperationsDisabled, TransactionError},
-        ChangePubKey, Close, ForcedExit, MintNFT, Swap, TimeRange, Transfer, TxEthSignature,
-        TxHash, TxSignature, Withdraw, WithdrawNFT,
+        ChangePubKey, Close, Eip712Domain, Eip712Error, Eip712TypedData, Eip712TypedStructure,
+        ForcedExit, MintNFT, Swap, TimeRange, Transfer, TxEthSignature, TxHash, TxSignature,
+        Withdraw, WithdrawNFT,
     },
     utils::deserialize_eth_message,
     CloseOp, ForcedExitOp, Nonce, SwapOp, Token, TokenId, TokenLike, TransferOp, TxFeeTypes,
@@ -296,6 +297,36 @@ impl ZkSyncTx {
         }
     }
 
+    /// Returns the EIP-712 typed data that user has to sign to send the transaction.
+    /// Returns an error if the transaction can't be signed as typed data.
+    pub fn eip712_typed_data(&self, domain: &Eip712Domain) -> Result<Eip712TypedData, Eip712Error> {
+        match self {
+            ZkSyncTx::Transfer(tx) => tx.typed_data(domain),
+            ZkSyncTx::Withdraw(tx) => tx.typed_data(domain),
+            ZkSyncTx::ChangePubKey(tx) => tx.typed_data(domain),
+            ZkSyncTx::ForcedExit(tx) => tx.typed_data(domain),
+            ZkSyncTx::MintNFT(tx) => tx.typed_data(domain),
+            ZkSyncTx::WithdrawNFT(tx) => tx.typed_data(domain),
+            ZkSyncTx::Swap(tx) => tx.typed_data(domain),
+            ZkSyncTx::Close(_) => Err(Eip712Error::UnsupportedTransaction(self.variance_name())),
+        }
+    }
+
+    /// Returns the EIP-712 hash that user has to sign to send the transaction.
+    /// Returns an error if the transaction can't be signed as typed data.
+    pub fn eip712_signing_hash(&self, domain: &Eip712Domain) -> Result<H256, Eip712Error> {
+        match self {
+            ZkSyncTx::Transfer(tx) => tx.signing_hash(domain),
+            ZkSyncTx::Withdraw(tx) => tx.signing_hash(domain),
+            ZkSyncTx::ChangePubKey(tx) => tx.signing_hash(domain),
+            ZkSyncTx::ForcedExit(tx) => tx.signing_hash(domain),
+            ZkSyncTx::MintNFT(tx) => tx.signing_hash(domain),
+            ZkSyncTx::WithdrawNFT(tx) => tx.signing_hash(domain),
+            ZkSyncTx::Swap(tx) => tx.signing_hash(domain),
+            ZkSyncTx::Close(_) => Err(Eip712Error::UnsupportedTransaction(self.variance_name())),
+        }
+    }
+
     /// Returns a message that user has to sign to send the transaction in the old format.
     /// If the transaction doesn't need a message signature, returns `None`.
     /// Needed for backwards compatibility.
@@ -11,15 +11,21 @@
 //! Integration with sentry for catching errors and react on them immediately
 //! https://docs.sentry.io/platforms/rust/
 //!
+//! Spans can be exported in the OpenTelemetry format, see the `telemetry` module for details.
+//!
 
 use std::{borrow::Cow, str::FromStr};
 
 pub use sentry;
 use sentry::{types::Dsn, ClientInitGuard};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
+pub use telemetry::{block_traces, remember_block_traces, shutdown_tracing, TraceContext};
 pub use tracing as __tracing;
 pub use tracing::{debug, info, log, trace};
 
+pub mod telemetry;
+
 #[macro_export]
 macro_rules! warn {
     ($($arg:tt)*) => {
@@ -55,19 +61,23 @@ fn get_sentry_url() -> Option<Dsn> {
 
 /// Initialize logging with tracing and set up log format
 ///
+/// If the tracing exporter is configured, spans are exported in the OpenTelemetry format as well.
 /// If the sentry URL is provided via an environment variable, this function will also initialize sentry.
 /// Returns a sentry client guard. The full description can be found in the official documentation:
 /// https://docs.sentry.io/platforms/rust/#configure
 pub fn init() -> Option<ClientInitGuard> {
     let log_format = std::env::var("MISC_LOG_FORMAT").unwrap_or_else(|_| "plain".to_string());
+    let telemetry_layer =
+        telemetry::init_tracer().map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer));
+    let subscriber = tracing_subscriber::registry()
+        .with(EnvFilter::from_default_env())
+        .with(telemetry_layer);
     match log_format.as_str() {
-        "plain" => tracing_subscriber::fmt::init(),
+        "plain" => subscriber.with(tracing_subscriber::fmt::layer()).init(),
         "json" => {
             let timer = tracing_subscriber::fmt::time::ChronoUtc::rfc3339();
-            tracing_subscriber::fmt::Subscriber::builder()
-                .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-                .with_timer(timer)
-                .json()
+            subscriber
+                .with(tracing_subscriber::fmt::layer().with_timer(timer).json())
                 .init();
         }
         _ => panic!("MISC_LOG_FORMAT has an unexpected value {}", log_format),
@@ -0,0 +1,350 @@
+//! Export of the `tracing` spans in the OpenTelemetry format.
+//!
+//! The exporter is chosen by the `MISC_TRACING_EXPORTER` env variable:
+//!
+//! - `none` (default): spans are not exported;
+//! - `otlp`: spans are sent to the OpenTelemetry collector at `MISC_TRACING_OTLP_ENDPOINT`;
+//! - `json_file`: spans are appended to `MISC_TRACING_FILE` as JSON lines for offline analysis.
+//!
+//! Trace context is passed between the components with `TraceContext`, which uses
+//! the W3C Trace Context format (`traceparent` / `tracestate` headers).
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use opentelemetry::{
+    global,
+    sdk::{
+        export::trace::{ExportResult, SpanData, SpanExporter},
+        propagation::TraceContextPropagator,
+        trace::{self as sdktrace, Tracer, TracerProvider},
+        Resource,
+    },
+    trace::{TraceError, TracerProvider as _},
+    KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use serde_json::json;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4317";
+const DEFAULT_TRACING_FILE: &str = "traces.jsonl";
+
+/// Context of the trace which can be passed to another component (over HTTP or a channel)
+/// in order to continue the trace there.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TraceContext(HashMap<String, String>);
+
+impl TraceContext {
+    /// Names of the HTTP headers used to pass the context.
+    pub const HEADERS: [&'static str; 2] = ["traceparent", "tracestate"];
+
+    /// Captures the context of the current span.
+    pub fn current() -> Self {
+        Self::from_span(&tracing::Span::current())
+    }
+
+    pub fn from_span(span: &tracing::Span) -> Self {
+        let mut carrier = HashMap::new();
+        global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&span.context(), &mut carrier)
+        });
+        Self(carrier)
+    }
+
+    /// Restores the context from the HTTP headers, unrelated headers are ignored.
+    pub fn from_headers<'a>(headers: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let carrier = headers
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let name = name.to_lowercase();
+                Self::HEADERS
+                    .contains(&name.as_str())
+                    .then(|| (name, value.to_string()))
+            })
+            .collect();
+        Self(carrier)
+    }
+
+    /// Returns the HTTP headers which should be sent to continue the trace.
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Makes the span a child of the span this context was captured from.
+    pub fn attach_to(&self, span: &tracing::Span) {
+        if self.is_empty() {
+            return;
+        }
+        let parent = global::get_text_map_propagator(|propagator| propagator.extract(&self.0));
+        span.set_parent(parent);
+    }
+
+    /// Links the span to the trace this context was captured from, without changing the parent of the span.
+    /// Used by the spans which process many transactions at once, e.g. the whole blocks.
+    ///
+    /// A short `trace_link` span is added to the linked trace, and the span follows from it.
+    pub fn link_to(&self, span: &tracing::Span) {
+        if self.is_empty() {
+            return;
+        }
+        let link = tracing::info_span!(
+            parent: None,
+            "trace_link",
+            linked_span = span.metadata().map(|metadata| metadata.name())
+        );
+        self.attach_to(&link);
+        span.follows_from(&link);
+    }
+}
+
+/// Amount of the recent blocks for which the trace contexts of the transactions are kept.
+const MAX_REMEMBERED_BLOCKS: usize = 2000;
+
+lazy_static::lazy_static! {
+    static ref BLOCK_TRACES: Mutex<BTreeMap<u32, Vec<TraceContext>>> = Mutex::new(BTreeMap::new());
+}
+
+/// Remembers the trace contexts of the transactions included into the block, so the components
+/// which process the blocks later (e.g. `eth_sender`) can link their spans to these traces.
+/// The contexts are only shared within the process, and only for the recent blocks.
+pub fn remember_block_traces(block_number: u32, contexts: Vec<TraceContext>) {
+    let contexts: Vec<_> = contexts
+        .into_iter()
+        .filter(|context| !context.is_empty())
+        .collect();
+    if contexts.is_empty() {
+        return;
+    }
+
+    let mut block_traces = BLOCK_TRACES.lock().unwrap();
+    block_traces.insert(block_number, contexts);
+    while block_traces.len() > MAX_REMEMBERED_BLOCKS {
+        let oldest_block = *block_traces.keys().next().unwrap();
+        block_traces.remove(&oldest_block);
+    }
+}
+
+/// Returns the trace contexts of the transactions included into the block, if they're remembered.
+pub fn block_traces(block_number: u32) -> Vec<TraceContext> {
+    BLOCK_TRACES
+        .lock()
+        .unwrap()
+        .get(&block_number)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Creates the tracer according to the `MISC_TRACING_EXPORTER` env variable.
+pub(crate) fn init_tracer() -> Option<Tracer> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let exporter = std::env::var("MISC_TRACING_EXPORTER").unwrap_or_else(|_| "none".to_string());
+    let tracer = match exporter.as_str() {
+        "none" => return None,
+        "otlp" => otlp_tracer(),
+        "json_file" => json_file_tracer(),
+        _ => panic!("MISC_TRACING_EXPORTER has an unexpected value {}", exporter),
+    };
+    Some(tracer.unwrap_or_else(|err| panic!("Unable to initialize tracing exporter: {}", err)))
+}
+
+/// Flushes the spans which are not exported yet.
+pub fn shutdown_tracing() {
+    global::shutdown_tracer_provider();
+}
+
+/// Name of the component in the exported spans, e.g. `zksync_server`.
+fn service_name() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "zksync".to_string())
+}
+
+fn trace_config() -> sdktrace::Config {
+    sdktrace::config().with_resource(Resource::new(vec![KeyValue::new(
+        "service.name",
+        service_name(),
+    )]))
+}
+
+/// OTLP exporter sends spans in background, which requires the tokio runtime. The exporter gets
+/// its own runtime, so the tracing can be initialized in any binary, whether it uses tokio or not,
+/// and before its runtime is started.
+fn otlp_tracer() -> Result<Tracer, TraceError> {
+    let endpoint = std::env::var("MISC_TRACING_OTLP_ENDPOINT")
+        .unwrap_or_else(|_| DEFAULT_OTLP_ENDPOINT.to_string());
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("tracing-exporter")
+        .enable_all()
+        .build()
+        .map_err(|err| TraceError::Other(Box::new(err)))?;
+    // The runtime must live as long as the exporter, which is until the process exits.
+    let runtime: &'static tokio::runtime::Runtime = Box::leak(Box::new(runtime));
+    let _guard = runtime.enter();
+
+    opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(trace_config())
+        .install_batch(opentelemetry::runtime::Tokio)
+}
+
+fn json_file_tracer() -> Result<Tracer, TraceError> {
+    let path =
+        std::env::var("MISC_TRACING_FILE").unwrap_or_else(|_| DEFAULT_TRACING_FILE.to_string());
+    let exporter = JsonFileExporter::new(&path).map_err(|err| TraceError::Other(Box::new(err)))?;
+
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(exporter)
+        .with_config(trace_config())
+        .build();
+    let tracer = provider.get_tracer("zksync", None);
+    global::set_tracer_provider(provider);
+    Ok(tracer)
+}
+
+/// Writes every finished span as a separate JSON line.
+#[derive(Debug)]
+struct JsonFileExporter {
+    writer: BufWriter<File>,
+    service_name: String,
+}
+
+impl JsonFileExporter {
+    fn new(path: &str) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            service_name: service_name(),
+        })
+    }
+
+    fn write_span(&mut self, span: &SpanData) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.writer, &span_to_json(&self.service_name, span))?;
+        self.writer.write_all(b"\n")
+    }
+}
+
+#[async_trait::async_trait]
+impl SpanExporter for JsonFileExporter {
+    async fn export(&mut self, batch: Vec<SpanData>) -> ExportResult {
+        for span in &batch {
+            self.write_span(span)
+                .map_err(|err| TraceError::Other(Box::new(err)))?;
+        }
+        self.writer
+            .flush()
+            .map_err(|err| TraceError::Other(Box::new(err)))
+    }
+
+    fn shutdown(&mut self) {
+        self.writer.flush().unwrap_or_default();
+    }
+}
+
+fn unix_micros(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_micros())
+        .unwrap_or_default()
+}
+
+fn attributes_to_json<'a>(
+    attributes: impl Iterator<Item = (&'a opentelemetry::Key, &'a opentelemetry::Value)>,
+) -> serde_json::Map<String, serde_json::Value> {
+    attributes
+        .map(|(key, value)| (key.as_str().to_string(), value.as_str().into()))
+        .collect()
+}
+
+fn span_to_json(service_name: &str, span: &SpanData) -> serde_json::Value {
+    let events = span
+        .events
+        .iter()
+        .map(|event| {
+            json!({
+                "name": event.name,
+                "time_us": unix_micros(event.timestamp),
+                "attributes": attributes_to_json(
+                    event.attributes.iter().map(|kv| (&kv.key, &kv.value))
+                ),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "service": service_name,
+        "trace_id": span.span_context.trace_id().to_hex(),
+        "span_id": span.span_context.span_id().to_hex(),
+        "parent_span_id": span.parent_span_id.to_hex(),
+        "name": span.name,
+        "start_time_us": unix_micros(span.start_time),
+        "end_time_us": unix_micros(span.end_time),
+        "attributes": attributes_to_json(span.attributes.iter()),
+        "events": events,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_context_headers() {
+        let context = TraceContext::from_headers(vec![
+            (
+                "Traceparent",
+                "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            ),
+            ("content-type", "application/json"),
+        ]);
+
+        assert_eq!(
+            context.headers().collect::<Vec<_>>(),
+            vec![(
+                "traceparent",
+                "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"
+            )]
+        );
+        assert!(TraceContext::from_headers(vec![("accept", "*/*")]).is_empty());
+    }
+
+    #[test]
+    fn block_traces_are_bounded() {
+        let context = TraceContext::from_headers(vec![(
+            "traceparent",
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+        )]);
+
+        remember_block_traces(1, vec![TraceContext::default()]);
+        assert!(block_traces(1).is_empty());
+
+        for block_number in 1..=(MAX_REMEMBERED_BLOCKS as u32 + 1) {
+            remember_block_traces(block_number, vec![context.clone()]);
+        }
+        assert!(block_traces(1).is_empty());
+        assert_eq!(block_traces(2), vec![context.clone()]);
+        assert_eq!(
+            block_traces(MAX_REMEMBERED_BLOCKS as u32 + 1),
+            vec![context]
+        );
+    }
+}
@@ -0,0 +1,512 @@
+//! Generation of the blocks with the requested operations mix.
+//!
+//! Operations are executed on a `ZkSyncState` exactly as the state keeper does it,
+//! so the resulting block passes all the checks of the witness generation.
+
+// Built-in deps
+use std::str::FromStr;
+// External deps
+use chrono::Utc;
+use num::BigUint;
+// Workspace deps
+use zksync_crypto::circuit::{account::CircuitAccount, CircuitAccountTree};
+use zksync_crypto::params::{account_tree_depth, MIN_NFT_TOKEN_ID};
+use zksync_state::state::ZkSyncState;
+use zksync_test_account::ZkSyncAccount;
+use zksync_types::{
+    block::Block, tx::ChangePubKeyType, Account, AccountId, Address, BlockNumber, Deposit,
+    ExecutedOperations, ExecutedPriorityOp, ExecutedTx, FullExit, PriorityOp, PubKeyHash, TokenId,
+    ZkSyncPriorityOp, ZkSyncTx, H256,
+};
+
+pub const FEE_ACCOUNT_ID: AccountId = AccountId(0);
+const BLOCK_TIMESTAMP: u64 = 0x12345678u64;
+
+const FEE_TOKEN: TokenId = TokenId(0);
+const SWAP_TOKENS: (TokenId, TokenId) = (TokenId(1), TokenId(2));
+const INITIAL_BALANCE: u64 = 1_000_000;
+const AMOUNT: u64 = 100;
+const FEE: u64 = 1;
+
+/// Operation which can be included into the profiled block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OperationKind {
+    Noop,
+    Deposit,
+    Transfer,
+    TransferToNew,
+    Withdraw,
+    FullExit,
+    ChangePubKey,
+    ForcedExit,
+    Swap,
+    MintNFT,
+    WithdrawNFT,
+}
+
+impl OperationKind {
+    /// All the operations that can be requested by user, i.e. everything except for noops.
+    pub const ALL: [OperationKind; 10] = [
+        Self::Deposit,
+        Self::Transfer,
+        Self::TransferToNew,
+        Self::Withdraw,
+        Self::FullExit,
+        Self::ChangePubKey,
+        Self::ForcedExit,
+        Self::Swap,
+        Self::MintNFT,
+        Self::WithdrawNFT,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Noop => "noop",
+            Self::Deposit => "deposit",
+            Self::Transfer => "transfer",
+            Self::TransferToNew => "transfer_to_new",
+            Self::Withdraw => "withdraw",
+            Self::FullExit => "full_exit",
+            Self::ChangePubKey => "change_pubkey",
+            Self::ForcedExit => "forced_exit",
+            Self::Swap => "swap",
+            Self::MintNFT => "mint_nft",
+            Self::WithdrawNFT => "withdraw_nft",
+        }
+    }
+}
+
+impl FromStr for OperationKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| {
+                let available: Vec<_> = Self::ALL.iter().map(|kind| kind.name()).collect();
+                format!(
+                    "Unknown operation '{}'. Available options are: {}",
+                    s,
+                    available.join(", ")
+                )
+            })
+    }
+}
+
+/// Amount of operations of each kind to include into the block, e.g. `transfer:10,swap:2`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationsMix(pub Vec<(OperationKind, usize)>);
+
+impl Default for OperationsMix {
+    fn default() -> Self {
+        Self(OperationKind::ALL.iter().map(|&kind| (kind, 1)).collect())
+    }
+}
+
+impl FromStr for OperationsMix {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(|entry| {
+                let mut parts = entry.trim().splitn(2, ':');
+                let kind = parts.next().unwrap_or_default().parse()?;
+                let count = match parts.next() {
+                    Some(count) => count
+                        .parse()
+                        .map_err(|_| format!("Invalid operations count in '{}'", entry))?,
+                    None => 1,
+                };
+                Ok((kind, count))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+/// Block ready to be turned into the circuit.
+pub struct ProfiledBlock {
+    pub block: Block,
+    /// Account tree before the block.
+    pub account_tree: CircuitAccountTree,
+    /// Kind of the operation occupying each chunk of the block, including padding noops.
+    pub chunk_operations: Vec<OperationKind>,
+}
+
+/// Accounts and state used to generate the operations.
+struct BlockGenerator {
+    state: ZkSyncState,
+    /// Account signing the most of the transactions.
+    main: ZkSyncAccount,
+    /// NFTs owned by the main account, which are available for withdrawals.
+    nfts: Vec<TokenId>,
+    next_account_id: AccountId,
+    next_seed: u32,
+}
+
+impl BlockGenerator {
+    fn new() -> Self {
+        let mut generator = Self {
+            state: ZkSyncState::empty(),
+            main: ZkSyncAccount::rand_with_seed([1, 2, 3, 4]),
+            nfts: Vec::new(),
+            next_account_id: AccountId(*FEE_ACCOUNT_ID + 1),
+            next_seed: 1,
+        };
+
+        let fee_account = Account::default_with_address(
+            &Address::from_str("feeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee").unwrap(),
+        );
+        generator.state.insert_account(FEE_ACCOUNT_ID, fee_account);
+
+        let main_id = generator.insert_account(
+            generator.main.address,
+            Some(generator.main.pubkey_hash),
+            &[FEE_TOKEN],
+        );
+        generator.main.set_account_id(Some(main_id));
+        generator
+    }
+
+    /// Creates a new account with a deterministic keys.
+    fn new_account(&mut self, tokens: &[TokenId], set_pubkey: bool) -> ZkSyncAccount {
+        self.next_seed += 1;
+        let account = ZkSyncAccount::rand_with_seed([self.next_seed, 0x5eed, 0x5eed, 0x5eed]);
+        let pubkey_hash = if set_pubkey {
+            Some(account.pubkey_hash)
+        } else {
+            None
+        };
+        let id = self.insert_account(account.address, pubkey_hash, tokens);
+        account.set_account_id(Some(id));
+        account
+    }
+
+    fn insert_account(
+        &mut self,
+        address: Address,
+        pubkey_hash: Option<PubKeyHash>,
+        tokens: &[TokenId],
+    ) -> AccountId {
+        let id = self.next_account_id;
+        self.next_account_id = AccountId(*id + 1);
+
+        let mut account = Account::default_with_address(&address);
+        for &token in tokens {
+            account.add_balance(token, &BigUint::from(INITIAL_BALANCE));
+        }
+        if let Some(pubkey_hash) = pubkey_hash {
+            account.pub_key_hash = pubkey_hash;
+        }
+        self.state.insert_account(id, account);
+        id
+    }
+
+    /// Mints the NFTs required for the block in advance, so they can be withdrawn within the block.
+    fn mint_nfts(&mut self, amount: usize) {
+        for _ in 0..amount {
+            self.next_seed += 1;
+            let tx = self.mint_nft_tx();
+            self.state
+                .execute_tx(tx, BLOCK_TIMESTAMP)
+                .expect("Failed to mint the NFT for withdrawal");
+        }
+
+        let main_id = self.main.get_account_id().unwrap();
+        let main_account = self.state.get_account(main_id).unwrap();
+        self.nfts = main_account
+            .get_nonzero_balances()
+            .into_iter()
+            .map(|(token, _)| token)
+            .filter(|token| **token >= MIN_NFT_TOKEN_ID)
+            .collect();
+        self.nfts.sort_unstable();
+    }
+
+    fn mint_nft_tx(&self) -> ZkSyncTx {
+        self.main
+            .sign_mint_nft(
+                FEE_TOKEN,
+                "",
+                H256::from_low_u64_be(self.next_seed as u64),
+                BigUint::from(FEE),
+                &self.main.address,
+                None,
+                true,
+            )
+            .0
+            .into()
+    }
+
+    /// Prepares the transaction or the priority operation of the given kind.
+    fn prepare_operation(&mut self, kind: OperationKind) -> Result<ZkSyncTx, ZkSyncPriorityOp> {
+        let amount = BigUint::from(AMOUNT);
+        let fee = BigUint::from(FEE);
+        let tx: ZkSyncTx = match kind {
+            OperationKind::Noop => unreachable!("Noops are only used as padding"),
+            OperationKind::Deposit => {
+                return Err(ZkSyncPriorityOp::Deposit(Deposit {
+                    from: self.main.address,
+                    token: FEE_TOKEN,
+                    amount,
+                    to: self.main.address,
+                }))
+            }
+            OperationKind::FullExit => {
+                // Full exit withdraws the whole balance, so the main account can't be used.
+                let account = self.new_account(&[FEE_TOKEN], false);
+                return Err(ZkSyncPriorityOp::FullExit(FullExit {
+                    account_id: account.get_account_id().unwrap(),
+                    eth_address: account.address,
+                    token: FEE_TOKEN,
+                    is_legacy: false,
+                }));
+            }
+            OperationKind::Transfer => {
+                let recipient = self.new_account(&[], false);
+                self.main
+                    .sign_transfer(
+                        FEE_TOKEN,
+                        "",
+                        amount,
+                        fee,
+                        &recipient.address,
+                        None,
+                        true,
+                        Default::default(),
+                    )
+                    .0
+                    .into()
+            }
+            OperationKind::TransferToNew => {
+                self.next_seed += 1;
+                let recipient =
+                    ZkSyncAccount::rand_with_seed([self.next_seed, 0x5eed, 0x5eed, 0x5eed]);
+                self.main
+                    .sign_transfer(
+                        FEE_TOKEN,
+                        "",
+                        amount,
+                        fee,
+                        &recipient.address,
+                        None,
+                        true,
+                        Default::default(),
+                    )
+                    .0
+                    .into()
+            }
+            OperationKind::Withdraw => self
+                .main
+                .sign_withdraw(
+                    FEE_TOKEN,
+                    "",
+                    amount,
+                    fee,
+                    &self.main.address,
+                    None,
+                    true,
+                    Default::default(),
+                )
+                .0
+                .into(),
+            OperationKind::ChangePubKey => {
+                let account = self.new_account(&[FEE_TOKEN], false);
+                account
+                    .sign_change_pubkey_tx(
+                        None,
+                        true,
+                        FEE_TOKEN,
+                        fee,
+                        ChangePubKeyType::ECDSA,
+                        Default::default(),
+                    )
+                    .into()
+            }
+            OperationKind::ForcedExit => {
+                // Forced exit can only be applied to the account without a public key.
+                let target = self.new_account(&[FEE_TOKEN], false);
+                self.main
+                    .sign_forced_exit(
+                        FEE_TOKEN,
+                        fee,
+                        &target.address,
+                        None,
+                        true,
+                        Default::default(),
+                    )
+                    .into()
+            }
+            OperationKind::Swap => {
+                let maker_0 = self.new_account(&[SWAP_TOKENS.0], true);
+                let maker_1 = self.new_account(&[SWAP_TOKENS.1], true);
+                let order_0 = maker_0.sign_order(
+                    SWAP_TOKENS.0,
+                    SWAP_TOKENS.1,
+                    BigUint::from(1u32),
+                    BigUint::from(1u32),
+                    amount.clone(),
+                    &maker_0.address,
+                    None,
+                    true,
+                    Default::default(),
+                );
+                let order_1 = maker_1.sign_order(
+                    SWAP_TOKENS.1,
+                    SWAP_TOKENS.0,
+                    BigUint::from(1u32),
+                    BigUint::from(1u32),
+                    amount.clone(),
+                    &maker_1.address,
+                    None,
+                    true,
+                    Default::default(),
+                );
+                self.main
+                    .sign_swap(
+                        (order_0, order_1),
+                        (amount.clone(), amount),
+                        None,
+                        true,
+                        FEE_TOKEN,
+                        "",
+                        fee,
+                    )
+                    .0
+                    .into()
+            }
+            OperationKind::MintNFT => {
+                self.next_seed += 1;
+                self.mint_nft_tx()
+            }
+            OperationKind::WithdrawNFT => {
+                let token = self.nfts.pop().expect("NFTs must be minted in advance");
+                self.main
+                    .sign_withdraw_nft(
+                        token,
+                        FEE_TOKEN,
+                        "",
+                        fee,
+                        &self.main.address,
+                        None,
+                        true,
+                        Default::default(),
+                    )
+                    .0
+                    .into()
+            }
+        };
+        Ok(tx)
+    }
+}
+
+/// Generates the block with the operations from the mix, padded with noops to `block_size` chunks.
+/// If `block_size` is not set, the block is exactly as big as needed to fit all the operations.
+pub fn generate_block(
+    mix: &OperationsMix,
+    block_size: Option<usize>,
+) -> anyhow::Result<ProfiledBlock> {
+    let mut generator = BlockGenerator::new();
+    let nfts_to_withdraw = mix
+        .0
+        .iter()
+        .filter(|(kind, _)| *kind == OperationKind::WithdrawNFT)
+        .map(|(_, count)| count)
+        .sum();
+    generator.mint_nfts(nfts_to_withdraw);
+
+    let mut operations = Vec::new();
+    for &(kind, count) in &mix.0 {
+        for _ in 0..count {
+            operations.push((kind, generator.prepare_operation(kind)));
+        }
+    }
+
+    // Snapshot the state before the block, when all the participants are created.
+    let mut account_tree = CircuitAccountTree::new(account_tree_depth());
+    for (id, account) in generator.state.get_accounts() {
+        account_tree.insert(id, CircuitAccount::from(account));
+    }
+    let previous_root_hash = Block::encode_fr_for_eth(generator.state.root_hash());
+
+    let mut block_transactions = Vec::with_capacity(operations.len());
+    let mut chunk_operations = Vec::new();
+    let mut fees = Vec::new();
+    let mut priority_ops = 0;
+    for (block_index, (kind, operation)) in operations.into_iter().enumerate() {
+        let block_index = block_index as u32;
+        let executed = match operation {
+            Ok(tx) => {
+                let op_success = generator
+                    .state
+                    .execute_tx(tx.clone(), BLOCK_TIMESTAMP)
+                    .map_err(|err| anyhow::anyhow!("Failed to execute {}: {}", kind.name(), err))?;
+                fees.extend(op_success.fee);
+                ExecutedOperations::Tx(Box::new(ExecutedTx {
+                    signed_tx: tx.into(),
+                    success: true,
+                    op: Some(op_success.executed_op),
+                    fail_reason: None,
+                    block_index: Some(block_index),
+                    created_at: Utc::now(),
+                    batch_id: None,
+                }))
+            }
+            Err(priority_op) => {
+                let op_success = generator.state.execute_priority_op(priority_op.clone());
+                let executed = ExecutedPriorityOp {
+                    op: op_success.executed_op,
+                    priority_op: PriorityOp {
+                        serial_id: priority_ops,
+                        data: priority_op,
+                        deadline_block: 0,
+                        eth_hash: H256::zero(),
+                        eth_block: 0,
+                        eth_block_index: None,
+                    },
+                    block_index,
+                    created_at: Utc::now(),
+                };
+                priority_ops += 1;
+                ExecutedOperations::PriorityOp(Box::new(executed))
+            }
+        };
+        let chunks = executed
+            .get_executed_op()
+            .map(|op| op.chunks())
+            .unwrap_or_default();
+        chunk_operations.extend(std::iter::repeat(kind).take(chunks));
+        block_transactions.push(executed);
+    }
+    generator.state.collect_fee(&fees, FEE_ACCOUNT_ID);
+
+    let block_size = block_size.unwrap_or_else(|| chunk_operations.len());
+    anyhow::ensure!(
+        block_size >= chunk_operations.len(),
+        "Operations take {} chunks and don't fit into the block of {} chunks",
+        chunk_operations.len(),
+        block_size
+    );
+    chunk_operations.resize(block_size, OperationKind::Noop);
+
+    let block = Block::new_from_available_block_sizes(
+        BlockNumber(1),
+        generator.state.root_hash(),
+        FEE_ACCOUNT_ID,
+        block_transactions,
+        (0, priority_ops),
+        &[block_size],
+        Default::default(),
+        Default::default(),
+        previous_root_hash,
+        BLOCK_TIMESTAMP,
+    );
+
+    Ok(ProfiledBlock {
+        block,
+        account_tree,
+        chunk_operations,
+    })
+}
@@ -0,0 +1,131 @@
+//! Profiler of the zkSync main circuit.
+//!
+//! Synthesizes the circuit for a block with the chosen operations mix using the test constraint system,
+//! and reports the amount of constraints per operation, per chunk and per subsystem of the circuit
+//! (signature verification, Rescue hashing, tree paths, etc.) as JSON.
+//!
+//! The report can be compared with the baseline one, so the changes to the circuit can be checked for
+//! regressions before the verification keys are regenerated. The amount of constraints doesn't depend
+//! on the witness, so the reports for the same operations mix are deterministic.
+
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+use zksync_circuit::witness::utils::build_block_witness;
+use zksync_crypto::franklin_crypto::bellman::Circuit;
+
+use crate::block::{generate_block, OperationsMix};
+use crate::profiler::ProfilingConstraintSystem;
+use crate::report::{compare_with_baseline, ProfileReport};
+
+mod block;
+mod profiler;
+mod report;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "circuit_profiler",
+    about = "Counts the constraints of the zkSync main circuit for the given operations mix"
+)]
+struct Options {
+    /// Operations to include into the block, e.g. `transfer:10,swap:2,mint_nft`.
+    /// By default, the block contains one operation of each kind.
+    #[structopt(long)]
+    ops: Option<OperationsMix>,
+    /// Size of the block in chunks. By default, the block is exactly as big as needed to fit the operations.
+    #[structopt(long)]
+    block_size: Option<usize>,
+    /// File to write the report to. By default, the report is written to stdout.
+    #[structopt(long, short)]
+    output: Option<PathBuf>,
+    /// Report to compare the results with.
+    #[structopt(long)]
+    baseline: Option<PathBuf>,
+    /// Overwrite the baseline with the current report instead of comparing with it.
+    #[structopt(long, requires = "baseline")]
+    update_baseline: bool,
+    /// Relative growth of any metric (e.g. `0.01` for 1%) above which it's considered a regression.
+    #[structopt(long, default_value = "0")]
+    tolerance: f64,
+}
+
+fn profile(mix: &OperationsMix, block_size: Option<usize>) -> anyhow::Result<ProfileReport> {
+    let mut profiled_block = generate_block(mix, block_size)?;
+    vlog::info!(
+        "Synthesizing the circuit for the block of {} chunks",
+        profiled_block.chunk_operations.len()
+    );
+
+    let circuit = build_block_witness(&mut profiled_block.account_tree, &profiled_block.block)?
+        .into_circuit_instance();
+    let mut cs = ProfilingConstraintSystem::new();
+    circuit
+        .synthesize(&mut cs)
+        .map_err(|err| anyhow::anyhow!("Circuit synthesis failed: {:?}", err))?;
+
+    let satisfied = match cs.which_is_unsatisfied() {
+        Some(constraint) => {
+            vlog::warn!("Circuit is not satisfied, constraint: {}", constraint);
+            false
+        }
+        None => true,
+    };
+
+    Ok(ProfileReport::new(
+        mix,
+        &profiled_block.chunk_operations,
+        cs.into_counts(),
+        satisfied,
+    ))
+}
+
+fn main() -> anyhow::Result<()> {
+    vlog::init();
+    let options = Options::from_args();
+
+    let mix = options.ops.unwrap_or_default();
+    let report = profile(&mix, options.block_size)?;
+    let serialized = serde_json::to_string_pretty(&report)?;
+    match &options.output {
+        Some(path) => std::fs::write(path, &serialized)?,
+        None => println!("{}", serialized),
+    }
+    anyhow::ensure!(report.satisfied, "Circuit has unsatisfied constraints");
+
+    let baseline_path = match options.baseline {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    if options.update_baseline {
+        std::fs::write(&baseline_path, &serialized)?;
+        vlog::info!("Baseline {} is updated", baseline_path.display());
+        return Ok(());
+    }
+
+    let baseline: ProfileReport = serde_json::from_slice(&std::fs::read(&baseline_path)?)?;
+    let changes = compare_with_baseline(&baseline, &report)?;
+    let mut regressions = 0;
+    for change in &changes {
+        let is_regression = change.is_regression(options.tolerance);
+        if is_regression {
+            regressions += 1;
+        }
+        vlog::info!(
+            "{}{}: {:?} -> {:?}",
+            if is_regression { "[REGRESSION] " } else { "" },
+            change.metric,
+            change.baseline,
+            change.current
+        );
+    }
+    anyhow::ensure!(
+        regressions == 0,
+        "{} metrics have regressed compared to the baseline",
+        regressions
+    );
+    vlog::info!(
+        "No regressions compared to the baseline, {} metrics have changed",
+        changes.len()
+    );
+    Ok(())
+}
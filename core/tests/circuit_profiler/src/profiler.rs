@@ -0,0 +1,307 @@
+//! Constraint system counting the constraints by the place in the circuit they were enforced at.
+//!
+//! The place is determined by the namespaces pushed by the circuit: `chunk number {i}` namespaces
+//! at the top level separate the chunks (the gadgets reuse the same name for the nested namespaces,
+//! e.g. for the nodes of the fees tree), the children of `execute_op` namespace are the operation branches, and some well-known
+//! namespaces denote the subsystems of the circuit. Once a namespace is attributed to a subsystem,
+//! all the nested namespaces are attributed to it as well, e.g. Rescue hashes within the tree paths
+//! are counted as tree paths.
+
+// Built-in deps
+use std::collections::BTreeMap;
+// External deps
+use serde::{Deserialize, Serialize};
+use zksync_crypto::franklin_crypto::{
+    bellman::{ConstraintSystem, LinearCombination, SynthesisError, Variable},
+    circuit::test::TestConstraintSystem,
+};
+// Workspace deps
+use zksync_crypto::{Engine, Fr};
+
+/// Part of the circuit which is profiled separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    SignatureVerification,
+    TreePaths,
+    PubdataCommitment,
+    RescueHashing,
+    Other,
+}
+
+impl Subsystem {
+    /// Namespaces of the signature verification gadgets.
+    const SIGNATURE_VERIFICATION: &'static [&'static str] =
+        &["verify circuit signature", "unpack pubkey"];
+    /// Prefixes of the namespaces calculating the roots from the audit paths.
+    const TREE_PATHS: &'static [&'static str] = &[
+        "calculate account root",
+        "calculate new account root",
+        "calculate_validator_root_from_processable_values",
+        "root from operator_account",
+        "continue subroot to root",
+        "continue initial_used_subtree root to old_root",
+        "from merkle tree hash",
+    ];
+    /// Namespaces of the SHA256 hashes of the block commitment.
+    const PUBDATA_COMMITMENT: &'static [&'static str] = &[
+        "initial rolling sha256",
+        "hash old_root",
+        "hash with new_root",
+        "hash with timestamp",
+        "final hash public",
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::SignatureVerification => "signature_verification",
+            Self::TreePaths => "tree_paths",
+            Self::PubdataCommitment => "pubdata_commitment",
+            Self::RescueHashing => "rescue_hashing",
+            Self::Other => "other",
+        }
+    }
+
+    /// Returns the subsystem the namespace belongs to, if any.
+    fn classify(namespace: &str) -> Option<Self> {
+        if Self::SIGNATURE_VERIFICATION.contains(&namespace) {
+            Some(Self::SignatureVerification)
+        } else if Self::TREE_PATHS
+            .iter()
+            .any(|prefix| namespace.starts_with(prefix))
+        {
+            Some(Self::TreePaths)
+        } else if Self::PUBDATA_COMMITMENT.contains(&namespace) {
+            Some(Self::PubdataCommitment)
+        } else if namespace.contains("rescue") || namespace.contains("hash") {
+            Some(Self::RescueHashing)
+        } else {
+            None
+        }
+    }
+}
+
+/// Attribution of the constraints enforced within a namespace.
+#[derive(Debug, Clone, Default)]
+struct Scope {
+    /// Nesting level of the namespace, the root one is 0.
+    depth: usize,
+    chunk: Option<usize>,
+    branch: Option<String>,
+    subsystem: Option<Subsystem>,
+    /// Whether the namespace is the `execute_op` one, so its children are the operation branches.
+    is_execute_op: bool,
+}
+
+impl Scope {
+    fn child(&self, namespace: &str) -> Self {
+        let chunk = match self.chunk {
+            Some(chunk) => Some(chunk),
+            None if self.depth == 0 => namespace
+                .strip_prefix("chunk number ")
+                .and_then(|chunk| chunk.parse().ok()),
+            None => None,
+        };
+        let branch = if self.is_execute_op {
+            Some(namespace.to_owned())
+        } else {
+            self.branch.clone()
+        };
+        Self {
+            depth: self.depth + 1,
+            chunk,
+            is_execute_op: chunk.is_some() && branch.is_none() && namespace == "execute_op",
+            branch,
+            subsystem: self.subsystem.or_else(|| Subsystem::classify(namespace)),
+        }
+    }
+}
+
+/// Amounts of constraints enforced in the parts of the circuit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstraintCounts {
+    pub total: usize,
+    /// Constraints which don't belong to any chunk, e.g. fees collection and the block commitment.
+    pub block_overhead: usize,
+    pub per_chunk: Vec<usize>,
+    /// Constraints within each operation branch, summed over all the chunks.
+    pub per_branch: BTreeMap<String, usize>,
+    pub per_subsystem: BTreeMap<Subsystem, usize>,
+}
+
+/// Wrapper over the `TestConstraintSystem` which counts the enforced constraints.
+pub struct ProfilingConstraintSystem {
+    inner: TestConstraintSystem<Engine>,
+    scopes: Vec<Scope>,
+    counts: ConstraintCounts,
+}
+
+impl ProfilingConstraintSystem {
+    pub fn new() -> Self {
+        Self {
+            inner: TestConstraintSystem::new(),
+            scopes: vec![Scope::default()],
+            counts: ConstraintCounts::default(),
+        }
+    }
+
+    /// Returns the path of the first unsatisfied constraint, if any.
+    pub fn which_is_unsatisfied(&self) -> Option<&str> {
+        self.inner.which_is_unsatisfied()
+    }
+
+    pub fn into_counts(self) -> ConstraintCounts {
+        self.counts
+    }
+
+    fn count_constraint(&mut self) {
+        let scope = self.scopes.last().expect("root scope is never popped");
+        let counts = &mut self.counts;
+
+        counts.total += 1;
+        match scope.chunk {
+            Some(chunk) => {
+                if counts.per_chunk.len() <= chunk {
+                    counts.per_chunk.resize(chunk + 1, 0);
+                }
+                counts.per_chunk[chunk] += 1;
+            }
+            None => counts.block_overhead += 1,
+        }
+        if let Some(branch) = &scope.branch {
+            match counts.per_branch.get_mut(branch) {
+                Some(count) => *count += 1,
+                None => {
+                    counts.per_branch.insert(branch.clone(), 1);
+                }
+            }
+        }
+        *counts
+            .per_subsystem
+            .entry(scope.subsystem.unwrap_or(Subsystem::Other))
+            .or_default() += 1;
+    }
+}
+
+impl Default for ProfilingConstraintSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConstraintSystem<Engine> for ProfilingConstraintSystem {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<Fr, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.inner.alloc(annotation, f)
+    }
+
+    fn alloc_input<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<Fr, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.inner.alloc_input(annotation, f)
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<Engine>) -> LinearCombination<Engine>,
+        LB: FnOnce(LinearCombination<Engine>) -> LinearCombination<Engine>,
+        LC: FnOnce(LinearCombination<Engine>) -> LinearCombination<Engine>,
+    {
+        self.count_constraint();
+        self.inner.enforce(annotation, a, b, c)
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        let name: String = name_fn().into();
+        let scope = self
+            .scopes
+            .last()
+            .expect("root scope is never popped")
+            .child(&name);
+        self.scopes.push(scope);
+        self.inner.push_namespace(|| name);
+    }
+
+    fn pop_namespace(&mut self) {
+        assert!(self.scopes.len() > 1, "root namespace can't be popped");
+        self.scopes.pop();
+        self.inner.pop_namespace();
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope_of(path: &[&str]) -> Scope {
+        path.iter()
+            .fold(Scope::default(), |scope, namespace| scope.child(namespace))
+    }
+
+    #[test]
+    fn constraints_attribution() {
+        let scope = scope_of(&[
+            "chunk number 3",
+            "execute_op",
+            "swap",
+            "verify circuit signature",
+            "musig sha256",
+        ]);
+        assert_eq!(scope.chunk, Some(3));
+        assert_eq!(scope.branch.as_deref(), Some("swap"));
+        assert_eq!(scope.subsystem, Some(Subsystem::SignatureVerification));
+
+        // Nested hashes are attributed to the outermost subsystem.
+        let scope = scope_of(&[
+            "chunk number 0",
+            "calculate account root",
+            "from merkle tree hash 5",
+            "hash tree level 5",
+        ]);
+        assert_eq!(scope.branch, None);
+        assert_eq!(scope.subsystem, Some(Subsystem::TreePaths));
+
+        // Only the direct children of `execute_op` within a chunk are the operation branches.
+        let scope = scope_of(&["chunk number 1", "execute_op", "transfer", "deposit"]);
+        assert_eq!(scope.branch.as_deref(), Some("transfer"));
+        assert_eq!(scope_of(&["execute_op", "transfer"]).branch, None);
+
+        let scope = scope_of(&["hash with timestamp"]);
+        assert_eq!(scope.chunk, None);
+        assert_eq!(scope.subsystem, Some(Subsystem::PubdataCommitment));
+        assert_eq!(
+            scope_of(&["chunk number 0", "execute_op", "swap", "hash orders"]).subsystem,
+            Some(Subsystem::RescueHashing)
+        );
+        assert_eq!(scope_of(&["chunk number 0", "lhs"]).subsystem, None);
+
+        // Nested `chunk number` namespaces (e.g. the fees tree nodes) are not the chunks of the block.
+        let scope = scope_of(&[
+            "calculate fees tree root",
+            "merkle tree level index number 2",
+            "chunk number 1",
+        ]);
+        assert_eq!(scope.chunk, None);
+        let scope = scope_of(&["chunk number 4", "select pubdata", "chunk number 7"]);
+        assert_eq!(scope.chunk, Some(4));
+    }
+}
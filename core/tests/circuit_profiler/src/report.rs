@@ -0,0 +1,275 @@
+//! Profiling report and its comparison with the baseline.
+
+// Built-in deps
+use std::collections::BTreeMap;
+// External deps
+use serde::{Deserialize, Serialize};
+// Local deps
+use crate::block::{OperationKind, OperationsMix};
+use crate::profiler::{ConstraintCounts, Subsystem};
+
+/// Constraints spent on the operations of a single kind.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationProfile {
+    pub count: usize,
+    pub chunks: usize,
+    /// Constraints of all the chunks occupied by the operations.
+    pub constraints: usize,
+    pub constraints_per_operation: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkStats {
+    pub min: usize,
+    pub max: usize,
+    pub average: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileReport {
+    pub block_size: usize,
+    pub operations_mix: BTreeMap<String, usize>,
+    /// Whether the synthesized circuit has no unsatisfied constraints.
+    pub satisfied: bool,
+    pub total_constraints: usize,
+    /// Constraints which don't belong to any chunk, e.g. fees collection and the block commitment.
+    pub block_overhead: usize,
+    pub per_chunk: ChunkStats,
+    /// Padding noops are reported as the `noop` operation.
+    pub per_operation: BTreeMap<String, OperationProfile>,
+    /// Constraints of each operation branch of the circuit. Every chunk contains all the branches.
+    pub branch_constraints_per_chunk: BTreeMap<String, usize>,
+    pub per_subsystem: BTreeMap<Subsystem, usize>,
+}
+
+impl ProfileReport {
+    pub fn new(
+        mix: &OperationsMix,
+        chunk_operations: &[OperationKind],
+        counts: ConstraintCounts,
+        satisfied: bool,
+    ) -> Self {
+        let block_size = chunk_operations.len();
+        let mut operations_mix = BTreeMap::new();
+        for (kind, count) in &mix.0 {
+            *operations_mix.entry(kind.name().to_owned()).or_default() += count;
+        }
+
+        let mut per_operation = BTreeMap::new();
+        for (kind, count) in &mix.0 {
+            per_operation
+                .entry(kind.name().to_owned())
+                .or_insert(OperationProfile {
+                    count: 0,
+                    chunks: 0,
+                    constraints: 0,
+                    constraints_per_operation: 0,
+                })
+                .count += count;
+        }
+        let padding = chunk_operations
+            .iter()
+            .filter(|&&kind| kind == OperationKind::Noop)
+            .count();
+        if padding > 0 {
+            per_operation.insert(
+                OperationKind::Noop.name().to_owned(),
+                OperationProfile {
+                    count: padding,
+                    chunks: 0,
+                    constraints: 0,
+                    constraints_per_operation: 0,
+                },
+            );
+        }
+        for (kind, constraints) in chunk_operations.iter().zip(&counts.per_chunk) {
+            let profile = per_operation
+                .get_mut(kind.name())
+                .expect("all the operations are in the mix");
+            profile.chunks += 1;
+            profile.constraints += constraints;
+        }
+        for profile in per_operation.values_mut() {
+            if profile.count > 0 {
+                profile.constraints_per_operation = profile.constraints / profile.count;
+            }
+        }
+
+        let per_chunk = ChunkStats {
+            min: counts.per_chunk.iter().copied().min().unwrap_or_default(),
+            max: counts.per_chunk.iter().copied().max().unwrap_or_default(),
+            average: counts.per_chunk.iter().sum::<usize>() / block_size.max(1),
+        };
+        let branch_constraints_per_chunk = counts
+            .per_branch
+            .into_iter()
+            .map(|(branch, constraints)| (branch, constraints / block_size.max(1)))
+            .collect();
+
+        Self {
+            block_size,
+            operations_mix,
+            satisfied,
+            total_constraints: counts.total,
+            block_overhead: counts.block_overhead,
+            per_chunk,
+            per_operation,
+            branch_constraints_per_chunk,
+            per_subsystem: counts.per_subsystem,
+        }
+    }
+
+    /// Flattens the report into the named metrics which can be compared between the runs.
+    fn metrics(&self) -> BTreeMap<String, usize> {
+        let mut metrics = BTreeMap::new();
+        metrics.insert("total_constraints".to_owned(), self.total_constraints);
+        metrics.insert("block_overhead".to_owned(), self.block_overhead);
+        metrics.insert("per_chunk.max".to_owned(), self.per_chunk.max);
+        for (operation, profile) in &self.per_operation {
+            metrics.insert(
+                format!("per_operation.{}.constraints_per_operation", operation),
+                profile.constraints_per_operation,
+            );
+        }
+        for (branch, constraints) in &self.branch_constraints_per_chunk {
+            metrics.insert(
+                format!("branch_constraints_per_chunk.{}", branch),
+                *constraints,
+            );
+        }
+        for (subsystem, constraints) in &self.per_subsystem {
+            metrics.insert(format!("per_subsystem.{}", subsystem.name()), *constraints);
+        }
+        metrics
+    }
+}
+
+/// Difference of a single metric between the baseline and the current report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricChange {
+    pub metric: String,
+    pub baseline: Option<usize>,
+    pub current: Option<usize>,
+}
+
+impl MetricChange {
+    /// Whether the metric has grown by more than `tolerance` (e.g. `0.01` for 1%).
+    /// A metric missing in the baseline (e.g. a new operation branch) is always a regression,
+    /// while a removed metric is not.
+    pub fn is_regression(&self, tolerance: f64) -> bool {
+        match (self.baseline, self.current) {
+            (Some(baseline), Some(current)) => current as f64 > baseline as f64 * (1.0 + tolerance),
+            (None, Some(_)) => true,
+            (_, None) => false,
+        }
+    }
+}
+
+/// Compares the report with the baseline, returning all the changed metrics.
+pub fn compare_with_baseline(
+    baseline: &ProfileReport,
+    current: &ProfileReport,
+) -> anyhow::Result<Vec<MetricChange>> {
+    anyhow::ensure!(
+        baseline.block_size == current.block_size
+            && baseline.operations_mix == current.operations_mix,
+        "Baseline was taken for a different block: {} chunks with {:?}, current block is {} chunks with {:?}",
+        baseline.block_size,
+        baseline.operations_mix,
+        current.block_size,
+        current.operations_mix
+    );
+
+    let baseline = baseline.metrics();
+    let current = current.metrics();
+    let mut metrics: Vec<_> = baseline.keys().chain(current.keys()).collect();
+    metrics.sort();
+    metrics.dedup();
+
+    let changes = metrics
+        .into_iter()
+        .map(|metric| MetricChange {
+            metric: metric.clone(),
+            baseline: baseline.get(metric).copied(),
+            current: current.get(metric).copied(),
+        })
+        .filter(|change| change.baseline != change.current)
+        .collect();
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(chunk_constraints: usize, signature_constraints: usize) -> ProfileReport {
+        let mix = OperationsMix(vec![(OperationKind::Transfer, 2)]);
+        let chunk_operations = vec![
+            OperationKind::Transfer,
+            OperationKind::Transfer,
+            OperationKind::Transfer,
+            OperationKind::Transfer,
+            OperationKind::Noop,
+        ];
+        let counts = ConstraintCounts {
+            total: chunk_constraints * 5 + 100,
+            block_overhead: 100,
+            per_chunk: vec![chunk_constraints; 5],
+            per_branch: vec![("transfer".to_owned(), 50)].into_iter().collect(),
+            per_subsystem: vec![(Subsystem::SignatureVerification, signature_constraints)]
+                .into_iter()
+                .collect(),
+        };
+        ProfileReport::new(&mix, &chunk_operations, counts, true)
+    }
+
+    #[test]
+    fn report_per_operation() {
+        let report = report(1000, 2000);
+        assert_eq!(report.block_size, 5);
+        assert_eq!(report.per_chunk.average, 1000);
+        assert_eq!(report.branch_constraints_per_chunk["transfer"], 10);
+
+        let transfer = &report.per_operation["transfer"];
+        assert_eq!((transfer.count, transfer.chunks), (2, 4));
+        assert_eq!(transfer.constraints, 4000);
+        assert_eq!(transfer.constraints_per_operation, 2000);
+        let noop = &report.per_operation["noop"];
+        assert_eq!((noop.count, noop.chunks), (1, 1));
+        assert_eq!(noop.constraints_per_operation, 1000);
+    }
+
+    #[test]
+    fn baseline_comparison() {
+        let baseline = report(1000, 2000);
+        assert!(compare_with_baseline(&baseline, &baseline)
+            .unwrap()
+            .is_empty());
+
+        let changes = compare_with_baseline(&baseline, &report(1000, 2010)).unwrap();
+        assert_eq!(
+            changes,
+            vec![MetricChange {
+                metric: "per_subsystem.signature_verification".to_owned(),
+                baseline: Some(2000),
+                current: Some(2010),
+            }]
+        );
+        assert!(changes[0].is_regression(0.0));
+        assert!(!changes[0].is_regression(0.01));
+
+        let mut new_branch = report(1000, 2000);
+        new_branch
+            .branch_constraints_per_chunk
+            .insert("close".to_owned(), 10);
+        let changes = compare_with_baseline(&baseline, &new_branch).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].is_regression(1.0));
+        let changes = compare_with_baseline(&new_branch, &baseline).unwrap();
+        assert!(!changes[0].is_regression(0.0));
+
+        let mut other_block = report(1000, 2000);
+        other_block.block_size += 1;
+        assert!(compare_with_baseline(&baseline, &other_block).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use zksync::{
+    error::ClientError,
+    provider::Provider,
+    web3::types::{BlockId, BlockNumber},
+};
+use zksync_api_client::rest::client::ClientError as RestClientError;
+use zksync_api_types::v02::{
+    fee::ApiTxFeeTypes,
+    pagination::{PaginationDirection, PaginationQuery},
+    Response, ResultStatus,
+};
+use zksync_types::{TokenLike, TxFeeTypes};
+
+use crate::{
+    account::AccountLifespan,
+    command::{ApiRequestCommand, ApiRequestType},
+    report::ReportLabel,
+};
+
+impl AccountLifespan {
+    pub(super) async fn execute_api_request(
+        &self,
+        command: &ApiRequestCommand,
+    ) -> Result<ReportLabel, ClientError> {
+        match command.request_type {
+            ApiRequestType::RestAccountInfo
+            | ApiRequestType::RestAccountTxs
+            | ApiRequestType::RestBlock
+            | ApiRequestType::RestToken
+            | ApiRequestType::RestTxFee
+            | ApiRequestType::RestNetworkStatus => self.execute_rest_request(command).await,
+            ApiRequestType::RpcAccountInfo
+            | ApiRequestType::RpcTokens
+            | ApiRequestType::RpcTxFee
+            | ApiRequestType::RpcContractAddress => self.execute_rpc_request(command).await,
+            ApiRequestType::Web3BlockNumber
+            | ApiRequestType::Web3Balance
+            | ApiRequestType::Web3Block => self.execute_web3_request(command).await,
+        }
+    }
+
+    async fn execute_rest_request(
+        &self,
+        command: &ApiRequestCommand,
+    ) -> Result<ReportLabel, ClientError> {
+        let address = format!("{:?}", command.address);
+        let response = match command.request_type {
+            ApiRequestType::RestAccountInfo => {
+                self.rest_client.account_info(&address, "committed").await
+            }
+            ApiRequestType::RestAccountTxs => {
+                let query = PaginationQuery {
+                    from: "latest".parse().unwrap(),
+                    limit: 10,
+                    direction: PaginationDirection::Older,
+                };
+                self.rest_client.account_txs(&query, &address).await
+            }
+            ApiRequestType::RestBlock => self.rest_client.block_by_position("lastCommitted").await,
+            ApiRequestType::RestToken => {
+                self.rest_client
+                    .token_by_id(&TokenLike::Id(self.main_token.id))
+                    .await
+            }
+            ApiRequestType::RestTxFee => {
+                self.rest_client
+                    .get_txs_fee(
+                        ApiTxFeeTypes::Transfer,
+                        command.address,
+                        TokenLike::Id(self.main_token.id),
+                    )
+                    .await
+            }
+            ApiRequestType::RestNetworkStatus => self.rest_client.status().await,
+            _ => unreachable!("Not a REST API request: {:?}", command.request_type),
+        };
+
+        Self::rest_response_label(response)
+    }
+
+    async fn execute_rpc_request(
+        &self,
+        command: &ApiRequestCommand,
+    ) -> Result<ReportLabel, ClientError> {
+        let provider = &self.wallet.provider;
+        match command.request_type {
+            ApiRequestType::RpcAccountInfo => {
+                provider.account_info(command.address).await?;
+            }
+            ApiRequestType::RpcTokens => {
+                provider.tokens().await?;
+            }
+            ApiRequestType::RpcTxFee => {
+                provider
+                    .get_tx_fee(TxFeeTypes::Transfer, command.address, self.main_token.id)
+                    .await?;
+            }
+            ApiRequestType::RpcContractAddress => {
+                provider.contract_address().await?;
+            }
+            _ => unreachable!("Not a JSON RPC API request: {:?}", command.request_type),
+        }
+
+        Ok(ReportLabel::done())
+    }
+
+    async fn execute_web3_request(
+        &self,
+        command: &ApiRequestCommand,
+    ) -> Result<ReportLabel, ClientError> {
+        let eth = self.web3.eth();
+        let result = match command.request_type {
+            ApiRequestType::Web3BlockNumber => eth.block_number().await.map(drop),
+            ApiRequestType::Web3Balance => eth.balance(command.address, None).await.map(drop),
+            ApiRequestType::Web3Block => eth
+                .block(BlockId::Number(BlockNumber::Latest))
+                .await
+                .map(drop),
+            _ => unreachable!("Not a web3 API request: {:?}", command.request_type),
+        };
+
+        match result {
+            Ok(()) => Ok(ReportLabel::done()),
+            // Connection issues are retried, same as for the other APIs.
+            Err(zksync::web3::Error::Transport(err)) => Err(ClientError::NetworkError(err)),
+            Err(err) => Ok(ReportLabel::failed(&err.to_string())),
+        }
+    }
+
+    /// Interprets the REST API response: the request is considered failed if
+    /// the server reported an error, while network errors are returned to be retried.
+    fn rest_response_label(
+        response: Result<Response, RestClientError>,
+    ) -> Result<ReportLabel, ClientError> {
+        match response {
+            Ok(response) if matches!(response.status, ResultStatus::Success) => {
+                Ok(ReportLabel::done())
+            }
+            Ok(response) => {
+                let error = format!("REST API returned an error: {:?}", response.error);
+                Ok(ReportLabel::failed(&error))
+            }
+            Err(RestClientError::Other(err)) => Err(ClientError::NetworkError(err.to_string())),
+            Err(err) => Ok(ReportLabel::failed(&err.to_string())),
+        }
+    }
+}
@@ -3,7 +3,7 @@ use zksync::{error::ClientError, operations::SyncTransactionHandle};
 use crate::{
     account::AccountLifespan,
     command::{ExpectedOutcome, IncorrectnessModifier, TxCommand, TxType},
-    report::ReportLabel,
+    report::{ActionType, ReportLabel},
 };
 
 impl AccountLifespan {
@@ -58,9 +58,12 @@ impl AccountLifespan {
                     .unwrap_or(IncorrectnessModifier::None)
             });
 
+        let action = ActionType::Batch {
+            batch_size: batch_command.len(),
+        };
         let provider = self.wallet.provider.clone();
-        self.submit(modifier, || async {
-            self.wallet.provider.send_txs_batch(batch, None).await?;
+        self.submit(action, modifier, || async {
+            self.wallet.provider.submit_txs_batch(batch, None).await?;
             Ok(SyncTransactionHandle::new(main_hash, provider))
         })
         .await
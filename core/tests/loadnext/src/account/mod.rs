@@ -1,11 +1,19 @@
 use std::{
     future::Future,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
 use futures::{channel::mpsc::Sender, SinkExt};
+use tokio::sync::Semaphore;
 
-use zksync::{error::ClientError, operations::SyncTransactionHandle, RpcProvider, Wallet};
+use zksync::{
+    error::ClientError,
+    operations::SyncTransactionHandle,
+    web3::{transports::Http, Web3},
+    RpcProvider, Wallet,
+};
+use zksync_api_client::rest::client::Client as RestClient;
 use zksync_eth_signer::PrivateKeySigner;
 use zksync_types::{Token, H256};
 
@@ -13,11 +21,13 @@ use crate::{
     account_pool::{AddressPool, TestWallet},
     command::{Command, ExpectedOutcome, IncorrectnessModifier, TxCommand},
     config::LoadtestConfig,
-    constants::{COMMIT_TIMEOUT, POLLING_INTERVAL},
-    report::{Report, ReportBuilder, ReportLabel},
+    constants::{COMMIT_TIMEOUT, POLLING_INTERVAL, VERIFY_TIMEOUT},
+    load_profile::acquire_permit,
+    report::{ActionType, Report, ReportBuilder, ReportLabel, ReportStage},
     rng::LoadtestRng,
 };
 
+mod api_command_executor;
 mod batch_command_executor;
 mod tx_command_executor;
 
@@ -43,6 +53,16 @@ pub struct AccountLifespan {
     main_token: Token,
     /// Channel for sending reports about performed operations.
     report_sink: Sender<Report>,
+    /// Client for the zkSync REST API.
+    rest_client: RestClient,
+    /// Client for the zkSync web3 API.
+    web3: Web3<Http>,
+    /// Counterparty for the swaps. It's created once the first swap is executed, since
+    /// it has to be funded and unlocked first.
+    swap_partner: Option<Wallet<PrivateKeySigner, RpcProvider>>,
+    /// Permits to execute operations in the open-loop test.
+    /// If not set, account executes `operations_per_account` operations as fast as possible.
+    operation_permits: Option<Arc<Semaphore>>,
 }
 
 impl AccountLifespan {
@@ -51,12 +71,15 @@ impl AccountLifespan {
         addresses: AddressPool,
         test_account: TestWallet,
         report_sink: Sender<Report>,
+        operation_permits: Option<Arc<Semaphore>>,
     ) -> Self {
         let main_token = test_account
             .wallet
             .tokens
             .resolve(config.main_token.as_str().into())
             .unwrap();
+        let web3_transport =
+            Http::new(&config.zksync_web3_api_addr).expect("Invalid zkSync web3 API address");
 
         Self {
             wallet: test_account.wallet,
@@ -67,6 +90,10 @@ impl AccountLifespan {
             main_token,
 
             report_sink,
+            rest_client: RestClient::new(config.zksync_rest_api_addr.clone()),
+            web3: Web3::new(web3_transport),
+            swap_partner: None,
+            operation_permits,
         }
     }
 
@@ -96,9 +123,22 @@ impl AccountLifespan {
             }
         }
 
-        let command_sequence = self.generate_commands();
-        for command in command_sequence {
-            self.execute_command(command).await;
+        match self.operation_permits.clone() {
+            Some(permits) => {
+                // In the open-loop test, the amount of operations is defined by the scheduler.
+                // We still start with a CPK to unlock the account.
+                self.execute_command(self.change_pubkey_command()).await;
+                while acquire_permit(&permits).await {
+                    let command = self.random_command();
+                    self.execute_command(command).await;
+                }
+            }
+            None => {
+                let command_sequence = self.generate_commands();
+                for command in command_sequence {
+                    self.execute_command(command).await;
+                }
+            }
         }
     }
 
@@ -119,9 +159,7 @@ impl AccountLifespan {
                 Command::Batch(tx_commands) => {
                     self.execute_batch_command(tx_commands.as_ref()).await
                 }
-                Command::ApiRequest(_) => {
-                    todo!()
-                }
+                Command::ApiRequest(api_command) => self.execute_api_request(api_command).await,
             };
 
             let label = match result {
@@ -183,8 +221,12 @@ impl AccountLifespan {
     /// execution result.
     /// Once result is obtained, it's compared to the expected operation outcome in order to check whether
     /// command was completed as planned.
+    ///
+    /// For successful transactions submit-to-commit latency is reported, and the submit-to-verify latency
+    /// is reported as well if it's enabled in the config.
     async fn submit<F, Fut>(
         &self,
+        action: ActionType,
         modifier: IncorrectnessModifier,
         send: F,
     ) -> Result<ReportLabel, ClientError>
@@ -194,6 +236,7 @@ impl AccountLifespan {
     {
         let expected_outcome = modifier.expected_outcome();
 
+        let submitted_at = Instant::now();
         let send_result = send().await;
         let mut handle = match (expected_outcome, send_result) {
             (ExpectedOutcome::ApiRequestFailed, Ok(_handle)) => {
@@ -217,14 +260,19 @@ impl AccountLifespan {
         };
 
         handle.polling_interval(POLLING_INTERVAL).unwrap();
-        let transaction_receipt = handle
+        let handle = handle
             .commit_timeout(COMMIT_TIMEOUT)
-            .wait_for_commit()
-            .await?;
+            .verify_timeout(VERIFY_TIMEOUT);
+        let transaction_receipt = handle.wait_for_commit().await?;
 
         match expected_outcome {
             ExpectedOutcome::TxSucceed if transaction_receipt.fail_reason.is_none() => {
                 // Transaction succeed and it should have.
+                self.report_stage(action, ReportStage::Committed, submitted_at.elapsed())
+                    .await;
+                if self.config.track_verify_latency {
+                    self.track_verification(action, handle, submitted_at);
+                }
                 Ok(ReportLabel::done())
             }
             ExpectedOutcome::TxRejected if transaction_receipt.fail_reason.is_some() => {
@@ -242,16 +290,69 @@ impl AccountLifespan {
         }
     }
 
+    /// Reports the latency of the transaction processing stage.
+    async fn report_stage(&self, action: ActionType, stage: ReportStage, time: Duration) {
+        let report = ReportBuilder::new()
+            .reporter(self.wallet.address())
+            .action(action)
+            .stage(stage)
+            .time(time)
+            .finish();
+
+        if let Err(_err) = self.report_sink.clone().send(report).await {
+            vlog::trace!("Failed to send report to the sink");
+        }
+    }
+
+    /// Spawns a task that waits for the transaction verification and reports the submit-to-verify latency.
+    /// Verification takes much longer than the commitment, so account doesn't wait for it and proceeds
+    /// with the next operations.
+    fn track_verification(
+        &self,
+        action: ActionType,
+        handle: SyncTransactionHandle<RpcProvider>,
+        submitted_at: Instant,
+    ) {
+        let reporter = self.wallet.address();
+        let mut report_sink = self.report_sink.clone();
+        tokio::spawn(async move {
+            if let Err(err) = handle.wait_for_verify().await {
+                vlog::warn!("Waiting for the transaction verification failed: {}", err);
+                return;
+            }
+
+            let report = ReportBuilder::new()
+                .reporter(reporter)
+                .action(action)
+                .stage(ReportStage::Verified)
+                .time(submitted_at.elapsed())
+                .finish();
+            if let Err(_err) = report_sink.send(report).await {
+                vlog::trace!("Failed to send report to the sink");
+            }
+        });
+    }
+
+    fn change_pubkey_command(&self) -> Command {
+        Command::SingleTx(TxCommand::change_pubkey(self.wallet.address()))
+    }
+
+    fn random_command(&mut self) -> Command {
+        Command::random(
+            &mut self.rng,
+            &self.config,
+            self.wallet.address(),
+            &self.addresses,
+        )
+    }
+
     /// Prepares a list of random operations to be executed by an account.
     fn generate_commands(&mut self) -> Vec<Command> {
         // We start with a CPK just to unlock accounts.
-        let mut commands = vec![Command::SingleTx(TxCommand::change_pubkey(
-            self.wallet.address(),
-        ))];
+        let mut commands = vec![self.change_pubkey_command()];
 
         for _ in 0..self.config.operations_per_account {
-            let command = Command::random(&mut self.rng, self.wallet.address(), &self.addresses);
-            commands.push(command)
+            commands.push(self.random_command());
         }
 
         commands
@@ -1,18 +1,22 @@
 use std::convert::TryInto;
 
-use num::{BigUint, Zero};
+use num::{BigUint, One, Zero};
 use zksync::{
     error::ClientError, ethereum::PriorityOpHolder, operations::SyncTransactionHandle,
-    provider::Provider,
+    provider::Provider, types::NFT, utils::private_key_from_seed, RpcProvider, Wallet,
+    WalletCredentials,
 };
-use zksync_types::{tokens::ETH_TOKEN_ID, tx::PackedEthSignature, Nonce, ZkSyncTx, H256};
+use zksync_eth_signer::PrivateKeySigner;
+use zksync_types::{tokens::ETH_TOKEN_ID, tx::TxEthSignature, Nonce, ZkSyncTx, H256};
 
 use crate::{
     account::AccountLifespan,
+    account_pool::AccountCredentials,
     command::{IncorrectnessModifier, TxCommand, TxType},
     constants::{COMMIT_TIMEOUT, POLLING_INTERVAL},
     corrupted_tx::Corrupted,
     report::ReportLabel,
+    rng::Random,
 };
 
 impl AccountLifespan {
@@ -30,6 +34,11 @@ impl AccountLifespan {
             }
             TxType::Deposit => self.execute_deposit(command).await,
             TxType::FullExit => self.execute_full_exit().await,
+            TxType::Swap => self.execute_swap(command).await,
+            TxType::MintNFT => self.execute_mint_nft(command).await,
+            TxType::TransferNFT => self.execute_transfer_nft(command).await,
+            TxType::WithdrawNFT => self.execute_withdraw_nft(command).await,
+            TxType::ForcedExit => self.execute_forced_exit(command).await,
         }
     }
 
@@ -48,9 +57,9 @@ impl AccountLifespan {
     fn apply_modifier(
         &self,
         tx: ZkSyncTx,
-        eth_signature: Option<PackedEthSignature>,
+        eth_signature: Option<TxEthSignature>,
         modifier: IncorrectnessModifier,
-    ) -> (ZkSyncTx, Option<PackedEthSignature>) {
+    ) -> (ZkSyncTx, Option<TxEthSignature>) {
         (tx, eth_signature).apply_modifier(
             modifier,
             self.eth_pk,
@@ -187,8 +196,8 @@ impl AccountLifespan {
         let (tx, eth_signature) = self.build_change_pubkey(command, None).await?;
 
         let provider = self.wallet.provider.clone();
-        self.submit(command.modifier, || async {
-            let tx_hash = provider.send_tx(tx, eth_signature).await?;
+        self.submit(command.command_type.into(), command.modifier, || async {
+            let tx_hash = provider.submit_tx(tx, eth_signature).await?;
             Ok(SyncTransactionHandle::new(tx_hash, provider))
         })
         .await
@@ -198,7 +207,7 @@ impl AccountLifespan {
         &self,
         command: &TxCommand,
         nonce: Option<Nonce>,
-    ) -> Result<(ZkSyncTx, Option<PackedEthSignature>), ClientError> {
+    ) -> Result<(ZkSyncTx, Option<TxEthSignature>), ClientError> {
         let mut builder = self
             .wallet
             .start_change_pubkey()
@@ -218,8 +227,8 @@ impl AccountLifespan {
         let (tx, eth_signature) = self.build_transfer(command, None).await?;
 
         let provider = self.wallet.provider.clone();
-        self.submit(command.modifier, || async {
-            let tx_hash = provider.send_tx(tx, eth_signature).await?;
+        self.submit(command.command_type.into(), command.modifier, || async {
+            let tx_hash = provider.submit_tx(tx, eth_signature).await?;
             Ok(SyncTransactionHandle::new(tx_hash, provider))
         })
         .await
@@ -229,7 +238,7 @@ impl AccountLifespan {
         &self,
         command: &TxCommand,
         nonce: Option<Nonce>,
-    ) -> Result<(ZkSyncTx, Option<PackedEthSignature>), ClientError> {
+    ) -> Result<(ZkSyncTx, Option<TxEthSignature>), ClientError> {
         let mut builder = self
             .wallet
             .start_transfer()
@@ -242,7 +251,7 @@ impl AccountLifespan {
             builder = builder.nonce(nonce);
         }
 
-        let (tx, eth_signature) = builder.tx().await.map_err(Self::tx_creation_error)?;
+        let (tx, eth_signature) = builder.signed_tx().await.map_err(Self::tx_creation_error)?;
 
         Ok(self.apply_modifier(tx, eth_signature, command.modifier))
     }
@@ -251,8 +260,8 @@ impl AccountLifespan {
         let (tx, eth_signature) = self.build_withdraw(command, None).await?;
 
         let provider = self.wallet.provider.clone();
-        self.submit(command.modifier, || async {
-            let tx_hash = provider.send_tx(tx, eth_signature).await?;
+        self.submit(command.command_type.into(), command.modifier, || async {
+            let tx_hash = provider.submit_tx(tx, eth_signature).await?;
             Ok(SyncTransactionHandle::new(tx_hash, provider))
         })
         .await
@@ -262,7 +271,7 @@ impl AccountLifespan {
         &self,
         command: &TxCommand,
         nonce: Option<Nonce>,
-    ) -> Result<(ZkSyncTx, Option<PackedEthSignature>), ClientError> {
+    ) -> Result<(ZkSyncTx, Option<TxEthSignature>), ClientError> {
         let mut builder = self
             .wallet
             .start_withdraw()
@@ -274,8 +283,292 @@ impl AccountLifespan {
             builder = builder.nonce(nonce);
         }
 
-        let (tx, eth_signature) = builder.tx().await.map_err(Self::tx_creation_error)?;
+        let (tx, eth_signature) = builder.signed_tx().await.map_err(Self::tx_creation_error)?;
 
         Ok(self.apply_modifier(tx, eth_signature, command.modifier))
     }
+
+    async fn execute_mint_nft(&self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
+        let (tx, eth_signature) = self
+            .wallet
+            .start_mint_nft()
+            .recipient(command.to)
+            .content_hash(H256::random())
+            .fee_token(self.config.main_token.as_str())
+            .unwrap()
+            .signed_tx()
+            .await
+            .map_err(Self::tx_creation_error)?;
+        let (tx, eth_signature) = self.apply_modifier(tx, eth_signature, command.modifier);
+
+        let provider = self.wallet.provider.clone();
+        self.submit(command.command_type.into(), command.modifier, || async {
+            let tx_hash = provider.submit_tx(tx, eth_signature).await?;
+            Ok(SyncTransactionHandle::new(tx_hash, provider))
+        })
+        .await
+    }
+
+    async fn execute_transfer_nft(&self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
+        let nft = match self.owned_nft().await? {
+            Some(nft) => nft,
+            None => return Ok(ReportLabel::skipped("No NFTs")),
+        };
+
+        // NFT is transferred to one of the test accounts, so it can be used in their further commands.
+        let (tx_nft, tx_fee) = self
+            .wallet
+            .start_transfer_nft()
+            .nft(nft)
+            .to(command.to)
+            .fee_token(self.config.main_token.as_str())
+            .unwrap()
+            .signed_txs()
+            .await
+            .map_err(Self::tx_creation_error)?;
+
+        // Transfer of NFT is a batch, it can be identified by the hash of its first transaction.
+        let main_hash = tx_nft.0.hash();
+        let provider = self.wallet.provider.clone();
+        self.submit(
+            command.command_type.into(),
+            IncorrectnessModifier::None,
+            || async {
+                provider
+                    .submit_txs_batch(vec![tx_nft, tx_fee], None)
+                    .await?;
+                Ok(SyncTransactionHandle::new(main_hash, provider))
+            },
+        )
+        .await
+    }
+
+    async fn execute_withdraw_nft(&self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
+        let nft = match self.owned_nft().await? {
+            Some(nft) => nft,
+            None => return Ok(ReportLabel::skipped("No NFTs")),
+        };
+
+        let (tx, eth_signature) = self
+            .wallet
+            .start_withdraw_nft()
+            .to(command.to)
+            .token(nft.id)
+            .unwrap()
+            .fee_token(self.config.main_token.as_str())
+            .unwrap()
+            .signed_tx()
+            .await
+            .map_err(Self::tx_creation_error)?;
+        let (tx, eth_signature) = self.apply_modifier(tx, eth_signature, command.modifier);
+
+        let provider = self.wallet.provider.clone();
+        self.submit(command.command_type.into(), command.modifier, || async {
+            let tx_hash = provider.submit_tx(tx, eth_signature).await?;
+            Ok(SyncTransactionHandle::new(tx_hash, provider))
+        })
+        .await
+    }
+
+    /// Returns the NFT with the lowest ID among the committed NFTs of the account.
+    async fn owned_nft(&self) -> Result<Option<NFT>, ClientError> {
+        let account_info = self.wallet.account_info().await?;
+
+        Ok(account_info
+            .committed
+            .nfts
+            .into_iter()
+            .map(|(_, nft)| nft)
+            .min_by_key(|nft| nft.id))
+    }
+
+    async fn execute_forced_exit(&self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
+        // The target of a forced exit must exist and must not have the signing key set,
+        // so we create a new account by transferring some funds to it.
+        let amount = if command.amount.is_zero() {
+            BigUint::one()
+        } else {
+            command.amount.clone()
+        };
+        let transfer_result = self
+            .wallet
+            .start_transfer()
+            .to(command.to)
+            .amount(amount)
+            .token(self.config.main_token.as_str())
+            .unwrap()
+            .send()
+            .await;
+        if let Some(label) = self
+            .wait_for_preparation(transfer_result, "Transfer to the forced exit target")
+            .await?
+        {
+            return Ok(label);
+        }
+
+        let (tx, eth_signature) = self
+            .wallet
+            .start_forced_exit()
+            .target(command.to)
+            .token(self.config.main_token.as_str())
+            .unwrap()
+            .signed_tx()
+            .await
+            .map_err(Self::tx_creation_error)?;
+        let (tx, eth_signature) = self.apply_modifier(tx, eth_signature, command.modifier);
+
+        let provider = self.wallet.provider.clone();
+        self.submit(command.command_type.into(), command.modifier, || async {
+            let tx_hash = provider.submit_tx(tx, eth_signature).await?;
+            Ok(SyncTransactionHandle::new(tx_hash, provider))
+        })
+        .await
+    }
+
+    async fn execute_swap(&mut self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
+        if self.swap_partner.is_none() {
+            if let Some(label) = self.init_swap_partner().await? {
+                return Ok(label);
+            }
+        }
+        let partner = self.swap_partner.as_ref().unwrap();
+
+        // Orders are matched with the `1:1` ratio: the account sells the main token for ETH,
+        // and the partner sells ETH for the main token.
+        let amount = if command.amount.is_zero() {
+            BigUint::one()
+        } else {
+            command.amount.clone()
+        };
+        let (own_order, own_signature) = self
+            .wallet
+            .start_order()
+            .token_sell(self.config.main_token.as_str())
+            .unwrap()
+            .token_buy(ETH_TOKEN_ID)
+            .unwrap()
+            .amount(amount.clone())
+            .order()
+            .await
+            .map_err(Self::tx_creation_error)?;
+        let (partner_order, partner_signature) = partner
+            .start_order()
+            .token_sell(ETH_TOKEN_ID)
+            .unwrap()
+            .token_buy(self.config.main_token.as_str())
+            .unwrap()
+            .amount(amount)
+            .order()
+            .await
+            .map_err(Self::tx_creation_error)?;
+
+        let (tx, eth_signatures) = self
+            .wallet
+            .start_swap()
+            .orders(
+                (own_order, partner_order),
+                (own_signature, partner_signature),
+            )
+            .fee_token(self.config.main_token.as_str())
+            .unwrap()
+            .tx()
+            .await
+            .map_err(Self::tx_creation_error)?;
+
+        let provider = self.wallet.provider.clone();
+        self.submit(command.command_type.into(), command.modifier, || async {
+            let tx_hash = provider.send_swap(tx, eth_signatures).await?;
+            Ok(SyncTransactionHandle::new(tx_hash, provider))
+        })
+        .await
+    }
+
+    /// Creates the counterparty for swaps. Partner receives ETH through a deposit from
+    /// the L1 balance of the account, and then sets its signing key.
+    ///
+    /// Returns the report label if the partner can't be initialized.
+    async fn init_swap_partner(&mut self) -> Result<Option<ReportLabel>, ClientError> {
+        let (eth_balance, _) = self.l1_balances().await?;
+        if eth_balance.is_zero() {
+            return Ok(Some(ReportLabel::skipped("No L1 balance")));
+        }
+
+        let credentials = AccountCredentials::random(&mut self.rng);
+        let zksync_pk = private_key_from_seed(credentials.eth_pk.as_bytes())
+            .expect("Can't generate the zkSync private key");
+        let wallet_credentials = WalletCredentials::<PrivateKeySigner>::from_pk(
+            credentials.address,
+            zksync_pk,
+            Some(credentials.eth_pk),
+        );
+
+        // A small share of the L1 balance is enough to pay for the partner's `ChangePubKey`
+        // and to sell ETH in the swaps.
+        let deposit_amount = (eth_balance / 10u32)
+            .try_into()
+            .unwrap_or_else(|_| u128::max_value())
+            .into();
+        let ethereum = self.wallet.ethereum(&self.config.web3_url).await?;
+        let eth_tx_hash = match ethereum
+            .deposit(ETH_TOKEN_ID, deposit_amount, credentials.address)
+            .await
+        {
+            Ok(hash) => hash,
+            Err(err) => {
+                let reason = format!("Unable to perform an L1 operation. Reason: {}", err);
+                return Ok(Some(ReportLabel::skipped(&reason)));
+            }
+        };
+        let label = self.handle_priority_op(eth_tx_hash).await?;
+        if !matches!(label, ReportLabel::ActionDone) {
+            return Ok(Some(label));
+        }
+
+        // Deposit is committed, so the wallet is created with the account ID already set.
+        let partner = Wallet::new(self.wallet.provider.clone(), wallet_credentials).await?;
+        let change_pubkey_result = partner
+            .start_change_pubkey()
+            .fee_token(ETH_TOKEN_ID)
+            .unwrap()
+            .send()
+            .await;
+        if let Some(label) = self
+            .wait_for_preparation(change_pubkey_result, "Swap partner ChangePubKey")
+            .await?
+        {
+            return Ok(Some(label));
+        }
+
+        self.swap_partner = Some(partner);
+        Ok(None)
+    }
+
+    /// Waits for the commitment of an auxiliary transaction which prepares the state for the command.
+    ///
+    /// Returns the report label if the transaction was not executed.
+    async fn wait_for_preparation(
+        &self,
+        send_result: Result<SyncTransactionHandle<RpcProvider>, ClientError>,
+        description: &str,
+    ) -> Result<Option<ReportLabel>, ClientError> {
+        let mut handle = match send_result {
+            Ok(handle) => handle,
+            Err(err @ ClientError::NetworkError(_)) => return Err(err),
+            Err(err) => {
+                let error = format!("{} was rejected: {}", description, err);
+                return Ok(Some(ReportLabel::failed(&error)));
+            }
+        };
+
+        handle.polling_interval(POLLING_INTERVAL).unwrap();
+        let transaction_receipt = handle
+            .commit_timeout(COMMIT_TIMEOUT)
+            .wait_for_commit()
+            .await?;
+
+        Ok(transaction_receipt.fail_reason.map(|reason| {
+            let error = format!("{} failed: {}", description, reason);
+            ReportLabel::failed(&error)
+        }))
+    }
 }
@@ -1,12 +1,131 @@
+use rand::{seq::SliceRandom, Rng};
+
 use zksync_types::Address;
 
-use crate::account_pool::AddressPool;
+use crate::{account_pool::AddressPool, all::All, config::LoadtestConfig, rng::LoadtestRng};
+
+/// API used to perform a request.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ApiType {
+    /// REST API v0.2.
+    Rest,
+    /// JSON RPC API.
+    JsonRpc,
+    /// Ethereum-compatible web3 API.
+    Web3,
+}
+
+impl ApiType {
+    /// Chooses the API according to the weights set in the config.
+    fn random(rng: &mut LoadtestRng, config: &LoadtestConfig) -> Self {
+        let weighted = [
+            (Self::Rest, config.rest_api_weight),
+            (Self::JsonRpc, config.json_rpc_api_weight),
+            (Self::Web3, config.web3_api_weight),
+        ];
+
+        weighted
+            .choose_weighted(rng, |item| item.1)
+            .expect("API weights are checked when the config is loaded")
+            .0
+    }
+}
+
+/// Read-only request to a certain API endpoint.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ApiRequestType {
+    RestAccountInfo,
+    RestAccountTxs,
+    RestBlock,
+    RestToken,
+    RestTxFee,
+    RestNetworkStatus,
+    RpcAccountInfo,
+    RpcTokens,
+    RpcTxFee,
+    RpcContractAddress,
+    Web3BlockNumber,
+    Web3Balance,
+    Web3Block,
+}
+
+impl All for ApiRequestType {
+    fn all() -> &'static [Self] {
+        &[
+            Self::RestAccountInfo,
+            Self::RestAccountTxs,
+            Self::RestBlock,
+            Self::RestToken,
+            Self::RestTxFee,
+            Self::RestNetworkStatus,
+            Self::RpcAccountInfo,
+            Self::RpcTokens,
+            Self::RpcTxFee,
+            Self::RpcContractAddress,
+            Self::Web3BlockNumber,
+            Self::Web3Balance,
+            Self::Web3Block,
+        ]
+    }
+}
+
+impl ApiRequestType {
+    /// Returns the API which serves the request.
+    pub fn api(self) -> ApiType {
+        match self {
+            Self::RestAccountInfo
+            | Self::RestAccountTxs
+            | Self::RestBlock
+            | Self::RestToken
+            | Self::RestTxFee
+            | Self::RestNetworkStatus => ApiType::Rest,
+            Self::RpcAccountInfo | Self::RpcTokens | Self::RpcTxFee | Self::RpcContractAddress => {
+                ApiType::JsonRpc
+            }
+            Self::Web3BlockNumber | Self::Web3Balance | Self::Web3Block => ApiType::Web3,
+        }
+    }
+
+    /// Chooses the API according to the config weights, and then picks one of its endpoints
+    /// with the equal probability.
+    fn random(rng: &mut LoadtestRng, config: &LoadtestConfig) -> Self {
+        let api = ApiType::random(rng, config);
+        let endpoints: Vec<_> = Self::all()
+            .iter()
+            .copied()
+            .filter(|request_type| request_type.api() == api)
+            .collect();
 
+        endpoints[rng.gen_range(0..endpoints.len())]
+    }
+}
+
+/// Complete description of an API request that must be performed by a test wallet.
 #[derive(Debug, Clone)]
-pub enum ApiRequestCommand {}
+pub struct ApiRequestCommand {
+    /// Requested endpoint.
+    pub request_type: ApiRequestType,
+    /// Address of the account to be requested (if applicable).
+    pub address: Address,
+}
 
 impl ApiRequestCommand {
-    pub fn random(_own_address: Address, _addresses: &AddressPool) -> Self {
-        todo!()
+    pub fn random(
+        rng: &mut LoadtestRng,
+        config: &LoadtestConfig,
+        own_address: Address,
+        addresses: &AddressPool,
+    ) -> Self {
+        // Half of the requests are about the own account, the rest are about random test accounts.
+        let address = if rng.gen_bool(0.5) {
+            own_address
+        } else {
+            addresses.random_address(rng)
+        };
+
+        Self {
+            request_type: ApiRequestType::random(rng, config),
+            address,
+        }
     }
 }
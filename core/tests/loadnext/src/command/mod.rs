@@ -4,13 +4,11 @@ use static_assertions::const_assert;
 use zksync_types::Address;
 
 use crate::{
-    account_pool::AddressPool,
-    constants::MAX_BATCH_SIZE,
-    rng::{LoadtestRng, Random},
+    account_pool::AddressPool, config::LoadtestConfig, constants::MAX_BATCH_SIZE, rng::LoadtestRng,
 };
 
 pub use self::{
-    api_command::ApiRequestCommand,
+    api_command::{ApiRequestCommand, ApiRequestType, ApiType},
     tx_command::{ExpectedOutcome, IncorrectnessModifier, TxCommand, TxType},
 };
 
@@ -18,8 +16,6 @@ mod api_command;
 mod tx_command;
 
 /// Generic command that can be executed by the loadtest.
-///
-/// `Command::ApiRequest` is currently unused.
 #[derive(Debug, Clone)]
 pub enum Command {
     SingleTx(TxCommand),
@@ -35,35 +31,42 @@ enum CommandType {
     ApiRequest,
 }
 
-impl Random for CommandType {
-    fn random(rng: &mut LoadtestRng) -> Self {
-        // Chances of a certain event generation.
+impl CommandType {
+    fn random(rng: &mut LoadtestRng, api_requests_share: f32) -> Self {
+        // Chances of a certain transaction command generation.
         // You must maintain the sum of these constants to be equal to 1.0f32.
         const SINGLE_TX_CHANCE: f32 = 0.7;
         const BATCH_CHANCE: f32 = 0.3;
-        // We don't generate API requests at the moment.
-        const _API_REQUEST_CHANCE: f32 = 0.0;
 
-        const _CHANCES_SUM: f32 = SINGLE_TX_CHANCE + BATCH_CHANCE + _API_REQUEST_CHANCE;
+        const _CHANCES_SUM: f32 = SINGLE_TX_CHANCE + BATCH_CHANCE;
         // Unfortunately. f64::abs()` is not yet a `const` function.
         const_assert!(
             -f32::EPSILON <= (_CHANCES_SUM - 1.0f32) && (_CHANCES_SUM - 1.0f32) <= f32::EPSILON
         );
-        let chance = rng.gen_range(0.0f32..1.0f32);
 
+        // API requests share is configurable, the rest of commands are split between
+        // transactions and batches.
+        if rng.gen_range(0.0f32..1.0f32) < api_requests_share {
+            return Self::ApiRequest;
+        }
+
+        let chance = rng.gen_range(0.0f32..1.0f32);
         if chance <= SINGLE_TX_CHANCE {
             Self::SingleTx
-        } else if chance <= (SINGLE_TX_CHANCE + BATCH_CHANCE) {
-            Self::Batch
         } else {
-            Self::ApiRequest
+            Self::Batch
         }
     }
 }
 
 impl Command {
-    pub fn random(rng: &mut LoadtestRng, own_address: Address, addresses: &AddressPool) -> Self {
-        match CommandType::random(rng) {
+    pub fn random(
+        rng: &mut LoadtestRng,
+        config: &LoadtestConfig,
+        own_address: Address,
+        addresses: &AddressPool,
+    ) -> Self {
+        match CommandType::random(rng, config.api_requests_share) {
             CommandType::SingleTx => Self::SingleTx(TxCommand::random(rng, own_address, addresses)),
             CommandType::Batch => {
                 // TODO: For some reason, batches of size 1 are being rejected because of nonce mistmatch.
@@ -88,9 +91,12 @@ impl Command {
 
                 Self::Batch(batch_command)
             }
-            CommandType::ApiRequest => {
-                unreachable!("We don't generate API commands currently")
-            }
+            CommandType::ApiRequest => Self::ApiRequest(ApiRequestCommand::random(
+                rng,
+                config,
+                own_address,
+                addresses,
+            )),
         }
     }
 }
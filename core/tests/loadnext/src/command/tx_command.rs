@@ -20,6 +20,11 @@ pub enum TxType {
     WithdrawToOther,
     FullExit,
     ChangePubKey,
+    Swap,
+    MintNFT,
+    TransferNFT,
+    WithdrawNFT,
+    ForcedExit,
 }
 
 impl All for TxType {
@@ -32,6 +37,11 @@ impl All for TxType {
             Self::WithdrawToOther,
             Self::FullExit,
             Self::ChangePubKey,
+            Self::Swap,
+            Self::MintNFT,
+            Self::TransferNFT,
+            Self::WithdrawNFT,
+            Self::ForcedExit,
         ]
     }
 }
@@ -50,6 +60,11 @@ impl AllWeighted for TxType {
             (Self::WithdrawToOther, DEFAULT_WEIGHT),
             (Self::FullExit, DEFAULT_WEIGHT),
             (Self::ChangePubKey, DEFAULT_WEIGHT),
+            (Self::Swap, DEFAULT_WEIGHT),
+            (Self::MintNFT, DEFAULT_WEIGHT),
+            (Self::TransferNFT, DEFAULT_WEIGHT),
+            (Self::WithdrawNFT, DEFAULT_WEIGHT),
+            (Self::ForcedExit, DEFAULT_WEIGHT),
         ]
     }
 }
@@ -69,17 +84,22 @@ impl TxType {
 
     /// Checks whether `TxType` can be used as a part of the batch.
     fn is_batchable(self) -> bool {
-        !matches!(self, Self::Deposit | Self::FullExit)
+        // Swaps, NFT and forced exit flows consist of several dependent steps,
+        // thus they are only sent as standalone transactions.
+        matches!(
+            self,
+            Self::TransferToNew
+                | Self::TransferToExisting
+                | Self::WithdrawToSelf
+                | Self::WithdrawToOther
+                | Self::ChangePubKey
+        )
     }
 
     fn is_withdrawal(self) -> bool {
         matches!(self, Self::WithdrawToOther | Self::WithdrawToSelf)
     }
 
-    fn is_change_pubkey(self) -> bool {
-        matches!(self, Self::ChangePubKey)
-    }
-
     fn is_priority(self) -> bool {
         matches!(self, Self::Deposit | Self::FullExit)
     }
@@ -87,6 +107,21 @@ impl TxType {
     fn is_target_self(self) -> bool {
         matches!(self, Self::WithdrawToSelf | Self::FullExit)
     }
+
+    /// Checks whether the transaction has no amount of fungible tokens to be corrupted.
+    fn has_no_amount(self) -> bool {
+        matches!(
+            self,
+            Self::ChangePubKey | Self::MintNFT | Self::WithdrawNFT | Self::ForcedExit
+        )
+    }
+
+    /// Checks whether the transaction can only be sent without any modifier.
+    fn is_always_correct(self) -> bool {
+        // Swap is signed by two accounts and `TransferNFT` is a batch of two transfers,
+        // corrupting them doesn't test anything new in comparison to the regular transfers.
+        matches!(self, Self::Swap | Self::TransferNFT)
+    }
 }
 
 /// Modifier to be applied to the transaction in order to make it incorrect.
@@ -246,7 +281,11 @@ impl TxCommand {
         };
 
         // Check whether we should use a non-existent address.
-        if matches!(command.command_type, TxType::TransferToNew) {
+        // Forced exit requires the target to be an account without the signing key set.
+        if matches!(
+            command.command_type,
+            TxType::TransferToNew | TxType::ForcedExit
+        ) {
             command.to = Address::random();
         }
 
@@ -257,7 +296,11 @@ impl TxCommand {
 
         // Transactions that have no amount field.
         let no_amount_field =
-            command.command_type.is_change_pubkey() && command.modifier.affects_amount();
+            command.command_type.has_no_amount() && command.modifier.affects_amount();
+        // Forced exit can't be verified by the Ethereum signature, since it's not required
+        // for a standalone transaction.
+        let forced_exit_eth_signature = matches!(command.command_type, TxType::ForcedExit)
+            && command.modifier == IncorrectnessModifier::IncorrectEthSignature;
         // It doesn't make sense to fail contract-based functions.
         let incorrect_priority_op = command.command_type.is_priority();
         // Amount doesn't have to be packable for withdrawals.
@@ -265,7 +308,12 @@ impl TxCommand {
             command.command_type.is_withdrawal() && command.modifier.is_not_packable_amount();
 
         // Check whether generator modifier does not make sense.
-        if no_amount_field || incorrect_priority_op || unpackable_withdrawal {
+        if no_amount_field
+            || incorrect_priority_op
+            || unpackable_withdrawal
+            || forced_exit_eth_signature
+            || command.command_type.is_always_correct()
+        {
             command.modifier = IncorrectnessModifier::None;
         }
 
@@ -1,5 +1,7 @@
 use serde::Deserialize;
 
+use crate::load_profile::{LoadProfile, LoadProfileKind};
+
 /// Configuration for the loadtest.
 ///
 /// This structure is meant to provide the least possible amount of parameters:
@@ -14,6 +16,13 @@ pub struct LoadtestConfig {
     pub zksync_rpc_addr: String,
     /// Address of the Ethereum web3 API.
     pub web3_url: String,
+    /// Address of the zkSync REST API.
+    /// Requests to the API v0.2 are sent as a part of the API workload.
+    #[serde(default = "default_rest_api_addr")]
+    pub zksync_rest_api_addr: String,
+    /// Address of the zkSync web3 API.
+    #[serde(default = "default_web3_api_addr")]
+    pub zksync_web3_api_addr: String,
     /// Used Ethereum network (e.g. `rinkeby` or `localhost`).
     pub eth_network: String,
 
@@ -27,8 +36,37 @@ pub struct LoadtestConfig {
     /// Amount of operations per account.
     /// This option configures the "length" of the test:
     /// how many individual operations each account of the test will execute.
+    /// Only used by the closed-loop profile.
     pub operations_per_account: usize,
 
+    /// Load profile of the test. With the default `closed_loop` profile each account executes
+    /// `operations_per_account` operations as fast as possible. Other profiles are open-loop:
+    /// operations are started at the rate defined by the profile during `duration_secs`.
+    #[serde(default)]
+    pub load_profile: LoadProfileKind,
+    /// Duration of the open-loop test.
+    #[serde(default = "default_duration_secs")]
+    pub duration_secs: u64,
+    /// Rate of operations for the `constant` profile, final rate for the `ramp` and `step` profiles,
+    /// and the base rate for the `spike` profile.
+    #[serde(default = "default_target_tps")]
+    pub target_tps: f64,
+    /// Initial rate of operations for the `ramp` and `step` profiles.
+    #[serde(default)]
+    pub initial_tps: f64,
+    /// Amount of steps in the `step` profile.
+    #[serde(default = "default_steps_amount")]
+    pub steps_amount: u32,
+    /// Rate of operations during the spike in the `spike` profile.
+    #[serde(default)]
+    pub spike_tps: f64,
+    /// Moment of the spike start, relative to the test start.
+    #[serde(default)]
+    pub spike_start_secs: u64,
+    /// Duration of the spike.
+    #[serde(default)]
+    pub spike_duration_secs: u64,
+
     /// Symbolic representation of the ERC-20 token to be used in test.
     ///
     /// Token must satisfy two criteria:
@@ -45,11 +83,112 @@ pub struct LoadtestConfig {
     pub seed: Option<String>,
     /// Allowed percent of failed transactions
     pub allowed_percent: u8,
+
+    /// Directory to store the JSON report and the CSV time series into.
+    /// If not set, results are only logged.
+    pub report_dir: Option<String>,
+    /// Size of the time series window.
+    #[serde(default = "default_report_window_secs")]
+    pub report_window_secs: u64,
+    /// Whether to wait for the verification of the executed transactions in order to measure
+    /// the submit-to-verify latency. Test lasts until all the transactions are verified.
+    #[serde(default)]
+    pub track_verify_latency: bool,
+    /// Service level objective: maximum p95 latency of every operation type, in milliseconds.
+    /// For transactions submit-to-commit latency is checked, for API requests the response time is checked.
+    pub slo_p95_latency_ms: Option<u64>,
+    /// Service level objective: maximum percent of failed operations.
+    pub slo_max_error_percent: Option<f64>,
+
+    /// Share of the read API requests among all the commands executed by accounts.
+    /// Must be in range `[0.0; 1.0)`, the rest of commands are transactions and batches.
+    #[serde(default = "default_api_requests_share")]
+    pub api_requests_share: f32,
+    /// Relative weight of the REST API requests in the API workload.
+    /// Weights must be non-negative, and at least one of them must be positive if API requests are sent.
+    #[serde(default = "default_api_weight")]
+    pub rest_api_weight: f32,
+    /// Relative weight of the JSON RPC API requests in the API workload.
+    #[serde(default = "default_api_weight")]
+    pub json_rpc_api_weight: f32,
+    /// Relative weight of the web3 API requests in the API workload.
+    #[serde(default = "default_api_weight")]
+    pub web3_api_weight: f32,
+}
+
+fn default_duration_secs() -> u64 {
+    300
+}
+
+fn default_target_tps() -> f64 {
+    10.0
+}
+
+fn default_steps_amount() -> u32 {
+    5
+}
+
+fn default_report_window_secs() -> u64 {
+    10
+}
+
+fn default_rest_api_addr() -> String {
+    "http://127.0.0.1:3001".into()
+}
+
+fn default_web3_api_addr() -> String {
+    "http://127.0.0.1:3002".into()
+}
+
+fn default_api_requests_share() -> f32 {
+    0.2
+}
+
+fn default_api_weight() -> f32 {
+    1.0
 }
 
 impl LoadtestConfig {
     pub fn from_env() -> envy::Result<Self> {
-        envy::from_env()
+        let config: Self = envy::from_env()?;
+        config.check_api_weights().map_err(envy::Error::Custom)?;
+        Ok(config)
+    }
+
+    /// Checks that the API to send a request to can be chosen according to the weights.
+    fn check_api_weights(&self) -> Result<(), String> {
+        let weights = [
+            ("REST_API_WEIGHT", self.rest_api_weight),
+            ("JSON_RPC_API_WEIGHT", self.json_rpc_api_weight),
+            ("WEB3_API_WEIGHT", self.web3_api_weight),
+        ];
+        for (name, weight) in &weights {
+            if !weight.is_finite() || *weight < 0.0 {
+                return Err(format!(
+                    "{} must be a non-negative number, got {}",
+                    name, weight
+                ));
+            }
+        }
+        if self.api_requests_share > 0.0 && weights.iter().all(|(_, weight)| *weight <= 0.0) {
+            return Err(
+                "At least one API must have a positive weight if API_REQUESTS_SHARE is positive"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the expected amount of operations per account.
+    /// For the open-loop profiles it's estimated from the highest rate of the profile.
+    pub fn expected_operations_per_account(&self) -> usize {
+        match LoadProfile::from_config(self) {
+            Some(profile) => {
+                let operations = profile.max_tps() * self.duration_secs as f64;
+                (operations / self.accounts_amount as f64).ceil() as usize
+            }
+            None => self.operations_per_account,
+        }
     }
 }
 
@@ -61,14 +200,59 @@ impl Default for LoadtestConfig {
         Self {
             zksync_rpc_addr: "http://127.0.0.1:3030".into(),
             web3_url: "http://127.0.0.1:8545".into(),
+            zksync_rest_api_addr: default_rest_api_addr(),
+            zksync_web3_api_addr: default_web3_api_addr(),
             eth_network: "localhost".into(),
             master_wallet_pk: "74d8b3a188f7260f67698eb44da07397a298df5427df681ef68c45b34b61f998"
                 .into(),
             accounts_amount: 80,
             operations_per_account: 40,
+            load_profile: LoadProfileKind::default(),
+            duration_secs: default_duration_secs(),
+            target_tps: default_target_tps(),
+            initial_tps: 0.0,
+            steps_amount: default_steps_amount(),
+            spike_tps: 0.0,
+            spike_start_secs: 0,
+            spike_duration_secs: 0,
             main_token: "DAI".into(),
             seed: None,
             allowed_percent: 10,
+            report_dir: None,
+            report_window_secs: default_report_window_secs(),
+            track_verify_latency: false,
+            slo_p95_latency_ms: None,
+            slo_max_error_percent: None,
+            api_requests_share: default_api_requests_share(),
+            rest_api_weight: default_api_weight(),
+            json_rpc_api_weight: default_api_weight(),
+            web3_api_weight: default_api_weight(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_weights() {
+        let mut config = LoadtestConfig::default();
+        assert!(config.check_api_weights().is_ok());
+
+        config.rest_api_weight = 0.0;
+        config.json_rpc_api_weight = 0.0;
+        assert!(config.check_api_weights().is_ok());
+
+        config.web3_api_weight = 0.0;
+        assert!(config.check_api_weights().is_err());
+        // No API is chosen if the API requests are not sent.
+        config.api_requests_share = 0.0;
+        assert!(config.check_api_weights().is_ok());
+
+        config.web3_api_weight = -1.0;
+        assert!(config.check_api_weights().is_err());
+        config.web3_api_weight = f32::NAN;
+        assert!(config.check_api_weights().is_err());
+    }
+}
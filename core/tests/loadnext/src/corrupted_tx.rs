@@ -4,7 +4,10 @@ use zksync::utils::{
     private_key_from_seed,
 };
 use zksync_types::{
-    tx::{ChangePubKeyECDSAData, ChangePubKeyEthAuthData, PackedEthSignature, TxSignature},
+    tx::{
+        ChangePubKeyECDSAData, ChangePubKeyEthAuthData, PackedEthSignature, TxEthSignature,
+        TxSignature,
+    },
     TokenId, ZkSyncTx, H256,
 };
 
@@ -69,7 +72,7 @@ pub trait Corrupted: Sized {
     }
 }
 
-impl Corrupted for (ZkSyncTx, Option<PackedEthSignature>) {
+impl Corrupted for (ZkSyncTx, Option<TxEthSignature>) {
     fn resign(&mut self, eth_pk: H256, token_symbol: &str, decimals: u8) {
         let zksync_pk = private_key_from_seed(eth_pk.as_bytes()).unwrap();
 
@@ -118,8 +121,9 @@ impl Corrupted for (ZkSyncTx, Option<PackedEthSignature>) {
         };
 
         if let Some(eth_sig) = &mut self.1 {
-            *eth_sig = PackedEthSignature::sign(&eth_pk, &eth_message)
-                .expect("Signing the transfer unexpectedly failed")
+            let signature = PackedEthSignature::sign(&eth_pk, &eth_message)
+                .expect("Signing the transfer unexpectedly failed");
+            *eth_sig = TxEthSignature::EthereumSignature(signature);
         }
     }
 
@@ -138,7 +142,10 @@ impl Corrupted for (ZkSyncTx, Option<PackedEthSignature>) {
 
         self.resign(eth_pk, token_symbol, decimals);
         let (tx, eth_signature) = self;
-        (tx, eth_signature.and(bad_signature))
+        (
+            tx,
+            eth_signature.and(bad_signature.map(TxEthSignature::EthereumSignature)),
+        )
     }
 
     fn bad_zksync_signature(mut self) -> Self {
@@ -328,7 +335,7 @@ mod tests {
     const AMOUNT: u64 = 100;
     const FEE: u64 = 100;
 
-    fn create_transfer(account: &ZkSyncAccount) -> (ZkSyncTx, Option<PackedEthSignature>) {
+    fn create_transfer(account: &ZkSyncAccount) -> (ZkSyncTx, Option<TxEthSignature>) {
         let (transfer, eth_signature) = account.sign_transfer(
             TokenId(0),
             "ETH",
@@ -341,7 +348,7 @@ mod tests {
         );
         let tx = ZkSyncTx::from(transfer);
 
-        (tx, eth_signature)
+        (tx, eth_signature.map(TxEthSignature::EthereumSignature))
     }
 
     fn unwrap_transfer(transfer: ZkSyncTx) -> Transfer {
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use futures::{channel::mpsc, future::join_all};
 
 use tokio::task::JoinHandle;
@@ -8,7 +10,10 @@ use zksync::{
 use zksync_types::{tx::TxHash, TransactionReceipt, TxFeeTypes, U256};
 
 use crate::{
-    account::AccountLifespan, account_pool::AccountPool, config::LoadtestConfig,
+    account::AccountLifespan,
+    account_pool::AccountPool,
+    config::LoadtestConfig,
+    load_profile::{LoadProfile, OperationScheduler},
     report_collector::LoadtestResult,
 };
 use crate::{constants::*, report_collector::ReportCollector};
@@ -22,6 +27,7 @@ use crate::{constants::*, report_collector::ReportCollector};
 /// - Spawning the report collector.
 /// - Distributing the funds among the test wallets.
 /// - Spawning account lifespan futures.
+/// - Scheduling the operations for the open-loop load profiles.
 /// - Awaiting for all the account futures to complete.
 /// - Getting the final test resolution from the report collector.
 #[derive(Debug)]
@@ -54,7 +60,14 @@ impl Executor {
         self.mint().await?;
         self.deposit_to_master().await?;
         self.set_signing_key().await?;
-        let (executor_future, account_futures) = self.send_initial_transfers().await?;
+        let scheduler = LoadProfile::from_config(&self.config).map(|profile| {
+            OperationScheduler::new(profile, Duration::from_secs(self.config.duration_secs))
+        });
+        let (executor_future, account_futures) =
+            self.send_initial_transfers(scheduler.as_ref()).await?;
+        if let Some(scheduler) = scheduler {
+            scheduler.run().await;
+        }
         self.wait_account_routines(account_futures).await;
 
         let final_resultion = executor_future.await.unwrap_or(LoadtestResult::TestFailed);
@@ -318,8 +331,11 @@ impl Executor {
     /// - Distributing ETH in L1 among test wallets in order to make them able to perform priority operations.
     /// - Spawning test account routine futures.
     /// - Collecting all the spawned tasks and returning them to the caller.
+    ///
+    /// If the scheduler is provided, the accounts don't start the operations until it issues the permits.
     async fn send_initial_transfers(
         &mut self,
+        scheduler: Option<&OperationScheduler>,
     ) -> anyhow::Result<(JoinHandle<LoadtestResult>, Vec<JoinHandle<()>>)> {
         vlog::info!("Master Account: Sending initial transfers");
         // How many times we will resend a batch.
@@ -328,12 +344,17 @@ impl Executor {
         // Prepare channels for the report collector.
         let (report_sender, report_receiver) = mpsc::channel(256);
 
-        let report_collector = ReportCollector::new(report_receiver, self.config.allowed_percent);
+        let report_collector = ReportCollector::new(
+            report_receiver,
+            &self.config,
+            scheduler.map(OperationScheduler::scheduled_operations),
+        );
         let report_collector_future = tokio::spawn(report_collector.run());
 
         let config = &self.config;
         let accounts_amount = config.accounts_amount;
         let addresses = self.pool.addresses.clone();
+        let operation_permits = scheduler.map(OperationScheduler::permits);
 
         let mut retry_counter = 0;
         let mut accounts_processed = 0;
@@ -398,6 +419,7 @@ impl Executor {
                             addresses.clone(),
                             wallet,
                             report_sender.clone(),
+                            operation_permits.clone(),
                         );
                         tokio::spawn(account.run())
                     });
@@ -432,7 +454,7 @@ impl Executor {
 
         // Amount of priority operations expected to be made by account.
         // We assume that 10% of operations made by account will be priority operations.
-        let priority_ops_per_account = self.config.operations_per_account / 10;
+        let priority_ops_per_account = self.config.expected_operations_per_account() / 10;
 
         Ok(average_gas_price * gas_per_priority_op * priority_ops_per_account)
     }
@@ -6,6 +6,7 @@ pub mod config;
 pub mod constants;
 pub mod corrupted_tx;
 pub mod executor;
+pub mod load_profile;
 pub mod report;
 pub mod report_collector;
 pub mod rng;
@@ -0,0 +1,304 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+use tokio::sync::Semaphore;
+
+use crate::config::LoadtestConfig;
+
+/// Interval between the permit issuing rounds of the scheduler.
+const SCHEDULER_TICK: Duration = Duration::from_millis(10);
+
+/// Kind of the load generated by the test.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadProfileKind {
+    /// Each account executes `operations_per_account` operations as fast as possible.
+    ClosedLoop,
+    /// Operations are started with the constant rate.
+    Constant,
+    /// Rate grows linearly from the initial to the target one.
+    Ramp,
+    /// Rate grows from the initial to the target one in several equal steps.
+    Step,
+    /// Rate is constant, except for a short period of the increased load.
+    Spike,
+}
+
+impl Default for LoadProfileKind {
+    fn default() -> Self {
+        Self::ClosedLoop
+    }
+}
+
+/// Open-loop load profile: defines how many operations per second should be started
+/// at each moment of the test, regardless of how fast the server processes them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadProfile {
+    Constant {
+        tps: f64,
+    },
+    Ramp {
+        from_tps: f64,
+        to_tps: f64,
+    },
+    Step {
+        from_tps: f64,
+        to_tps: f64,
+        steps: u32,
+    },
+    Spike {
+        base_tps: f64,
+        spike_tps: f64,
+        start: Duration,
+        duration: Duration,
+    },
+}
+
+impl LoadProfile {
+    /// Creates the profile from the config. Returns `None` for the closed-loop test.
+    pub fn from_config(config: &LoadtestConfig) -> Option<Self> {
+        let profile = match config.load_profile {
+            LoadProfileKind::ClosedLoop => return None,
+            LoadProfileKind::Constant => Self::Constant {
+                tps: config.target_tps,
+            },
+            LoadProfileKind::Ramp => Self::Ramp {
+                from_tps: config.initial_tps,
+                to_tps: config.target_tps,
+            },
+            LoadProfileKind::Step => Self::Step {
+                from_tps: config.initial_tps,
+                to_tps: config.target_tps,
+                steps: config.steps_amount,
+            },
+            LoadProfileKind::Spike => Self::Spike {
+                base_tps: config.target_tps,
+                spike_tps: config.spike_tps,
+                start: Duration::from_secs(config.spike_start_secs),
+                duration: Duration::from_secs(config.spike_duration_secs),
+            },
+        };
+
+        Some(profile)
+    }
+
+    /// Returns the rate of operations at the given moment of the test lasting `total` time.
+    pub fn tps_at(&self, elapsed: Duration, total: Duration) -> f64 {
+        let progress = if total.as_secs_f64() > 0.0 {
+            (elapsed.as_secs_f64() / total.as_secs_f64()).min(1.0)
+        } else {
+            1.0
+        };
+
+        match *self {
+            Self::Constant { tps } => tps,
+            Self::Ramp { from_tps, to_tps } => from_tps + (to_tps - from_tps) * progress,
+            Self::Step {
+                from_tps,
+                to_tps,
+                steps,
+            } => {
+                if steps <= 1 {
+                    return to_tps;
+                }
+                // The last step starts at the end of the test, so it's excluded from the range.
+                let step = ((progress * steps as f64) as u32).min(steps - 1);
+                from_tps + (to_tps - from_tps) * step as f64 / (steps - 1) as f64
+            }
+            Self::Spike {
+                base_tps,
+                spike_tps,
+                start,
+                duration,
+            } => {
+                if elapsed >= start && elapsed < start + duration {
+                    spike_tps
+                } else {
+                    base_tps
+                }
+            }
+        }
+    }
+
+    /// Returns the highest rate of operations during the test.
+    pub fn max_tps(&self) -> f64 {
+        match *self {
+            Self::Constant { tps } => tps,
+            Self::Ramp { from_tps, to_tps }
+            | Self::Step {
+                from_tps, to_tps, ..
+            } => from_tps.max(to_tps),
+            Self::Spike {
+                base_tps,
+                spike_tps,
+                ..
+            } => base_tps.max(spike_tps),
+        }
+    }
+}
+
+/// Scheduler for the open-loop test.
+///
+/// Accounts acquire a permit before each operation, and the scheduler issues permits according
+/// to the load profile. Thus the rate of operations doesn't depend on the server response time,
+/// as long as there are idle accounts. If all the accounts are busy, permits are accumulated,
+/// and the amount of operations that were scheduled but not started is reported at the end.
+#[derive(Debug)]
+pub struct OperationScheduler {
+    profile: LoadProfile,
+    duration: Duration,
+    permits: Arc<Semaphore>,
+    scheduled: Arc<AtomicU64>,
+}
+
+impl OperationScheduler {
+    pub fn new(profile: LoadProfile, duration: Duration) -> Self {
+        Self {
+            profile,
+            duration,
+            permits: Arc::new(Semaphore::new(0)),
+            scheduled: Arc::default(),
+        }
+    }
+
+    /// Returns the permits to be shared with the accounts.
+    pub fn permits(&self) -> Arc<Semaphore> {
+        self.permits.clone()
+    }
+
+    /// Returns the counter of the operations scheduled so far.
+    pub fn scheduled_operations(&self) -> Arc<AtomicU64> {
+        self.scheduled.clone()
+    }
+
+    /// Issues permits until the end of the test, and then closes the permits, so
+    /// accounts stop the execution.
+    /// Returns the amount of scheduled operations that were not started.
+    pub async fn run(self) -> usize {
+        vlog::info!(
+            "Starting the open-loop test with {:?} profile for {}s",
+            self.profile,
+            self.duration.as_secs()
+        );
+
+        let start = Instant::now();
+        let mut last_tick = start;
+        // Rates are not integer, so the fractional part is carried over to the next tick.
+        let mut credit = 0.0f64;
+        loop {
+            tokio::time::sleep(SCHEDULER_TICK).await;
+
+            let now = Instant::now();
+            let elapsed = now.duration_since(start).min(self.duration);
+            let tick = now.duration_since(last_tick);
+            last_tick = now;
+
+            credit += self.profile.tps_at(elapsed, self.duration) * tick.as_secs_f64();
+            let new_permits = credit.floor();
+            credit -= new_permits;
+
+            self.permits.add_permits(new_permits as usize);
+            self.scheduled
+                .fetch_add(new_permits as u64, Ordering::SeqCst);
+
+            if elapsed >= self.duration {
+                break;
+            }
+        }
+
+        let backlog = self.permits.available_permits();
+        self.permits.close();
+        if backlog > 0 {
+            vlog::warn!(
+                "{} scheduled operations were not started: all the accounts were busy",
+                backlog
+            );
+        }
+
+        backlog
+    }
+}
+
+/// Waits for the permit to execute the next operation.
+/// Returns `false` once the open-loop test is over.
+pub async fn acquire_permit(permits: &Semaphore) -> bool {
+    match permits.acquire().await {
+        Ok(permit) => {
+            permit.forget();
+            true
+        }
+        Err(_closed) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOTAL: Duration = Duration::from_secs(100);
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn ramp_profile() {
+        let profile = LoadProfile::Ramp {
+            from_tps: 10.0,
+            to_tps: 20.0,
+        };
+
+        assert_eq!(profile.tps_at(secs(0), TOTAL), 10.0);
+        assert_eq!(profile.tps_at(secs(50), TOTAL), 15.0);
+        assert_eq!(profile.tps_at(secs(100), TOTAL), 20.0);
+        // Rate doesn't grow after the end of the test.
+        assert_eq!(profile.tps_at(secs(200), TOTAL), 20.0);
+        assert_eq!(profile.max_tps(), 20.0);
+    }
+
+    #[test]
+    fn step_profile() {
+        let profile = LoadProfile::Step {
+            from_tps: 10.0,
+            to_tps: 40.0,
+            steps: 4,
+        };
+
+        // Test is split into 4 intervals of 25 seconds.
+        assert_eq!(profile.tps_at(secs(0), TOTAL), 10.0);
+        assert_eq!(profile.tps_at(secs(24), TOTAL), 10.0);
+        assert_eq!(profile.tps_at(secs(25), TOTAL), 20.0);
+        assert_eq!(profile.tps_at(secs(60), TOTAL), 30.0);
+        assert_eq!(profile.tps_at(secs(99), TOTAL), 40.0);
+        assert_eq!(profile.tps_at(secs(100), TOTAL), 40.0);
+
+        let single_step = LoadProfile::Step {
+            from_tps: 10.0,
+            to_tps: 40.0,
+            steps: 1,
+        };
+        assert_eq!(single_step.tps_at(secs(0), TOTAL), 40.0);
+    }
+
+    #[test]
+    fn spike_profile() {
+        let profile = LoadProfile::Spike {
+            base_tps: 5.0,
+            spike_tps: 50.0,
+            start: secs(30),
+            duration: secs(10),
+        };
+
+        assert_eq!(profile.tps_at(secs(29), TOTAL), 5.0);
+        assert_eq!(profile.tps_at(secs(30), TOTAL), 50.0);
+        assert_eq!(profile.tps_at(secs(39), TOTAL), 50.0);
+        assert_eq!(profile.tps_at(secs(40), TOTAL), 5.0);
+        assert_eq!(profile.max_tps(), 50.0);
+    }
+}
@@ -10,14 +10,19 @@ use loadnext::{config::LoadtestConfig, executor::Executor, report_collector::Loa
 async fn main() -> anyhow::Result<()> {
     vlog::init();
 
-    let config = LoadtestConfig::from_env().unwrap_or_else(|err| {
-        vlog::warn!(
-            "Loading the config from the environment variables failed: {:?}",
-            err
-        );
-        vlog::warn!("Using the hard-coded config, assuming it's the development run");
-        LoadtestConfig::default()
-    });
+    let config = match LoadtestConfig::from_env() {
+        Ok(config) => config,
+        Err(envy::Error::MissingValue(var)) => {
+            vlog::warn!(
+                "Loading the config from the environment variables failed: {} is missing",
+                var
+            );
+            vlog::warn!("Using the hard-coded config, assuming it's the development run");
+            LoadtestConfig::default()
+        }
+        // The config is provided, but it's invalid, so there's no point in running the test.
+        Err(err) => return Err(anyhow::anyhow!("Invalid loadtest config: {}", err)),
+    };
 
     let mut executor = Executor::new(config).await?;
     let final_resolution = executor.start().await;
@@ -1,10 +1,10 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use zksync_types::Address;
 
 use crate::{
     all::All,
-    command::{ApiRequestCommand, Command, TxType},
+    command::{ApiRequestCommand, ApiRequestType, Command, TxType},
     constants::MAX_BATCH_SIZE,
 };
 
@@ -25,8 +25,13 @@ pub struct Report {
     pub action: ActionType,
     /// Amount of retries that it took the wallet to finish the action.
     pub retries: usize,
-    /// Duration of the latest execution attempt.
+    /// Duration of the latest execution attempt, or the latency of the
+    /// transaction processing stage (see `ReportStage`).
     pub time: Duration,
+    /// Stage of the action processing the report is related to.
+    pub stage: ReportStage,
+    /// Moment of the report creation.
+    pub timestamp: Instant,
 }
 
 /// Builder structure for `Report`.
@@ -50,6 +55,8 @@ impl ReportBuilder {
                 action: ActionType::Tx(TxActionType::Transfer),
                 retries: 0,
                 time: Default::default(),
+                stage: ReportStage::Executed,
+                timestamp: Instant::now(),
             },
         }
     }
@@ -79,6 +86,11 @@ impl ReportBuilder {
         self
     }
 
+    pub fn stage(mut self, stage: ReportStage) -> Self {
+        self.report.stage = stage;
+        self
+    }
+
     pub fn finish(self) -> Report {
         self.report
     }
@@ -110,6 +122,21 @@ impl ReportLabel {
     }
 }
 
+/// Denotes the stage of the action processing.
+///
+/// Every action is reported once it's executed. Successful transactions are additionally
+/// reported once they are committed and (if configured) verified, so the latency of each
+/// processing stage can be analyzed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReportStage {
+    /// Action is completed, time is the duration of the latest execution attempt.
+    Executed,
+    /// Transaction is committed, time is measured from the transaction submission.
+    Committed,
+    /// Transaction is verified, time is measured from the transaction submission.
+    Verified,
+}
+
 /// Denotes the type of executed transaction.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TxActionType {
@@ -119,6 +146,10 @@ pub enum TxActionType {
     ChangePubKey,
     FullExit,
     Deposit,
+    Swap,
+    MintNFT,
+    TransferNFT,
+    WithdrawNFT,
 }
 
 impl All for TxActionType {
@@ -130,6 +161,10 @@ impl All for TxActionType {
             TxActionType::ChangePubKey,
             TxActionType::FullExit,
             TxActionType::Deposit,
+            TxActionType::Swap,
+            TxActionType::MintNFT,
+            TxActionType::TransferNFT,
+            TxActionType::WithdrawNFT,
         ];
 
         ALL
@@ -144,27 +179,79 @@ impl From<TxType> for TxActionType {
             TxType::WithdrawToSelf | TxType::WithdrawToOther => Self::Withdraw,
             TxType::FullExit => Self::FullExit,
             TxType::ChangePubKey => Self::ChangePubKey,
+            TxType::Swap => Self::Swap,
+            TxType::MintNFT => Self::MintNFT,
+            TxType::TransferNFT => Self::TransferNFT,
+            TxType::WithdrawNFT => Self::WithdrawNFT,
+            TxType::ForcedExit => Self::ForcedExit,
         }
     }
 }
 
-/// Denotes the type of the performed API action.
-/// Currently loadtest doesn't do any API actions (only as part of the transactions flow), thus enum exists
-/// for the future.
+/// Denotes the API endpoint that was requested.
+/// API actions are reported per endpoint, so the latency of each one can be analyzed separately.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum ApiActionType {}
+pub enum ApiActionType {
+    RestAccountInfo,
+    RestAccountTxs,
+    RestBlock,
+    RestToken,
+    RestTxFee,
+    RestNetworkStatus,
+    RpcAccountInfo,
+    RpcTokens,
+    RpcTxFee,
+    RpcContractAddress,
+    Web3BlockNumber,
+    Web3Balance,
+    Web3Block,
+}
 
 impl All for ApiActionType {
     fn all() -> &'static [Self] {
-        const ALL: &[ApiActionType] = &[];
+        const ALL: &[ApiActionType] = &[
+            ApiActionType::RestAccountInfo,
+            ApiActionType::RestAccountTxs,
+            ApiActionType::RestBlock,
+            ApiActionType::RestToken,
+            ApiActionType::RestTxFee,
+            ApiActionType::RestNetworkStatus,
+            ApiActionType::RpcAccountInfo,
+            ApiActionType::RpcTokens,
+            ApiActionType::RpcTxFee,
+            ApiActionType::RpcContractAddress,
+            ApiActionType::Web3BlockNumber,
+            ApiActionType::Web3Balance,
+            ApiActionType::Web3Block,
+        ];
 
         ALL
     }
 }
 
+impl From<ApiRequestType> for ApiActionType {
+    fn from(request_type: ApiRequestType) -> Self {
+        match request_type {
+            ApiRequestType::RestAccountInfo => Self::RestAccountInfo,
+            ApiRequestType::RestAccountTxs => Self::RestAccountTxs,
+            ApiRequestType::RestBlock => Self::RestBlock,
+            ApiRequestType::RestToken => Self::RestToken,
+            ApiRequestType::RestTxFee => Self::RestTxFee,
+            ApiRequestType::RestNetworkStatus => Self::RestNetworkStatus,
+            ApiRequestType::RpcAccountInfo => Self::RpcAccountInfo,
+            ApiRequestType::RpcTokens => Self::RpcTokens,
+            ApiRequestType::RpcTxFee => Self::RpcTxFee,
+            ApiRequestType::RpcContractAddress => Self::RpcContractAddress,
+            ApiRequestType::Web3BlockNumber => Self::Web3BlockNumber,
+            ApiRequestType::Web3Balance => Self::Web3Balance,
+            ApiRequestType::Web3Block => Self::Web3Block,
+        }
+    }
+}
+
 impl From<ApiRequestCommand> for ApiActionType {
-    fn from(_: ApiRequestCommand) -> Self {
-        todo!()
+    fn from(command: ApiRequestCommand) -> Self {
+        command.request_type.into()
     }
 }
 
@@ -182,6 +269,12 @@ impl From<TxActionType> for ActionType {
     }
 }
 
+impl From<TxType> for ActionType {
+    fn from(command: TxType) -> Self {
+        Self::Tx(command.into())
+    }
+}
+
 impl From<ApiActionType> for ActionType {
     fn from(action: ApiActionType) -> Self {
         Self::Api(action)
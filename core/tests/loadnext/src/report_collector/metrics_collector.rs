@@ -3,7 +3,7 @@ use std::{
     time::Duration,
 };
 
-use crate::report::ActionType;
+use crate::{report::ActionType, report_collector::summary::LatencySummary};
 
 #[derive(Debug, Clone)]
 pub struct TimeHistogram {
@@ -23,14 +23,21 @@ impl Default for TimeHistogram {
 
 impl TimeHistogram {
     pub fn new() -> Self {
-        // Ranges from the 0 to 1000 ms with windows of 100 ms.
-        let sub_sec_ranges = (0..10).map(|window_idx| Self::window(window_idx, 100));
+        // Ranges from the 0 to 100 ms with windows of 10 ms.
+        // API requests are expected to be fast, so they need a more precise distribution.
+        let api_ranges = (0..10).map(|window_idx| Self::window(window_idx, 10));
+        // Ranges from the 100 to 1000 ms with windows of 100 ms.
+        let sub_sec_ranges = (1..10).map(|window_idx| Self::window(window_idx, 100));
         // Ranges from 1 second to 20 seconds with windows of 1 second.
         let sec_ranges = (1..20).map(|window_idx| Self::window(window_idx, 1000));
         // Range for (20 sec; MAX).
         let rest_range = std::iter::once((20_000u64, u64::max_value()));
 
-        let ranges: Vec<_> = sub_sec_ranges.chain(sec_ranges).chain(rest_range).collect();
+        let ranges: Vec<_> = api_ranges
+            .chain(sub_sec_ranges)
+            .chain(sec_ranges)
+            .chain(rest_range)
+            .collect();
         let mut histogram = BTreeMap::new();
 
         for &(start, _) in ranges.iter() {
@@ -55,6 +62,10 @@ impl TimeHistogram {
         self.total == 0
     }
 
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
     /// Returns the time range for the requested distribution percentile.
     pub fn percentile(&self, percentile: u64) -> (Duration, Duration) {
         let lower_gap_float = self.total as f64 * percentile as f64 / 100.0;
@@ -137,17 +148,34 @@ impl MetricsCollector {
             .and_modify(|hist| hist.add_metric(time));
     }
 
+    /// Returns the latency distribution for each action that was actually performed.
+    pub fn summary(&self) -> Vec<LatencySummary> {
+        let mut summary: Vec<_> = self
+            .action_stats
+            .iter()
+            .filter(|(_, histogram)| !histogram.is_empty())
+            .map(|(action, histogram)| LatencySummary::new(format!("{:?}", action), histogram))
+            .collect();
+        summary.sort_by(|lhs, rhs| lhs.action.cmp(&rhs.action));
+
+        summary
+    }
+
     pub fn report(&self) {
-        vlog::info!("Action: [10 percentile, 50 percentile, 90 percentile]");
+        vlog::info!(
+            "Action: [10 percentile, 50 percentile, 90 percentile, 95 percentile, 99 percentile]"
+        );
         for (action, histogram) in self.action_stats.iter() {
             // Only report data that was actually gathered.
             if !histogram.is_empty() {
                 vlog::info!(
-                    "{:?}: [>{}ms >{}ms >{}ms]",
+                    "{:?}: [>{}ms >{}ms >{}ms >{}ms >{}ms]",
                     action,
                     histogram.percentile(10).0.as_millis(),
                     histogram.percentile(50).0.as_millis(),
                     histogram.percentile(90).0.as_millis(),
+                    histogram.percentile(95).0.as_millis(),
+                    histogram.percentile(99).0.as_millis(),
                 );
             }
         }
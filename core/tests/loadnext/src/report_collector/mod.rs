@@ -1,13 +1,29 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
 use futures::{channel::mpsc::Receiver, StreamExt};
 use operation_results_collector::OperationResultsCollector;
 
 use crate::{
-    report::{Report, ReportLabel},
-    report_collector::metrics_collector::MetricsCollector,
+    config::LoadtestConfig,
+    report::{Report, ReportLabel, ReportStage},
+    report_collector::{
+        metrics_collector::MetricsCollector,
+        summary::{write_reports, LatencySummary, LoadtestSummary, SloCheck},
+        time_series_collector::TimeSeriesCollector,
+    },
 };
 
 mod metrics_collector;
 mod operation_results_collector;
+mod summary;
+mod time_series_collector;
 
 /// Decision on whether loadtest considered passed or failed.
 #[derive(Debug, Clone, Copy)]
@@ -25,8 +41,14 @@ pub enum LoadtestResult {
 /// Currently, only the following collectors are used:
 ///
 /// - MetricsCollector, which builds time distribution histograms for each kind of performed action.
-/// - OperationResultsCollector, a primitive collector that counts the amount of failures and decides whether
-///   test is passed.
+///   Separate instances are used for the execution time and for the commit and verification latencies
+///   of the transactions.
+/// - OperationResultsCollector, a primitive collector that counts the amount of failures.
+/// - TimeSeriesCollector, which gathers the statistics per time window, so it's possible to see how
+///   the server behaves under the changing load.
+///
+/// Once the test is finished, the results are checked against the configured SLOs, and (if the report
+/// directory is set) stored as a JSON summary and a CSV time series.
 ///
 /// Other possible collectors that can be implemented:
 ///
@@ -44,19 +66,45 @@ pub enum LoadtestResult {
 #[derive(Debug)]
 pub struct ReportCollector {
     allowed_percent: u8,
+    slo_p95_latency_ms: Option<u64>,
+    slo_max_error_percent: Option<f64>,
+    report_dir: Option<PathBuf>,
     reports_stream: Receiver<Report>,
+    scheduled_operations: Option<Arc<AtomicU64>>,
     metrics_collector: MetricsCollector,
+    commit_metrics_collector: MetricsCollector,
+    verify_metrics_collector: MetricsCollector,
     operations_results_collector: OperationResultsCollector,
+    time_series_collector: TimeSeriesCollector,
+    /// Moments of the first and the last executed actions.
+    execution_period: Option<(Instant, Instant)>,
 }
 
 impl ReportCollector {
-    pub fn new(reports_stream: Receiver<Report>, allowed_percent: u8) -> Self {
-        assert!(allowed_percent < 100, "Allowed percent more than 100");
+    pub fn new(
+        reports_stream: Receiver<Report>,
+        config: &LoadtestConfig,
+        scheduled_operations: Option<Arc<AtomicU64>>,
+    ) -> Self {
+        assert!(
+            config.allowed_percent < 100,
+            "Allowed percent more than 100"
+        );
         Self {
-            allowed_percent,
+            allowed_percent: config.allowed_percent,
+            slo_p95_latency_ms: config.slo_p95_latency_ms,
+            slo_max_error_percent: config.slo_max_error_percent,
+            report_dir: config.report_dir.as_ref().map(PathBuf::from),
             reports_stream,
+            scheduled_operations,
             metrics_collector: MetricsCollector::new(),
+            commit_metrics_collector: MetricsCollector::new(),
+            verify_metrics_collector: MetricsCollector::new(),
             operations_results_collector: OperationResultsCollector::new(),
+            time_series_collector: TimeSeriesCollector::new(Duration::from_secs(
+                config.report_window_secs,
+            )),
+            execution_period: None,
         }
     }
 
@@ -64,36 +112,147 @@ impl ReportCollector {
         while let Some(report) = self.reports_stream.next().await {
             vlog::trace!("Report: {:?}", &report);
 
-            if matches!(&report.label, ReportLabel::ActionDone) {
-                // We only count successfully created statistics.
-                self.metrics_collector
-                    .add_metric(report.action, report.time);
-            }
-
-            self.operations_results_collector.add_status(&report.label);
-
-            // Report failure, if it exists.
-            if let ReportLabel::ActionFailed { error } = &report.label {
-                vlog::warn!("Operation failed: {}", error);
+            self.time_series_collector.add_report(&report);
+            match report.stage {
+                ReportStage::Executed => self.add_execution_report(&report),
+                // Reports about the later stages are only sent for successful transactions.
+                ReportStage::Committed => self
+                    .commit_metrics_collector
+                    .add_metric(report.action, report.time),
+                ReportStage::Verified => self
+                    .verify_metrics_collector
+                    .add_metric(report.action, report.time),
             }
         }
 
         // All the receivers are gone, it's likely the end of the test.
         // Now we can output the statistics.
         self.metrics_collector.report();
+        vlog::info!("Commit latency:");
+        self.commit_metrics_collector.report();
+        vlog::info!("Verify latency:");
+        self.verify_metrics_collector.report();
         self.operations_results_collector.report();
 
-        self.final_resolution()
-    }
+        let summary = self.summary();
+        for check in summary.checks.iter().filter(|check| !check.passed) {
+            vlog::error!(
+                "Check {} failed: {} (threshold {})",
+                check.name,
+                check.actual,
+                check.threshold
+            );
+        }
+        if let Some(report_dir) = &self.report_dir {
+            let time_series = self.time_series_collector.rows();
+            if let Err(err) = write_reports(report_dir, &summary, &time_series) {
+                vlog::error!("Unable to save the loadtest reports: {}", err);
+            }
+        }
 
-    fn final_resolution(&self) -> LoadtestResult {
-        let failure_percent = (self.operations_results_collector.failures() as f64
-            / self.operations_results_collector.total() as f64)
-            * 100.0;
-        if failure_percent > self.allowed_percent as f64 {
+        if summary.passed {
+            LoadtestResult::TestPassed
+        } else {
             LoadtestResult::TestFailed
+        }
+    }
+
+    fn add_execution_report(&mut self, report: &Report) {
+        if matches!(&report.label, ReportLabel::ActionDone) {
+            // We only count successfully created statistics.
+            self.metrics_collector
+                .add_metric(report.action, report.time);
+        }
+
+        self.operations_results_collector.add_status(&report.label);
+
+        // Report failure, if it exists.
+        if let ReportLabel::ActionFailed { error } = &report.label {
+            vlog::warn!("Operation failed: {}", error);
+        }
+
+        let first = self
+            .execution_period
+            .map_or(report.timestamp, |(first, _)| first);
+        self.execution_period = Some((first, report.timestamp));
+    }
+
+    fn summary(&self) -> LoadtestSummary {
+        let results = &self.operations_results_collector;
+        let error_percent = if results.total() > 0 {
+            results.failures() as f64 / results.total() as f64 * 100.0
         } else {
-            LoadtestResult::TestPassed
+            0.0
+        };
+        let duration_secs = self
+            .execution_period
+            .map(|(first, last)| last.duration_since(first).as_secs_f64())
+            .unwrap_or_default();
+        let throughput = if duration_secs > 0.0 {
+            results.successes() as f64 / duration_secs
+        } else {
+            0.0
+        };
+
+        let execution_latency = self.metrics_collector.summary();
+        let commit_latency = self.commit_metrics_collector.summary();
+        let verify_latency = self.verify_metrics_collector.summary();
+
+        let mut checks = vec![SloCheck::at_most(
+            "allowed_percent",
+            self.allowed_percent as f64,
+            error_percent,
+        )];
+        if let Some(max_error_percent) = self.slo_max_error_percent {
+            checks.push(SloCheck::at_most(
+                "max_error_percent",
+                max_error_percent,
+                error_percent,
+            ));
+        }
+        if let Some(p95_latency_ms) = self.slo_p95_latency_ms {
+            checks.extend(
+                Self::latency_slo_subjects(&execution_latency, &commit_latency).map(|latency| {
+                    SloCheck::at_most(
+                        format!("p95_latency_ms({})", latency.action),
+                        p95_latency_ms as f64,
+                        latency.p95_ms as f64,
+                    )
+                }),
+            );
+        }
+
+        LoadtestSummary {
+            passed: checks.iter().all(|check| check.passed),
+            duration_secs,
+            scheduled_operations: self
+                .scheduled_operations
+                .as_ref()
+                .map(|scheduled| scheduled.load(Ordering::SeqCst)),
+            successes: results.successes(),
+            skipped: results.skipped(),
+            failures: results.failures(),
+            error_percent,
+            throughput,
+            execution_latency,
+            commit_latency,
+            verify_latency,
+            checks,
         }
     }
+
+    /// Chooses the latency to be checked against the SLO for each action: for transactions it's the time until
+    /// the commitment, and for the actions without it (e.g. API requests) it's the execution time.
+    fn latency_slo_subjects<'a>(
+        execution_latency: &'a [LatencySummary],
+        commit_latency: &'a [LatencySummary],
+    ) -> impl Iterator<Item = &'a LatencySummary> {
+        let executed_only = execution_latency.iter().filter(move |executed| {
+            !commit_latency
+                .iter()
+                .any(|committed| committed.action == executed.action)
+        });
+
+        commit_latency.iter().chain(executed_only)
+    }
 }
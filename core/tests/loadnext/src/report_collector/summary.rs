@@ -0,0 +1,103 @@
+use std::{fs, io, path::Path};
+
+use serde::Serialize;
+
+use crate::report_collector::{
+    metrics_collector::TimeHistogram, time_series_collector::TimeSeriesRow,
+};
+
+const SUMMARY_FILE: &str = "loadtest_summary.json";
+const TIME_SERIES_FILE: &str = "loadtest_time_series.csv";
+
+/// Latency distribution of a single kind of action.
+///
+/// Percentiles are represented by the lower bound of the corresponding histogram window.
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencySummary {
+    pub action: String,
+    pub count: usize,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub p99_ms: u64,
+}
+
+impl LatencySummary {
+    pub fn new(action: String, histogram: &TimeHistogram) -> Self {
+        let percentile_ms = |percentile| histogram.percentile(percentile).0.as_millis() as u64;
+
+        Self {
+            action,
+            count: histogram.total(),
+            p50_ms: percentile_ms(50),
+            p95_ms: percentile_ms(95),
+            p99_ms: percentile_ms(99),
+        }
+    }
+}
+
+/// Outcome of a single pass criterion of the test.
+#[derive(Debug, Clone, Serialize)]
+pub struct SloCheck {
+    pub name: String,
+    pub threshold: f64,
+    pub actual: f64,
+    pub passed: bool,
+}
+
+impl SloCheck {
+    /// Creates a check that passes if the actual value doesn't exceed the threshold.
+    pub fn at_most(name: impl Into<String>, threshold: f64, actual: f64) -> Self {
+        Self {
+            name: name.into(),
+            threshold,
+            actual,
+            passed: actual <= threshold,
+        }
+    }
+}
+
+/// Machine-readable results of the loadtest.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadtestSummary {
+    pub passed: bool,
+    /// Time between the first and the last executed actions.
+    pub duration_secs: f64,
+    /// Amount of operations scheduled by the open-loop profile.
+    pub scheduled_operations: Option<u64>,
+    pub successes: u64,
+    pub skipped: u64,
+    pub failures: u64,
+    pub error_percent: f64,
+    /// Successfully executed actions per second.
+    pub throughput: f64,
+    /// Time of the action execution.
+    pub execution_latency: Vec<LatencySummary>,
+    /// Time from the transaction submission until its commitment.
+    pub commit_latency: Vec<LatencySummary>,
+    /// Time from the transaction submission until its verification.
+    pub verify_latency: Vec<LatencySummary>,
+    pub checks: Vec<SloCheck>,
+}
+
+/// Stores the summary as JSON and the time series as CSV into the directory.
+pub fn write_reports(
+    dir: &Path,
+    summary: &LoadtestSummary,
+    time_series: &[TimeSeriesRow],
+) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    let summary_json = serde_json::to_string_pretty(summary)?;
+    fs::write(dir.join(SUMMARY_FILE), summary_json)?;
+
+    let mut csv = String::from(TimeSeriesRow::CSV_HEADER);
+    csv.push('\n');
+    for row in time_series {
+        csv.push_str(&row.to_csv());
+        csv.push('\n');
+    }
+    fs::write(dir.join(TIME_SERIES_FILE), csv)?;
+
+    vlog::info!("Loadtest reports are saved to {}", dir.display());
+    Ok(())
+}
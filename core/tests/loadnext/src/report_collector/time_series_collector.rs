@@ -0,0 +1,184 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use crate::{
+    report::{ActionType, Report, ReportLabel, ReportStage},
+    report_collector::metrics_collector::TimeHistogram,
+};
+
+/// Statistics of a single action type and stage within the time window.
+#[derive(Debug, Clone, Default)]
+struct WindowStats {
+    failures: usize,
+    /// Latency of the successful actions.
+    histogram: TimeHistogram,
+}
+
+/// Single entry of the time series.
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeSeriesRow {
+    /// Start of the window relative to the beginning of the test.
+    pub window_start_secs: u64,
+    pub action: String,
+    pub stage: String,
+    pub successes: usize,
+    pub failures: usize,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub p99_ms: u64,
+}
+
+impl TimeSeriesRow {
+    pub const CSV_HEADER: &'static str =
+        "window_start_secs,action,stage,successes,failures,p50_ms,p95_ms,p99_ms";
+
+    pub fn to_csv(&self) -> String {
+        format!(
+            "{},\"{}\",{},{},{},{},{},{}",
+            self.window_start_secs,
+            self.action,
+            self.stage,
+            self.successes,
+            self.failures,
+            self.p50_ms,
+            self.p95_ms,
+            self.p99_ms
+        )
+    }
+}
+
+/// Collector that splits the test into windows of equal size and gathers the amount of
+/// actions and their latency distribution for each window, action type and processing stage.
+/// It allows to see how the server behaves under the changing load.
+#[derive(Debug, Clone)]
+pub struct TimeSeriesCollector {
+    start: Instant,
+    window: Duration,
+    windows: HashMap<(u64, ActionType, ReportStage), WindowStats>,
+}
+
+impl TimeSeriesCollector {
+    pub fn new(window: Duration) -> Self {
+        assert!(window.as_secs() > 0, "Time series window must be positive");
+
+        Self {
+            start: Instant::now(),
+            window,
+            windows: HashMap::new(),
+        }
+    }
+
+    pub fn add_report(&mut self, report: &Report) {
+        let window_idx = report
+            .timestamp
+            .saturating_duration_since(self.start)
+            .as_secs()
+            / self.window.as_secs();
+        let stats = self
+            .windows
+            .entry((window_idx, report.action, report.stage))
+            .or_default();
+
+        match &report.label {
+            ReportLabel::ActionDone => stats.histogram.add_metric(report.time),
+            ReportLabel::ActionFailed { .. } => stats.failures += 1,
+            ReportLabel::ActionSkipped { .. } => {}
+        }
+    }
+
+    /// Returns the collected time series ordered by the window start.
+    pub fn rows(&self) -> Vec<TimeSeriesRow> {
+        let mut rows: Vec<_> = self
+            .windows
+            .iter()
+            .map(|(&(window_idx, action, stage), stats)| {
+                let percentile_ms = |percentile| {
+                    if stats.histogram.is_empty() {
+                        0
+                    } else {
+                        stats.histogram.percentile(percentile).0.as_millis() as u64
+                    }
+                };
+
+                TimeSeriesRow {
+                    window_start_secs: window_idx * self.window.as_secs(),
+                    action: format!("{:?}", action),
+                    stage: format!("{:?}", stage),
+                    successes: stats.histogram.total(),
+                    failures: stats.failures,
+                    p50_ms: percentile_ms(50),
+                    p95_ms: percentile_ms(95),
+                    p99_ms: percentile_ms(99),
+                }
+            })
+            .collect();
+
+        rows.sort_by(|lhs, rhs| {
+            (lhs.window_start_secs, &lhs.action, &lhs.stage).cmp(&(
+                rhs.window_start_secs,
+                &rhs.action,
+                &rhs.stage,
+            ))
+        });
+
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{ReportBuilder, TxActionType};
+
+    #[test]
+    fn reports_are_split_into_windows() {
+        let mut collector = TimeSeriesCollector::new(Duration::from_secs(10));
+        let start = collector.start;
+
+        let report = |offset_secs, label, stage| {
+            let mut report = ReportBuilder::new()
+                .label(label)
+                .action(TxActionType::Transfer)
+                .stage(stage)
+                .time(Duration::from_millis(150))
+                .finish();
+            report.timestamp = start + Duration::from_secs(offset_secs);
+            report
+        };
+
+        collector.add_report(&report(1, ReportLabel::done(), ReportStage::Executed));
+        collector.add_report(&report(
+            9,
+            ReportLabel::failed("err"),
+            ReportStage::Executed,
+        ));
+        collector.add_report(&report(9, ReportLabel::done(), ReportStage::Committed));
+        collector.add_report(&report(25, ReportLabel::done(), ReportStage::Executed));
+
+        let rows = collector.rows();
+        let summary: Vec<_> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.window_start_secs,
+                    row.stage.as_str(),
+                    row.successes,
+                    row.failures,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, "Committed", 1, 0),
+                (0, "Executed", 1, 1),
+                (20, "Executed", 1, 0),
+            ]
+        );
+        assert_eq!(rows[0].p50_ms, 100);
+    }
+}
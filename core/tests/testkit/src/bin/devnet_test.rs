@@ -0,0 +1,129 @@
+//! Checks the devnet started by `zk run devnet`: a deposit, a full exit and a token listing
+//! are added through the control API and are expected to be executed and verified by the devnet.
+
+use std::time::Duration;
+
+use anyhow::ensure;
+use num::{BigUint, Zero};
+use structopt::StructOpt;
+
+use zksync_config::ZkSyncConfig;
+use zksync_testkit::{
+    devnet::{wait_until, AccountState, DevnetClient},
+    eth_account::parse_ether,
+    zksync_account::ZkSyncAccount,
+};
+use zksync_types::{Address, Deposit, FullExit, TokenId};
+
+/// Time given to the devnet to start, it includes the genesis block creation.
+const START_TIMEOUT: Duration = Duration::from_secs(300);
+/// Time given to the devnet to execute and verify an operation.
+const OPERATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "ZkSync devnet test", author = "Matter Labs")]
+struct Opt {
+    /// URL of the devnet control API
+    #[structopt(long, default_value = "http://127.0.0.1:3040")]
+    control_url: String,
+    /// URL of the devnet JSON RPC API. The configured JSON RPC URL is used by default
+    #[structopt(long)]
+    rpc_url: Option<String>,
+}
+
+fn eth_balance(state: &AccountState) -> BigUint {
+    state
+        .balances
+        .get("ETH")
+        .map(|balance| balance.0.clone())
+        .unwrap_or_default()
+}
+
+async fn wait_for_eth_balance(
+    client: &DevnetClient,
+    address: Address,
+    expected: &BigUint,
+) -> anyhow::Result<()> {
+    wait_until(
+        &format!(
+            "the verified balance of {:?} to become {}",
+            address, expected
+        ),
+        OPERATION_TIMEOUT,
+        || async move {
+            let info = client.account_info(address).await?;
+            Ok(eth_balance(&info.committed) == *expected
+                && eth_balance(&info.verified) == *expected)
+        },
+    )
+    .await
+}
+
+async fn devnet_test(client: &DevnetClient) -> anyhow::Result<()> {
+    client.wait_for_start(START_TIMEOUT).await?;
+
+    let account = ZkSyncAccount::rand();
+    let amount = parse_ether("1.0")?;
+
+    println!("Depositing {} wei to {:?}", amount, account.address);
+    let receipt = client
+        .deposit(&Deposit {
+            from: account.address,
+            token: TokenId(0),
+            amount: amount.clone(),
+            to: account.address,
+        })
+        .await?;
+    println!("Deposit is added to the mock L1: {:?}", receipt);
+    wait_for_eth_balance(client, account.address, &amount).await?;
+
+    let account_id = client
+        .account_info(account.address)
+        .await?
+        .id
+        .ok_or_else(|| anyhow::format_err!("Account is not created by the deposit"))?;
+    println!("Full exit of account {}", account_id);
+    let receipt = client
+        .full_exit(&FullExit {
+            account_id,
+            eth_address: account.address,
+            token: TokenId(0),
+            is_legacy: false,
+        })
+        .await?;
+    println!("Full exit is added to the mock L1: {:?}", receipt);
+    wait_for_eth_balance(client, account.address, &BigUint::zero()).await?;
+
+    let token_address = Address::random();
+    let receipt = client.add_token(token_address).await?;
+    println!("Token is listed on the mock L1: {:?}", receipt);
+    wait_until("the token to be added", OPERATION_TIMEOUT, || async move {
+        let tokens = client.tokens().await?;
+        Ok(tokens.iter().any(|token| token.address == token_address))
+    })
+    .await?;
+    let tokens = client.tokens().await?;
+    ensure!(
+        tokens
+            .iter()
+            .any(|token| token.address == token_address && token.id == receipt.id),
+        "Token {:?} is added with the wrong id, expected {}",
+        token_address,
+        receipt.id
+    );
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+    let rpc_url = opt
+        .rpc_url
+        .unwrap_or_else(|| ZkSyncConfig::from_env().api.json_rpc.http_url);
+    let client = DevnetClient::new(opt.control_url, rpc_url);
+
+    devnet_test(&client).await?;
+    println!("Devnet test is successful");
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use std::path::{Path, PathBuf};
+
+use structopt::StructOpt;
+
+use zksync_testkit::scenario_dsl::{replay_trace, run_scenario, Scenario, Trace};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "ZkSync testkit scenario runner", author = "Matter Labs")]
+enum Opt {
+    /// Executes the scenario files. If a directory is provided, all the `.json` files in it are executed.
+    Run {
+        #[structopt(required = true)]
+        paths: Vec<PathBuf>,
+        /// Directory to store the execution traces into.
+        #[structopt(long)]
+        trace_dir: Option<PathBuf>,
+    },
+    /// Replays the recorded trace and checks that the outcomes of all the steps are the same.
+    Replay {
+        trace: PathBuf,
+        /// File to store the trace of the replay into.
+        #[structopt(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+fn scenario_files(paths: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut dir_files = Vec::new();
+            for entry in std::fs::read_dir(path)? {
+                let file = entry?.path();
+                if file.extension().map_or(false, |ext| ext == "json") {
+                    dir_files.push(file);
+                }
+            }
+            dir_files.sort();
+            files.extend(dir_files);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    Ok(files)
+}
+
+fn save_trace(trace: &Trace, path: &Path) {
+    match trace.save(path) {
+        Ok(()) => println!("Trace is saved to {}", path.display()),
+        Err(err) => println!("{:?}", err),
+    }
+}
+
+async fn run(paths: Vec<PathBuf>, trace_dir: Option<PathBuf>) -> anyhow::Result<bool> {
+    let mut all_passed = true;
+    for file in scenario_files(&paths)? {
+        let scenario = Scenario::load(&file)?;
+        println!(
+            "Running scenario '{}' from {}",
+            scenario.name,
+            file.display()
+        );
+
+        let name = scenario.name.clone();
+        let (trace, result) = run_scenario(scenario).await;
+        if let Some(trace_dir) = &trace_dir {
+            std::fs::create_dir_all(trace_dir)?;
+            save_trace(&trace, &trace_dir.join(format!("{}.trace.json", name)));
+        }
+
+        match result {
+            Ok(()) => println!("Scenario '{}' passed", name),
+            Err(err) => {
+                println!("Scenario '{}' failed: {:?}", name, err);
+                all_passed = false;
+            }
+        }
+    }
+
+    Ok(all_passed)
+}
+
+async fn replay(trace_path: PathBuf, output: Option<PathBuf>) -> anyhow::Result<bool> {
+    let trace = Trace::load(&trace_path)?;
+    println!(
+        "Replaying scenario '{}' from {}",
+        trace.scenario.name,
+        trace_path.display()
+    );
+
+    let (replayed, result) = replay_trace(&trace).await;
+    if let Some(output) = &output {
+        save_trace(&replayed, output);
+    }
+
+    match result {
+        Ok(()) => {
+            println!("Replay of '{}' matches the trace", trace.scenario.name);
+            Ok(true)
+        }
+        Err(err) => {
+            println!("Replay of '{}' failed: {:?}", trace.scenario.name, err);
+            Ok(false)
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let _sentry_guard = vlog::init();
+
+    let passed = match Opt::from_args() {
+        Opt::Run { paths, trace_dir } => run(paths, trace_dir).await?,
+        Opt::Replay { trace, output } => replay(trace, output).await?,
+    };
+
+    if !passed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
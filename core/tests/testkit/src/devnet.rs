@@ -0,0 +1,167 @@
+//! Client of the devnet started by `zk run devnet`.
+//!
+//! The L1 operations are added through the control API of the devnet, and their results are
+//! observed through the JSON RPC API, the same way the application would observe them.
+
+use std::{collections::HashMap, future::Future, time::Duration};
+
+use anyhow::{bail, format_err};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use zksync_types::{AccountId, Address, Deposit, FullExit, SerialId, Token, TokenId, H256};
+use zksync_utils::BigUintSerdeWrapper;
+
+/// Interval between the checks of the devnet state.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Priority operation added to the mock L1, as returned by the control API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PriorityOpReceipt {
+    pub serial_id: SerialId,
+    pub eth_hash: H256,
+    pub eth_block: u64,
+}
+
+/// Token listed on the mock L1, as returned by the control API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenReceipt {
+    pub id: TokenId,
+    pub address: Address,
+    pub eth_block: u64,
+}
+
+/// Balances of the account, keyed by the token symbol.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountState {
+    pub balances: HashMap<String, BigUintSerdeWrapper>,
+}
+
+/// Part of the `account_info` JSON RPC response used by the devnet tests.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountInfo {
+    pub id: Option<AccountId>,
+    pub committed: AccountState,
+    pub verified: AccountState,
+}
+
+#[derive(Debug, Serialize)]
+struct AddTokenRequest {
+    address: Address,
+}
+
+#[derive(Debug, Clone)]
+pub struct DevnetClient {
+    http: reqwest::Client,
+    control_url: String,
+    rpc_url: String,
+}
+
+impl DevnetClient {
+    pub fn new(control_url: impl Into<String>, rpc_url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            control_url: control_url.into(),
+            rpc_url: rpc_url.into(),
+        }
+    }
+
+    /// Waits until the devnet API is available.
+    pub async fn wait_for_start(&self, timeout: Duration) -> anyhow::Result<()> {
+        wait_until("the devnet to start", timeout, || async move {
+            Ok(self.tokens().await.is_ok())
+        })
+        .await
+    }
+
+    pub async fn deposit(&self, deposit: &Deposit) -> anyhow::Result<PriorityOpReceipt> {
+        self.post("deposit", deposit).await
+    }
+
+    pub async fn full_exit(&self, full_exit: &FullExit) -> anyhow::Result<PriorityOpReceipt> {
+        self.post("full_exit", full_exit).await
+    }
+
+    pub async fn add_token(&self, address: Address) -> anyhow::Result<TokenReceipt> {
+        self.post("token", &AddTokenRequest { address }).await
+    }
+
+    pub async fn account_info(&self, address: Address) -> anyhow::Result<AccountInfo> {
+        self.rpc_call("account_info", json!([address])).await
+    }
+
+    pub async fn tokens(&self) -> anyhow::Result<Vec<Token>> {
+        let tokens: HashMap<String, Token> = self.rpc_call("tokens", json!([])).await?;
+        Ok(tokens.into_values().collect())
+    }
+
+    async fn post<T: Serialize, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        request: &T,
+    ) -> anyhow::Result<R> {
+        let response = self
+            .http
+            .post(&format!("{}/{}", self.control_url, method))
+            .json(request)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            bail!(
+                "Control API request `{}` failed: {}",
+                method,
+                response.text().await?
+            );
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn rpc_call<R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> anyhow::Result<R> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let mut response: Value = self
+            .http
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await?
+            .json()
+            .await?;
+        if let Some(error) = response.get("error") {
+            bail!("JSON RPC call `{}` failed: {}", method, error);
+        }
+        let result = response
+            .get_mut("result")
+            .ok_or_else(|| format_err!("JSON RPC call `{}` returned no result", method))?
+            .take();
+        Ok(serde_json::from_value(result)?)
+    }
+}
+
+/// Polls the condition until it's met or the timeout is reached.
+pub async fn wait_until<F, Fut>(
+    what: &str,
+    timeout: Duration,
+    mut condition: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<bool>>,
+{
+    let started_at = tokio::time::Instant::now();
+    while !condition().await? {
+        if started_at.elapsed() > timeout {
+            bail!("Timeout of {:?} is reached waiting for {}", timeout, what);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    Ok(())
+}
@@ -14,8 +14,10 @@ pub use zksync_test_account as zksync_account;
 
 pub mod account_set;
 pub mod data_restore;
+pub mod devnet;
 pub mod eth_account;
 pub mod external_commands;
+pub mod scenario_dsl;
 pub mod scenarios;
 pub mod state_keeper_utils;
 pub mod test_setup;
@@ -0,0 +1,41 @@
+//! Declarative testkit scenarios.
+//!
+//! Scenario is a JSON file describing the accounts, the operations to be executed (deposits,
+//! transactions, batches, full exits), block seals and reverts, and the expectations about the
+//! transaction results, balances and root hashes. Scenarios are executed against the state keeper
+//! in the same way as the imperative testkit tests, but don't require writing any Rust code.
+//!
+//! Each run produces a trace, which contains the scenario with the used seed and the outcomes of
+//! all the steps. Trace can be replayed to check that the execution is deterministic.
+//!
+//! Example scenarios can be found in the `core/tests/testkit/scenarios` directory.
+
+pub use self::{
+    runner::ScenarioRunner,
+    spec::{Scenario, Step, TxSpec},
+    trace::{OperationTrace, StepOutcome, StepTrace, Trace},
+};
+
+mod runner;
+mod spec;
+mod trace;
+
+/// Executes the scenario and returns its trace along with the result.
+pub async fn run_scenario(scenario: Scenario) -> (Trace, anyhow::Result<()>) {
+    match ScenarioRunner::new(scenario.clone()) {
+        Ok(runner) => runner.run().await,
+        Err(err) => (Trace::new(scenario), Err(err)),
+    }
+}
+
+/// Executes the scenario recorded in the trace once again and checks that all the steps
+/// have the same outcomes. Returns the new trace.
+pub async fn replay_trace(trace: &Trace) -> (Trace, anyhow::Result<()>) {
+    let (replayed, result) = run_scenario(trace.scenario.clone()).await;
+    let result = result.and_then(|()| match trace.first_divergence(&replayed) {
+        Some(divergence) => Err(anyhow::format_err!("Replay diverged: {}", divergence)),
+        None => Ok(()),
+    });
+
+    (replayed, result)
+}
@@ -0,0 +1,628 @@
+//! Execution of the scenarios against the state keeper.
+
+use std::thread::JoinHandle;
+
+use anyhow::{bail, ensure, format_err};
+use futures::channel::oneshot;
+use num::{BigUint, Zero};
+use web3::transports::Http;
+
+use zksync_core::state_keeper::ZkSyncStateInitParams;
+use zksync_crypto::{
+    convert::FeConvert,
+    priv_key_from_fs,
+    rand::{thread_rng, Rng, SeedableRng, XorShiftRng},
+    Fr,
+};
+use zksync_types::{
+    block::{Block, ExecutedOperations},
+    tx::TxHash,
+    Nonce, TokenId, ZkSyncTx,
+};
+
+use super::{
+    spec::{parse_amount, parse_root_hash, Scenario, Step, TxSpec},
+    trace::{OperationTrace, StepOutcome, StepTrace, Trace},
+};
+use crate::{
+    eth_account::EthereumAccount,
+    external_commands::{deploy_contracts, get_test_accounts, Contracts},
+    genesis_state, spawn_state_keeper,
+    zksync_account::{ZkSyncAccount, ZkSyncETHAccountData},
+    AccountSet, ETHAccountId, TestSetup, TestkitConfig, Token, ZKSyncAccountId,
+};
+
+/// Expected result of the L2 transaction, checked once the block is sealed.
+#[derive(Debug, Clone)]
+enum TxExpectation {
+    Success,
+    Failure { reason: Option<String> },
+}
+
+/// Sealed block along with the state required to revert to it.
+struct SealedBlock {
+    block: Block,
+    verified: bool,
+    state: ZkSyncStateInitParams,
+    accounts: AccountSet,
+}
+
+/// Executes the scenario steps one by one, checking the expectations and recording the trace.
+///
+/// Scenario account `i` is represented by `ETHAccountId(i)` and `ZKSyncAccountId(i + 1)`,
+/// since the first zkSync account is the fee account.
+pub struct ScenarioRunner {
+    contracts: Contracts,
+    commit_account: EthereumAccount,
+    fee_account: ZkSyncAccount,
+    state_keeper: Option<(JoinHandle<()>, oneshot::Sender<()>)>,
+    test_setup: TestSetup,
+    genesis_accounts: AccountSet,
+    sealed_blocks: Vec<SealedBlock>,
+    /// Transactions executed after the last sealed block.
+    pending_txs: Vec<(TxHash, TxExpectation)>,
+    trace: Trace,
+}
+
+impl ScenarioRunner {
+    /// Deploys the contracts and starts the state keeper for the scenario.
+    pub fn new(mut scenario: Scenario) -> anyhow::Result<Self> {
+        let seed = *scenario.seed.get_or_insert_with(|| thread_rng().gen());
+        let testkit_config = TestkitConfig::from_env();
+
+        // All the L2 keys are derived from the seed, so the root hashes are reproducible.
+        let mut rng = XorShiftRng::from_seed(seed);
+        let fee_account = ZkSyncAccount::rand_with_seed(rng.gen());
+
+        let (test_accounts_info, commit_account_info) = get_test_accounts();
+        ensure!(
+            test_accounts_info.len() >= scenario.accounts,
+            "Scenario requires {} accounts, but only {} test accounts are available",
+            scenario.accounts,
+            test_accounts_info.len()
+        );
+
+        let genesis = genesis_state(&fee_account.address);
+        let contracts = deploy_contracts(false, genesis.tree.root_hash());
+
+        let transport = Http::new(&testkit_config.web3_url).expect("http transport start");
+        let new_eth_account = |private_key, address| {
+            EthereumAccount::new(
+                private_key,
+                address,
+                transport.clone(),
+                contracts.contract,
+                testkit_config.chain_id,
+                testkit_config.gas_price_factor,
+            )
+        };
+        let commit_account =
+            new_eth_account(commit_account_info.private_key, commit_account_info.address);
+        let eth_accounts: Vec<_> = test_accounts_info
+            .into_iter()
+            .take(scenario.accounts)
+            .map(|info| new_eth_account(info.private_key, info.address))
+            .collect();
+
+        let mut zksync_accounts = vec![fee_account.clone()];
+        zksync_accounts.extend(eth_accounts.iter().map(|eth_account| {
+            ZkSyncAccount::new(
+                priv_key_from_fs(rng.gen()),
+                Nonce(0),
+                eth_account.address,
+                ZkSyncETHAccountData::EOA {
+                    eth_private_key: eth_account.private_key,
+                },
+            )
+        }));
+
+        let accounts = AccountSet {
+            eth_accounts,
+            zksync_accounts,
+            fee_account_id: ZKSyncAccountId(0),
+        };
+
+        let initial_root = genesis.tree.root_hash();
+        let (sk_thread_handle, stop_state_keeper_sender, sk_channels) =
+            spawn_state_keeper(&fee_account.address, genesis);
+        let test_setup = TestSetup::new(
+            sk_channels,
+            accounts.clone(),
+            &contracts,
+            commit_account.clone(),
+            initial_root,
+            None,
+        );
+
+        Ok(Self {
+            contracts,
+            commit_account,
+            fee_account,
+            state_keeper: Some((sk_thread_handle, stop_state_keeper_sender)),
+            test_setup,
+            genesis_accounts: accounts,
+            sealed_blocks: Vec::new(),
+            pending_txs: Vec::new(),
+            trace: Trace::new(scenario),
+        })
+    }
+
+    /// Executes the scenario until the first failed step.
+    /// Returns the trace of the executed steps along with the scenario result.
+    pub async fn run(mut self) -> (Trace, anyhow::Result<()>) {
+        let steps = self.trace.scenario.steps.clone();
+        let mut result = Ok(());
+        for (idx, step) in steps.iter().enumerate() {
+            vlog::info!("Executing step #{} ({})", idx, step.name());
+            match self.execute_step(step).await {
+                Ok(outcome) => self.trace.steps.push(StepTrace {
+                    step: idx,
+                    action: step.name().to_string(),
+                    outcome,
+                }),
+                Err(err) => {
+                    result = Err(err.context(format!("Step #{} ({}) failed", idx, step.name())));
+                    break;
+                }
+            }
+        }
+
+        self.stop_state_keeper();
+        (self.trace, result)
+    }
+
+    async fn execute_step(&mut self, step: &Step) -> anyhow::Result<StepOutcome> {
+        match step {
+            Step::Deposit {
+                from,
+                to,
+                token,
+                amount,
+            } => {
+                let (_, op) = self
+                    .test_setup
+                    .deposit(
+                        eth_account(*from),
+                        zksync_account(*to),
+                        Token(TokenId(*token)),
+                        parse_amount(amount)?,
+                    )
+                    .await;
+                Ok(StepOutcome::PriorityOp {
+                    serial_id: op.serial_id,
+                })
+            }
+            Step::FullExit { account, token } => {
+                self.set_account_id(*account).await?;
+                let (_, op) = self
+                    .test_setup
+                    .full_exit(
+                        eth_account(*account),
+                        zksync_account(*account),
+                        Token(TokenId(*token)),
+                    )
+                    .await;
+                Ok(StepOutcome::PriorityOp {
+                    serial_id: op.serial_id,
+                })
+            }
+            Step::Tx {
+                tx,
+                expect_failure,
+                fail_reason,
+            } => {
+                let tx = self.build_tx(tx, *expect_failure).await?;
+                let tx_hash = tx.hash();
+                self.test_setup.execute_tx(tx).await;
+
+                self.pending_txs
+                    .push((tx_hash, expectation(*expect_failure, fail_reason)));
+                Ok(StepOutcome::Txs {
+                    tx_hashes: vec![tx_hash.to_string()],
+                })
+            }
+            Step::Batch {
+                txs,
+                expect_failure,
+                fail_reason,
+            } => {
+                let mut batch = Vec::with_capacity(txs.len());
+                for tx in txs {
+                    batch.push(self.build_tx(tx, *expect_failure).await?);
+                }
+                let tx_hashes: Vec<_> = batch.iter().map(ZkSyncTx::hash).collect();
+                self.test_setup.execute_batch(batch).await;
+
+                self.pending_txs.extend(
+                    tx_hashes
+                        .iter()
+                        .map(|&tx_hash| (tx_hash, expectation(*expect_failure, fail_reason))),
+                );
+                Ok(StepOutcome::Txs {
+                    tx_hashes: tx_hashes.iter().map(TxHash::to_string).collect(),
+                })
+            }
+            Step::SealBlock { verify } => self.seal_block(*verify).await,
+            Step::RevertBlocks { count } => self.revert_blocks(*count).await,
+            Step::ExpectBalance {
+                account,
+                token,
+                amount,
+            } => {
+                let expected = parse_amount(amount)?;
+                let balance = self
+                    .test_setup
+                    .get_zksync_account_committed_state(zksync_account(*account))
+                    .await
+                    .map(|(_, state)| state.get_balance(TokenId(*token)))
+                    .unwrap_or_default();
+                ensure!(
+                    balance == expected,
+                    "Account {} balance of token {} is {}, expected {}",
+                    account,
+                    token,
+                    balance,
+                    expected
+                );
+                Ok(StepOutcome::Checked)
+            }
+            Step::ExpectRootHash { root_hash } => {
+                let expected = parse_root_hash(root_hash)?;
+                let actual = format_root_hash(self.test_setup.last_committed_block.new_root_hash);
+                ensure!(
+                    actual == expected,
+                    "Root hash of the last sealed block is {}, expected {}",
+                    actual,
+                    expected
+                );
+                Ok(StepOutcome::Checked)
+            }
+        }
+    }
+
+    /// Signs the transaction. For the transactions expected to succeed, updates the
+    /// expected balances of the current block and increments the account nonce.
+    async fn build_tx(&mut self, tx: &TxSpec, expect_failure: bool) -> anyhow::Result<ZkSyncTx> {
+        let increment_nonce = !expect_failure;
+        let tx = match tx {
+            TxSpec::Transfer {
+                from,
+                to,
+                token,
+                amount,
+                fee,
+                nonce,
+            } => {
+                self.set_account_id(*from).await?;
+                let (token, amount, fee) = (
+                    Token(TokenId(*token)),
+                    parse_amount(amount)?,
+                    parse_amount(fee)?,
+                );
+                if !expect_failure {
+                    self.test_setup
+                        .expect_transfer(
+                            zksync_account(*from),
+                            zksync_account(*to),
+                            token,
+                            &amount,
+                            &fee,
+                        )
+                        .await;
+                }
+                self.test_setup.accounts.transfer(
+                    zksync_account(*from),
+                    zksync_account(*to),
+                    token,
+                    amount,
+                    fee,
+                    nonce.map(Nonce),
+                    Default::default(),
+                    increment_nonce,
+                )
+            }
+            TxSpec::Withdraw {
+                from,
+                to,
+                token,
+                amount,
+                fee,
+                nonce,
+            } => {
+                self.set_account_id(*from).await?;
+                let (token, amount, fee) = (
+                    Token(TokenId(*token)),
+                    parse_amount(amount)?,
+                    parse_amount(fee)?,
+                );
+                if !expect_failure {
+                    self.test_setup
+                        .expect_withdraw(
+                            zksync_account(*from),
+                            eth_account(*to),
+                            token,
+                            &amount,
+                            &fee,
+                        )
+                        .await;
+                }
+                self.test_setup.accounts.withdraw(
+                    zksync_account(*from),
+                    eth_account(*to),
+                    token,
+                    amount,
+                    fee,
+                    nonce.map(Nonce),
+                    increment_nonce,
+                    Default::default(),
+                )
+            }
+            TxSpec::ChangePubKey {
+                account,
+                fee_token,
+                fee,
+                nonce,
+            } => {
+                self.set_account_id(*account).await?;
+                let (fee_token, fee) = (Token(TokenId(*fee_token)), parse_amount(fee)?);
+                if !expect_failure {
+                    // Paying the fee is equivalent to the empty transfer to the fee account.
+                    let fee_account_id = self.test_setup.accounts.fee_account_id;
+                    self.test_setup
+                        .expect_transfer(
+                            zksync_account(*account),
+                            fee_account_id,
+                            fee_token,
+                            &BigUint::zero(),
+                            &fee,
+                        )
+                        .await;
+                }
+                self.test_setup.accounts.change_pubkey_with_tx(
+                    zksync_account(*account),
+                    fee_token.0,
+                    fee,
+                    nonce.map(Nonce),
+                    increment_nonce,
+                    Default::default(),
+                )
+            }
+        };
+
+        Ok(tx)
+    }
+
+    async fn seal_block(&mut self, verify: bool) -> anyhow::Result<StepOutcome> {
+        let block = if verify {
+            self.test_setup
+                .execute_commit_and_verify_block()
+                .await?
+                .block
+        } else {
+            self.test_setup.execute_commit_block().await
+        };
+        self.test_setup.start_block();
+
+        let operations = self.check_block_operations(&block)?;
+        self.sealed_blocks.push(SealedBlock {
+            block: block.clone(),
+            verified: verify,
+            state: self.test_setup.get_current_state().await,
+            accounts: self.test_setup.accounts.clone(),
+        });
+
+        Ok(StepOutcome::BlockSealed {
+            block_number: *block.block_number,
+            root_hash: format_root_hash(block.new_root_hash),
+            verified: verify,
+            operations,
+        })
+    }
+
+    /// Checks that all the pending transactions were included into the block with the expected results.
+    fn check_block_operations(&mut self, block: &Block) -> anyhow::Result<Vec<OperationTrace>> {
+        let mut operations = Vec::with_capacity(block.block_transactions.len());
+        for operation in &block.block_transactions {
+            let operation = match operation {
+                ExecutedOperations::Tx(tx) => {
+                    let tx_hash = tx.signed_tx.hash();
+                    let position = self
+                        .pending_txs
+                        .iter()
+                        .position(|(hash, _)| *hash == tx_hash)
+                        .ok_or_else(|| {
+                            format_err!(
+                                "Unexpected transaction in the block: {}",
+                                tx_hash.to_string()
+                            )
+                        })?;
+                    let (_, expectation) = self.pending_txs.remove(position);
+                    check_tx_result(
+                        &tx_hash,
+                        &expectation,
+                        tx.success,
+                        tx.fail_reason.as_deref(),
+                    )?;
+
+                    OperationTrace {
+                        id: tx_hash.to_string(),
+                        success: tx.success,
+                        fail_reason: tx.fail_reason.clone(),
+                    }
+                }
+                ExecutedOperations::PriorityOp(op) => OperationTrace {
+                    id: format!("priority_op:{}", op.priority_op.serial_id),
+                    success: true,
+                    fail_reason: None,
+                },
+            };
+            operations.push(operation);
+        }
+
+        if !self.pending_txs.is_empty() {
+            let missing: Vec<_> = self
+                .pending_txs
+                .drain(..)
+                .map(|(tx_hash, _)| tx_hash.to_string())
+                .collect();
+            bail!(
+                "Transactions were not included into the block: {:?}",
+                missing
+            );
+        }
+
+        Ok(operations)
+    }
+
+    async fn revert_blocks(&mut self, count: usize) -> anyhow::Result<StepOutcome> {
+        ensure!(
+            count <= self.sealed_blocks.len(),
+            "Unable to revert {} blocks, only {} blocks are sealed",
+            count,
+            self.sealed_blocks.len()
+        );
+        let first_reverted = self.sealed_blocks.len() - count;
+        ensure!(
+            self.sealed_blocks[first_reverted..]
+                .iter()
+                .all(|sealed| !sealed.verified),
+            "Verified blocks can't be reverted"
+        );
+
+        let reverted = self.sealed_blocks.split_off(first_reverted);
+        // Blocks are reverted starting from the latest one.
+        let blocks: Vec<_> = reverted
+            .iter()
+            .rev()
+            .map(|sealed| sealed.block.clone())
+            .collect();
+        self.test_setup.revert_blocks(&blocks).await?;
+
+        // State keeper can't revert blocks, so it's restarted with the state of the last remaining block.
+        let (state, accounts, last_block) = match self.sealed_blocks.last() {
+            Some(sealed) => (
+                sealed.state.clone(),
+                sealed.accounts.clone(),
+                Some(sealed.block.clone()),
+            ),
+            None => (
+                genesis_state(&self.fee_account.address),
+                self.genesis_accounts.clone(),
+                None,
+            ),
+        };
+        let last_block_number = last_block
+            .as_ref()
+            .map(|block| *block.block_number)
+            .unwrap_or_default();
+        self.restart_state_keeper(state, accounts, last_block);
+        self.pending_txs.clear();
+
+        Ok(StepOutcome::Reverted {
+            reverted_blocks: reverted
+                .iter()
+                .map(|sealed| *sealed.block.block_number)
+                .collect(),
+            last_block_number,
+        })
+    }
+
+    fn restart_state_keeper(
+        &mut self,
+        state: ZkSyncStateInitParams,
+        accounts: AccountSet,
+        last_block: Option<Block>,
+    ) {
+        self.stop_state_keeper();
+
+        let root_hash = state.tree.root_hash();
+        let (sk_thread_handle, stop_state_keeper_sender, sk_channels) =
+            spawn_state_keeper(&self.fee_account.address, state);
+        self.state_keeper = Some((sk_thread_handle, stop_state_keeper_sender));
+        self.test_setup = TestSetup::new(
+            sk_channels,
+            accounts,
+            &self.contracts,
+            self.commit_account.clone(),
+            root_hash,
+            last_block,
+        );
+    }
+
+    fn stop_state_keeper(&mut self) {
+        if let Some((sk_thread_handle, stop_state_keeper_sender)) = self.state_keeper.take() {
+            stop_state_keeper_sender.send(()).expect("sk stop send");
+            sk_thread_handle.join().expect("sk thread join");
+        }
+    }
+
+    /// Sets the L2 account ID required to sign the transactions.
+    async fn set_account_id(&mut self, account: usize) -> anyhow::Result<()> {
+        let account_id = self
+            .test_setup
+            .get_zksync_account_id(zksync_account(account))
+            .await
+            .ok_or_else(|| format_err!("Account {} does not exist in L2", account))?;
+        self.test_setup.accounts.zksync_accounts[zksync_account(account).0]
+            .set_account_id(Some(account_id));
+
+        Ok(())
+    }
+}
+
+fn eth_account(account: usize) -> ETHAccountId {
+    ETHAccountId(account)
+}
+
+fn zksync_account(account: usize) -> ZKSyncAccountId {
+    ZKSyncAccountId(account + 1)
+}
+
+fn expectation(expect_failure: bool, fail_reason: &Option<String>) -> TxExpectation {
+    if expect_failure {
+        TxExpectation::Failure {
+            reason: fail_reason.clone(),
+        }
+    } else {
+        TxExpectation::Success
+    }
+}
+
+fn check_tx_result(
+    tx_hash: &TxHash,
+    expectation: &TxExpectation,
+    success: bool,
+    fail_reason: Option<&str>,
+) -> anyhow::Result<()> {
+    match expectation {
+        TxExpectation::Success => ensure!(
+            success,
+            "Transaction {} failed: {}",
+            tx_hash.to_string(),
+            fail_reason.unwrap_or_default()
+        ),
+        TxExpectation::Failure { reason } => {
+            ensure!(
+                !success,
+                "Transaction {} was expected to fail, but succeeded",
+                tx_hash.to_string()
+            );
+            if let Some(reason) = reason {
+                let actual = fail_reason.unwrap_or_default();
+                ensure!(
+                    actual.contains(reason.as_str()),
+                    "Transaction {} failed with '{}', expected '{}'",
+                    tx_hash.to_string(),
+                    actual,
+                    reason
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn format_root_hash(root_hash: Fr) -> String {
+    format!("0x{}", root_hash.to_hex())
+}
@@ -0,0 +1,305 @@
+//! Format of the scenario files.
+
+use std::{fs, path::Path};
+
+use anyhow::{ensure, Context};
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+
+use crate::eth_account::parse_ether;
+
+/// Tokens available in the testkit: ETH and the test ERC-20 token.
+const SUPPORTED_TOKENS: &[u32] = &[0, 1];
+
+/// Declarative description of the testkit scenario.
+///
+/// Each scenario account has both an L1 wallet (one of the test accounts of the local Ethereum node)
+/// and an L2 wallet. Accounts are referenced by their index in range `0..accounts`.
+/// Amounts are represented as decimal strings in the token units, e.g. `"0.25"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Seed for the L2 keys generation. Root hashes of the blocks depend on the keys,
+    /// so the seed must be set in order to check them. Generated randomly if not set.
+    #[serde(default)]
+    pub seed: Option<[u32; 4]>,
+    /// Amount of the accounts participating in the scenario.
+    pub accounts: usize,
+    pub steps: Vec<Step>,
+}
+
+/// Single step of the scenario.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Step {
+    /// Deposit from the L1 wallet of one account to the L2 wallet of another one.
+    Deposit {
+        from: usize,
+        to: usize,
+        token: u32,
+        amount: String,
+    },
+    /// Full exit requested from the L1 wallet.
+    FullExit { account: usize, token: u32 },
+    /// L2 transaction.
+    Tx {
+        tx: TxSpec,
+        /// If set, the transaction is expected to fail.
+        #[serde(default)]
+        expect_failure: bool,
+        /// Substring of the expected failure reason.
+        #[serde(default)]
+        fail_reason: Option<String>,
+    },
+    /// Batch of L2 transactions, which either succeed or fail together.
+    Batch {
+        txs: Vec<TxSpec>,
+        #[serde(default)]
+        expect_failure: bool,
+        #[serde(default)]
+        fail_reason: Option<String>,
+    },
+    /// Seals the block with all the executed operations and commits it on L1.
+    SealBlock {
+        /// Whether the block should also be verified and executed on L1.
+        /// Balance changes are only checked for the verified blocks.
+        #[serde(default = "default_verify")]
+        verify: bool,
+    },
+    /// Reverts the latest committed, but not verified blocks, along with all the operations
+    /// executed after the last sealed block.
+    RevertBlocks { count: usize },
+    /// Checks the L2 balance of the account, including the operations that were not sealed yet.
+    ExpectBalance {
+        account: usize,
+        token: u32,
+        amount: String,
+    },
+    /// Checks the root hash of the last sealed block.
+    ExpectRootHash { root_hash: String },
+}
+
+fn default_verify() -> bool {
+    true
+}
+
+/// L2 transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TxSpec {
+    Transfer {
+        from: usize,
+        to: usize,
+        token: u32,
+        amount: String,
+        fee: String,
+        /// Nonce override, the current account nonce is used if not set.
+        #[serde(default)]
+        nonce: Option<u32>,
+    },
+    /// Withdrawal to the L1 wallet of the account.
+    Withdraw {
+        from: usize,
+        to: usize,
+        token: u32,
+        amount: String,
+        fee: String,
+        #[serde(default)]
+        nonce: Option<u32>,
+    },
+    /// Setting the signing key authorized with the ECDSA signature.
+    ChangePubKey {
+        account: usize,
+        fee_token: u32,
+        fee: String,
+        #[serde(default)]
+        nonce: Option<u32>,
+    },
+}
+
+impl Scenario {
+    /// Loads the scenario from the JSON file and checks its correctness.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read scenario {}", path.display()))?;
+        let scenario: Self = serde_json::from_str(&contents)
+            .with_context(|| format!("Unable to parse scenario {}", path.display()))?;
+        scenario
+            .validate()
+            .with_context(|| format!("Invalid scenario {}", path.display()))?;
+
+        Ok(scenario)
+    }
+
+    /// Checks that the steps only reference existing accounts and tokens, and all the amounts are correct.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(self.accounts > 0, "Scenario must have at least one account");
+        ensure!(
+            self.seed != Some([0; 4]),
+            "Seed must contain at least one non-zero element"
+        );
+
+        for (idx, step) in self.steps.iter().enumerate() {
+            self.validate_step(step)
+                .with_context(|| format!("Step #{} ({}) is incorrect", idx, step.name()))?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_step(&self, step: &Step) -> anyhow::Result<()> {
+        match step {
+            Step::Deposit {
+                from,
+                to,
+                token,
+                amount,
+            } => {
+                self.check_accounts(&[*from, *to])?;
+                check_token(*token)?;
+                parse_amount(amount)?;
+            }
+            Step::FullExit { account, token } => {
+                self.check_accounts(&[*account])?;
+                check_token(*token)?;
+            }
+            Step::Tx { tx, .. } => self.validate_tx(tx)?,
+            Step::Batch { txs, .. } => {
+                ensure!(
+                    !txs.is_empty(),
+                    "Batch must contain at least one transaction"
+                );
+                for tx in txs {
+                    self.validate_tx(tx)?;
+                }
+            }
+            Step::SealBlock { .. } => {}
+            Step::RevertBlocks { count } => {
+                ensure!(*count > 0, "At least one block must be reverted");
+            }
+            Step::ExpectBalance {
+                account,
+                token,
+                amount,
+            } => {
+                self.check_accounts(&[*account])?;
+                check_token(*token)?;
+                parse_amount(amount)?;
+            }
+            Step::ExpectRootHash { root_hash } => {
+                ensure!(
+                    self.seed.is_some(),
+                    "Root hashes can only be checked if the seed is set"
+                );
+                parse_root_hash(root_hash)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_tx(&self, tx: &TxSpec) -> anyhow::Result<()> {
+        match tx {
+            TxSpec::Transfer {
+                from,
+                to,
+                token,
+                amount,
+                fee,
+                ..
+            }
+            | TxSpec::Withdraw {
+                from,
+                to,
+                token,
+                amount,
+                fee,
+                ..
+            } => {
+                self.check_accounts(&[*from, *to])?;
+                check_token(*token)?;
+                parse_amount(amount)?;
+                parse_amount(fee)?;
+            }
+            TxSpec::ChangePubKey {
+                account,
+                fee_token,
+                fee,
+                ..
+            } => {
+                self.check_accounts(&[*account])?;
+                check_token(*fee_token)?;
+                parse_amount(fee)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_accounts(&self, accounts: &[usize]) -> anyhow::Result<()> {
+        for &account in accounts {
+            ensure!(
+                account < self.accounts,
+                "Account {} does not exist, scenario has {} accounts",
+                account,
+                self.accounts
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Step {
+    /// Short name of the step used in the logs and errors.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Deposit { .. } => "deposit",
+            Self::FullExit { .. } => "full_exit",
+            Self::Tx { tx, .. } => tx.name(),
+            Self::Batch { .. } => "batch",
+            Self::SealBlock { .. } => "seal_block",
+            Self::RevertBlocks { .. } => "revert_blocks",
+            Self::ExpectBalance { .. } => "expect_balance",
+            Self::ExpectRootHash { .. } => "expect_root_hash",
+        }
+    }
+}
+
+impl TxSpec {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Transfer { .. } => "transfer",
+            Self::Withdraw { .. } => "withdraw",
+            Self::ChangePubKey { .. } => "change_pub_key",
+        }
+    }
+}
+
+fn check_token(token: u32) -> anyhow::Result<()> {
+    ensure!(
+        SUPPORTED_TOKENS.contains(&token),
+        "Token {} is not supported, available tokens: {:?}",
+        token,
+        SUPPORTED_TOKENS
+    );
+    Ok(())
+}
+
+/// Parses the amount represented in the token units. Both supported tokens have 18 decimals.
+pub fn parse_amount(amount: &str) -> anyhow::Result<BigUint> {
+    parse_ether(amount).with_context(|| format!("Incorrect amount: {}", amount))
+}
+
+/// Parses the hexadecimal root hash, with or without the `0x` prefix.
+pub fn parse_root_hash(root_hash: &str) -> anyhow::Result<String> {
+    let hash = root_hash.strip_prefix("0x").unwrap_or(root_hash);
+    ensure!(
+        hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()),
+        "Incorrect root hash: {}",
+        root_hash
+    );
+    Ok(format!("0x{}", hash.to_ascii_lowercase()))
+}
@@ -0,0 +1,103 @@
+//! Trace of the scenario execution.
+
+use std::{fs, path::Path};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use super::spec::Scenario;
+
+/// Record of the scenario execution.
+///
+/// Trace contains the scenario itself (with the seed that was actually used), so it can be
+/// replayed later, and the outcomes of all the executed steps to compare the replay results with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trace {
+    pub scenario: Scenario,
+    pub steps: Vec<StepTrace>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepTrace {
+    /// Index of the step in the scenario.
+    pub step: usize,
+    pub action: String,
+    pub outcome: StepOutcome,
+}
+
+/// Observable result of the step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum StepOutcome {
+    /// Priority operation was sent to L1 and executed by the state keeper.
+    PriorityOp { serial_id: u64 },
+    /// L2 transactions were executed by the state keeper.
+    Txs { tx_hashes: Vec<String> },
+    BlockSealed {
+        block_number: u32,
+        root_hash: String,
+        verified: bool,
+        operations: Vec<OperationTrace>,
+    },
+    Reverted {
+        reverted_blocks: Vec<u32>,
+        last_block_number: u32,
+    },
+    /// Expectation was met.
+    Checked,
+}
+
+/// Operation included into the block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationTrace {
+    /// Transaction hash for L2 transactions, or serial ID for the priority operations.
+    pub id: String,
+    pub success: bool,
+    pub fail_reason: Option<String>,
+}
+
+impl Trace {
+    pub fn new(scenario: Scenario) -> Self {
+        Self {
+            scenario,
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read trace {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("Unable to parse trace {}", path.display()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(path, contents)
+            .with_context(|| format!("Unable to write trace {}", path.display()))
+    }
+
+    /// Compares the trace with the replayed one and returns the description of the first difference.
+    pub fn first_divergence(&self, replayed: &Self) -> Option<String> {
+        for (expected, actual) in self.steps.iter().zip(&replayed.steps) {
+            if expected != actual {
+                return Some(format!(
+                    "Step #{} ({}) diverged.\nRecorded: {:#?}\nReplayed: {:#?}",
+                    expected.step, expected.action, expected.outcome, actual.outcome
+                ));
+            }
+        }
+
+        if self.steps.len() != replayed.steps.len() {
+            return Some(format!(
+                "Recorded trace has {} steps, while replayed one has {}",
+                self.steps.len(),
+                replayed.steps.len()
+            ));
+        }
+
+        None
+    }
+}
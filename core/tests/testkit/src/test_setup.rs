@@ -315,10 +315,24 @@ impl TestSetup {
         (receipts, deposit_op, transfers)
     }
 
-    async fn execute_tx(&mut self, tx: ZkSyncTx) {
+    pub(crate) async fn execute_tx(&mut self, tx: ZkSyncTx) {
+        self.execute_tx_variant(SignedTxVariant::from(SignedZkSyncTx::from(tx)))
+            .await;
+    }
+
+    /// Executes the transactions as a single batch.
+    /// Balance side effects are not checked, so expected changes (if any) should be set separately.
+    pub async fn execute_batch(&mut self, txs: Vec<ZkSyncTx>) {
+        let txs = txs.into_iter().map(SignedZkSyncTx::from).collect();
+        self.execute_tx_variant(SignedTxVariant::batch(txs, 0, Vec::new()))
+            .await;
+    }
+
+    async fn execute_tx_variant(&mut self, tx: SignedTxVariant) {
         let block = ProposedBlock {
             priority_ops: Vec::new(),
-            txs: vec![SignedTxVariant::from(SignedZkSyncTx::from(tx))],
+            txs: vec![tx],
+            trace_contexts: Default::default(),
         };
 
         // Request miniblock execution.
@@ -400,6 +414,7 @@ impl TestSetup {
         let block = ProposedBlock {
             priority_ops: vec![op],
             txs: Vec::new(),
+            trace_contexts: Default::default(),
         };
 
         // Request miniblock execution.
@@ -684,18 +699,36 @@ impl TestSetup {
         amount: BigUint,
         fee: BigUint,
         time_range: TimeRange,
+    ) {
+        self.expect_transfer(from, to, token, &amount, &fee).await;
+
+        let transfer = self
+            .accounts
+            .transfer(from, to, token, amount, fee, None, time_range, true);
+
+        self.execute_tx(transfer).await;
+    }
+
+    /// Updates the expected balances of the current block according to the transfer.
+    pub async fn expect_transfer(
+        &mut self,
+        from: ZKSyncAccountId,
+        to: ZKSyncAccountId,
+        token: Token,
+        amount: &BigUint,
+        fee: &BigUint,
     ) {
         let mut zksync0_old = self
             .get_expected_zksync_account_balance(from, token.0)
             .await;
-        zksync0_old -= &amount;
-        zksync0_old -= &fee;
+        zksync0_old -= amount;
+        zksync0_old -= fee;
         self.expected_changes_for_current_block
             .sync_accounts_state
             .insert((from, token.0), zksync0_old);
 
         let mut zksync0_old = self.get_expected_zksync_account_balance(to, token.0).await;
-        zksync0_old += &amount;
+        zksync0_old += amount;
         self.expected_changes_for_current_block
             .sync_accounts_state
             .insert((to, token.0), zksync0_old);
@@ -703,16 +736,10 @@ impl TestSetup {
         let mut zksync0_old = self
             .get_expected_zksync_account_balance(self.accounts.fee_account_id, token.0)
             .await;
-        zksync0_old += &fee;
+        zksync0_old += fee;
         self.expected_changes_for_current_block
             .sync_accounts_state
             .insert((self.accounts.fee_account_id, token.0), zksync0_old);
-
-        let transfer = self
-            .accounts
-            .transfer(from, to, token, amount, fee, None, time_range, true);
-
-        self.execute_tx(transfer).await;
     }
 
     pub async fn transfer_to_new_random(
@@ -754,18 +781,36 @@ impl TestSetup {
         token: Token,
         amount: BigUint,
         fee: BigUint,
+    ) {
+        self.expect_withdraw(from, to, token, &amount, &fee).await;
+
+        let withdraw =
+            self.accounts
+                .withdraw(from, to, token, amount, fee, None, true, Default::default());
+
+        self.execute_tx(withdraw).await;
+    }
+
+    /// Updates the expected balances of the current block according to the withdrawal.
+    pub async fn expect_withdraw(
+        &mut self,
+        from: ZKSyncAccountId,
+        to: ETHAccountId,
+        token: Token,
+        amount: &BigUint,
+        fee: &BigUint,
     ) {
         let mut zksync0_old = self
             .get_expected_zksync_account_balance(from, token.0)
             .await;
-        zksync0_old -= &amount;
-        zksync0_old -= &fee;
+        zksync0_old -= amount;
+        zksync0_old -= fee;
         self.expected_changes_for_current_block
             .sync_accounts_state
             .insert((from, token.0), zksync0_old);
 
         let mut to_eth_balance = self.get_expected_eth_account_balance(to, token.0).await;
-        to_eth_balance += &amount;
+        to_eth_balance += amount;
         self.expected_changes_for_current_block
             .eth_accounts_state
             .insert((to, token.0), to_eth_balance);
@@ -773,16 +818,10 @@ impl TestSetup {
         let mut zksync0_old = self
             .get_expected_zksync_account_balance(self.accounts.fee_account_id, token.0)
             .await;
-        zksync0_old += &fee;
+        zksync0_old += fee;
         self.expected_changes_for_current_block
             .sync_accounts_state
             .insert((self.accounts.fee_account_id, token.0), zksync0_old);
-
-        let withdraw =
-            self.accounts
-                .withdraw(from, to, token, amount, fee, None, true, Default::default());
-
-        self.execute_tx(withdraw).await;
     }
 
     pub async fn withdraw_nft(
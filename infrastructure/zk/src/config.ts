@@ -4,6 +4,7 @@ import * as fs from 'fs';
 import * as path from 'path';
 import deepExtend from 'deep-extend';
 import { env } from 'process';
+import * as utils from './utils';
 
 const CONFIG_FILES = [
     'api.toml',
@@ -158,6 +159,17 @@ export async function compileConfig(environment?: string) {
     console.log('Configs compiled');
 }
 
+export async function checkConfig(environment?: string) {
+    if (!environment) {
+        environment = await getEnvironment();
+    }
+
+    await checkConfigExistence(environment);
+
+    const configFolder = path.join(envDirPath(), environment);
+    await utils.spawn(`cargo run --release --bin config_tool -- check ${configFolder}`);
+}
+
 export const command = new Command('config').description('config management');
 
 command.command('load [environment]').description('load the config for a certain environment').action(printAllConfigs);
@@ -165,3 +177,7 @@ command
     .command('compile [environment]')
     .description('compile the config for a certain environment')
     .action(compileConfig);
+command
+    .command('check [environment]')
+    .description('print the effective config (with secrets redacted) and validate it')
+    .action(checkConfig);
@@ -63,6 +63,14 @@ export async function updateToken(token: string, symbol: string) {
     await utils.exec(`${SQL()} "UPDATE tokens SET symbol = '${symbol}' WHERE address = '${token}'"`);
 }
 
+export async function check(fromBlock?: string, toBlock?: string, skipState?: boolean) {
+    let args = '';
+    if (fromBlock) args += ` --from_block ${fromBlock}`;
+    if (toBlock) args += ` --to_block ${toBlock}`;
+    if (skipState) args += ' --skip_state';
+    await utils.spawn(`cargo run --bin storage_check --release --${args}`);
+}
+
 export async function wait(tries: number = 4) {
     for (let i = 0; i < tries; i++) {
         const result = await utils.allowFail(utils.exec(`pg_isready -d "${process.env.DATABASE_URL}"`));
@@ -86,3 +94,12 @@ command
 command.command('setup').description('initialize the database and perform migrations').action(setup);
 command.command('wait').description('wait for database to get ready for interaction').action(wait);
 command.command('reset').description('reinitialize the database').action(reset);
+command
+    .command('check')
+    .description('check the consistency of the chain storage')
+    .option('--from-block <block>', 'first block to replay the account updates from')
+    .option('--to-block <block>', 'last block to replay the account updates to')
+    .option('--skip-state', 'skip the replay of the account updates')
+    .action(async (cmd: Command) => {
+        await check(cmd.fromBlock, cmd.toBlock, cmd.skipState);
+    });
@@ -32,6 +32,15 @@ export async function run() {
     await utils.spawn('cargo run --bin zksync_data_restore --release -- --genesis --finite');
 }
 
+export async function exportArchive(path: string) {
+    await utils.spawn(`cargo run --bin zksync_data_restore --release -- --export_archive ${path}`);
+}
+
+export async function restoreArchive(path: string) {
+    await db.reset();
+    await utils.spawn(`cargo run --bin zksync_data_restore --release -- --genesis --finite --archive ${path}`);
+}
+
 export async function check(expectedHash: string) {
     await db.reset();
     await utils.spawn(
@@ -50,6 +59,16 @@ command.command('restart').description('wipe the database and run data restore i
 command.command('resume').description('run data restore in "resume" mode').action(resume);
 command.command('run').description('do not wipe the database and run data restore in finite mode').action(run);
 
+command
+    .command('export <path>')
+    .description('export the Ethereum data required to restore the state into the archive file')
+    .action(exportArchive);
+
+command
+    .command('restore-archive <path>')
+    .description('wipe the database and run data restore in finite mode using the archive file')
+    .action(restoreArchive);
+
 command
     .command('check <hash>')
     .description('wipe the database, run the data restore in finite mode and check the root hash')
@@ -0,0 +1,30 @@
+import { Command } from 'commander';
+import * as utils from '../utils';
+import * as db from '../db/db';
+
+export function databaseUrl() {
+    return `${process.env.DATABASE_URL}_devnet`;
+}
+
+// The mock L1 state isn't persisted, so the devnet always starts from the empty database.
+// The devnet has its own database, the server database is never touched.
+export async function resetDatabase() {
+    const url = databaseUrl();
+    await db.wait();
+    process.chdir('core/lib/storage');
+    await utils.exec(`DATABASE_URL="${url}" diesel database reset`);
+    process.chdir(process.env.ZKSYNC_HOME as string);
+}
+
+export async function devnet(controlPort?: string) {
+    await resetDatabase();
+    const port = controlPort ? `--control_port ${controlPort}` : '';
+    await utils.spawn(`cargo run --bin zksync_devnet --release -- --database_url "${databaseUrl()}" ${port}`);
+}
+
+export const command = new Command('devnet')
+    .description('run the whole server with the mock L1 and the dummy prover in one process on a separate database')
+    .option('--control-port <port>', 'port of the API used to add deposits, full exits and tokens')
+    .action(async (cmd: Command) => {
+        await devnet(cmd.controlPort);
+    });
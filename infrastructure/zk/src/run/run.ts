@@ -6,9 +6,11 @@ import * as path from 'path';
 import * as verifyKeys from './verify-keys';
 import * as eventListener from './event-listener';
 import * as dataRestore from './data-restore';
+import * as stateSnapshot from './state-snapshot';
+import * as devnet from './devnet';
 import * as docker from '../docker';
 
-export { verifyKeys, dataRestore };
+export { verifyKeys, dataRestore, stateSnapshot, devnet };
 
 export async function deployERC20(command: 'dev' | 'new', name?: string, symbol?: string, decimals?: string) {
     if (command == 'dev') {
@@ -102,6 +104,10 @@ export async function exitProof(...args: string[]) {
     await utils.spawn(`cargo run --example generate_exit_proof --release -- ${args.join(' ')}`);
 }
 
+export async function verifyProofs(...args: string[]) {
+    await utils.spawn(`cargo run --example verify_proofs --release -- ${args.join(' ')}`);
+}
+
 export async function catLogs(exitCode?: number) {
     utils.allowFailSync(() => {
         console.log('\nSERVER LOGS:\n', fs.readFileSync('server.log').toString());
@@ -148,6 +154,8 @@ export const command = new Command('run')
     .description('run miscellaneous applications')
     .addCommand(verifyKeys.command)
     .addCommand(dataRestore.command)
+    .addCommand(stateSnapshot.command)
+    .addCommand(devnet.command)
     .addCommand(eventListener.command);
 
 command.command('test-accounts').description('print ethereum test accounts').action(testAccounts);
@@ -237,6 +245,14 @@ command
         }
     });
 
+command
+    .command('verify-proofs [options...]')
+    .description('verify stored proofs against the block commitments')
+    .allowUnknownOption()
+    .action(async (options: string[]) => {
+        await verifyProofs(...options);
+    });
+
 command
     .command('loadtest [options...]')
     .description('run the loadtest')
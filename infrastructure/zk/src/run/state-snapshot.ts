@@ -0,0 +1,35 @@
+import { Command } from 'commander';
+import * as utils from '../utils';
+import * as db from '../db/db';
+
+export async function exportSnapshot(path: string, block?: string) {
+    block = block ? `--block ${block}` : '';
+    await utils.spawn(`cargo run --bin state_snapshot --release -- export ${path} ${block}`);
+}
+
+export async function importSnapshot(path: string, force: boolean = false) {
+    // The snapshot is imported into the empty database only, so the existing data
+    // is wiped only on explicit request.
+    if (force) {
+        await db.reset();
+    }
+    await utils.spawn(`cargo run --bin state_snapshot --release -- import ${path}`);
+}
+
+export const command = new Command('state-snapshot').description('export and import the state snapshots');
+
+command
+    .command('export <path>')
+    .description('export the committed state into the snapshot file')
+    .option('--block <block>', 'block to export the state at, the last committed block by default')
+    .action(async (path: string, cmd: Command) => {
+        await exportSnapshot(path, cmd.block);
+    });
+
+command
+    .command('import <path>')
+    .description('verify the snapshot file and import the state from it into the empty database')
+    .option('--force', 'wipe the database before the import')
+    .action(async (path: string, cmd: Command) => {
+        await importSnapshot(path, !!cmd.force);
+    });
@@ -168,19 +168,52 @@ export async function testkit(command: string, timeout: number) {
     await run.verifyKeys.unpack();
     await contract.build();
 
+    const scenarios = `${process.env.ZKSYNC_HOME}/core/tests/testkit/scenarios`;
     if (command.includes('block_sizes_test ')) {
         await utils.spawn(`cargo run --release --bin ${command}`);
+    } else if (command == 'scenarios') {
+        await utils.spawn(`cargo run --release --bin scenario_runner -- run ${scenarios}`);
     } else if (command == 'fast') {
         await utils.spawn('cargo run --bin testkit_tests --release');
         await utils.spawn('cargo run --bin gas_price_test --release');
         await utils.spawn('cargo run --bin revert_blocks_test --release');
         await utils.spawn('cargo run --bin migration_test --release');
         await utils.spawn('cargo run --bin exodus_test --release');
+        await utils.spawn(`cargo run --release --bin scenario_runner -- run ${scenarios}`);
     } else {
         await utils.spawn(`cargo run --bin ${command} --release`);
     }
 }
 
+export async function devnet(timeout: number) {
+    await run.devnet.resetDatabase();
+    await utils.spawn('cargo build --bin zksync_devnet --bin devnet_test --release');
+
+    const devnetLog = fs.openSync('devnet.log', 'w');
+    const devnet = utils.background(
+        `cargo run --bin zksync_devnet --release -- --database_url "${run.devnet.databaseUrl()}"`,
+        [0, devnetLog, devnetLog] // redirect stdout and stderr to devnet.log
+    );
+
+    const timer = setTimeout(() => {
+        console.log('Timeout reached!');
+        process.exit(1);
+    }, timeout * 1000);
+    timer.unref();
+
+    process.on('exit', (code) => {
+        // the devnet process is detached, so it's killed along with its children by group id
+        utils.allowFailSync(() => process.kill(-devnet.pid, 'SIGKILL'));
+        utils.allowFailSync(() => clearTimeout(timer));
+        if (code !== 0) {
+            console.log(fs.readFileSync('devnet.log').toString());
+        }
+    });
+
+    await utils.spawn('cargo run --bin devnet_test --release');
+    process.exit(0);
+}
+
 export async function rustSDK() {
     await utils.spawn('cargo test -p zksync --release -- --ignored --test-threads=1');
 }
@@ -243,6 +276,13 @@ command
         cmd.withServer ? await withServer(apiDocs, 240) : await apiDocs();
     });
 
+command
+    .command('devnet')
+    .description('run testkit devnet tests against the in-process devnet')
+    .action(async () => {
+        await devnet(1200);
+    });
+
 command
     .command('testkit [mode]')
     .description('run testkit tests')
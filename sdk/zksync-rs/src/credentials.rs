@@ -2,8 +2,24 @@ use crate::{error::ClientError, utils::private_key_from_seed};
 
 use web3::types::{Address, H256};
 use zksync_crypto::PrivateKey;
-use zksync_eth_signer::{EthereumSigner, PrivateKeySigner};
-use zksync_types::{network::Network, tx::TxEthSignature};
+use zksync_eth_signer::{
+    mnemonic::{derive_private_key, seed_from_mnemonic},
+    DerivationPath, EthereumSigner, Keystore, PrivateKeySigner,
+};
+use zksync_types::{
+    network::Network,
+    tx::{PackedEthSignature, TxEthSignature},
+};
+
+/// Returns the derivation path of the zkSync private key: `m/44'/60'/0'/1'/{account_index}'`.
+///
+/// Unlike the Ethereum path, all the indices are hardened, so the zkSync key can't be derived
+/// from the extended public key of the wallet.
+pub fn zksync_derivation_path(account_index: u32) -> Result<DerivationPath, ClientError> {
+    format!("m/44'/60'/0'/1'/{}'", account_index)
+        .parse()
+        .map_err(ClientError::SigningError)
+}
 
 pub struct WalletCredentials<S: EthereumSigner> {
     pub(crate) eth_signer: Option<S>,
@@ -41,7 +57,10 @@ impl<S: EthereumSigner> WalletCredentials<S> {
         let eth_sign_message = if let Network::Mainnet = network {
             MESSAGE.into()
         } else {
-            format!("{}\nChain ID: {}.", MESSAGE, network.chain_id())
+            let chain_id = network
+                .chain_id()
+                .ok_or_else(|| ClientError::NetworkNotSupported(network.to_string()))?;
+            format!("{}\nChain ID: {}.", MESSAGE, chain_id)
         }
         .into_bytes();
 
@@ -115,4 +134,63 @@ impl<S: EthereumSigner> WalletCredentials<S> {
             zksync_private_key: private_key,
         }
     }
+
+    /// Creates wallet credentials from the BIP-39 mnemonic phrase.
+    /// Ethereum private key is derived using the `m/44'/60'/0'/0/{account_index}` path (the same way
+    /// the most of the wallets do), and zkSync private key is derived using the `zksync_derivation_path`.
+    ///
+    /// ## Arguments
+    ///
+    /// - `phrase`: BIP-39 mnemonic phrase.
+    /// - `passphrase`: Optional BIP-39 passphrase, empty string if not used.
+    /// - `account_index`: Index of the account derived from the mnemonic.
+    pub fn from_mnemonic(
+        phrase: &str,
+        passphrase: &str,
+        account_index: u32,
+    ) -> Result<WalletCredentials<PrivateKeySigner>, ClientError> {
+        let seed = seed_from_mnemonic(phrase, passphrase).map_err(ClientError::SigningError)?;
+
+        let eth_private_key = derive_private_key(&seed, &DerivationPath::ethereum(account_index))
+            .map_err(ClientError::SigningError)?;
+        let eth_address = PackedEthSignature::address_from_private_key(&eth_private_key)
+            .map_err(|_| ClientError::IncorrectCredentials)?;
+
+        let zksync_seed = derive_private_key(&seed, &zksync_derivation_path(account_index)?)
+            .map_err(ClientError::SigningError)?;
+        let zksync_private_key = private_key_from_seed(zksync_seed.as_bytes())?;
+
+        Ok(WalletCredentials {
+            eth_address,
+            eth_signer: Some(PrivateKeySigner::new(eth_private_key)),
+            zksync_private_key,
+        })
+    }
+
+    /// Creates wallet credentials from the Ethereum private key stored in the Web3 Secret Storage keystore.
+    /// zkSync private key is obtained the same way as in `from_eth_signer`.
+    ///
+    /// ## Arguments
+    ///
+    /// - `keystore`: Encrypted keystore with the Ethereum private key.
+    /// - `password`: Password to decrypt the keystore.
+    /// - `network`: Network this wallet is used on.
+    pub async fn from_keystore(
+        keystore: &Keystore,
+        password: &str,
+        network: Network,
+    ) -> Result<WalletCredentials<PrivateKeySigner>, ClientError> {
+        let eth_private_key = keystore
+            .decrypt(password)
+            .map_err(ClientError::SigningError)?;
+        let eth_address = PackedEthSignature::address_from_private_key(&eth_private_key)
+            .map_err(|_| ClientError::IncorrectCredentials)?;
+
+        WalletCredentials::from_eth_signer(
+            eth_address,
+            PrivateKeySigner::new(eth_private_key),
+            network,
+        )
+        .await
+    }
 }
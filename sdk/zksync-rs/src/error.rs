@@ -35,6 +35,9 @@ pub enum ClientError {
     #[error("Ethereum private key was not provided for this wallet")]
     NoEthereumPrivateKey,
 
+    #[error("Provider doesn't support the swap order signatures")]
+    SwapsNotSupported,
+
     #[error("Provided value is not packable")]
     NotPackableValue,
 
@@ -71,6 +71,9 @@ impl<S: EthereumSigner> EthereumProvider<S> {
             .map_err(|err| ClientError::NetworkError(err.to_string()))?;
 
         let network = provider.network();
+        let chain_id = network
+            .chain_id()
+            .ok_or_else(|| ClientError::NetworkNotSupported(network.to_string()))?;
 
         let address_response = provider.contract_address().await?;
         let contract_address =
@@ -88,7 +91,7 @@ impl<S: EthereumSigner> EthereumProvider<S> {
             contract_address
                 .parse()
                 .map_err(|err| ClientError::MalformedResponse(format!("{}", err)))?,
-            network.chain_id(),
+            chain_id,
             1.5f64,
         );
         let erc20_abi = ierc20_contract();
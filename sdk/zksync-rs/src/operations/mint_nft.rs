@@ -3,12 +3,13 @@ use zksync_eth_signer::EthereumSigner;
 use zksync_types::{
     helpers::{closest_packable_fee_amount, is_fee_amount_packable},
     tokens::TxFeeTypes,
-    tx::PackedEthSignature,
+    tx::{PackedEthSignature, TxEthSignature},
     Address, Nonce, Token, TokenLike, ZkSyncTx, H256,
 };
 
 use crate::{
-    error::ClientError, operations::SyncTransactionHandle, provider::Provider, wallet::Wallet,
+    error::ClientError, operations::SyncTransactionHandle, provider::Provider,
+    signer::packed_eth_signature, wallet::Wallet,
 };
 
 #[derive(Debug)]
@@ -40,6 +41,14 @@ where
 
     /// Directly returns the signed mint nft transaction for the subsequent usage.
     pub async fn tx(self) -> Result<(ZkSyncTx, Option<PackedEthSignature>), ClientError> {
+        let (tx, eth_signature) = self.signed_tx().await?;
+        let eth_signature =
+            packed_eth_signature(eth_signature).map_err(ClientError::SigningError)?;
+        Ok((tx, eth_signature))
+    }
+
+    /// Same as `tx`, but returns any kind of Ethereum signature, including the EIP-712 one.
+    pub async fn signed_tx(self) -> Result<(ZkSyncTx, Option<TxEthSignature>), ClientError> {
         let recipient = self
             .recipient
             .ok_or_else(|| ClientError::MissingRequiredField("recipient".into()))?;
@@ -76,7 +85,7 @@ where
 
         self.wallet
             .signer
-            .sign_mint_nft(recipient, content_hash, fee_token, fee, nonce)
+            .sign_mint_nft_tx(recipient, content_hash, fee_token, fee, nonce)
             .await
             .map(|(tx, signature)| (ZkSyncTx::MintNFT(Box::new(tx)), signature))
             .map_err(ClientError::SigningError)
@@ -86,8 +95,8 @@ where
     pub async fn send(self) -> Result<SyncTransactionHandle<P>, ClientError> {
         let provider = self.wallet.provider.clone();
 
-        let (tx, eth_signature) = self.tx().await?;
-        let tx_hash = provider.send_tx(tx, eth_signature).await?;
+        let (tx, eth_signature) = self.signed_tx().await?;
+        let tx_hash = provider.submit_tx(tx, eth_signature).await?;
 
         Ok(SyncTransactionHandle::new(tx_hash, provider))
     }
@@ -11,12 +11,20 @@ use crate::{
 };
 
 pub use self::{
-    change_pubkey::ChangePubKeyBuilder, mint_nft::MintNFTBuilder, transfer::TransferBuilder,
-    transfer_nft::TransferNFTBuilder, withdraw::WithdrawBuilder, withdraw_nft::WithdrawNFTBuilder,
+    change_pubkey::ChangePubKeyBuilder,
+    forced_exit::ForcedExitBuilder,
+    mint_nft::MintNFTBuilder,
+    swap::{OrderBuilder, SwapBuilder},
+    transfer::TransferBuilder,
+    transfer_nft::TransferNFTBuilder,
+    withdraw::WithdrawBuilder,
+    withdraw_nft::WithdrawNFTBuilder,
 };
 
 mod change_pubkey;
+mod forced_exit;
 mod mint_nft;
+mod swap;
 mod transfer;
 mod transfer_nft;
 mod withdraw;
@@ -0,0 +1,342 @@
+use num::{BigUint, One, Zero};
+use zksync_eth_signer::EthereumSigner;
+use zksync_types::{
+    helpers::{
+        closest_packable_fee_amount, closest_packable_token_amount, is_fee_amount_packable,
+        is_token_amount_packable,
+    },
+    tx::{TimeRange, TxEthSignature, TxEthSignatureVariant},
+    Address, Nonce, Order, Token, TokenLike, TxFeeTypes, ZkSyncTx,
+};
+
+use crate::{
+    error::ClientError, operations::SyncTransactionHandle, provider::Provider, wallet::Wallet,
+};
+
+/// Builder for the limit orders which are filled by the `Swap` transaction.
+#[derive(Debug)]
+pub struct OrderBuilder<'a, S: EthereumSigner, P: Provider> {
+    wallet: &'a Wallet<S, P>,
+    recipient: Option<Address>,
+    token_sell: Option<Token>,
+    token_buy: Option<Token>,
+    ratio: Option<(BigUint, BigUint)>,
+    amount: Option<BigUint>,
+    nonce: Option<Nonce>,
+    valid_from: Option<u64>,
+    valid_until: Option<u64>,
+}
+
+impl<'a, S, P> OrderBuilder<'a, S, P>
+where
+    S: EthereumSigner,
+    P: Provider + Clone,
+{
+    /// Initializes an order building process.
+    pub fn new(wallet: &'a Wallet<S, P>) -> Self {
+        Self {
+            wallet,
+            recipient: None,
+            token_sell: None,
+            token_buy: None,
+            ratio: None,
+            amount: None,
+            nonce: None,
+            valid_from: None,
+            valid_until: None,
+        }
+    }
+
+    /// Returns the signed order along with its Ethereum signature.
+    /// The order itself can't be sent to the network, it should be used to build a `Swap` transaction.
+    pub async fn order(self) -> Result<(Order, Option<TxEthSignature>), ClientError> {
+        let token_sell = self
+            .token_sell
+            .ok_or_else(|| ClientError::MissingRequiredField("token_sell".into()))?;
+        let token_buy = self
+            .token_buy
+            .ok_or_else(|| ClientError::MissingRequiredField("token_buy".into()))?;
+        let amount = self
+            .amount
+            .ok_or_else(|| ClientError::MissingRequiredField("amount".into()))?;
+        let ratio = self
+            .ratio
+            .unwrap_or_else(|| (BigUint::one(), BigUint::one()));
+        let recipient = self.recipient.unwrap_or_else(|| self.wallet.address());
+
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                let account_info = self
+                    .wallet
+                    .provider
+                    .account_info(self.wallet.address())
+                    .await?;
+                account_info.committed.nonce
+            }
+        };
+
+        let valid_from = self.valid_from.unwrap_or(0);
+        let valid_until = self.valid_until.unwrap_or(u64::MAX);
+
+        self.wallet
+            .signer
+            .sign_order(
+                recipient,
+                nonce,
+                &token_sell,
+                &token_buy,
+                ratio,
+                amount,
+                TimeRange::new(valid_from, valid_until),
+            )
+            .await
+            .map_err(ClientError::SigningError)
+    }
+
+    /// Sets the token to be sold.
+    /// Returns an error if token is not supported by zkSync.
+    pub fn token_sell(mut self, token: impl Into<TokenLike>) -> Result<Self, ClientError> {
+        self.token_sell = Some(self.resolve_token(token)?);
+        Ok(self)
+    }
+
+    /// Sets the token to be bought.
+    /// Returns an error if token is not supported by zkSync.
+    pub fn token_buy(mut self, token: impl Into<TokenLike>) -> Result<Self, ClientError> {
+        self.token_buy = Some(self.resolve_token(token)?);
+        Ok(self)
+    }
+
+    /// Sets the price of the order as the ratio between the sold and the bought amounts.
+    /// If not set, the `1:1` ratio is used.
+    pub fn ratio(mut self, sell: impl Into<BigUint>, buy: impl Into<BigUint>) -> Self {
+        self.ratio = Some((sell.into(), buy.into()));
+        self
+    }
+
+    /// Sets the amount of the sold token. Zero amount means that the order
+    /// can be filled by any amount. If the amount provided is not packable,
+    /// rounds it to the closest packable token amount.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn amount(mut self, amount: impl Into<BigUint>) -> Self {
+        let amount = closest_packable_token_amount(&amount.into());
+        self.amount = Some(amount);
+
+        self
+    }
+
+    /// Sets the amount of the sold token. If the provided amount is not packable,
+    /// returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn amount_exact(mut self, amount: impl Into<BigUint>) -> Result<Self, ClientError> {
+        let amount = amount.into();
+        if !is_token_amount_packable(&amount) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.amount = Some(amount);
+
+        Ok(self)
+    }
+
+    /// Sets the address of the bought tokens receiver.
+    /// If not set, the tokens are received by the wallet itself.
+    pub fn recipient(mut self, recipient: Address) -> Self {
+        self.recipient = Some(recipient);
+        self
+    }
+
+    /// Sets the order nonce. It must be equal to the account nonce at the moment of the swap execution.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Sets the unix format timestamp of the first moment when the order can be filled.
+    pub fn valid_from(mut self, valid_from: u64) -> Self {
+        self.valid_from = Some(valid_from);
+        self
+    }
+
+    /// Sets the unix format timestamp of the last moment when the order can be filled.
+    pub fn valid_until(mut self, valid_until: u64) -> Self {
+        self.valid_until = Some(valid_until);
+        self
+    }
+
+    fn resolve_token(&self, token: impl Into<TokenLike>) -> Result<Token, ClientError> {
+        self.wallet
+            .tokens
+            .resolve(token.into())
+            .ok_or(ClientError::UnknownToken)
+    }
+}
+
+#[derive(Debug)]
+pub struct SwapBuilder<'a, S: EthereumSigner, P: Provider> {
+    wallet: &'a Wallet<S, P>,
+    orders: Option<(Order, Order)>,
+    order_signatures: (Option<TxEthSignature>, Option<TxEthSignature>),
+    amounts: Option<(BigUint, BigUint)>,
+    fee_token: Option<Token>,
+    fee: Option<BigUint>,
+    nonce: Option<Nonce>,
+}
+
+impl<'a, S, P> SwapBuilder<'a, S, P>
+where
+    S: EthereumSigner,
+    P: Provider + Clone,
+{
+    /// Initializes a swap transaction building process.
+    pub fn new(wallet: &'a Wallet<S, P>) -> Self {
+        Self {
+            wallet,
+            orders: None,
+            order_signatures: (None, None),
+            amounts: None,
+            fee_token: None,
+            fee: None,
+            nonce: None,
+        }
+    }
+
+    /// Directly returns the signed swap transaction for the subsequent usage.
+    /// Besides the signature of the swap itself, the Ethereum signatures of both orders are returned.
+    pub async fn tx(self) -> Result<(ZkSyncTx, TxEthSignatureVariant), ClientError> {
+        let orders = self
+            .orders
+            .ok_or_else(|| ClientError::MissingRequiredField("orders".into()))?;
+        let fee_token = self
+            .fee_token
+            .ok_or_else(|| ClientError::MissingRequiredField("fee_token".into()))?;
+        // By default, the orders are filled by their full amounts.
+        let amounts = self
+            .amounts
+            .unwrap_or_else(|| (orders.0.amount.clone(), orders.1.amount.clone()));
+        if amounts.0.is_zero() || amounts.1.is_zero() {
+            return Err(ClientError::MissingRequiredField("amounts".into()));
+        }
+
+        let fee = match self.fee {
+            Some(fee) => fee,
+            None => {
+                let fee = self
+                    .wallet
+                    .provider
+                    .get_tx_fee(TxFeeTypes::Swap, self.wallet.address(), fee_token.id)
+                    .await?;
+                fee.total_fee
+            }
+        };
+
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                let account_info = self
+                    .wallet
+                    .provider
+                    .account_info(self.wallet.address())
+                    .await?;
+                account_info.committed.nonce
+            }
+        };
+
+        let (swap, swap_signature) = self
+            .wallet
+            .signer
+            .sign_swap(orders, amounts, fee_token, fee, nonce)
+            .await
+            .map_err(ClientError::SigningError)?;
+
+        let (order0_signature, order1_signature) = self.order_signatures;
+        Ok((
+            ZkSyncTx::Swap(Box::new(swap)),
+            TxEthSignatureVariant::Triple(swap_signature, order0_signature, order1_signature),
+        ))
+    }
+
+    /// Sends the transaction, returning the handle for its awaiting.
+    pub async fn send(self) -> Result<SyncTransactionHandle<P>, ClientError> {
+        let provider = self.wallet.provider.clone();
+
+        let (tx, eth_signatures) = self.tx().await?;
+        let tx_hash = provider.send_swap(tx, eth_signatures).await?;
+
+        Ok(SyncTransactionHandle::new(tx_hash, provider))
+    }
+
+    /// Sets the orders to be filled along with their Ethereum signatures.
+    pub fn orders(
+        mut self,
+        orders: (Order, Order),
+        signatures: (Option<TxEthSignature>, Option<TxEthSignature>),
+    ) -> Self {
+        self.orders = Some(orders);
+        self.order_signatures = signatures;
+        self
+    }
+
+    /// Sets the amounts of tokens sold by the first and the second orders respectively.
+    /// If not set, the orders are filled by their full amounts.
+    /// Returns an error if any of the amounts is not packable.
+    pub fn amounts(
+        mut self,
+        amounts: (impl Into<BigUint>, impl Into<BigUint>),
+    ) -> Result<Self, ClientError> {
+        let amounts = (amounts.0.into(), amounts.1.into());
+        if !is_token_amount_packable(&amounts.0) || !is_token_amount_packable(&amounts.1) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.amounts = Some(amounts);
+
+        Ok(self)
+    }
+
+    /// Sets the transaction fee token. Returns an error if token is not supported by zkSync.
+    pub fn fee_token(mut self, token: impl Into<TokenLike>) -> Result<Self, ClientError> {
+        let token_like = token.into();
+        let token = self
+            .wallet
+            .tokens
+            .resolve(token_like)
+            .ok_or(ClientError::UnknownToken)?;
+
+        self.fee_token = Some(token);
+
+        Ok(self)
+    }
+
+    /// Set the fee amount. If the amount provided is not packable,
+    /// rounds it to the closest packable fee amount.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee(mut self, fee: impl Into<BigUint>) -> Self {
+        let fee = closest_packable_fee_amount(&fee.into());
+        self.fee = Some(fee);
+
+        self
+    }
+
+    /// Set the fee amount. If the provided fee is not packable,
+    /// returns an error.
+    ///
+    /// For more details, see [utils](../utils/index.html) functions.
+    pub fn fee_exact(mut self, fee: impl Into<BigUint>) -> Result<Self, ClientError> {
+        let fee = fee.into();
+        if !is_fee_amount_packable(&fee) {
+            return Err(ClientError::NotPackableValue);
+        }
+        self.fee = Some(fee);
+
+        Ok(self)
+    }
+
+    /// Sets the transaction nonce.
+    pub fn nonce(mut self, nonce: Nonce) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+}
@@ -5,12 +5,13 @@ use zksync_types::{
         closest_packable_fee_amount, closest_packable_token_amount, is_fee_amount_packable,
         is_token_amount_packable,
     },
-    tx::PackedEthSignature,
+    tx::{PackedEthSignature, TxEthSignature},
     Address, Nonce, Token, TokenLike, TxFeeTypes, ZkSyncTx,
 };
 
 use crate::{
-    error::ClientError, operations::SyncTransactionHandle, provider::Provider, wallet::Wallet,
+    error::ClientError, operations::SyncTransactionHandle, provider::Provider,
+    signer::packed_eth_signature, wallet::Wallet,
 };
 use zksync_types::tx::TimeRange;
 
@@ -47,6 +48,14 @@ where
 
     /// Directly returns the signed transfer transaction for the subsequent usage.
     pub async fn tx(self) -> Result<(ZkSyncTx, Option<PackedEthSignature>), ClientError> {
+        let (tx, eth_signature) = self.signed_tx().await?;
+        let eth_signature =
+            packed_eth_signature(eth_signature).map_err(ClientError::SigningError)?;
+        Ok((tx, eth_signature))
+    }
+
+    /// Same as `tx`, but returns any kind of Ethereum signature, including the EIP-712 one.
+    pub async fn signed_tx(self) -> Result<(ZkSyncTx, Option<TxEthSignature>), ClientError> {
         let token = self
             .token
             .ok_or_else(|| ClientError::MissingRequiredField("token".into()))?;
@@ -85,7 +94,7 @@ where
 
         self.wallet
             .signer
-            .sign_transfer(
+            .sign_transfer_tx(
                 token,
                 amount,
                 fee,
@@ -102,8 +111,8 @@ where
     pub async fn send(self) -> Result<SyncTransactionHandle<P>, ClientError> {
         let provider = self.wallet.provider.clone();
 
-        let (tx, eth_signature) = self.tx().await?;
-        let tx_hash = provider.send_tx(tx, eth_signature).await?;
+        let (tx, eth_signature) = self.signed_tx().await?;
+        let tx_hash = provider.submit_tx(tx, eth_signature).await?;
 
         Ok(SyncTransactionHandle::new(tx_hash, provider))
     }
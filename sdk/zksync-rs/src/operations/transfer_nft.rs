@@ -2,13 +2,13 @@ use num::BigUint;
 use zksync_eth_signer::EthereumSigner;
 use zksync_types::{
     helpers::{closest_packable_fee_amount, is_fee_amount_packable},
-    tx::PackedEthSignature,
+    tx::{PackedEthSignature, TxEthSignature},
     Address, Nonce, Token, TokenLike, TxFeeTypes, ZkSyncTx,
 };
 
 use crate::{
-    error::ClientError, operations::SyncTransactionHandle, provider::Provider, types::NFT,
-    wallet::Wallet,
+    error::ClientError, operations::SyncTransactionHandle, provider::Provider,
+    signer::packed_eth_signature, types::NFT, wallet::Wallet,
 };
 use zksync_types::tx::TimeRange;
 
@@ -52,6 +52,24 @@ where
             (ZkSyncTx, Option<PackedEthSignature>),
         ),
         ClientError,
+    > {
+        let ((tx_nft, tx_nft_signature), (tx_fee, tx_fee_signature)) = self.signed_txs().await?;
+        let tx_nft_signature =
+            packed_eth_signature(tx_nft_signature).map_err(ClientError::SigningError)?;
+        let tx_fee_signature =
+            packed_eth_signature(tx_fee_signature).map_err(ClientError::SigningError)?;
+        Ok(((tx_nft, tx_nft_signature), (tx_fee, tx_fee_signature)))
+    }
+
+    /// Same as `tx`, but returns any kind of Ethereum signatures, including the EIP-712 ones.
+    pub async fn signed_txs(
+        self,
+    ) -> Result<
+        (
+            (ZkSyncTx, Option<TxEthSignature>),
+            (ZkSyncTx, Option<TxEthSignature>),
+        ),
+        ClientError,
     > {
         let nft = self
             .nft
@@ -97,7 +115,7 @@ where
         let (tx_nft, tx_nft_signature) = self
             .wallet
             .signer
-            .sign_transfer(
+            .sign_transfer_tx(
                 nft_token,
                 BigUint::from(1u16),
                 BigUint::from(0u16),
@@ -111,7 +129,7 @@ where
         let (tx_fee, tx_fee_signature) = self
             .wallet
             .signer
-            .sign_transfer(
+            .sign_transfer_tx(
                 fee_token,
                 BigUint::from(0u16),
                 fee,
@@ -130,9 +148,11 @@ where
     pub async fn send(self) -> Result<Vec<SyncTransactionHandle<P>>, ClientError> {
         let provider = self.wallet.provider.clone();
 
-        let (tx_nft, tx_fee) = self.tx().await?;
+        let (tx_nft, tx_fee) = self.signed_txs().await?;
         println!("tx is got");
-        let tx_hashes = provider.send_txs_batch(vec![tx_nft, tx_fee], None).await?;
+        let tx_hashes = provider
+            .submit_txs_batch(vec![tx_nft, tx_fee], None)
+            .await?;
 
         Ok(tx_hashes
             .into_iter()
@@ -3,12 +3,13 @@ use zksync_crypto::params::MIN_NFT_TOKEN_ID;
 use zksync_eth_signer::EthereumSigner;
 use zksync_types::{
     helpers::{closest_packable_fee_amount, is_fee_amount_packable},
-    tx::{PackedEthSignature, TimeRange},
+    tx::{PackedEthSignature, TimeRange, TxEthSignature},
     Address, Nonce, Token, TokenId, TokenLike, TxFeeTypes, ZkSyncTx,
 };
 
 use crate::{
-    error::ClientError, operations::SyncTransactionHandle, provider::Provider, wallet::Wallet,
+    error::ClientError, operations::SyncTransactionHandle, provider::Provider,
+    signer::packed_eth_signature, wallet::Wallet,
 };
 
 #[derive(Debug)]
@@ -44,6 +45,14 @@ where
 
     /// Directly returns the signed withdraw transaction for the subsequent usage.
     pub async fn tx(self) -> Result<(ZkSyncTx, Option<PackedEthSignature>), ClientError> {
+        let (tx, eth_signature) = self.signed_tx().await?;
+        let eth_signature =
+            packed_eth_signature(eth_signature).map_err(ClientError::SigningError)?;
+        Ok((tx, eth_signature))
+    }
+
+    /// Same as `tx`, but returns any kind of Ethereum signature, including the EIP-712 one.
+    pub async fn signed_tx(self) -> Result<(ZkSyncTx, Option<TxEthSignature>), ClientError> {
         let to = self
             .to
             .ok_or_else(|| ClientError::MissingRequiredField("to".into()))?;
@@ -83,7 +92,7 @@ where
 
         self.wallet
             .signer
-            .sign_withdraw_nft(
+            .sign_withdraw_nft_tx(
                 to,
                 token,
                 fee_token,
@@ -100,8 +109,8 @@ where
     pub async fn send(self) -> Result<SyncTransactionHandle<P>, ClientError> {
         let provider = self.wallet.provider.clone();
 
-        let (tx, eth_signature) = self.tx().await?;
-        let tx_hash = provider.send_tx(tx, eth_signature).await?;
+        let (tx, eth_signature) = self.signed_tx().await?;
+        let tx_hash = provider.submit_tx(tx, eth_signature).await?;
 
         Ok(SyncTransactionHandle::new(tx_hash, provider))
     }
@@ -12,7 +12,7 @@ use num::BigUint;
 // Workspace uses
 use zksync_types::{
     network::Network,
-    tx::{PackedEthSignature, TxHash, ZkSyncTx},
+    tx::{PackedEthSignature, TxEthSignature, TxEthSignatureVariant, TxHash, ZkSyncTx},
     Address, TokenLike, TxFeeTypes,
 };
 
@@ -21,14 +21,17 @@ use self::messages::JsonRpcRequest;
 use crate::{error::ClientError, types::*};
 
 /// Returns a corresponding address for a provided network name.
-pub fn get_rpc_addr(network: Network) -> &'static str {
+/// Custom, unknown and test networks have no well-known address, so the provider
+/// for them has to be created with an explicit address, e.g. via `RpcProvider::from_addr_and_network`.
+pub fn get_rpc_addr(network: Network) -> Result<&'static str, ClientError> {
     match network {
-        Network::Mainnet => "https://api.zksync.io/jsrpc",
-        Network::Rinkeby => "https://rinkeby-api.zksync.io/jsrpc",
-        Network::Ropsten => "https://ropsten-api.zksync.io/jsrpc",
-        Network::Localhost => "http://127.0.0.1:3030",
-        Network::Unknown => panic!("Attempt to create a provider from an unknown network"),
-        Network::Test => panic!("Attempt to create a provider from an test network"),
+        Network::Mainnet => Ok("https://api.zksync.io/jsrpc"),
+        Network::Rinkeby => Ok("https://rinkeby-api.zksync.io/jsrpc"),
+        Network::Ropsten => Ok("https://ropsten-api.zksync.io/jsrpc"),
+        Network::Localhost => Ok("http://127.0.0.1:3030"),
+        Network::Custom(_) | Network::Unknown | Network::Test => {
+            Err(ClientError::NetworkNotSupported(network.to_string()))
+        }
     }
 }
 
@@ -82,14 +85,61 @@ pub trait Provider {
         &self,
         tx: ZkSyncTx,
         eth_signature: Option<PackedEthSignature>,
+    ) -> ResponseResult<TxHash> {
+        self.submit_tx(tx, eth_signature.map(TxEthSignature::EthereumSignature))
+            .await
+    }
+
+    /// Submits a transaction signed with any kind of Ethereum signature to the zkSync network.
+    /// Returns the hash of the created transaction.
+    async fn submit_tx(
+        &self,
+        tx: ZkSyncTx,
+        eth_signature: Option<TxEthSignature>,
     ) -> ResponseResult<TxHash>;
 
+    /// Submits a swap to the zkSync network. Besides the signature of the swap itself,
+    /// the Ethereum signatures of both orders are sent.
+    /// Returns the hash of the created transaction.
+    ///
+    /// By default only a single signature is supported and is sent via `submit_tx`,
+    /// providers which can send the signatures of the orders must override this method.
+    async fn send_swap(
+        &self,
+        tx: ZkSyncTx,
+        eth_signatures: TxEthSignatureVariant,
+    ) -> ResponseResult<TxHash> {
+        match eth_signatures {
+            TxEthSignatureVariant::Single(eth_signature) => self.submit_tx(tx, eth_signature).await,
+            TxEthSignatureVariant::Triple(..) => Err(ClientError::SwapsNotSupported),
+        }
+    }
+
     /// Submits a batch of transactions to the zkSync network.
     /// Returns the hashes of the created transactions.
     async fn send_txs_batch(
         &self,
         txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
         eth_signature: Option<PackedEthSignature>,
+    ) -> ResponseResult<Vec<TxHash>> {
+        let txs_signed = txs_signed
+            .into_iter()
+            .map(|(tx, eth_signature)| (tx, eth_signature.map(TxEthSignature::EthereumSignature)))
+            .collect();
+        self.submit_txs_batch(
+            txs_signed,
+            eth_signature.map(TxEthSignature::EthereumSignature),
+        )
+        .await
+    }
+
+    /// Submits a batch of transactions signed with any kind of Ethereum signatures
+    /// to the zkSync network.
+    /// Returns the hashes of the created transactions.
+    async fn submit_txs_batch(
+        &self,
+        txs_signed: Vec<(ZkSyncTx, Option<TxEthSignature>)>,
+        eth_signature: Option<TxEthSignature>,
     ) -> ResponseResult<Vec<TxHash>>;
 
     /// Type of network this provider is allowing access to.
@@ -163,19 +213,28 @@ impl Provider for RpcProvider {
         self.send_and_deserialize(&msg).await
     }
 
-    async fn send_tx(
+    async fn submit_tx(
         &self,
         tx: ZkSyncTx,
-        eth_signature: Option<PackedEthSignature>,
+        eth_signature: Option<TxEthSignature>,
     ) -> ResponseResult<TxHash> {
         let msg = JsonRpcRequest::submit_tx(tx, eth_signature);
         self.send_and_deserialize(&msg).await
     }
 
-    async fn send_txs_batch(
+    async fn send_swap(
         &self,
-        txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
-        eth_signature: Option<PackedEthSignature>,
+        tx: ZkSyncTx,
+        eth_signatures: TxEthSignatureVariant,
+    ) -> ResponseResult<TxHash> {
+        let msg = JsonRpcRequest::submit_swap(tx, eth_signatures);
+        self.send_and_deserialize(&msg).await
+    }
+
+    async fn submit_txs_batch(
+        &self,
+        txs_signed: Vec<(ZkSyncTx, Option<TxEthSignature>)>,
+        eth_signature: Option<TxEthSignature>,
     ) -> ResponseResult<Vec<TxHash>> {
         let msg = JsonRpcRequest::submit_tx_batch(txs_signed, eth_signature);
         self.send_and_deserialize(&msg).await
@@ -188,12 +247,13 @@ impl Provider for RpcProvider {
 
 impl RpcProvider {
     /// Creates a new `RpcProvider` connected to the desired zkSync network.
-    pub fn new(network: Network) -> Self {
-        Self {
-            rpc_addr: get_rpc_addr(network).into(),
+    /// Returns an error if the network has no well-known address.
+    pub fn new(network: Network) -> Result<Self, ClientError> {
+        Ok(Self {
+            rpc_addr: get_rpc_addr(network)?.into(),
             client: reqwest::Client::new(),
             network,
-        }
+        })
     }
 
     /// Creates a new `Provider` object connected to a custom address.
@@ -214,6 +274,12 @@ impl RpcProvider {
         }
     }
 
+    /// Creates a new `Provider` object connected to a custom address and the network
+    /// with the given Ethereum chain ID.
+    pub fn from_addr_and_chain_id(rpc_addr: impl Into<String>, chain_id: u64) -> Self {
+        Self::from_addr_and_network(rpc_addr, Network::from_chain_id(chain_id))
+    }
+
     /// Submits a batch transaction to the zkSync network.
     /// Returns the hashes of the created transactions.
     pub async fn send_txs_batch(
@@ -221,8 +287,7 @@ impl RpcProvider {
         txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
         eth_signature: Option<PackedEthSignature>,
     ) -> Result<Vec<TxHash>, ClientError> {
-        let msg = JsonRpcRequest::submit_tx_batch(txs_signed, eth_signature);
-        self.send_and_deserialize(&msg).await
+        Provider::send_txs_batch(self, txs_signed, eth_signature).await
     }
 
     /// Requests and returns information about an Ethereum operation given its `serial_id`.
@@ -322,7 +387,7 @@ impl RpcProvider {
 mod messages {
     use serde::Serialize;
     use zksync_types::{
-        tx::{PackedEthSignature, TxEthSignature, TxHash, ZkSyncTx},
+        tx::{PackedEthSignature, TxEthSignature, TxEthSignatureVariant, TxHash, ZkSyncTx},
         Address, TokenLike, TxFeeTypes,
     };
 
@@ -366,27 +431,33 @@ mod messages {
             Self::create("account_info", json_values![address])
         }
 
-        pub fn submit_tx(tx: ZkSyncTx, eth_signature: Option<PackedEthSignature>) -> Self {
-            let params = json_values![tx, eth_signature.map(TxEthSignature::EthereumSignature)];
+        pub fn submit_tx(tx: ZkSyncTx, eth_signature: Option<TxEthSignature>) -> Self {
+            let params = json_values![tx, eth_signature];
+            Self::create("tx_submit", params)
+        }
+
+        pub fn submit_swap(tx: ZkSyncTx, eth_signatures: TxEthSignatureVariant) -> Self {
+            let params = json_values![tx, eth_signatures];
             Self::create("tx_submit", params)
         }
 
         pub fn submit_tx_batch(
-            txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
-            eth_signature: Option<PackedEthSignature>,
+            txs_signed: Vec<(ZkSyncTx, Option<TxEthSignature>)>,
+            eth_signature: Option<TxEthSignature>,
         ) -> Self {
             let mut params = Vec::with_capacity(2);
 
-            let txs_signed = txs_signed.into_iter().map(|(tx, eth_signature)| {
-                serde_json::json!({
-                    "tx": to_json_value(tx),
-                    "signature": to_json_value(eth_signature.map(TxEthSignature::EthereumSignature)),
+            let txs_signed = txs_signed
+                .into_iter()
+                .map(|(tx, eth_signature)| {
+                    serde_json::json!({
+                        "tx": to_json_value(tx),
+                        "signature": to_json_value(eth_signature),
+                    })
                 })
-            }).collect();
+                .collect();
             params.push(serde_json::Value::Array(txs_signed));
-            params.push(to_json_value(
-                eth_signature.map(TxEthSignature::EthereumSignature),
-            ));
+            params.push(to_json_value(eth_signature));
 
             Self::create("submit_txs_batch", params)
         }
@@ -7,11 +7,11 @@ use zksync_crypto::PrivateKey;
 use zksync_eth_signer::{error::SignerError, EthereumSigner};
 use zksync_types::{
     tx::{
-        ChangePubKey, ChangePubKeyECDSAData, ChangePubKeyEthAuthData, PackedEthSignature,
-        TimeRange, TxEthSignature,
+        ChangePubKey, ChangePubKeyECDSAData, ChangePubKeyEthAuthData, Eip712Domain,
+        Eip712TypedStructure, PackedEthSignature, TimeRange, TxEthSignature,
     },
-    AccountId, Address, ForcedExit, MintNFT, Nonce, PubKeyHash, Token, TokenId, Transfer, Withdraw,
-    WithdrawNFT, H256,
+    AccountId, Address, ForcedExit, MintNFT, Nonce, Order, PubKeyHash, Swap, Token, TokenId,
+    Transfer, Withdraw, WithdrawNFT, H256,
 };
 // Local imports
 use crate::WalletCredentials;
@@ -20,12 +20,29 @@ fn signing_failed_error(err: impl ToString) -> SignerError {
     SignerError::SigningFailed(err.to_string())
 }
 
+/// Converts the signature into the plain Ethereum one, which is the only kind
+/// supported by the methods returning `PackedEthSignature`.
+pub(crate) fn packed_eth_signature(
+    signature: Option<TxEthSignature>,
+) -> Result<Option<PackedEthSignature>, SignerError> {
+    match signature {
+        None => Ok(None),
+        Some(TxEthSignature::EthereumSignature(signature)) => Ok(Some(signature)),
+        Some(_) => Err(SignerError::CustomError(
+            "Signature can't be represented as a plain Ethereum signature".to_string(),
+        )),
+    }
+}
+
 pub struct Signer<S: EthereumSigner> {
     pub pubkey_hash: PubKeyHash,
     pub address: Address,
     pub(crate) private_key: PrivateKey,
     pub(crate) eth_signer: Option<S>,
     pub(crate) account_id: Option<AccountId>,
+    /// Domain of the EIP-712 typed data. If set, transactions are signed as typed data
+    /// instead of the human-readable messages.
+    pub(crate) eip712_domain: Option<Eip712Domain>,
 }
 
 impl<S: EthereumSigner> fmt::Debug for Signer<S> {
@@ -51,6 +68,7 @@ impl<S: EthereumSigner> Signer<S> {
             address,
             eth_signer,
             account_id: None,
+            eip712_domain: None,
         }
     }
 
@@ -75,6 +93,36 @@ impl<S: EthereumSigner> Signer<S> {
         self.account_id
     }
 
+    pub fn set_eip712_domain(&mut self, eip712_domain: Option<Eip712Domain>) {
+        self.eip712_domain = eip712_domain;
+    }
+
+    pub fn get_eip712_domain(&self) -> Option<Eip712Domain> {
+        self.eip712_domain
+    }
+
+    /// Signs the transaction with the Ethereum signer, if there is one.
+    /// The typed data is signed if the EIP-712 domain is set, otherwise the `message` is signed.
+    async fn sign_eth_data<T: Eip712TypedStructure>(
+        &self,
+        tx: &T,
+        message: impl FnOnce() -> String,
+    ) -> Result<Option<TxEthSignature>, SignerError> {
+        let signer = match &self.eth_signer {
+            Some(signer) => signer,
+            None => return Ok(None),
+        };
+
+        let signature = match &self.eip712_domain {
+            Some(domain) => {
+                let typed_data = tx.typed_data(domain).map_err(signing_failed_error)?;
+                signer.sign_typed_data(&typed_data).await?
+            }
+            None => signer.sign_message(message().as_bytes()).await?,
+        };
+        Ok(Some(signature))
+    }
+
     pub async fn sign_change_pubkey_tx(
         &self,
         nonce: Nonce,
@@ -116,9 +164,13 @@ impl<S: EthereumSigner> Signer<S> {
 
             let eth_signature = match eth_signature {
                 TxEthSignature::EthereumSignature(packed_signature) => Ok(packed_signature),
-                TxEthSignature::EIP1271Signature(..) => Err(SignerError::CustomError(
+                TxEthSignature::EIP1271Signature(..)
+                | TxEthSignature::EIP712EIP1271Signature(..) => Err(SignerError::CustomError(
                     "Can't sign ChangePubKey message with EIP1271 signer".to_string(),
                 )),
+                TxEthSignature::EIP712Signature(..) => Err(SignerError::CustomError(
+                    "ChangePubKey message can't be signed as typed data".to_string(),
+                )),
             }?;
 
             ChangePubKeyEthAuthData::ECDSA(ChangePubKeyECDSAData {
@@ -136,6 +188,8 @@ impl<S: EthereumSigner> Signer<S> {
         Ok(change_pubkey)
     }
 
+    /// Signs the transfer with the human-readable Ethereum message.
+    /// Fails if the EIP-712 domain is set, use `sign_transfer_tx` to get the typed data signature.
     #[allow(clippy::too_many_arguments)]
     pub async fn sign_transfer(
         &self,
@@ -146,6 +200,24 @@ impl<S: EthereumSigner> Signer<S> {
         nonce: Nonce,
         time_range: TimeRange,
     ) -> Result<(Transfer, Option<PackedEthSignature>), SignerError> {
+        let (tx, eth_signature) = self
+            .sign_transfer_tx(token, amount, fee, to, nonce, time_range)
+            .await?;
+        Ok((tx, packed_eth_signature(eth_signature)?))
+    }
+
+    /// Signs the transfer. The Ethereum signature is made for the EIP-712 typed data
+    /// if the domain is set, and for the human-readable message otherwise.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn sign_transfer_tx(
+        &self,
+        token: Token,
+        amount: BigUint,
+        fee: BigUint,
+        to: Address,
+        nonce: Nonce,
+        time_range: TimeRange,
+    ) -> Result<(Transfer, Option<TxEthSignature>), SignerError> {
         let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
 
         let transfer = Transfer::new_signed(
@@ -161,23 +233,17 @@ impl<S: EthereumSigner> Signer<S> {
         )
         .map_err(signing_failed_error)?;
 
-        let eth_signature = match &self.eth_signer {
-            Some(signer) => {
-                let message = transfer.get_ethereum_sign_message(&token.symbol, token.decimals);
-                let signature = signer.sign_message(message.as_bytes()).await?;
-
-                if let TxEthSignature::EthereumSignature(packed_signature) = signature {
-                    Some(packed_signature)
-                } else {
-                    return Err(SignerError::MissingEthSigner);
-                }
-            }
-            _ => None,
-        };
+        let eth_signature = self
+            .sign_eth_data(&transfer, || {
+                transfer.get_ethereum_sign_message(&token.symbol, token.decimals)
+            })
+            .await?;
 
         Ok((transfer, eth_signature))
     }
 
+    /// Signs the withdrawal with the human-readable Ethereum message.
+    /// Fails if the EIP-712 domain is set, use `sign_withdraw_tx` to get the typed data signature.
     pub async fn sign_withdraw(
         &self,
         token: Token,
@@ -187,6 +253,23 @@ impl<S: EthereumSigner> Signer<S> {
         nonce: Nonce,
         time_range: TimeRange,
     ) -> Result<(Withdraw, Option<PackedEthSignature>), SignerError> {
+        let (tx, eth_signature) = self
+            .sign_withdraw_tx(token, amount, fee, eth_address, nonce, time_range)
+            .await?;
+        Ok((tx, packed_eth_signature(eth_signature)?))
+    }
+
+    /// Signs the withdrawal. The Ethereum signature is made for the EIP-712 typed data
+    /// if the domain is set, and for the human-readable message otherwise.
+    pub async fn sign_withdraw_tx(
+        &self,
+        token: Token,
+        amount: BigUint,
+        fee: BigUint,
+        eth_address: Address,
+        nonce: Nonce,
+        time_range: TimeRange,
+    ) -> Result<(Withdraw, Option<TxEthSignature>), SignerError> {
         let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
 
         let withdraw = Withdraw::new_signed(
@@ -202,23 +285,17 @@ impl<S: EthereumSigner> Signer<S> {
         )
         .map_err(signing_failed_error)?;
 
-        let eth_signature = match &self.eth_signer {
-            Some(signer) => {
-                let message = withdraw.get_ethereum_sign_message(&token.symbol, token.decimals);
-                let signature = signer.sign_message(message.as_bytes()).await?;
-
-                if let TxEthSignature::EthereumSignature(packed_signature) = signature {
-                    Some(packed_signature)
-                } else {
-                    return Err(SignerError::MissingEthSigner);
-                }
-            }
-            _ => None,
-        };
+        let eth_signature = self
+            .sign_eth_data(&withdraw, || {
+                withdraw.get_ethereum_sign_message(&token.symbol, token.decimals)
+            })
+            .await?;
 
         Ok((withdraw, eth_signature))
     }
 
+    /// Signs the forced exit with the human-readable Ethereum message.
+    /// Fails if the EIP-712 domain is set, use `sign_forced_exit_tx` to get the typed data signature.
     pub async fn sign_forced_exit(
         &self,
         target: Address,
@@ -227,6 +304,22 @@ impl<S: EthereumSigner> Signer<S> {
         nonce: Nonce,
         time_range: TimeRange,
     ) -> Result<(ForcedExit, Option<PackedEthSignature>), SignerError> {
+        let (tx, eth_signature) = self
+            .sign_forced_exit_tx(target, token, fee, nonce, time_range)
+            .await?;
+        Ok((tx, packed_eth_signature(eth_signature)?))
+    }
+
+    /// Signs the forced exit. The Ethereum signature is made for the EIP-712 typed data
+    /// if the domain is set, and for the human-readable message otherwise.
+    pub async fn sign_forced_exit_tx(
+        &self,
+        target: Address,
+        token: Token,
+        fee: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+    ) -> Result<(ForcedExit, Option<TxEthSignature>), SignerError> {
         let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
 
         let forced_exit = ForcedExit::new_signed(
@@ -240,23 +333,17 @@ impl<S: EthereumSigner> Signer<S> {
         )
         .map_err(signing_failed_error)?;
 
-        let eth_signature = match &self.eth_signer {
-            Some(signer) => {
-                let message = forced_exit.get_ethereum_sign_message(&token.symbol, token.decimals);
-                let signature = signer.sign_message(message.as_bytes()).await?;
-
-                if let TxEthSignature::EthereumSignature(packed_signature) = signature {
-                    Some(packed_signature)
-                } else {
-                    return Err(SignerError::MissingEthSigner);
-                }
-            }
-            _ => None,
-        };
+        let eth_signature = self
+            .sign_eth_data(&forced_exit, || {
+                forced_exit.get_ethereum_sign_message(&token.symbol, token.decimals)
+            })
+            .await?;
 
         Ok((forced_exit, eth_signature))
     }
 
+    /// Signs the NFT minting with the human-readable Ethereum message.
+    /// Fails if the EIP-712 domain is set, use `sign_mint_nft_tx` to get the typed data signature.
     pub async fn sign_mint_nft(
         &self,
         recipient: Address,
@@ -265,6 +352,22 @@ impl<S: EthereumSigner> Signer<S> {
         fee: BigUint,
         nonce: Nonce,
     ) -> Result<(MintNFT, Option<PackedEthSignature>), SignerError> {
+        let (tx, eth_signature) = self
+            .sign_mint_nft_tx(recipient, content_hash, fee_token, fee, nonce)
+            .await?;
+        Ok((tx, packed_eth_signature(eth_signature)?))
+    }
+
+    /// Signs the NFT minting. The Ethereum signature is made for the EIP-712 typed data
+    /// if the domain is set, and for the human-readable message otherwise.
+    pub async fn sign_mint_nft_tx(
+        &self,
+        recipient: Address,
+        content_hash: H256,
+        fee_token: Token,
+        fee: BigUint,
+        nonce: Nonce,
+    ) -> Result<(MintNFT, Option<TxEthSignature>), SignerError> {
         let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
 
         let mint_nft = MintNFT::new_signed(
@@ -279,24 +382,17 @@ impl<S: EthereumSigner> Signer<S> {
         )
         .map_err(signing_failed_error)?;
 
-        let eth_signature = match &self.eth_signer {
-            Some(signer) => {
-                let message =
-                    mint_nft.get_ethereum_sign_message(&fee_token.symbol, fee_token.decimals);
-                let signature = signer.sign_message(message.as_bytes()).await?;
-
-                if let TxEthSignature::EthereumSignature(packed_signature) = signature {
-                    Some(packed_signature)
-                } else {
-                    return Err(SignerError::MissingEthSigner);
-                }
-            }
-            _ => None,
-        };
+        let eth_signature = self
+            .sign_eth_data(&mint_nft, || {
+                mint_nft.get_ethereum_sign_message(&fee_token.symbol, fee_token.decimals)
+            })
+            .await?;
 
         Ok((mint_nft, eth_signature))
     }
 
+    /// Signs the NFT withdrawal with the human-readable Ethereum message.
+    /// Fails if the EIP-712 domain is set, use `sign_withdraw_nft_tx` to get the typed data signature.
     pub async fn sign_withdraw_nft(
         &self,
         to: Address,
@@ -306,6 +402,23 @@ impl<S: EthereumSigner> Signer<S> {
         nonce: Nonce,
         time_range: TimeRange,
     ) -> Result<(WithdrawNFT, Option<PackedEthSignature>), SignerError> {
+        let (tx, eth_signature) = self
+            .sign_withdraw_nft_tx(to, token, fee_token, fee, nonce, time_range)
+            .await?;
+        Ok((tx, packed_eth_signature(eth_signature)?))
+    }
+
+    /// Signs the NFT withdrawal. The Ethereum signature is made for the EIP-712 typed data
+    /// if the domain is set, and for the human-readable message otherwise.
+    pub async fn sign_withdraw_nft_tx(
+        &self,
+        to: Address,
+        token: TokenId,
+        fee_token: Token,
+        fee: BigUint,
+        nonce: Nonce,
+        time_range: TimeRange,
+    ) -> Result<(WithdrawNFT, Option<TxEthSignature>), SignerError> {
         let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
 
         let withdraw_nft = WithdrawNFT::new_signed(
@@ -321,21 +434,82 @@ impl<S: EthereumSigner> Signer<S> {
         )
         .map_err(signing_failed_error)?;
 
-        let eth_signature = match &self.eth_signer {
-            Some(signer) => {
-                let message =
-                    withdraw_nft.get_ethereum_sign_message(&fee_token.symbol, fee_token.decimals);
-                let signature = signer.sign_message(message.as_bytes()).await?;
-
-                if let TxEthSignature::EthereumSignature(packed_signature) = signature {
-                    Some(packed_signature)
-                } else {
-                    return Err(SignerError::MissingEthSigner);
-                }
-            }
-            _ => None,
-        };
+        let eth_signature = self
+            .sign_eth_data(&withdraw_nft, || {
+                withdraw_nft.get_ethereum_sign_message(&fee_token.symbol, fee_token.decimals)
+            })
+            .await?;
 
         Ok((withdraw_nft, eth_signature))
     }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn sign_order(
+        &self,
+        recipient: Address,
+        nonce: Nonce,
+        token_sell: &Token,
+        token_buy: &Token,
+        price: (BigUint, BigUint),
+        amount: BigUint,
+        time_range: TimeRange,
+    ) -> Result<(Order, Option<TxEthSignature>), SignerError> {
+        let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
+
+        let order = Order::new_signed(
+            account_id,
+            recipient,
+            nonce,
+            token_sell.id,
+            token_buy.id,
+            price,
+            amount,
+            time_range,
+            &self.private_key,
+        )
+        .map_err(signing_failed_error)?;
+
+        let eth_signature = self
+            .sign_eth_data(&order, || {
+                order.get_ethereum_sign_message(
+                    &token_sell.symbol,
+                    &token_buy.symbol,
+                    token_sell.decimals,
+                )
+            })
+            .await?;
+
+        Ok((order, eth_signature))
+    }
+
+    pub async fn sign_swap(
+        &self,
+        orders: (Order, Order),
+        amounts: (BigUint, BigUint),
+        fee_token: Token,
+        fee: BigUint,
+        nonce: Nonce,
+    ) -> Result<(Swap, Option<TxEthSignature>), SignerError> {
+        let account_id = self.account_id.ok_or(SignerError::NoSigningKey)?;
+
+        let swap = Swap::new_signed(
+            account_id,
+            self.address,
+            nonce,
+            orders,
+            amounts,
+            fee,
+            fee_token.id,
+            &self.private_key,
+        )
+        .map_err(signing_failed_error)?;
+
+        let eth_signature = self
+            .sign_eth_data(&swap, || {
+                swap.get_ethereum_sign_message(&fee_token.symbol, fee_token.decimals)
+            })
+            .await?;
+
+        Ok((swap, eth_signature))
+    }
 }
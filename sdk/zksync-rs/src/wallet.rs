@@ -1,6 +1,6 @@
 use num::BigUint;
 use zksync_eth_signer::EthereumSigner;
-use zksync_types::{AccountId, Address, TokenId, TokenLike};
+use zksync_types::{tx::Eip712Domain, AccountId, Address, TokenId, TokenLike};
 
 use crate::{
     credentials::WalletCredentials,
@@ -51,6 +51,27 @@ where
         Ok(())
     }
 
+    /// Makes the wallet sign transactions as EIP-712 typed data instead of the
+    /// human-readable messages. The signing domain is bound to the network and
+    /// the zkSync contract address reported by the provider.
+    pub async fn enable_eip712_signatures(&mut self) -> Result<(), ClientError> {
+        let address_response = self.provider.contract_address().await?;
+        let contract_address = address_response
+            .main_contract
+            .strip_prefix("0x")
+            .unwrap_or(&address_response.main_contract)
+            .parse()
+            .map_err(|err| ClientError::MalformedResponse(format!("{}", err)))?;
+        let network = self.provider.network();
+        let chain_id = network
+            .chain_id()
+            .ok_or_else(|| ClientError::NetworkNotSupported(network.to_string()))?;
+
+        self.signer
+            .set_eip712_domain(Some(Eip712Domain::new(chain_id, contract_address)));
+        Ok(())
+    }
+
     /// Returns the wallet address.
     pub fn address(&self) -> Address {
         self.signer.address
@@ -158,6 +179,21 @@ where
         WithdrawNFTBuilder::new(self)
     }
 
+    /// Initializes `ForcedExit` transaction sending.
+    pub fn start_forced_exit(&self) -> ForcedExitBuilder<'_, S, P> {
+        ForcedExitBuilder::new(self)
+    }
+
+    /// Initializes signing of an order to be filled by a `Swap` transaction.
+    pub fn start_order(&self) -> OrderBuilder<'_, S, P> {
+        OrderBuilder::new(self)
+    }
+
+    /// Initializes `Swap` transaction sending.
+    pub fn start_swap(&self) -> SwapBuilder<'_, S, P> {
+        SwapBuilder::new(self)
+    }
+
     /// Creates an `EthereumProvider` to interact with the Ethereum network.
     ///
     /// Returns an error if wallet was created without providing an Ethereum private key.
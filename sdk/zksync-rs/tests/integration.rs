@@ -138,7 +138,7 @@ async fn transfer_to(
 ) -> Result<(), anyhow::Error> {
     let (main_eth_address, main_eth_private_key) = eth_main_account_credentials();
 
-    let provider = RpcProvider::new(Network::Localhost);
+    let provider = RpcProvider::new(Network::Localhost).unwrap();
     let eth_signer = PrivateKeySigner::new(main_eth_private_key);
     let credentials =
         WalletCredentials::from_eth_signer(main_eth_address, eth_signer, Network::Localhost)
@@ -163,7 +163,7 @@ where
     S: EthereumSigner,
     P: Provider + Clone,
 {
-    let provider = RpcProvider::new(Network::Localhost);
+    let provider = RpcProvider::new(Network::Localhost).unwrap();
 
     let (random_eth_address, random_eth_private_key) = eth_random_account_credentials();
     let eth_signer = PrivateKeySigner::new(random_eth_private_key);
@@ -526,7 +526,7 @@ async fn init_account_with_one_ether(
     // account in subsequent test runs).
     transfer_to("ETH", one_ether(), eth_address).await?;
 
-    let provider = RpcProvider::new(Network::Localhost);
+    let provider = RpcProvider::new(Network::Localhost).unwrap();
 
     let eth_signer = PrivateKeySigner::new(eth_private_key);
     let credentials =
@@ -575,7 +575,7 @@ async fn make_wallet(
 #[tokio::test]
 #[cfg_attr(not(feature = "integration-tests"), ignore)]
 async fn comprehensive_test() -> Result<(), anyhow::Error> {
-    let provider = RpcProvider::new(Network::Localhost);
+    let provider = RpcProvider::new(Network::Localhost).unwrap();
 
     let main_wallet = make_wallet(provider.clone(), eth_main_account_credentials()).await?;
     let sync_depositor_wallet =
@@ -1,9 +1,25 @@
 use std::collections::HashMap;
-use zksync::{tokens_cache::TokensCache, utils::*, web3::types::H160};
+use zksync::{
+    error::ClientError, provider::get_rpc_addr, tokens_cache::TokensCache, utils::*,
+    web3::types::H160, Network,
+};
 use zksync_config::test_config::unit_vectors::{Config as TestVectorsConfig, TestEntry};
 use zksync_crypto::PrivateKey;
 use zksync_types::{tx::TxSignature, AccountId, Nonce, Token, TokenId, TokenKind};
 
+#[test]
+fn test_rpc_addr() {
+    assert_eq!(
+        get_rpc_addr(Network::Localhost),
+        Ok("http://127.0.0.1:3030")
+    );
+    // Custom networks have no well-known address and must be connected to explicitly.
+    assert_eq!(
+        get_rpc_addr(Network::Custom(1337)),
+        Err(ClientError::NetworkNotSupported("custom-1337".into()))
+    );
+}
+
 #[test]
 fn test_tokens_cache() {
     let mut tokens: HashMap<String, Token> = HashMap::default();
@@ -154,7 +170,10 @@ mod signatures_with_vectors {
     use zksync::{signer::Signer, WalletCredentials};
     use zksync_config::test_config::unit_vectors::TxData;
     use zksync_eth_signer::PrivateKeySigner;
-    use zksync_types::tx::{ChangePubKeyECDSAData, ChangePubKeyEthAuthData};
+    use zksync_types::tx::{
+        ChangePubKeyECDSAData, ChangePubKeyEthAuthData, Eip712Domain, Eip712TypedStructure,
+        PackedEthSignature, TxEthSignature,
+    };
     use zksync_types::{network::Network, AccountId, Address, H256};
 
     async fn get_signer(
@@ -503,6 +522,51 @@ mod signatures_with_vectors {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_transfer_typed_data_signature() {
+        let eth_private_key = [5; 32];
+        let address =
+            PackedEthSignature::address_from_private_key(&H256::from(eth_private_key)).unwrap();
+        let mut signer = get_signer(&eth_private_key, address, AccountId(1)).await;
+        let domain = Eip712Domain::new(1, Address::repeat_byte(0x11));
+        signer.set_eip712_domain(Some(domain));
+
+        let token = Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20);
+        let (transfer, eth_signature) = signer
+            .sign_transfer_tx(
+                token.clone(),
+                1_000u64.into(),
+                10u64.into(),
+                Address::repeat_byte(0x22),
+                Nonce(0),
+                Default::default(),
+            )
+            .await
+            .expect("Transfer signing error");
+
+        // Typed data signature can't be returned as the plain Ethereum one.
+        let result = signer
+            .sign_transfer(
+                token,
+                1_000u64.into(),
+                10u64.into(),
+                Address::repeat_byte(0x22),
+                Nonce(0),
+                Default::default(),
+            )
+            .await;
+        assert!(result.is_err());
+
+        let signature = match eth_signature {
+            Some(TxEthSignature::EIP712Signature(signature)) => signature,
+            other => panic!("Expected a typed data signature, got {:?}", other),
+        };
+        let signer_address = signature
+            .signature_recover_signer_from_hash(transfer.signing_hash(&domain).unwrap())
+            .unwrap();
+        assert_eq!(signer_address, address);
+    }
 }
 
 #[cfg(test)]
@@ -522,7 +586,7 @@ mod wallet_tests {
     use zksync_eth_signer::PrivateKeySigner;
     use zksync_types::{
         tokens::get_genesis_token_list,
-        tx::{PackedEthSignature, TxHash},
+        tx::{PackedEthSignature, TxEthSignature, TxHash},
         Address, PubKeyHash, TokenId, TokenLike, TxFeeTypes, ZkSyncTx, H256,
     };
 
@@ -643,18 +707,18 @@ mod wallet_tests {
             })
         }
 
-        async fn send_tx(
+        async fn submit_tx(
             &self,
             _tx: ZkSyncTx,
-            _eth_signature: Option<PackedEthSignature>,
+            _eth_signature: Option<TxEthSignature>,
         ) -> Result<TxHash, ClientError> {
             unreachable!()
         }
 
-        async fn send_txs_batch(
+        async fn submit_txs_batch(
             &self,
-            _txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
-            _eth_signature: Option<PackedEthSignature>,
+            _txs_signed: Vec<(ZkSyncTx, Option<TxEthSignature>)>,
+            _eth_signature: Option<TxEthSignature>,
         ) -> Result<Vec<TxHash>, ClientError> {
             unreachable!()
         }
@@ -704,6 +768,22 @@ mod wallet_tests {
         assert_eq!(wallet.account_id(), Some(AccountId(42)));
     }
 
+    #[tokio::test]
+    async fn test_wallet_custom_network_chain_id() {
+        let chain_id = 1337;
+        let mut wallet = get_test_wallet(&[16; 32], Network::Mainnet).await;
+        wallet.provider.network = Network::from_chain_id(chain_id);
+        wallet.enable_eip712_signatures().await.unwrap();
+        let domain = wallet.signer.get_eip712_domain().unwrap();
+        assert_eq!(domain.chain_id, chain_id);
+
+        wallet.provider.network = Network::Unknown;
+        assert_eq!(
+            wallet.enable_eip712_signatures().await.unwrap_err(),
+            ClientError::NetworkNotSupported("unknown".into())
+        );
+    }
+
     #[tokio::test]
     async fn test_wallet_refresh_tokens() {
         let mut wallet = get_test_wallet(&[20; 32], Network::Mainnet).await;
@@ -772,3 +852,75 @@ mod wallet_tests {
         assert_eq!(eth_provider.contract_address().as_bytes(), expected_address);
     }
 }
+
+mod credentials_tests {
+    use zksync::{signer::Signer, WalletCredentials};
+    use zksync_eth_signer::{keystore::KdfParams, Keystore, PrivateKeySigner};
+    use zksync_types::{network::Network, tx::PackedEthSignature, Address, H256};
+
+    const PHRASE: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_credentials_from_mnemonic() {
+        let first = WalletCredentials::<PrivateKeySigner>::from_mnemonic(PHRASE, "", 0).unwrap();
+        let second = WalletCredentials::<PrivateKeySigner>::from_mnemonic(PHRASE, "", 1).unwrap();
+
+        // Ethereum addresses match the ones derived by the wallets for the `m/44'/60'/0'/0/{index}` path.
+        let first = Signer::with_credentials(first);
+        let second = Signer::with_credentials(second);
+        assert_eq!(
+            first.address,
+            "9858effd232b4033e47d90003d41ec34ecaeda94"
+                .parse::<Address>()
+                .unwrap()
+        );
+        assert_eq!(
+            second.address,
+            "6fac4d18c912343bf86fa7049364dd4e424ab9c0"
+                .parse::<Address>()
+                .unwrap()
+        );
+        assert_ne!(first.pubkey_hash, second.pubkey_hash);
+
+        // Derivation is deterministic.
+        let again = Signer::with_credentials(
+            WalletCredentials::<PrivateKeySigner>::from_mnemonic(PHRASE, "", 0).unwrap(),
+        );
+        assert_eq!(again.pubkey_hash, first.pubkey_hash);
+    }
+
+    #[tokio::test]
+    async fn test_credentials_from_keystore() {
+        let eth_private_key = H256::from([5; 32]);
+        let kdf = KdfParams::Scrypt {
+            dklen: 32,
+            n: 1024,
+            r: 8,
+            p: 1,
+            salt: vec![1; 32],
+        };
+        let keystore = Keystore::encrypt(eth_private_key, "password", kdf).unwrap();
+        let address = PackedEthSignature::address_from_private_key(&eth_private_key).unwrap();
+
+        let from_keystore = WalletCredentials::<PrivateKeySigner>::from_keystore(
+            &keystore,
+            "password",
+            Network::Mainnet,
+        )
+        .await
+        .unwrap();
+        let from_signer = WalletCredentials::from_eth_signer(
+            address,
+            PrivateKeySigner::new(eth_private_key),
+            Network::Mainnet,
+        )
+        .await
+        .unwrap();
+
+        let from_keystore = Signer::with_credentials(from_keystore);
+        let from_signer = Signer::with_credentials(from_signer);
+        assert_eq!(from_keystore.address, address);
+        assert_eq!(from_keystore.pubkey_hash, from_signer.pubkey_hash);
+    }
+}